@@ -0,0 +1,701 @@
+//! mnemosyne-cli：无界面环境下的供应商 / MCP / 代理管理工具
+//!
+//! 复用 GUI 相同的数据库与服务层，适用于远程开发机与 CI 容器。
+//! 所有子命令均支持 `--json` 输出，便于脚本处理。
+
+use std::cell::RefCell;
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Args, Parser, Subcommand};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use cavin_tools_lib::cc_switch::app_config::{AppType, MultiAppConfig};
+use cavin_tools_lib::cc_switch::config::{get_app_config_path, get_db_path};
+use cavin_tools_lib::cc_switch::database::Database;
+use cavin_tools_lib::cc_switch::provider::Provider;
+use cavin_tools_lib::cc_switch::services::{McpService, ProviderService, SkillService};
+use cavin_tools_lib::cc_switch::store::AppState;
+use cavin_tools_lib::cc_switch::{app_store, proxy};
+
+#[derive(Parser)]
+#[command(
+    name = "mnemosyne-cli",
+    version,
+    about = "Headless Mnemosyne (CC Switch) manager"
+)]
+struct Cli {
+    /// 以 JSON 输出结果
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// 覆盖应用配置目录（默认 ~/.config/mnemosyne）
+    #[arg(long, global = true, env = "MNEMOSYNE_CONFIG_DIR")]
+    config_dir: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 供应商管理
+    #[command(subcommand)]
+    Provider(ProviderCommand),
+    /// MCP 服务器管理
+    #[command(subcommand)]
+    Mcp(McpCommand),
+    /// Skills 管理
+    #[command(subcommand)]
+    Skill(SkillCommand),
+    /// 本地代理
+    #[command(subcommand)]
+    Proxy(ProxyCommand),
+    /// 查看使用统计
+    Usage(UsageArgs),
+    /// 导出数据库为 SQL 备份
+    Export {
+        /// 目标文件路径
+        file: PathBuf,
+    },
+    /// 从 SQL 备份导入数据库（导入前自动备份）
+    Import {
+        /// SQL 备份文件路径
+        file: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProviderCommand {
+    /// 列出供应商
+    List {
+        /// 应用：claude / codex / gemini / opencode / cursor / qwen
+        #[arg(long, default_value = "claude")]
+        app: String,
+    },
+    /// 显示当前供应商
+    Current {
+        #[arg(long, default_value = "claude")]
+        app: String,
+    },
+    /// 切换供应商（按 ID 或名称）
+    Switch {
+        #[arg(long, default_value = "claude")]
+        app: String,
+        /// 供应商 ID 或名称
+        provider: String,
+    },
+    /// 添加供应商
+    Add(ProviderAddArgs),
+}
+
+#[derive(Args)]
+struct ProviderAddArgs {
+    #[arg(long, default_value = "claude")]
+    app: String,
+    /// 供应商名称
+    #[arg(long)]
+    name: String,
+    /// 配置 JSON，或以 @ 开头的文件路径（如 @settings.json）
+    #[arg(long)]
+    settings: String,
+    /// 供应商 ID（缺省时自动生成）
+    #[arg(long)]
+    id: Option<String>,
+    /// 官网地址
+    #[arg(long)]
+    website: Option<String>,
+    /// 添加后立即切换
+    #[arg(long)]
+    switch: bool,
+}
+
+#[derive(Subcommand)]
+enum McpCommand {
+    /// 列出 MCP 服务器
+    List,
+    /// 将已启用的 MCP 服务器同步到各应用的 live 配置
+    Sync,
+}
+
+#[derive(Subcommand)]
+enum SkillCommand {
+    /// 列出已安装的 Skills
+    List,
+    /// 将已启用的 Skills 同步到各应用目录
+    Sync {
+        /// 仅同步指定应用：claude / codex / gemini / opencode / cursor / qwen
+        #[arg(long)]
+        app: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProxyCommand {
+    /// 在前台启动代理，Ctrl-C 退出
+    Start {
+        /// 同时接管各应用的 live 配置，退出时恢复
+        #[arg(long)]
+        takeover: bool,
+    },
+    /// 查看代理配置与接管状态
+    Status,
+}
+
+#[derive(Args)]
+struct UsageArgs {
+    /// 起始时间（Unix 秒）
+    #[arg(long)]
+    since: Option<i64>,
+    /// 结束时间（Unix 秒）
+    #[arg(long)]
+    until: Option<i64>,
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let json_output = cli.json;
+
+    if let Err(err) = run(cli, &Output::stdout(json_output)) {
+        if json_output {
+            println!("{}", json!({ "error": format!("{err:#}") }));
+        } else {
+            eprintln!("error: {err:#}");
+        }
+        std::process::exit(1);
+    }
+}
+
+fn run(cli: Cli, out: &Output) -> Result<()> {
+    // 命令行参数优先，其次沿用 GUI 设置中保存的配置目录
+    match cli.config_dir.as_deref() {
+        Some(dir) => app_store::set_app_config_dir_override(Some(dir)),
+        None => {
+            app_store::load_stored_app_config_dir_override();
+        }
+    }
+    let state = AppState::new(open_database()?);
+
+    match cli.command {
+        Command::Provider(cmd) => run_provider(&state, out, cmd),
+        Command::Mcp(cmd) => run_mcp(&state, out, cmd),
+        Command::Skill(cmd) => run_skill(&state, out, cmd),
+        Command::Proxy(cmd) => run_proxy(state, out, cmd),
+        Command::Usage(args) => run_usage(&state, out, args),
+        Command::Export { file } => {
+            state.db.export_sql(&file)?;
+            out.print(&json!({ "filePath": file }), || {
+                format!("已导出到 {}", file.display())
+            })
+        }
+        Command::Import { file } => {
+            let backup_id = state.db.import_sql(&file)?;
+            if let Err(err) = ProviderService::sync_current_to_live(&state) {
+                eprintln!("warning: 导入后同步 live 配置失败: {err}");
+            }
+            if let Err(err) = McpService::sync_all_enabled(&state) {
+                eprintln!("warning: 导入后同步 MCP 配置失败: {err}");
+            }
+            out.print(&json!({ "backupId": backup_id }), || {
+                format!("已从 {} 导入，导入前备份: {backup_id}", file.display())
+            })
+        }
+    }
+}
+
+/// 打开数据库；首次运行且存在旧版 config.json 时与 GUI 一样执行迁移
+fn open_database() -> Result<Arc<Database>> {
+    let json_path = get_app_config_path();
+    let migration_config = if !get_db_path().exists() && json_path.exists() {
+        Some(MultiAppConfig::load().context("加载旧配置文件失败")?)
+    } else {
+        None
+    };
+
+    let db = Arc::new(Database::init().context("初始化数据库失败")?);
+
+    if let Some(config) = migration_config {
+        db.migrate_from_json(&config).context("配置迁移失败")?;
+        let archive_path = json_path.with_extension("json.migrated");
+        if let Err(e) = std::fs::rename(&json_path, &archive_path) {
+            eprintln!("warning: 归档旧配置文件失败: {e}");
+        }
+    }
+
+    Ok(db)
+}
+
+struct Output {
+    json: bool,
+    writer: RefCell<Box<dyn Write>>,
+}
+
+impl Output {
+    fn stdout(json: bool) -> Self {
+        Self {
+            json,
+            writer: RefCell::new(Box::new(std::io::stdout())),
+        }
+    }
+
+    fn print<T: Serialize>(&self, value: &T, human: impl FnOnce() -> String) -> Result<()> {
+        let mut writer = self.writer.borrow_mut();
+        if self.json {
+            writeln!(writer, "{}", serde_json::to_string_pretty(value)?)?;
+        } else {
+            writeln!(writer, "{}", human())?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_app(app: &str) -> Result<AppType> {
+    Ok(AppType::from_str(app)?)
+}
+
+fn run_provider(state: &AppState, out: &Output, cmd: ProviderCommand) -> Result<()> {
+    match cmd {
+        ProviderCommand::List { app } => {
+            let app_type = parse_app(&app)?;
+            let current = ProviderService::current(state, app_type.clone())?;
+            let providers = ProviderService::list(state, app_type)?;
+            let rows: Vec<Value> = providers
+                .values()
+                .map(|p| {
+                    json!({
+                        "id": p.id,
+                        "name": p.name,
+                        "current": p.id == current,
+                        "category": p.category,
+                        "websiteUrl": p.website_url,
+                    })
+                })
+                .collect();
+            out.print(&rows, || {
+                providers
+                    .values()
+                    .map(|p| {
+                        let marker = if p.id == current { "*" } else { " " };
+                        format!("{marker} {}\t{}", p.id, p.name)
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+        }
+        ProviderCommand::Current { app } => {
+            let app_type = parse_app(&app)?;
+            let current = ProviderService::current(state, app_type.clone())?;
+            let provider = ProviderService::list(state, app_type)?.shift_remove(&current);
+            out.print(&provider, || match &provider {
+                Some(p) => format!("{}\t{}", p.id, p.name),
+                None => "(none)".to_string(),
+            })
+        }
+        ProviderCommand::Switch { app, provider } => {
+            let app_type = parse_app(&app)?;
+            let target = resolve_provider(state, &app_type, &provider)?;
+            ProviderService::switch(state, app_type, &target.id)?;
+            out.print(&json!({ "id": target.id, "name": target.name }), || {
+                format!("已切换到 {} ({})", target.name, target.id)
+            })
+        }
+        ProviderCommand::Add(args) => {
+            let app_type = parse_app(&args.app)?;
+            let settings = read_settings_arg(&args.settings)?;
+            let id = args.id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+            let mut provider = Provider::with_id(id.clone(), args.name, settings, args.website);
+            provider.created_at = Some(chrono::Utc::now().timestamp_millis());
+            let name = provider.name.clone();
+
+            ProviderService::add(state, app_type.clone(), provider)?;
+            if args.switch {
+                ProviderService::switch(state, app_type, &id)?;
+            }
+            out.print(
+                &json!({ "id": id, "name": name, "switched": args.switch }),
+                || format!("已添加供应商 {name} ({id})"),
+            )
+        }
+    }
+}
+
+/// 按 ID 精确匹配，其次按名称（不区分大小写）唯一匹配
+fn resolve_provider(state: &AppState, app_type: &AppType, key: &str) -> Result<Provider> {
+    let mut providers = ProviderService::list(state, app_type.clone())?;
+    if let Some(provider) = providers.shift_remove(key) {
+        return Ok(provider);
+    }
+    let mut matches: Vec<Provider> = providers
+        .into_values()
+        .filter(|p| p.name.eq_ignore_ascii_case(key))
+        .collect();
+    match matches.len() {
+        0 => bail!("未找到供应商: {key}"),
+        1 => Ok(matches.remove(0)),
+        _ => bail!("名称 {key} 匹配到多个供应商，请改用 ID"),
+    }
+}
+
+fn read_settings_arg(raw: &str) -> Result<Value> {
+    let text = match raw.strip_prefix('@') {
+        Some(path) => {
+            std::fs::read_to_string(path).with_context(|| format!("读取文件失败: {path}"))?
+        }
+        None => raw.to_string(),
+    };
+    let value: Value = serde_json::from_str(&text).context("解析配置 JSON 失败")?;
+    if !value.is_object() {
+        bail!("配置必须是 JSON 对象");
+    }
+    Ok(value)
+}
+
+fn run_mcp(state: &AppState, out: &Output, cmd: McpCommand) -> Result<()> {
+    match cmd {
+        McpCommand::List => {
+            let servers = McpService::get_all_servers(state)?;
+            out.print(&servers.values().collect::<Vec<_>>(), || {
+                servers
+                    .values()
+                    .map(|s| {
+                        let apps: Vec<String> = s
+                            .apps
+                            .enabled_apps()
+                            .iter()
+                            .map(|a| a.as_str().to_string())
+                            .collect();
+                        format!("{}\t{}\t[{}]", s.id, s.name, apps.join(","))
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+        }
+        McpCommand::Sync => {
+            McpService::sync_all_enabled(state)?;
+            out.print(&json!({ "synced": true }), || "MCP 配置已同步".to_string())
+        }
+    }
+}
+
+fn run_skill(state: &AppState, out: &Output, cmd: SkillCommand) -> Result<()> {
+    match cmd {
+        SkillCommand::List => {
+            let skills = SkillService::get_all_installed(&state.db)?;
+            out.print(&skills, || {
+                skills
+                    .iter()
+                    .map(|s| format!("{}\t{}", s.id, s.name))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+        }
+        SkillCommand::Sync { app } => {
+            let apps = match app.as_deref().map(parse_app).transpose()? {
+                Some(app_type) => vec![app_type],
+                None => AppType::ALL.to_vec(),
+            };
+            for app_type in &apps {
+                SkillService::sync_to_app(&state.db, app_type)?;
+            }
+            out.print(&json!({ "synced": true }), || "Skills 已同步".to_string())
+        }
+    }
+}
+
+fn run_proxy(state: AppState, out: &Output, cmd: ProxyCommand) -> Result<()> {
+    let runtime = tokio::runtime::Runtime::new().context("创建异步运行时失败")?;
+    runtime.block_on(async move {
+        match cmd {
+            ProxyCommand::Status => {
+                let config = state
+                    .proxy_service
+                    .get_config()
+                    .await
+                    .map_err(|e| anyhow!(e))?;
+                let takeover = state
+                    .proxy_service
+                    .get_takeover_status()
+                    .await
+                    .map_err(|e| anyhow!(e))?;
+                let value = json!({ "config": config, "takeover": takeover });
+                out.print(&value, || {
+                    serde_json::to_string_pretty(&value).unwrap_or_default()
+                })
+            }
+            ProxyCommand::Start { takeover } => run_proxy_foreground(&state, out, takeover).await,
+        }
+    })
+}
+
+async fn run_proxy_foreground(state: &AppState, out: &Output, takeover: bool) -> Result<()> {
+    let proxy_url = state.db.get_global_proxy_url().ok().flatten();
+    if let Err(e) = proxy::http_client::init(proxy_url.as_deref()) {
+        eprintln!("warning: 初始化全局代理失败，改为直连: {e}");
+        let _ = proxy::http_client::init(None);
+    }
+
+    // 上次异常退出遗留的接管状态先恢复
+    let has_backups = state.db.has_any_live_backup().await.unwrap_or(false);
+    if has_backups || state.proxy_service.detect_takeover_in_live_configs() {
+        state
+            .proxy_service
+            .recover_from_crash()
+            .await
+            .map_err(|e| anyhow!(e))?;
+    }
+
+    let info = if takeover {
+        state.proxy_service.start_with_takeover().await
+    } else {
+        state.proxy_service.start().await
+    }
+    .map_err(|e| anyhow!(e))?;
+
+    out.print(&info, || {
+        format!(
+            "代理已启动: http://{}:{}（Ctrl-C 退出）",
+            info.address, info.port
+        )
+    })?;
+
+    tokio::signal::ctrl_c().await.context("等待退出信号失败")?;
+
+    let stopped = if takeover {
+        state.proxy_service.stop_with_restore().await
+    } else {
+        state.proxy_service.stop().await
+    };
+    stopped.map_err(|e| anyhow!(e))?;
+    if !out.json {
+        println!("代理已停止");
+    }
+    Ok(())
+}
+
+fn run_usage(state: &AppState, out: &Output, args: UsageArgs) -> Result<()> {
+    let summary = state.db.get_usage_summary(args.since, args.until)?;
+    let providers = state.db.get_provider_stats()?;
+    let models = state.db.get_model_stats()?;
+
+    let value = json!({
+        "summary": summary,
+        "providers": providers,
+        "models": models,
+    });
+    out.print(&value, || {
+        let mut lines = vec![
+            format!("requests: {}", summary.total_requests),
+            format!("cost (USD): {}", summary.total_cost),
+            format!(
+                "tokens: in {} / out {} / cache write {} / cache read {}",
+                summary.total_input_tokens,
+                summary.total_output_tokens,
+                summary.total_cache_creation_tokens,
+                summary.total_cache_read_tokens
+            ),
+            format!("success rate: {:.1}%", summary.success_rate),
+            String::new(),
+            "providers:".to_string(),
+        ];
+        lines.extend(providers.iter().map(|p| {
+            format!(
+                "  {}\t{} req\t{} tokens\t${}",
+                p.provider_name, p.request_count, p.total_tokens, p.total_cost
+            )
+        }));
+        lines.push("models:".to_string());
+        lines.extend(models.iter().map(|m| {
+            format!(
+                "  {}\t{} req\t{} tokens",
+                m.model, m.request_count, m.total_tokens
+            )
+        }));
+        lines.join("\n")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::env;
+    use std::rc::Rc;
+    use tempfile::TempDir;
+
+    /// 可在测试中读取的输出缓冲区
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// 将 HOME 与数据目录指向临时目录，结束时恢复
+    struct TempEnv {
+        home: TempDir,
+        saved: Vec<(&'static str, Option<String>)>,
+    }
+
+    impl TempEnv {
+        fn new() -> Self {
+            let home = TempDir::new().expect("temp home");
+            let keys = ["HOME", "XDG_DATA_HOME", "MNEMOSYNE_CONFIG_DIR"];
+            let saved = keys.iter().map(|k| (*k, env::var(k).ok())).collect();
+            env::set_var("HOME", home.path());
+            env::set_var("XDG_DATA_HOME", home.path().join("data"));
+            env::remove_var("MNEMOSYNE_CONFIG_DIR");
+            Self { home, saved }
+        }
+    }
+
+    impl Drop for TempEnv {
+        fn drop(&mut self) {
+            for (key, value) in &self.saved {
+                match value {
+                    Some(value) => env::set_var(key, value),
+                    None => env::remove_var(key),
+                }
+            }
+            app_store::set_app_config_dir_override(None);
+        }
+    }
+
+    fn run_json(args: &[&str]) -> Value {
+        let cli = Cli::try_parse_from(
+            ["mnemosyne-cli", "--json"]
+                .iter()
+                .chain(args)
+                .copied()
+                .collect::<Vec<_>>(),
+        )
+        .expect("parse args");
+        let buffer = SharedBuffer::default();
+        let out = Output {
+            json: cli.json,
+            writer: RefCell::new(Box::new(buffer.clone())),
+        };
+        run(cli, &out).expect("run command");
+        let bytes = buffer.0.borrow().clone();
+        serde_json::from_slice(&bytes).expect("json output")
+    }
+
+    #[test]
+    fn parses_global_flags_after_subcommand() {
+        let cli = Cli::try_parse_from([
+            "mnemosyne-cli",
+            "provider",
+            "list",
+            "--app",
+            "codex",
+            "--json",
+            "--config-dir",
+            "/tmp/mnemosyne-test",
+        ])
+        .expect("parse args");
+
+        assert!(cli.json);
+        assert_eq!(cli.config_dir.as_deref(), Some("/tmp/mnemosyne-test"));
+        match cli.command {
+            Command::Provider(ProviderCommand::List { app }) => assert_eq!(app, "codex"),
+            _ => panic!("unexpected command"),
+        }
+    }
+
+    #[test]
+    fn parses_subcommand_arguments() {
+        let cli = Cli::try_parse_from(["mnemosyne-cli", "provider", "current"]).expect("parse");
+        match cli.command {
+            Command::Provider(ProviderCommand::Current { app }) => assert_eq!(app, "claude"),
+            _ => panic!("unexpected command"),
+        }
+
+        let cli = Cli::try_parse_from(["mnemosyne-cli", "skill", "sync", "--app", "gemini"])
+            .expect("parse");
+        match cli.command {
+            Command::Skill(SkillCommand::Sync { app }) => {
+                assert_eq!(app.as_deref(), Some("gemini"))
+            }
+            _ => panic!("unexpected command"),
+        }
+
+        let cli = Cli::try_parse_from(["mnemosyne-cli", "usage", "--since", "100"]).expect("parse");
+        match cli.command {
+            Command::Usage(args) => {
+                assert_eq!(args.since, Some(100));
+                assert_eq!(args.until, None);
+            }
+            _ => panic!("unexpected command"),
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        assert!(Cli::try_parse_from(["mnemosyne-cli"]).is_err());
+        assert!(Cli::try_parse_from(["mnemosyne-cli", "provider", "switch"]).is_err());
+        assert!(Cli::try_parse_from(["mnemosyne-cli", "provider", "add", "--name", "x"]).is_err());
+        assert!(Cli::try_parse_from(["mnemosyne-cli", "usage", "--since", "soon"]).is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn json_output_uses_temp_config_dir() {
+        let env = TempEnv::new();
+        let config_dir = env.home.path().join("cli-config");
+        let config_arg = config_dir.to_string_lossy().to_string();
+
+        let added = run_json(&[
+            "--config-dir",
+            &config_arg,
+            "provider",
+            "add",
+            "--name",
+            "Demo",
+            "--id",
+            "demo",
+            "--settings",
+            r#"{"env":{"ANTHROPIC_AUTH_TOKEN":"sk-test"}}"#,
+        ]);
+        assert_eq!(added["id"], "demo");
+        assert_eq!(added["switched"], false);
+        assert!(config_dir.join("mnemosyne.db").exists());
+
+        let listed = run_json(&["--config-dir", &config_arg, "provider", "list"]);
+        let rows = listed.as_array().expect("array output");
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["id"], "demo");
+        assert_eq!(rows[0]["name"], "Demo");
+        assert_eq!(rows[0]["current"], true);
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(not(windows))]
+    fn falls_back_to_config_dir_stored_by_gui() {
+        let env = TempEnv::new();
+        let config_dir = env.home.path().join("gui-config");
+        std::fs::create_dir_all(&config_dir).expect("create config dir");
+        let store_dir = dirs::data_dir()
+            .expect("data dir")
+            .join("com.mnemosyne.tools");
+        std::fs::create_dir_all(&store_dir).expect("create store dir");
+        std::fs::write(
+            store_dir.join("app_paths.json"),
+            json!({ "app_config_dir_override": config_dir }).to_string(),
+        )
+        .expect("write store");
+
+        let listed = run_json(&["mcp", "list"]);
+        assert!(listed.as_array().expect("array output").is_empty());
+        assert!(config_dir.join("mnemosyne.db").exists());
+    }
+}
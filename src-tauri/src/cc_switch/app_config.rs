@@ -17,6 +17,8 @@ pub struct McpApps {
     pub opencode: bool,
     #[serde(default)]
     pub cursor: bool,
+    #[serde(default)]
+    pub qwen: bool,
 }
 
 impl McpApps {
@@ -28,6 +30,7 @@ impl McpApps {
             AppType::Gemini => self.gemini,
             AppType::OpenCode => self.opencode,
             AppType::Cursor => self.cursor,
+            AppType::Qwen => self.qwen,
         }
     }
 
@@ -39,6 +42,7 @@ impl McpApps {
             AppType::Gemini => self.gemini = enabled,
             AppType::OpenCode => self.opencode = enabled,
             AppType::Cursor => self.cursor = enabled,
+            AppType::Qwen => self.qwen = enabled,
         }
     }
 
@@ -60,12 +64,15 @@ impl McpApps {
         if self.cursor {
             apps.push(AppType::Cursor);
         }
+        if self.qwen {
+            apps.push(AppType::Qwen);
+        }
         apps
     }
 
     /// 检查是否所有应用都未启用
     pub fn is_empty(&self) -> bool {
-        !self.claude && !self.codex && !self.gemini && !self.opencode && !self.cursor
+        !self.claude && !self.codex && !self.gemini && !self.opencode && !self.cursor && !self.qwen
     }
 }
 
@@ -82,6 +89,8 @@ pub struct SkillApps {
     pub opencode: bool,
     #[serde(default)]
     pub cursor: bool,
+    #[serde(default)]
+    pub qwen: bool,
 }
 
 impl SkillApps {
@@ -93,6 +102,7 @@ impl SkillApps {
             AppType::Gemini => self.gemini,
             AppType::OpenCode => self.opencode,
             AppType::Cursor => self.cursor,
+            AppType::Qwen => self.qwen,
         }
     }
 
@@ -104,6 +114,7 @@ impl SkillApps {
             AppType::Gemini => self.gemini = enabled,
             AppType::OpenCode => self.opencode = enabled,
             AppType::Cursor => self.cursor = enabled,
+            AppType::Qwen => self.qwen = enabled,
         }
     }
 
@@ -125,12 +136,15 @@ impl SkillApps {
         if self.cursor {
             apps.push(AppType::Cursor);
         }
+        if self.qwen {
+            apps.push(AppType::Qwen);
+        }
         apps
     }
 
     /// 检查是否所有应用都未启用
     pub fn is_empty(&self) -> bool {
-        !self.claude && !self.codex && !self.gemini && !self.opencode && !self.cursor
+        !self.claude && !self.codex && !self.gemini && !self.opencode && !self.cursor && !self.qwen
     }
 
     /// 仅启用指定应用（其他应用设为禁用）
@@ -169,6 +183,12 @@ pub struct InstalledSkill {
     /// 安装时对应的 Git 树提交 ID（Tree SHA）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tree_commit_id: Option<String>,
+    /// 锁定的 Git 引用（commit SHA 或 tag），为空表示跟随分支最新提交
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pinned_ref: Option<String>,
+    /// 当前 SSOT 内容对应的 Git commit SHA
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit_id: Option<String>,
     /// 应用启用状态
     pub apps: SkillApps,
     /// 安装时间（Unix 时间戳）
@@ -242,6 +262,9 @@ pub struct McpRoot {
     /// Cursor MCP 配置
     #[serde(default, skip_serializing_if = "McpConfig::is_empty")]
     pub cursor: McpConfig,
+    /// Qwen Code MCP 配置
+    #[serde(default, skip_serializing_if = "McpConfig::is_empty")]
+    pub qwen: McpConfig,
 }
 
 impl Default for McpRoot {
@@ -255,6 +278,7 @@ impl Default for McpRoot {
             gemini: McpConfig::default(),
             opencode: McpConfig::default(),
             cursor: McpConfig::default(),
+            qwen: McpConfig::default(),
         }
     }
 }
@@ -279,6 +303,8 @@ pub struct PromptRoot {
     pub opencode: PromptConfig,
     #[serde(default)]
     pub cursor: PromptConfig,
+    #[serde(default)]
+    pub qwen: PromptConfig,
 }
 
 use crate::cc_switch::config::{
@@ -298,9 +324,20 @@ pub enum AppType {
     Gemini,
     OpenCode,
     Cursor,
+    Qwen,
 }
 
 impl AppType {
+    /// 全部受管理的应用；需要遍历应用的地方以此为准，新增应用时只需在这里登记
+    pub const ALL: [AppType; 6] = [
+        AppType::Claude,
+        AppType::Codex,
+        AppType::Gemini,
+        AppType::OpenCode,
+        AppType::Cursor,
+        AppType::Qwen,
+    ];
+
     pub fn as_str(&self) -> &str {
         match self {
             AppType::Claude => "claude",
@@ -308,6 +345,7 @@ impl AppType {
             AppType::Gemini => "gemini",
             AppType::OpenCode => "opencode",
             AppType::Cursor => "cursor",
+            AppType::Qwen => "qwen",
         }
     }
 }
@@ -323,10 +361,11 @@ impl FromStr for AppType {
             "gemini" => Ok(AppType::Gemini),
             "opencode" => Ok(AppType::OpenCode),
             "cursor" => Ok(AppType::Cursor),
+            "qwen" => Ok(AppType::Qwen),
             other => Err(AppError::localized(
                 "unsupported_app",
-                format!("不支持的应用标识: '{other}'。可选值: claude, codex, gemini, opencode, cursor。"),
-                format!("Unsupported app id: '{other}'. Allowed: claude, codex, gemini, opencode, cursor."),
+                format!("不支持的应用标识: '{other}'。可选值: claude, codex, gemini, opencode, cursor, qwen。"),
+                format!("Unsupported app id: '{other}'. Allowed: claude, codex, gemini, opencode, cursor, qwen."),
             )),
         }
     }
@@ -349,6 +388,9 @@ pub struct CommonConfigSnippets {
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub cursor: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub qwen: Option<String>,
 }
 
 impl CommonConfigSnippets {
@@ -360,6 +402,7 @@ impl CommonConfigSnippets {
             AppType::Gemini => self.gemini.as_ref(),
             AppType::OpenCode => self.opencode.as_ref(),
             AppType::Cursor => self.cursor.as_ref(),
+            AppType::Qwen => self.qwen.as_ref(),
         }
     }
 
@@ -371,6 +414,7 @@ impl CommonConfigSnippets {
             AppType::Gemini => self.gemini = snippet,
             AppType::OpenCode => self.opencode = snippet,
             AppType::Cursor => self.cursor = snippet,
+            AppType::Qwen => self.qwen = snippet,
         }
     }
 }
@@ -412,6 +456,7 @@ impl Default for MultiAppConfig {
         apps.insert("gemini".to_string(), ProviderManager::default());
         apps.insert("opencode".to_string(), ProviderManager::default());
         apps.insert("cursor".to_string(), ProviderManager::default());
+        apps.insert("qwen".to_string(), ProviderManager::default());
 
         Self {
             version: 2,
@@ -572,6 +617,7 @@ impl MultiAppConfig {
             AppType::Gemini => &self.mcp.gemini,
             AppType::OpenCode => &self.mcp.opencode,
             AppType::Cursor => &self.mcp.cursor,
+            AppType::Qwen => &self.mcp.qwen,
         }
     }
 
@@ -583,6 +629,7 @@ impl MultiAppConfig {
             AppType::Gemini => &mut self.mcp.gemini,
             AppType::OpenCode => &mut self.mcp.opencode,
             AppType::Cursor => &mut self.mcp.cursor,
+            AppType::Qwen => &mut self.mcp.qwen,
         }
     }
 
@@ -689,6 +736,7 @@ impl MultiAppConfig {
             content,
             description: Some("Automatically imported on first launch".to_string()),
             enabled: true, // 自动启用
+            templated: false,
             created_at: Some(timestamp),
             updated_at: Some(timestamp),
         };
@@ -700,6 +748,7 @@ impl MultiAppConfig {
             AppType::Gemini => &mut config.prompts.gemini.prompts,
             AppType::OpenCode => &mut config.prompts.opencode.prompts,
             AppType::Cursor => &mut config.prompts.cursor.prompts,
+            AppType::Qwen => &mut config.prompts.qwen.prompts,
         };
 
         prompts.insert(id, prompt);
@@ -735,6 +784,7 @@ impl MultiAppConfig {
                 AppType::Gemini => &self.mcp.gemini.servers,
                 AppType::OpenCode => &self.mcp.opencode.servers,
                 AppType::Cursor => &self.mcp.cursor.servers,
+                AppType::Qwen => &self.mcp.qwen.servers,
             };
 
             for (id, entry) in old_servers {
@@ -1,10 +1,16 @@
 use serde_json::Value;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{OnceLock, RwLock};
 use tauri_plugin_store::StoreExt;
 
 use crate::cc_switch::error::AppError;
 
+/// Store 文件名（相对于 AppData 目录）
+const STORE_FILE_NAME: &str = "app_paths.json";
+
+/// 应用标识，需与 tauri.conf.json 中的 identifier 保持一致
+const APP_IDENTIFIER: &str = "com.mnemosyne.tools";
+
 /// Store 中的键名
 const STORE_KEY_APP_CONFIG_DIR: &str = "app_config_dir_override";
 
@@ -27,7 +33,7 @@ pub fn get_app_config_dir_override() -> Option<PathBuf> {
 }
 
 fn read_override_from_store(app: &tauri::AppHandle) -> Option<PathBuf> {
-    let store = match app.store_builder("app_paths.json").build() {
+    let store = match app.store_builder(STORE_FILE_NAME).build() {
         Ok(store) => store,
         Err(e) => {
             log::warn!("无法创建 Store: {e}");
@@ -35,7 +41,33 @@ fn read_override_from_store(app: &tauri::AppHandle) -> Option<PathBuf> {
         }
     };
 
-    match store.get(STORE_KEY_APP_CONFIG_DIR) {
+    override_from_value(store.get(STORE_KEY_APP_CONFIG_DIR))
+}
+
+/// 直接读取 Store 文件中的覆盖路径（供无 AppHandle 的命令行模式使用）
+///
+/// Store 文件位于 Tauri 的 AppData 目录，即 `<data_dir>/<identifier>/app_paths.json`。
+fn read_override_from_file(path: &Path) -> Option<PathBuf> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            log::warn!("读取 Store 文件失败 {path:?}: {e}");
+            return None;
+        }
+    };
+
+    match serde_json::from_str::<serde_json::Map<String, Value>>(&content) {
+        Ok(mut map) => override_from_value(map.remove(STORE_KEY_APP_CONFIG_DIR)),
+        Err(e) => {
+            log::warn!("解析 Store 文件失败 {path:?}: {e}");
+            None
+        }
+    }
+}
+
+fn override_from_value(value: Option<Value>) -> Option<PathBuf> {
+    match value {
         Some(Value::String(path_str)) => {
             let path_str = path_str.trim();
             if path_str.is_empty() {
@@ -70,13 +102,31 @@ pub fn refresh_app_config_dir_override(app: &tauri::AppHandle) -> Option<PathBuf
     value
 }
 
+/// 直接设置 app_config_dir 覆盖路径（供无 AppHandle 的命令行模式使用）
+pub fn set_app_config_dir_override(path: Option<&str>) {
+    update_cached_override(
+        path.map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(resolve_path),
+    );
+}
+
+/// 从 GUI 写入的 Store 文件加载 app_config_dir 覆盖值并更新缓存（供命令行模式使用）
+pub fn load_stored_app_config_dir_override() -> Option<PathBuf> {
+    let value = dirs::data_dir()
+        .map(|dir| dir.join(APP_IDENTIFIER).join(STORE_FILE_NAME))
+        .and_then(|path| read_override_from_file(&path));
+    update_cached_override(value.clone());
+    value
+}
+
 /// 写入 app_config_dir 到 Tauri Store
 pub fn set_app_config_dir_to_store(
     app: &tauri::AppHandle,
     path: Option<&str>,
 ) -> Result<(), AppError> {
     let store = app
-        .store_builder("app_paths.json")
+        .store_builder(STORE_FILE_NAME)
         .build()
         .map_err(|e| AppError::Message(format!("创建 Store 失败: {e}")))?;
 
@@ -134,3 +184,31 @@ pub fn migrate_app_config_dir_from_settings(app: &tauri::AppHandle) -> Result<()
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_override_from_store_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("config");
+        std::fs::create_dir_all(&target).unwrap();
+        let store_path = dir.path().join(STORE_FILE_NAME);
+
+        assert_eq!(read_override_from_file(&store_path), None);
+
+        std::fs::write(
+            &store_path,
+            serde_json::to_string(&serde_json::json!({
+                STORE_KEY_APP_CONFIG_DIR: target.to_string_lossy(),
+            }))
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(read_override_from_file(&store_path), Some(target.clone()));
+
+        // 路径不存在时回退到默认目录
+        std::fs::remove_dir_all(&target).unwrap();
+        assert_eq!(read_override_from_file(&store_path), None);
+    }
+}
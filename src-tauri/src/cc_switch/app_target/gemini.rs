@@ -0,0 +1,80 @@
+//! Gemini CLI 应用目标
+//!
+//! 复用 `gemini_config.rs` / `gemini_mcp.rs` 中的既有实现，行为与迁移前保持一致。
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use super::AppTarget;
+use crate::cc_switch::app_config::AppType;
+use crate::cc_switch::error::AppError;
+use crate::cc_switch::gemini_config::{
+    env_to_json, get_gemini_dir, get_gemini_env_path, get_gemini_settings_path, read_gemini_env,
+    validate_gemini_settings,
+};
+use crate::cc_switch::gemini_mcp::{spec_from_gemini_format, spec_to_gemini_format};
+use crate::cc_switch::provider::Provider;
+
+/// Gemini CLI（`~/.gemini`）
+pub struct GeminiTarget;
+
+impl AppTarget for GeminiTarget {
+    fn app_type(&self) -> AppType {
+        AppType::Gemini
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Gemini CLI"
+    }
+
+    fn config_dir(&self) -> PathBuf {
+        get_gemini_dir()
+    }
+
+    fn live_config_paths(&self) -> Vec<PathBuf> {
+        vec![get_gemini_env_path(), get_gemini_settings_path()]
+    }
+
+    fn read_provider_live(&self) -> Result<Value, AppError> {
+        Ok(env_to_json(&read_gemini_env()?))
+    }
+
+    fn write_provider_live(&self, provider: &Provider) -> Result<(), AppError> {
+        // 认证类型识别（Google 官方 / PackyCode / 通用）仍由 live.rs 负责
+        crate::cc_switch::services::provider::ProviderService::write_gemini_live(provider)
+    }
+
+    fn validate_provider_settings(&self, settings_config: &Value) -> Result<(), AppError> {
+        validate_gemini_settings(settings_config)
+    }
+
+    fn mcp_config_path(&self) -> PathBuf {
+        get_gemini_settings_path()
+    }
+
+    fn mcp_spec_to_live(&self, id: &str, spec: &Value) -> Result<Value, AppError> {
+        spec_to_gemini_format(id, spec)
+    }
+
+    fn mcp_spec_from_live(&self, mut spec: Value) -> Value {
+        spec_from_gemini_format(&mut spec);
+        spec
+    }
+
+    fn read_mcp_servers(&self) -> Result<HashMap<String, Value>, AppError> {
+        crate::cc_switch::gemini_mcp::read_mcp_servers_map()
+    }
+
+    fn write_mcp_servers(&self, servers: &HashMap<String, Value>) -> Result<(), AppError> {
+        crate::cc_switch::gemini_mcp::set_mcp_servers_map(servers)
+    }
+
+    fn prompt_file_path(&self) -> PathBuf {
+        get_gemini_dir().join("GEMINI.md")
+    }
+
+    fn skills_dir(&self) -> PathBuf {
+        get_gemini_dir().join("skills")
+    }
+}
@@ -0,0 +1,217 @@
+//! 应用目标（App Target）抽象
+//!
+//! 每个受管理的 CLI 都需要：配置目录、供应商 Live 配置读写、MCP 格式转换、
+//! 提示词文件路径和 Skills 目录。过去这些逻辑散落在 `config.rs`、`gemini_config.rs`、
+//! `mcp/*.rs` 等文件的 match 分支里，新增一个 CLI 要改动几十处。
+//!
+//! [`AppTarget`] 把这些能力收拢到一个 trait 中，新应用只需实现该 trait 并在
+//! [`target_for`] 中注册。现有应用按需逐步迁移，尚未迁移的应用返回 `None`，
+//! 调用方继续走原有分支。
+
+mod gemini;
+mod qwen;
+
+pub use gemini::GeminiTarget;
+pub use qwen::QwenTarget;
+
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::cc_switch::app_config::AppType;
+use crate::cc_switch::config::{read_json_file, write_json_file};
+use crate::cc_switch::error::AppError;
+use crate::cc_switch::provider::Provider;
+
+/// 应用目标：描述一个 CLI 的配置布局与格式转换
+pub trait AppTarget: Send + Sync {
+    /// 对应的应用类型
+    fn app_type(&self) -> AppType;
+
+    /// 显示名称（用于日志与 UI）
+    fn display_name(&self) -> &'static str;
+
+    /// 配置目录（已考虑 settings 中的目录覆盖）
+    fn config_dir(&self) -> PathBuf;
+
+    /// 应用是否已安装/初始化
+    ///
+    /// 未初始化时 MCP 等同步操作跳过写入，不主动创建目录。
+    fn is_installed(&self) -> bool {
+        self.config_dir().exists()
+    }
+
+    /// 供应商 Live 配置涉及的文件
+    fn live_config_paths(&self) -> Vec<PathBuf>;
+
+    /// 读取 Live 配置，返回与供应商 `settings_config` 同构的 JSON
+    fn read_provider_live(&self) -> Result<Value, AppError>;
+
+    /// 将供应商配置写入 Live 配置
+    fn write_provider_live(&self, provider: &Provider) -> Result<(), AppError>;
+
+    /// 校验供应商 `settings_config`
+    fn validate_provider_settings(&self, settings_config: &Value) -> Result<(), AppError>;
+
+    /// MCP 配置文件路径（JSON，服务器位于 `mcpServers` 字段）
+    fn mcp_config_path(&self) -> PathBuf;
+
+    /// 统一 MCP 格式 → 应用格式
+    fn mcp_spec_to_live(&self, id: &str, spec: &Value) -> Result<Value, AppError>;
+
+    /// 应用格式 → 统一 MCP 格式
+    fn mcp_spec_from_live(&self, spec: Value) -> Value;
+
+    /// 读取 Live 中的 MCP 服务器（已转换为统一格式）
+    fn read_mcp_servers(&self) -> Result<HashMap<String, Value>, AppError> {
+        let path = self.mcp_config_path();
+        let root = read_json_or_empty(&path)?;
+        Ok(root
+            .get("mcpServers")
+            .and_then(|v| v.as_object())
+            .map(|obj| {
+                obj.iter()
+                    .map(|(id, spec)| (id.clone(), self.mcp_spec_from_live(spec.clone())))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    /// 用给定的服务器集合覆盖 Live 中的 `mcpServers`，其它字段保持不变
+    fn write_mcp_servers(&self, servers: &HashMap<String, Value>) -> Result<(), AppError> {
+        let path = self.mcp_config_path();
+        let mut root = read_json_or_empty(&path)?;
+
+        let mut out = Map::new();
+        for (id, spec) in servers {
+            out.insert(id.clone(), self.mcp_spec_to_live(id, spec)?);
+        }
+
+        let obj = root
+            .as_object_mut()
+            .ok_or_else(|| AppError::Config(format!("{} 根必须是对象", path.display())))?;
+        obj.insert("mcpServers".to_string(), Value::Object(out));
+
+        write_json_file(&path, &root)
+    }
+
+    /// 提示词文件路径
+    fn prompt_file_path(&self) -> PathBuf;
+
+    /// Skills 目录
+    fn skills_dir(&self) -> PathBuf;
+}
+
+/// 获取应用对应的 [`AppTarget`]；尚未迁移到 trait 的应用返回 `None`
+///
+/// 调用方先经此分发，返回 `None` 时再走原有的 match 分支。
+pub fn target_for(app: &AppType) -> Option<&'static dyn AppTarget> {
+    match app {
+        AppType::Gemini => Some(&GeminiTarget),
+        AppType::Qwen => Some(&QwenTarget),
+        AppType::Claude | AppType::Codex | AppType::OpenCode | AppType::Cursor => None,
+    }
+}
+
+/// 原有 match 分支的兜底错误
+///
+/// 已迁移的应用在 [`target_for`] 处就被处理，原有分支只列出未迁移的应用，
+/// 其余情况返回该错误而不是 panic。
+pub fn not_ported(app: &AppType) -> AppError {
+    AppError::Message(format!("应用 {} 未注册 AppTarget", app.as_str()))
+}
+
+/// 读取 JSON 文件；文件不存在时返回空对象
+fn read_json_or_empty(path: &Path) -> Result<Value, AppError> {
+    if !path.exists() {
+        return Ok(json!({}));
+    }
+    read_json_file(path)
+}
+
+/// 获取用户主目录，失败时回退到当前目录
+fn home_dir() -> PathBuf {
+    dirs::home_dir().unwrap_or_else(|| {
+        log::warn!("无法获取用户主目录，回退到当前目录");
+        PathBuf::from(".")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::env;
+    use tempfile::TempDir;
+
+    #[test]
+    fn registry_returns_matching_targets() {
+        for app in [AppType::Gemini, AppType::Qwen] {
+            let target = target_for(&app).expect("ported app should have a target");
+            assert_eq!(target.app_type(), app);
+        }
+        for app in [
+            AppType::Claude,
+            AppType::Codex,
+            AppType::OpenCode,
+            AppType::Cursor,
+        ] {
+            assert!(target_for(&app).is_none(), "{app:?} is not ported yet");
+        }
+    }
+
+    #[test]
+    fn call_sites_dispatch_ported_apps_through_target() {
+        for app in AppType::ALL {
+            let Some(target) = target_for(&app) else {
+                continue;
+            };
+            assert_eq!(
+                crate::cc_switch::prompt_files::prompt_file_path(&app).expect("prompt path"),
+                target.prompt_file_path()
+            );
+            assert_eq!(
+                crate::cc_switch::services::skill::SkillService::get_app_skills_dir(&app)
+                    .expect("skills dir"),
+                target.skills_dir()
+            );
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn gemini_target_mcp_round_trip_matches_legacy_format() {
+        let dir = TempDir::new().expect("failed to create temp home");
+        let original_home = env::var("HOME").ok();
+        env::set_var("HOME", dir.path());
+        crate::cc_switch::settings::reload_settings().expect("reload settings");
+
+        let target = GeminiTarget;
+        std::fs::create_dir_all(target.config_dir()).expect("create gemini dir");
+        write_json_file(&target.mcp_config_path(), &json!({ "theme": "dark" }))
+            .expect("seed settings");
+
+        let mut servers = HashMap::new();
+        servers.insert(
+            "remote".to_string(),
+            json!({ "type": "http", "url": "https://mcp.example.com/mcp" }),
+        );
+        target.write_mcp_servers(&servers).expect("write mcp");
+
+        let raw = read_json_or_empty(&target.mcp_config_path()).expect("read raw");
+        assert_eq!(raw["theme"], "dark");
+        assert_eq!(
+            raw["mcpServers"]["remote"]["httpUrl"],
+            "https://mcp.example.com/mcp"
+        );
+        assert_eq!(
+            target.read_mcp_servers().expect("read mcp"),
+            crate::cc_switch::gemini_mcp::read_mcp_servers_map().expect("legacy read")
+        );
+
+        match original_home {
+            Some(value) => env::set_var("HOME", value),
+            None => env::remove_var("HOME"),
+        }
+    }
+}
@@ -0,0 +1,281 @@
+//! Qwen Code 应用目标
+//!
+//! Qwen Code 是 Gemini CLI 的分支，配置布局基本一致：
+//! - `~/.qwen/.env`：`OPENAI_API_KEY` / `OPENAI_BASE_URL` / `OPENAI_MODEL`
+//! - `~/.qwen/settings.json`：`security.auth.selectedType` 与 `mcpServers`
+//! - `~/.qwen/QWEN.md`：全局提示词
+//!
+//! 供应商 `settings_config` 与 Gemini 同构：`{ "env": {...}, "config": {...} }`。
+
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+use super::{home_dir, read_json_or_empty, AppTarget};
+use crate::cc_switch::app_config::AppType;
+use crate::cc_switch::config::write_json_file;
+use crate::cc_switch::error::AppError;
+use crate::cc_switch::gemini_config::{
+    env_to_json, json_to_env, parse_env_file, validate_gemini_settings, write_env_file_atomic,
+};
+use crate::cc_switch::gemini_mcp::{spec_from_gemini_format, spec_to_gemini_format};
+use crate::cc_switch::provider::Provider;
+
+/// 使用 API Key 时的认证方式
+const AUTH_TYPE_OPENAI: &str = "openai";
+/// 未配置 API Key 时回退到 Qwen OAuth（官方免费额度）
+const AUTH_TYPE_QWEN_OAUTH: &str = "qwen-oauth";
+
+/// Qwen Code（`~/.qwen`）
+pub struct QwenTarget;
+
+impl QwenTarget {
+    fn env_path(&self) -> PathBuf {
+        self.config_dir().join(".env")
+    }
+
+    fn settings_path(&self) -> PathBuf {
+        self.config_dir().join("settings.json")
+    }
+}
+
+impl AppTarget for QwenTarget {
+    fn app_type(&self) -> AppType {
+        AppType::Qwen
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Qwen Code"
+    }
+
+    fn config_dir(&self) -> PathBuf {
+        crate::cc_switch::settings::get_qwen_override_dir()
+            .unwrap_or_else(|| home_dir().join(".qwen"))
+    }
+
+    fn live_config_paths(&self) -> Vec<PathBuf> {
+        vec![self.env_path(), self.settings_path()]
+    }
+
+    fn read_provider_live(&self) -> Result<Value, AppError> {
+        let path = self.env_path();
+        if !path.exists() {
+            return Err(AppError::localized(
+                "qwen.live.missing",
+                "Qwen Code 配置文件不存在：缺少 .env",
+                "Qwen Code configuration missing: .env not found",
+            ));
+        }
+        let content = std::fs::read_to_string(&path).map_err(|e| AppError::io(&path, e))?;
+        Ok(env_to_json(&parse_env_file(&content)))
+    }
+
+    fn write_provider_live(&self, provider: &Provider) -> Result<(), AppError> {
+        self.validate_provider_settings(&provider.settings_config)?;
+        let env_map = json_to_env(&provider.settings_config)?;
+
+        // 与 Gemini 一致：config 合并进 settings.json，保留 mcpServers 等字段
+        let settings_path = self.settings_path();
+        let mut settings = read_json_or_empty(&settings_path)?;
+        let root = settings
+            .as_object_mut()
+            .ok_or_else(|| AppError::Config(format!("{} 根必须是对象", settings_path.display())))?;
+        if let Some(config) = provider
+            .settings_config
+            .get("config")
+            .and_then(|v| v.as_object())
+        {
+            for (k, v) in config {
+                root.insert(k.clone(), v.clone());
+            }
+        }
+
+        let has_api_key = env_map
+            .get("OPENAI_API_KEY")
+            .is_some_and(|key| !key.trim().is_empty());
+        let auth_type = if has_api_key {
+            AUTH_TYPE_OPENAI
+        } else {
+            AUTH_TYPE_QWEN_OAUTH
+        };
+        let security = root.entry("security").or_insert_with(|| json!({}));
+        if !security.is_object() {
+            *security = json!({});
+        }
+        let auth = security
+            .as_object_mut()
+            .map(|obj| obj.entry("auth").or_insert_with(|| json!({})));
+        match auth {
+            Some(auth) if auth.is_object() => {
+                auth["selectedType"] = json!(auth_type);
+            }
+            Some(auth) => {
+                *auth = json!({ "selectedType": auth_type });
+            }
+            None => {}
+        }
+
+        write_env_file_atomic(&self.env_path(), &env_map)?;
+        write_json_file(&settings_path, &settings)
+    }
+
+    fn validate_provider_settings(&self, settings_config: &Value) -> Result<(), AppError> {
+        if !settings_config.is_object() {
+            return Err(AppError::localized(
+                "provider.qwen.settings.not_object",
+                "Qwen Code 配置必须是 JSON 对象",
+                "Qwen Code configuration must be a JSON object",
+            ));
+        }
+        // env / config 的结构要求与 Gemini 相同
+        validate_gemini_settings(settings_config)
+    }
+
+    fn mcp_config_path(&self) -> PathBuf {
+        self.settings_path()
+    }
+
+    fn mcp_spec_to_live(&self, id: &str, spec: &Value) -> Result<Value, AppError> {
+        spec_to_gemini_format(id, spec)
+    }
+
+    fn mcp_spec_from_live(&self, mut spec: Value) -> Value {
+        spec_from_gemini_format(&mut spec);
+        spec
+    }
+
+    fn prompt_file_path(&self) -> PathBuf {
+        self.config_dir().join("QWEN.md")
+    }
+
+    fn skills_dir(&self) -> PathBuf {
+        self.config_dir().join("skills")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::collections::HashMap;
+    use std::env;
+    use tempfile::TempDir;
+
+    struct TempHome {
+        dir: TempDir,
+        original_home: Option<String>,
+    }
+
+    impl TempHome {
+        fn new() -> Self {
+            let dir = TempDir::new().expect("failed to create temp home");
+            let original_home = env::var("HOME").ok();
+            env::set_var("HOME", dir.path());
+            crate::cc_switch::settings::reload_settings().expect("reload settings");
+            Self { dir, original_home }
+        }
+    }
+
+    impl Drop for TempHome {
+        fn drop(&mut self) {
+            match &self.original_home {
+                Some(value) => env::set_var("HOME", value),
+                None => env::remove_var("HOME"),
+            }
+        }
+    }
+
+    fn provider(settings_config: Value) -> Provider {
+        Provider::with_id(
+            "qwen".to_string(),
+            "Qwen".to_string(),
+            settings_config,
+            None,
+        )
+    }
+
+    #[test]
+    #[serial]
+    fn provider_live_round_trip_preserves_mcp_servers() {
+        let home = TempHome::new();
+        let target = QwenTarget;
+        assert_eq!(target.config_dir(), home.dir.path().join(".qwen"));
+
+        let mut servers = HashMap::new();
+        servers.insert(
+            "fs".to_string(),
+            json!({ "type": "stdio", "command": "fs-mcp", "args": ["--root", "/"] }),
+        );
+        target.write_mcp_servers(&servers).expect("write mcp");
+
+        let settings_config = json!({
+            "env": {
+                "OPENAI_API_KEY": "sk-qwen",
+                "OPENAI_BASE_URL": "https://dashscope.aliyuncs.com/compatible-mode/v1",
+                "OPENAI_MODEL": "qwen3-coder-plus"
+            },
+            "config": { "ui": { "theme": "GitHub" } }
+        });
+        target
+            .write_provider_live(&provider(settings_config.clone()))
+            .expect("write live");
+
+        let live = target.read_provider_live().expect("read live");
+        assert_eq!(live["env"], settings_config["env"]);
+
+        let settings = read_json_or_empty(&target.settings_path()).expect("read settings");
+        assert_eq!(settings["security"]["auth"]["selectedType"], "openai");
+        assert_eq!(settings["ui"]["theme"], "GitHub");
+        assert!(settings["mcpServers"]["fs"].is_object());
+
+        // 无 API Key 时回退到 Qwen OAuth
+        target
+            .write_provider_live(&provider(json!({ "env": {} })))
+            .expect("write oauth");
+        let settings = read_json_or_empty(&target.settings_path()).expect("read settings");
+        assert_eq!(settings["security"]["auth"]["selectedType"], "qwen-oauth");
+    }
+
+    #[test]
+    #[serial]
+    fn mcp_round_trip_converts_http_transport() {
+        let _home = TempHome::new();
+        let target = QwenTarget;
+
+        let mut servers = HashMap::new();
+        servers.insert(
+            "remote".to_string(),
+            json!({ "type": "http", "url": "https://mcp.example.com/mcp" }),
+        );
+        servers.insert(
+            "local".to_string(),
+            json!({ "type": "stdio", "command": "npx", "args": ["-y", "mcp"] }),
+        );
+        target.write_mcp_servers(&servers).expect("write mcp");
+
+        let raw = read_json_or_empty(&target.mcp_config_path()).expect("read raw");
+        assert_eq!(
+            raw["mcpServers"]["remote"]["httpUrl"],
+            "https://mcp.example.com/mcp"
+        );
+        assert!(raw["mcpServers"]["remote"].get("type").is_none());
+
+        let read_back = target.read_mcp_servers().expect("read mcp");
+        assert_eq!(read_back["remote"]["type"], "http");
+        assert_eq!(read_back["remote"]["url"], "https://mcp.example.com/mcp");
+        assert_eq!(read_back["local"]["type"], "stdio");
+        assert_eq!(read_back["local"]["command"], "npx");
+    }
+
+    #[test]
+    #[serial]
+    fn prompt_and_skills_follow_config_dir() {
+        let home = TempHome::new();
+        let target = QwenTarget;
+        let base = home.dir.path().join(".qwen");
+
+        assert_eq!(target.prompt_file_path(), base.join("QWEN.md"));
+        assert_eq!(target.skills_dir(), base.join("skills"));
+        assert!(!target.is_installed());
+        assert!(target.validate_provider_settings(&json!("x")).is_err());
+    }
+}
@@ -0,0 +1,51 @@
+use tauri::{AppHandle, State};
+
+use crate::cc_switch::services::auto_switch::{AutoSwitchConfig, AutoSwitchRecord};
+use crate::cc_switch::services::AutoSwitchService;
+use crate::cc_switch::store::AppState;
+
+/// 获取供应商自动切换策略
+#[tauri::command]
+pub fn get_auto_switch_config(state: State<'_, AppState>) -> Result<AutoSwitchConfig, String> {
+    AutoSwitchService::get_config(state.inner()).map_err(|e| e.to_string())
+}
+
+/// 更新供应商自动切换策略
+#[tauri::command]
+pub fn set_auto_switch_config(
+    state: State<'_, AppState>,
+    config: AutoSwitchConfig,
+) -> Result<AutoSwitchConfig, String> {
+    AutoSwitchService::set_config(state.inner(), config).map_err(|e| e.to_string())
+}
+
+/// 预览 cron 表达式接下来的触发时间
+#[tauri::command]
+pub fn preview_auto_switch_schedule(
+    cron: String,
+    count: Option<usize>,
+) -> Result<Vec<String>, String> {
+    AutoSwitchService::preview_schedule(&cron, count.unwrap_or(5)).map_err(|e| e.to_string())
+}
+
+/// 立即检查一轮额度策略
+#[tauri::command]
+pub async fn run_auto_switch_quota_check_now(
+    app: AppHandle,
+) -> Result<Vec<AutoSwitchRecord>, String> {
+    AutoSwitchService::run_quota_checks(&app)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 获取供应商自动切换历史
+#[tauri::command]
+pub fn get_auto_switch_history(
+    state: State<'_, AppState>,
+    app: Option<String>,
+    #[allow(non_snake_case)] policyId: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<AutoSwitchRecord>, String> {
+    AutoSwitchService::list_history(state.inner(), app.as_deref(), policyId.as_deref(), limit)
+        .map_err(|e| e.to_string())
+}
@@ -5,6 +5,7 @@ use tauri_plugin_dialog::DialogExt;
 use tauri_plugin_opener::OpenerExt;
 
 use crate::cc_switch::app_config::AppType;
+use crate::cc_switch::app_target::{not_ported, target_for};
 use crate::cc_switch::codex_config;
 use crate::cc_switch::config::{self, get_claude_settings_path, ConfigStatus};
 use crate::cc_switch::settings;
@@ -39,7 +40,14 @@ fn invalid_json_format_error(error: serde_json::Error) -> String {
 
 #[tauri::command]
 pub async fn get_config_status(app: String) -> Result<ConfigStatus, String> {
-    match AppType::from_str(&app).map_err(|e| e.to_string())? {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    if let Some(target) = target_for(&app_type) {
+        let exists = target.live_config_paths().iter().any(|p| p.exists());
+        let path = target.config_dir().to_string_lossy().to_string();
+        return Ok(ConfigStatus { exists, path });
+    }
+
+    match app_type {
         AppType::Claude => Ok(config::get_claude_config_status()),
         AppType::Codex => {
             let auth_path = codex_config::get_codex_auth_path();
@@ -50,15 +58,6 @@ pub async fn get_config_status(app: String) -> Result<ConfigStatus, String> {
 
             Ok(ConfigStatus { exists, path })
         }
-        AppType::Gemini => {
-            let env_path = crate::cc_switch::gemini_config::get_gemini_env_path();
-            let exists = env_path.exists();
-            let path = crate::cc_switch::gemini_config::get_gemini_dir()
-                .to_string_lossy()
-                .to_string();
-
-            Ok(ConfigStatus { exists, path })
-        }
         AppType::OpenCode => {
             let config_path = crate::cc_switch::opencode_config::get_opencode_config_path();
             let exists = config_path.exists();
@@ -75,6 +74,7 @@ pub async fn get_config_status(app: String) -> Result<ConfigStatus, String> {
 
             Ok(ConfigStatus { exists, path })
         }
+        _ => Err(not_ported(&app_type).to_string()),
     }
 }
 
@@ -84,16 +84,26 @@ pub async fn get_claude_code_config_path() -> Result<String, String> {
     Ok(get_claude_settings_path().to_string_lossy().to_string())
 }
 
-/// 获取当前生效的配置目录
-#[tauri::command]
-pub async fn get_config_dir(app: String) -> Result<String, String> {
-    let dir = match AppType::from_str(&app).map_err(|e| e.to_string())? {
+/// 解析应用当前生效的配置目录，已迁移的应用由 AppTarget 给出
+fn app_config_dir(app: &str) -> Result<PathBuf, String> {
+    let app_type = AppType::from_str(app).map_err(|e| e.to_string())?;
+    if let Some(target) = target_for(&app_type) {
+        return Ok(target.config_dir());
+    }
+
+    Ok(match app_type {
         AppType::Claude => config::get_claude_config_dir(),
         AppType::Codex => codex_config::get_codex_config_dir(),
-        AppType::Gemini => crate::cc_switch::gemini_config::get_gemini_dir(),
         AppType::OpenCode => crate::cc_switch::opencode_config::get_opencode_dir(),
         AppType::Cursor => get_cursor_dir(),
-    };
+        _ => return Err(not_ported(&app_type).to_string()),
+    })
+}
+
+/// 获取当前生效的配置目录
+#[tauri::command]
+pub async fn get_config_dir(app: String) -> Result<String, String> {
+    let dir = app_config_dir(&app)?;
 
     Ok(dir.to_string_lossy().to_string())
 }
@@ -101,13 +111,7 @@ pub async fn get_config_dir(app: String) -> Result<String, String> {
 /// 打开配置文件夹
 #[tauri::command]
 pub async fn open_config_folder(handle: AppHandle, app: String) -> Result<bool, String> {
-    let config_dir = match AppType::from_str(&app).map_err(|e| e.to_string())? {
-        AppType::Claude => config::get_claude_config_dir(),
-        AppType::Codex => codex_config::get_codex_config_dir(),
-        AppType::Gemini => crate::cc_switch::gemini_config::get_gemini_dir(),
-        AppType::OpenCode => crate::cc_switch::opencode_config::get_opencode_dir(),
-        AppType::Cursor => get_cursor_dir(),
-    };
+    let config_dir = app_config_dir(&app)?;
 
     if !config_dir.exists() {
         std::fs::create_dir_all(&config_dir).map_err(|e| format!("创建目录失败: {e}"))?;
@@ -1,6 +1,8 @@
 use crate::cc_switch::deeplink::{
-    import_mcp_from_deeplink, import_prompt_from_deeplink, import_provider_from_deeplink,
-    import_skill_from_deeplink, parse_deeplink_url, DeepLinkImportRequest,
+    add_trusted_publisher, fetch_bundle_manifest, import_bundle, import_mcp_from_deeplink,
+    import_prompt_from_deeplink, import_provider_from_deeplink, import_skill_from_deeplink,
+    parse_deeplink_url, preview_bundle, remove_trusted_publisher, BundleImportResult,
+    BundlePreview, DeepLinkImportRequest, TrustedPublisher,
 };
 use crate::cc_switch::store::AppState;
 use tauri::State;
@@ -84,8 +86,72 @@ pub async fn import_from_deeplink_unified(
                 "key": skill_key
             }))
         }
+        "bundle" => Err(
+            "Bundles must be previewed first, use preview_deeplink_bundle / import_deeplink_bundle"
+                .to_string(),
+        ),
         _ => Err(format!("Unsupported resource type: {}", request.resource)),
     }
 }
 
+/// Preview every item of a bundle deep link (nothing is written)
+#[tauri::command]
+pub async fn preview_deeplink_bundle(
+    state: State<'_, AppState>,
+    request: DeepLinkImportRequest,
+) -> Result<BundlePreview, String> {
+    log::info!("Previewing bundle from deep link");
+
+    let manifest = fetch_bundle_manifest(&request)
+        .await
+        .map_err(|e| e.to_string())?;
+    preview_bundle(&state, &request, &manifest).map_err(|e| e.to_string())
+}
+
+/// Import the selected items of a previewed bundle
+///
+/// `digest` must be the digest returned by `preview_deeplink_bundle`.
+#[tauri::command]
+pub async fn import_deeplink_bundle(
+    state: State<'_, AppState>,
+    request: DeepLinkImportRequest,
+    selected: Vec<String>,
+    digest: String,
+) -> Result<BundleImportResult, String> {
+    log::info!("Importing {} selected bundle items", selected.len());
+
+    let manifest = fetch_bundle_manifest(&request)
+        .await
+        .map_err(|e| e.to_string())?;
+    import_bundle(&state, &request, &manifest, &selected, &digest).map_err(|e| e.to_string())
+}
 
+/// List trusted bundle publisher keys
+#[tauri::command]
+pub fn get_trusted_bundle_publishers(
+    state: State<AppState>,
+) -> Result<Vec<TrustedPublisher>, String> {
+    state
+        .db
+        .get_trusted_bundle_publishers()
+        .map_err(|e| e.to_string())
+}
+
+/// Trust a bundle publisher's Ed25519 public key (Base64)
+#[tauri::command]
+pub fn add_trusted_bundle_publisher(
+    state: State<AppState>,
+    name: String,
+    public_key: String,
+) -> Result<Vec<TrustedPublisher>, String> {
+    add_trusted_publisher(&state.db, &name, &public_key).map_err(|e| e.to_string())
+}
+
+/// Remove a trusted bundle publisher key
+#[tauri::command]
+pub fn remove_trusted_bundle_publisher(
+    state: State<AppState>,
+    public_key: String,
+) -> Result<Vec<TrustedPublisher>, String> {
+    remove_trusted_publisher(&state.db, &public_key).map_err(|e| e.to_string())
+}
@@ -1,4 +1,9 @@
-use crate::cc_switch::services::env_checker::{check_env_conflicts as check_conflicts, EnvConflict};
+use crate::cc_switch::services::env_checker::{
+    check_env_conflicts as check_conflicts, get_keywords_for_app, EnvConflict,
+};
+use crate::cc_switch::services::env_fixer::{
+    self, EnvFixPlan, EnvFixRecord, EnvFixTarget, FixMode, RcAssignment,
+};
 use crate::cc_switch::services::env_manager::{
     delete_env_vars as delete_vars, restore_from_backup, BackupInfo,
 };
@@ -21,3 +26,45 @@ pub fn restore_env_backup(backup_path: String) -> Result<(), String> {
     restore_from_backup(backup_path)
 }
 
+fn home_dir() -> Result<std::path::PathBuf, String> {
+    dirs::home_dir().ok_or_else(|| "无法获取用户主目录".to_string())
+}
+
+/// Scan shell rc files (including sourced files) for variables of an app
+#[tauri::command]
+pub fn scan_env_rc_files(app: String) -> Result<Vec<RcAssignment>, String> {
+    let keywords = get_keywords_for_app(&app);
+    if keywords.is_empty() {
+        return Err(format!("不支持的应用: {app}"));
+    }
+    Ok(env_fixer::scan_rc_files(&home_dir()?, &keywords))
+}
+
+/// Preview disabling the selected rc file assignments as a unified diff
+#[tauri::command]
+pub fn preview_env_fix(
+    targets: Vec<EnvFixTarget>,
+    mode: Option<FixMode>,
+) -> Result<EnvFixPlan, String> {
+    env_fixer::plan_env_fix(&home_dir()?, targets, mode.unwrap_or_default())
+}
+
+/// Preview restoring the lines disabled by a previous fix
+#[tauri::command]
+pub fn preview_env_fix_restore(
+    #[allow(non_snake_case)] fixId: String,
+) -> Result<EnvFixPlan, String> {
+    env_fixer::plan_env_restore(&home_dir()?, &fixId)
+}
+
+/// Apply a previewed fix or restore plan
+#[tauri::command]
+pub fn apply_env_fix(plan: EnvFixPlan) -> Result<(), String> {
+    env_fixer::apply_env_plan(&home_dir()?, &plan)
+}
+
+/// List fixes whose markers are still present in rc files
+#[tauri::command]
+pub fn list_env_fixes() -> Result<Vec<EnvFixRecord>, String> {
+    Ok(env_fixer::list_env_fixes(&home_dir()?))
+}
@@ -0,0 +1,61 @@
+use tauri::{AppHandle, Manager, State};
+
+use crate::cc_switch::error::AppError;
+use crate::cc_switch::services::folder_sync::{
+    FolderSyncConfig, FolderSyncReport, SyncConflictEntry,
+};
+use crate::cc_switch::services::FolderSyncService;
+use crate::cc_switch::store::AppState;
+
+/// 获取目录同步配置
+#[tauri::command]
+pub async fn get_folder_sync_config(
+    state: State<'_, AppState>,
+) -> Result<FolderSyncConfig, String> {
+    FolderSyncService::get_config(&state).map_err(|e| e.to_string())
+}
+
+/// 更新目录同步配置
+#[tauri::command]
+pub async fn set_folder_sync_config(
+    config: FolderSyncConfig,
+    state: State<'_, AppState>,
+) -> Result<FolderSyncConfig, String> {
+    FolderSyncService::set_config(&state, config).map_err(|e| e.to_string())
+}
+
+/// 立即执行一次目录同步
+#[tauri::command]
+pub async fn run_folder_sync(app: AppHandle) -> Result<FolderSyncReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let app_state = app.state::<AppState>();
+        // 手动同步前备份现有数据库
+        app_state.db.backup_database_file()?;
+
+        let report = FolderSyncService::sync_now(&app_state)?;
+        if !report.pulled.is_empty() {
+            FolderSyncService::apply_to_live(&app_state, &report)?;
+        }
+        Ok::<_, AppError>(report)
+    })
+    .await
+    .map_err(|e| format!("目录同步失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
+/// 列出同步冲突记录
+#[tauri::command]
+pub async fn list_folder_sync_conflicts(
+    state: State<'_, AppState>,
+) -> Result<Vec<SyncConflictEntry>, String> {
+    FolderSyncService::list_conflicts(&state).map_err(|e| e.to_string())
+}
+
+/// 确认并删除一条同步冲突记录
+#[tauri::command]
+pub async fn dismiss_folder_sync_conflict(
+    #[allow(non_snake_case)] fileName: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    FolderSyncService::dismiss_conflict(&state, &fileName).map_err(|e| e.to_string())
+}
@@ -2,11 +2,16 @@
 
 use serde_json::{json, Value};
 use std::path::PathBuf;
-use tauri::State;
+use tauri::{AppHandle, Manager, State};
 use tauri_plugin_dialog::DialogExt;
 
 use crate::cc_switch::error::AppError;
+use crate::cc_switch::services::config_bundle::{
+    ConfigBundleImportReport, ConfigBundleManifest, ConfigBundlePreview, ConfigBundleService,
+    ExportOptions, ImportOptions,
+};
 use crate::cc_switch::services::provider::ProviderService;
+use crate::cc_switch::services::McpService;
 use crate::cc_switch::store::AppState;
 
 /// 导出数据库为 SQL 备份
@@ -64,6 +69,85 @@ pub async fn import_config_from_file(
     .map_err(|e: AppError| e.to_string())
 }
 
+/// 按范围导出配置包（zip，可加密或移除密钥）
+#[tauri::command]
+pub async fn export_config_bundle(
+    #[allow(non_snake_case)] filePath: String,
+    options: ExportOptions,
+    state: State<'_, AppState>,
+) -> Result<ConfigBundleManifest, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let app_state = AppState::new(db);
+        ConfigBundleService::export(&app_state, &PathBuf::from(&filePath), &options)
+    })
+    .await
+    .map_err(|e| format!("导出配置包失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
+/// 读取配置包 manifest（用于判断是否需要口令）
+#[tauri::command]
+pub async fn read_config_bundle_manifest(
+    #[allow(non_snake_case)] filePath: String,
+) -> Result<ConfigBundleManifest, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        ConfigBundleService::read_manifest(&PathBuf::from(&filePath))
+    })
+    .await
+    .map_err(|e| format!("读取配置包失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
+/// 预览配置包内容及冲突
+#[tauri::command]
+pub async fn inspect_config_bundle(
+    #[allow(non_snake_case)] filePath: String,
+    passphrase: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ConfigBundlePreview, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let app_state = AppState::new(db);
+        ConfigBundleService::inspect(&app_state, &PathBuf::from(&filePath), passphrase.as_deref())
+    })
+    .await
+    .map_err(|e| format!("读取配置包失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
+/// 以合并模式导入配置包
+#[tauri::command]
+pub async fn import_config_bundle(
+    #[allow(non_snake_case)] filePath: String,
+    options: ImportOptions,
+    app: AppHandle,
+) -> Result<ConfigBundleImportReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        // 使用托管的状态，代理接管期间同步 live 时只更新备份
+        let app_state = app.state::<AppState>();
+        // 导入前备份现有数据库
+        app_state.db.backup_database_file()?;
+
+        let report = ConfigBundleService::import(&app_state, &PathBuf::from(&filePath), &options)?;
+
+        if let Err(err) = ProviderService::sync_current_to_live(&app_state) {
+            log::warn!("导入配置包后同步 live 配置失败: {err}");
+        }
+        if let Err(err) = McpService::sync_all_enabled(&app_state) {
+            log::warn!("导入配置包后同步 MCP 配置失败: {err}");
+        }
+        if let Err(err) = crate::cc_switch::settings::reload_settings() {
+            log::warn!("导入配置包后重载设置失败: {err}");
+        }
+
+        Ok::<_, AppError>(report)
+    })
+    .await
+    .map_err(|e| format!("导入配置包失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
 #[tauri::command]
 pub async fn sync_current_providers_live(state: State<'_, AppState>) -> Result<Value, String> {
     let db = state.db.clone();
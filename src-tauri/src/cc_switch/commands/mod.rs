@@ -1,9 +1,11 @@
 #![allow(non_snake_case)]
 
+mod auto_switch;
 mod config;
 mod deeplink;
 mod env;
 mod failover;
+mod folder_sync;
 mod global_proxy;
 mod import_export;
 mod mcp;
@@ -14,13 +16,16 @@ mod provider;
 mod proxy;
 mod settings;
 pub mod skill;
+mod snapshot;
 mod stream_check;
 mod usage;
 
+pub use auto_switch::*;
 pub use config::*;
 pub use deeplink::*;
 pub use env::*;
 pub use failover::*;
+pub use folder_sync::*;
 pub use global_proxy::*;
 pub use import_export::*;
 pub use mcp::*;
@@ -31,5 +36,6 @@ pub use provider::*;
 pub use proxy::*;
 pub use settings::*;
 pub use skill::*;
+pub use snapshot::*;
 pub use stream_check::*;
 pub use usage::*;
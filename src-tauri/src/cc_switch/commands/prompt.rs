@@ -1,10 +1,13 @@
 use indexmap::IndexMap;
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
 use tauri::State;
 
 use crate::cc_switch::app_config::AppType;
-use crate::cc_switch::prompt::Prompt;
+use crate::cc_switch::prompt::{Prompt, PromptFragment, PromptVersion};
+use crate::cc_switch::prompt_template::RenderedPrompt;
+use crate::cc_switch::services::prompt::PromptVersionDiff;
 use crate::cc_switch::services::PromptService;
 use crate::cc_switch::store::AppState;
 
@@ -63,3 +66,91 @@ pub async fn get_current_prompt_file_content(app: String) -> Result<Option<Strin
     PromptService::get_current_file_content(app_type).map_err(|e| e.to_string())
 }
 
+/// 预览提示词模板渲染结果（不写入文件）
+#[tauri::command]
+pub async fn preview_prompt_template(
+    app: String,
+    content: String,
+    state: State<'_, AppState>,
+) -> Result<RenderedPrompt, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    PromptService::preview(&state, app_type, &content).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_prompt_fragments(
+    state: State<'_, AppState>,
+) -> Result<IndexMap<String, PromptFragment>, String> {
+    PromptService::get_fragments(&state).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn upsert_prompt_fragment(
+    fragment: PromptFragment,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    PromptService::upsert_fragment(&state, fragment).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_prompt_fragment(id: String, state: State<'_, AppState>) -> Result<(), String> {
+    PromptService::delete_fragment(&state, &id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_prompt_variables(
+    state: State<'_, AppState>,
+) -> Result<BTreeMap<String, String>, String> {
+    PromptService::get_variables(&state).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_prompt_variable(
+    name: String,
+    value: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    PromptService::set_variable(&state, &name, &value).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_prompt_variable(
+    name: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    PromptService::delete_variable(&state, &name).map_err(|e| e.to_string())
+}
+
+/// 获取提示词历史版本；`prompt_id` 为空时返回 live 提示词文件的历史
+#[tauri::command]
+pub async fn list_prompt_versions(
+    app: String,
+    prompt_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<PromptVersion>, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    PromptService::list_versions(&state, app_type, prompt_id.as_deref()).map_err(|e| e.to_string())
+}
+
+/// 对比两个历史版本；`to_version_id` 为空时与当前内容对比
+#[tauri::command]
+pub async fn diff_prompt_versions(
+    app: String,
+    from_version_id: i64,
+    to_version_id: Option<i64>,
+    state: State<'_, AppState>,
+) -> Result<PromptVersionDiff, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    PromptService::diff_versions(&state, app_type, from_version_id, to_version_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn restore_prompt_version(
+    app: String,
+    version_id: i64,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    PromptService::restore_version(&state, app_type, version_id).map_err(|e| e.to_string())
+}
@@ -4,7 +4,13 @@ use tauri::State;
 use crate::cc_switch::app_config::AppType;
 use crate::cc_switch::error::AppError;
 use crate::cc_switch::provider::Provider;
-use crate::cc_switch::services::{EndpointLatency, ProviderService, ProviderSortUpdate, SpeedtestService};
+use crate::cc_switch::services::endpoint_auto_select::{
+    EndpointAutoSelectConfig, EndpointProbe, EndpointSwitchRecord,
+};
+use crate::cc_switch::services::{
+    EndpointAutoSelectService, EndpointLatency, ProviderService, ProviderSortUpdate,
+    SpeedtestService,
+};
 use crate::cc_switch::store::AppState;
 use std::str::FromStr;
 
@@ -231,6 +237,63 @@ pub fn update_endpoint_last_used(
         .map_err(|e| e.to_string())
 }
 
+/// 获取端点自动选择配置
+#[tauri::command]
+pub fn get_endpoint_auto_select_config(
+    state: State<'_, AppState>,
+) -> Result<EndpointAutoSelectConfig, String> {
+    EndpointAutoSelectService::get_config(state.inner()).map_err(|e| e.to_string())
+}
+
+/// 更新端点自动选择配置
+#[tauri::command]
+pub fn set_endpoint_auto_select_config(
+    state: State<'_, AppState>,
+    config: EndpointAutoSelectConfig,
+) -> Result<EndpointAutoSelectConfig, String> {
+    EndpointAutoSelectService::set_config(state.inner(), config).map_err(|e| e.to_string())
+}
+
+/// 使用真实 API 请求测试供应商的全部端点（不切换）
+#[tauri::command]
+pub async fn probe_provider_endpoints(
+    state: State<'_, AppState>,
+    app: String,
+    #[allow(non_snake_case)] providerId: String,
+) -> Result<Vec<EndpointProbe>, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    EndpointAutoSelectService::probe_provider(&state.db, &app_type, &providerId)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 立即执行一轮端点自动选择
+#[tauri::command]
+pub async fn run_endpoint_auto_select_now(
+    app: AppHandle,
+) -> Result<Vec<EndpointSwitchRecord>, String> {
+    EndpointAutoSelectService::run_once(&app)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 获取端点自动切换历史
+#[tauri::command]
+pub fn get_endpoint_switch_history(
+    state: State<'_, AppState>,
+    app: Option<String>,
+    #[allow(non_snake_case)] providerId: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<EndpointSwitchRecord>, String> {
+    EndpointAutoSelectService::list_history(
+        state.inner(),
+        app.as_deref(),
+        providerId.as_deref(),
+        limit,
+    )
+    .map_err(|e| e.to_string())
+}
+
 /// 更新多个供应商的排序
 #[tauri::command]
 pub fn update_providers_sort_order(
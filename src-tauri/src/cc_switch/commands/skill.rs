@@ -5,14 +5,18 @@
 //! - SSOT 存储在 ~/.config/mnemosyne/skills/
 
 use crate::cc_switch::app_config::{AppType, InstalledSkill, UnmanagedSkill};
+use crate::cc_switch::database::dao::SkillVersion;
 use crate::cc_switch::error::format_skill_error;
 use crate::cc_switch::services::skill::{
-    DiscoverableSkill, Skill, SkillFileContentResult, SkillFileTreeResult, SkillRemoteRefreshResult,
-    SkillRepo, SkillService, SkillSingleRemoteRefreshResult, SkillUpdateInfo,
+    DiscoverableSkill, Skill, SkillContentUpdateResult, SkillFileContentResult,
+    SkillFileTreeResult, SkillLockApplyResult, SkillLockfile, SkillRemoteRefreshResult, SkillRepo,
+    SkillService, SkillSingleRemoteRefreshResult, SkillUpdateInfo,
 };
+use crate::cc_switch::services::skill_scan::SkillScanReport;
 use crate::cc_switch::store::AppState;
 use serde_json::json;
 use serde_json::Value;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::State;
 
@@ -27,6 +31,7 @@ fn parse_app_type(app: &str) -> Result<AppType, String> {
         "gemini" => Ok(AppType::Gemini),
         "opencode" => Ok(AppType::OpenCode),
         "cursor" => Ok(AppType::Cursor),
+        "qwen" => Ok(AppType::Qwen),
         _ => Err(format!("不支持的 app 类型: {app}")),
     }
 }
@@ -91,10 +96,12 @@ pub async fn refresh_installed_skill_remote(
 /// 参数：
 /// - skill: 从发现列表获取的技能信息
 /// - current_app: 当前选中的应用，安装后默认启用该应用
+/// - acknowledged_fingerprint: 用户已确认的扫描报告指纹（报告含风险项时必填）
 #[tauri::command]
 pub async fn install_skill_unified(
     skill: DiscoverableSkill,
     current_app: String,
+    acknowledged_fingerprint: Option<String>,
     service: State<'_, SkillServiceState>,
     app_state: State<'_, AppState>,
 ) -> Result<InstalledSkill, String> {
@@ -102,7 +109,12 @@ pub async fn install_skill_unified(
 
     service
         .0
-        .install(&app_state.db, &skill, &app_type)
+        .install(
+            &app_state.db,
+            &skill,
+            &app_type,
+            acknowledged_fingerprint.as_deref(),
+        )
         .await
         .map_err(|e| e.to_string())
 }
@@ -144,6 +156,110 @@ pub fn import_skills_from_apps(
     SkillService::import_from_apps(&app_state.db, directories).map_err(|e| e.to_string())
 }
 
+// ========== 安全扫描命令 ==========
+
+/// 安装前扫描技能，返回需用户确认的报告
+#[tauri::command]
+pub async fn scan_skill_before_install(
+    skill: DiscoverableSkill,
+    service: State<'_, SkillServiceState>,
+) -> Result<SkillScanReport, String> {
+    service
+        .0
+        .scan_discoverable(&skill)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 扫描已安装技能的当前内容
+#[tauri::command]
+pub fn scan_installed_skill(
+    id: String,
+    app_state: State<'_, AppState>,
+) -> Result<SkillScanReport, String> {
+    SkillService::scan_installed(&app_state.db, &id).map_err(|e| e.to_string())
+}
+
+// ========== 版本锁定与回滚命令 ==========
+
+/// 设置或清除 Skill 的锁定引用（commit SHA 或 tag）
+///
+/// 仅更新记录；调用 `update_installed_skill` 后才会拉取锁定版本的内容。
+#[tauri::command]
+pub fn set_skill_pin(
+    id: String,
+    git_ref: Option<String>,
+    app_state: State<'_, AppState>,
+) -> Result<InstalledSkill, String> {
+    SkillService::set_pinned_ref(&app_state.db, &id, git_ref).map_err(|e| e.to_string())
+}
+
+/// 将 Skill 内容更新到锁定引用（未锁定时为分支最新提交）
+#[tauri::command]
+pub async fn update_installed_skill(
+    id: String,
+    acknowledged_fingerprint: Option<String>,
+    service: State<'_, SkillServiceState>,
+    app_state: State<'_, AppState>,
+) -> Result<SkillContentUpdateResult, String> {
+    service
+        .0
+        .update_installed(&app_state.db, &id, acknowledged_fingerprint.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 获取 Skill 的历史版本列表
+#[tauri::command]
+pub fn list_skill_versions(
+    id: String,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<SkillVersion>, String> {
+    SkillService::list_versions(&app_state.db, &id).map_err(|e| e.to_string())
+}
+
+/// 回滚 Skill 到指定历史版本
+#[tauri::command]
+pub fn rollback_skill(
+    id: String,
+    version_id: i64,
+    app_state: State<'_, AppState>,
+) -> Result<InstalledSkill, String> {
+    SkillService::rollback(&app_state.db, &id, version_id).map_err(|e| e.to_string())
+}
+
+/// 导出 Skills lockfile
+#[tauri::command]
+pub fn export_skill_lockfile(
+    file_path: String,
+    app_state: State<'_, AppState>,
+) -> Result<SkillLockfile, String> {
+    SkillService::export_lockfile(&app_state.db, std::path::Path::new(&file_path))
+        .map_err(|e| e.to_string())
+}
+
+/// 按 lockfile 安装/更新 Skills
+///
+/// `acknowledged` 为用户确认过的扫描指纹（条目 ID -> 指纹），
+/// 未确认的风险条目会在结果的 `pending` 中返回扫描报告。
+#[tauri::command]
+pub async fn apply_skill_lockfile(
+    file_path: String,
+    acknowledged: Option<HashMap<String, String>>,
+    service: State<'_, SkillServiceState>,
+    app_state: State<'_, AppState>,
+) -> Result<SkillLockApplyResult, String> {
+    service
+        .0
+        .apply_lockfile(
+            &app_state.db,
+            std::path::Path::new(&file_path),
+            &acknowledged.unwrap_or_default(),
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
 // ========== 发现功能命令 ==========
 
 /// 浏览 skills.sh 技能列表
@@ -271,7 +387,7 @@ pub async fn install_skill(
     service: State<'_, SkillServiceState>,
     app_state: State<'_, AppState>,
 ) -> Result<bool, String> {
-    install_skill_for_app("claude".to_string(), directory, service, app_state).await
+    install_skill_for_app("claude".to_string(), directory, None, service, app_state).await
 }
 
 /// 安装指定应用的技能（兼容旧 API）
@@ -279,6 +395,7 @@ pub async fn install_skill(
 pub async fn install_skill_for_app(
     app: String,
     directory: String,
+    acknowledged_fingerprint: Option<String>,
     service: State<'_, SkillServiceState>,
     app_state: State<'_, AppState>,
 ) -> Result<bool, String> {
@@ -312,7 +429,12 @@ pub async fn install_skill_for_app(
 
     service
         .0
-        .install(&app_state.db, &skill, &app_type)
+        .install(
+            &app_state.db,
+            &skill,
+            &app_type,
+            acknowledged_fingerprint.as_deref(),
+        )
         .await
         .map_err(|e| e.to_string())?;
 
@@ -0,0 +1,81 @@
+use tauri::{AppHandle, Manager, State};
+
+use crate::cc_switch::database::{DbSnapshotDetails, DbSnapshotDiff, DbSnapshotInfo};
+use crate::cc_switch::error::AppError;
+use crate::cc_switch::services::snapshot::SnapshotScheduleConfig;
+use crate::cc_switch::services::SnapshotService;
+use crate::cc_switch::store::AppState;
+
+/// 获取数据库定时快照配置
+#[tauri::command]
+pub async fn get_db_snapshot_schedule(
+    state: State<'_, AppState>,
+) -> Result<SnapshotScheduleConfig, String> {
+    SnapshotService::get_schedule(&state).map_err(|e| e.to_string())
+}
+
+/// 更新数据库定时快照配置
+#[tauri::command]
+pub async fn set_db_snapshot_schedule(
+    config: SnapshotScheduleConfig,
+    state: State<'_, AppState>,
+) -> Result<SnapshotScheduleConfig, String> {
+    SnapshotService::set_schedule(&state, config).map_err(|e| e.to_string())
+}
+
+/// 列出数据库快照
+#[tauri::command]
+pub async fn list_db_snapshots() -> Result<Vec<DbSnapshotInfo>, String> {
+    SnapshotService::list().map_err(|e| e.to_string())
+}
+
+/// 立即创建一个数据库快照
+#[tauri::command]
+pub async fn create_db_snapshot(app: AppHandle) -> Result<DbSnapshotInfo, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        SnapshotService::create_now(&app.state::<AppState>())
+    })
+    .await
+    .map_err(|e| format!("创建快照失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
+/// 查看快照内容概要
+#[tauri::command]
+pub async fn inspect_db_snapshot(
+    #[allow(non_snake_case)] snapshotId: String,
+) -> Result<DbSnapshotDetails, String> {
+    tauri::async_runtime::spawn_blocking(move || SnapshotService::inspect(&snapshotId))
+        .await
+        .map_err(|e| format!("读取快照失败: {e}"))?
+        .map_err(|e: AppError| e.to_string())
+}
+
+/// 对比快照与当前数据库
+#[tauri::command]
+pub async fn diff_db_snapshot(
+    #[allow(non_snake_case)] snapshotId: String,
+    app: AppHandle,
+) -> Result<DbSnapshotDiff, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        SnapshotService::diff(&app.state::<AppState>(), &snapshotId)
+    })
+    .await
+    .map_err(|e| format!("对比快照失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
+/// 从快照恢复数据库，返回恢复前自动创建的安全快照
+#[tauri::command]
+pub async fn restore_db_snapshot(
+    #[allow(non_snake_case)] snapshotId: String,
+    app: AppHandle,
+) -> Result<DbSnapshotInfo, String> {
+    // 恢复后会写入 live 配置，需使用托管的 AppState 以识别代理接管
+    tauri::async_runtime::spawn_blocking(move || {
+        SnapshotService::restore(&app.state::<AppState>(), &snapshotId)
+    })
+    .await
+    .map_err(|e| format!("恢复快照失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
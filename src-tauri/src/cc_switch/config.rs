@@ -137,6 +137,16 @@ pub fn get_models_dir() -> PathBuf {
     get_app_config_dir().join("models")
 }
 
+/// 获取媒体任务队列文件路径
+pub fn get_media_jobs_path() -> PathBuf {
+    get_app_config_dir().join("media_jobs.json")
+}
+
+/// 获取托管 FFmpeg 可执行文件目录路径
+pub fn get_ffmpeg_binaries_dir() -> PathBuf {
+    get_app_config_dir().join("ffmpeg")
+}
+
 /// 清理供应商名称，确保文件名安全
 #[allow(dead_code)]
 pub fn sanitize_provider_name(name: &str) -> String {
@@ -2,6 +2,7 @@
 //!
 //! 提供 SQL 导出/导入和二进制快照备份功能。
 
+use super::snapshot::DbSnapshotKind;
 use super::{lock_conn, Database, DB_BACKUP_RETAIN};
 use crate::cc_switch::config::get_db_path;
 use crate::cc_switch::error::AppError;
@@ -96,6 +97,17 @@ impl Database {
         Ok(snapshot)
     }
 
+    /// 用内存快照覆盖主库（批量写入失败时回滚）
+    pub(crate) fn restore_from_snapshot(&self, snapshot: &Connection) -> Result<(), AppError> {
+        let mut main_conn = lock_conn!(self.conn);
+        let backup =
+            Backup::new(snapshot, &mut main_conn).map_err(|e| AppError::Database(e.to_string()))?;
+        backup
+            .step(-1)
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
     fn validate_cc_switch_sql_export(sql: &str) -> Result<(), AppError> {
         let trimmed = sql.trim_start();
         if trimmed.starts_with(CC_SWITCH_SQL_EXPORT_HEADER) {
@@ -110,74 +122,16 @@ impl Database {
     }
 
     /// 生成一致性快照备份，返回备份文件路径（不存在主库时返回 None）
-    fn backup_database_file(&self) -> Result<Option<PathBuf>, AppError> {
+    pub(crate) fn backup_database_file(&self) -> Result<Option<PathBuf>, AppError> {
         let db_path = get_db_path();
         if !db_path.exists() {
             return Ok(None);
         }
 
-        let backup_dir = db_path
-            .parent()
-            .ok_or_else(|| AppError::Config("无效的数据库路径".to_string()))?
-            .join("backups");
-
-        fs::create_dir_all(&backup_dir).map_err(|e| AppError::io(&backup_dir, e))?;
-
-        let base_id = format!("db_backup_{}", Utc::now().format("%Y%m%d_%H%M%S"));
-        let mut backup_id = base_id.clone();
-        let mut backup_path = backup_dir.join(format!("{backup_id}.db"));
-        let mut counter = 1;
-        while backup_path.exists() {
-            backup_id = format!("{base_id}_{counter}");
-            backup_path = backup_dir.join(format!("{backup_id}.db"));
-            counter += 1;
-        }
-
-        {
-            let conn = lock_conn!(self.conn);
-            let mut dest_conn =
-                Connection::open(&backup_path).map_err(|e| AppError::Database(e.to_string()))?;
-            let backup = Backup::new(&conn, &mut dest_conn)
-                .map_err(|e| AppError::Database(e.to_string()))?;
-            backup
-                .step(-1)
-                .map_err(|e| AppError::Database(e.to_string()))?;
-        }
-
-        Self::cleanup_db_backups(&backup_dir)?;
-        Ok(Some(backup_path))
-    }
-
-    /// 清理旧的数据库备份，保留最新的 N 个
-    fn cleanup_db_backups(dir: &Path) -> Result<(), AppError> {
-        let entries = match fs::read_dir(dir) {
-            Ok(iter) => iter
-                .filter_map(|entry| entry.ok())
-                .filter(|entry| {
-                    entry
-                        .path()
-                        .extension()
-                        .map(|ext| ext == "db")
-                        .unwrap_or(false)
-                })
-                .collect::<Vec<_>>(),
-            Err(_) => return Ok(()),
-        };
-
-        if entries.len() <= DB_BACKUP_RETAIN {
-            return Ok(());
-        }
-
-        let remove_count = entries.len().saturating_sub(DB_BACKUP_RETAIN);
-        let mut sorted = entries;
-        sorted.sort_by_key(|entry| entry.metadata().and_then(|m| m.modified()).ok());
-
-        for entry in sorted.into_iter().take(remove_count) {
-            if let Err(err) = fs::remove_file(entry.path()) {
-                log::warn!("删除旧数据库备份失败 {}: {}", entry.path().display(), err);
-            }
-        }
-        Ok(())
+        let backup_dir = Self::snapshot_dir()?;
+        let info =
+            self.create_snapshot_in(&backup_dir, DbSnapshotKind::Backup, DB_BACKUP_RETAIN)?;
+        Ok(Some(backup_dir.join(format!("{}.db", info.id))))
     }
 
     /// 基础状态校验
@@ -284,7 +238,10 @@ impl Database {
     }
 
     /// 获取表的列名列表
-    fn get_table_columns(conn: &Connection, table: &str) -> Result<Vec<String>, AppError> {
+    pub(super) fn get_table_columns(
+        conn: &Connection,
+        table: &str,
+    ) -> Result<Vec<String>, AppError> {
         let mut stmt = conn
             .prepare(&format!("PRAGMA table_info(\"{table}\")"))
             .map_err(|e| AppError::Database(e.to_string()))?;
@@ -0,0 +1,119 @@
+//! 供应商自动切换数据访问对象
+//!
+//! 提供 provider_switch_logs 表的读写操作与自动切换策略配置的存取。
+
+use crate::cc_switch::database::{lock_conn, Database};
+use crate::cc_switch::error::AppError;
+use crate::cc_switch::services::auto_switch::{
+    AutoSwitchConfig, AutoSwitchRecord, AutoSwitchTriggerKind,
+};
+use rusqlite::{params, Row};
+
+const PROVIDER_SWITCH_COLUMNS: &str =
+    "id, policy_id, policy_name, app_type, trigger_type, from_provider_id, \
+     to_provider_id, to_provider_name, detail, switched_at";
+
+fn map_provider_switch(row: &Row<'_>) -> rusqlite::Result<AutoSwitchRecord> {
+    let trigger: String = row.get(4)?;
+    Ok(AutoSwitchRecord {
+        id: row.get(0)?,
+        policy_id: row.get(1)?,
+        policy_name: row.get(2)?,
+        app_type: row.get(3)?,
+        trigger: AutoSwitchTriggerKind::parse(&trigger),
+        from_provider_id: row.get(5)?,
+        to_provider_id: row.get(6)?,
+        to_provider_name: row.get(7)?,
+        detail: row.get(8)?,
+        switched_at: row.get(9)?,
+    })
+}
+
+impl Database {
+    /// 记录一次自动切换，返回记录 ID
+    pub fn insert_provider_switch_log(&self, record: &AutoSwitchRecord) -> Result<i64, AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT INTO provider_switch_logs
+             (policy_id, policy_name, app_type, trigger_type, from_provider_id,
+              to_provider_id, to_provider_name, detail, switched_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                record.policy_id,
+                record.policy_name,
+                record.app_type,
+                record.trigger.as_str(),
+                record.from_provider_id,
+                record.to_provider_id,
+                record.to_provider_name,
+                record.detail,
+                record.switched_at,
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// 获取自动切换历史（最新在前），可按应用和策略过滤
+    pub fn list_provider_switch_logs(
+        &self,
+        app_type: Option<&str>,
+        policy_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<AutoSwitchRecord>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {PROVIDER_SWITCH_COLUMNS} FROM provider_switch_logs
+                 WHERE (?1 IS NULL OR app_type = ?1) AND (?2 IS NULL OR policy_id = ?2)
+                 ORDER BY switched_at DESC, id DESC
+                 LIMIT ?3"
+            ))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map(
+                params![app_type, policy_id, limit as i64],
+                map_provider_switch,
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row.map_err(|e| AppError::Database(e.to_string()))?);
+        }
+        Ok(result)
+    }
+
+    /// 只保留最新的 `keep` 条自动切换历史，返回删除的数量
+    pub fn prune_provider_switch_logs(&self, keep: usize) -> Result<usize, AppError> {
+        let conn = lock_conn!(self.conn);
+        let removed = conn
+            .execute(
+                "DELETE FROM provider_switch_logs WHERE id NOT IN (
+                    SELECT id FROM provider_switch_logs
+                    ORDER BY switched_at DESC, id DESC
+                    LIMIT ?1
+                )",
+                params![keep as i64],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(removed)
+    }
+
+    /// 获取供应商自动切换策略配置
+    pub fn get_auto_switch_config(&self) -> Result<AutoSwitchConfig, AppError> {
+        match self.get_setting("provider_auto_switch_config")? {
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|e| AppError::Database(format!("解析自动切换策略失败: {e}"))),
+            None => Ok(AutoSwitchConfig::default()),
+        }
+    }
+
+    /// 保存供应商自动切换策略配置
+    pub fn set_auto_switch_config(&self, config: &AutoSwitchConfig) -> Result<(), AppError> {
+        let json = serde_json::to_string(config)
+            .map_err(|e| AppError::Database(format!("序列化自动切换策略失败: {e}")))?;
+        self.set_setting("provider_auto_switch_config", &json)
+    }
+}
@@ -0,0 +1,125 @@
+//! 端点自动切换历史数据访问对象
+//!
+//! 提供 endpoint_switch_logs 表的读写操作。
+
+use crate::cc_switch::database::{lock_conn, Database};
+use crate::cc_switch::error::AppError;
+use crate::cc_switch::services::endpoint_auto_select::{
+    EndpointAutoSelectConfig, EndpointSwitchReason, EndpointSwitchRecord,
+};
+use rusqlite::{params, Row};
+
+const ENDPOINT_SWITCH_COLUMNS: &str =
+    "id, app_type, provider_id, provider_name, from_url, to_url, \
+     from_latency_ms, to_latency_ms, reason, switched_at";
+
+fn map_endpoint_switch(row: &Row<'_>) -> rusqlite::Result<EndpointSwitchRecord> {
+    let reason: String = row.get(8)?;
+    Ok(EndpointSwitchRecord {
+        id: row.get(0)?,
+        app_type: row.get(1)?,
+        provider_id: row.get(2)?,
+        provider_name: row.get(3)?,
+        from_url: row.get(4)?,
+        to_url: row.get(5)?,
+        from_latency_ms: row.get::<_, Option<i64>>(6)?.map(|v| v as u64),
+        to_latency_ms: row.get::<_, i64>(7)? as u64,
+        reason: EndpointSwitchReason::parse(&reason),
+        switched_at: row.get(9)?,
+    })
+}
+
+impl Database {
+    /// 记录一次端点切换，返回记录 ID
+    pub fn insert_endpoint_switch_log(
+        &self,
+        record: &EndpointSwitchRecord,
+    ) -> Result<i64, AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT INTO endpoint_switch_logs
+             (app_type, provider_id, provider_name, from_url, to_url,
+              from_latency_ms, to_latency_ms, reason, switched_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                record.app_type,
+                record.provider_id,
+                record.provider_name,
+                record.from_url,
+                record.to_url,
+                record.from_latency_ms.map(|v| v as i64),
+                record.to_latency_ms as i64,
+                record.reason.as_str(),
+                record.switched_at,
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// 获取端点切换历史（最新在前），可按应用和供应商过滤
+    pub fn list_endpoint_switch_logs(
+        &self,
+        app_type: Option<&str>,
+        provider_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<EndpointSwitchRecord>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {ENDPOINT_SWITCH_COLUMNS} FROM endpoint_switch_logs
+                 WHERE (?1 IS NULL OR app_type = ?1) AND (?2 IS NULL OR provider_id = ?2)
+                 ORDER BY switched_at DESC, id DESC
+                 LIMIT ?3"
+            ))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map(
+                params![app_type, provider_id, limit as i64],
+                map_endpoint_switch,
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row.map_err(|e| AppError::Database(e.to_string()))?);
+        }
+        Ok(result)
+    }
+
+    /// 只保留最新的 `keep` 条端点切换历史，返回删除的数量
+    pub fn prune_endpoint_switch_logs(&self, keep: usize) -> Result<usize, AppError> {
+        let conn = lock_conn!(self.conn);
+        let removed = conn
+            .execute(
+                "DELETE FROM endpoint_switch_logs WHERE id NOT IN (
+                    SELECT id FROM endpoint_switch_logs
+                    ORDER BY switched_at DESC, id DESC
+                    LIMIT ?1
+                )",
+                params![keep as i64],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(removed)
+    }
+
+    /// 获取端点自动选择配置
+    pub fn get_endpoint_auto_select_config(&self) -> Result<EndpointAutoSelectConfig, AppError> {
+        match self.get_setting("endpoint_auto_select_config")? {
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|e| AppError::Database(format!("解析端点自动选择配置失败: {e}"))),
+            None => Ok(EndpointAutoSelectConfig::default()),
+        }
+    }
+
+    /// 保存端点自动选择配置
+    pub fn set_endpoint_auto_select_config(
+        &self,
+        config: &EndpointAutoSelectConfig,
+    ) -> Result<(), AppError> {
+        let json = serde_json::to_string(config)
+            .map_err(|e| AppError::Database(format!("序列化端点自动选择配置失败: {e}")))?;
+        self.set_setting("endpoint_auto_select_config", &json)
+    }
+}
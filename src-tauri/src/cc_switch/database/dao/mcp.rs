@@ -13,7 +13,7 @@ impl Database {
     pub fn get_all_mcp_servers(&self) -> Result<IndexMap<String, McpServer>, AppError> {
         let conn = lock_conn!(self.conn);
         let mut stmt = conn.prepare(
-            "SELECT id, name, server_config, description, homepage, docs, tags, enabled_claude, enabled_codex, enabled_gemini, enabled_opencode, enabled_cursor
+            "SELECT id, name, server_config, description, homepage, docs, tags, enabled_claude, enabled_codex, enabled_gemini, enabled_opencode, enabled_cursor, enabled_qwen
              FROM mcp_servers
              ORDER BY name ASC, id ASC"
         ).map_err(|e| AppError::Database(e.to_string()))?;
@@ -32,6 +32,7 @@ impl Database {
                 let enabled_gemini: bool = row.get(9)?;
                 let enabled_opencode: bool = row.get(10)?;
                 let enabled_cursor: bool = row.get(11)?;
+                let enabled_qwen: bool = row.get(12)?;
 
                 let server = serde_json::from_str(&server_config_str).unwrap_or_default();
                 let tags = serde_json::from_str(&tags_str).unwrap_or_default();
@@ -48,6 +49,7 @@ impl Database {
                             gemini: enabled_gemini,
                             opencode: enabled_opencode,
                             cursor: enabled_cursor,
+                            qwen: enabled_qwen,
                         },
                         description,
                         homepage,
@@ -72,8 +74,9 @@ impl Database {
         conn.execute(
             "INSERT OR REPLACE INTO mcp_servers (
                 id, name, server_config, description, homepage, docs, tags,
-                enabled_claude, enabled_codex, enabled_gemini, enabled_opencode, enabled_cursor
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                enabled_claude, enabled_codex, enabled_gemini, enabled_opencode, enabled_cursor,
+                enabled_qwen
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
             params![
                 server.id,
                 server.name,
@@ -90,6 +93,7 @@ impl Database {
                 server.apps.gemini,
                 server.apps.opencode,
                 server.apps.cursor,
+                server.apps.qwen,
             ],
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
@@ -2,13 +2,18 @@
 //!
 //! Database access operations for each domain
 
+pub mod auto_switch;
+pub mod endpoint_switch;
 pub mod failover;
 pub mod mcp;
+pub mod prompt_fragments;
+pub mod prompt_versions;
 pub mod prompts;
 pub mod providers;
 pub mod proxy;
 pub mod settings;
 pub mod skill_cache;
+pub mod skill_versions;
 pub mod skills;
 pub mod stream_check;
 pub mod universal_providers;
@@ -16,3 +21,4 @@ pub mod universal_providers;
 // 所有 DAO 方法都通过 Database impl 提供，无需单独导出
 // 导出 FailoverQueueItem 供外部使用
 pub use failover::FailoverQueueItem;
+pub use skill_versions::SkillVersion;
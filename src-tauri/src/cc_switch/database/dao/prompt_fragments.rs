@@ -0,0 +1,114 @@
+//! 提示词片段与模板变量数据访问对象
+//!
+//! 提供 prompt_fragments / prompt_variables 表的 CRUD 操作。
+//! 片段与变量跨应用共享，由提示词模板在启用时引用。
+
+use crate::cc_switch::database::{lock_conn, Database};
+use crate::cc_switch::error::AppError;
+use crate::cc_switch::prompt::PromptFragment;
+use indexmap::IndexMap;
+use rusqlite::params;
+use std::collections::BTreeMap;
+
+impl Database {
+    /// 获取所有提示词片段（按创建时间排序）
+    pub fn get_prompt_fragments(&self) -> Result<IndexMap<String, PromptFragment>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, name, content, description, created_at, updated_at
+                 FROM prompt_fragments
+                 ORDER BY created_at ASC, id ASC",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(PromptFragment {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    content: row.get(2)?,
+                    description: row.get(3)?,
+                    created_at: row.get(4)?,
+                    updated_at: row.get(5)?,
+                })
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut fragments = IndexMap::new();
+        for row in rows {
+            let fragment = row.map_err(|e| AppError::Database(e.to_string()))?;
+            fragments.insert(fragment.id.clone(), fragment);
+        }
+        Ok(fragments)
+    }
+
+    /// 保存提示词片段
+    pub fn save_prompt_fragment(&self, fragment: &PromptFragment) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT OR REPLACE INTO prompt_fragments (
+                id, name, content, description, created_at, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                fragment.id,
+                fragment.name,
+                fragment.content,
+                fragment.description,
+                fragment.created_at,
+                fragment.updated_at,
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 删除提示词片段
+    pub fn delete_prompt_fragment(&self, id: &str) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute("DELETE FROM prompt_fragments WHERE id = ?1", params![id])
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 获取所有用户自定义模板变量（按名称排序）
+    pub fn get_prompt_variables(&self) -> Result<BTreeMap<String, String>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare("SELECT name, value FROM prompt_variables")
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut variables = BTreeMap::new();
+        for row in rows {
+            let (name, value) = row.map_err(|e| AppError::Database(e.to_string()))?;
+            variables.insert(name, value);
+        }
+        Ok(variables)
+    }
+
+    /// 设置模板变量
+    pub fn set_prompt_variable(&self, name: &str, value: &str) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT OR REPLACE INTO prompt_variables (name, value) VALUES (?1, ?2)",
+            params![name, value],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 删除模板变量
+    pub fn delete_prompt_variable(&self, name: &str) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "DELETE FROM prompt_variables WHERE name = ?1",
+            params![name],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+//! 提示词历史版本数据访问对象
+//!
+//! 提供 prompt_versions 表的读写操作。
+//! `prompt_id` 为 NULL 的记录对应 live 提示词文件本身。
+
+use crate::cc_switch::database::{lock_conn, Database};
+use crate::cc_switch::error::AppError;
+use crate::cc_switch::prompt::PromptVersion;
+use rusqlite::{params, Row};
+
+const PROMPT_VERSION_COLUMNS: &str =
+    "id, app_type, prompt_id, name, content, templated, source, created_at";
+
+fn map_prompt_version(row: &Row<'_>) -> rusqlite::Result<PromptVersion> {
+    Ok(PromptVersion {
+        id: row.get(0)?,
+        app_type: row.get(1)?,
+        prompt_id: row.get(2)?,
+        name: row.get(3)?,
+        content: row.get(4)?,
+        templated: row.get(5)?,
+        source: row.get(6)?,
+        created_at: row.get(7)?,
+    })
+}
+
+impl Database {
+    /// 记录一个提示词历史版本，返回新版本 ID
+    pub fn insert_prompt_version(&self, version: &PromptVersion) -> Result<i64, AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT INTO prompt_versions
+             (app_type, prompt_id, name, content, templated, source, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                version.app_type,
+                version.prompt_id,
+                version.name,
+                version.content,
+                version.templated,
+                version.source,
+                version.created_at,
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// 获取提示词（或 live 文件，`prompt_id` 为 None）的历史版本（最新在前）
+    pub fn list_prompt_versions(
+        &self,
+        app_type: &str,
+        prompt_id: Option<&str>,
+    ) -> Result<Vec<PromptVersion>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {PROMPT_VERSION_COLUMNS} FROM prompt_versions
+                 WHERE app_type = ?1 AND prompt_id IS ?2
+                 ORDER BY created_at DESC, id DESC"
+            ))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map(params![app_type, prompt_id], map_prompt_version)
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row.map_err(|e| AppError::Database(e.to_string()))?);
+        }
+        Ok(result)
+    }
+
+    /// 获取单个提示词历史版本
+    pub fn get_prompt_version(&self, id: i64) -> Result<Option<PromptVersion>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let result = conn.query_row(
+            &format!("SELECT {PROMPT_VERSION_COLUMNS} FROM prompt_versions WHERE id = ?1"),
+            params![id],
+            map_prompt_version,
+        );
+
+        match result {
+            Ok(version) => Ok(Some(version)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(AppError::Database(e.to_string())),
+        }
+    }
+
+    /// 获取最新一个历史版本的内容（用于去重）
+    pub fn get_latest_prompt_version_content(
+        &self,
+        app_type: &str,
+        prompt_id: Option<&str>,
+    ) -> Result<Option<String>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let result = conn.query_row(
+            "SELECT content FROM prompt_versions
+             WHERE app_type = ?1 AND prompt_id IS ?2
+             ORDER BY created_at DESC, id DESC LIMIT 1",
+            params![app_type, prompt_id],
+            |row| row.get(0),
+        );
+
+        match result {
+            Ok(content) => Ok(Some(content)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(AppError::Database(e.to_string())),
+        }
+    }
+
+    /// 仅保留最新的 `keep` 个历史版本，返回删除的数量
+    pub fn prune_prompt_versions(
+        &self,
+        app_type: &str,
+        prompt_id: Option<&str>,
+        keep: usize,
+    ) -> Result<usize, AppError> {
+        let conn = lock_conn!(self.conn);
+        let deleted = conn
+            .execute(
+                "DELETE FROM prompt_versions
+                 WHERE app_type = ?1 AND prompt_id IS ?2 AND id NOT IN (
+                     SELECT id FROM prompt_versions
+                     WHERE app_type = ?1 AND prompt_id IS ?2
+                     ORDER BY created_at DESC, id DESC LIMIT ?3
+                 )",
+                params![app_type, prompt_id, keep as i64],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(deleted)
+    }
+}
@@ -177,11 +177,11 @@ impl Database {
         Ok(())
     }
 
-    /// 初始化 proxy_config 表的三行数据
+    /// 初始化 proxy_config 表的每应用数据行
     async fn init_proxy_config_rows(&self) -> Result<(), AppError> {
         let conn = lock_conn!(self.conn);
 
-        for app_type in &["claude", "codex", "gemini"] {
+        for app_type in &["claude", "codex", "gemini", "opencode"] {
             conn.execute(
                 "INSERT OR IGNORE INTO proxy_config (app_type) VALUES (?1)",
                 [app_type],
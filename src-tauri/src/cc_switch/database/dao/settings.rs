@@ -5,6 +5,7 @@
 use crate::cc_switch::database::{lock_conn, Database};
 use crate::cc_switch::error::AppError;
 use rusqlite::params;
+use std::collections::BTreeMap;
 
 impl Database {
     /// 获取设置值
@@ -38,6 +39,25 @@ impl Database {
         Ok(())
     }
 
+    /// 获取所有设置键值对（按键排序）
+    pub fn get_all_settings(&self) -> Result<BTreeMap<String, String>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare("SELECT key, value FROM settings")
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut settings = BTreeMap::new();
+        for row in rows {
+            let (key, value) = row.map_err(|e| AppError::Database(e.to_string()))?;
+            settings.insert(key, value);
+        }
+        Ok(settings)
+    }
+
     // --- Config Snippets 辅助方法 ---
 
     /// 获取通用配置片段
@@ -204,6 +224,95 @@ impl Database {
             .map_err(|e| AppError::Database(format!("序列化日志配置失败: {e}")))?;
         self.set_setting("log_config", &json)
     }
-}
 
+    // --- Bundle 受信任发布者 ---
+
+    /// 获取受信任的 bundle 发布者公钥列表
+    pub fn get_trusted_bundle_publishers(
+        &self,
+    ) -> Result<Vec<crate::cc_switch::deeplink::TrustedPublisher>, AppError> {
+        match self.get_setting("trusted_bundle_publishers")? {
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|e| AppError::Database(format!("解析受信任发布者失败: {e}"))),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// 更新受信任的 bundle 发布者公钥列表
+    pub fn set_trusted_bundle_publishers(
+        &self,
+        publishers: &[crate::cc_switch::deeplink::TrustedPublisher],
+    ) -> Result<(), AppError> {
+        let json = serde_json::to_string(publishers)
+            .map_err(|e| AppError::Database(format!("序列化受信任发布者失败: {e}")))?;
+        self.set_setting("trusted_bundle_publishers", &json)
+    }
+
+    // --- 目录同步 ---
+
+    /// 获取目录同步配置
+    pub fn get_folder_sync_config(
+        &self,
+    ) -> Result<crate::cc_switch::services::folder_sync::FolderSyncConfig, AppError> {
+        match self.get_setting("folder_sync_config")? {
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|e| AppError::Database(format!("解析目录同步配置失败: {e}"))),
+            None => Ok(Default::default()),
+        }
+    }
+
+    /// 更新目录同步配置
+    pub fn set_folder_sync_config(
+        &self,
+        config: &crate::cc_switch::services::folder_sync::FolderSyncConfig,
+    ) -> Result<(), AppError> {
+        let json = serde_json::to_string(config)
+            .map_err(|e| AppError::Database(format!("序列化目录同步配置失败: {e}")))?;
+        self.set_setting("folder_sync_config", &json)
+    }
 
+    /// 获取各条目上次同步时的状态
+    pub fn get_folder_sync_state(
+        &self,
+    ) -> Result<BTreeMap<String, crate::cc_switch::services::folder_sync::SyncBaseEntry>, AppError>
+    {
+        match self.get_setting("folder_sync_state")? {
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|e| AppError::Database(format!("解析目录同步状态失败: {e}"))),
+            None => Ok(BTreeMap::new()),
+        }
+    }
+
+    /// 更新各条目上次同步时的状态
+    pub fn set_folder_sync_state(
+        &self,
+        state: &BTreeMap<String, crate::cc_switch::services::folder_sync::SyncBaseEntry>,
+    ) -> Result<(), AppError> {
+        let json = serde_json::to_string(state)
+            .map_err(|e| AppError::Database(format!("序列化目录同步状态失败: {e}")))?;
+        self.set_setting("folder_sync_state", &json)
+    }
+
+    // --- 数据库定时快照 ---
+
+    /// 获取定时快照配置
+    pub fn get_db_snapshot_schedule(
+        &self,
+    ) -> Result<crate::cc_switch::services::snapshot::SnapshotScheduleConfig, AppError> {
+        match self.get_setting("db_snapshot_schedule")? {
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|e| AppError::Database(format!("解析定时快照配置失败: {e}"))),
+            None => Ok(Default::default()),
+        }
+    }
+
+    /// 更新定时快照配置
+    pub fn set_db_snapshot_schedule(
+        &self,
+        config: &crate::cc_switch::services::snapshot::SnapshotScheduleConfig,
+    ) -> Result<(), AppError> {
+        let json = serde_json::to_string(config)
+            .map_err(|e| AppError::Database(format!("序列化定时快照配置失败: {e}")))?;
+        self.set_setting("db_snapshot_schedule", &json)
+    }
+}
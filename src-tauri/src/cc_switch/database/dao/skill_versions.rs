@@ -0,0 +1,127 @@
+//! Skill 历史版本数据访问对象
+//!
+//! 提供 skill_versions 表的 CRUD 操作。
+//! 实际文件快照存放在 SSOT 下的 `.versions/<directory>/<snapshot_dir>`。
+
+use crate::cc_switch::database::{lock_conn, Database};
+use crate::cc_switch::error::AppError;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+/// Skill 历史版本条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillVersion {
+    pub id: i64,
+    pub skill_id: String,
+    /// 快照目录名（位于 `.versions/<directory>/` 下）
+    pub snapshot_dir: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tree_commit_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pinned_ref: Option<String>,
+    pub created_at: i64,
+}
+
+impl Database {
+    /// 记录一个 Skill 历史版本，返回新版本 ID
+    pub fn insert_skill_version(&self, version: &SkillVersion) -> Result<i64, AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT INTO skill_versions
+             (skill_id, snapshot_dir, commit_id, tree_commit_id, pinned_ref, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                version.skill_id,
+                version.snapshot_dir,
+                version.commit_id,
+                version.tree_commit_id,
+                version.pinned_ref,
+                version.created_at,
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// 获取 Skill 的所有历史版本（最新在前）
+    pub fn list_skill_versions(&self, skill_id: &str) -> Result<Vec<SkillVersion>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, skill_id, snapshot_dir, commit_id, tree_commit_id, pinned_ref, created_at
+                 FROM skill_versions WHERE skill_id = ?1
+                 ORDER BY created_at DESC, id DESC",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map(params![skill_id], |row| {
+                Ok(SkillVersion {
+                    id: row.get(0)?,
+                    skill_id: row.get(1)?,
+                    snapshot_dir: row.get(2)?,
+                    commit_id: row.get(3)?,
+                    tree_commit_id: row.get(4)?,
+                    pinned_ref: row.get(5)?,
+                    created_at: row.get(6)?,
+                })
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row.map_err(|e| AppError::Database(e.to_string()))?);
+        }
+        Ok(result)
+    }
+
+    /// 获取单个 Skill 历史版本
+    pub fn get_skill_version(&self, id: i64) -> Result<Option<SkillVersion>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let result = conn.query_row(
+            "SELECT id, skill_id, snapshot_dir, commit_id, tree_commit_id, pinned_ref, created_at
+             FROM skill_versions WHERE id = ?1",
+            params![id],
+            |row| {
+                Ok(SkillVersion {
+                    id: row.get(0)?,
+                    skill_id: row.get(1)?,
+                    snapshot_dir: row.get(2)?,
+                    commit_id: row.get(3)?,
+                    tree_commit_id: row.get(4)?,
+                    pinned_ref: row.get(5)?,
+                    created_at: row.get(6)?,
+                })
+            },
+        );
+
+        match result {
+            Ok(version) => Ok(Some(version)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(AppError::Database(e.to_string())),
+        }
+    }
+
+    /// 删除单个 Skill 历史版本记录
+    pub fn delete_skill_version(&self, id: i64) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let affected = conn
+            .execute("DELETE FROM skill_versions WHERE id = ?1", params![id])
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(affected > 0)
+    }
+
+    /// 删除 Skill 的所有历史版本记录（卸载时调用）
+    pub fn delete_skill_versions_by_skill(&self, skill_id: &str) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "DELETE FROM skill_versions WHERE skill_id = ?1",
+            params![skill_id],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+}
@@ -3,7 +3,7 @@
 //! 提供 Skills 和 Skill Repos 的 CRUD 操作。
 //!
 //! v3.10.0+ 统一管理架构：
-//! - Skills 使用统一的 id 主键，支持四应用启用标志
+//! - Skills 使用统一的 id 主键，支持按应用启用标志
 //! - 实际文件存储在 ~/.config/mnemosyne/skills/，同步到各应用目录
 
 use crate::cc_switch::app_config::{InstalledSkill, SkillApps};
@@ -22,7 +22,8 @@ impl Database {
         let mut stmt = conn
             .prepare(
                 "SELECT id, name, description, directory, repo_owner, repo_name, repo_branch,
-                        readme_url, tree_commit_id, enabled_claude, enabled_codex, enabled_gemini, enabled_opencode, enabled_cursor, installed_at
+                        readme_url, tree_commit_id, enabled_claude, enabled_codex, enabled_gemini, enabled_opencode, enabled_cursor, installed_at,
+                        pinned_ref, commit_id, enabled_qwen
                  FROM skills ORDER BY name ASC",
             )
             .map_err(|e| AppError::Database(e.to_string()))?;
@@ -45,8 +46,11 @@ impl Database {
                         gemini: row.get(11)?,
                         opencode: row.get(12)?,
                         cursor: row.get(13)?,
+                        qwen: row.get(17)?,
                     },
                     installed_at: row.get(14)?,
+                    pinned_ref: row.get(15)?,
+                    commit_id: row.get(16)?,
                 })
             })
             .map_err(|e| AppError::Database(e.to_string()))?;
@@ -65,7 +69,8 @@ impl Database {
         let mut stmt = conn
             .prepare(
                 "SELECT id, name, description, directory, repo_owner, repo_name, repo_branch,
-                        readme_url, tree_commit_id, enabled_claude, enabled_codex, enabled_gemini, enabled_opencode, enabled_cursor, installed_at
+                        readme_url, tree_commit_id, enabled_claude, enabled_codex, enabled_gemini, enabled_opencode, enabled_cursor, installed_at,
+                        pinned_ref, commit_id, enabled_qwen
                  FROM skills WHERE id = ?1",
             )
             .map_err(|e| AppError::Database(e.to_string()))?;
@@ -87,8 +92,11 @@ impl Database {
                     gemini: row.get(11)?,
                     opencode: row.get(12)?,
                     cursor: row.get(13)?,
+                    qwen: row.get(17)?,
                 },
                 installed_at: row.get(14)?,
+                pinned_ref: row.get(15)?,
+                commit_id: row.get(16)?,
             })
         });
 
@@ -105,8 +113,9 @@ impl Database {
         conn.execute(
             "INSERT OR REPLACE INTO skills
              (id, name, description, directory, repo_owner, repo_name, repo_branch,
-              readme_url, tree_commit_id, enabled_claude, enabled_codex, enabled_gemini, enabled_opencode, enabled_cursor, installed_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+              readme_url, tree_commit_id, enabled_claude, enabled_codex, enabled_gemini, enabled_opencode, enabled_cursor, installed_at,
+              pinned_ref, commit_id, enabled_qwen)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)",
             params![
                 skill.id,
                 skill.name,
@@ -123,6 +132,9 @@ impl Database {
                 skill.apps.opencode,
                 skill.apps.cursor,
                 skill.installed_at,
+                skill.pinned_ref,
+                skill.commit_id,
+                skill.apps.qwen,
             ],
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
@@ -151,8 +163,16 @@ impl Database {
         let conn = lock_conn!(self.conn);
         let affected = conn
             .execute(
-                "UPDATE skills SET enabled_claude = ?1, enabled_codex = ?2, enabled_gemini = ?3, enabled_opencode = ?4, enabled_cursor = ?5 WHERE id = ?6",
-                params![apps.claude, apps.codex, apps.gemini, apps.opencode, apps.cursor, id],
+                "UPDATE skills SET enabled_claude = ?1, enabled_codex = ?2, enabled_gemini = ?3, enabled_opencode = ?4, enabled_cursor = ?5, enabled_qwen = ?6 WHERE id = ?7",
+                params![
+                    apps.claude,
+                    apps.codex,
+                    apps.gemini,
+                    apps.opencode,
+                    apps.cursor,
+                    apps.qwen,
+                    id
+                ],
             )
             .map_err(|e| AppError::Database(e.to_string()))?;
         Ok(affected > 0)
@@ -14,6 +14,7 @@
 //! ├── mod.rs        - Database 结构体 + 初始化
 //! ├── schema.rs     - 表结构定义 + Schema 迁移
 //! ├── backup.rs     - SQL 导入导出 + 快照备份
+//! ├── snapshot.rs   - 时间点快照：列出、检查、对比、恢复
 //! ├── migration.rs  - JSON → SQLite 数据迁移
 //! └── dao/          - 数据访问对象
 //!     ├── providers.rs
@@ -27,12 +28,17 @@ mod backup;
 pub(crate) mod dao;
 mod migration;
 mod schema;
+mod snapshot;
 
 #[cfg(test)]
 mod tests;
 
 // DAO 类型导出供外部使用
 pub use dao::FailoverQueueItem;
+pub use snapshot::{
+    DbSnapshotDetails, DbSnapshotDiff, DbSnapshotInfo, DbSnapshotKind, SnapshotDiffEntry,
+    SnapshotDiffStatus,
+};
 
 use crate::cc_switch::config::get_db_path;
 use crate::cc_switch::error::AppError;
@@ -47,7 +53,7 @@ const DB_BACKUP_RETAIN: usize = 10;
 
 /// 当前 Schema 版本号
 /// 每次修改表结构时递增，并在 schema.rs 中添加相应的迁移逻辑
-pub(crate) const SCHEMA_VERSION: i32 = 7;
+pub(crate) const SCHEMA_VERSION: i32 = 14;
 
 /// 安全地序列化 JSON，避免 unwrap panic
 pub(crate) fn to_json_string<T: Serialize>(value: &T) -> Result<String, AppError> {
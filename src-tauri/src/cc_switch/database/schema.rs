@@ -4,7 +4,7 @@
 
 use super::{lock_conn, Database, SCHEMA_VERSION};
 use crate::cc_switch::error::AppError;
-use rusqlite::Connection;
+use rusqlite::{Connection, OptionalExtension};
 
 impl Database {
     /// 创建所有数据库表
@@ -59,7 +59,7 @@ impl Database {
             description TEXT, homepage TEXT, docs TEXT, tags TEXT NOT NULL DEFAULT '[]',
             enabled_claude BOOLEAN NOT NULL DEFAULT 0, enabled_codex BOOLEAN NOT NULL DEFAULT 0,
             enabled_gemini BOOLEAN NOT NULL DEFAULT 0, enabled_opencode BOOLEAN NOT NULL DEFAULT 0,
-            enabled_cursor BOOLEAN NOT NULL DEFAULT 0
+            enabled_cursor BOOLEAN NOT NULL DEFAULT 0, enabled_qwen BOOLEAN NOT NULL DEFAULT 0
         )",
             [],
         )
@@ -69,6 +69,7 @@ impl Database {
         conn.execute("CREATE TABLE IF NOT EXISTS prompts (
             id TEXT NOT NULL, app_type TEXT NOT NULL, name TEXT NOT NULL, content TEXT NOT NULL,
             description TEXT, enabled BOOLEAN NOT NULL DEFAULT 1, created_at INTEGER, updated_at INTEGER,
+            templated BOOLEAN NOT NULL DEFAULT 0,
             PRIMARY KEY (id, app_type)
         )", []).map_err(|e| AppError::Database(e.to_string()))?;
 
@@ -84,11 +85,14 @@ impl Database {
             repo_branch TEXT DEFAULT 'main',
             readme_url TEXT,
             tree_commit_id TEXT,
+            pinned_ref TEXT,
+            commit_id TEXT,
             enabled_claude BOOLEAN NOT NULL DEFAULT 0,
             enabled_codex BOOLEAN NOT NULL DEFAULT 0,
             enabled_gemini BOOLEAN NOT NULL DEFAULT 0,
             enabled_opencode BOOLEAN NOT NULL DEFAULT 0,
             enabled_cursor BOOLEAN NOT NULL DEFAULT 0,
+            enabled_qwen BOOLEAN NOT NULL DEFAULT 0,
             installed_at INTEGER NOT NULL DEFAULT 0
         )",
             [],
@@ -112,21 +116,10 @@ impl Database {
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
 
-        // 8. Proxy Config 表（三行结构，app_type 主键）
-        conn.execute("CREATE TABLE IF NOT EXISTS proxy_config (
-            app_type TEXT PRIMARY KEY CHECK (app_type IN ('claude','codex','gemini')),
-            proxy_enabled INTEGER NOT NULL DEFAULT 0, listen_address TEXT NOT NULL DEFAULT '127.0.0.1',
-            listen_port INTEGER NOT NULL DEFAULT 15721, enable_logging INTEGER NOT NULL DEFAULT 1,
-            enabled INTEGER NOT NULL DEFAULT 0, auto_failover_enabled INTEGER NOT NULL DEFAULT 0,
-            max_retries INTEGER NOT NULL DEFAULT 3, streaming_first_byte_timeout INTEGER NOT NULL DEFAULT 60,
-            streaming_idle_timeout INTEGER NOT NULL DEFAULT 120, non_streaming_timeout INTEGER NOT NULL DEFAULT 600,
-            circuit_failure_threshold INTEGER NOT NULL DEFAULT 4, circuit_success_threshold INTEGER NOT NULL DEFAULT 2,
-            circuit_timeout_seconds INTEGER NOT NULL DEFAULT 60, circuit_error_rate_threshold REAL NOT NULL DEFAULT 0.6,
-            circuit_min_requests INTEGER NOT NULL DEFAULT 10,
-            created_at TEXT NOT NULL DEFAULT (datetime('now')), updated_at TEXT NOT NULL DEFAULT (datetime('now'))
-        )", []).map_err(|e| AppError::Database(e.to_string()))?;
+        // 8. Proxy Config 表（每应用一行，app_type 主键）
+        Self::create_proxy_config_table(conn, "proxy_config")?;
 
-        // 初始化三行数据（每应用不同默认值）
+        // 初始化每应用一行数据（每应用不同默认值）
         //
         // 兼容旧数据库：
         // - 老版本 proxy_config 是单例表（没有 app_type 列），此时不能执行三行 seed insert；
@@ -159,6 +152,11 @@ impl Database {
                 [],
             )
             .map_err(|e| AppError::Database(e.to_string()))?;
+
+            // v12 及更早的表 CHECK 约束不含 opencode，需等 v12 -> v13 迁移重建后再插入
+            if Self::proxy_config_accepts_opencode(conn)? {
+                Self::seed_opencode_proxy_config(conn)?;
+            }
         }
 
         // 9. Provider Health 表
@@ -345,6 +343,216 @@ impl Database {
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
 
+        // 15. Skill Versions 表（v8+：技能历史版本，用于回滚）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS skill_versions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                skill_id TEXT NOT NULL,
+                snapshot_dir TEXT NOT NULL,
+                commit_id TEXT,
+                tree_commit_id TEXT,
+                pinned_ref TEXT,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_skill_versions_skill
+             ON skill_versions(skill_id, created_at DESC)",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        // 17. Prompt Fragments 表（v9+：可被提示词模板引用的片段）
+        Self::create_prompt_template_tables(conn)?;
+
+        // 19. Prompt Versions 表（v10+：提示词与 live 文件的历史版本）
+        Self::create_prompt_versions_table(conn)?;
+
+        // 20. Endpoint Switch Logs 表（v11+：端点自动切换历史）
+        Self::create_endpoint_switch_logs_table(conn)?;
+
+        // 21. Provider Switch Logs 表（v12+：按策略自动切换供应商的历史）
+        Self::create_provider_switch_logs_table(conn)?;
+
+        Ok(())
+    }
+
+    /// 创建 proxy_config 表（建表与 v12 -> v13 迁移共用）
+    fn create_proxy_config_table(conn: &Connection, table: &str) -> Result<(), AppError> {
+        Self::validate_identifier(table, "表名")?;
+
+        conn.execute(&format!("CREATE TABLE IF NOT EXISTS {table} (
+            app_type TEXT PRIMARY KEY CHECK (app_type IN ('claude','codex','gemini','opencode')),
+            proxy_enabled INTEGER NOT NULL DEFAULT 0, listen_address TEXT NOT NULL DEFAULT '127.0.0.1',
+            listen_port INTEGER NOT NULL DEFAULT 15721, enable_logging INTEGER NOT NULL DEFAULT 1,
+            enabled INTEGER NOT NULL DEFAULT 0, auto_failover_enabled INTEGER NOT NULL DEFAULT 0,
+            max_retries INTEGER NOT NULL DEFAULT 3, streaming_first_byte_timeout INTEGER NOT NULL DEFAULT 60,
+            streaming_idle_timeout INTEGER NOT NULL DEFAULT 120, non_streaming_timeout INTEGER NOT NULL DEFAULT 600,
+            circuit_failure_threshold INTEGER NOT NULL DEFAULT 4, circuit_success_threshold INTEGER NOT NULL DEFAULT 2,
+            circuit_timeout_seconds INTEGER NOT NULL DEFAULT 60, circuit_error_rate_threshold REAL NOT NULL DEFAULT 0.6,
+            circuit_min_requests INTEGER NOT NULL DEFAULT 10,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')), updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )"), []).map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// proxy_config 的 CHECK 约束是否已允许 opencode 行
+    fn proxy_config_accepts_opencode(conn: &Connection) -> Result<bool, AppError> {
+        let sql: Option<String> = conn
+            .query_row(
+                "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'proxy_config'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| AppError::Database(format!("读取 proxy_config 表结构失败: {e}")))?;
+
+        Ok(sql.is_some_and(|sql| sql.contains("'opencode'")))
+    }
+
+    /// 插入 OpenCode 的代理配置行
+    ///
+    /// 监听地址等全局字段与 claude 行保持一致（全局配置读写以 claude 行为准）。
+    fn seed_opencode_proxy_config(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "INSERT OR IGNORE INTO proxy_config (app_type, proxy_enabled, listen_address,
+            listen_port, enable_logging, max_retries, streaming_first_byte_timeout,
+            streaming_idle_timeout, non_streaming_timeout, circuit_failure_threshold,
+            circuit_success_threshold, circuit_timeout_seconds, circuit_error_rate_threshold,
+            circuit_min_requests)
+            SELECT 'opencode', proxy_enabled, listen_address, listen_port, enable_logging,
+                   3, 60, 120, 600, 4, 2, 60, 0.6, 10
+            FROM proxy_config WHERE app_type = 'claude'",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("插入 opencode 代理配置失败: {e}")))?;
+
+        // 兜底：claude 行缺失时使用默认全局配置
+        conn.execute(
+            "INSERT OR IGNORE INTO proxy_config (app_type) VALUES ('opencode')",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("插入 opencode 代理配置失败: {e}")))?;
+
+        Ok(())
+    }
+
+    /// 创建供应商自动切换历史表（建表与 v11 -> v12 迁移共用）
+    fn create_provider_switch_logs_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS provider_switch_logs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                policy_id TEXT NOT NULL,
+                policy_name TEXT NOT NULL,
+                app_type TEXT NOT NULL,
+                trigger_type TEXT NOT NULL,
+                from_provider_id TEXT,
+                to_provider_id TEXT NOT NULL,
+                to_provider_name TEXT NOT NULL,
+                detail TEXT NOT NULL DEFAULT '',
+                switched_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_provider_switch_logs_app
+             ON provider_switch_logs(app_type, switched_at DESC)",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// 创建端点自动切换历史表（建表与 v10 -> v11 迁移共用）
+    fn create_endpoint_switch_logs_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS endpoint_switch_logs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                app_type TEXT NOT NULL,
+                provider_id TEXT NOT NULL,
+                provider_name TEXT NOT NULL,
+                from_url TEXT NOT NULL,
+                to_url TEXT NOT NULL,
+                from_latency_ms INTEGER,
+                to_latency_ms INTEGER NOT NULL,
+                reason TEXT NOT NULL,
+                switched_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_endpoint_switch_logs_provider
+             ON endpoint_switch_logs(app_type, provider_id, switched_at DESC)",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// 创建提示词历史版本表（建表与 v9 -> v10 迁移共用）
+    ///
+    /// prompt_id 为空的记录对应 live 提示词文件本身（CLAUDE.md 等）。
+    fn create_prompt_versions_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS prompt_versions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                app_type TEXT NOT NULL,
+                prompt_id TEXT,
+                name TEXT,
+                content TEXT NOT NULL,
+                templated BOOLEAN NOT NULL DEFAULT 0,
+                source TEXT NOT NULL,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_prompt_versions_prompt
+             ON prompt_versions(app_type, prompt_id, created_at DESC)",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// 创建提示词片段与模板变量表（建表与 v8 -> v9 迁移共用）
+    fn create_prompt_template_tables(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS prompt_fragments (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                content TEXT NOT NULL,
+                description TEXT,
+                created_at INTEGER,
+                updated_at INTEGER
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        // 18. Prompt Variables 表（v9+：用户自定义模板变量）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS prompt_variables (
+                name TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
         Ok(())
     }
 
@@ -409,6 +617,41 @@ impl Database {
                         Self::migrate_v6_to_v7(conn)?;
                         Self::set_user_version(conn, 7)?;
                     }
+                    7 => {
+                        log::info!("迁移数据库从 v7 到 v8（Skills 版本锁定与回滚）");
+                        Self::migrate_v7_to_v8(conn)?;
+                        Self::set_user_version(conn, 8)?;
+                    }
+                    8 => {
+                        log::info!("迁移数据库从 v8 到 v9（提示词模板与片段）");
+                        Self::migrate_v8_to_v9(conn)?;
+                        Self::set_user_version(conn, 9)?;
+                    }
+                    9 => {
+                        log::info!("迁移数据库从 v9 到 v10（提示词历史版本）");
+                        Self::migrate_v9_to_v10(conn)?;
+                        Self::set_user_version(conn, 10)?;
+                    }
+                    10 => {
+                        log::info!("迁移数据库从 v10 到 v11（端点自动切换历史）");
+                        Self::migrate_v10_to_v11(conn)?;
+                        Self::set_user_version(conn, 11)?;
+                    }
+                    11 => {
+                        log::info!("迁移数据库从 v11 到 v12（供应商自动切换历史）");
+                        Self::migrate_v11_to_v12(conn)?;
+                        Self::set_user_version(conn, 12)?;
+                    }
+                    12 => {
+                        log::info!("迁移数据库从 v12 到 v13（OpenCode 代理接管）");
+                        Self::migrate_v12_to_v13(conn)?;
+                        Self::set_user_version(conn, 13)?;
+                    }
+                    13 => {
+                        log::info!("迁移数据库从 v13 到 v14（Qwen Code 支持）");
+                        Self::migrate_v13_to_v14(conn)?;
+                        Self::set_user_version(conn, 14)?;
+                    }
                     _ => {
                         return Err(AppError::Database(format!(
                             "未知的数据库版本 {version}，无法迁移到 {SCHEMA_VERSION}"
@@ -1016,6 +1259,134 @@ impl Database {
         Ok(())
     }
 
+    /// v7 -> v8 迁移：Skills 版本锁定与历史版本
+    ///
+    /// 为 skills 表添加 pinned_ref / commit_id 列，并新增 skill_versions 表。
+    fn migrate_v7_to_v8(conn: &Connection) -> Result<(), AppError> {
+        Self::add_column_if_missing(conn, "skills", "pinned_ref", "TEXT")?;
+        Self::add_column_if_missing(conn, "skills", "commit_id", "TEXT")?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS skill_versions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                skill_id TEXT NOT NULL,
+                snapshot_dir TEXT NOT NULL,
+                commit_id TEXT,
+                tree_commit_id TEXT,
+                pinned_ref TEXT,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_skill_versions_skill
+             ON skill_versions(skill_id, created_at DESC)",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        log::info!("v7 -> v8 迁移完成：已添加 Skills 版本锁定与历史版本");
+        Ok(())
+    }
+
+    /// v8 -> v9 迁移：提示词模板
+    ///
+    /// 为 prompts 表添加 templated 列，并新增 prompt_fragments / prompt_variables 表。
+    fn migrate_v8_to_v9(conn: &Connection) -> Result<(), AppError> {
+        if Self::table_exists(conn, "prompts")? {
+            Self::add_column_if_missing(
+                conn,
+                "prompts",
+                "templated",
+                "BOOLEAN NOT NULL DEFAULT 0",
+            )?;
+        }
+        Self::create_prompt_template_tables(conn)?;
+
+        log::info!("v8 -> v9 迁移完成：已添加提示词模板与片段");
+        Ok(())
+    }
+
+    /// v9 -> v10 迁移：新增 prompt_versions 表
+    fn migrate_v9_to_v10(conn: &Connection) -> Result<(), AppError> {
+        Self::create_prompt_versions_table(conn)?;
+        log::info!("v9 -> v10 迁移完成：已添加提示词历史版本");
+        Ok(())
+    }
+
+    /// v10 -> v11 迁移：新增 endpoint_switch_logs 表
+    fn migrate_v10_to_v11(conn: &Connection) -> Result<(), AppError> {
+        Self::create_endpoint_switch_logs_table(conn)?;
+        log::info!("v10 -> v11 迁移完成：已添加端点自动切换历史");
+        Ok(())
+    }
+
+    /// v11 -> v12 迁移：新增 provider_switch_logs 表
+    fn migrate_v11_to_v12(conn: &Connection) -> Result<(), AppError> {
+        Self::create_provider_switch_logs_table(conn)?;
+        log::info!("v11 -> v12 迁移完成：已添加供应商自动切换历史");
+        Ok(())
+    }
+
+    /// v12 -> v13 迁移：重建 proxy_config 以放开 opencode 行，并插入默认配置
+    ///
+    /// SQLite 不支持修改 CHECK 约束，只能建新表、拷贝数据后替换。
+    fn migrate_v12_to_v13(conn: &Connection) -> Result<(), AppError> {
+        if !Self::table_exists(conn, "proxy_config")?
+            || !Self::has_column(conn, "proxy_config", "app_type")?
+        {
+            return Ok(());
+        }
+
+        if !Self::proxy_config_accepts_opencode(conn)? {
+            const COLUMNS: &str = "app_type, proxy_enabled, listen_address, listen_port,
+                enable_logging, enabled, auto_failover_enabled, max_retries,
+                streaming_first_byte_timeout, streaming_idle_timeout, non_streaming_timeout,
+                circuit_failure_threshold, circuit_success_threshold, circuit_timeout_seconds,
+                circuit_error_rate_threshold, circuit_min_requests, created_at, updated_at";
+
+            conn.execute("DROP TABLE IF EXISTS proxy_config_new", [])
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            Self::create_proxy_config_table(conn, "proxy_config_new")?;
+            conn.execute(
+                &format!(
+                    "INSERT INTO proxy_config_new ({COLUMNS}) SELECT {COLUMNS} FROM proxy_config"
+                ),
+                [],
+            )
+            .map_err(|e| AppError::Database(format!("拷贝 proxy_config 数据失败: {e}")))?;
+            conn.execute("DROP TABLE proxy_config", [])
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            conn.execute("ALTER TABLE proxy_config_new RENAME TO proxy_config", [])
+                .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+
+        Self::seed_opencode_proxy_config(conn)?;
+        log::info!("v12 -> v13 迁移完成：proxy_config 已支持 OpenCode");
+        Ok(())
+    }
+
+    /// v13 -> v14 迁移：添加 Qwen Code 支持
+    ///
+    /// 为 mcp_servers 和 skills 表添加 enabled_qwen 列。
+    fn migrate_v13_to_v14(conn: &Connection) -> Result<(), AppError> {
+        for table in ["mcp_servers", "skills"] {
+            if Self::table_exists(conn, table)? {
+                Self::add_column_if_missing(
+                    conn,
+                    table,
+                    "enabled_qwen",
+                    "BOOLEAN NOT NULL DEFAULT 0",
+                )?;
+            }
+        }
+
+        log::info!("v13 -> v14 迁移完成：已添加 Qwen Code 支持");
+        Ok(())
+    }
+
     /// 插入默认模型定价数据
     /// 格式: (model_id, display_name, input, output, cache_read, cache_creation)
     /// 注意: model_id 使用短横线格式（如 claude-haiku-4-5），与 API 返回的模型名称标准化后一致
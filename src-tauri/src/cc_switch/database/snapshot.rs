@@ -0,0 +1,474 @@
+//! 数据库时间点快照
+//!
+//! 快照均为 `<数据库目录>/backups/` 下的 SQLite 文件，通过文件名前缀区分来源：
+//! - `db_backup_`：导入前等操作触发的备份
+//! - `db_auto_`：定时自动快照
+//! - `db_prerestore_`：恢复快照前的安全快照
+//!
+//! 提供快照的创建、列出、检查（统计与 Schema 版本）、与当前库对比以及恢复。
+
+use super::{lock_conn, Database, SCHEMA_VERSION};
+use crate::cc_switch::config::get_db_path;
+use crate::cc_switch::error::AppError;
+use chrono::{NaiveDateTime, Utc};
+use rusqlite::backup::Backup;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// 快照来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DbSnapshotKind {
+    /// 导入、同步等操作前的备份
+    Backup,
+    /// 定时自动快照
+    Scheduled,
+    /// 恢复前的安全快照
+    PreRestore,
+}
+
+impl DbSnapshotKind {
+    const ALL: [DbSnapshotKind; 3] = [Self::Backup, Self::Scheduled, Self::PreRestore];
+
+    fn prefix(self) -> &'static str {
+        match self {
+            Self::Backup => "db_backup_",
+            Self::Scheduled => "db_auto_",
+            Self::PreRestore => "db_prerestore_",
+        }
+    }
+
+    fn from_id(id: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| id.starts_with(kind.prefix()))
+    }
+}
+
+/// 快照文件信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DbSnapshotInfo {
+    /// 快照 ID（文件名去掉 `.db` 后缀）
+    pub id: String,
+    pub kind: DbSnapshotKind,
+    /// 创建时间（Unix 秒）
+    pub created_at: i64,
+    pub size_bytes: u64,
+}
+
+/// 快照内容概要
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DbSnapshotDetails {
+    #[serde(flatten)]
+    pub info: DbSnapshotInfo,
+    pub schema_version: i32,
+    /// 快照的 Schema 版本不高于当前版本时才可恢复
+    pub restorable: bool,
+    /// 各应用的供应商数量
+    pub provider_counts: BTreeMap<String, i64>,
+    pub mcp_server_count: i64,
+    /// 各应用的提示词数量
+    pub prompt_counts: BTreeMap<String, i64>,
+}
+
+/// 快照与当前库的差异类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SnapshotDiffStatus {
+    /// 仅存在于快照（当前库中已删除）
+    OnlyInSnapshot,
+    /// 仅存在于当前库（快照之后新增）
+    OnlyInCurrent,
+    /// 两边都有但内容不同
+    Changed,
+}
+
+/// 单个条目的差异
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotDiffEntry {
+    /// 供应商所属应用（MCP 服务器为 None）
+    pub app_type: Option<String>,
+    pub id: String,
+    pub name: String,
+    pub status: SnapshotDiffStatus,
+    /// 内容不同的列名（仅 Changed 时有值）
+    pub changed_fields: Vec<String>,
+}
+
+/// 快照与当前库的差异
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DbSnapshotDiff {
+    pub providers: Vec<SnapshotDiffEntry>,
+    pub mcp_servers: Vec<SnapshotDiffEntry>,
+}
+
+/// 参与对比的表行：主键 → (名称, 列名 → 值)
+type RowMap = BTreeMap<(Option<String>, String), (String, BTreeMap<String, SqlValue>)>;
+
+impl Database {
+    /// 默认快照目录：`<数据库目录>/backups`
+    pub(crate) fn snapshot_dir() -> Result<PathBuf, AppError> {
+        Ok(get_db_path()
+            .parent()
+            .ok_or_else(|| AppError::Config("无效的数据库路径".to_string()))?
+            .join("backups"))
+    }
+
+    /// 在指定目录创建一个快照，并按来源清理只保留最新的 `retain` 个
+    pub(crate) fn create_snapshot_in(
+        &self,
+        dir: &Path,
+        kind: DbSnapshotKind,
+        retain: usize,
+    ) -> Result<DbSnapshotInfo, AppError> {
+        fs::create_dir_all(dir).map_err(|e| AppError::io(dir, e))?;
+
+        let base_id = format!("{}{}", kind.prefix(), Utc::now().format("%Y%m%d_%H%M%S"));
+        let mut id = base_id.clone();
+        let mut path = dir.join(format!("{id}.db"));
+        let mut counter = 1;
+        while path.exists() {
+            id = format!("{base_id}_{counter}");
+            path = dir.join(format!("{id}.db"));
+            counter += 1;
+        }
+
+        {
+            let conn = lock_conn!(self.conn);
+            let mut dest_conn =
+                Connection::open(&path).map_err(|e| AppError::Database(e.to_string()))?;
+            let backup = Backup::new(&conn, &mut dest_conn)
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            backup
+                .step(-1)
+                .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+
+        Self::cleanup_snapshots(dir, kind, retain)?;
+        Self::snapshot_info(&path)?
+            .ok_or_else(|| AppError::Message(format!("快照文件无效: {}", path.display())))
+    }
+
+    /// 列出目录中的全部快照（按创建时间倒序）
+    pub(crate) fn list_snapshots_in(dir: &Path) -> Result<Vec<DbSnapshotInfo>, AppError> {
+        let entries = match fs::read_dir(dir) {
+            Ok(iter) => iter,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(AppError::io(dir, e)),
+        };
+
+        let mut snapshots = Vec::new();
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            if let Some(info) = Self::snapshot_info(&entry.path())? {
+                snapshots.push(info);
+            }
+        }
+        snapshots.sort_by(|a, b| {
+            b.created_at
+                .cmp(&a.created_at)
+                .then_with(|| b.id.cmp(&a.id))
+        });
+        Ok(snapshots)
+    }
+
+    /// 检查快照：Schema 版本以及供应商、MCP、提示词数量
+    pub(crate) fn inspect_snapshot_in(dir: &Path, id: &str) -> Result<DbSnapshotDetails, AppError> {
+        let path = Self::snapshot_path(dir, id)?;
+        let info = Self::snapshot_info(&path)?
+            .ok_or_else(|| AppError::InvalidInput(format!("快照不存在: {id}")))?;
+        let conn = Self::open_snapshot_read_only(&path)?;
+
+        let schema_version = Self::get_user_version(&conn)?;
+        let provider_counts = Self::count_by_app(&conn, "providers")?;
+        let prompt_counts = Self::count_by_app(&conn, "prompts")?;
+        let mcp_server_count = if Self::table_exists(&conn, "mcp_servers")? {
+            conn.query_row("SELECT COUNT(*) FROM mcp_servers", [], |row| row.get(0))
+                .map_err(|e| AppError::Database(e.to_string()))?
+        } else {
+            0
+        };
+
+        Ok(DbSnapshotDetails {
+            info,
+            schema_version,
+            restorable: schema_version <= SCHEMA_VERSION,
+            provider_counts,
+            mcp_server_count,
+            prompt_counts,
+        })
+    }
+
+    /// 对比快照与当前库中的供应商和 MCP 服务器
+    pub(crate) fn diff_snapshot_in(
+        &self,
+        dir: &Path,
+        id: &str,
+    ) -> Result<DbSnapshotDiff, AppError> {
+        let path = Self::snapshot_path(dir, id)?;
+        let snapshot = Self::open_snapshot_read_only(&path)?;
+        let current = self.snapshot_to_memory()?;
+
+        Ok(DbSnapshotDiff {
+            providers: Self::diff_table(&snapshot, &current, "providers", true)?,
+            mcp_servers: Self::diff_table(&snapshot, &current, "mcp_servers", false)?,
+        })
+    }
+
+    /// 从快照恢复主库，返回恢复前创建的安全快照
+    ///
+    /// 快照先复制到内存并补齐表结构、执行 Schema 迁移，成功后才覆盖主库。
+    pub(crate) fn restore_snapshot_in(
+        &self,
+        dir: &Path,
+        id: &str,
+        retain: usize,
+    ) -> Result<DbSnapshotInfo, AppError> {
+        let path = Self::snapshot_path(dir, id)?;
+        let source = Self::open_snapshot_read_only(&path)?;
+
+        let mut staged =
+            Connection::open_in_memory().map_err(|e| AppError::Database(e.to_string()))?;
+        {
+            let backup =
+                Backup::new(&source, &mut staged).map_err(|e| AppError::Database(e.to_string()))?;
+            backup
+                .step(-1)
+                .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+        Self::create_tables_on_conn(&staged)?;
+        Self::apply_schema_migrations_on_conn(&staged)?;
+
+        let safety = self.create_snapshot_in(dir, DbSnapshotKind::PreRestore, retain)?;
+        self.restore_from_snapshot(&staged)?;
+        log::info!("已从快照 {id} 恢复数据库（恢复前快照: {}）", safety.id);
+        Ok(safety)
+    }
+
+    /// 清理指定来源的旧快照，保留最新的 N 个
+    pub(crate) fn cleanup_snapshots(
+        dir: &Path,
+        kind: DbSnapshotKind,
+        retain: usize,
+    ) -> Result<(), AppError> {
+        let entries = match fs::read_dir(dir) {
+            Ok(iter) => iter
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| {
+                    let path = entry.path();
+                    path.extension().map(|ext| ext == "db").unwrap_or(false)
+                        && path
+                            .file_stem()
+                            .and_then(|s| s.to_str())
+                            .and_then(DbSnapshotKind::from_id)
+                            == Some(kind)
+                })
+                .collect::<Vec<_>>(),
+            Err(_) => return Ok(()),
+        };
+
+        if entries.len() <= retain {
+            return Ok(());
+        }
+
+        let remove_count = entries.len().saturating_sub(retain);
+        let mut sorted = entries;
+        sorted.sort_by_key(|entry| entry.metadata().and_then(|m| m.modified()).ok());
+
+        for entry in sorted.into_iter().take(remove_count) {
+            if let Err(err) = fs::remove_file(entry.path()) {
+                log::warn!("删除旧数据库备份失败 {}: {}", entry.path().display(), err);
+            }
+        }
+        Ok(())
+    }
+
+    /// 校验快照 ID 并返回文件路径（防止路径穿越）
+    fn snapshot_path(dir: &Path, id: &str) -> Result<PathBuf, AppError> {
+        let valid = DbSnapshotKind::from_id(id).is_some()
+            && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(AppError::InvalidInput(format!("无效的快照 ID: {id}")));
+        }
+        let path = dir.join(format!("{id}.db"));
+        if !path.is_file() {
+            return Err(AppError::InvalidInput(format!("快照不存在: {id}")));
+        }
+        Ok(path)
+    }
+
+    /// 读取快照文件信息；非快照文件返回 None
+    fn snapshot_info(path: &Path) -> Result<Option<DbSnapshotInfo>, AppError> {
+        if path.extension().map(|ext| ext != "db").unwrap_or(true) {
+            return Ok(None);
+        }
+        let Some(id) = path.file_stem().and_then(|s| s.to_str()) else {
+            return Ok(None);
+        };
+        let Some(kind) = DbSnapshotKind::from_id(id) else {
+            return Ok(None);
+        };
+        let metadata = fs::metadata(path).map_err(|e| AppError::io(path, e))?;
+
+        // 优先使用文件名中的时间戳，复制或同步后修改时间可能不准确
+        let created_at = id[kind.prefix().len()..]
+            .get(..15)
+            .and_then(|ts| NaiveDateTime::parse_from_str(ts, "%Y%m%d_%H%M%S").ok())
+            .map(|dt| dt.and_utc().timestamp())
+            .or_else(|| {
+                let modified = metadata.modified().ok()?;
+                let elapsed = modified.duration_since(UNIX_EPOCH).ok()?;
+                Some(elapsed.as_secs() as i64)
+            })
+            .unwrap_or(0);
+
+        Ok(Some(DbSnapshotInfo {
+            id: id.to_string(),
+            kind,
+            created_at,
+            size_bytes: metadata.len(),
+        }))
+    }
+
+    fn open_snapshot_read_only(path: &Path) -> Result<Connection, AppError> {
+        Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .map_err(|e| AppError::Database(format!("打开快照失败: {e}")))
+    }
+
+    /// 按 app_type 分组统计行数（表不存在时为空）
+    fn count_by_app(conn: &Connection, table: &str) -> Result<BTreeMap<String, i64>, AppError> {
+        if !Self::table_exists(conn, table)? {
+            return Ok(BTreeMap::new());
+        }
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT app_type, COUNT(*) FROM \"{table}\" GROUP BY app_type"
+            ))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut counts = BTreeMap::new();
+        for row in rows {
+            let (app, count) = row.map_err(|e| AppError::Database(e.to_string()))?;
+            counts.insert(app, count);
+        }
+        Ok(counts)
+    }
+
+    /// 按两边共有的列逐行对比
+    fn diff_table(
+        snapshot: &Connection,
+        current: &Connection,
+        table: &str,
+        has_app_type: bool,
+    ) -> Result<Vec<SnapshotDiffEntry>, AppError> {
+        let snapshot_columns = if Self::table_exists(snapshot, table)? {
+            Self::get_table_columns(snapshot, table)?
+        } else {
+            Vec::new()
+        };
+        let current_columns = Self::get_table_columns(current, table)?;
+        let columns: Vec<String> = current_columns
+            .into_iter()
+            .filter(|c| snapshot_columns.contains(c))
+            .collect();
+
+        let before = Self::load_rows(snapshot, table, &columns, has_app_type)?;
+        let after = Self::load_rows(current, table, &columns, has_app_type)?;
+
+        let mut entries = Vec::new();
+        for (key, (name, values)) in &before {
+            let (status, changed_fields, name) = match after.get(key) {
+                None => (SnapshotDiffStatus::OnlyInSnapshot, Vec::new(), name),
+                Some((current_name, current_values)) => {
+                    let changed: Vec<String> = columns
+                        .iter()
+                        .filter(|c| values.get(*c) != current_values.get(*c))
+                        .cloned()
+                        .collect();
+                    if changed.is_empty() {
+                        continue;
+                    }
+                    (SnapshotDiffStatus::Changed, changed, current_name)
+                }
+            };
+            entries.push(SnapshotDiffEntry {
+                app_type: key.0.clone(),
+                id: key.1.clone(),
+                name: name.clone(),
+                status,
+                changed_fields,
+            });
+        }
+        for (key, (name, _)) in after.iter().filter(|(key, _)| !before.contains_key(*key)) {
+            entries.push(SnapshotDiffEntry {
+                app_type: key.0.clone(),
+                id: key.1.clone(),
+                name: name.clone(),
+                status: SnapshotDiffStatus::OnlyInCurrent,
+                changed_fields: Vec::new(),
+            });
+        }
+        entries.sort_by(|a, b| (&a.app_type, &a.id).cmp(&(&b.app_type, &b.id)));
+        Ok(entries)
+    }
+
+    fn load_rows(
+        conn: &Connection,
+        table: &str,
+        columns: &[String],
+        has_app_type: bool,
+    ) -> Result<RowMap, AppError> {
+        let mut rows_map = RowMap::new();
+        if columns.is_empty() {
+            return Ok(rows_map);
+        }
+
+        let cols = columns
+            .iter()
+            .map(|c| format!("\"{c}\""))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut stmt = conn
+            .prepare(&format!("SELECT {cols} FROM \"{table}\""))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let mut rows = stmt
+            .query([])
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        while let Some(row) = rows.next().map_err(|e| AppError::Database(e.to_string()))? {
+            let mut values = BTreeMap::new();
+            for (idx, column) in columns.iter().enumerate() {
+                let value: SqlValue = row
+                    .get(idx)
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+                values.insert(column.clone(), value);
+            }
+
+            let text = |column: &str| match values.get(column) {
+                Some(SqlValue::Text(s)) => Some(s.clone()),
+                _ => None,
+            };
+            let Some(id) = text("id") else {
+                continue;
+            };
+            let app_type = if has_app_type { text("app_type") } else { None };
+            let name = text("name").unwrap_or_else(|| id.clone());
+            rows_map.insert((app_type, id), (name, values));
+        }
+        Ok(rows_map)
+    }
+}
@@ -419,11 +419,12 @@ fn migration_from_v3_8_schema_v1_to_current_schema_v3() {
         "skills_ssot_migration_pending should be set after v2->v3 migration"
     );
 
-    // v3.9+ 新增：proxy_config 三行 seed 必须存在（否则 UI 会查不到默认值）
+    // v3.9+ 新增：proxy_config 每应用 seed 必须存在（否则 UI 会查不到默认值）
+    // v13 起额外包含 opencode 行
     let proxy_rows: i64 = conn
         .query_row("SELECT COUNT(*) FROM proxy_config", [], |r| r.get(0))
         .expect("count proxy_config rows");
-    assert_eq!(proxy_rows, 3);
+    assert_eq!(proxy_rows, 4);
 
     // model_pricing 应具备默认数据（迁移时会 seed）
     let pricing_rows: i64 = conn
@@ -565,3 +566,641 @@ fn model_pricing_is_seeded_on_init() {
     );
 }
 
+#[test]
+fn migration_v7_to_v8_adds_skill_pin_columns_and_versions_table() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+    conn.execute_batch(
+        r#"
+        CREATE TABLE skills (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            description TEXT,
+            directory TEXT NOT NULL,
+            repo_owner TEXT,
+            repo_name TEXT,
+            repo_branch TEXT DEFAULT 'main',
+            readme_url TEXT,
+            tree_commit_id TEXT,
+            enabled_claude BOOLEAN NOT NULL DEFAULT 0,
+            enabled_codex BOOLEAN NOT NULL DEFAULT 0,
+            enabled_gemini BOOLEAN NOT NULL DEFAULT 0,
+            enabled_opencode BOOLEAN NOT NULL DEFAULT 0,
+            enabled_cursor BOOLEAN NOT NULL DEFAULT 0,
+            installed_at INTEGER NOT NULL DEFAULT 0
+        );
+        "#,
+    )
+    .expect("seed v7 skills table");
+    Database::set_user_version(&conn, 7).expect("set user_version=7");
+
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply migrations");
+
+    for column in ["pinned_ref", "commit_id"] {
+        assert!(
+            Database::has_column(&conn, "skills", column).expect("check column"),
+            "skills.{column} should exist after migration"
+        );
+    }
+    assert!(
+        Database::table_exists(&conn, "skill_versions").expect("check table"),
+        "skill_versions table should exist after migration"
+    );
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn skill_versions_are_listed_newest_first_and_scoped_to_skill() {
+    use crate::cc_switch::database::dao::SkillVersion;
+
+    let db = Database::memory().expect("create memory db");
+
+    let make = |skill_id: &str, snapshot_dir: &str, created_at: i64| SkillVersion {
+        id: 0,
+        skill_id: skill_id.to_string(),
+        snapshot_dir: snapshot_dir.to_string(),
+        commit_id: Some(format!("commit-{snapshot_dir}")),
+        tree_commit_id: None,
+        pinned_ref: None,
+        created_at,
+    };
+
+    let first = db
+        .insert_skill_version(&make("owner/repo:a", "v1", 100))
+        .expect("insert v1");
+    let second = db
+        .insert_skill_version(&make("owner/repo:a", "v2", 200))
+        .expect("insert v2");
+    db.insert_skill_version(&make("owner/repo:b", "other", 300))
+        .expect("insert other");
+
+    let versions = db.list_skill_versions("owner/repo:a").expect("list");
+    assert_eq!(
+        versions.iter().map(|v| v.id).collect::<Vec<_>>(),
+        vec![second, first]
+    );
+
+    let fetched = db
+        .get_skill_version(first)
+        .expect("get")
+        .expect("version exists");
+    assert_eq!(fetched.commit_id.as_deref(), Some("commit-v1"));
+
+    assert!(db.delete_skill_version(first).expect("delete"));
+    db.delete_skill_versions_by_skill("owner/repo:a")
+        .expect("delete by skill");
+    assert!(db
+        .list_skill_versions("owner/repo:a")
+        .expect("list after delete")
+        .is_empty());
+    assert_eq!(
+        db.list_skill_versions("owner/repo:b")
+            .expect("list other")
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn migration_v8_to_v9_adds_prompt_template_tables() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+    conn.execute_batch(
+        r#"
+        CREATE TABLE prompts (
+            id TEXT NOT NULL,
+            app_type TEXT NOT NULL,
+            name TEXT NOT NULL,
+            content TEXT NOT NULL,
+            description TEXT,
+            enabled BOOLEAN NOT NULL DEFAULT 1,
+            created_at INTEGER,
+            updated_at INTEGER,
+            PRIMARY KEY (id, app_type)
+        );
+        INSERT INTO prompts (id, app_type, name, content) VALUES ('p1', 'claude', 'P1', 'hello');
+        "#,
+    )
+    .expect("seed v8 prompts table");
+    Database::set_user_version(&conn, 8).expect("set user_version=8");
+
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply migrations");
+
+    assert!(
+        Database::has_column(&conn, "prompts", "templated").expect("check column"),
+        "prompts.templated should exist after migration"
+    );
+    for table in ["prompt_fragments", "prompt_variables"] {
+        assert!(
+            Database::table_exists(&conn, table).expect("check table"),
+            "{table} table should exist after migration"
+        );
+    }
+    let templated: bool = conn
+        .query_row("SELECT templated FROM prompts WHERE id = 'p1'", [], |row| {
+            row.get(0)
+        })
+        .expect("read templated");
+    assert!(
+        !templated,
+        "existing prompts should default to non-template"
+    );
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn prompt_fragments_and_variables_round_trip() {
+    use crate::cc_switch::prompt::{Prompt, PromptFragment};
+
+    let db = Database::memory().expect("create memory db");
+
+    db.save_prompt_fragment(&PromptFragment {
+        id: "base".to_string(),
+        name: "Base".to_string(),
+        content: "# {{project_name}}".to_string(),
+        description: None,
+        created_at: Some(1),
+        updated_at: Some(1),
+    })
+    .expect("save fragment");
+    db.set_prompt_variable("project_name", "mnemosyne")
+        .expect("set variable");
+    db.save_prompt(
+        "claude",
+        &Prompt {
+            id: "tpl".to_string(),
+            name: "Template".to_string(),
+            content: "{{> base}}".to_string(),
+            description: None,
+            enabled: false,
+            templated: true,
+            created_at: Some(1),
+            updated_at: Some(1),
+        },
+    )
+    .expect("save prompt");
+
+    let fragments = db.get_prompt_fragments().expect("get fragments");
+    assert_eq!(fragments["base"].content, "# {{project_name}}");
+    assert_eq!(
+        db.get_prompt_variables()
+            .expect("get variables")
+            .get("project_name")
+            .map(String::as_str),
+        Some("mnemosyne")
+    );
+    assert!(db.get_prompts("claude").expect("get prompts")["tpl"].templated);
+
+    db.delete_prompt_fragment("base").expect("delete fragment");
+    db.delete_prompt_variable("project_name")
+        .expect("delete variable");
+    assert!(db.get_prompt_fragments().expect("get fragments").is_empty());
+    assert!(db.get_prompt_variables().expect("get variables").is_empty());
+}
+
+#[test]
+fn migration_v9_to_v10_adds_prompt_versions_table() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+    Database::set_user_version(&conn, 9).expect("set user_version=9");
+
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply migrations");
+
+    assert!(
+        Database::table_exists(&conn, "prompt_versions").expect("check table"),
+        "prompt_versions table should exist after migration"
+    );
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn migration_v10_to_v11_adds_endpoint_switch_logs_table() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+    Database::set_user_version(&conn, 10).expect("set user_version=10");
+
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply migrations");
+
+    assert!(
+        Database::table_exists(&conn, "endpoint_switch_logs").expect("check table"),
+        "endpoint_switch_logs table should exist after migration"
+    );
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn endpoint_switch_logs_filter_and_prune() {
+    use crate::cc_switch::services::endpoint_auto_select::{
+        EndpointSwitchReason, EndpointSwitchRecord,
+    };
+
+    let db = Database::memory().expect("create memory db");
+    let make = |provider_id: &str, switched_at: i64, reason| EndpointSwitchRecord {
+        id: 0,
+        app_type: "claude".to_string(),
+        provider_id: provider_id.to_string(),
+        provider_name: provider_id.to_uppercase(),
+        from_url: "https://a".to_string(),
+        to_url: "https://b".to_string(),
+        from_latency_ms: Some(500),
+        to_latency_ms: 120,
+        reason,
+        switched_at,
+    };
+
+    db.insert_endpoint_switch_log(&make("p1", 1, EndpointSwitchReason::Faster))
+        .expect("insert log");
+    db.insert_endpoint_switch_log(&make("p1", 2, EndpointSwitchReason::Unhealthy))
+        .expect("insert log");
+    db.insert_endpoint_switch_log(&make("p2", 3, EndpointSwitchReason::Faster))
+        .expect("insert log");
+
+    let p1 = db
+        .list_endpoint_switch_logs(Some("claude"), Some("p1"), 10)
+        .expect("list p1");
+    assert_eq!(p1.len(), 2);
+    assert_eq!(p1[0].reason, EndpointSwitchReason::Unhealthy);
+    assert_eq!(p1[0].from_latency_ms, Some(500));
+    assert!(db
+        .list_endpoint_switch_logs(Some("codex"), None, 10)
+        .expect("list codex")
+        .is_empty());
+
+    assert_eq!(db.prune_endpoint_switch_logs(1).expect("prune"), 2);
+    let remaining = db
+        .list_endpoint_switch_logs(None, None, 10)
+        .expect("list all");
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].provider_id, "p2");
+}
+
+#[test]
+fn migration_v11_to_v12_adds_provider_switch_logs_table() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+    Database::set_user_version(&conn, 11).expect("set user_version=11");
+
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply migrations");
+
+    assert!(
+        Database::table_exists(&conn, "provider_switch_logs").expect("check table"),
+        "provider_switch_logs table should exist after migration"
+    );
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn migration_v12_to_v13_rebuilds_proxy_config_for_opencode() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+    conn.execute_batch(
+        r#"
+        CREATE TABLE proxy_config (
+            app_type TEXT PRIMARY KEY CHECK (app_type IN ('claude','codex','gemini')),
+            proxy_enabled INTEGER NOT NULL DEFAULT 0, listen_address TEXT NOT NULL DEFAULT '127.0.0.1',
+            listen_port INTEGER NOT NULL DEFAULT 15721, enable_logging INTEGER NOT NULL DEFAULT 1,
+            enabled INTEGER NOT NULL DEFAULT 0, auto_failover_enabled INTEGER NOT NULL DEFAULT 0,
+            max_retries INTEGER NOT NULL DEFAULT 3, streaming_first_byte_timeout INTEGER NOT NULL DEFAULT 60,
+            streaming_idle_timeout INTEGER NOT NULL DEFAULT 120, non_streaming_timeout INTEGER NOT NULL DEFAULT 600,
+            circuit_failure_threshold INTEGER NOT NULL DEFAULT 4, circuit_success_threshold INTEGER NOT NULL DEFAULT 2,
+            circuit_timeout_seconds INTEGER NOT NULL DEFAULT 60, circuit_error_rate_threshold REAL NOT NULL DEFAULT 0.6,
+            circuit_min_requests INTEGER NOT NULL DEFAULT 10,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')), updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+            live_takeover_active INTEGER NOT NULL DEFAULT 0
+        );
+        INSERT INTO proxy_config (app_type, proxy_enabled, listen_port, enabled, max_retries)
+            VALUES ('claude', 1, 18000, 1, 6);
+        INSERT INTO proxy_config (app_type) VALUES ('codex');
+        INSERT INTO proxy_config (app_type) VALUES ('gemini');
+        "#,
+    )
+    .expect("seed v12 proxy_config");
+    Database::set_user_version(&conn, 12).expect("set user_version=12");
+
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply migrations");
+
+    let (enabled, retries): (i32, i32) = conn
+        .query_row(
+            "SELECT enabled, max_retries FROM proxy_config WHERE app_type = 'claude'",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .expect("claude row survives rebuild");
+    assert_eq!((enabled, retries), (1, 6));
+
+    // opencode 行继承 claude 行的全局监听配置，但默认不接管
+    let (proxy_enabled, port, opencode_enabled): (i32, i32, i32) = conn
+        .query_row(
+            "SELECT proxy_enabled, listen_port, enabled FROM proxy_config WHERE app_type = 'opencode'",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+        )
+        .expect("opencode row seeded");
+    assert_eq!((proxy_enabled, port, opencode_enabled), (1, 18000, 0));
+
+    assert!(
+        conn.execute("INSERT INTO proxy_config (app_type) VALUES ('cursor')", [])
+            .is_err(),
+        "unsupported apps should still be rejected by the CHECK constraint"
+    );
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn migration_v13_to_v14_adds_qwen_columns() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+    conn.execute_batch(
+        r#"
+        CREATE TABLE mcp_servers (
+            id TEXT PRIMARY KEY, name TEXT NOT NULL, server_config TEXT NOT NULL,
+            enabled_cursor BOOLEAN NOT NULL DEFAULT 0
+        );
+        CREATE TABLE skills (
+            id TEXT PRIMARY KEY, name TEXT NOT NULL, directory TEXT NOT NULL,
+            enabled_cursor BOOLEAN NOT NULL DEFAULT 0
+        );
+        INSERT INTO mcp_servers (id, name, server_config) VALUES ('fs', 'fs', '{}');
+        "#,
+    )
+    .expect("seed v13 tables");
+    Database::set_user_version(&conn, 13).expect("set user_version=13");
+
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply migrations");
+
+    for table in ["mcp_servers", "skills"] {
+        assert!(
+            Database::has_column(&conn, table, "enabled_qwen").expect("check column"),
+            "{table}.enabled_qwen should exist after migration"
+        );
+    }
+    let enabled: bool = conn
+        .query_row(
+            "SELECT enabled_qwen FROM mcp_servers WHERE id = 'fs'",
+            [],
+            |r| r.get(0),
+        )
+        .expect("existing row readable");
+    assert!(
+        !enabled,
+        "existing servers should default to disabled for qwen"
+    );
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn provider_switch_logs_filter_and_prune() {
+    use crate::cc_switch::services::auto_switch::{AutoSwitchRecord, AutoSwitchTriggerKind};
+
+    let db = Database::memory().expect("create memory db");
+    let make = |policy_id: &str, app_type: &str, switched_at: i64, trigger| AutoSwitchRecord {
+        id: 0,
+        policy_id: policy_id.to_string(),
+        policy_name: policy_id.to_uppercase(),
+        app_type: app_type.to_string(),
+        trigger,
+        from_provider_id: None,
+        to_provider_id: "cheap".to_string(),
+        to_provider_name: "Cheap".to_string(),
+        detail: "0 22 * * *".to_string(),
+        switched_at,
+    };
+
+    db.insert_provider_switch_log(&make("night", "claude", 1, AutoSwitchTriggerKind::Schedule))
+        .expect("insert log");
+    db.insert_provider_switch_log(&make("quota", "claude", 2, AutoSwitchTriggerKind::Quota))
+        .expect("insert log");
+    db.insert_provider_switch_log(&make("night", "codex", 3, AutoSwitchTriggerKind::Schedule))
+        .expect("insert log");
+
+    let claude = db
+        .list_provider_switch_logs(Some("claude"), None, 10)
+        .expect("list claude");
+    assert_eq!(claude.len(), 2);
+    assert_eq!(claude[0].trigger, AutoSwitchTriggerKind::Quota);
+    assert_eq!(claude[0].from_provider_id, None);
+    assert_eq!(
+        db.list_provider_switch_logs(None, Some("night"), 10)
+            .expect("list by policy")
+            .len(),
+        2
+    );
+
+    assert_eq!(db.prune_provider_switch_logs(1).expect("prune"), 2);
+    let remaining = db
+        .list_provider_switch_logs(None, None, 10)
+        .expect("list all");
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].app_type, "codex");
+}
+
+#[test]
+fn prompt_versions_separate_live_file_history_and_prune_oldest() {
+    use crate::cc_switch::prompt::PromptVersion;
+
+    let db = Database::memory().expect("create memory db");
+
+    let make = |prompt_id: Option<&str>, content: &str, created_at: i64| PromptVersion {
+        id: 0,
+        app_type: "claude".to_string(),
+        prompt_id: prompt_id.map(str::to_string),
+        name: None,
+        content: content.to_string(),
+        templated: false,
+        source: "save".to_string(),
+        created_at,
+    };
+
+    for (i, content) in ["v1", "v2", "v3"].iter().enumerate() {
+        db.insert_prompt_version(&make(Some("p1"), content, i as i64))
+            .expect("insert prompt version");
+    }
+    let live = db
+        .insert_prompt_version(&make(None, "live", 10))
+        .expect("insert live version");
+
+    let versions = db
+        .list_prompt_versions("claude", Some("p1"))
+        .expect("list prompt versions");
+    assert_eq!(
+        versions
+            .iter()
+            .map(|v| v.content.as_str())
+            .collect::<Vec<_>>(),
+        vec!["v3", "v2", "v1"]
+    );
+    assert_eq!(
+        db.get_latest_prompt_version_content("claude", None)
+            .expect("latest live"),
+        Some("live".to_string())
+    );
+    assert!(db
+        .list_prompt_versions("codex", Some("p1"))
+        .expect("list other app")
+        .is_empty());
+
+    assert_eq!(
+        db.prune_prompt_versions("claude", Some("p1"), 2)
+            .expect("prune"),
+        1
+    );
+    assert_eq!(
+        db.list_prompt_versions("claude", Some("p1"))
+            .expect("list after prune")
+            .len(),
+        2
+    );
+    assert!(db.get_prompt_version(live).expect("get live").is_some());
+}
+
+fn snapshot_test_db() -> Database {
+    let db = Database::memory().expect("create memory db");
+    db.apply_schema_migrations().expect("apply migrations");
+    for (id, name) in [("p1", "Team"), ("p2", "Personal")] {
+        db.save_provider(
+            "claude",
+            &Provider::with_id(id.to_string(), name.to_string(), json!({ "env": {} }), None),
+        )
+        .expect("save provider");
+    }
+    db.save_mcp_server(&crate::cc_switch::app_config::McpServer {
+        id: "fetch".to_string(),
+        name: "Fetch".to_string(),
+        server: json!({ "command": "uvx" }),
+        apps: Default::default(),
+        description: None,
+        homepage: None,
+        docs: None,
+        tags: Vec::new(),
+    })
+    .expect("save mcp server");
+    db
+}
+
+#[test]
+fn snapshots_are_listed_inspected_and_retained_per_kind() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let db = snapshot_test_db();
+
+    let backup = db
+        .create_snapshot_in(dir.path(), DbSnapshotKind::Backup, 10)
+        .expect("create backup");
+    for _ in 0..3 {
+        db.create_snapshot_in(dir.path(), DbSnapshotKind::Scheduled, 2)
+            .expect("create scheduled snapshot");
+    }
+
+    let snapshots = Database::list_snapshots_in(dir.path()).expect("list snapshots");
+    let scheduled = snapshots
+        .iter()
+        .filter(|s| s.kind == DbSnapshotKind::Scheduled)
+        .count();
+    assert_eq!(scheduled, 2, "scheduled snapshots should be pruned");
+    assert!(
+        snapshots.iter().any(|s| s.id == backup.id),
+        "pruning scheduled snapshots must not remove import backups"
+    );
+
+    let details = Database::inspect_snapshot_in(dir.path(), &backup.id).expect("inspect");
+    assert_eq!(details.schema_version, SCHEMA_VERSION);
+    assert!(details.restorable);
+    assert_eq!(details.provider_counts.get("claude"), Some(&2));
+    assert_eq!(details.mcp_server_count, 1);
+    assert!(details.prompt_counts.is_empty());
+
+    assert!(Database::inspect_snapshot_in(dir.path(), "../mnemosyne").is_err());
+    assert!(Database::inspect_snapshot_in(dir.path(), "db_auto_missing").is_err());
+}
+
+#[test]
+fn snapshot_diff_and_restore_with_safety_snapshot() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let db = snapshot_test_db();
+    let snapshot = db
+        .create_snapshot_in(dir.path(), DbSnapshotKind::Scheduled, 5)
+        .expect("create snapshot");
+
+    db.save_provider(
+        "claude",
+        &Provider::with_id(
+            "p1".to_string(),
+            "Team (renamed)".to_string(),
+            json!({ "env": {} }),
+            None,
+        ),
+    )
+    .expect("rename provider");
+    db.delete_provider("claude", "p2").expect("delete provider");
+    db.save_provider(
+        "codex",
+        &Provider::with_id("p3".to_string(), "New".to_string(), json!({}), None),
+    )
+    .expect("add provider");
+    db.delete_mcp_server("fetch").expect("delete mcp server");
+
+    let diff = db
+        .diff_snapshot_in(dir.path(), &snapshot.id)
+        .expect("diff snapshot");
+    let status_of = |id: &str| {
+        diff.providers
+            .iter()
+            .find(|e| e.id == id)
+            .map(|e| (e.status, e.changed_fields.clone()))
+    };
+    assert_eq!(
+        status_of("p1"),
+        Some((SnapshotDiffStatus::Changed, vec!["name".to_string()]))
+    );
+    assert_eq!(
+        status_of("p2").map(|s| s.0),
+        Some(SnapshotDiffStatus::OnlyInSnapshot)
+    );
+    assert_eq!(
+        status_of("p3").map(|s| s.0),
+        Some(SnapshotDiffStatus::OnlyInCurrent)
+    );
+    assert_eq!(diff.mcp_servers.len(), 1);
+    assert_eq!(
+        diff.mcp_servers[0].status,
+        SnapshotDiffStatus::OnlyInSnapshot
+    );
+
+    let safety = db
+        .restore_snapshot_in(dir.path(), &snapshot.id, 5)
+        .expect("restore snapshot");
+    assert_eq!(safety.kind, DbSnapshotKind::PreRestore);
+
+    let providers = db.get_all_providers("claude").expect("providers");
+    assert_eq!(providers["p1"].name, "Team");
+    assert!(providers.contains_key("p2"));
+    assert!(db.get_all_providers("codex").expect("codex").is_empty());
+    assert!(db
+        .get_all_mcp_servers()
+        .expect("mcp servers")
+        .contains_key("fetch"));
+
+    let after = db
+        .diff_snapshot_in(dir.path(), &snapshot.id)
+        .expect("diff after restore");
+    assert!(after.providers.is_empty() && after.mcp_servers.is_empty());
+
+    // 安全快照保留了恢复前的状态
+    let undo = db
+        .diff_snapshot_in(dir.path(), &safety.id)
+        .expect("diff safety snapshot");
+    assert_eq!(undo.providers.len(), 3);
+}
@@ -0,0 +1,864 @@
+//! Bundle import from deep link
+//!
+//! A bundle is a JSON manifest of providers, MCP servers, prompts and skills,
+//! carried inline (`config`, Base64) or referenced by an HTTPS `configUrl`.
+//! An optional Ed25519 `signature` over the raw manifest bytes is verified
+//! against trusted publisher keys.
+//!
+//! Import is two-step: `preview_bundle` lists every item (nothing is written),
+//! then `import_bundle` applies only the items the user selected. The preview
+//! digest is checked again on import so the manifest cannot change in between.
+
+use super::mcp::parse_mcp_apps;
+use super::utils::{decode_base64_param, validate_https_url, validate_url};
+use super::{
+    import_prompt_from_deeplink, import_provider_from_deeplink, import_skill_from_deeplink,
+    DeepLinkImportRequest,
+};
+use crate::cc_switch::app_config::McpServer;
+use crate::cc_switch::database::Database;
+use crate::cc_switch::error::AppError;
+use crate::cc_switch::services::McpService;
+use crate::cc_switch::store::AppState;
+use crate::cc_switch::AppType;
+use base64::prelude::*;
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Maximum manifest size (inline or remote)
+const MAX_MANIFEST_BYTES: usize = 1024 * 1024;
+/// Remote manifest fetch timeout
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+/// Manifest schema version understood by this build
+const SUPPORTED_SCHEMA_VERSION: u32 = 1;
+
+// =============================================================================
+// Manifest
+// =============================================================================
+
+/// Bundle manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleManifest {
+    #[serde(default = "default_schema_version")]
+    pub schema_version: u32,
+    /// Bundle display name
+    pub name: String,
+    /// Publisher name as declared by the manifest (informational only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub providers: Vec<BundleProvider>,
+    #[serde(default)]
+    pub mcp_servers: Vec<BundleMcpServer>,
+    #[serde(default)]
+    pub prompts: Vec<BundlePrompt>,
+    #[serde(default)]
+    pub skills: Vec<BundleSkill>,
+}
+
+fn default_schema_version() -> u32 {
+    SUPPORTED_SCHEMA_VERSION
+}
+
+/// Provider entry (same fields as a provider deep link, config as plain JSON)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleProvider {
+    pub app: String,
+    pub name: String,
+    /// API endpoint (supports comma-separated multiple URLs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub haiku_model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sonnet_model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opus_model: Option<String>,
+    /// App config file content (same shape as the provider deep link `config`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<serde_json::Value>,
+    /// Switch to this provider after import
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+}
+
+/// MCP server entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleMcpServer {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Target applications ("claude", "codex", ...)
+    pub apps: Vec<String>,
+    /// Server spec in standard MCP JSON format
+    pub server: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+/// Prompt entry (content is plain Markdown)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundlePrompt {
+    pub app: String,
+    pub name: String,
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Activate this prompt after import
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+}
+
+/// Skill repository entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleSkill {
+    /// GitHub repository (format: "owner/name")
+    pub repo: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub directory: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+}
+
+// =============================================================================
+// Signature
+// =============================================================================
+
+/// Trusted bundle publisher (Ed25519 public key)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustedPublisher {
+    pub name: String,
+    /// Base64 encoded raw 32-byte Ed25519 public key
+    pub public_key: String,
+    pub added_at: i64,
+}
+
+/// Signature verification status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureStatus {
+    /// Signed by a trusted publisher
+    Verified,
+    /// No signature provided
+    Unsigned,
+    /// Signature malformed or not matching any trusted publisher key
+    Invalid,
+}
+
+/// Signature verification result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleSignature {
+    pub status: SignatureStatus,
+    /// Trusted publisher name (verified only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+    /// SHA-256 fingerprint prefix of the matching key (verified only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_fingerprint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+// =============================================================================
+// Preview / Result
+// =============================================================================
+
+/// Bundle item type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BundleItemKind {
+    Provider,
+    Mcp,
+    Prompt,
+    Skill,
+}
+
+impl BundleItemKind {
+    fn key(&self, index: usize) -> String {
+        let prefix = match self {
+            Self::Provider => "provider",
+            Self::Mcp => "mcp",
+            Self::Prompt => "prompt",
+            Self::Skill => "skill",
+        };
+        format!("{prefix}:{index}")
+    }
+}
+
+/// What importing an item will do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BundleItemAction {
+    /// A new entry is created
+    Create,
+    /// An existing entry is updated (MCP apps merged / skill repo replaced)
+    Update,
+}
+
+/// Preview of a single bundle item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleItemPreview {
+    /// Stable key used to select the item on import (e.g. "provider:0")
+    pub key: String,
+    pub kind: BundleItemKind,
+    pub name: String,
+    /// Target applications
+    pub apps: Vec<String>,
+    pub action: BundleItemAction,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// Bundle preview shown before anything is written
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundlePreview {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Manifest source: "inline" or the remote URL
+    pub source: String,
+    /// SHA-256 (hex) of the manifest bytes, must be passed back on import
+    pub digest: String,
+    pub signature: BundleSignature,
+    pub items: Vec<BundleItemPreview>,
+}
+
+/// Imported bundle item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleImportedItem {
+    pub key: String,
+    /// Created/updated resource ID
+    pub id: String,
+}
+
+/// Failed bundle item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleImportError {
+    pub key: String,
+    pub error: String,
+}
+
+/// Bundle import result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleImportResult {
+    pub imported: Vec<BundleImportedItem>,
+    pub failed: Vec<BundleImportError>,
+    /// Items not selected by the user
+    pub skipped: Vec<String>,
+}
+
+// =============================================================================
+// Loading & Verification
+// =============================================================================
+
+/// Load raw manifest bytes from a bundle request (inline Base64 or HTTPS URL)
+pub async fn fetch_bundle_manifest(request: &DeepLinkImportRequest) -> Result<Vec<u8>, AppError> {
+    ensure_bundle_request(request)?;
+
+    if let Some(config_b64) = &request.config {
+        let bytes = decode_base64_param("config", config_b64)?;
+        ensure_manifest_size(bytes.len())?;
+        return Ok(bytes);
+    }
+
+    let url = request.config_url.as_deref().ok_or_else(|| {
+        AppError::InvalidInput("Missing 'config' or 'configUrl' for bundle".to_string())
+    })?;
+    validate_https_url(url, "configUrl")?;
+
+    let response = crate::cc_switch::proxy::http_client::get()
+        .get(url)
+        .header("User-Agent", "mnemosyne-deeplink")
+        .timeout(FETCH_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| AppError::Message(format!("Failed to fetch bundle manifest: {e}")))?;
+
+    if !response.status().is_success() {
+        return Err(AppError::Message(format!(
+            "Failed to fetch bundle manifest: HTTP {}",
+            response.status()
+        )));
+    }
+    if let Some(length) = response.content_length() {
+        ensure_manifest_size(length as usize)?;
+    }
+
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| AppError::Message(format!("Failed to read bundle manifest: {e}")))?;
+    ensure_manifest_size(bytes.len())?;
+    Ok(bytes.to_vec())
+}
+
+/// Parse and validate manifest bytes
+pub(crate) fn parse_bundle_manifest(bytes: &[u8]) -> Result<BundleManifest, AppError> {
+    let manifest: BundleManifest = serde_json::from_slice(bytes)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid bundle manifest: {e}")))?;
+
+    if manifest.schema_version != SUPPORTED_SCHEMA_VERSION {
+        return Err(AppError::InvalidInput(format!(
+            "Unsupported bundle schema version: {}",
+            manifest.schema_version
+        )));
+    }
+    if manifest.name.trim().is_empty() {
+        return Err(AppError::InvalidInput(
+            "Bundle manifest 'name' cannot be empty".to_string(),
+        ));
+    }
+    if manifest.providers.is_empty()
+        && manifest.mcp_servers.is_empty()
+        && manifest.prompts.is_empty()
+        && manifest.skills.is_empty()
+    {
+        return Err(AppError::InvalidInput(
+            "Bundle contains no items".to_string(),
+        ));
+    }
+
+    for (i, provider) in manifest.providers.iter().enumerate() {
+        let key = BundleItemKind::Provider.key(i);
+        if !matches!(
+            provider.app.as_str(),
+            "claude" | "codex" | "gemini" | "opencode"
+        ) {
+            return Err(AppError::InvalidInput(format!(
+                "{key}: invalid app type '{}'",
+                provider.app
+            )));
+        }
+        if provider.name.trim().is_empty() {
+            return Err(AppError::InvalidInput(format!(
+                "{key}: 'name' cannot be empty"
+            )));
+        }
+        if let Some(endpoint) = &provider.endpoint {
+            for (j, url) in endpoint.split(',').enumerate() {
+                let trimmed = url.trim();
+                if !trimmed.is_empty() {
+                    validate_url(trimmed, &format!("{key}.endpoint[{j}]"))?;
+                }
+            }
+        }
+        if let Some(homepage) = provider.homepage.as_deref().filter(|s| !s.is_empty()) {
+            validate_url(homepage, &format!("{key}.homepage"))?;
+        }
+    }
+
+    for (i, server) in manifest.mcp_servers.iter().enumerate() {
+        let key = BundleItemKind::Mcp.key(i);
+        if server.id.trim().is_empty() {
+            return Err(AppError::InvalidInput(format!(
+                "{key}: 'id' cannot be empty"
+            )));
+        }
+        parse_mcp_apps(&server.apps.join(","))
+            .map_err(|e| AppError::InvalidInput(format!("{key}: {e}")))?;
+        if !server.server.is_object() {
+            return Err(AppError::InvalidInput(format!(
+                "{key}: 'server' must be an object"
+            )));
+        }
+    }
+
+    for (i, prompt) in manifest.prompts.iter().enumerate() {
+        let key = BundleItemKind::Prompt.key(i);
+        AppType::from_str(&prompt.app).map_err(|_| {
+            AppError::InvalidInput(format!("{key}: invalid app type '{}'", prompt.app))
+        })?;
+        if prompt.name.trim().is_empty() {
+            return Err(AppError::InvalidInput(format!(
+                "{key}: 'name' cannot be empty"
+            )));
+        }
+    }
+
+    for (i, skill) in manifest.skills.iter().enumerate() {
+        let key = BundleItemKind::Skill.key(i);
+        if skill.repo.split('/').count() != 2 || skill.repo.split('/').any(str::is_empty) {
+            return Err(AppError::InvalidInput(format!(
+                "{key}: invalid repo format, expected 'owner/name', got '{}'",
+                skill.repo
+            )));
+        }
+    }
+
+    Ok(manifest)
+}
+
+/// Verify the manifest signature against trusted publisher keys
+pub(crate) fn verify_bundle_signature(
+    manifest_bytes: &[u8],
+    signature: Option<&str>,
+    trusted: &[TrustedPublisher],
+) -> BundleSignature {
+    let invalid = |message: String| BundleSignature {
+        status: SignatureStatus::Invalid,
+        publisher: None,
+        key_fingerprint: None,
+        message: Some(message),
+    };
+
+    let Some(signature) = signature else {
+        return BundleSignature {
+            status: SignatureStatus::Unsigned,
+            publisher: None,
+            key_fingerprint: None,
+            message: None,
+        };
+    };
+
+    let signature = match decode_base64_param("signature", signature) {
+        Ok(bytes) if bytes.len() == 64 => bytes,
+        Ok(bytes) => {
+            return invalid(format!(
+                "Malformed signature: expected 64 bytes, got {}",
+                bytes.len()
+            ))
+        }
+        Err(e) => return invalid(e.to_string()),
+    };
+
+    if trusted.is_empty() {
+        return invalid("No trusted publisher keys configured".to_string());
+    }
+
+    for publisher in trusted {
+        let Ok(key) = decode_public_key(&publisher.public_key) else {
+            log::warn!("Skipping malformed trusted key for '{}'", publisher.name);
+            continue;
+        };
+        if UnparsedPublicKey::new(&ED25519, &key)
+            .verify(manifest_bytes, &signature)
+            .is_ok()
+        {
+            return BundleSignature {
+                status: SignatureStatus::Verified,
+                publisher: Some(publisher.name.clone()),
+                key_fingerprint: Some(key_fingerprint(&key)),
+                message: None,
+            };
+        }
+    }
+
+    invalid("Signature does not match any trusted publisher key".to_string())
+}
+
+fn decode_public_key(raw: &str) -> Result<Vec<u8>, AppError> {
+    let key = decode_base64_param("publicKey", raw)?;
+    if key.len() != 32 {
+        return Err(AppError::InvalidInput(format!(
+            "Invalid Ed25519 public key: expected 32 bytes, got {}",
+            key.len()
+        )));
+    }
+    Ok(key)
+}
+
+fn key_fingerprint(key: &[u8]) -> String {
+    hex::encode(&Sha256::digest(key)[..8])
+}
+
+fn manifest_digest(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+fn ensure_manifest_size(len: usize) -> Result<(), AppError> {
+    if len > MAX_MANIFEST_BYTES {
+        return Err(AppError::InvalidInput(format!(
+            "Bundle manifest too large: {len} bytes (max {MAX_MANIFEST_BYTES})"
+        )));
+    }
+    Ok(())
+}
+
+fn ensure_bundle_request(request: &DeepLinkImportRequest) -> Result<(), AppError> {
+    if request.resource != "bundle" {
+        return Err(AppError::InvalidInput(format!(
+            "Expected bundle resource, got '{}'",
+            request.resource
+        )));
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Trusted Publishers
+// =============================================================================
+
+/// Add (or rename) a trusted publisher key
+pub fn add_trusted_publisher(
+    db: &Database,
+    name: &str,
+    public_key: &str,
+) -> Result<Vec<TrustedPublisher>, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::InvalidInput(
+            "Publisher name cannot be empty".to_string(),
+        ));
+    }
+    let key = decode_public_key(public_key.trim())?;
+    let public_key = BASE64_STANDARD.encode(&key);
+
+    let mut publishers = db.get_trusted_bundle_publishers()?;
+    match publishers.iter_mut().find(|p| p.public_key == public_key) {
+        Some(existing) => existing.name = name.to_string(),
+        None => publishers.push(TrustedPublisher {
+            name: name.to_string(),
+            public_key,
+            added_at: chrono::Utc::now().timestamp(),
+        }),
+    }
+    db.set_trusted_bundle_publishers(&publishers)?;
+    Ok(publishers)
+}
+
+/// Remove a trusted publisher key
+pub fn remove_trusted_publisher(
+    db: &Database,
+    public_key: &str,
+) -> Result<Vec<TrustedPublisher>, AppError> {
+    let normalized = decode_public_key(public_key.trim())
+        .map(|key| BASE64_STANDARD.encode(key))
+        .unwrap_or_else(|_| public_key.trim().to_string());
+
+    let mut publishers = db.get_trusted_bundle_publishers()?;
+    publishers.retain(|p| p.public_key != normalized);
+    db.set_trusted_bundle_publishers(&publishers)?;
+    Ok(publishers)
+}
+
+// =============================================================================
+// Preview & Import
+// =============================================================================
+
+/// Build an item-by-item preview of a bundle without writing anything
+pub fn preview_bundle(
+    state: &AppState,
+    request: &DeepLinkImportRequest,
+    manifest_bytes: &[u8],
+) -> Result<BundlePreview, AppError> {
+    ensure_bundle_request(request)?;
+    let manifest = parse_bundle_manifest(manifest_bytes)?;
+    let trusted = state.db.get_trusted_bundle_publishers()?;
+    let mut signature =
+        verify_bundle_signature(manifest_bytes, request.signature.as_deref(), &trusted);
+    if signature.status == SignatureStatus::Unsigned {
+        if let Some(publisher) = &manifest.publisher {
+            signature.message = Some(format!(
+                "Manifest names publisher '{publisher}' but is not signed"
+            ));
+        }
+    }
+
+    let mut items = Vec::new();
+
+    for (i, provider) in manifest.providers.iter().enumerate() {
+        let mut warnings = Vec::new();
+        let existing = state.db.get_all_providers(&provider.app)?;
+        if existing.values().any(|p| p.name == provider.name) {
+            warnings.push(format!(
+                "A provider named '{}' already exists; another one will be added",
+                provider.name
+            ));
+        }
+        if provider.enabled.unwrap_or(false) {
+            warnings.push("Will become the current provider".to_string());
+        }
+        items.push(BundleItemPreview {
+            key: BundleItemKind::Provider.key(i),
+            kind: BundleItemKind::Provider,
+            name: provider.name.clone(),
+            apps: vec![provider.app.clone()],
+            action: BundleItemAction::Create,
+            warnings,
+        });
+    }
+
+    let existing_servers = state.db.get_all_mcp_servers()?;
+    for (i, server) in manifest.mcp_servers.iter().enumerate() {
+        let mut warnings = Vec::new();
+        let action = if existing_servers.contains_key(&server.id) {
+            warnings.push("Already installed; only the target apps are merged".to_string());
+            BundleItemAction::Update
+        } else {
+            BundleItemAction::Create
+        };
+        if let Some(command) = server.server.get("command").and_then(|v| v.as_str()) {
+            warnings.push(format!("Runs local command '{command}'"));
+        }
+        items.push(BundleItemPreview {
+            key: BundleItemKind::Mcp.key(i),
+            kind: BundleItemKind::Mcp,
+            name: server.name.clone().unwrap_or_else(|| server.id.clone()),
+            apps: server.apps.clone(),
+            action,
+            warnings,
+        });
+    }
+
+    for (i, prompt) in manifest.prompts.iter().enumerate() {
+        let mut warnings = Vec::new();
+        if prompt.enabled.unwrap_or(false) {
+            warnings.push("Will replace the active prompt".to_string());
+        }
+        items.push(BundleItemPreview {
+            key: BundleItemKind::Prompt.key(i),
+            kind: BundleItemKind::Prompt,
+            name: prompt.name.clone(),
+            apps: vec![prompt.app.clone()],
+            action: BundleItemAction::Create,
+            warnings,
+        });
+    }
+
+    let existing_repos = state.db.get_skill_repos()?;
+    for (i, skill) in manifest.skills.iter().enumerate() {
+        let exists = existing_repos
+            .iter()
+            .any(|r| format!("{}/{}", r.owner, r.name) == skill.repo);
+        items.push(BundleItemPreview {
+            key: BundleItemKind::Skill.key(i),
+            kind: BundleItemKind::Skill,
+            name: skill.repo.clone(),
+            apps: vec!["claude".to_string()],
+            action: if exists {
+                BundleItemAction::Update
+            } else {
+                BundleItemAction::Create
+            },
+            warnings: Vec::new(),
+        });
+    }
+
+    Ok(BundlePreview {
+        name: manifest.name,
+        publisher: manifest.publisher,
+        description: manifest.description,
+        source: request
+            .config_url
+            .clone()
+            .unwrap_or_else(|| "inline".to_string()),
+        digest: manifest_digest(manifest_bytes),
+        signature,
+        items,
+    })
+}
+
+/// Import the selected items of a previewed bundle
+///
+/// `expected_digest` is the digest returned by `preview_bundle`; import is
+/// refused if the manifest changed or its signature is invalid.
+pub fn import_bundle(
+    state: &AppState,
+    request: &DeepLinkImportRequest,
+    manifest_bytes: &[u8],
+    selected: &[String],
+    expected_digest: &str,
+) -> Result<BundleImportResult, AppError> {
+    ensure_bundle_request(request)?;
+    if manifest_digest(manifest_bytes) != expected_digest {
+        return Err(AppError::InvalidInput(
+            "Bundle manifest changed since preview, please preview again".to_string(),
+        ));
+    }
+    let manifest = parse_bundle_manifest(manifest_bytes)?;
+
+    let trusted = state.db.get_trusted_bundle_publishers()?;
+    let signature = verify_bundle_signature(manifest_bytes, request.signature.as_deref(), &trusted);
+    if signature.status == SignatureStatus::Invalid {
+        return Err(AppError::InvalidInput(format!(
+            "Bundle signature verification failed: {}",
+            signature.message.unwrap_or_default()
+        )));
+    }
+
+    let mut result = BundleImportResult {
+        imported: Vec::new(),
+        failed: Vec::new(),
+        skipped: Vec::new(),
+    };
+    let mut apply = |key: String, import: &dyn Fn() -> Result<String, AppError>| {
+        if !selected.contains(&key) {
+            result.skipped.push(key);
+            return;
+        }
+        match import() {
+            Ok(id) => {
+                log::info!("Imported bundle item {key} as '{id}'");
+                result.imported.push(BundleImportedItem { key, id });
+            }
+            Err(e) => {
+                log::warn!("Failed to import bundle item {key}: {e}");
+                result.failed.push(BundleImportError {
+                    key,
+                    error: e.to_string(),
+                });
+            }
+        }
+    };
+
+    for (i, provider) in manifest.providers.iter().enumerate() {
+        apply(BundleItemKind::Provider.key(i), &|| {
+            import_provider_from_deeplink(state, provider_request(&request.version, provider)?)
+        });
+    }
+    for (i, server) in manifest.mcp_servers.iter().enumerate() {
+        apply(BundleItemKind::Mcp.key(i), &|| {
+            import_mcp_server(state, server)
+        });
+    }
+    for (i, prompt) in manifest.prompts.iter().enumerate() {
+        apply(BundleItemKind::Prompt.key(i), &|| {
+            import_prompt_from_deeplink(state, prompt_request(&request.version, prompt))
+        });
+    }
+    for (i, skill) in manifest.skills.iter().enumerate() {
+        apply(BundleItemKind::Skill.key(i), &|| {
+            import_skill_from_deeplink(state, skill_request(&request.version, skill))
+        });
+    }
+
+    log::info!(
+        "Bundle '{}' imported: {} ok, {} failed, {} skipped",
+        manifest.name,
+        result.imported.len(),
+        result.failed.len(),
+        result.skipped.len()
+    );
+    Ok(result)
+}
+
+/// Convert a provider entry into a provider deep link request
+fn provider_request(
+    version: &str,
+    provider: &BundleProvider,
+) -> Result<DeepLinkImportRequest, AppError> {
+    let config = provider
+        .config
+        .as_ref()
+        .map(|value| {
+            serde_json::to_vec(value)
+                .map(|bytes| BASE64_STANDARD.encode(bytes))
+                .map_err(|e| AppError::JsonSerialize { source: e })
+        })
+        .transpose()?;
+
+    Ok(DeepLinkImportRequest {
+        version: version.to_string(),
+        resource: "provider".to_string(),
+        app: Some(provider.app.clone()),
+        name: Some(provider.name.clone()),
+        enabled: provider.enabled,
+        homepage: provider.homepage.clone(),
+        endpoint: provider.endpoint.clone(),
+        api_key: provider.api_key.clone(),
+        icon: provider.icon.clone(),
+        model: provider.model.clone(),
+        notes: provider.notes.clone(),
+        haiku_model: provider.haiku_model.clone(),
+        sonnet_model: provider.sonnet_model.clone(),
+        opus_model: provider.opus_model.clone(),
+        config_format: config.as_ref().map(|_| "json".to_string()),
+        config,
+        ..Default::default()
+    })
+}
+
+/// Convert a prompt entry into a prompt deep link request
+fn prompt_request(version: &str, prompt: &BundlePrompt) -> DeepLinkImportRequest {
+    DeepLinkImportRequest {
+        version: version.to_string(),
+        resource: "prompt".to_string(),
+        app: Some(prompt.app.clone()),
+        name: Some(prompt.name.clone()),
+        enabled: prompt.enabled,
+        content: Some(BASE64_STANDARD.encode(&prompt.content)),
+        description: prompt.description.clone(),
+        ..Default::default()
+    }
+}
+
+/// Convert a skill entry into a skill deep link request
+fn skill_request(version: &str, skill: &BundleSkill) -> DeepLinkImportRequest {
+    DeepLinkImportRequest {
+        version: version.to_string(),
+        resource: "skill".to_string(),
+        app: Some("claude".to_string()),
+        enabled: skill.enabled,
+        repo: Some(skill.repo.clone()),
+        directory: skill.directory.clone(),
+        branch: skill.branch.clone(),
+        ..Default::default()
+    }
+}
+
+/// Import one MCP server; existing servers only get the target apps merged
+fn import_mcp_server(state: &AppState, item: &BundleMcpServer) -> Result<String, AppError> {
+    let target_apps = parse_mcp_apps(&item.apps.join(","))?;
+
+    let server = match state.db.get_all_mcp_servers()?.get(&item.id) {
+        Some(existing) => {
+            let mut merged = existing.clone();
+            for app in target_apps.enabled_apps() {
+                merged.apps.set_enabled_for(&app, true);
+            }
+            merged
+        }
+        None => McpServer {
+            id: item.id.clone(),
+            name: item.name.clone().unwrap_or_else(|| item.id.clone()),
+            server: item.server.clone(),
+            apps: target_apps,
+            description: item.description.clone(),
+            homepage: item.homepage.clone(),
+            docs: None,
+            tags: if item.tags.is_empty() {
+                vec!["imported".to_string()]
+            } else {
+                item.tags.clone()
+            },
+        },
+    };
+
+    McpService::upsert_server(state, server)?;
+    Ok(item.id.clone())
+}
@@ -168,6 +168,7 @@ pub(crate) fn parse_mcp_apps(apps_str: &str) -> Result<McpApps, AppError> {
         gemini: false,
         opencode: false,
         cursor: false,
+        qwen: false,
     };
 
     for app in apps_str.split(',') {
@@ -177,6 +178,7 @@ pub(crate) fn parse_mcp_apps(apps_str: &str) -> Result<McpApps, AppError> {
             "gemini" => apps.gemini = true,
             "opencode" => apps.opencode = true,
             "cursor" => apps.cursor = true,
+            "qwen" => apps.qwen = true,
             other => {
                 return Err(AppError::InvalidInput(format!(
                     "Invalid app in 'apps': {other}"
@@ -6,9 +6,11 @@
 //! - MCP server configurations
 //! - Prompts
 //! - Skills
+//! - Bundles of all of the above (optionally signed)
 //!
 //! See docs/ccswitch-deeplink-design.md for detailed design.
 
+mod bundle;
 mod mcp;
 mod parser;
 mod prompt;
@@ -22,6 +24,10 @@ mod tests;
 use serde::{Deserialize, Serialize};
 
 // Re-export public API
+pub use bundle::{
+    add_trusted_publisher, fetch_bundle_manifest, import_bundle, preview_bundle,
+    remove_trusted_publisher, BundleImportResult, BundlePreview, TrustedPublisher,
+};
 pub use mcp::import_mcp_from_deeplink;
 pub use parser::parse_deeplink_url;
 pub use prompt::import_prompt_from_deeplink;
@@ -32,12 +38,12 @@ pub use skill::import_skill_from_deeplink;
 ///
 /// Represents a parsed ccswitch:// URL ready for processing.
 /// This struct contains all possible fields for all resource types.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DeepLinkImportRequest {
     /// Protocol version (e.g., "v1")
     pub version: String,
-    /// Resource type to import: "provider" | "prompt" | "mcp" | "skill"
+    /// Resource type to import: "provider" | "prompt" | "mcp" | "skill" | "bundle"
     pub resource: String,
 
     // ============ Common fields ============
@@ -104,13 +110,13 @@ pub struct DeepLinkImportRequest {
     pub branch: Option<String>,
 
     // ============ Config file fields (v3.8+) ============
-    /// Base64 encoded config content
+    /// Base64 encoded config content (bundle: the JSON manifest)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub config: Option<String>,
     /// Config format (json/toml)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub config_format: Option<String>,
-    /// Remote config URL
+    /// Remote config URL (bundle: HTTPS URL of the JSON manifest)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub config_url: Option<String>,
 
@@ -136,4 +142,9 @@ pub struct DeepLinkImportRequest {
     /// Auto query interval in minutes (0 to disable)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub usage_auto_interval: Option<u64>,
+
+    // ============ Bundle fields ============
+    /// Base64 encoded Ed25519 signature over the raw manifest bytes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
 }
@@ -2,7 +2,7 @@
 //!
 //! Parses ccswitch:// URLs into DeepLinkImportRequest structures.
 
-use super::utils::validate_url;
+use super::utils::{validate_https_url, validate_url};
 use super::DeepLinkImportRequest;
 use crate::cc_switch::error::AppError;
 use std::collections::HashMap;
@@ -61,6 +61,7 @@ pub fn parse_deeplink_url(url_str: &str) -> Result<DeepLinkImportRequest, AppErr
         "prompt" => parse_prompt_deeplink(&params, version, resource),
         "mcp" => parse_mcp_deeplink(&params, version, resource),
         "skill" => parse_skill_deeplink(&params, version, resource),
+        "bundle" => parse_bundle_deeplink(&params, version, resource),
         _ => Err(AppError::InvalidInput(format!(
             "Unsupported resource type: {resource}"
         ))),
@@ -170,6 +171,7 @@ fn parse_provider_deeplink(
         usage_access_token,
         usage_user_id,
         usage_auto_interval,
+        signature: None,
     })
 }
 
@@ -237,6 +239,7 @@ fn parse_prompt_deeplink(
         usage_access_token: None,
         usage_user_id: None,
         usage_auto_interval: None,
+        signature: None,
     })
 }
 
@@ -299,6 +302,7 @@ fn parse_mcp_deeplink(
         usage_access_token: None,
         usage_user_id: None,
         usage_auto_interval: None,
+        signature: None,
     })
 }
 
@@ -354,6 +358,46 @@ fn parse_skill_deeplink(
         usage_access_token: None,
         usage_user_id: None,
         usage_auto_interval: None,
+        signature: None,
     })
 }
 
+/// Parse bundle deep link parameters
+///
+/// The manifest is carried inline (`config`, Base64 JSON) or referenced by an
+/// HTTPS `configUrl`. `signature` is an optional Base64 Ed25519 signature.
+fn parse_bundle_deeplink(
+    params: &HashMap<String, String>,
+    version: String,
+    resource: String,
+) -> Result<DeepLinkImportRequest, AppError> {
+    let config = params.get("config").cloned().filter(|v| !v.is_empty());
+    let config_url = params.get("configUrl").cloned().filter(|v| !v.is_empty());
+
+    match (&config, &config_url) {
+        (None, None) => {
+            return Err(AppError::InvalidInput(
+                "Missing 'config' or 'configUrl' parameter for bundle".to_string(),
+            ))
+        }
+        (Some(_), Some(_)) => {
+            return Err(AppError::InvalidInput(
+                "Bundle accepts either 'config' or 'configUrl', not both".to_string(),
+            ))
+        }
+        (None, Some(url)) => validate_https_url(url, "configUrl")?,
+        (Some(_), None) => {}
+    }
+
+    let signature = params.get("signature").cloned().filter(|v| !v.is_empty());
+
+    Ok(DeepLinkImportRequest {
+        version,
+        resource,
+        config,
+        config_format: Some("json".to_string()), // Bundle manifest is always JSON
+        config_url,
+        signature,
+        ..Default::default()
+    })
+}
@@ -151,6 +151,11 @@ pub(crate) fn build_provider_from_request(
                 "Cursor does not support provider import via deep link".to_string(),
             ))
         }
+        AppType::Qwen => {
+            return Err(AppError::InvalidInput(
+                "Qwen Code does not support provider import via deep link".to_string(),
+            ))
+        }
     };
 
     // Build usage script configuration if provided
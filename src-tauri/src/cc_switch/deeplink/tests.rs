@@ -1,5 +1,9 @@
 //! Deep link module tests
 
+use super::bundle::{
+    import_bundle, parse_bundle_manifest, preview_bundle, verify_bundle_signature, SignatureStatus,
+    TrustedPublisher,
+};
 use super::mcp::parse_mcp_apps;
 use super::parser::parse_deeplink_url;
 use super::prompt::import_prompt_from_deeplink;
@@ -152,6 +156,7 @@ fn test_build_gemini_provider_with_model() {
         usage_access_token: None,
         usage_user_id: None,
         usage_auto_interval: None,
+        signature: None,
     };
 
     let provider = build_provider_from_request(&AppType::Gemini, &request).unwrap();
@@ -205,6 +210,7 @@ fn test_build_gemini_provider_without_model() {
         usage_access_token: None,
         usage_user_id: None,
         usage_auto_interval: None,
+        signature: None,
     };
 
     let provider = build_provider_from_request(&AppType::Gemini, &request).unwrap();
@@ -253,6 +259,7 @@ fn test_parse_and_merge_config_claude() {
         usage_access_token: None,
         usage_user_id: None,
         usage_auto_interval: None,
+        signature: None,
     };
 
     let merged = parse_and_merge_config(&request).unwrap();
@@ -303,6 +310,7 @@ fn test_parse_and_merge_config_url_override() {
         usage_access_token: None,
         usage_user_id: None,
         usage_auto_interval: None,
+        signature: None,
     };
 
     let merged = parse_and_merge_config(&request).unwrap();
@@ -459,3 +467,194 @@ fn test_infer_homepage_from_endpoint_without_homepage() {
     );
 }
 
+// =============================================================================
+// Bundle Tests
+// =============================================================================
+
+fn bundle_manifest_json() -> String {
+    serde_json::json!({
+        "schemaVersion": 1,
+        "name": "Team onboarding",
+        "publisher": "Acme",
+        "providers": [{
+            "app": "claude",
+            "name": "Acme Claude",
+            "endpoint": "https://api.acme.dev",
+            "apiKey": "sk-acme"
+        }],
+        "mcpServers": [{
+            "id": "fetch",
+            "apps": ["claude", "codex"],
+            "server": { "command": "uvx", "args": ["mcp-server-fetch"] }
+        }],
+        "prompts": [{
+            "app": "claude",
+            "name": "Team rules",
+            "content": "# Rules\nBe kind."
+        }],
+        "skills": [{ "repo": "acme/skills", "branch": "main" }]
+    })
+    .to_string()
+}
+
+fn bundle_request(manifest: &str, signature: Option<&[u8]>) -> DeepLinkImportRequest {
+    let mut url = format!(
+        "ccswitch://v1/import?resource=bundle&config={}",
+        BASE64_URL_SAFE_NO_PAD.encode(manifest)
+    );
+    if let Some(signature) = signature {
+        url.push_str(&format!(
+            "&signature={}",
+            BASE64_URL_SAFE_NO_PAD.encode(signature)
+        ));
+    }
+    parse_deeplink_url(&url).unwrap()
+}
+
+#[test]
+fn test_parse_bundle_deeplink() {
+    let request = bundle_request(&bundle_manifest_json(), Some(&[7u8; 64]));
+    assert_eq!(request.resource, "bundle");
+    assert_eq!(request.config_format.as_deref(), Some("json"));
+    assert!(request.config.is_some());
+    assert!(request.signature.is_some());
+
+    let request = parse_deeplink_url(
+        "ccswitch://v1/import?resource=bundle&configUrl=https%3A%2F%2Fexample.com%2Fteam.json",
+    )
+    .unwrap();
+    assert_eq!(
+        request.config_url.as_deref(),
+        Some("https://example.com/team.json")
+    );
+
+    let err = parse_deeplink_url(
+        "ccswitch://v1/import?resource=bundle&configUrl=http%3A%2F%2Fexample.com%2Fteam.json",
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("must be https"));
+
+    let err = parse_deeplink_url("ccswitch://v1/import?resource=bundle").unwrap_err();
+    assert!(err.to_string().contains("Missing 'config' or 'configUrl'"));
+}
+
+#[test]
+fn test_bundle_manifest_validation() {
+    let err = parse_bundle_manifest(br#"{"name":"Empty"}"#).unwrap_err();
+    assert!(err.to_string().contains("no items"));
+
+    let err =
+        parse_bundle_manifest(br#"{"name":"Bad","skills":[{"repo":"no-slash"}]}"#).unwrap_err();
+    assert!(err.to_string().contains("skill:0"));
+
+    let err =
+        parse_bundle_manifest(br#"{"schemaVersion":2,"name":"Future","skills":[{"repo":"a/b"}]}"#)
+            .unwrap_err();
+    assert!(err.to_string().contains("schema version"));
+}
+
+#[test]
+fn test_bundle_signature_verification() {
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+    let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+    let trusted = vec![TrustedPublisher {
+        name: "Acme".to_string(),
+        public_key: BASE64_STANDARD.encode(key_pair.public_key().as_ref()),
+        added_at: 0,
+    }];
+
+    let manifest = bundle_manifest_json();
+    let signature = BASE64_STANDARD.encode(key_pair.sign(manifest.as_bytes()).as_ref());
+
+    let result = verify_bundle_signature(manifest.as_bytes(), Some(&signature), &trusted);
+    assert_eq!(result.status, SignatureStatus::Verified);
+    assert_eq!(result.publisher.as_deref(), Some("Acme"));
+
+    let tampered = manifest.replace("sk-acme", "sk-evil");
+    let result = verify_bundle_signature(tampered.as_bytes(), Some(&signature), &trusted);
+    assert_eq!(result.status, SignatureStatus::Invalid);
+
+    let result = verify_bundle_signature(manifest.as_bytes(), Some(&signature), &[]);
+    assert_eq!(result.status, SignatureStatus::Invalid);
+
+    let result = verify_bundle_signature(manifest.as_bytes(), None, &trusted);
+    assert_eq!(result.status, SignatureStatus::Unsigned);
+}
+
+#[test]
+fn test_bundle_preview_then_import_selected_items() {
+    let db = Arc::new(Database::memory().expect("create memory db"));
+    let state = AppState::new(db.clone());
+
+    let manifest = bundle_manifest_json();
+    let request = bundle_request(&manifest, None);
+
+    let preview = preview_bundle(&state, &request, manifest.as_bytes()).expect("preview");
+    assert_eq!(preview.name, "Team onboarding");
+    assert_eq!(preview.signature.status, SignatureStatus::Unsigned);
+    assert!(preview.signature.message.is_some());
+    let keys: Vec<&str> = preview.items.iter().map(|i| i.key.as_str()).collect();
+    assert_eq!(keys, vec!["provider:0", "mcp:0", "prompt:0", "skill:0"]);
+
+    // Preview writes nothing
+    assert!(db.get_prompts("claude").unwrap().is_empty());
+    assert!(db.get_skill_repos().unwrap().is_empty());
+
+    let err = import_bundle(
+        &state,
+        &request,
+        manifest.as_bytes(),
+        &["prompt:0".to_string()],
+        "stale-digest",
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("changed since preview"));
+
+    let selected = vec!["prompt:0".to_string(), "skill:0".to_string()];
+    let result = import_bundle(
+        &state,
+        &request,
+        manifest.as_bytes(),
+        &selected,
+        &preview.digest,
+    )
+    .expect("import");
+
+    assert_eq!(result.imported.len(), 2);
+    assert!(result.failed.is_empty());
+    assert_eq!(result.skipped, vec!["provider:0", "mcp:0"]);
+
+    let prompts = db.get_prompts("claude").unwrap();
+    let prompt = prompts.values().next().expect("prompt imported");
+    assert_eq!(prompt.content, "# Rules\nBe kind.");
+    assert!(db
+        .get_skill_repos()
+        .unwrap()
+        .iter()
+        .any(|r| r.owner == "acme" && r.name == "skills"));
+    assert!(db.get_all_mcp_servers().unwrap().is_empty());
+}
+
+#[test]
+fn test_bundle_import_rejects_untrusted_signature() {
+    let db = Arc::new(Database::memory().expect("create memory db"));
+    let state = AppState::new(db);
+
+    let manifest = bundle_manifest_json();
+    let request = bundle_request(&manifest, Some(&[1u8; 64]));
+    let preview = preview_bundle(&state, &request, manifest.as_bytes()).expect("preview");
+    assert_eq!(preview.signature.status, SignatureStatus::Invalid);
+
+    let err = import_bundle(
+        &state,
+        &request,
+        manifest.as_bytes(),
+        &["skill:0".to_string()],
+        &preview.digest,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("signature verification failed"));
+}
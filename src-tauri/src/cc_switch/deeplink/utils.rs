@@ -21,6 +21,21 @@ pub fn validate_url(url_str: &str, field_name: &str) -> Result<(), AppError> {
     Ok(())
 }
 
+/// Validate that a string is an HTTPS URL (used for remote content that gets imported)
+pub fn validate_https_url(url_str: &str, field_name: &str) -> Result<(), AppError> {
+    let url = Url::parse(url_str)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid URL for '{field_name}': {e}")))?;
+
+    let scheme = url.scheme();
+    if scheme != "https" {
+        return Err(AppError::InvalidInput(format!(
+            "Invalid URL scheme for '{field_name}': must be https, got '{scheme}'"
+        )));
+    }
+
+    Ok(())
+}
+
 /// Decode a Base64 parameter from deep link URL
 ///
 /// This function handles common issues with Base64 in URLs:
@@ -3,7 +3,7 @@ use crate::cc_switch::error::AppError;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// 获取用户主目录，带回退和日志
 fn get_home_dir() -> PathBuf {
@@ -162,8 +162,16 @@ pub fn read_gemini_env() -> Result<HashMap<String, String>, AppError> {
 
 /// 写入 Gemini .env 文件（原子操作）
 pub fn write_gemini_env_atomic(map: &HashMap<String, String>) -> Result<(), AppError> {
-    let path = get_gemini_env_path();
+    write_env_file_atomic(&get_gemini_env_path(), map)
+}
 
+/// 写入 .env 文件（原子操作，目录 700 / 文件 600）
+///
+/// Qwen Code 等沿用 Gemini CLI 布局的应用共用此实现。
+pub(crate) fn write_env_file_atomic(
+    path: &Path,
+    map: &HashMap<String, String>,
+) -> Result<(), AppError> {
     // 确保目录存在
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
@@ -181,17 +189,17 @@ pub fn write_gemini_env_atomic(map: &HashMap<String, String>) -> Result<(), AppE
     }
 
     let content = serialize_env_file(map);
-    write_text_file(&path, &content)?;
+    write_text_file(path, &content)?;
 
     // 设置文件权限为 600（仅所有者可读写）
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
-        let mut perms = fs::metadata(&path)
-            .map_err(|e| AppError::io(&path, e))?
+        let mut perms = fs::metadata(path)
+            .map_err(|e| AppError::io(path, e))?
             .permissions();
         perms.set_mode(0o600);
-        fs::set_permissions(&path, perms).map_err(|e| AppError::io(&path, e))?;
+        fs::set_permissions(path, perms).map_err(|e| AppError::io(path, e))?;
     }
 
     Ok(())
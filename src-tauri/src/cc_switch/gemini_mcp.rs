@@ -58,23 +58,8 @@ pub fn read_mcp_servers_map() -> Result<std::collections::HashMap<String, Value>
         .unwrap_or_default();
 
     // 反向格式转换：Gemini 特有格式 → 统一 MCP 格式
-    for (_, spec) in servers.iter_mut() {
-        if let Some(obj) = spec.as_object_mut() {
-            // httpUrl → url + type: "http"
-            if let Some(http_url) = obj.remove("httpUrl") {
-                obj.insert("url".to_string(), http_url);
-                obj.insert("type".to_string(), Value::String("http".to_string()));
-            }
-
-            // Gemini CLI 不使用 type 字段：这里补齐成统一结构，便于校验与导入
-            if obj.get("type").is_none() {
-                if obj.contains_key("command") {
-                    obj.insert("type".to_string(), Value::String("stdio".to_string()));
-                } else if obj.contains_key("url") {
-                    obj.insert("type".to_string(), Value::String("sse".to_string()));
-                }
-            }
-        }
+    for spec in servers.values_mut() {
+        spec_from_gemini_format(spec);
     }
 
     Ok(servers)
@@ -95,73 +80,7 @@ pub fn set_mcp_servers_map(
     // 构建 mcpServers 对象：移除 UI 辅助字段（enabled/source），仅保留实际 MCP 规范
     let mut out: Map<String, Value> = Map::new();
     for (id, spec) in servers.iter() {
-        let mut obj = if let Some(map) = spec.as_object() {
-            map.clone()
-        } else {
-            return Err(AppError::McpValidation(format!(
-                "MCP 服务器 '{id}' 不是对象"
-            )));
-        };
-
-        // 提取 server 字段（如果存在）
-        if let Some(server_val) = obj.remove("server") {
-            let server_obj = server_val.as_object().cloned().ok_or_else(|| {
-                AppError::McpValidation(format!("MCP 服务器 '{id}' server 字段不是对象"))
-            })?;
-            obj = server_obj;
-        }
-
-        // Gemini CLI 格式转换：
-        // - Gemini 不使用 "type" 字段（从字段名推断传输类型）
-        // - HTTP 使用 "httpUrl" 字段，SSE 使用 "url" 字段
-        let transport_type = obj.get("type").and_then(|v| v.as_str());
-        if transport_type == Some("http") {
-            // HTTP streaming: 将 "url" 重命名为 "httpUrl"
-            if let Some(url_value) = obj.remove("url") {
-                obj.insert("httpUrl".to_string(), url_value);
-            }
-        }
-        // SSE 保持 "url" 字段不变
-
-        // 移除 UI 辅助字段和 type 字段（Gemini 不需要）
-        obj.remove("type");
-        obj.remove("enabled");
-        obj.remove("source");
-        obj.remove("id");
-        obj.remove("name");
-        obj.remove("description");
-        obj.remove("tags");
-        obj.remove("homepage");
-        obj.remove("docs");
-
-        // Timeout 转换：Claude/Codex 使用 startup_timeout_sec/tool_timeout_sec
-        // Gemini CLI 只支持 timeout（单位 ms）
-        // 默认值：startup=10s, tool=60s
-        const DEFAULT_STARTUP_MS: u64 = 10_000;
-        const DEFAULT_TOOL_MS: u64 = 60_000;
-
-        let extract_timeout =
-            |obj: &mut Map<String, Value>, key: &str, multiplier: u64| -> Option<u64> {
-                obj.remove(key).and_then(|val| {
-                    val.as_u64()
-                        .map(|n| n * multiplier)
-                        .or_else(|| val.as_f64().map(|f| (f * multiplier as f64) as u64))
-                })
-            };
-
-        // 分别收集 startup 和 tool timeout，未设置时使用默认值
-        let startup_ms = extract_timeout(&mut obj, "startup_timeout_sec", 1000)
-            .or_else(|| extract_timeout(&mut obj, "startup_timeout_ms", 1))
-            .unwrap_or(DEFAULT_STARTUP_MS);
-        let tool_ms = extract_timeout(&mut obj, "tool_timeout_sec", 1000)
-            .or_else(|| extract_timeout(&mut obj, "tool_timeout_ms", 1))
-            .unwrap_or(DEFAULT_TOOL_MS);
-
-        // 取最大值作为 Gemini timeout
-        let final_timeout = startup_ms.max(tool_ms);
-        obj.insert("timeout".to_string(), Value::Number(final_timeout.into()));
-
-        out.insert(id.clone(), Value::Object(obj));
+        out.insert(id.clone(), spec_to_gemini_format(id, spec)?);
     }
 
     {
@@ -175,3 +94,99 @@ pub fn set_mcp_servers_map(
     Ok(())
 }
 
+/// Gemini 格式 → 统一 MCP 格式（原地转换）
+///
+/// - httpUrl → url + type: "http"
+/// - 仅有 url 字段 → 补齐 type: "sse"（Gemini 以字段名推断传输类型）
+/// - 仅有 command 字段 → 补齐 type: "stdio"
+///
+/// Qwen Code 沿用 Gemini CLI 的 settings.json 格式，同样使用该转换。
+pub(crate) fn spec_from_gemini_format(spec: &mut Value) {
+    if let Some(obj) = spec.as_object_mut() {
+        // httpUrl → url + type: "http"
+        if let Some(http_url) = obj.remove("httpUrl") {
+            obj.insert("url".to_string(), http_url);
+            obj.insert("type".to_string(), Value::String("http".to_string()));
+        }
+
+        // Gemini CLI 不使用 type 字段：这里补齐成统一结构，便于校验与导入
+        if obj.get("type").is_none() {
+            if obj.contains_key("command") {
+                obj.insert("type".to_string(), Value::String("stdio".to_string()));
+            } else if obj.contains_key("url") {
+                obj.insert("type".to_string(), Value::String("sse".to_string()));
+            }
+        }
+    }
+}
+
+/// 统一 MCP 格式 → Gemini 格式
+pub(crate) fn spec_to_gemini_format(id: &str, spec: &Value) -> Result<Value, AppError> {
+    let mut obj = if let Some(map) = spec.as_object() {
+        map.clone()
+    } else {
+        return Err(AppError::McpValidation(format!(
+            "MCP 服务器 '{id}' 不是对象"
+        )));
+    };
+
+    // 提取 server 字段（如果存在）
+    if let Some(server_val) = obj.remove("server") {
+        let server_obj = server_val.as_object().cloned().ok_or_else(|| {
+            AppError::McpValidation(format!("MCP 服务器 '{id}' server 字段不是对象"))
+        })?;
+        obj = server_obj;
+    }
+
+    // Gemini CLI 格式转换：
+    // - Gemini 不使用 "type" 字段（从字段名推断传输类型）
+    // - HTTP 使用 "httpUrl" 字段，SSE 使用 "url" 字段
+    let transport_type = obj.get("type").and_then(|v| v.as_str());
+    if transport_type == Some("http") {
+        // HTTP streaming: 将 "url" 重命名为 "httpUrl"
+        if let Some(url_value) = obj.remove("url") {
+            obj.insert("httpUrl".to_string(), url_value);
+        }
+    }
+    // SSE 保持 "url" 字段不变
+
+    // 移除 UI 辅助字段和 type 字段（Gemini 不需要）
+    obj.remove("type");
+    obj.remove("enabled");
+    obj.remove("source");
+    obj.remove("id");
+    obj.remove("name");
+    obj.remove("description");
+    obj.remove("tags");
+    obj.remove("homepage");
+    obj.remove("docs");
+
+    // Timeout 转换：Claude/Codex 使用 startup_timeout_sec/tool_timeout_sec
+    // Gemini CLI 只支持 timeout（单位 ms）
+    // 默认值：startup=10s, tool=60s
+    const DEFAULT_STARTUP_MS: u64 = 10_000;
+    const DEFAULT_TOOL_MS: u64 = 60_000;
+
+    let extract_timeout =
+        |obj: &mut Map<String, Value>, key: &str, multiplier: u64| -> Option<u64> {
+            obj.remove(key).and_then(|val| {
+                val.as_u64()
+                    .map(|n| n * multiplier)
+                    .or_else(|| val.as_f64().map(|f| (f * multiplier as f64) as u64))
+            })
+        };
+
+    // 分别收集 startup 和 tool timeout，未设置时使用默认值
+    let startup_ms = extract_timeout(&mut obj, "startup_timeout_sec", 1000)
+        .or_else(|| extract_timeout(&mut obj, "startup_timeout_ms", 1))
+        .unwrap_or(DEFAULT_STARTUP_MS);
+    let tool_ms = extract_timeout(&mut obj, "tool_timeout_sec", 1000)
+        .or_else(|| extract_timeout(&mut obj, "tool_timeout_ms", 1))
+        .unwrap_or(DEFAULT_TOOL_MS);
+
+    // 取最大值作为 Gemini timeout
+    let final_timeout = startup_ms.max(tool_ms);
+    obj.insert("timeout".to_string(), Value::Number(final_timeout.into()));
+
+    Ok(Value::Object(obj))
+}
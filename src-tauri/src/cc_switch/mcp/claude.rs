@@ -93,6 +93,7 @@ pub fn import_from_claude(config: &mut MultiAppConfig) -> Result<usize, AppError
                         gemini: false,
                         opencode: false,
                         cursor: false,
+                        qwen: false,
                     },
                     description: None,
                     homepage: None,
@@ -167,6 +167,7 @@ pub fn import_from_cursor(config: &mut MultiAppConfig) -> Result<usize, AppError
                         gemini: false,
                         opencode: false,
                         cursor: true,
+                        qwen: false,
                     },
                     description: None,
                     homepage: None,
@@ -89,6 +89,7 @@ pub fn import_from_gemini(config: &mut MultiAppConfig) -> Result<usize, AppError
                         gemini: true,
                         opencode: false,
                         cursor: false,
+                        qwen: false,
                     },
                     description: None,
                     homepage: None,
@@ -4,6 +4,7 @@
 
 pub mod app_config;
 pub mod app_store;
+pub mod app_target;
 pub mod auto_launch;
 pub mod claude_mcp;
 pub mod claude_plugin;
@@ -22,12 +23,15 @@ pub mod panic_hook;
 pub mod path_migration;
 pub mod prompt;
 pub mod prompt_files;
+pub mod prompt_template;
 pub mod provider;
 pub mod provider_defaults;
 pub mod proxy;
+pub mod secrets;
 pub mod services;
 pub mod settings;
 pub mod store;
+pub mod text_diff;
 pub mod tray;
 pub mod usage_script;
 
@@ -9,8 +9,73 @@ pub struct Prompt {
     pub description: Option<String>,
     #[serde(default)]
     pub enabled: bool,
+    /// 是否为模板（启用时渲染变量与片段引用后再写入文件）
+    #[serde(default)]
+    pub templated: bool,
     #[serde(rename = "createdAt", skip_serializing_if = "Option::is_none")]
     pub created_at: Option<i64>,
     #[serde(rename = "updatedAt", skip_serializing_if = "Option::is_none")]
     pub updated_at: Option<i64>,
 }
+
+/// 可被提示词模板按 ID 引用的片段（跨应用共享）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptFragment {
+    pub id: String,
+    pub name: String,
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "createdAt", skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<i64>,
+    #[serde(rename = "updatedAt", skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<i64>,
+}
+
+/// 提示词历史版本
+///
+/// `prompt_id` 为空时表示 live 提示词文件（CLAUDE.md / AGENTS.md / GEMINI.md）的快照。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptVersion {
+    pub id: i64,
+    #[serde(rename = "appType")]
+    pub app_type: String,
+    #[serde(rename = "promptId", skip_serializing_if = "Option::is_none")]
+    pub prompt_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub content: String,
+    #[serde(default)]
+    pub templated: bool,
+    /// 版本来源，见 `PromptVersionSource`
+    pub source: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+}
+
+/// 提示词版本来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptVersionSource {
+    /// 在应用内保存
+    Save,
+    /// 从 live 文件导入
+    Import,
+    /// 应用写入 live 文件
+    Write,
+    /// 检测到 live 文件被外部修改（手动编辑等）
+    External,
+    /// 从历史版本恢复
+    Restore,
+}
+
+impl PromptVersionSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PromptVersionSource::Save => "save",
+            PromptVersionSource::Import => "import",
+            PromptVersionSource::Write => "write",
+            PromptVersionSource::External => "external",
+            PromptVersionSource::Restore => "restore",
+        }
+    }
+}
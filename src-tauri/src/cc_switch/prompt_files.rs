@@ -1,32 +1,43 @@
 use std::path::PathBuf;
 
 use crate::cc_switch::app_config::AppType;
+use crate::cc_switch::app_target::{not_ported, target_for};
 use crate::cc_switch::codex_config::get_codex_auth_path;
 use crate::cc_switch::config::get_claude_settings_path;
 use crate::cc_switch::error::AppError;
-use crate::cc_switch::gemini_config::get_gemini_dir;
 use crate::cc_switch::opencode_config::get_opencode_dir;
 
+/// 应用是否支持提示词文件（Cursor 不支持）
+pub fn supports_prompt_file(app: &AppType) -> bool {
+    !matches!(app, AppType::Cursor)
+}
+
+/// 支持提示词文件的全部应用
+pub fn prompt_apps() -> impl Iterator<Item = AppType> {
+    AppType::ALL.into_iter().filter(supports_prompt_file)
+}
+
 /// 返回指定应用所使用的提示词文件路径。
 pub fn prompt_file_path(app: &AppType) -> Result<PathBuf, AppError> {
-    let base_dir: PathBuf = match app {
-        AppType::Claude => get_base_dir_with_fallback(get_claude_settings_path(), ".claude")?,
-        AppType::Codex => get_base_dir_with_fallback(get_codex_auth_path(), ".codex")?,
-        AppType::Gemini => get_gemini_dir(),
-        AppType::OpenCode => get_opencode_dir(),
-        AppType::Cursor => {
-            return Err(AppError::Message("Cursor 不支持提示词功能".to_string()));
-        }
-    };
+    // 已迁移到 AppTarget 的应用直接由 trait 给出完整路径
+    if let Some(target) = target_for(app) {
+        return Ok(target.prompt_file_path());
+    }
 
-    let filename = match app {
-        AppType::Claude => "CLAUDE.md",
-        AppType::Codex => "AGENTS.md",
-        AppType::Gemini => "GEMINI.md",
-        AppType::OpenCode => "AGENTS.md",
+    let (base_dir, filename): (PathBuf, &str) = match app {
+        AppType::Claude => (
+            get_base_dir_with_fallback(get_claude_settings_path(), ".claude")?,
+            "CLAUDE.md",
+        ),
+        AppType::Codex => (
+            get_base_dir_with_fallback(get_codex_auth_path(), ".codex")?,
+            "AGENTS.md",
+        ),
+        AppType::OpenCode => (get_opencode_dir(), "AGENTS.md"),
         AppType::Cursor => {
             return Err(AppError::Message("Cursor 不支持提示词功能".to_string()));
         }
+        _ => return Err(not_ported(app)),
     };
 
     Ok(base_dir.join(filename))
@@ -0,0 +1,358 @@
+//! 提示词模板渲染
+//!
+//! 模板语法：
+//! - `{{ name }}`：变量，按「用户变量 > 内置变量」的优先级取值
+//! - `{{> fragment-id }}`：按 ID 引用片段，片段内可继续引用变量与其他片段
+//! - `\{{`：输出字面量 `{{`
+//!
+//! 渲染只依赖模板、片段与变量，不读取时间等易变信息，相同输入总是得到相同输出。
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::cc_switch::app_config::AppType;
+use crate::cc_switch::error::AppError;
+
+/// 片段嵌套引用的最大深度
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// 渲染上下文
+#[derive(Debug, Clone, Default)]
+pub struct TemplateContext {
+    /// 变量（已合并内置变量与用户变量）
+    pub variables: BTreeMap<String, String>,
+    /// 片段 ID -> 片段内容
+    pub fragments: HashMap<String, String>,
+}
+
+impl TemplateContext {
+    /// 构建指定应用的渲染上下文，用户变量覆盖同名内置变量
+    pub fn for_app(
+        app: &AppType,
+        user_variables: BTreeMap<String, String>,
+        fragments: HashMap<String, String>,
+    ) -> Self {
+        let mut variables = builtin_variables(app);
+        variables.extend(user_variables);
+        Self {
+            variables,
+            fragments,
+        }
+    }
+}
+
+/// 渲染结果（供预览与写入）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedPrompt {
+    pub content: String,
+    /// 引用到的片段（按首次出现顺序）
+    pub fragments: Vec<String>,
+    /// 实际使用的变量及其取值
+    pub variables: BTreeMap<String, String>,
+    /// 未定义的变量（预览时原样保留占位符）
+    pub missing_variables: Vec<String>,
+    /// 不存在的片段（预览时原样保留占位符）
+    pub missing_fragments: Vec<String>,
+}
+
+impl RenderedPrompt {
+    /// 是否所有变量与片段都已解析
+    pub fn is_complete(&self) -> bool {
+        self.missing_variables.is_empty() && self.missing_fragments.is_empty()
+    }
+
+    /// 写入文件前校验：存在未解析的变量或片段时报错
+    pub fn ensure_complete(&self) -> Result<(), AppError> {
+        if self.is_complete() {
+            return Ok(());
+        }
+        let mut missing = Vec::new();
+        if !self.missing_variables.is_empty() {
+            missing.push(format!("变量 {}", self.missing_variables.join(", ")));
+        }
+        if !self.missing_fragments.is_empty() {
+            missing.push(format!("片段 {}", self.missing_fragments.join(", ")));
+        }
+        Err(AppError::InvalidInput(format!(
+            "提示词模板存在未定义的{}",
+            missing.join("；")
+        )))
+    }
+}
+
+/// 内置变量
+///
+/// 仅包含同一台机器上稳定不变的信息，保证渲染结果可复现。
+pub fn builtin_variables(app: &AppType) -> BTreeMap<String, String> {
+    let mut variables = BTreeMap::new();
+    variables.insert("os".to_string(), std::env::consts::OS.to_string());
+    variables.insert("arch".to_string(), std::env::consts::ARCH.to_string());
+    variables.insert("app".to_string(), app.as_str().to_string());
+    variables
+}
+
+/// 校验变量名 / 片段 ID：字母开头，仅含字母、数字、`_`、`-`、`.`
+pub fn validate_identifier(kind: &str, name: &str) -> Result<(), AppError> {
+    let mut chars = name.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::InvalidInput(format!(
+            "{kind}名称无效: {name}（需以字母开头，仅包含字母、数字、_、-、.）"
+        )))
+    }
+}
+
+/// 列出模板直接引用的片段 ID（不展开嵌套）
+pub fn referenced_fragments(template: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let Ok(tokens) = tokenize(template) else {
+        return result;
+    };
+    for token in tokens {
+        if let Token::Include(id) = token {
+            if !result.contains(&id) {
+                result.push(id);
+            }
+        }
+    }
+    result
+}
+
+/// 渲染模板
+///
+/// 语法错误与循环引用返回错误；未定义的变量 / 片段记录在结果中，
+/// 由调用方决定是否允许（预览允许，写入文件前需 `ensure_complete`）。
+pub fn render(template: &str, ctx: &TemplateContext) -> Result<RenderedPrompt, AppError> {
+    let mut rendered = RenderedPrompt::default();
+    let mut stack = Vec::new();
+    let content = render_inner(template, ctx, &mut stack, &mut rendered)?;
+    rendered.content = content;
+    Ok(rendered)
+}
+
+fn render_inner(
+    template: &str,
+    ctx: &TemplateContext,
+    stack: &mut Vec<String>,
+    rendered: &mut RenderedPrompt,
+) -> Result<String, AppError> {
+    let mut output = String::with_capacity(template.len());
+
+    for token in tokenize(template)? {
+        match token {
+            Token::Text(text) => output.push_str(&text),
+            Token::Variable(name) => match ctx.variables.get(&name) {
+                Some(value) => {
+                    output.push_str(value);
+                    rendered.variables.insert(name, value.clone());
+                }
+                None => {
+                    output.push_str(&format!("{{{{{name}}}}}"));
+                    if !rendered.missing_variables.contains(&name) {
+                        rendered.missing_variables.push(name);
+                    }
+                }
+            },
+            Token::Include(id) => {
+                let Some(fragment) = ctx.fragments.get(&id) else {
+                    output.push_str(&format!("{{{{> {id}}}}}"));
+                    if !rendered.missing_fragments.contains(&id) {
+                        rendered.missing_fragments.push(id);
+                    }
+                    continue;
+                };
+                if stack.contains(&id) {
+                    let mut chain = stack.clone();
+                    chain.push(id);
+                    return Err(AppError::InvalidInput(format!(
+                        "提示词片段循环引用: {}",
+                        chain.join(" -> ")
+                    )));
+                }
+                if stack.len() >= MAX_INCLUDE_DEPTH {
+                    return Err(AppError::InvalidInput(format!(
+                        "提示词片段嵌套超过 {MAX_INCLUDE_DEPTH} 层"
+                    )));
+                }
+
+                if !rendered.fragments.contains(&id) {
+                    rendered.fragments.push(id.clone());
+                }
+                stack.push(id);
+                let expanded = render_inner(fragment, ctx, stack, rendered)?;
+                stack.pop();
+
+                // 去掉片段末尾的一个换行，避免 `{{> a}}\n{{> b}}` 产生多余空行
+                output.push_str(
+                    expanded
+                        .strip_suffix("\r\n")
+                        .or_else(|| expanded.strip_suffix('\n'))
+                        .unwrap_or(&expanded),
+                );
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+    Text(String),
+    Variable(String),
+    Include(String),
+}
+
+fn tokenize(template: &str) -> Result<Vec<Token>, AppError> {
+    let mut tokens = Vec::new();
+    let mut text = String::new();
+    let mut rest = template;
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("\\{{") {
+            text.push_str("{{");
+            rest = after;
+            continue;
+        }
+        let Some(after_open) = rest.strip_prefix("{{") else {
+            let mut chars = rest.chars();
+            if let Some(c) = chars.next() {
+                text.push(c);
+            }
+            rest = chars.as_str();
+            continue;
+        };
+
+        let line = template[..template.len() - rest.len()]
+            .matches('\n')
+            .count()
+            + 1;
+        let Some(end) = after_open.find("}}") else {
+            return Err(AppError::InvalidInput(format!(
+                "提示词模板第 {line} 行存在未闭合的 {{{{"
+            )));
+        };
+        let inner = after_open[..end].trim();
+        rest = &after_open[end + 2..];
+
+        let token = match inner.strip_prefix('>') {
+            Some(id) => {
+                let id = id.trim();
+                validate_identifier("片段", id).map_err(|_| {
+                    AppError::InvalidInput(format!(
+                        "提示词模板第 {line} 行的片段引用无效: {{{{{inner}}}}}"
+                    ))
+                })?;
+                Token::Include(id.to_string())
+            }
+            None => {
+                validate_identifier("变量", inner).map_err(|_| {
+                    AppError::InvalidInput(format!(
+                        "提示词模板第 {line} 行的变量无效: {{{{{inner}}}}}"
+                    ))
+                })?;
+                Token::Variable(inner.to_string())
+            }
+        };
+
+        if !text.is_empty() {
+            tokens.push(Token::Text(std::mem::take(&mut text)));
+        }
+        tokens.push(token);
+    }
+
+    if !text.is_empty() {
+        tokens.push(Token::Text(text));
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx(variables: &[(&str, &str)], fragments: &[(&str, &str)]) -> TemplateContext {
+        TemplateContext {
+            variables: variables
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            fragments: fragments
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn renders_variables_and_nested_fragments() {
+        let ctx = ctx(
+            &[("project_name", "mnemosyne"), ("os", "linux")],
+            &[
+                ("base", "# {{project_name}}\nRunning on {{ os }}.\n"),
+                ("team", "{{> base}}\nTeam rules.\n"),
+            ],
+        );
+
+        let rendered = render("{{> team}}\n\nDone.", &ctx).unwrap();
+
+        assert_eq!(
+            rendered.content,
+            "# mnemosyne\nRunning on linux.\nTeam rules.\n\nDone."
+        );
+        assert_eq!(rendered.fragments, vec!["team", "base"]);
+        assert_eq!(rendered.variables.len(), 2);
+        assert!(rendered.is_complete());
+    }
+
+    #[test]
+    fn missing_items_are_kept_as_placeholders() {
+        let rendered = render("Hi {{user}} {{> extra}}", &ctx(&[], &[])).unwrap();
+
+        assert_eq!(rendered.content, "Hi {{user}} {{> extra}}");
+        assert_eq!(rendered.missing_variables, vec!["user"]);
+        assert_eq!(rendered.missing_fragments, vec!["extra"]);
+        assert!(rendered.ensure_complete().is_err());
+    }
+
+    #[test]
+    fn escaped_braces_are_literal() {
+        let rendered = render(r"Use \{{name}} syntax", &ctx(&[], &[])).unwrap();
+        assert_eq!(rendered.content, "Use {{name}} syntax");
+        assert!(rendered.is_complete());
+    }
+
+    #[test]
+    fn include_cycles_are_rejected() {
+        let ctx = ctx(&[], &[("a", "{{> b}}"), ("b", "{{> a}}")]);
+        let err = render("{{> a}}", &ctx).unwrap_err().to_string();
+        assert!(err.contains("a -> b -> a"), "{err}");
+    }
+
+    #[test]
+    fn syntax_errors_report_line() {
+        let err = render("ok\n{{ bad name }}", &ctx(&[], &[]))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("第 2 行"), "{err}");
+
+        let err = render("{{unclosed", &ctx(&[], &[]))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("未闭合"), "{err}");
+    }
+
+    #[test]
+    fn user_variables_override_builtins() {
+        let mut user = BTreeMap::new();
+        user.insert("os".to_string(), "custom".to_string());
+        let ctx = TemplateContext::for_app(&AppType::Claude, user, HashMap::new());
+
+        let rendered = render("{{app}} on {{os}}", &ctx).unwrap();
+        assert_eq!(rendered.content, "claude on custom");
+    }
+}
@@ -81,6 +81,11 @@ impl FailoverSwitchManager {
         provider_id: &str,
         provider_name: &str,
     ) -> Result<bool, AppError> {
+        // OpenCode 为累加模式，没有“当前供应商”：故障转移只影响单次请求，不做切换
+        if app_type == "opencode" {
+            return Ok(false);
+        }
+
         // 检查该应用是否已被代理接管（enabled=true）
         // 只有被接管的应用才允许执行故障转移切换
         let app_enabled = match self.db.get_proxy_config_for_app(app_type).await {
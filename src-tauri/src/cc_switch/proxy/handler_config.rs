@@ -131,6 +131,30 @@ pub const GEMINI_PARSER_CONFIG: UsageParserConfig = UsageParserConfig {
     app_type_str: "gemini",
 };
 
+/// OpenCode（`@ai-sdk/anthropic` 供应商）解析配置
+pub const OPENCODE_ANTHROPIC_PARSER_CONFIG: UsageParserConfig = UsageParserConfig {
+    stream_parser: TokenUsage::from_claude_stream_events,
+    response_parser: TokenUsage::from_claude_response,
+    model_extractor: claude_model_extractor,
+    app_type_str: "opencode",
+};
+
+/// OpenCode（OpenAI 兼容供应商）解析配置，同时兼容 Chat Completions 与 Responses 格式
+pub const OPENCODE_OPENAI_PARSER_CONFIG: UsageParserConfig = UsageParserConfig {
+    stream_parser: TokenUsage::from_codex_stream_events_auto,
+    response_parser: TokenUsage::from_codex_response_auto,
+    model_extractor: codex_auto_model_extractor,
+    app_type_str: "opencode",
+};
+
+/// OpenCode（`@ai-sdk/google` 供应商）解析配置
+pub const OPENCODE_GEMINI_PARSER_CONFIG: UsageParserConfig = UsageParserConfig {
+    stream_parser: TokenUsage::from_gemini_stream_chunks,
+    response_parser: TokenUsage::from_gemini_response,
+    model_extractor: gemini_model_extractor,
+    app_type_str: "opencode",
+};
+
 // ============================================================================
 // Handler 配置（预留，用于进一步简化）
 // ============================================================================
@@ -7,6 +7,7 @@ use crate::cc_switch::provider::Provider;
 use crate::cc_switch::proxy::{
     extract_session_id,
     forwarder::RequestForwarder,
+    providers::opencode,
     server::ProxyState,
     types::{AppProxyConfig, RectifierConfig},
     ProxyError,
@@ -81,6 +82,40 @@ impl RequestContext {
         app_type: AppType,
         tag: &'static str,
         app_type_str: &'static str,
+    ) -> Result<Self, ProxyError> {
+        Self::build(state, body, headers, app_type, tag, app_type_str, None).await
+    }
+
+    /// 创建 OpenCode 请求上下文
+    ///
+    /// OpenCode 为累加模式，没有“当前供应商”；目标供应商由请求路径
+    /// `/opencode/<provider_id>/...` 指定，故障转移只会落到协议兼容的队列成员上。
+    pub async fn for_opencode_provider(
+        state: &ProxyState,
+        body: &serde_json::Value,
+        headers: &HeaderMap,
+        provider_id: &str,
+    ) -> Result<Self, ProxyError> {
+        Self::build(
+            state,
+            body,
+            headers,
+            AppType::OpenCode,
+            "OpenCode",
+            "opencode",
+            Some(provider_id),
+        )
+        .await
+    }
+
+    async fn build(
+        state: &ProxyState,
+        body: &serde_json::Value,
+        headers: &HeaderMap,
+        app_type: AppType,
+        tag: &'static str,
+        app_type_str: &'static str,
+        target_provider_id: Option<&str>,
     ) -> Result<Self, ProxyError> {
         let start_time = Instant::now();
 
@@ -94,8 +129,11 @@ impl RequestContext {
         // 从数据库读取整流器配置
         let rectifier_config = state.db.get_rectifier_config().unwrap_or_default();
 
-        let current_provider_id =
-            crate::cc_switch::settings::get_current_provider(&app_type).unwrap_or_default();
+        // 指定目标供应商时以它为“当前供应商”，故障转移到其它供应商才计为切换
+        let current_provider_id = match target_provider_id {
+            Some(id) => id.to_string(),
+            None => crate::cc_switch::settings::get_current_provider(&app_type).unwrap_or_default(),
+        };
 
         // 从请求体提取模型名称
         let request_model = body
@@ -118,17 +156,26 @@ impl RequestContext {
 
         // 使用共享的 ProviderRouter 选择 Provider（熔断器状态跨请求保持）
         // 注意：只在这里调用一次，结果传递给 forwarder，避免重复消耗 HalfOpen 名额
-        let providers = state
-            .provider_router
-            .select_providers(app_type_str)
-            .await
-            .map_err(|e| match e {
-                crate::cc_switch::error::AppError::AllProvidersCircuitOpen => {
-                    ProxyError::AllProvidersCircuitOpen
-                }
-                crate::cc_switch::error::AppError::NoProvidersConfigured => ProxyError::NoProvidersConfigured,
-                _ => ProxyError::DatabaseError(e.to_string()),
-            })?;
+        let selected = match target_provider_id {
+            Some(id) => {
+                state
+                    .provider_router
+                    .select_providers_for_target(app_type_str, id, |target, candidate| {
+                        opencode::is_failover_compatible(target, candidate, &request_model)
+                    })
+                    .await
+            }
+            None => state.provider_router.select_providers(app_type_str).await,
+        };
+        let providers = selected.map_err(|e| match e {
+            crate::cc_switch::error::AppError::AllProvidersCircuitOpen => {
+                ProxyError::AllProvidersCircuitOpen
+            }
+            crate::cc_switch::error::AppError::NoProvidersConfigured => {
+                ProxyError::NoProvidersConfigured
+            }
+            _ => ProxyError::DatabaseError(e.to_string()),
+        })?;
 
         let provider = providers
             .first()
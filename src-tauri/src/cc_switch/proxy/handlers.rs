@@ -13,7 +13,9 @@ use super::{
         CLAUDE_PARSER_CONFIG, CODEX_PARSER_CONFIG, GEMINI_PARSER_CONFIG, OPENAI_PARSER_CONFIG,
     },
     handler_context::RequestContext,
-    providers::{get_adapter, streaming::create_anthropic_sse_stream, transform},
+    providers::{
+        get_adapter, opencode::OpenCodeProtocol, streaming::create_anthropic_sse_stream, transform,
+    },
     response_processor::{create_logged_passthrough_stream, process_response, SseUsageCollector},
     server::ProxyState,
     types::*,
@@ -21,7 +23,12 @@ use super::{
     ProxyError,
 };
 use crate::cc_switch::app_config::AppType;
-use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
 use rust_decimal::Decimal;
 use serde_json::{json, Value};
 use std::str::FromStr;
@@ -401,6 +408,68 @@ pub async fn handle_gemini(
     process_response(response, &ctx, &state, &GEMINI_PARSER_CONFIG).await
 }
 
+// ============================================================================
+// OpenCode 处理器
+// ============================================================================
+
+/// 处理 /opencode/{provider_id}/{*path} 请求（透传到该供应商的 baseURL）
+///
+/// 协议由供应商的 `npm` 字段决定，路径与请求体原样转发；
+/// 使用量按实际命中的供应商协议解析，并以 `opencode` 记账。
+pub async fn handle_opencode(
+    State(state): State<ProxyState>,
+    Path((provider_id, path)): Path<(String, String)>,
+    uri: axum::http::Uri,
+    headers: axum::http::HeaderMap,
+    Json(body): Json<Value>,
+) -> Result<axum::response::Response, ProxyError> {
+    let mut ctx =
+        RequestContext::for_opencode_provider(&state, &body, &headers, &provider_id).await?;
+
+    // Gemini 协议的模型名称在 URI 中
+    if OpenCodeProtocol::of(&ctx.provider) == OpenCodeProtocol::Google {
+        ctx = ctx.with_model_from_uri(&uri);
+    }
+
+    let endpoint = match uri.query() {
+        Some(query) => format!("/{path}?{query}"),
+        None => format!("/{path}"),
+    };
+
+    let is_stream = body
+        .get("stream")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+        || path.contains(":streamGenerateContent");
+
+    let forwarder = ctx.create_forwarder(&state);
+    let result = match forwarder
+        .forward_with_retry(
+            &AppType::OpenCode,
+            &endpoint,
+            body,
+            headers,
+            ctx.get_providers(),
+        )
+        .await
+    {
+        Ok(result) => result,
+        Err(mut err) => {
+            if let Some(provider) = err.provider.take() {
+                ctx.provider = provider;
+            }
+            log_forward_error(&state, &ctx, is_stream, &err.error);
+            return Err(err.error);
+        }
+    };
+
+    ctx.provider = result.provider;
+    let response = result.response;
+    let parser_config = OpenCodeProtocol::of(&ctx.provider).parser_config();
+
+    process_response(response, &ctx, &state, parser_config).await
+}
+
 // ============================================================================
 // 使用量记录（保留用于 Claude 转换逻辑）
 // ============================================================================
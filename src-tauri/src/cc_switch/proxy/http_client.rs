@@ -5,7 +5,7 @@
 
 use crate::cc_switch::provider::ProviderProxyConfig;
 use once_cell::sync::OnceCell;
-use reqwest::Client;
+use reqwest::{Client, ClientBuilder};
 use std::env;
 use std::net::IpAddr;
 use std::sync::RwLock;
@@ -186,8 +186,26 @@ pub fn is_proxy_enabled() -> bool {
     get_current_proxy_url().is_some()
 }
 
+/// 获取不自动跟随重定向的 HTTP 客户端
+///
+/// 代理配置与全局客户端一致，供需要逐跳校验重定向地址的调用方使用。
+pub fn get_without_redirects() -> Result<Client, String> {
+    let proxy_url = get_current_proxy_url();
+    client_builder(proxy_url.as_deref())?
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {e}"))
+}
+
 /// 构建 HTTP 客户端
 fn build_client(proxy_url: Option<&str>) -> Result<Client, String> {
+    client_builder(proxy_url)?
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {e}"))
+}
+
+/// 创建带代理配置的客户端构建器
+fn client_builder(proxy_url: Option<&str>) -> Result<ClientBuilder, String> {
     let mut builder = Client::builder()
         .timeout(Duration::from_secs(600))
         .connect_timeout(Duration::from_secs(30))
@@ -226,9 +244,7 @@ fn build_client(proxy_url: Option<&str>) -> Result<Client, String> {
         }
     }
 
-    builder
-        .build()
-        .map_err(|e| format!("Failed to build HTTP client: {e}"))
+    Ok(builder)
 }
 
 fn system_proxy_points_to_loopback() -> bool {
@@ -108,6 +108,68 @@ impl ProviderRouter {
         Ok(result)
     }
 
+    /// 选择指定目标供应商的故障转移链（用于 OpenCode 等无“当前供应商”的累加模式应用）
+    ///
+    /// 请求路径已指明目标供应商，因此：
+    /// - 故障转移关闭时：仅返回目标供应商
+    /// - 故障转移开启时：目标供应商优先，其后按队列顺序追加 `is_compatible` 认可的供应商
+    pub async fn select_providers_for_target(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+        is_compatible: impl Fn(&Provider, &Provider) -> bool,
+    ) -> Result<Vec<Provider>, AppError> {
+        let Some(target) = self.db.get_provider_by_id(provider_id, app_type)? else {
+            log::warn!("[{app_type}] [FO-005] 目标供应商 {provider_id} 不存在");
+            return Err(AppError::NoProvidersConfigured);
+        };
+
+        let auto_failover_enabled = match self.db.get_proxy_config_for_app(app_type).await {
+            Ok(config) => config.auto_failover_enabled,
+            Err(e) => {
+                log::error!("[{app_type}] 读取 proxy_config 失败: {e}，默认禁用故障转移");
+                false
+            }
+        };
+
+        if !auto_failover_enabled {
+            return Ok(vec![target]);
+        }
+
+        let all_providers = self.db.get_all_providers(app_type)?;
+        let mut candidates = vec![target.clone()];
+        for item in self.db.get_failover_queue(app_type)? {
+            if item.provider_id == target.id {
+                continue;
+            }
+            if let Some(provider) = all_providers.get(&item.provider_id) {
+                if is_compatible(&target, provider) {
+                    candidates.push(provider.clone());
+                }
+            }
+        }
+
+        let mut result = Vec::new();
+        for provider in candidates {
+            let circuit_key = format!("{app_type}:{}", provider.id);
+            if self
+                .get_or_create_circuit_breaker(&circuit_key)
+                .await
+                .is_available()
+                .await
+            {
+                result.push(provider);
+            }
+        }
+
+        if result.is_empty() {
+            log::warn!("[{app_type}] [FO-004] 目标供应商 {provider_id} 及其备选均已熔断");
+            return Err(AppError::AllProvidersCircuitOpen);
+        }
+
+        Ok(result)
+    }
+
     /// 请求执行前获取熔断器“放行许可”
     ///
     /// - Closed：直接放行
@@ -405,6 +467,52 @@ mod tests {
         assert_eq!(providers[0].id, "b");
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_select_providers_for_target_puts_target_first_and_filters_queue() {
+        let _home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+
+        let anthropic = json!({ "npm": "@ai-sdk/anthropic" });
+        let openai = json!({ "npm": "@ai-sdk/openai-compatible" });
+        for (id, config) in [("a", &anthropic), ("b", &openai), ("c", &anthropic)] {
+            let provider = Provider::with_id(id.to_string(), id.to_string(), config.clone(), None);
+            db.save_provider("opencode", &provider).unwrap();
+        }
+        db.add_to_failover_queue("opencode", "b").unwrap();
+        db.add_to_failover_queue("opencode", "c").unwrap();
+        db.add_to_failover_queue("opencode", "a").unwrap();
+
+        let same_npm =
+            |t: &Provider, p: &Provider| t.settings_config["npm"] == p.settings_config["npm"];
+        let router = ProviderRouter::new(db.clone());
+
+        // 故障转移关闭：仅目标供应商
+        let providers = router
+            .select_providers_for_target("opencode", "a", same_npm)
+            .await
+            .unwrap();
+        assert_eq!(providers.len(), 1);
+        assert_eq!(providers[0].id, "a");
+
+        let mut config = db.get_proxy_config_for_app("opencode").await.unwrap();
+        config.auto_failover_enabled = true;
+        db.update_proxy_config_for_app(config).await.unwrap();
+
+        // 故障转移开启：目标优先，仅追加协议兼容的队列成员
+        let providers = router
+            .select_providers_for_target("opencode", "a", same_npm)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = providers.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "c"]);
+
+        assert!(router
+            .select_providers_for_target("opencode", "missing", same_npm)
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_select_providers_does_not_consume_half_open_permit() {
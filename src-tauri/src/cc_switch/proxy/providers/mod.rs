@@ -8,6 +8,7 @@
 //! - `claude`: Claude (Anthropic) 适配器
 //! - `codex`: Codex (OpenAI) 适配器
 //! - `gemini`: Gemini (Google) 适配器
+//! - `opencode`: OpenCode 适配器（按供应商 npm 包区分协议）
 //! - `models`: API 数据模型
 //! - `transform`: 格式转换
 
@@ -17,6 +18,7 @@ mod claude;
 mod codex;
 mod gemini;
 pub mod models;
+pub mod opencode;
 pub mod streaming;
 pub mod transform;
 
@@ -30,6 +32,7 @@ pub use auth::{AuthInfo, AuthStrategy};
 pub use claude::ClaudeAdapter;
 pub use codex::CodexAdapter;
 pub use gemini::GeminiAdapter;
+pub use opencode::OpenCodeAdapter;
 
 /// 供应商类型枚举
 ///
@@ -132,14 +135,19 @@ impl ProviderType {
                 }
                 ProviderType::Gemini
             }
-            AppType::OpenCode => {
-                // OpenCode doesn't support proxy, but return a default type for completeness
-                ProviderType::Codex // Fallback to Codex-like type
-            }
+            AppType::OpenCode => match opencode::OpenCodeProtocol::of(provider) {
+                opencode::OpenCodeProtocol::Anthropic => ProviderType::Claude,
+                opencode::OpenCodeProtocol::Google => ProviderType::Gemini,
+                opencode::OpenCodeProtocol::OpenAi => ProviderType::Codex,
+            },
             AppType::Cursor => {
                 // Cursor doesn't support proxy, fallback to Claude-like type
                 ProviderType::Claude
             }
+            AppType::Qwen => {
+                // Qwen Code doesn't support proxy, fallback to Codex type (OpenAI-compatible)
+                ProviderType::Codex
+            }
         }
     }
 
@@ -184,14 +192,15 @@ pub fn get_adapter(app_type: &AppType) -> Box<dyn ProviderAdapter> {
         AppType::Claude => Box::new(ClaudeAdapter::new()),
         AppType::Codex => Box::new(CodexAdapter::new()),
         AppType::Gemini => Box::new(GeminiAdapter::new()),
-        AppType::OpenCode => {
-            // OpenCode doesn't support proxy, fallback to Codex adapter
-            Box::new(CodexAdapter::new())
-        }
+        AppType::OpenCode => Box::new(OpenCodeAdapter::new()),
         AppType::Cursor => {
             // Cursor doesn't support proxy, fallback to Claude adapter
             Box::new(ClaudeAdapter::new())
         }
+        AppType::Qwen => {
+            // Qwen Code doesn't support proxy, fallback to Claude adapter
+            Box::new(ClaudeAdapter::new())
+        }
     }
 }
 
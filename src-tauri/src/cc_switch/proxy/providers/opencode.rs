@@ -0,0 +1,276 @@
+//! OpenCode Provider Adapter
+//!
+//! OpenCode 的每个供应商都带有 `npm` 字段（AI SDK 包名），决定上游协议：
+//! - `@ai-sdk/anthropic` → Anthropic Messages API
+//! - `@ai-sdk/google` → Gemini API
+//! - 其他（`@ai-sdk/openai`、`@ai-sdk/openai-compatible` 等）→ OpenAI 兼容 API
+//!
+//! 接管后 OpenCode 请求 `http://<proxy>/opencode/<provider_id>/<path>`，
+//! 代理将 `<path>` 原样拼接到该供应商真实的 `options.baseURL` 之后。
+
+use super::{AuthInfo, AuthStrategy, ProviderAdapter};
+use crate::cc_switch::provider::Provider;
+use crate::cc_switch::proxy::error::ProxyError;
+use crate::cc_switch::proxy::handler_config::{
+    UsageParserConfig, OPENCODE_ANTHROPIC_PARSER_CONFIG, OPENCODE_GEMINI_PARSER_CONFIG,
+    OPENCODE_OPENAI_PARSER_CONFIG,
+};
+use reqwest::RequestBuilder;
+
+/// 接管占位符（与 services::proxy 中写入 Live 的值一致）
+const PROXY_TOKEN_PLACEHOLDER: &str = "PROXY_MANAGED";
+
+/// OpenCode 供应商的上游协议
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenCodeProtocol {
+    Anthropic,
+    Google,
+    OpenAi,
+}
+
+impl OpenCodeProtocol {
+    /// 根据供应商的 `npm` 字段判断协议
+    pub fn of(provider: &Provider) -> Self {
+        let npm = provider
+            .settings_config
+            .get("npm")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+
+        if npm.starts_with("@ai-sdk/anthropic") {
+            Self::Anthropic
+        } else if npm.starts_with("@ai-sdk/google") {
+            Self::Google
+        } else {
+            Self::OpenAi
+        }
+    }
+
+    /// 未配置 baseURL 时 AI SDK 使用的默认地址
+    fn default_base_url(&self) -> Option<&'static str> {
+        match self {
+            Self::Anthropic => Some("https://api.anthropic.com/v1"),
+            Self::Google => Some("https://generativelanguage.googleapis.com/v1beta"),
+            Self::OpenAi => None,
+        }
+    }
+
+    /// 对应的使用量解析配置
+    pub fn parser_config(&self) -> &'static UsageParserConfig {
+        match self {
+            Self::Anthropic => &OPENCODE_ANTHROPIC_PARSER_CONFIG,
+            Self::Google => &OPENCODE_GEMINI_PARSER_CONFIG,
+            Self::OpenAi => &OPENCODE_OPENAI_PARSER_CONFIG,
+        }
+    }
+}
+
+/// 判断 `candidate` 能否作为 `requested` 的故障转移目标
+///
+/// OpenCode 的请求体和路径都是按目标供应商的协议构造的，因此只能在
+/// 相同 `npm` 包之间转移；若候选供应商声明了 `models`，还要求包含请求的模型。
+pub fn is_failover_compatible(requested: &Provider, candidate: &Provider, model: &str) -> bool {
+    let npm = |p: &Provider| {
+        p.settings_config
+            .get("npm")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+    if npm(requested) != npm(candidate) {
+        return false;
+    }
+
+    match candidate
+        .settings_config
+        .get("models")
+        .and_then(|v| v.as_object())
+    {
+        Some(models) if !models.is_empty() => models.contains_key(model),
+        _ => true,
+    }
+}
+
+/// OpenCode 适配器
+pub struct OpenCodeAdapter;
+
+impl OpenCodeAdapter {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// 解析 OpenCode 的 `{env:VAR}` 写法
+    fn resolve_env_reference(value: &str) -> Option<String> {
+        match value
+            .strip_prefix("{env:")
+            .and_then(|rest| rest.strip_suffix('}'))
+        {
+            Some(var) => std::env::var(var.trim()).ok(),
+            None => Some(value.to_string()),
+        }
+    }
+}
+
+impl Default for OpenCodeAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProviderAdapter for OpenCodeAdapter {
+    fn name(&self) -> &'static str {
+        "OpenCode"
+    }
+
+    fn extract_base_url(&self, provider: &Provider) -> Result<String, ProxyError> {
+        if let Some(url) = provider
+            .settings_config
+            .pointer("/options/baseURL")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.trim().is_empty())
+        {
+            return Ok(url.trim().trim_end_matches('/').to_string());
+        }
+
+        OpenCodeProtocol::of(provider)
+            .default_base_url()
+            .map(str::to_string)
+            .ok_or_else(|| {
+                ProxyError::ConfigError("OpenCode Provider 缺少 options.baseURL 配置".to_string())
+            })
+    }
+
+    fn extract_auth(&self, provider: &Provider) -> Option<AuthInfo> {
+        let key = provider
+            .settings_config
+            .pointer("/options/apiKey")
+            .and_then(|v| v.as_str())
+            .and_then(Self::resolve_env_reference)
+            .filter(|k| !k.trim().is_empty() && k != PROXY_TOKEN_PLACEHOLDER)?;
+
+        let strategy = match OpenCodeProtocol::of(provider) {
+            OpenCodeProtocol::Anthropic => AuthStrategy::Anthropic,
+            OpenCodeProtocol::Google => AuthStrategy::Google,
+            OpenCodeProtocol::OpenAi => AuthStrategy::Bearer,
+        };
+        Some(AuthInfo::new(key, strategy))
+    }
+
+    fn build_url(&self, base_url: &str, endpoint: &str) -> String {
+        format!(
+            "{}/{}",
+            base_url.trim_end_matches('/'),
+            endpoint.trim_start_matches('/')
+        )
+    }
+
+    fn add_auth_headers(&self, request: RequestBuilder, auth: &AuthInfo) -> RequestBuilder {
+        match auth.strategy {
+            AuthStrategy::Anthropic => request.header("x-api-key", &auth.api_key),
+            AuthStrategy::Google => request.header("x-goog-api-key", &auth.api_key),
+            _ => request.header("Authorization", format!("Bearer {}", auth.api_key)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn create_provider(config: serde_json::Value) -> Provider {
+        Provider {
+            id: "test".to_string(),
+            name: "Test OpenCode".to_string(),
+            settings_config: config,
+            website_url: None,
+            category: None,
+            created_at: None,
+            sort_index: None,
+            notes: None,
+            meta: None,
+            icon: None,
+            icon_color: None,
+            in_failover_queue: false,
+        }
+    }
+
+    #[test]
+    fn test_extract_base_url_and_auth_by_npm() {
+        let adapter = OpenCodeAdapter::new();
+        let provider = create_provider(json!({
+            "npm": "@ai-sdk/anthropic",
+            "options": { "apiKey": "sk-ant-12345678" }
+        }));
+
+        assert_eq!(
+            adapter.extract_base_url(&provider).unwrap(),
+            "https://api.anthropic.com/v1"
+        );
+        let auth = adapter.extract_auth(&provider).unwrap();
+        assert_eq!(auth.strategy, AuthStrategy::Anthropic);
+
+        let provider = create_provider(json!({
+            "npm": "@ai-sdk/openai-compatible",
+            "options": { "baseURL": "https://relay.example.com/v1/", "apiKey": "sk-1" }
+        }));
+        assert_eq!(
+            adapter.extract_base_url(&provider).unwrap(),
+            "https://relay.example.com/v1"
+        );
+        assert_eq!(
+            adapter.extract_auth(&provider).unwrap().strategy,
+            AuthStrategy::Bearer
+        );
+    }
+
+    #[test]
+    fn test_openai_compatible_requires_base_url() {
+        let adapter = OpenCodeAdapter::new();
+        let provider = create_provider(json!({ "npm": "@ai-sdk/openai-compatible" }));
+        assert!(adapter.extract_base_url(&provider).is_err());
+    }
+
+    #[test]
+    fn test_placeholder_key_is_not_forwarded() {
+        let adapter = OpenCodeAdapter::new();
+        let provider = create_provider(json!({
+            "npm": "@ai-sdk/openai-compatible",
+            "options": { "baseURL": "https://x/v1", "apiKey": PROXY_TOKEN_PLACEHOLDER }
+        }));
+        assert!(adapter.extract_auth(&provider).is_none());
+    }
+
+    #[test]
+    fn test_build_url_appends_client_path() {
+        let adapter = OpenCodeAdapter::new();
+        assert_eq!(
+            adapter.build_url("https://relay.example.com/v1", "/chat/completions"),
+            "https://relay.example.com/v1/chat/completions"
+        );
+    }
+
+    #[test]
+    fn test_failover_compatibility() {
+        let requested = create_provider(json!({
+            "npm": "@ai-sdk/openai-compatible",
+            "models": { "glm-4.6": { "name": "GLM" } }
+        }));
+        let same_model = create_provider(json!({
+            "npm": "@ai-sdk/openai-compatible",
+            "models": { "glm-4.6": { "name": "GLM" } }
+        }));
+        let other_model = create_provider(json!({
+            "npm": "@ai-sdk/openai-compatible",
+            "models": { "kimi-k2": { "name": "Kimi" } }
+        }));
+        let other_protocol = create_provider(json!({ "npm": "@ai-sdk/anthropic" }));
+
+        assert!(is_failover_compatible(&requested, &same_model, "glm-4.6"));
+        assert!(!is_failover_compatible(&requested, &other_model, "glm-4.6"));
+        assert!(!is_failover_compatible(
+            &requested,
+            &other_protocol,
+            "glm-4.6"
+        ));
+    }
+}
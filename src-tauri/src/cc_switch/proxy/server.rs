@@ -237,6 +237,11 @@ impl ProxyServer {
             // Gemini API (支持带前缀和不带前缀)
             .route("/v1beta/*path", post(handlers::handle_gemini))
             .route("/gemini/v1beta/*path", post(handlers::handle_gemini))
+            // OpenCode（接管后 baseURL 形如 /opencode/<provider_id>，按供应商路由）
+            .route(
+                "/opencode/:provider_id/*path",
+                post(handlers::handle_opencode),
+            )
             // 提高默认请求体大小限制（避免 413 Payload Too Large）
             .layer(DefaultBodyLimit::max(200 * 1024 * 1024))
             .layer(cors)
@@ -113,6 +113,9 @@ pub struct ProxyTakeoverStatus {
     pub claude: bool,
     pub codex: bool,
     pub gemini: bool,
+    pub opencode: bool,
+    /// Cursor 没有可改写的供应商配置，始终为 false
+    pub cursor: bool,
 }
 
 /// API 格式类型（预留，当前不需要格式转换）
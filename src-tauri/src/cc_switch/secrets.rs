@@ -0,0 +1,274 @@
+//! 密钥处理工具
+//!
+//! - 识别配置 JSON 中的敏感字段（API Key、Token、密码等），支持移除与回填
+//! - 基于口令的加密：PBKDF2-HMAC-SHA256 派生密钥，AES-256-GCM 加密
+//!
+//! 供配置包导出、目录同步等需要把数据写出本机的场景使用。
+
+use base64::prelude::*;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::num::NonZeroU32;
+
+use crate::cc_switch::error::AppError;
+
+/// 默认 PBKDF2 迭代次数
+pub const DEFAULT_KDF_ITERATIONS: u32 = 200_000;
+
+/// 允许的最小迭代次数（防止被篡改的参数削弱加密强度）
+const MIN_KDF_ITERATIONS: u32 = 10_000;
+
+/// 盐长度（字节）
+const SALT_LEN: usize = 16;
+
+/// 敏感字段名后缀（字段名去掉非字母数字字符并转小写后匹配）
+const SECRET_KEY_SUFFIXES: &[&str] = &[
+    "apikey",
+    "token",
+    "secret",
+    "secretkey",
+    "accesskey",
+    "privatekey",
+    "password",
+    "authorization",
+    "cookie",
+];
+
+/// 判断字段名是否为敏感字段
+pub fn is_secret_key(key: &str) -> bool {
+    let normalized: String = key
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    SECRET_KEY_SUFFIXES
+        .iter()
+        .any(|suffix| normalized.ends_with(suffix))
+}
+
+/// 将所有敏感字段的非空字符串值置空，返回移除的数量
+pub fn strip_secrets(value: &mut Value) -> usize {
+    match value {
+        Value::Object(map) => map
+            .iter_mut()
+            .map(|(key, child)| match child {
+                Value::String(s) if is_secret_key(key) && !s.is_empty() => {
+                    s.clear();
+                    1
+                }
+                _ => strip_secrets(child),
+            })
+            .sum(),
+        Value::Array(items) => items.iter_mut().map(strip_secrets).sum(),
+        _ => 0,
+    }
+}
+
+/// 用已有配置中的值回填被置空的敏感字段，返回回填的数量
+///
+/// 导入已移除密钥的配置覆盖本机条目时使用，避免本机密钥丢失。
+pub fn restore_secrets(incoming: &mut Value, existing: &Value) -> usize {
+    match (incoming, existing) {
+        (Value::Object(map), Value::Object(existing_map)) => map
+            .iter_mut()
+            .map(|(key, child)| {
+                let Some(existing_child) = existing_map.get(key) else {
+                    return 0;
+                };
+                match (child, existing_child) {
+                    (Value::String(s), Value::String(old))
+                        if is_secret_key(key) && s.is_empty() && !old.is_empty() =>
+                    {
+                        *s = old.clone();
+                        1
+                    }
+                    (child, existing_child) => restore_secrets(child, existing_child),
+                }
+            })
+            .sum(),
+        (Value::Array(items), Value::Array(existing_items)) => items
+            .iter_mut()
+            .zip(existing_items)
+            .map(|(child, existing_child)| restore_secrets(child, existing_child))
+            .sum(),
+        _ => 0,
+    }
+}
+
+/// 是否存在被置空的敏感字段
+pub fn has_stripped_secrets(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => map.iter().any(|(key, child)| match child {
+            Value::String(s) => is_secret_key(key) && s.is_empty(),
+            _ => has_stripped_secrets(child),
+        }),
+        Value::Array(items) => items.iter().any(has_stripped_secrets),
+        _ => false,
+    }
+}
+
+/// 口令派生参数（随加密数据一起保存）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KdfParams {
+    /// 固定为 "pbkdf2-sha256"
+    pub algorithm: String,
+    pub iterations: u32,
+    /// Base64 编码的盐
+    pub salt: String,
+}
+
+impl KdfParams {
+    /// 生成随机盐的新参数
+    pub fn generate() -> Result<Self, AppError> {
+        let mut salt = [0u8; SALT_LEN];
+        SystemRandom::new()
+            .fill(&mut salt)
+            .map_err(|_| AppError::Message("生成随机盐失败".to_string()))?;
+        Ok(Self {
+            algorithm: "pbkdf2-sha256".to_string(),
+            iterations: DEFAULT_KDF_ITERATIONS,
+            salt: BASE64_STANDARD.encode(salt),
+        })
+    }
+}
+
+/// 由口令派生的 AES-256-GCM 密钥
+pub struct SecretKey {
+    key: LessSafeKey,
+}
+
+impl SecretKey {
+    /// 按参数从口令派生密钥
+    pub fn derive(passphrase: &str, params: &KdfParams) -> Result<Self, AppError> {
+        if passphrase.is_empty() {
+            return Err(AppError::InvalidInput("口令不能为空".to_string()));
+        }
+        if params.algorithm != "pbkdf2-sha256" {
+            return Err(AppError::InvalidInput(format!(
+                "不支持的密钥派生算法: {}",
+                params.algorithm
+            )));
+        }
+        let iterations = NonZeroU32::new(params.iterations)
+            .filter(|n| n.get() >= MIN_KDF_ITERATIONS)
+            .ok_or_else(|| {
+                AppError::InvalidInput(format!("密钥派生迭代次数无效: {}", params.iterations))
+            })?;
+        let salt = BASE64_STANDARD
+            .decode(&params.salt)
+            .map_err(|e| AppError::InvalidInput(format!("密钥派生盐无效: {e}")))?;
+
+        let mut key_bytes = [0u8; 32];
+        pbkdf2::derive(
+            pbkdf2::PBKDF2_HMAC_SHA256,
+            iterations,
+            &salt,
+            passphrase.as_bytes(),
+            &mut key_bytes,
+        );
+        let unbound = UnboundKey::new(&AES_256_GCM, &key_bytes)
+            .map_err(|_| AppError::Message("初始化加密密钥失败".to_string()))?;
+        Ok(Self {
+            key: LessSafeKey::new(unbound),
+        })
+    }
+
+    /// 加密，输出格式：nonce(12 字节) || 密文 || tag
+    ///
+    /// `aad` 为附加认证数据（如文件名），解密时必须一致，防止密文被挪用。
+    pub fn encrypt(&self, plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>, AppError> {
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| AppError::Message("生成随机数失败".to_string()))?;
+
+        let mut buffer = plaintext.to_vec();
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(aad),
+                &mut buffer,
+            )
+            .map_err(|_| AppError::Message("加密失败".to_string()))?;
+
+        let mut output = Vec::with_capacity(NONCE_LEN + buffer.len());
+        output.extend_from_slice(&nonce);
+        output.extend_from_slice(&buffer);
+        Ok(output)
+    }
+
+    /// 解密 `encrypt` 的输出；口令错误或数据被篡改时返回错误
+    pub fn decrypt(&self, data: &[u8], aad: &[u8]) -> Result<Vec<u8>, AppError> {
+        if data.len() < NONCE_LEN {
+            return Err(AppError::InvalidInput("加密数据已损坏".to_string()));
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce)
+            .map_err(|_| AppError::InvalidInput("加密数据已损坏".to_string()))?;
+
+        let mut buffer = ciphertext.to_vec();
+        let plaintext = self
+            .key
+            .open_in_place(nonce, Aad::from(aad), &mut buffer)
+            .map_err(|_| AppError::InvalidInput("口令错误或加密数据已损坏".to_string()))?;
+        Ok(plaintext.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn strip_and_restore_secrets() {
+        let original = json!({
+            "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-secret",
+                "ANTHROPIC_BASE_URL": "https://api.example.com",
+                "MAX_TOKENS": "4096"
+            },
+            "headers": [{ "Authorization": "Bearer abc" }],
+            "apiKey": ""
+        });
+
+        let mut stripped = original.clone();
+        assert_eq!(strip_secrets(&mut stripped), 2);
+        assert_eq!(stripped["env"]["ANTHROPIC_AUTH_TOKEN"], "");
+        assert_eq!(stripped["env"]["MAX_TOKENS"], "4096");
+        assert_eq!(stripped["headers"][0]["Authorization"], "");
+        assert!(has_stripped_secrets(&stripped));
+
+        assert_eq!(restore_secrets(&mut stripped, &original), 2);
+        assert_eq!(stripped, original);
+    }
+
+    #[test]
+    fn encrypt_round_trip_and_wrong_passphrase() {
+        let params = KdfParams {
+            iterations: MIN_KDF_ITERATIONS,
+            ..KdfParams::generate().unwrap()
+        };
+        let key = SecretKey::derive("correct horse", &params).unwrap();
+
+        let sealed = key.encrypt(b"payload", b"providers.json").unwrap();
+        assert_eq!(key.decrypt(&sealed, b"providers.json").unwrap(), b"payload");
+        assert!(key.decrypt(&sealed, b"other.json").is_err());
+
+        let wrong = SecretKey::derive("wrong", &params).unwrap();
+        assert!(wrong.decrypt(&sealed, b"providers.json").is_err());
+    }
+
+    #[test]
+    fn weak_kdf_params_are_rejected() {
+        let params = KdfParams {
+            iterations: 1,
+            ..KdfParams::generate().unwrap()
+        };
+        assert!(SecretKey::derive("pw", &params).is_err());
+    }
+}
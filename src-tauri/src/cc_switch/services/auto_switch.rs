@@ -0,0 +1,943 @@
+//! 供应商自动切换策略服务
+//!
+//! 按应用配置的自动切换策略：
+//! - 定时策略：按类 cron 表达式（分 时 日 月 周，本地时间）切换到指定供应商
+//! - 额度策略：当前供应商的剩余额度低于阈值时，按顺序切换到第一个额度充足的备用供应商；
+//!   剩余额度来自使用限额（`check_provider_limits`）或用量查询脚本
+//! - 开启了故障转移的应用不做自动切换，避免与故障转移争抢当前供应商
+//! - 每次切换记录到 provider_switch_logs 表，并发送系统通知
+
+use chrono::{Datelike, Duration as ChronoDuration, Local, NaiveDateTime, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::cc_switch::app_config::AppType;
+use crate::cc_switch::error::AppError;
+use crate::cc_switch::provider::UsageResult;
+use crate::cc_switch::services::{ProviderLimitStatus, ProviderService};
+use crate::cc_switch::store::AppState;
+
+/// 支持自动切换的应用（OpenCode 为累加模式，没有"当前供应商"）
+const AUTO_SWITCH_APPS: [AppType; 3] = [AppType::Claude, AppType::Codex, AppType::Gemini];
+
+/// 允许的最小额度检查间隔（秒）
+const MIN_QUOTA_INTERVAL_SECS: u64 = 60;
+
+/// 后台任务检查间隔（秒）
+const SCHEDULER_POLL_SECS: u64 = 20;
+
+/// 系统休眠唤醒后最多补触发的分钟数
+const MAX_CATCH_UP_MINUTES: i64 = 60;
+
+/// 预览定时策略时最多向后查找的天数
+const PREVIEW_SEARCH_DAYS: i64 = 366;
+
+/// 保留的切换历史条数
+const MAX_HISTORY: usize = 500;
+
+// ============================================================================
+// 类型定义
+// ============================================================================
+
+/// 自动切换配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AutoSwitchConfig {
+    /// 后台任务总开关
+    pub enabled: bool,
+    /// 额度策略的检查间隔（秒）
+    pub quota_check_interval_secs: u64,
+    pub policies: Vec<AutoSwitchPolicy>,
+}
+
+impl Default for AutoSwitchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            quota_check_interval_secs: 300,
+            policies: Vec::new(),
+        }
+    }
+}
+
+/// 单条自动切换策略
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoSwitchPolicy {
+    pub id: String,
+    pub name: String,
+    pub app_type: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub trigger: AutoSwitchTrigger,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 策略触发条件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum AutoSwitchTrigger {
+    /// 到点切换到指定供应商
+    Schedule { cron: String, provider_id: String },
+    /// 剩余额度低于阈值时切换到备用供应商
+    Quota {
+        source: QuotaSource,
+        /// 剩余额度阈值（限额来源为美元，脚本来源为脚本返回的单位）
+        threshold: f64,
+        /// 仅在该供应商为当前供应商时检查；为空表示检查任意当前供应商
+        #[serde(default)]
+        watch_provider_id: Option<String>,
+        /// 备用供应商，按顺序尝试
+        fallback_provider_ids: Vec<String>,
+    },
+}
+
+/// 剩余额度来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QuotaSource {
+    /// 供应商设置的每日 / 每月限额减去代理统计的花费
+    Limits,
+    /// 用量查询脚本返回的剩余额度
+    UsageScript,
+}
+
+/// 切换记录中的触发类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AutoSwitchTriggerKind {
+    Schedule,
+    Quota,
+}
+
+impl AutoSwitchTriggerKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Schedule => "schedule",
+            Self::Quota => "quota",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "quota" => Self::Quota,
+            _ => Self::Schedule,
+        }
+    }
+}
+
+/// 自动切换记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoSwitchRecord {
+    pub id: i64,
+    pub policy_id: String,
+    pub policy_name: String,
+    pub app_type: String,
+    pub trigger: AutoSwitchTriggerKind,
+    pub from_provider_id: Option<String>,
+    pub to_provider_id: String,
+    pub to_provider_name: String,
+    /// 触发说明（如 cron 表达式或剩余额度）
+    pub detail: String,
+    /// 切换时间（Unix 秒）
+    pub switched_at: i64,
+}
+
+/// 类 cron 时间表：`分 时 日 月 周`
+///
+/// 每个字段支持 `*`、数字、`a-b` 区间、`x/n` 步长与逗号列表；周日可写 0 或 7。
+/// 与 cron 一致，日与周同时受限时满足其一即可。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    day_restricted: bool,
+    weekday_restricted: bool,
+}
+
+impl FromStr for CronSchedule {
+    type Err = AppError;
+
+    fn from_str(expr: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(AppError::InvalidInput(format!(
+                "cron 表达式需包含 5 个字段（分 时 日 月 周）: {expr}"
+            )));
+        };
+
+        let mut weekdays = parse_cron_field(weekday, 0, 7, "周")?;
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            minutes: parse_cron_field(minute, 0, 59, "分钟")?,
+            hours: parse_cron_field(hour, 0, 23, "小时")?,
+            days: parse_cron_field(day, 1, 31, "日")?,
+            months: parse_cron_field(month, 1, 12, "月")?,
+            weekdays,
+            day_restricted: !day.starts_with('*'),
+            weekday_restricted: !weekday.starts_with('*'),
+        })
+    }
+}
+
+impl CronSchedule {
+    /// 给定时间（精确到分钟）是否命中
+    pub fn matches(&self, t: &NaiveDateTime) -> bool {
+        let hit = |mask: u64, value: u32| mask & (1 << value) != 0;
+        let day = hit(self.days, t.day());
+        let weekday = hit(self.weekdays, t.weekday().num_days_from_sunday());
+        let day_ok = if self.day_restricted && self.weekday_restricted {
+            day || weekday
+        } else {
+            day && weekday
+        };
+        hit(self.minutes, t.minute())
+            && hit(self.hours, t.hour())
+            && hit(self.months, t.month())
+            && day_ok
+    }
+
+    /// 之后（不含 `after` 所在分钟）的下一次触发时间
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let start = truncate_to_minute(after) + ChronoDuration::minutes(1);
+        let end = start + ChronoDuration::days(PREVIEW_SEARCH_DAYS);
+        let mut t = start;
+        while t < end {
+            if self.matches(&t) {
+                return Some(t);
+            }
+            t += ChronoDuration::minutes(1);
+        }
+        None
+    }
+}
+
+// ============================================================================
+// 服务
+// ============================================================================
+
+pub struct AutoSwitchService;
+
+impl AutoSwitchService {
+    /// 获取配置
+    pub fn get_config(state: &AppState) -> Result<AutoSwitchConfig, AppError> {
+        state.db.get_auto_switch_config()
+    }
+
+    /// 校验并保存配置
+    pub fn set_config(
+        state: &AppState,
+        config: AutoSwitchConfig,
+    ) -> Result<AutoSwitchConfig, AppError> {
+        if config.quota_check_interval_secs < MIN_QUOTA_INTERVAL_SECS {
+            return Err(AppError::InvalidInput(format!(
+                "额度检查间隔不能少于 {MIN_QUOTA_INTERVAL_SECS} 秒"
+            )));
+        }
+
+        let mut ids = HashSet::new();
+        for policy in &config.policies {
+            if policy.id.trim().is_empty() || !ids.insert(policy.id.as_str()) {
+                return Err(AppError::InvalidInput(format!(
+                    "策略 ID 为空或重复: '{}'",
+                    policy.id
+                )));
+            }
+            let app_type = parse_policy_app(policy)?;
+            let exists = |id: &str| -> Result<(), AppError> {
+                match state.db.get_provider_by_id(id, app_type.as_str())? {
+                    Some(_) => Ok(()),
+                    None => Err(AppError::InvalidInput(format!(
+                        "策略 {} 引用的供应商不存在: {id}",
+                        policy.name
+                    ))),
+                }
+            };
+
+            match &policy.trigger {
+                AutoSwitchTrigger::Schedule { cron, provider_id } => {
+                    CronSchedule::from_str(cron)?;
+                    exists(provider_id)?;
+                }
+                AutoSwitchTrigger::Quota {
+                    threshold,
+                    watch_provider_id,
+                    fallback_provider_ids,
+                    ..
+                } => {
+                    if !threshold.is_finite() || *threshold < 0.0 {
+                        return Err(AppError::InvalidInput(format!(
+                            "策略 {} 的额度阈值必须为非负数",
+                            policy.name
+                        )));
+                    }
+                    if fallback_provider_ids.is_empty() {
+                        return Err(AppError::InvalidInput(format!(
+                            "策略 {} 至少需要一个备用供应商",
+                            policy.name
+                        )));
+                    }
+                    if let Some(id) = watch_provider_id {
+                        exists(id)?;
+                    }
+                    for id in fallback_provider_ids {
+                        exists(id)?;
+                    }
+                }
+            }
+        }
+
+        state.db.set_auto_switch_config(&config)?;
+        Ok(config)
+    }
+
+    /// 获取切换历史（最新在前）
+    pub fn list_history(
+        state: &AppState,
+        app_type: Option<&str>,
+        policy_id: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<Vec<AutoSwitchRecord>, AppError> {
+        state.db.list_provider_switch_logs(
+            app_type,
+            policy_id,
+            limit.unwrap_or(50).min(MAX_HISTORY),
+        )
+    }
+
+    /// 预览 cron 表达式接下来的触发时间（本地时间，RFC 3339）
+    pub fn preview_schedule(cron: &str, count: usize) -> Result<Vec<String>, AppError> {
+        let schedule = CronSchedule::from_str(cron)?;
+        let mut result = Vec::new();
+        let mut t = Local::now().naive_local();
+        while result.len() < count.min(20) {
+            let Some(next) = schedule.next_after(t) else {
+                break;
+            };
+            if let Some(local) = next.and_local_timezone(Local).earliest() {
+                result.push(local.to_rfc3339());
+            }
+            t = next;
+        }
+        Ok(result)
+    }
+
+    /// 执行 (after, until] 时间段内到期的定时策略
+    pub async fn run_schedules(
+        app: &tauri::AppHandle,
+        config: &AutoSwitchConfig,
+        after: NaiveDateTime,
+        until: NaiveDateTime,
+    ) -> Result<Vec<AutoSwitchRecord>, AppError> {
+        let mut records = Vec::new();
+        for (policy, fired_at) in due_schedule_policies(&config.policies, after, until) {
+            let AutoSwitchTrigger::Schedule { cron, provider_id } = &policy.trigger else {
+                continue;
+            };
+            let detail = format!("{cron} @ {}", fired_at.format("%Y-%m-%d %H:%M"));
+            match Self::switch_to(
+                app,
+                policy,
+                AutoSwitchTriggerKind::Schedule,
+                provider_id,
+                detail,
+            )
+            .await
+            {
+                Ok(Some(record)) => records.push(record),
+                Ok(None) => {}
+                Err(e) => log::warn!("定时切换策略 {} 执行失败: {e}", policy.name),
+            }
+        }
+        Ok(records)
+    }
+
+    /// 检查所有额度策略，返回本轮发生的切换
+    pub async fn run_quota_checks(
+        app: &tauri::AppHandle,
+    ) -> Result<Vec<AutoSwitchRecord>, AppError> {
+        let config = app.state::<AppState>().db.get_auto_switch_config()?;
+        let mut records = Vec::new();
+        let mut switched_apps = HashSet::new();
+
+        for policy in config.policies.iter().filter(|p| p.enabled) {
+            if !matches!(policy.trigger, AutoSwitchTrigger::Quota { .. })
+                || switched_apps.contains(&policy.app_type)
+            {
+                continue;
+            }
+            match Self::check_quota_policy(app, policy).await {
+                Ok(Some(record)) => {
+                    switched_apps.insert(policy.app_type.clone());
+                    records.push(record);
+                }
+                Ok(None) => {}
+                Err(e) => log::warn!("额度切换策略 {} 执行失败: {e}", policy.name),
+            }
+        }
+        Ok(records)
+    }
+
+    /// 检查单条额度策略，额度不足时切换到第一个额度充足的备用供应商
+    async fn check_quota_policy(
+        app: &tauri::AppHandle,
+        policy: &AutoSwitchPolicy,
+    ) -> Result<Option<AutoSwitchRecord>, AppError> {
+        let AutoSwitchTrigger::Quota {
+            source,
+            threshold,
+            watch_provider_id,
+            fallback_provider_ids,
+        } = &policy.trigger
+        else {
+            return Ok(None);
+        };
+        let app_type = parse_policy_app(policy)?;
+        let state = app.state::<AppState>();
+
+        let current = ProviderService::current(&state, app_type.clone())?;
+        if current.is_empty()
+            || watch_provider_id
+                .as_deref()
+                .is_some_and(|watched| watched != current)
+        {
+            return Ok(None);
+        }
+
+        let Some(remaining) = remaining_quota(&state, &app_type, &current, *source).await? else {
+            return Ok(None);
+        };
+        if remaining >= *threshold {
+            return Ok(None);
+        }
+
+        for candidate in fallback_provider_ids.iter().filter(|id| **id != current) {
+            let candidate_remaining =
+                match remaining_quota(&state, &app_type, candidate, *source).await {
+                    Ok(value) => value,
+                    Err(e) => {
+                        log::debug!("读取备用供应商 {candidate} 的额度失败: {e}");
+                        None
+                    }
+                };
+            if candidate_remaining.is_some_and(|value| value < *threshold) {
+                continue;
+            }
+
+            let detail = format!("remaining {remaining:.2} < {threshold:.2}");
+            return Self::switch_to(app, policy, AutoSwitchTriggerKind::Quota, candidate, detail)
+                .await;
+        }
+
+        log::warn!(
+            "策略 {} 触发但没有额度充足的备用供应商（剩余 {remaining:.2}）",
+            policy.name
+        );
+        Ok(None)
+    }
+
+    /// 切换到目标供应商，记录历史并通知
+    ///
+    /// 目标已是当前供应商，或应用开启了故障转移时返回 None。
+    async fn switch_to(
+        app: &tauri::AppHandle,
+        policy: &AutoSwitchPolicy,
+        trigger: AutoSwitchTriggerKind,
+        target_id: &str,
+        detail: String,
+    ) -> Result<Option<AutoSwitchRecord>, AppError> {
+        let app_type = parse_policy_app(policy)?;
+        let handle = app.clone();
+        let target = target_id.to_string();
+        let switch_app_type = app_type.clone();
+
+        // ProviderService::switch 内部会阻塞等待，需放到阻塞线程中执行
+        let switched = tauri::async_runtime::spawn_blocking(move || {
+            let state = handle.state::<AppState>();
+            let (_, auto_failover) = state.db.get_proxy_flags_sync(switch_app_type.as_str());
+            if auto_failover {
+                log::debug!("{} 已开启故障转移，跳过自动切换", switch_app_type.as_str());
+                return Ok(None);
+            }
+
+            let current = ProviderService::current(&state, switch_app_type.clone())?;
+            if current == target {
+                return Ok(None);
+            }
+            let provider = state
+                .db
+                .get_provider_by_id(&target, switch_app_type.as_str())?
+                .ok_or_else(|| AppError::InvalidInput(format!("供应商不存在: {target}")))?;
+            ProviderService::switch(&state, switch_app_type, &target)?;
+            Ok::<_, AppError>(Some((
+                (!current.is_empty()).then_some(current),
+                provider.name,
+            )))
+        })
+        .await
+        .map_err(|e| AppError::Message(format!("自动切换任务异常: {e}")))??;
+
+        let Some((from_provider_id, to_provider_name)) = switched else {
+            return Ok(None);
+        };
+
+        let db = app.state::<AppState>().db.clone();
+        let mut record = AutoSwitchRecord {
+            id: 0,
+            policy_id: policy.id.clone(),
+            policy_name: policy.name.clone(),
+            app_type: app_type.as_str().to_string(),
+            trigger,
+            from_provider_id,
+            to_provider_id: target_id.to_string(),
+            to_provider_name,
+            detail,
+            switched_at: chrono::Utc::now().timestamp(),
+        };
+        record.id = db.insert_provider_switch_log(&record)?;
+        db.prune_provider_switch_logs(MAX_HISTORY)?;
+        log::info!(
+            "策略 {} 已将 {} 切换到 {} ({})",
+            record.policy_name,
+            record.app_type,
+            record.to_provider_name,
+            record.detail
+        );
+
+        announce_switch(app, &record);
+        Ok(Some(record))
+    }
+}
+
+/// 后台自动切换任务
+pub async fn run_auto_switch_scheduler(app: tauri::AppHandle) {
+    let mut last_minute = truncate_to_minute(Local::now().naive_local());
+    let mut last_quota_check: Option<Instant> = None;
+
+    loop {
+        tokio::time::sleep(Duration::from_secs(SCHEDULER_POLL_SECS)).await;
+
+        let config = match app.state::<AppState>().db.get_auto_switch_config() {
+            Ok(config) => config,
+            Err(e) => {
+                log::warn!("读取自动切换策略失败: {e}");
+                continue;
+            }
+        };
+
+        // 时钟回拨时只重置游标，不补触发
+        let now = truncate_to_minute(Local::now().naive_local());
+        if now > last_minute && config.enabled {
+            if let Err(e) = AutoSwitchService::run_schedules(&app, &config, last_minute, now).await
+            {
+                log::warn!("执行定时切换策略失败: {e}");
+            }
+        }
+        last_minute = now;
+
+        if !config.enabled {
+            continue;
+        }
+        let interval = config
+            .quota_check_interval_secs
+            .max(MIN_QUOTA_INTERVAL_SECS);
+        if last_quota_check.is_some_and(|t| t.elapsed().as_secs() < interval) {
+            continue;
+        }
+        last_quota_check = Some(Instant::now());
+
+        if let Err(e) = AutoSwitchService::run_quota_checks(&app).await {
+            log::warn!("检查额度切换策略失败: {e}");
+        }
+    }
+}
+
+// ============================================================================
+// 辅助函数
+// ============================================================================
+
+fn parse_policy_app(policy: &AutoSwitchPolicy) -> Result<AppType, AppError> {
+    let app_type = AppType::from_str(&policy.app_type)?;
+    if !AUTO_SWITCH_APPS.contains(&app_type) {
+        return Err(AppError::InvalidInput(format!(
+            "策略 {} 的应用不支持自动切换: {}",
+            policy.name, policy.app_type
+        )));
+    }
+    Ok(app_type)
+}
+
+fn truncate_to_minute(t: NaiveDateTime) -> NaiveDateTime {
+    t.with_second(0)
+        .and_then(|t| t.with_nanosecond(0))
+        .unwrap_or(t)
+}
+
+/// 解析 cron 单个字段为位图
+fn parse_cron_field(field: &str, min: u32, max: u32, label: &str) -> Result<u64, AppError> {
+    let invalid = || AppError::InvalidInput(format!("cron {label}字段无效: {field}"));
+    let parse = |s: &str| s.parse::<u32>().map_err(|_| invalid());
+
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, parse(step)?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            return Err(invalid());
+        }
+        let (lo, hi) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (parse(a)?, parse(b)?)
+        } else {
+            let value = parse(range)?;
+            // `5/15` 表示从 5 开始每 15 个单位
+            (value, if part.contains('/') { max } else { value })
+        };
+        if lo < min || hi > max || lo > hi {
+            return Err(invalid());
+        }
+        for value in (lo..=hi).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+/// 找出 (after, until] 内触发的定时策略；同一应用只保留最后触发的一条
+///
+/// 最多回看 `MAX_CATCH_UP_MINUTES` 分钟，避免长时间休眠后集中补触发。
+fn due_schedule_policies(
+    policies: &[AutoSwitchPolicy],
+    after: NaiveDateTime,
+    until: NaiveDateTime,
+) -> Vec<(&AutoSwitchPolicy, NaiveDateTime)> {
+    let schedules: Vec<(&AutoSwitchPolicy, CronSchedule)> = policies
+        .iter()
+        .filter(|p| p.enabled)
+        .filter_map(|p| match &p.trigger {
+            AutoSwitchTrigger::Schedule { cron, .. } => {
+                CronSchedule::from_str(cron).ok().map(|s| (p, s))
+            }
+            AutoSwitchTrigger::Quota { .. } => None,
+        })
+        .collect();
+
+    let until = truncate_to_minute(until);
+    let earliest = until - ChronoDuration::minutes(MAX_CATCH_UP_MINUTES - 1);
+    let mut t = (truncate_to_minute(after) + ChronoDuration::minutes(1)).max(earliest);
+
+    let mut due: BTreeMap<&str, (&AutoSwitchPolicy, NaiveDateTime)> = BTreeMap::new();
+    while t <= until {
+        for (policy, schedule) in &schedules {
+            if schedule.matches(&t) {
+                due.insert(policy.app_type.as_str(), (policy, t));
+            }
+        }
+        t += ChronoDuration::minutes(1);
+    }
+    due.into_values().collect()
+}
+
+/// 按来源读取供应商剩余额度，无法得知时返回 None
+async fn remaining_quota(
+    state: &AppState,
+    app_type: &AppType,
+    provider_id: &str,
+    source: QuotaSource,
+) -> Result<Option<f64>, AppError> {
+    match source {
+        QuotaSource::Limits => Ok(remaining_from_limits(
+            &state
+                .db
+                .check_provider_limits(provider_id, app_type.as_str())?,
+        )),
+        QuotaSource::UsageScript => {
+            let script_enabled = state
+                .db
+                .get_provider_by_id(provider_id, app_type.as_str())?
+                .and_then(|p| p.meta)
+                .and_then(|m| m.usage_script)
+                .is_some_and(|script| script.enabled);
+            if !script_enabled {
+                return Ok(None);
+            }
+            let result = ProviderService::query_usage(state, app_type.clone(), provider_id).await?;
+            Ok(remaining_from_usage(&result))
+        }
+    }
+}
+
+/// 每日与每月限额中剩余最少的一项（美元）
+fn remaining_from_limits(status: &ProviderLimitStatus) -> Option<f64> {
+    let remaining = |limit: Option<&String>, usage: &str| {
+        let limit = limit?.parse::<f64>().ok()?;
+        Some(limit - usage.parse::<f64>().unwrap_or(0.0))
+    };
+    [
+        remaining(status.daily_limit.as_ref(), &status.daily_usage),
+        remaining(status.monthly_limit.as_ref(), &status.monthly_usage),
+    ]
+    .into_iter()
+    .flatten()
+    .reduce(f64::min)
+}
+
+/// 脚本返回的各套餐中剩余最少的一项（忽略已失效的套餐）
+fn remaining_from_usage(result: &UsageResult) -> Option<f64> {
+    if !result.success {
+        return None;
+    }
+    result
+        .data
+        .as_ref()?
+        .iter()
+        .filter(|plan| plan.is_valid != Some(false))
+        .filter_map(|plan| plan.remaining)
+        .reduce(f64::min)
+}
+
+/// 刷新托盘、通知前端并发送系统通知
+fn announce_switch(app: &tauri::AppHandle, record: &AutoSwitchRecord) {
+    if let Some(state) = app.try_state::<AppState>() {
+        if let Ok(menu) = crate::cc_switch::tray::create_tray_menu(app, state.inner()) {
+            if let Some(tray) = app.tray_by_id("main") {
+                if let Err(e) = tray.set_menu(Some(menu)) {
+                    log::error!("更新托盘菜单失败: {e}");
+                }
+            }
+        }
+    }
+
+    let event_data = serde_json::json!({
+        "appType": record.app_type,
+        "providerId": record.to_provider_id,
+        "source": "autoSwitch"
+    });
+    if let Err(e) = app.emit("provider-switched", event_data) {
+        log::error!("发射 provider-switched 事件失败: {e}");
+    }
+    if let Err(e) = app.emit("provider-auto-switched", record) {
+        log::error!("发射 provider-auto-switched 事件失败: {e}");
+    }
+
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title("已自动切换供应商")
+        .body(format!(
+            "{}: {}（策略：{}）",
+            record.app_type, record.to_provider_name, record.policy_name
+        ))
+        .show()
+    {
+        log::warn!("发送自动切换通知失败: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cc_switch::provider::UsageData;
+    use chrono::NaiveDate;
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, min, 0)
+            .unwrap()
+    }
+
+    fn schedule(id: &str, app: &str, cron: &str, provider: &str) -> AutoSwitchPolicy {
+        AutoSwitchPolicy {
+            id: id.to_string(),
+            name: id.to_string(),
+            app_type: app.to_string(),
+            enabled: true,
+            trigger: AutoSwitchTrigger::Schedule {
+                cron: cron.to_string(),
+                provider_id: provider.to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn cron_fields_and_day_semantics() {
+        let weekdays_evening = CronSchedule::from_str("30 22 * * 1-5").unwrap();
+        // 2026-10-16 是周五，10-17 是周六
+        assert!(weekdays_evening.matches(&at(2026, 10, 16, 22, 30)));
+        assert!(!weekdays_evening.matches(&at(2026, 10, 17, 22, 30)));
+        assert!(!weekdays_evening.matches(&at(2026, 10, 16, 22, 31)));
+
+        let steps = CronSchedule::from_str("5/15 */6 * * *").unwrap();
+        assert!(steps.matches(&at(2026, 1, 1, 6, 50)));
+        assert!(!steps.matches(&at(2026, 1, 1, 7, 5)));
+
+        // 日与周同时受限时满足其一即可；周日可写 7
+        let first_or_sunday = CronSchedule::from_str("0 0 1 * 7").unwrap();
+        assert!(first_or_sunday.matches(&at(2026, 10, 1, 0, 0)));
+        assert!(first_or_sunday.matches(&at(2026, 10, 18, 0, 0)));
+        assert!(!first_or_sunday.matches(&at(2026, 10, 19, 0, 0)));
+
+        for bad in [
+            "* * * *",
+            "60 * * * *",
+            "* * 0 * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+        ] {
+            assert!(
+                CronSchedule::from_str(bad).is_err(),
+                "{bad} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn due_policies_keep_last_fire_per_app() {
+        let policies = vec![
+            schedule("night", "claude", "0 22 * * *", "cheap"),
+            schedule("late", "claude", "30 22 * * *", "cheaper"),
+            schedule("codex", "codex", "15 22 * * *", "c1"),
+            AutoSwitchPolicy {
+                enabled: false,
+                ..schedule("off", "gemini", "* * * * *", "g1")
+            },
+        ];
+
+        let due = due_schedule_policies(
+            &policies,
+            at(2026, 10, 18, 21, 59),
+            at(2026, 10, 18, 22, 45),
+        );
+        let ids: Vec<(&str, NaiveDateTime)> =
+            due.iter().map(|(p, t)| (p.id.as_str(), *t)).collect();
+        assert_eq!(
+            ids,
+            vec![
+                ("late", at(2026, 10, 18, 22, 30)),
+                ("codex", at(2026, 10, 18, 22, 15)),
+            ]
+        );
+
+        // 左开右闭：上次检查所在分钟不重复触发
+        assert!(due_schedule_policies(
+            &policies,
+            at(2026, 10, 18, 22, 0),
+            at(2026, 10, 18, 22, 10)
+        )
+        .is_empty());
+
+        // 长时间休眠后只回看最近一段时间
+        assert!(
+            due_schedule_policies(&policies, at(2026, 10, 18, 8, 0), at(2026, 10, 19, 0, 0))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn remaining_quota_from_limits_and_script() {
+        let status = ProviderLimitStatus {
+            provider_id: "p1".to_string(),
+            daily_usage: "4.5".to_string(),
+            daily_limit: Some("5.00".to_string()),
+            daily_exceeded: false,
+            monthly_usage: "20".to_string(),
+            monthly_limit: Some("100.00".to_string()),
+            monthly_exceeded: false,
+        };
+        assert_eq!(remaining_from_limits(&status), Some(0.5));
+        let unlimited = ProviderLimitStatus {
+            daily_limit: None,
+            monthly_limit: None,
+            ..status
+        };
+        assert_eq!(remaining_from_limits(&unlimited), None);
+
+        let plan = |remaining: f64, is_valid: Option<bool>| UsageData {
+            plan_name: None,
+            extra: None,
+            is_valid,
+            invalid_message: None,
+            total: None,
+            used: None,
+            remaining: Some(remaining),
+            unit: None,
+        };
+        let result = UsageResult {
+            success: true,
+            data: Some(vec![
+                plan(12.0, None),
+                plan(3.0, Some(true)),
+                plan(0.0, Some(false)),
+            ]),
+            error: None,
+            logs: Vec::new(),
+        };
+        assert_eq!(remaining_from_usage(&result), Some(3.0));
+        assert_eq!(
+            remaining_from_usage(&UsageResult {
+                success: false,
+                ..result
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn config_round_trips_with_tagged_triggers() {
+        let json = r#"{
+            "quotaCheckIntervalSecs": 120,
+            "policies": [{
+                "id": "q1",
+                "name": "Daily quota",
+                "appType": "claude",
+                "trigger": {
+                    "type": "quota",
+                    "source": "usageScript",
+                    "threshold": 1.5,
+                    "fallbackProviderIds": ["b", "c"]
+                }
+            }]
+        }"#;
+        let config: AutoSwitchConfig = serde_json::from_str(json).unwrap();
+        assert!(config.enabled);
+        assert!(config.policies[0].enabled);
+        assert_eq!(
+            config.policies[0].trigger,
+            AutoSwitchTrigger::Quota {
+                source: QuotaSource::UsageScript,
+                threshold: 1.5,
+                watch_provider_id: None,
+                fallback_provider_ids: vec!["b".to_string(), "c".to_string()],
+            }
+        );
+
+        let value = serde_json::to_value(&config).unwrap();
+        assert_eq!(value["policies"][0]["trigger"]["type"], "quota");
+        assert_eq!(
+            serde_json::from_value::<AutoSwitchConfig>(value).unwrap(),
+            config
+        );
+    }
+}
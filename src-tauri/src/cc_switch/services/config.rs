@@ -1,5 +1,6 @@
 use super::provider::ProviderService;
 use crate::cc_switch::app_config::{AppType, MultiAppConfig};
+use crate::cc_switch::app_target::{not_ported, target_for};
 use crate::cc_switch::error::AppError;
 use crate::cc_switch::provider::Provider;
 use chrono::Utc;
@@ -121,6 +122,7 @@ impl ConfigService {
         match app_type {
             AppType::Codex => Self::sync_codex_live(config, &current_id, &provider)?,
             AppType::Claude => Self::sync_claude_live(config, &current_id, &provider)?,
+            // Gemini 写入后还需回读 live 配置到 MultiAppConfig，保留专用路径
             AppType::Gemini => Self::sync_gemini_live(config, &current_id, &provider)?,
             AppType::OpenCode => {
                 // OpenCode uses additive mode, no live sync needed
@@ -129,6 +131,10 @@ impl ConfigService {
             AppType::Cursor => {
                 // Cursor doesn't support live sync
             }
+            _ => match target_for(app_type) {
+                Some(target) => target.write_provider_live(&provider)?,
+                None => return Err(not_ported(app_type)),
+            },
         }
 
         Ok(())
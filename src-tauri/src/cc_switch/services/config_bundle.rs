@@ -0,0 +1,1284 @@
+//! 可移植配置包导出/导入
+//!
+//! 配置包是一个 zip 文件，内含若干 JSON：
+//!
+//! ```text
+//! manifest.json      - 格式版本、加密参数、是否已移除密钥、各类条目数量
+//! providers.json     - 供应商（按应用）
+//! mcp_servers.json   - MCP 服务器
+//! prompts.json       - 提示词（按应用）
+//! skills.json        - Skill 仓库与已安装 Skill 引用
+//! settings.json      - 可跨设备共享的通用设置
+//! ```
+//!
+//! 设置口令时，除 manifest 外的文件均以 AES-256-GCM 加密（文件名追加 `.enc`）；
+//! 不设置口令时必须移除密钥。导入为合并模式：按 ID、名称检测冲突，
+//! 逐条按冲突策略处理，不会整体替换数据库。
+
+use indexmap::IndexMap;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use crate::cc_switch::app_config::{AppType, InstalledSkill, McpServer};
+use crate::cc_switch::error::AppError;
+use crate::cc_switch::prompt::Prompt;
+use crate::cc_switch::provider::Provider;
+use crate::cc_switch::secrets::{self, KdfParams, SecretKey};
+use crate::cc_switch::services::skill::SkillRepo;
+use crate::cc_switch::store::AppState;
+
+/// 配置包格式标识
+pub const CONFIG_BUNDLE_FORMAT: &str = "mnemosyne-config-bundle";
+/// 配置包格式版本
+pub const CONFIG_BUNDLE_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const PROVIDERS_FILE: &str = "providers.json";
+const MCP_SERVERS_FILE: &str = "mcp_servers.json";
+const PROMPTS_FILE: &str = "prompts.json";
+const SKILLS_FILE: &str = "skills.json";
+const SETTINGS_FILE: &str = "settings.json";
+const ENCRYPTED_SUFFIX: &str = ".enc";
+
+/// 单个条目文件的大小上限，防止异常文件耗尽内存
+const MAX_ENTRY_BYTES: u64 = 64 * 1024 * 1024;
+
+/// 支持供应商的全部应用
+const BUNDLE_APPS: [AppType; 6] = AppType::ALL;
+
+/// 可跨设备共享的设置键（全局代理、代理接管等设备相关设置不导出）
+///
+/// 受信任的签名公钥（`trusted_bundle_publishers`）不在其列：
+/// 导入的配置包不能为自己或其他配置包添加信任。
+const PORTABLE_SETTING_KEYS: &[&str] = &["rectifier_config", "log_config", "stream_check_config"];
+const PORTABLE_SETTING_PREFIXES: &[&str] = &["common_config_"];
+
+fn is_portable_setting(key: &str) -> bool {
+    PORTABLE_SETTING_KEYS.contains(&key)
+        || PORTABLE_SETTING_PREFIXES
+            .iter()
+            .any(|prefix| key.starts_with(prefix))
+}
+
+// ============================================================================
+// 导出范围与选项
+// ============================================================================
+
+/// 某类条目的导出范围
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", content = "ids", rename_all = "lowercase")]
+pub enum ScopeSelection {
+    /// 不导出
+    #[default]
+    None,
+    /// 全部导出
+    All,
+    /// 仅导出指定条目（供应商/提示词为 `app:id`，Skill 为 Skill ID，MCP 为服务器 ID）
+    Only(Vec<String>),
+}
+
+impl ScopeSelection {
+    fn includes(&self, key: &str) -> bool {
+        match self {
+            Self::None => false,
+            Self::All => true,
+            Self::Only(keys) => keys.iter().any(|k| k == key),
+        }
+    }
+
+    fn is_none(&self) -> bool {
+        matches!(self, Self::None)
+    }
+}
+
+/// 导出范围
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportScope {
+    #[serde(default)]
+    pub providers: ScopeSelection,
+    #[serde(default)]
+    pub mcp_servers: ScopeSelection,
+    #[serde(default)]
+    pub prompts: ScopeSelection,
+    #[serde(default)]
+    pub skills: ScopeSelection,
+    #[serde(default)]
+    pub settings: bool,
+}
+
+/// 导出选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportOptions {
+    pub scope: ExportScope,
+    /// 加密口令（为空则不加密）
+    #[serde(default)]
+    pub passphrase: Option<String>,
+    /// 是否移除密钥（未加密导出时必须为 true）
+    #[serde(default)]
+    pub strip_secrets: bool,
+}
+
+// ============================================================================
+// 配置包内容
+// ============================================================================
+
+/// 各类条目数量
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleCounts {
+    pub providers: usize,
+    pub mcp_servers: usize,
+    pub prompts: usize,
+    pub skill_repos: usize,
+    pub skills: usize,
+    pub settings: usize,
+}
+
+/// manifest.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigBundleManifest {
+    pub format: String,
+    pub version: u32,
+    pub created_at: i64,
+    pub app_version: String,
+    /// 加密参数（未加密为 None）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<KdfParams>,
+    /// 导出时是否已移除密钥
+    #[serde(default)]
+    pub secrets_stripped: bool,
+    pub counts: BundleCounts,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProviderEntry {
+    app_type: String,
+    provider: Provider,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PromptEntry {
+    app_type: String,
+    prompt: Prompt,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SkillsPayload {
+    #[serde(default)]
+    repos: Vec<SkillRepo>,
+    /// 已安装 Skill 的引用（不含文件内容，导入后需重新安装）
+    #[serde(default)]
+    installed: Vec<InstalledSkill>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct BundlePayload {
+    providers: Vec<ProviderEntry>,
+    mcp_servers: Vec<McpServer>,
+    prompts: Vec<PromptEntry>,
+    skills: SkillsPayload,
+    settings: BTreeMap<String, String>,
+}
+
+// ============================================================================
+// 预览与导入结果
+// ============================================================================
+
+/// 条目类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BundleEntryKind {
+    Provider,
+    Mcp,
+    Prompt,
+    SkillRepo,
+    Skill,
+    Setting,
+}
+
+/// 冲突类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictKind {
+    /// ID 相同
+    Id,
+    /// ID 不同但名称相同
+    Name,
+}
+
+/// 冲突处理策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictStrategy {
+    /// 保留本机条目
+    #[default]
+    Skip,
+    /// 用配置包中的条目覆盖本机条目（被移除的密钥沿用本机值）
+    Overwrite,
+    /// 两者都保留，导入条目使用新 ID 与名称（仅供应商、MCP、提示词支持，其余视为跳过）
+    KeepBoth,
+}
+
+/// 预览条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleEntryPreview {
+    /// 条目键，用于在导入时指定单独的冲突策略
+    pub key: String,
+    pub kind: BundleEntryKind,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conflict: Option<ConflictKind>,
+    /// 冲突的本机条目 ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub existing_id: Option<String>,
+    /// 条目中存在被移除的密钥
+    pub secrets_missing: bool,
+}
+
+/// 配置包预览
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigBundlePreview {
+    pub manifest: ConfigBundleManifest,
+    pub entries: Vec<BundleEntryPreview>,
+}
+
+/// 导入选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportOptions {
+    #[serde(default)]
+    pub passphrase: Option<String>,
+    /// 默认冲突策略
+    #[serde(default)]
+    pub conflict_strategy: ConflictStrategy,
+    /// 按条目键覆盖冲突策略
+    #[serde(default)]
+    pub resolutions: HashMap<String, ConflictStrategy>,
+    /// 仅导入指定条目键（为空表示全部）
+    #[serde(default)]
+    pub only: Vec<String>,
+}
+
+/// 导入动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportAction {
+    Created,
+    Overwritten,
+    Renamed,
+    Skipped,
+    /// 需用户后续处理（如重新安装 Skill）
+    Pending,
+}
+
+/// 单个条目的导入结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedEntry {
+    pub key: String,
+    pub kind: BundleEntryKind,
+    pub name: String,
+    pub action: ImportAction,
+    /// 写入后的 ID（重命名时为新 ID）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+}
+
+/// 导入报告
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigBundleImportReport {
+    pub entries: Vec<ImportedEntry>,
+    /// 本机未安装、需要重新安装的 Skill
+    pub pending_skills: Vec<InstalledSkill>,
+    pub warnings: Vec<String>,
+}
+
+impl ConfigBundleImportReport {
+    fn push(
+        &mut self,
+        key: String,
+        kind: BundleEntryKind,
+        name: &str,
+        action: ImportAction,
+        id: Option<String>,
+    ) {
+        self.entries.push(ImportedEntry {
+            key,
+            kind,
+            name: name.to_string(),
+            action,
+            id,
+        });
+    }
+}
+
+// ============================================================================
+// 服务
+// ============================================================================
+
+/// 配置包业务逻辑
+pub struct ConfigBundleService;
+
+impl ConfigBundleService {
+    /// 按范围导出配置包，返回 manifest
+    pub fn export(
+        state: &AppState,
+        target_path: &Path,
+        options: &ExportOptions,
+    ) -> Result<ConfigBundleManifest, AppError> {
+        let passphrase = options.passphrase.as_deref().filter(|p| !p.is_empty());
+        if passphrase.is_none() && !options.strip_secrets {
+            return Err(AppError::InvalidInput(
+                "未加密的配置包必须移除密钥，请设置口令或勾选移除密钥".to_string(),
+            ));
+        }
+
+        let payload = Self::collect(state, &options.scope)?;
+        let encryption = passphrase.map(|_| KdfParams::generate()).transpose()?;
+        let key = match (passphrase, &encryption) {
+            (Some(passphrase), Some(params)) => Some(SecretKey::derive(passphrase, params)?),
+            _ => None,
+        };
+
+        let manifest = ConfigBundleManifest {
+            format: CONFIG_BUNDLE_FORMAT.to_string(),
+            version: CONFIG_BUNDLE_VERSION,
+            created_at: chrono::Utc::now().timestamp(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            encryption,
+            secrets_stripped: options.strip_secrets,
+            counts: BundleCounts {
+                providers: payload.providers.len(),
+                mcp_servers: payload.mcp_servers.len(),
+                prompts: payload.prompts.len(),
+                skill_repos: payload.skills.repos.len(),
+                skills: payload.skills.installed.len(),
+                settings: payload.settings.len(),
+            },
+        };
+
+        let mut files: Vec<(&str, Value)> = Vec::new();
+        let scope = &options.scope;
+        if !scope.providers.is_none() {
+            files.push((PROVIDERS_FILE, to_value(&payload.providers)?));
+        }
+        if !scope.mcp_servers.is_none() {
+            files.push((MCP_SERVERS_FILE, to_value(&payload.mcp_servers)?));
+        }
+        if !scope.prompts.is_none() {
+            files.push((PROMPTS_FILE, to_value(&payload.prompts)?));
+        }
+        if !scope.skills.is_none() {
+            files.push((SKILLS_FILE, to_value(&payload.skills)?));
+        }
+        if scope.settings {
+            files.push((SETTINGS_FILE, to_value(&payload.settings)?));
+        }
+        if options.strip_secrets {
+            let stripped: usize = files
+                .iter_mut()
+                .map(|(_, value)| secrets::strip_secrets(value))
+                .sum();
+            log::info!("导出配置包已移除 {stripped} 个密钥字段");
+        }
+
+        if let Some(parent) = target_path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+        }
+        let file = File::create(target_path).map_err(|e| AppError::io(target_path, e))?;
+        let mut writer = zip::ZipWriter::new(file);
+        let zip_options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+
+        let manifest_bytes = serde_json::to_vec_pretty(&manifest)
+            .map_err(|e| AppError::JsonSerialize { source: e })?;
+        write_zip_entry(&mut writer, MANIFEST_FILE, &manifest_bytes, zip_options)?;
+
+        for (name, value) in files {
+            let bytes = serde_json::to_vec_pretty(&value)
+                .map_err(|e| AppError::JsonSerialize { source: e })?;
+            match &key {
+                Some(key) => {
+                    let sealed = key.encrypt(&bytes, name.as_bytes())?;
+                    let entry_name = format!("{name}{ENCRYPTED_SUFFIX}");
+                    write_zip_entry(&mut writer, &entry_name, &sealed, zip_options)?;
+                }
+                None => write_zip_entry(&mut writer, name, &bytes, zip_options)?,
+            }
+        }
+
+        writer
+            .finish()
+            .map_err(|e| AppError::Message(format!("写入配置包失败: {e}")))?;
+
+        log::info!(
+            "已导出配置包 {}（加密: {}）",
+            target_path.display(),
+            manifest.encryption.is_some()
+        );
+        Ok(manifest)
+    }
+
+    /// 读取 manifest（无需口令，用于判断是否需要输入口令）
+    pub fn read_manifest(source_path: &Path) -> Result<ConfigBundleManifest, AppError> {
+        let mut archive = open_archive(source_path)?;
+        read_manifest_from(&mut archive)
+    }
+
+    /// 预览配置包内容及与本机数据的冲突
+    pub fn inspect(
+        state: &AppState,
+        source_path: &Path,
+        passphrase: Option<&str>,
+    ) -> Result<ConfigBundlePreview, AppError> {
+        let (manifest, payload) = Self::read(source_path, passphrase)?;
+        let mut entries = Vec::new();
+
+        for entry in &payload.providers {
+            let existing = state.db.get_all_providers(&entry.app_type)?;
+            let conflict =
+                find_conflict(&existing, &entry.provider.id, &entry.provider.name, |p| {
+                    &p.name
+                });
+            entries.push(BundleEntryPreview {
+                key: provider_key(entry),
+                kind: BundleEntryKind::Provider,
+                name: entry.provider.name.clone(),
+                app_type: Some(entry.app_type.clone()),
+                conflict: conflict.as_ref().map(|(kind, _)| *kind),
+                existing_id: conflict.map(|(_, id)| id),
+                secrets_missing: secrets::has_stripped_secrets(&to_value(&entry.provider)?),
+            });
+        }
+
+        let existing_servers = state.db.get_all_mcp_servers()?;
+        for server in &payload.mcp_servers {
+            let conflict = find_conflict(&existing_servers, &server.id, &server.name, |s| &s.name);
+            entries.push(BundleEntryPreview {
+                key: mcp_key(server),
+                kind: BundleEntryKind::Mcp,
+                name: server.name.clone(),
+                app_type: None,
+                conflict: conflict.as_ref().map(|(kind, _)| *kind),
+                existing_id: conflict.map(|(_, id)| id),
+                secrets_missing: secrets::has_stripped_secrets(&server.server),
+            });
+        }
+
+        for entry in &payload.prompts {
+            let existing = state.db.get_prompts(&entry.app_type)?;
+            let conflict =
+                find_conflict(&existing, &entry.prompt.id, &entry.prompt.name, |p| &p.name);
+            entries.push(BundleEntryPreview {
+                key: prompt_key(entry),
+                kind: BundleEntryKind::Prompt,
+                name: entry.prompt.name.clone(),
+                app_type: Some(entry.app_type.clone()),
+                conflict: conflict.as_ref().map(|(kind, _)| *kind),
+                existing_id: conflict.map(|(_, id)| id),
+                secrets_missing: false,
+            });
+        }
+
+        let existing_repos = state.db.get_skill_repos()?;
+        for repo in &payload.skills.repos {
+            let id = format!("{}/{}", repo.owner, repo.name);
+            let exists = existing_repos
+                .iter()
+                .any(|r| r.owner == repo.owner && r.name == repo.name);
+            entries.push(BundleEntryPreview {
+                key: format!("skillRepo:{id}"),
+                kind: BundleEntryKind::SkillRepo,
+                name: id.clone(),
+                app_type: None,
+                conflict: exists.then_some(ConflictKind::Id),
+                existing_id: exists.then_some(id),
+                secrets_missing: false,
+            });
+        }
+
+        let existing_skills = state.db.get_all_installed_skills()?;
+        for skill in &payload.skills.installed {
+            let exists = existing_skills.contains_key(&skill.id);
+            entries.push(BundleEntryPreview {
+                key: format!("skill:{}", skill.id),
+                kind: BundleEntryKind::Skill,
+                name: skill.name.clone(),
+                app_type: None,
+                conflict: exists.then_some(ConflictKind::Id),
+                existing_id: exists.then(|| skill.id.clone()),
+                secrets_missing: false,
+            });
+        }
+
+        let existing_settings = state.db.get_all_settings()?;
+        for (key, value) in &payload.settings {
+            let conflict = existing_settings
+                .get(key)
+                .filter(|existing| *existing != value)
+                .map(|_| ConflictKind::Id);
+            entries.push(BundleEntryPreview {
+                key: format!("setting:{key}"),
+                kind: BundleEntryKind::Setting,
+                name: key.clone(),
+                app_type: None,
+                conflict,
+                existing_id: conflict.map(|_| key.clone()),
+                secrets_missing: false,
+            });
+        }
+
+        Ok(ConfigBundlePreview { manifest, entries })
+    }
+
+    /// 以合并模式导入配置包（仅写数据库，live 配置由调用方同步）
+    pub fn import(
+        state: &AppState,
+        source_path: &Path,
+        options: &ImportOptions,
+    ) -> Result<ConfigBundleImportReport, AppError> {
+        let (manifest, payload) = Self::read(source_path, options.passphrase.as_deref())?;
+        let mut report = ConfigBundleImportReport::default();
+
+        let selected = |key: &str| options.only.is_empty() || options.only.iter().any(|k| k == key);
+        let strategy = |key: &str| {
+            options
+                .resolutions
+                .get(key)
+                .copied()
+                .unwrap_or(options.conflict_strategy)
+        };
+
+        // ---- 供应商 ----
+        for entry in payload.providers {
+            let key = provider_key(&entry);
+            if !selected(&key) {
+                continue;
+            }
+            entry.app_type.parse::<AppType>()?;
+            let existing = state.db.get_all_providers(&entry.app_type)?;
+            let mut provider = entry.provider;
+            let name = provider.name.clone();
+
+            let action = match find_conflict(&existing, &provider.id, &provider.name, |p| &p.name) {
+                None => ImportAction::Created,
+                Some((_, existing_id)) => match strategy(&key) {
+                    ConflictStrategy::Skip => {
+                        report.push(
+                            key,
+                            BundleEntryKind::Provider,
+                            &name,
+                            ImportAction::Skipped,
+                            None,
+                        );
+                        continue;
+                    }
+                    ConflictStrategy::Overwrite => {
+                        provider = merge_stripped_secrets(provider, &existing[&existing_id])?;
+                        provider.id = existing_id;
+                        ImportAction::Overwritten
+                    }
+                    ConflictStrategy::KeepBoth => {
+                        provider.id = unique_id(&provider.id, |id| existing.contains_key(id));
+                        provider.name =
+                            unique_name(&provider.name, |n| existing.values().any(|p| p.name == n));
+                        ImportAction::Renamed
+                    }
+                },
+            };
+
+            if secrets::has_stripped_secrets(&to_value(&provider)?) {
+                report.warnings.push(format!(
+                    "供应商「{}」（{}）缺少密钥，请手动补全",
+                    provider.name, entry.app_type
+                ));
+            }
+            state.db.save_provider(&entry.app_type, &provider)?;
+            report.push(
+                key,
+                BundleEntryKind::Provider,
+                &name,
+                action,
+                Some(provider.id),
+            );
+        }
+
+        // ---- MCP 服务器 ----
+        let existing_servers = state.db.get_all_mcp_servers()?;
+        for mut server in payload.mcp_servers {
+            let key = mcp_key(&server);
+            if !selected(&key) {
+                continue;
+            }
+            let name = server.name.clone();
+
+            let action = match find_conflict(&existing_servers, &server.id, &server.name, |s| {
+                &s.name
+            }) {
+                None => ImportAction::Created,
+                Some((_, existing_id)) => match strategy(&key) {
+                    ConflictStrategy::Skip => {
+                        report.push(
+                            key,
+                            BundleEntryKind::Mcp,
+                            &name,
+                            ImportAction::Skipped,
+                            None,
+                        );
+                        continue;
+                    }
+                    ConflictStrategy::Overwrite => {
+                        server = merge_stripped_secrets(server, &existing_servers[&existing_id])?;
+                        server.id = existing_id;
+                        ImportAction::Overwritten
+                    }
+                    ConflictStrategy::KeepBoth => {
+                        server.id = unique_id(&server.id, |id| existing_servers.contains_key(id));
+                        server.name = unique_name(&server.name, |n| {
+                            existing_servers.values().any(|s| s.name == n)
+                        });
+                        ImportAction::Renamed
+                    }
+                },
+            };
+
+            if secrets::has_stripped_secrets(&server.server) {
+                report
+                    .warnings
+                    .push(format!("MCP 服务器「{}」缺少密钥，请手动补全", server.name));
+            }
+            state.db.save_mcp_server(&server)?;
+            report.push(key, BundleEntryKind::Mcp, &name, action, Some(server.id));
+        }
+
+        // ---- 提示词 ----
+        for entry in payload.prompts {
+            let key = prompt_key(&entry);
+            if !selected(&key) {
+                continue;
+            }
+            entry.app_type.parse::<AppType>()?;
+            let existing = state.db.get_prompts(&entry.app_type)?;
+            let mut prompt = entry.prompt;
+            let name = prompt.name.clone();
+            // 导入的提示词默认不启用，避免与本机已启用的提示词冲突
+            prompt.enabled = false;
+
+            let action = match find_conflict(&existing, &prompt.id, &prompt.name, |p| &p.name) {
+                None => ImportAction::Created,
+                Some((_, existing_id)) => match strategy(&key) {
+                    ConflictStrategy::Skip => {
+                        report.push(
+                            key,
+                            BundleEntryKind::Prompt,
+                            &name,
+                            ImportAction::Skipped,
+                            None,
+                        );
+                        continue;
+                    }
+                    ConflictStrategy::Overwrite => {
+                        prompt.enabled = existing[&existing_id].enabled;
+                        prompt.id = existing_id;
+                        ImportAction::Overwritten
+                    }
+                    ConflictStrategy::KeepBoth => {
+                        prompt.id = unique_id(&prompt.id, |id| existing.contains_key(id));
+                        prompt.name =
+                            unique_name(&prompt.name, |n| existing.values().any(|p| p.name == n));
+                        ImportAction::Renamed
+                    }
+                },
+            };
+
+            if prompt.enabled {
+                report.warnings.push(format!(
+                    "提示词「{}」为启用状态，内容已更新，请重新启用以写入文件",
+                    prompt.name
+                ));
+            }
+            state.db.save_prompt(&entry.app_type, &prompt)?;
+            report.push(key, BundleEntryKind::Prompt, &name, action, Some(prompt.id));
+        }
+
+        // ---- Skill 仓库 ----
+        let existing_repos = state.db.get_skill_repos()?;
+        for repo in payload.skills.repos {
+            let id = format!("{}/{}", repo.owner, repo.name);
+            let key = format!("skillRepo:{id}");
+            if !selected(&key) {
+                continue;
+            }
+            let exists = existing_repos
+                .iter()
+                .any(|r| r.owner == repo.owner && r.name == repo.name);
+            let action = match (exists, strategy(&key)) {
+                (false, _) => ImportAction::Created,
+                (true, ConflictStrategy::Overwrite) => ImportAction::Overwritten,
+                (true, _) => {
+                    report.push(
+                        key,
+                        BundleEntryKind::SkillRepo,
+                        &id,
+                        ImportAction::Skipped,
+                        None,
+                    );
+                    continue;
+                }
+            };
+            state.db.save_skill_repo(&repo)?;
+            report.push(
+                key,
+                BundleEntryKind::SkillRepo,
+                &id,
+                action,
+                Some(id.clone()),
+            );
+        }
+
+        // ---- 已安装 Skill（仅记录待安装项，文件需重新下载）----
+        let existing_skills = state.db.get_all_installed_skills()?;
+        for skill in payload.skills.installed {
+            let key = format!("skill:{}", skill.id);
+            if !selected(&key) {
+                continue;
+            }
+            let action = if existing_skills.contains_key(&skill.id) {
+                ImportAction::Skipped
+            } else {
+                ImportAction::Pending
+            };
+            report.push(
+                key,
+                BundleEntryKind::Skill,
+                &skill.name,
+                action,
+                Some(skill.id.clone()),
+            );
+            if action == ImportAction::Pending {
+                report.pending_skills.push(skill);
+            }
+        }
+
+        // ---- 设置 ----
+        let existing_settings = state.db.get_all_settings()?;
+        for (setting_key, value) in payload.settings {
+            let key = format!("setting:{setting_key}");
+            if !selected(&key) {
+                continue;
+            }
+            if !is_portable_setting(&setting_key) {
+                log::warn!("忽略配置包中的非共享设置: {setting_key}");
+                continue;
+            }
+            let action = match existing_settings.get(&setting_key) {
+                None => ImportAction::Created,
+                Some(existing) if *existing == value => ImportAction::Skipped,
+                Some(_) if strategy(&key) == ConflictStrategy::Overwrite => {
+                    ImportAction::Overwritten
+                }
+                Some(_) => ImportAction::Skipped,
+            };
+            if action != ImportAction::Skipped {
+                state.db.set_setting(&setting_key, &value)?;
+            }
+            report.push(
+                key,
+                BundleEntryKind::Setting,
+                &setting_key,
+                action,
+                Some(setting_key.clone()),
+            );
+        }
+
+        log::info!(
+            "已导入配置包 {}（格式版本 {}，{} 个条目）",
+            source_path.display(),
+            manifest.version,
+            report.entries.len()
+        );
+        Ok(report)
+    }
+
+    /// 按范围从数据库收集条目
+    fn collect(state: &AppState, scope: &ExportScope) -> Result<BundlePayload, AppError> {
+        let mut payload = BundlePayload::default();
+
+        if !scope.providers.is_none() {
+            for app in BUNDLE_APPS {
+                for provider in state.db.get_all_providers(app.as_str())?.into_values() {
+                    if scope
+                        .providers
+                        .includes(&format!("{}:{}", app.as_str(), provider.id))
+                    {
+                        payload.providers.push(ProviderEntry {
+                            app_type: app.as_str().to_string(),
+                            provider,
+                        });
+                    }
+                }
+            }
+        }
+
+        if !scope.mcp_servers.is_none() {
+            payload.mcp_servers = state
+                .db
+                .get_all_mcp_servers()?
+                .into_values()
+                .filter(|server| scope.mcp_servers.includes(&server.id))
+                .collect();
+        }
+
+        if !scope.prompts.is_none() {
+            for app in BUNDLE_APPS {
+                for prompt in state.db.get_prompts(app.as_str())?.into_values() {
+                    if scope
+                        .prompts
+                        .includes(&format!("{}:{}", app.as_str(), prompt.id))
+                    {
+                        payload.prompts.push(PromptEntry {
+                            app_type: app.as_str().to_string(),
+                            prompt,
+                        });
+                    }
+                }
+            }
+        }
+
+        if !scope.skills.is_none() {
+            payload.skills.installed = state
+                .db
+                .get_all_installed_skills()?
+                .into_values()
+                .filter(|skill| scope.skills.includes(&skill.id))
+                .collect();
+            // 全部导出时包含所有仓库，否则只包含所选 Skill 来源的仓库
+            payload.skills.repos = state
+                .db
+                .get_skill_repos()?
+                .into_iter()
+                .filter(|repo| {
+                    scope.skills == ScopeSelection::All
+                        || payload.skills.installed.iter().any(|skill| {
+                            skill.repo_owner.as_deref() == Some(repo.owner.as_str())
+                                && skill.repo_name.as_deref() == Some(repo.name.as_str())
+                        })
+                })
+                .collect();
+        }
+
+        if scope.settings {
+            payload.settings = state
+                .db
+                .get_all_settings()?
+                .into_iter()
+                .filter(|(key, _)| is_portable_setting(key))
+                .collect();
+        }
+
+        Ok(payload)
+    }
+
+    /// 读取并解密配置包
+    fn read(
+        source_path: &Path,
+        passphrase: Option<&str>,
+    ) -> Result<(ConfigBundleManifest, BundlePayload), AppError> {
+        let mut archive = open_archive(source_path)?;
+        let manifest = read_manifest_from(&mut archive)?;
+
+        let key = match &manifest.encryption {
+            Some(params) => {
+                let passphrase = passphrase.filter(|p| !p.is_empty()).ok_or_else(|| {
+                    AppError::InvalidInput("该配置包已加密，请输入口令".to_string())
+                })?;
+                Some(SecretKey::derive(passphrase, params)?)
+            }
+            None => None,
+        };
+
+        let mut read_json = |name: &str| -> Result<Option<Vec<u8>>, AppError> {
+            match &key {
+                Some(key) => {
+                    let entry_name = format!("{name}{ENCRYPTED_SUFFIX}");
+                    read_zip_entry(&mut archive, &entry_name)?
+                        .map(|sealed| key.decrypt(&sealed, name.as_bytes()))
+                        .transpose()
+                }
+                None => read_zip_entry(&mut archive, name),
+            }
+        };
+
+        let payload = BundlePayload {
+            providers: parse_entry(PROVIDERS_FILE, read_json(PROVIDERS_FILE)?)?,
+            mcp_servers: parse_entry(MCP_SERVERS_FILE, read_json(MCP_SERVERS_FILE)?)?,
+            prompts: parse_entry(PROMPTS_FILE, read_json(PROMPTS_FILE)?)?,
+            skills: parse_entry(SKILLS_FILE, read_json(SKILLS_FILE)?)?,
+            settings: parse_entry(SETTINGS_FILE, read_json(SETTINGS_FILE)?)?,
+        };
+        Ok((manifest, payload))
+    }
+}
+
+// ============================================================================
+// 辅助函数
+// ============================================================================
+
+fn provider_key(entry: &ProviderEntry) -> String {
+    format!("provider:{}:{}", entry.app_type, entry.provider.id)
+}
+
+fn prompt_key(entry: &PromptEntry) -> String {
+    format!("prompt:{}:{}", entry.app_type, entry.prompt.id)
+}
+
+fn mcp_key(server: &McpServer) -> String {
+    format!("mcp:{}", server.id)
+}
+
+fn to_value<T: Serialize>(value: &T) -> Result<Value, AppError> {
+    serde_json::to_value(value).map_err(|e| AppError::JsonSerialize { source: e })
+}
+
+/// 按 ID、名称查找冲突的本机条目
+fn find_conflict<T>(
+    existing: &IndexMap<String, T>,
+    id: &str,
+    name: &str,
+    name_of: impl Fn(&T) -> &String,
+) -> Option<(ConflictKind, String)> {
+    if existing.contains_key(id) {
+        return Some((ConflictKind::Id, id.to_string()));
+    }
+    existing
+        .iter()
+        .find(|(_, item)| name_of(item) == name)
+        .map(|(existing_id, _)| (ConflictKind::Name, existing_id.clone()))
+}
+
+/// 覆盖本机条目时，沿用本机的密钥值填补被移除的字段
+fn merge_stripped_secrets<T: Serialize + DeserializeOwned>(
+    incoming: T,
+    existing: &T,
+) -> Result<T, AppError> {
+    let mut value = to_value(&incoming)?;
+    if secrets::restore_secrets(&mut value, &to_value(existing)?) == 0 {
+        return Ok(incoming);
+    }
+    serde_json::from_value(value).map_err(|e| AppError::JsonSerialize { source: e })
+}
+
+fn unique_id(base: &str, exists: impl Fn(&str) -> bool) -> String {
+    let mut candidate = format!("{base}-imported");
+    let mut counter = 2;
+    while exists(&candidate) {
+        candidate = format!("{base}-imported-{counter}");
+        counter += 1;
+    }
+    candidate
+}
+
+fn unique_name(base: &str, exists: impl Fn(&str) -> bool) -> String {
+    let mut counter = 2;
+    let mut candidate = format!("{base} ({counter})");
+    while exists(&candidate) {
+        counter += 1;
+        candidate = format!("{base} ({counter})");
+    }
+    candidate
+}
+
+fn open_archive(source_path: &Path) -> Result<zip::ZipArchive<File>, AppError> {
+    let file = File::open(source_path).map_err(|e| AppError::io(source_path, e))?;
+    zip::ZipArchive::new(file)
+        .map_err(|e| AppError::InvalidInput(format!("无法读取配置包（不是有效的 zip 文件）: {e}")))
+}
+
+fn read_manifest_from(
+    archive: &mut zip::ZipArchive<File>,
+) -> Result<ConfigBundleManifest, AppError> {
+    let bytes = read_zip_entry(archive, MANIFEST_FILE)?
+        .ok_or_else(|| AppError::InvalidInput("配置包缺少 manifest.json".to_string()))?;
+    let manifest: ConfigBundleManifest = serde_json::from_slice(&bytes)
+        .map_err(|e| AppError::InvalidInput(format!("配置包 manifest 无效: {e}")))?;
+
+    if manifest.format != CONFIG_BUNDLE_FORMAT {
+        return Err(AppError::InvalidInput(format!(
+            "不支持的配置包格式: {}",
+            manifest.format
+        )));
+    }
+    if manifest.version > CONFIG_BUNDLE_VERSION {
+        return Err(AppError::InvalidInput(format!(
+            "配置包版本 {} 高于当前支持的版本 {}，请升级应用",
+            manifest.version, CONFIG_BUNDLE_VERSION
+        )));
+    }
+    Ok(manifest)
+}
+
+fn read_zip_entry(
+    archive: &mut zip::ZipArchive<File>,
+    name: &str,
+) -> Result<Option<Vec<u8>>, AppError> {
+    let entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => {
+            return Err(AppError::InvalidInput(format!(
+                "读取配置包条目 {name} 失败: {e}"
+            )))
+        }
+    };
+    if entry.size() > MAX_ENTRY_BYTES {
+        return Err(AppError::InvalidInput(format!("配置包条目 {name} 过大")));
+    }
+
+    let mut bytes = Vec::with_capacity(entry.size() as usize);
+    entry
+        .take(MAX_ENTRY_BYTES)
+        .read_to_end(&mut bytes)
+        .map_err(|e| AppError::InvalidInput(format!("读取配置包条目 {name} 失败: {e}")))?;
+    Ok(Some(bytes))
+}
+
+fn parse_entry<T: DeserializeOwned + Default>(
+    name: &str,
+    bytes: Option<Vec<u8>>,
+) -> Result<T, AppError> {
+    match bytes {
+        Some(bytes) => serde_json::from_slice(&bytes)
+            .map_err(|e| AppError::InvalidInput(format!("配置包条目 {name} 无效: {e}"))),
+        None => Ok(T::default()),
+    }
+}
+
+fn write_zip_entry(
+    writer: &mut zip::ZipWriter<File>,
+    name: &str,
+    bytes: &[u8],
+    options: zip::write::SimpleFileOptions,
+) -> Result<(), AppError> {
+    writer
+        .start_file(name, options)
+        .map_err(|e| AppError::Message(format!("写入配置包条目 {name} 失败: {e}")))?;
+    writer
+        .write_all(bytes)
+        .map_err(|e| AppError::Message(format!("写入配置包条目 {name} 失败: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cc_switch::app_config::McpApps;
+    use crate::cc_switch::database::Database;
+    use serde_json::json;
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    fn claude_provider(id: &str, name: &str, token: &str) -> Provider {
+        Provider::with_id(
+            id.to_string(),
+            name.to_string(),
+            json!({
+                "env": {
+                    "ANTHROPIC_BASE_URL": "https://api.example.com",
+                    "ANTHROPIC_AUTH_TOKEN": token
+                }
+            }),
+            None,
+        )
+    }
+
+    fn seed_source() -> AppState {
+        let db = Arc::new(Database::memory().expect("init db"));
+        db.save_provider("claude", &claude_provider("p1", "Example", "sk-source"))
+            .expect("save provider");
+        db.save_provider("claude", &claude_provider("p2", "Other", "sk-other"))
+            .expect("save provider");
+        db.save_mcp_server(&McpServer {
+            id: "fetch".to_string(),
+            name: "Fetch".to_string(),
+            server: json!({ "command": "uvx", "env": { "API_KEY": "mcp-secret" } }),
+            apps: McpApps {
+                claude: true,
+                ..Default::default()
+            },
+            description: None,
+            homepage: None,
+            docs: None,
+            tags: Vec::new(),
+        })
+        .expect("save mcp");
+        db.set_setting("common_config_claude", "{}")
+            .expect("set setting");
+        db.set_setting("global_proxy_url", "http://127.0.0.1:7890")
+            .expect("set setting");
+        db.set_setting(
+            "trusted_bundle_publishers",
+            r#"[{"name":"x","publicKey":"k"}]"#,
+        )
+        .expect("set setting");
+        AppState::new(db)
+    }
+
+    fn scope_all() -> ExportScope {
+        ExportScope {
+            providers: ScopeSelection::All,
+            mcp_servers: ScopeSelection::All,
+            prompts: ScopeSelection::All,
+            skills: ScopeSelection::All,
+            settings: true,
+        }
+    }
+
+    #[test]
+    fn unencrypted_export_requires_stripping_secrets() {
+        let dir = tempdir().expect("tempdir");
+        let options = ExportOptions {
+            scope: scope_all(),
+            passphrase: None,
+            strip_secrets: false,
+        };
+        let err = ConfigBundleService::export(&seed_source(), &dir.path().join("b.zip"), &options)
+            .expect_err("plaintext export with secrets must fail");
+        assert!(err.to_string().contains("移除密钥"));
+    }
+
+    #[test]
+    fn encrypted_bundle_round_trip_with_selective_scope() {
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("bundle.zip");
+        let options = ExportOptions {
+            scope: ExportScope {
+                providers: ScopeSelection::Only(vec!["claude:p1".to_string()]),
+                ..scope_all()
+            },
+            passphrase: Some("passphrase".to_string()),
+            strip_secrets: false,
+        };
+        let manifest =
+            ConfigBundleService::export(&seed_source(), &path, &options).expect("export");
+        assert!(manifest.encryption.is_some());
+        assert_eq!(manifest.counts.providers, 1);
+        assert_eq!(manifest.counts.settings, 1, "device settings are excluded");
+
+        let target = AppState::new(Arc::new(Database::memory().expect("init db")));
+        assert!(ConfigBundleService::inspect(&target, &path, None).is_err());
+        assert!(ConfigBundleService::inspect(&target, &path, Some("wrong")).is_err());
+
+        let report = ConfigBundleService::import(
+            &target,
+            &path,
+            &ImportOptions {
+                passphrase: Some("passphrase".to_string()),
+                ..Default::default()
+            },
+        )
+        .expect("import");
+        assert!(report.warnings.is_empty());
+
+        let providers = target.db.get_all_providers("claude").expect("providers");
+        assert_eq!(providers.len(), 1);
+        assert_eq!(
+            providers["p1"].settings_config["env"]["ANTHROPIC_AUTH_TOKEN"],
+            "sk-source"
+        );
+        let servers = target.db.get_all_mcp_servers().expect("servers");
+        assert_eq!(servers["fetch"].server["env"]["API_KEY"], "mcp-secret");
+        assert_eq!(
+            target
+                .db
+                .get_setting("common_config_claude")
+                .expect("setting"),
+            Some("{}".to_string())
+        );
+        assert_eq!(target.db.get_global_proxy_url().expect("setting"), None);
+        assert_eq!(
+            target
+                .db
+                .get_setting("trusted_bundle_publishers")
+                .expect("setting"),
+            None,
+            "bundles must not add trusted publishers"
+        );
+    }
+
+    #[test]
+    fn stripped_bundle_merges_by_id_and_name() {
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("bundle.zip");
+        let options = ExportOptions {
+            scope: ExportScope {
+                providers: ScopeSelection::All,
+                ..Default::default()
+            },
+            passphrase: None,
+            strip_secrets: true,
+        };
+        ConfigBundleService::export(&seed_source(), &path, &options).expect("export");
+
+        let target = AppState::new(Arc::new(Database::memory().expect("init db")));
+        target
+            .db
+            .save_provider("claude", &claude_provider("p1", "Example", "sk-local"))
+            .expect("save provider");
+        target
+            .db
+            .save_provider("claude", &claude_provider("local-other", "Other", "sk-x"))
+            .expect("save provider");
+
+        let preview = ConfigBundleService::inspect(&target, &path, None).expect("inspect");
+        let conflicts: Vec<_> = preview.entries.iter().map(|e| e.conflict).collect();
+        assert_eq!(
+            conflicts,
+            vec![Some(ConflictKind::Id), Some(ConflictKind::Name)]
+        );
+        assert!(preview.entries.iter().all(|e| e.secrets_missing));
+
+        let report = ConfigBundleService::import(
+            &target,
+            &path,
+            &ImportOptions {
+                conflict_strategy: ConflictStrategy::Overwrite,
+                resolutions: HashMap::from([(
+                    "provider:claude:p2".to_string(),
+                    ConflictStrategy::KeepBoth,
+                )]),
+                ..Default::default()
+            },
+        )
+        .expect("import");
+        let actions: Vec<_> = report.entries.iter().map(|e| e.action).collect();
+        assert_eq!(
+            actions,
+            vec![ImportAction::Overwritten, ImportAction::Renamed]
+        );
+
+        let providers = target.db.get_all_providers("claude").expect("providers");
+        assert_eq!(providers.len(), 3);
+        assert_eq!(
+            providers["p1"].settings_config["env"]["ANTHROPIC_AUTH_TOKEN"], "sk-local",
+            "overwrite keeps the local secret"
+        );
+        let renamed = &providers["p2-imported"];
+        assert_eq!(renamed.name, "Other (2)");
+        assert_eq!(renamed.settings_config["env"]["ANTHROPIC_AUTH_TOKEN"], "");
+        assert_eq!(report.warnings.len(), 1);
+    }
+}
@@ -0,0 +1,835 @@
+//! 端点自动选择服务
+//!
+//! 后台定期对开启了 `endpointAutoSelect` 的供应商测速：
+//! - 对当前地址与 `custom_endpoints` 中的每个地址发起真实的轻量 API 请求（列出模型），
+//!   携带供应商的认证信息，能同时发现网络不通与鉴权失败的端点
+//! - 发现更快的健康端点时切换供应商的 base URL（代理与 live 配置同时生效）
+//! - 通过最小提升阈值、连续确认轮数与冷却时间避免来回切换
+//! - 每次切换记录到 endpoint_switch_logs 表
+
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::cc_switch::app_config::AppType;
+use crate::cc_switch::database::Database;
+use crate::cc_switch::error::AppError;
+use crate::cc_switch::provider::Provider;
+use crate::cc_switch::proxy::providers::{get_adapter, AuthInfo, ProviderAdapter};
+use crate::cc_switch::services::{ProviderService, ProxyService};
+use crate::cc_switch::store::AppState;
+use tauri::Manager;
+
+/// 支持自动选择端点的应用
+const AUTO_SELECT_APPS: [AppType; 3] = [AppType::Claude, AppType::Codex, AppType::Gemini];
+
+/// 允许的最小测速间隔（秒）
+const MIN_INTERVAL_SECS: u64 = 60;
+
+/// 后台任务检查间隔（秒）
+const SCHEDULER_POLL_SECS: u64 = 30;
+
+/// 保留的切换历史条数
+const MAX_HISTORY: usize = 500;
+
+/// 等待确认的候选端点（按 `app_type/provider_id` 记录，仅保存在内存中）
+static PENDING: Mutex<BTreeMap<String, PendingSwitch>> = Mutex::new(BTreeMap::new());
+
+// ============================================================================
+// 类型定义
+// ============================================================================
+
+/// 端点自动选择配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EndpointAutoSelectConfig {
+    /// 后台任务总开关（各供应商仍需单独开启 endpointAutoSelect）
+    pub enabled: bool,
+    /// 测速间隔（秒）
+    pub interval_secs: u64,
+    /// 单次请求超时（秒）
+    pub timeout_secs: u64,
+    /// 新端点至少要快多少毫秒
+    pub min_improvement_ms: u64,
+    /// 新端点至少要快多少百分比
+    pub min_improvement_percent: u32,
+    /// 同一候选端点需连续胜出的轮数
+    pub confirm_rounds: u32,
+    /// 两次切换之间的最短间隔（秒）；冷却期内当前端点需连续 `confirm_rounds` 轮
+    /// 不可用才会切换
+    pub cooldown_secs: u64,
+}
+
+impl Default for EndpointAutoSelectConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 600,
+            timeout_secs: 8,
+            min_improvement_ms: 100,
+            min_improvement_percent: 20,
+            confirm_rounds: 2,
+            cooldown_secs: 1800,
+        }
+    }
+}
+
+/// 单个端点的测速结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointProbe {
+    pub url: String,
+    pub healthy: bool,
+    pub latency_ms: Option<u64>,
+    pub status: Option<u16>,
+    pub error: Option<String>,
+}
+
+/// 切换原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EndpointSwitchReason {
+    /// 发现明显更快的端点
+    Faster,
+    /// 当前端点不可用
+    Unhealthy,
+}
+
+impl EndpointSwitchReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Faster => "faster",
+            Self::Unhealthy => "unhealthy",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "unhealthy" => Self::Unhealthy,
+            _ => Self::Faster,
+        }
+    }
+}
+
+/// 端点切换记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointSwitchRecord {
+    pub id: i64,
+    pub app_type: String,
+    pub provider_id: String,
+    pub provider_name: String,
+    pub from_url: String,
+    pub to_url: String,
+    pub from_latency_ms: Option<u64>,
+    pub to_latency_ms: u64,
+    pub reason: EndpointSwitchReason,
+    /// 切换时间（Unix 秒）
+    pub switched_at: i64,
+}
+
+/// 等待确认的候选端点
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PendingSwitch {
+    url: String,
+    wins: u32,
+    /// 当前端点连续不可用的轮数
+    failures: u32,
+}
+
+/// 单轮测速后的决策
+#[derive(Debug, PartialEq, Eq)]
+enum Decision {
+    Stay(Option<PendingSwitch>),
+    Switch {
+        to: String,
+        reason: EndpointSwitchReason,
+    },
+}
+
+// ============================================================================
+// 服务
+// ============================================================================
+
+pub struct EndpointAutoSelectService;
+
+impl EndpointAutoSelectService {
+    /// 获取配置
+    pub fn get_config(state: &AppState) -> Result<EndpointAutoSelectConfig, AppError> {
+        state.db.get_endpoint_auto_select_config()
+    }
+
+    /// 更新配置
+    pub fn set_config(
+        state: &AppState,
+        config: EndpointAutoSelectConfig,
+    ) -> Result<EndpointAutoSelectConfig, AppError> {
+        if config.interval_secs < MIN_INTERVAL_SECS {
+            return Err(AppError::InvalidInput(format!(
+                "测速间隔不能少于 {MIN_INTERVAL_SECS} 秒"
+            )));
+        }
+        if config.timeout_secs == 0 || config.confirm_rounds == 0 {
+            return Err(AppError::InvalidInput(
+                "超时时间与确认轮数必须大于 0".to_string(),
+            ));
+        }
+        if config.min_improvement_percent >= 100 {
+            return Err(AppError::InvalidInput(
+                "最小提升百分比必须小于 100".to_string(),
+            ));
+        }
+        state.db.set_endpoint_auto_select_config(&config)?;
+        Ok(config)
+    }
+
+    /// 获取切换历史（最新在前）
+    pub fn list_history(
+        state: &AppState,
+        app_type: Option<&str>,
+        provider_id: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<Vec<EndpointSwitchRecord>, AppError> {
+        state.db.list_endpoint_switch_logs(
+            app_type,
+            provider_id,
+            limit.unwrap_or(50).min(MAX_HISTORY),
+        )
+    }
+
+    /// 对指定供应商的全部端点测速（不切换）
+    pub async fn probe_provider(
+        db: &Database,
+        app_type: &AppType,
+        provider_id: &str,
+    ) -> Result<Vec<EndpointProbe>, AppError> {
+        let provider = db
+            .get_provider_by_id(provider_id, app_type.as_str())?
+            .ok_or_else(|| AppError::InvalidInput(format!("供应商不存在: {provider_id}")))?;
+        let config = db.get_endpoint_auto_select_config()?;
+        let (_, probes) = Self::probe_all(app_type, &provider, &config).await?;
+        Ok(probes)
+    }
+
+    /// 执行一轮测速，返回本轮发生的切换
+    ///
+    /// 切换需使用托管的 AppState，以便识别正在运行的代理并走热切换。
+    pub async fn run_once(app: &tauri::AppHandle) -> Result<Vec<EndpointSwitchRecord>, AppError> {
+        let db = app.state::<AppState>().db.clone();
+        let config = db.get_endpoint_auto_select_config()?;
+        let mut records = Vec::new();
+
+        for app_type in AUTO_SELECT_APPS {
+            let providers = db.get_all_providers(app_type.as_str())?;
+            for provider in providers.values() {
+                let auto_select = provider.meta.as_ref().is_some_and(|meta| {
+                    meta.endpoint_auto_select == Some(true) && !meta.custom_endpoints.is_empty()
+                });
+                if !auto_select {
+                    continue;
+                }
+
+                match Self::check_provider(app, &db, &app_type, provider, &config).await {
+                    Ok(Some(record)) => records.push(record),
+                    Ok(None) => {}
+                    Err(e) => log::warn!(
+                        "端点自动选择失败 ({}/{}): {e}",
+                        app_type.as_str(),
+                        provider.id
+                    ),
+                }
+            }
+        }
+
+        if !records.is_empty() {
+            db.prune_endpoint_switch_logs(MAX_HISTORY)?;
+        }
+        Ok(records)
+    }
+
+    /// 对单个供应商测速并按需切换
+    async fn check_provider(
+        app: &tauri::AppHandle,
+        db: &Database,
+        app_type: &AppType,
+        provider: &Provider,
+        config: &EndpointAutoSelectConfig,
+    ) -> Result<Option<EndpointSwitchRecord>, AppError> {
+        let (current, probes) = Self::probe_all(app_type, provider, config).await?;
+
+        let key = format!("{}/{}", app_type.as_str(), provider.id);
+        let pending = lock_pending()?.get(&key).cloned();
+        let last_switch_at = db
+            .list_endpoint_switch_logs(Some(app_type.as_str()), Some(&provider.id), 1)?
+            .first()
+            .map(|r| r.switched_at);
+        let now = chrono::Utc::now().timestamp();
+
+        let (to, reason) = match decide(
+            &current,
+            &probes,
+            pending.as_ref(),
+            last_switch_at,
+            now,
+            config,
+        ) {
+            Decision::Stay(next) => {
+                let mut map = lock_pending()?;
+                match next {
+                    Some(next) => map.insert(key, next),
+                    None => map.remove(&key),
+                };
+                return Ok(None);
+            }
+            Decision::Switch { to, reason } => {
+                lock_pending()?.remove(&key);
+                (to, reason)
+            }
+        };
+
+        let latency_of = |url: &str| {
+            probes
+                .iter()
+                .find(|p| p.url == url)
+                .and_then(|p| p.latency_ms)
+        };
+        let from_latency_ms =
+            latency_of(&current).filter(|_| reason == EndpointSwitchReason::Faster);
+        let to_latency_ms = latency_of(&to).unwrap_or_default();
+
+        let provider_id = provider.id.clone();
+        let apply_app_type = app_type.clone();
+        let (from, target) = (current.clone(), to.clone());
+        let handle = app.clone();
+        let provider_name = tauri::async_runtime::spawn_blocking(move || {
+            let state = handle.state::<AppState>();
+            Self::apply_switch(&state, &apply_app_type, &provider_id, &from, &target)
+        })
+        .await
+        .map_err(|e| AppError::Message(format!("切换端点任务异常: {e}")))??;
+
+        // 测速期间供应商已被修改，放弃本次切换
+        let Some(provider_name) = provider_name else {
+            return Ok(None);
+        };
+
+        let mut record = EndpointSwitchRecord {
+            id: 0,
+            app_type: app_type.as_str().to_string(),
+            provider_id: provider.id.clone(),
+            provider_name,
+            from_url: current,
+            to_url: to,
+            from_latency_ms,
+            to_latency_ms,
+            reason,
+            switched_at: now,
+        };
+        record.id = db.insert_endpoint_switch_log(&record)?;
+        log::info!(
+            "供应商 {} 的端点已自动切换: {} -> {} ({})",
+            record.provider_name,
+            record.from_url,
+            record.to_url,
+            reason.as_str()
+        );
+        Ok(Some(record))
+    }
+
+    /// 对当前地址和所有自定义端点测速，返回 (当前地址, 测速结果)
+    async fn probe_all(
+        app_type: &AppType,
+        provider: &Provider,
+        config: &EndpointAutoSelectConfig,
+    ) -> Result<(String, Vec<EndpointProbe>), AppError> {
+        let adapter = get_adapter(app_type);
+        let current = adapter
+            .extract_base_url(provider)
+            .map_err(|e| AppError::Message(format!("读取 base_url 失败: {e}")))?;
+        let auth = adapter
+            .extract_auth(provider)
+            .ok_or_else(|| AppError::Message("API Key not found".to_string()))?;
+
+        let mut urls = vec![current.clone()];
+        if let Some(meta) = provider.meta.as_ref() {
+            let mut endpoints: Vec<String> = meta
+                .custom_endpoints
+                .keys()
+                .map(|url| url.trim().trim_end_matches('/').to_string())
+                .filter(|url| !url.is_empty())
+                .collect();
+            endpoints.sort();
+            for url in endpoints {
+                if !urls.contains(&url) {
+                    urls.push(url);
+                }
+            }
+        }
+
+        let proxy_config = provider.meta.as_ref().and_then(|m| m.proxy_config.as_ref());
+        let client = crate::cc_switch::proxy::http_client::get_for_provider(proxy_config);
+        let timeout = Duration::from_secs(config.timeout_secs.max(1));
+
+        let tasks = urls
+            .iter()
+            .map(|url| probe_endpoint(&client, app_type, adapter.as_ref(), &auth, url, timeout));
+        let probes = futures::future::join_all(tasks).await;
+        Ok((current, probes))
+    }
+
+    /// 把供应商的 base URL 从 `expected_current` 切换为 `new_url`
+    ///
+    /// 返回供应商名称；若供应商已删除或 base URL 已被修改则返回 None。
+    fn apply_switch(
+        state: &AppState,
+        app_type: &AppType,
+        provider_id: &str,
+        expected_current: &str,
+        new_url: &str,
+    ) -> Result<Option<String>, AppError> {
+        let Some(mut provider) = state
+            .db
+            .get_provider_by_id(provider_id, app_type.as_str())?
+        else {
+            return Ok(None);
+        };
+        let current = get_adapter(app_type).extract_base_url(&provider).ok();
+        if current.as_deref() != Some(expected_current) {
+            return Ok(None);
+        }
+
+        set_base_url(app_type, &mut provider.settings_config, new_url);
+        let name = provider.name.clone();
+        ProviderService::update(state, app_type.clone(), provider)?;
+        ProviderService::update_endpoint_last_used(
+            state,
+            app_type.clone(),
+            provider_id,
+            new_url.to_string(),
+        )?;
+        Ok(Some(name))
+    }
+}
+
+/// 后台端点自动选择任务
+pub async fn run_endpoint_auto_select(app: tauri::AppHandle) {
+    use tauri::Emitter;
+
+    let mut last_run: Option<Instant> = None;
+
+    loop {
+        tokio::time::sleep(Duration::from_secs(SCHEDULER_POLL_SECS)).await;
+
+        let db = app.state::<AppState>().db.clone();
+        let config = match db.get_endpoint_auto_select_config() {
+            Ok(config) => config,
+            Err(e) => {
+                log::warn!("读取端点自动选择配置失败: {e}");
+                continue;
+            }
+        };
+        if !config.enabled {
+            continue;
+        }
+        let interval = config.interval_secs.max(MIN_INTERVAL_SECS);
+        if last_run.is_some_and(|t| t.elapsed().as_secs() < interval) {
+            continue;
+        }
+        last_run = Some(Instant::now());
+
+        match EndpointAutoSelectService::run_once(&app).await {
+            Ok(records) if !records.is_empty() => {
+                if let Err(e) = app.emit("endpoint-auto-switched", &records) {
+                    log::error!("发送端点切换事件失败: {e}");
+                }
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("端点自动选择失败: {e}"),
+        }
+    }
+}
+
+// ============================================================================
+// 辅助函数
+// ============================================================================
+
+fn lock_pending(
+) -> Result<std::sync::MutexGuard<'static, BTreeMap<String, PendingSwitch>>, AppError> {
+    PENDING
+        .lock()
+        .map_err(|e| AppError::Message(format!("Mutex lock failed: {e}")))
+}
+
+/// 轻量 API 请求地址：各协议的模型列表接口
+fn models_url(app_type: &AppType, base_url: &str) -> String {
+    let base = base_url.trim_end_matches('/');
+    match app_type {
+        AppType::Gemini if base.ends_with("/v1beta") || base.ends_with("/v1") => {
+            format!("{base}/models")
+        }
+        AppType::Gemini => format!("{base}/v1beta/models"),
+        _ if base.ends_with("/v1") => format!("{base}/models"),
+        _ => format!("{base}/v1/models"),
+    }
+}
+
+/// 端点是否可用：能正常响应且未拒绝鉴权或限流
+///
+/// 部分中转服务未实现模型列表接口（404/405），仍视为可用。
+fn is_healthy_status(status: StatusCode) -> bool {
+    !(status.is_server_error()
+        || matches!(
+            status,
+            StatusCode::UNAUTHORIZED
+                | StatusCode::FORBIDDEN
+                | StatusCode::PROXY_AUTHENTICATION_REQUIRED
+                | StatusCode::TOO_MANY_REQUESTS
+        ))
+}
+
+/// 对单个端点测速：先热身一次复用连接，再计时第二次请求
+async fn probe_endpoint(
+    client: &Client,
+    app_type: &AppType,
+    adapter: &dyn ProviderAdapter,
+    auth: &AuthInfo,
+    base_url: &str,
+    timeout: Duration,
+) -> EndpointProbe {
+    let url = models_url(app_type, base_url);
+    let build = || {
+        let request = client.get(&url).timeout(timeout);
+        let request = match app_type {
+            AppType::Claude => request.header("anthropic-version", "2023-06-01"),
+            _ => request,
+        };
+        adapter.add_auth_headers(request, auth)
+    };
+
+    let _ = build().send().await;
+
+    let start = Instant::now();
+    match build().send().await {
+        Ok(resp) => {
+            let status = resp.status();
+            let healthy = is_healthy_status(status);
+            EndpointProbe {
+                url: base_url.to_string(),
+                healthy,
+                latency_ms: Some(start.elapsed().as_millis() as u64),
+                status: Some(status.as_u16()),
+                error: (!healthy).then(|| format!("HTTP {}", status.as_u16())),
+            }
+        }
+        Err(err) => EndpointProbe {
+            url: base_url.to_string(),
+            healthy: false,
+            latency_ms: None,
+            status: err.status().map(|s| s.as_u16()),
+            error: Some(if err.is_timeout() {
+                "请求超时".to_string()
+            } else if err.is_connect() {
+                "连接失败".to_string()
+            } else {
+                err.to_string()
+            }),
+        },
+    }
+}
+
+/// 根据测速结果决定是否切换
+///
+/// - 当前端点不可用时切换到最快的健康端点；冷却期内需连续 `confirm_rounds` 轮
+///   不可用，避免时好时坏的端点每轮都触发切换
+/// - 否则候选端点需同时满足毫秒与百分比提升阈值，并连续胜出 `confirm_rounds` 轮，
+///   且距上次切换超过冷却时间
+fn decide(
+    current: &str,
+    probes: &[EndpointProbe],
+    pending: Option<&PendingSwitch>,
+    last_switch_at: Option<i64>,
+    now: i64,
+    config: &EndpointAutoSelectConfig,
+) -> Decision {
+    let Some(best) = probes
+        .iter()
+        .filter(|p| p.url != current && p.healthy)
+        .filter_map(|p| p.latency_ms.map(|latency| (p, latency)))
+        .min_by_key(|(_, latency)| *latency)
+    else {
+        return Decision::Stay(None);
+    };
+
+    let cooling_down = last_switch_at.is_some_and(|last| now - last < config.cooldown_secs as i64);
+
+    let current_latency = probes
+        .iter()
+        .find(|p| p.url == current && p.healthy)
+        .and_then(|p| p.latency_ms);
+    let Some(current_latency) = current_latency else {
+        let failures = pending.map_or(0, |p| p.failures) + 1;
+        if cooling_down && failures < config.confirm_rounds {
+            return Decision::Stay(Some(PendingSwitch {
+                url: best.0.url.clone(),
+                wins: 0,
+                failures,
+            }));
+        }
+        return Decision::Switch {
+            to: best.0.url.clone(),
+            reason: EndpointSwitchReason::Unhealthy,
+        };
+    };
+
+    let (best, best_latency) = best;
+    let improvement = current_latency.saturating_sub(best_latency);
+    let significant = improvement >= config.min_improvement_ms
+        && improvement * 100 >= current_latency * u64::from(config.min_improvement_percent);
+    if !significant {
+        return Decision::Stay(None);
+    }
+
+    let wins = match pending {
+        Some(p) if p.url == best.url => p.wins + 1,
+        _ => 1,
+    };
+    if wins >= config.confirm_rounds && !cooling_down {
+        Decision::Switch {
+            to: best.url.clone(),
+            reason: EndpointSwitchReason::Faster,
+        }
+    } else {
+        Decision::Stay(Some(PendingSwitch {
+            url: best.url.clone(),
+            wins,
+            failures: 0,
+        }))
+    }
+}
+
+/// 写入供应商配置中的 base URL（与各适配器的读取位置保持一致）
+fn set_base_url(app_type: &AppType, settings: &mut Value, new_url: &str) {
+    let env_key = match app_type {
+        AppType::Codex => {
+            if let Some(config) = settings.get("config").and_then(|v| v.as_str()) {
+                let updated = ProxyService::update_toml_base_url(config, new_url);
+                settings["config"] = Value::String(updated);
+            } else if let Some(obj) = settings.as_object_mut() {
+                let key = if obj.contains_key("baseURL") {
+                    "baseURL"
+                } else {
+                    "base_url"
+                };
+                obj.insert(key.to_string(), json!(new_url));
+            }
+            return;
+        }
+        AppType::Gemini => "GOOGLE_GEMINI_BASE_URL",
+        _ => "ANTHROPIC_BASE_URL",
+    };
+
+    let has_env_url = settings
+        .get("env")
+        .and_then(|env| env.get(env_key))
+        .is_some();
+    if !has_env_url {
+        if let Some(obj) = settings.as_object_mut() {
+            if let Some(key) = ["base_url", "baseURL", "apiEndpoint"]
+                .into_iter()
+                .find(|key| obj.contains_key(*key))
+            {
+                obj.insert(key.to_string(), json!(new_url));
+                return;
+            }
+        }
+    }
+
+    if !settings.get("env").is_some_and(|env| env.is_object()) {
+        settings["env"] = json!({});
+    }
+    settings["env"][env_key] = json!(new_url);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(url: &str, latency: Option<u64>, healthy: bool) -> EndpointProbe {
+        EndpointProbe {
+            url: url.to_string(),
+            healthy,
+            latency_ms: latency,
+            status: latency.map(|_| 200),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn switch_requires_significant_and_confirmed_improvement() {
+        let config = EndpointAutoSelectConfig::default();
+        let probes = vec![
+            probe("https://a", Some(500), true),
+            probe("https://b", Some(200), true),
+        ];
+
+        // 首轮胜出只记录候选
+        let first = decide("https://a", &probes, None, None, 10_000, &config);
+        let pending = match first {
+            Decision::Stay(Some(p)) => p,
+            other => panic!("expected pending candidate, got {other:?}"),
+        };
+        assert_eq!(pending.wins, 1);
+
+        // 第二轮确认后切换
+        assert_eq!(
+            decide("https://a", &probes, Some(&pending), None, 10_000, &config),
+            Decision::Switch {
+                to: "https://b".to_string(),
+                reason: EndpointSwitchReason::Faster
+            }
+        );
+
+        // 冷却期内不切换
+        assert!(matches!(
+            decide(
+                "https://a",
+                &probes,
+                Some(&pending),
+                Some(10_000 - 60),
+                10_000,
+                &config
+            ),
+            Decision::Stay(Some(_))
+        ));
+
+        // 提升不足阈值时不切换，并清空候选
+        let close = vec![
+            probe("https://a", Some(500), true),
+            probe("https://b", Some(450), true),
+        ];
+        assert_eq!(
+            decide("https://a", &close, Some(&pending), None, 10_000, &config),
+            Decision::Stay(None)
+        );
+    }
+
+    #[test]
+    fn unhealthy_current_endpoint_switches_immediately() {
+        let config = EndpointAutoSelectConfig::default();
+        let probes = vec![
+            probe("https://a", None, false),
+            probe("https://b", Some(900), true),
+            probe("https://c", Some(300), false),
+        ];
+        assert_eq!(
+            decide("https://a", &probes, None, None, 10_000, &config),
+            Decision::Switch {
+                to: "https://b".to_string(),
+                reason: EndpointSwitchReason::Unhealthy
+            }
+        );
+
+        let all_down = vec![
+            probe("https://a", None, false),
+            probe("https://b", None, false),
+        ];
+        assert_eq!(
+            decide("https://a", &all_down, None, None, 10_000, &config),
+            Decision::Stay(None)
+        );
+    }
+
+    #[test]
+    fn unhealthy_switch_during_cooldown_requires_consecutive_failures() {
+        let config = EndpointAutoSelectConfig::default();
+        let down = vec![
+            probe("https://a", None, false),
+            probe("https://b", Some(300), true),
+        ];
+        let up = vec![
+            probe("https://a", Some(300), true),
+            probe("https://b", Some(290), true),
+        ];
+        let last_switch = Some(10_000 - 60);
+
+        // 刚切换过，首轮失败只记录
+        let pending = match decide("https://a", &down, None, last_switch, 10_000, &config) {
+            Decision::Stay(Some(p)) => p,
+            other => panic!("expected pending failure, got {other:?}"),
+        };
+        assert_eq!(pending.failures, 1);
+
+        // 恢复后失败计数清零，时好时坏的端点不会触发切换
+        let recovered = decide(
+            "https://a",
+            &up,
+            Some(&pending),
+            last_switch,
+            10_000,
+            &config,
+        );
+        assert_eq!(recovered, Decision::Stay(None));
+        assert!(matches!(
+            decide("https://a", &down, None, last_switch, 10_000, &config),
+            Decision::Stay(Some(_))
+        ));
+
+        // 连续失败达到确认轮数后切换
+        assert_eq!(
+            decide(
+                "https://a",
+                &down,
+                Some(&pending),
+                last_switch,
+                10_000,
+                &config
+            ),
+            Decision::Switch {
+                to: "https://b".to_string(),
+                reason: EndpointSwitchReason::Unhealthy
+            }
+        );
+    }
+
+    #[test]
+    fn set_base_url_matches_adapter_locations() {
+        let mut claude = json!({ "env": { "ANTHROPIC_BASE_URL": "https://old" } });
+        set_base_url(&AppType::Claude, &mut claude, "https://new");
+        assert_eq!(claude["env"]["ANTHROPIC_BASE_URL"], "https://new");
+
+        let mut gemini = json!({ "env": {} });
+        set_base_url(&AppType::Gemini, &mut gemini, "https://new");
+        assert_eq!(gemini["env"]["GOOGLE_GEMINI_BASE_URL"], "https://new");
+
+        let mut codex = json!({
+            "config": "model_provider = \"any\"\n\n[model_providers.any]\nbase_url = \"https://old/v1\"\n"
+        });
+        set_base_url(&AppType::Codex, &mut codex, "https://new/v1");
+        let provider = Provider::with_id("p".into(), "P".into(), codex, None);
+        assert_eq!(
+            get_adapter(&AppType::Codex)
+                .extract_base_url(&provider)
+                .expect("codex base url"),
+            "https://new/v1"
+        );
+
+        assert_eq!(
+            models_url(&AppType::Claude, "https://api.example.com/"),
+            "https://api.example.com/v1/models"
+        );
+        assert_eq!(
+            models_url(&AppType::Codex, "https://relay.example.com/v1"),
+            "https://relay.example.com/v1/models"
+        );
+        assert_eq!(
+            models_url(
+                &AppType::Gemini,
+                "https://generativelanguage.googleapis.com"
+            ),
+            "https://generativelanguage.googleapis.com/v1beta/models"
+        );
+    }
+}
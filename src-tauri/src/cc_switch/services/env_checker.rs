@@ -32,7 +32,7 @@ pub fn check_env_conflicts(app: &str) -> Result<Vec<EnvConflict>, String> {
 }
 
 /// Get relevant keywords for each app
-fn get_keywords_for_app(app: &str) -> Vec<&str> {
+pub(crate) fn get_keywords_for_app(app: &str) -> Vec<&str> {
     match app.to_lowercase().as_str() {
         "claude" => vec!["ANTHROPIC"],
         "codex" => vec!["OPENAI"],
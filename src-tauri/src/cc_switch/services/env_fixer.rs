@@ -0,0 +1,1006 @@
+//! Shell rc file fixer for conflicting environment variables.
+//!
+//! Parses bash/zsh/fish rc files (following `source` / `.` includes), disables the
+//! selected assignments by prefixing them with a marker comment, and restores them
+//! exactly from that marker. Every change is previewed as a unified diff first.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cc_switch::text_diff::unified_diff;
+
+/// Marker written in front of every disabled line
+pub const ENV_FIX_MARKER: &str = "mnemosyne:env-fix";
+
+/// Maximum depth of nested `source` includes that are followed
+const MAX_SOURCE_DEPTH: usize = 4;
+
+/// Shell dialect of an rc file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShellKind {
+    /// bash / zsh / sh
+    Posix,
+    Fish,
+}
+
+impl ShellKind {
+    fn from_path(path: &Path) -> Self {
+        if path.extension().is_some_and(|ext| ext == "fish") {
+            ShellKind::Fish
+        } else {
+            ShellKind::Posix
+        }
+    }
+}
+
+/// How a line is disabled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FixMode {
+    /// Guard lines inside POSIX conditionals, comment out everything else
+    #[default]
+    Auto,
+    /// `# marker <original>`
+    Comment,
+    /// `true # marker <original>`, keeps `if ...; then ... fi` blocks non-empty
+    Guard,
+}
+
+/// An environment variable assignment found in an rc file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RcAssignment {
+    pub file: String,
+    /// First line of the assignment (1-based)
+    pub line: usize,
+    /// Last line of the assignment, differs from `line` for multi-line values
+    pub end_line: usize,
+    pub var_name: String,
+    pub var_value: String,
+    pub shell: ShellKind,
+    /// Inside an `if` / `case` / loop / function block
+    pub in_conditional: bool,
+    /// The line contains other statements that are disabled together with it
+    pub shares_line: bool,
+    /// The rc file that sourced this file, if it is not a top-level rc file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sourced_from: Option<String>,
+}
+
+/// An assignment selected for fixing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvFixTarget {
+    pub file: String,
+    pub line: usize,
+    pub var_name: String,
+}
+
+/// Whether a plan disables lines or restores them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnvFixAction {
+    Fix,
+    Restore,
+}
+
+/// Pending change to a single file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvFixFile {
+    pub path: String,
+    /// SHA-256 of the file content the plan was computed from
+    pub checksum: String,
+    pub diff: String,
+}
+
+/// Previewed change set, passed back unchanged to apply it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvFixPlan {
+    pub fix_id: String,
+    pub action: EnvFixAction,
+    #[serde(default)]
+    pub mode: FixMode,
+    #[serde(default)]
+    pub targets: Vec<EnvFixTarget>,
+    pub files: Vec<EnvFixFile>,
+}
+
+/// Lines disabled by one fix in one file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvFixRecord {
+    pub fix_id: String,
+    pub file: String,
+    pub lines: Vec<usize>,
+}
+
+/// Find assignments of variables matching `keywords` (all variables when empty)
+pub fn scan_rc_files(home: &Path, keywords: &[&str]) -> Vec<RcAssignment> {
+    let mut result = Vec::new();
+    for rc in load_rc_files(home) {
+        for assignment in &rc.parsed.assignments {
+            let upper = assignment.name.to_uppercase();
+            if !keywords.is_empty() && !keywords.iter().any(|k| upper.contains(k)) {
+                continue;
+            }
+            result.push(RcAssignment {
+                file: rc.display(),
+                line: assignment.start_line,
+                end_line: assignment.end_line,
+                var_name: assignment.name.clone(),
+                var_value: assignment.value.clone(),
+                shell: rc.shell,
+                in_conditional: assignment.in_conditional,
+                shares_line: assignment.shares_line,
+                sourced_from: rc.sourced_from.clone(),
+            });
+        }
+    }
+    result
+}
+
+/// Preview disabling the selected assignments
+pub fn plan_env_fix(
+    home: &Path,
+    targets: Vec<EnvFixTarget>,
+    mode: FixMode,
+) -> Result<EnvFixPlan, String> {
+    build_fix_plan(home, targets, mode, new_fix_id())
+}
+
+/// Preview restoring every line disabled by `fix_id`
+pub fn plan_env_restore(home: &Path, fix_id: &str) -> Result<EnvFixPlan, String> {
+    let files = build_restore_changes(home, fix_id)?;
+    Ok(EnvFixPlan {
+        fix_id: fix_id.to_string(),
+        action: EnvFixAction::Restore,
+        mode: FixMode::default(),
+        targets: Vec::new(),
+        files: files.into_iter().map(|c| c.file).collect(),
+    })
+}
+
+/// Apply a previewed plan, refusing if any file changed since the preview
+pub fn apply_env_plan(home: &Path, plan: &EnvFixPlan) -> Result<(), String> {
+    let changes = match plan.action {
+        EnvFixAction::Fix => build_fix_changes(home, &plan.targets, plan.mode, &plan.fix_id)?,
+        EnvFixAction::Restore => build_restore_changes(home, &plan.fix_id)?,
+    };
+
+    let current: Vec<&EnvFixFile> = changes.iter().map(|c| &c.file).collect();
+    let expected: Vec<&EnvFixFile> = plan.files.iter().collect();
+    if current != expected {
+        return Err("配置文件在预览后已被修改，请重新预览".to_string());
+    }
+
+    for change in &changes {
+        fs::write(&change.path, &change.updated)
+            .map_err(|e| format!("写入文件失败 {}: {e}", change.path.display()))?;
+        log::info!(
+            "已{}环境变量配置: {} ({})",
+            match plan.action {
+                EnvFixAction::Fix => "修复",
+                EnvFixAction::Restore => "恢复",
+            },
+            change.path.display(),
+            plan.fix_id
+        );
+    }
+    Ok(())
+}
+
+/// List fixes that can still be restored
+pub fn list_env_fixes(home: &Path) -> Vec<EnvFixRecord> {
+    let mut records: Vec<EnvFixRecord> = Vec::new();
+    for rc in load_rc_files(home) {
+        for (index, line) in rc.content.lines().enumerate() {
+            let Some((fix_id, _)) = parse_marked_line(line) else {
+                continue;
+            };
+            let file = rc.display();
+            match records
+                .iter_mut()
+                .find(|r| r.fix_id == fix_id && r.file == file)
+            {
+                Some(record) => record.lines.push(index + 1),
+                None => records.push(EnvFixRecord {
+                    fix_id: fix_id.to_string(),
+                    file,
+                    lines: vec![index + 1],
+                }),
+            }
+        }
+    }
+    records.sort_by(|a, b| b.fix_id.cmp(&a.fix_id).then_with(|| a.file.cmp(&b.file)));
+    records
+}
+
+/// Computed change to one file
+struct FileChange {
+    path: PathBuf,
+    updated: String,
+    file: EnvFixFile,
+}
+
+fn build_fix_plan(
+    home: &Path,
+    targets: Vec<EnvFixTarget>,
+    mode: FixMode,
+    fix_id: String,
+) -> Result<EnvFixPlan, String> {
+    let files = build_fix_changes(home, &targets, mode, &fix_id)?;
+    Ok(EnvFixPlan {
+        fix_id,
+        action: EnvFixAction::Fix,
+        mode,
+        targets,
+        files: files.into_iter().map(|c| c.file).collect(),
+    })
+}
+
+fn build_fix_changes(
+    home: &Path,
+    targets: &[EnvFixTarget],
+    mode: FixMode,
+    fix_id: &str,
+) -> Result<Vec<FileChange>, String> {
+    if targets.is_empty() {
+        return Err("未选择要修复的环境变量".to_string());
+    }
+    if !is_valid_fix_id(fix_id) {
+        return Err(format!("无效的修复标识: {fix_id}"));
+    }
+
+    let rc_files = load_rc_files(home);
+    if let Some(target) = targets
+        .iter()
+        .find(|t| !rc_files.iter().any(|rc| rc.display() == t.file))
+    {
+        return Err(format!("不是受支持的 shell 配置文件: {}", target.file));
+    }
+
+    let mut changes = Vec::new();
+    for rc in &rc_files {
+        let file = rc.display();
+        let mut selected = Vec::new();
+        for target in targets.iter().filter(|t| t.file == file) {
+            // Only assignments the parser found can be touched, never arbitrary lines
+            let assignment = rc
+                .parsed
+                .assignments
+                .iter()
+                .find(|a| a.start_line == target.line && a.name == target.var_name)
+                .ok_or_else(|| {
+                    format!(
+                        "未找到环境变量 {} ({}:{})",
+                        target.var_name, target.file, target.line
+                    )
+                })?;
+            selected.push(assignment);
+        }
+        if selected.is_empty() {
+            continue;
+        }
+
+        let mut lines = split_lines(&rc.content);
+        let mut done = HashSet::new();
+        for assignment in selected {
+            let guard = match mode {
+                FixMode::Auto => rc.shell == ShellKind::Posix && assignment.in_conditional,
+                FixMode::Comment => false,
+                FixMode::Guard => true,
+            };
+            for line_no in assignment.start_line..=assignment.end_line {
+                if !done.insert(line_no) {
+                    continue;
+                }
+                let (body, _) = &mut lines[line_no - 1];
+                *body = disable_line(body, fix_id, guard);
+            }
+        }
+
+        changes.push(file_change(rc, join_lines(&lines)));
+    }
+
+    Ok(changes)
+}
+
+fn build_restore_changes(home: &Path, fix_id: &str) -> Result<Vec<FileChange>, String> {
+    let mut changes = Vec::new();
+    for rc in load_rc_files(home) {
+        let mut lines = split_lines(&rc.content);
+        let mut restored = false;
+        for (body, _) in lines.iter_mut() {
+            if let Some((id, original)) = parse_marked_line(body) {
+                if id == fix_id {
+                    *body = original.to_string();
+                    restored = true;
+                }
+            }
+        }
+        if restored {
+            let updated = join_lines(&lines);
+            changes.push(file_change(&rc, updated));
+        }
+    }
+
+    if changes.is_empty() {
+        return Err(format!("未找到修复记录: {fix_id}"));
+    }
+    Ok(changes)
+}
+
+fn file_change(rc: &RcFile, updated: String) -> FileChange {
+    let display = rc.display();
+    FileChange {
+        path: rc.path.clone(),
+        file: EnvFixFile {
+            path: display.clone(),
+            checksum: hex::encode(Sha256::digest(rc.content.as_bytes())),
+            diff: unified_diff(&rc.content, &updated, &display, &display, 3),
+        },
+        updated,
+    }
+}
+
+fn new_fix_id() -> String {
+    let now = Utc::now();
+    format!(
+        "{}-{:04x}",
+        now.format("%Y%m%d%H%M%S"),
+        now.timestamp_subsec_micros() & 0xffff
+    )
+}
+
+fn is_valid_fix_id(fix_id: &str) -> bool {
+    !fix_id.is_empty()
+        && fix_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn marker_comment(fix_id: &str) -> String {
+    format!("# {ENV_FIX_MARKER}[{fix_id}] ")
+}
+
+/// Disable a line, keeping the original text verbatim after the marker
+fn disable_line(body: &str, fix_id: &str, guard: bool) -> String {
+    let indent = &body[..body.len() - body.trim_start().len()];
+    let marker = marker_comment(fix_id);
+    if guard {
+        format!("{indent}true {marker}{body}")
+    } else {
+        format!("{indent}{marker}{body}")
+    }
+}
+
+/// Parse a disabled line into (fix id, original line)
+fn parse_marked_line(body: &str) -> Option<(&str, &str)> {
+    let trimmed = body.trim_start();
+    let trimmed = trimmed.strip_prefix("true ").unwrap_or(trimmed);
+    let rest = trimmed
+        .strip_prefix("# ")?
+        .strip_prefix(ENV_FIX_MARKER)?
+        .strip_prefix('[')?;
+    let (fix_id, original) = rest.split_once("] ")?;
+    is_valid_fix_id(fix_id).then_some((fix_id, original))
+}
+
+/// Split content into (line, terminator) pairs so it can be rebuilt byte for byte
+fn split_lines(content: &str) -> Vec<(String, &'static str)> {
+    content
+        .split_inclusive('\n')
+        .map(|line| {
+            if let Some(body) = line.strip_suffix("\r\n") {
+                (body.to_string(), "\r\n")
+            } else if let Some(body) = line.strip_suffix('\n') {
+                (body.to_string(), "\n")
+            } else {
+                (line.to_string(), "")
+            }
+        })
+        .collect()
+}
+
+fn join_lines(lines: &[(String, &'static str)]) -> String {
+    lines
+        .iter()
+        .map(|(body, terminator)| format!("{body}{terminator}"))
+        .collect()
+}
+
+/// A loaded rc file
+struct RcFile {
+    path: PathBuf,
+    shell: ShellKind,
+    sourced_from: Option<String>,
+    content: String,
+    parsed: ParsedRc,
+}
+
+impl RcFile {
+    fn display(&self) -> String {
+        self.path.to_string_lossy().to_string()
+    }
+}
+
+/// Top-level rc files read by bash, zsh and fish
+fn default_rc_files(home: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = [
+        ".bashrc",
+        ".bash_profile",
+        ".bash_login",
+        ".profile",
+        ".zshenv",
+        ".zprofile",
+        ".zshrc",
+        ".zlogin",
+        ".config/fish/config.fish",
+    ]
+    .iter()
+    .map(|name| home.join(name))
+    .collect();
+
+    if let Ok(entries) = fs::read_dir(home.join(".config/fish/conf.d")) {
+        let mut conf_d: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "fish"))
+            .collect();
+        conf_d.sort();
+        files.extend(conf_d);
+    }
+    files
+}
+
+/// Load the default rc files and everything they source
+fn load_rc_files(home: &Path) -> Vec<RcFile> {
+    let mut queue: Vec<(PathBuf, Option<String>, usize)> = default_rc_files(home)
+        .into_iter()
+        .map(|path| (path, None, 0))
+        .collect();
+    queue.reverse();
+
+    let mut visited = HashSet::new();
+    let mut result = Vec::new();
+    while let Some((path, sourced_from, depth)) = queue.pop() {
+        if !path.is_file() {
+            continue;
+        }
+        let key = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+        if !visited.insert(key) {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+
+        let shell = ShellKind::from_path(&path);
+        let parsed = parse_rc(&content, shell);
+        if depth < MAX_SOURCE_DEPTH {
+            let parent = path.to_string_lossy().to_string();
+            for source in parsed.sources.iter().rev() {
+                if let Some(resolved) = resolve_source_path(source, home) {
+                    queue.push((resolved, Some(parent.clone()), depth + 1));
+                }
+            }
+        }
+
+        result.push(RcFile {
+            path,
+            shell,
+            sourced_from,
+            content,
+            parsed,
+        });
+    }
+    result
+}
+
+/// Resolve the argument of `source` / `.`; paths with other expansions are skipped
+fn resolve_source_path(raw: &str, home: &Path) -> Option<PathBuf> {
+    let raw = unquote(raw);
+    let path = if raw == "~" || raw == "$HOME" || raw == "${HOME}" {
+        home.to_path_buf()
+    } else if let Some(rest) = raw
+        .strip_prefix("~/")
+        .or_else(|| raw.strip_prefix("$HOME/"))
+        .or_else(|| raw.strip_prefix("${HOME}/"))
+    {
+        home.join(rest)
+    } else {
+        PathBuf::from(&raw)
+    };
+
+    let text = path.to_string_lossy();
+    if text.is_empty() || text.contains(['$', '`', '*', '?', '(']) {
+        return None;
+    }
+    // Login shells start in $HOME, so relative includes resolve from there
+    Some(if path.is_absolute() {
+        path
+    } else {
+        home.join(path)
+    })
+}
+
+/// Result of parsing one rc file
+#[derive(Debug, Default)]
+struct ParsedRc {
+    assignments: Vec<ParsedAssignment>,
+    sources: Vec<String>,
+}
+
+#[derive(Debug)]
+struct ParsedAssignment {
+    start_line: usize,
+    end_line: usize,
+    name: String,
+    value: String,
+    in_conditional: bool,
+    shares_line: bool,
+}
+
+fn parse_rc(content: &str, shell: ShellKind) -> ParsedRc {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut parsed = ParsedRc::default();
+    let mut depth = 0usize;
+    let mut index = 0;
+
+    while index < lines.len() {
+        // Join continuation lines and multi-line quoted values into one logical line
+        let start = index;
+        let mut text = lines[index].to_string();
+        while is_incomplete(&text) && index + 1 < lines.len() {
+            index += 1;
+            text.push('\n');
+            text.push_str(lines[index]);
+        }
+        let end = index;
+        index += 1;
+
+        let statements = split_statements(&text);
+        let shares_line = statements.len() > 1;
+        for statement in &statements {
+            let mut words = split_words(statement);
+            while words
+                .first()
+                .is_some_and(|w| matches!(w.as_str(), "then" | "do" | "else" | "{" | "(" | "!"))
+            {
+                words.remove(0);
+            }
+            let Some(first) = words.first().map(String::as_str) else {
+                continue;
+            };
+
+            let (opens, closes) = match shell {
+                ShellKind::Posix => (
+                    matches!(first, "if" | "case" | "while" | "until" | "for" | "select")
+                        || words.last().is_some_and(|w| w == "{"),
+                    matches!(first, "fi" | "esac" | "done" | "}"),
+                ),
+                ShellKind::Fish => (
+                    matches!(
+                        first,
+                        "if" | "for" | "while" | "function" | "begin" | "switch"
+                    ),
+                    first == "end",
+                ),
+            };
+            if closes {
+                depth = depth.saturating_sub(1);
+                continue;
+            }
+            if opens {
+                depth += 1;
+                continue;
+            }
+
+            if first == "source" || (first == "." && shell == ShellKind::Posix) {
+                if let Some(arg) = words.get(1) {
+                    parsed.sources.push(arg.clone());
+                }
+                continue;
+            }
+
+            let found = match shell {
+                ShellKind::Posix => posix_assignments(&words),
+                ShellKind::Fish => fish_assignments(&words),
+            };
+            for (name, value) in found {
+                parsed.assignments.push(ParsedAssignment {
+                    start_line: start + 1,
+                    end_line: end + 1,
+                    name,
+                    value,
+                    in_conditional: depth > 0,
+                    shares_line,
+                });
+            }
+        }
+    }
+    parsed
+}
+
+/// `export A=1 B=2`, `declare -x A=1`, or a plain `A=1`
+fn posix_assignments(words: &[String]) -> Vec<(String, String)> {
+    let rest = match words[0].as_str() {
+        "export" => &words[1..],
+        "declare" | "typeset" => {
+            let exported = words[1..]
+                .iter()
+                .take_while(|w| w.starts_with('-'))
+                .any(|w| w.contains('x'));
+            if !exported {
+                return Vec::new();
+            }
+            &words[1..]
+        }
+        // A plain assignment, but not `VAR=value command`
+        _ if words.iter().all(|w| split_assignment(w).is_some()) => words,
+        _ => return Vec::new(),
+    };
+    rest.iter()
+        .filter(|w| !w.starts_with('-'))
+        .filter_map(|w| split_assignment(w))
+        .collect()
+}
+
+/// `set -gx NAME value...` or `export NAME=value`
+fn fish_assignments(words: &[String]) -> Vec<(String, String)> {
+    match words[0].as_str() {
+        "export" => words[1..]
+            .iter()
+            .filter_map(|w| split_assignment(w))
+            .collect(),
+        "set" => {
+            let flags: Vec<&String> = words[1..]
+                .iter()
+                .take_while(|w| w.starts_with('-'))
+                .collect();
+            let exported = flags
+                .iter()
+                .any(|f| *f == "--export" || (!f.starts_with("--") && f.contains('x')));
+            let Some(name) = words.get(1 + flags.len()) else {
+                return Vec::new();
+            };
+            if !exported || !is_var_name(name) {
+                return Vec::new();
+            }
+            let value = words[2 + flags.len()..]
+                .iter()
+                .map(|w| unquote(w))
+                .collect::<Vec<_>>()
+                .join(" ");
+            vec![(name.clone(), value)]
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn split_assignment(word: &str) -> Option<(String, String)> {
+    let (name, value) = word.split_once('=')?;
+    is_var_name(name).then(|| (name.to_string(), unquote(value)))
+}
+
+fn is_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn unquote(value: &str) -> String {
+    let bytes = value.as_bytes();
+    if bytes.len() >= 2
+        && (bytes[0] == b'"' || bytes[0] == b'\'')
+        && bytes[bytes.len() - 1] == bytes[0]
+    {
+        value[1..value.len() - 1].to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Quote / escape state while scanning shell text
+#[derive(Default)]
+struct ScanState {
+    quote: Option<char>,
+    escaped: bool,
+}
+
+impl ScanState {
+    /// Feed one character, returns true if it is outside quotes and not escaped
+    fn feed(&mut self, c: char) -> bool {
+        if self.escaped {
+            self.escaped = false;
+            return false;
+        }
+        match self.quote {
+            Some('\'') => {
+                if c == '\'' {
+                    self.quote = None;
+                }
+                false
+            }
+            Some(q) => {
+                if c == '\\' {
+                    self.escaped = true;
+                } else if c == q {
+                    self.quote = None;
+                }
+                false
+            }
+            None => match c {
+                '\\' => {
+                    self.escaped = true;
+                    false
+                }
+                '\'' | '"' => {
+                    self.quote = Some(c);
+                    false
+                }
+                _ => true,
+            },
+        }
+    }
+}
+
+/// An open quote or a trailing backslash continues on the next line
+fn is_incomplete(text: &str) -> bool {
+    let mut state = ScanState::default();
+    let mut prev_blank = true;
+    for c in text.chars() {
+        let bare = state.feed(c);
+        if bare && c == '#' && prev_blank {
+            return false;
+        }
+        prev_blank = bare && c.is_whitespace();
+    }
+    state.quote.is_some() || state.escaped
+}
+
+/// Split a logical line into statements on unquoted `;`, `&&`, `||` and newlines,
+/// dropping trailing comments
+fn split_statements(text: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut state = ScanState::default();
+    let mut chars = text.chars().peekable();
+
+    let mut flush = |current: &mut String| {
+        let statement = current.trim();
+        if !statement.is_empty() {
+            statements.push(statement.to_string());
+        }
+        current.clear();
+    };
+
+    while let Some(c) = chars.next() {
+        if !state.feed(c) {
+            current.push(c);
+            continue;
+        }
+        match c {
+            '#' if current.is_empty() || current.ends_with(char::is_whitespace) => break,
+            ';' | '\n' => flush(&mut current),
+            '&' | '|' if chars.peek() == Some(&c) => {
+                chars.next();
+                flush(&mut current);
+            }
+            _ => current.push(c),
+        }
+    }
+    flush(&mut current);
+    statements
+}
+
+/// Split a statement into words on unquoted whitespace, keeping quotes in place
+fn split_words(statement: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut state = ScanState::default();
+    for c in statement.chars() {
+        if state.feed(c) && c.is_whitespace() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(c);
+        }
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(home: &Path, name: &str, content: &str) -> PathBuf {
+        let path = home.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn target(a: &RcAssignment) -> EnvFixTarget {
+        EnvFixTarget {
+            file: a.file.clone(),
+            line: a.line,
+            var_name: a.var_name.clone(),
+        }
+    }
+
+    #[test]
+    fn scans_posix_conditionals_and_sourced_files() {
+        let home = TempDir::new().unwrap();
+        let zshrc = write(
+            home.path(),
+            ".zshrc",
+            "export PATH=$HOME/bin:$PATH\n\
+             if [ -n \"$WORK\" ]; then\n  export ANTHROPIC_BASE_URL=\"https://example.com\"\nfi\n\
+             source ~/.config/ai.sh # keys\n\
+             FOO=1 ANTHROPIC_MODEL=x some-command\n",
+        );
+        let sourced = write(
+            home.path(),
+            ".config/ai.sh",
+            "ANTHROPIC_API_KEY='sk-1'; export OPENAI_API_KEY=sk-2\n\
+             export ANTHROPIC_CUSTOM_HEADERS=\"a: 1\n  b: 2\"\n",
+        );
+
+        let found = scan_rc_files(home.path(), &["ANTHROPIC"]);
+        assert_eq!(found.len(), 3);
+
+        assert_eq!(found[0].file, zshrc.to_string_lossy());
+        assert_eq!(found[0].var_name, "ANTHROPIC_BASE_URL");
+        assert_eq!(found[0].var_value, "https://example.com");
+        assert_eq!(found[0].line, 3);
+        assert!(found[0].in_conditional);
+
+        assert_eq!(found[1].file, sourced.to_string_lossy());
+        assert_eq!(found[1].var_name, "ANTHROPIC_API_KEY");
+        assert!(found[1].shares_line);
+        assert_eq!(
+            found[1].sourced_from.as_deref(),
+            Some(&*zshrc.to_string_lossy())
+        );
+
+        assert_eq!(found[2].var_name, "ANTHROPIC_CUSTOM_HEADERS");
+        assert_eq!((found[2].line, found[2].end_line), (2, 3));
+    }
+
+    #[test]
+    fn scans_fish_set_export() {
+        let home = TempDir::new().unwrap();
+        write(
+            home.path(),
+            ".config/fish/config.fish",
+            "set -gx ANTHROPIC_API_KEY sk-fish\nset -g ANTHROPIC_LOCAL 1\n\
+             if status is-interactive\n    set --export GEMINI_API_KEY 'g k'\nend\n",
+        );
+        write(
+            home.path(),
+            ".config/fish/conf.d/openai.fish",
+            "export OPENAI_BASE_URL=https://o.example.com\n",
+        );
+
+        let found = scan_rc_files(home.path(), &[]);
+        let names: Vec<(&str, &str, bool)> = found
+            .iter()
+            .map(|a| (a.var_name.as_str(), a.var_value.as_str(), a.in_conditional))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("ANTHROPIC_API_KEY", "sk-fish", false),
+                ("GEMINI_API_KEY", "g k", true),
+                ("OPENAI_BASE_URL", "https://o.example.com", false),
+            ]
+        );
+        assert!(found.iter().all(|a| a.shell == ShellKind::Fish));
+    }
+
+    #[test]
+    fn fix_and_restore_round_trip() {
+        let home = TempDir::new().unwrap();
+        let original = "# env\r\nexport ANTHROPIC_API_KEY=sk-1\r\n\
+                        if true; then\r\n\texport ANTHROPIC_BASE_URL=https://a.example.com\r\nfi\r\n\
+                        export KEEP=1";
+        let bashrc = write(home.path(), ".bashrc", original);
+
+        let found = scan_rc_files(home.path(), &["ANTHROPIC"]);
+        let plan = build_fix_plan(
+            home.path(),
+            found.iter().map(target).collect(),
+            FixMode::Auto,
+            "20261018120000-abcd".to_string(),
+        )
+        .unwrap();
+        assert_eq!(plan.files.len(), 1);
+        let diff = &plan.files[0].diff;
+        assert!(diff
+            .contains("+# mnemosyne:env-fix[20261018120000-abcd] export ANTHROPIC_API_KEY=sk-1"));
+        assert!(diff.contains(
+            "+\ttrue # mnemosyne:env-fix[20261018120000-abcd] \texport ANTHROPIC_BASE_URL"
+        ));
+
+        apply_env_plan(home.path(), &plan).unwrap();
+        assert!(scan_rc_files(home.path(), &["ANTHROPIC"]).is_empty());
+        assert_eq!(
+            list_env_fixes(home.path()),
+            vec![EnvFixRecord {
+                fix_id: "20261018120000-abcd".to_string(),
+                file: bashrc.to_string_lossy().to_string(),
+                lines: vec![2, 4],
+            }]
+        );
+
+        let restore = plan_env_restore(home.path(), "20261018120000-abcd").unwrap();
+        apply_env_plan(home.path(), &restore).unwrap();
+        assert_eq!(fs::read_to_string(&bashrc).unwrap(), original);
+        assert!(list_env_fixes(home.path()).is_empty());
+    }
+
+    #[test]
+    fn multi_line_values_are_disabled_together() {
+        let home = TempDir::new().unwrap();
+        write(
+            home.path(),
+            ".zshrc",
+            "export ANTHROPIC_CUSTOM_HEADERS=\"x-a: 1\nx-b: 2\"\nexport OTHER=1\n",
+        );
+        let found = scan_rc_files(home.path(), &["ANTHROPIC"]);
+        let plan = plan_env_fix(home.path(), vec![target(&found[0])], FixMode::Comment).unwrap();
+        apply_env_plan(home.path(), &plan).unwrap();
+
+        let content = fs::read_to_string(home.path().join(".zshrc")).unwrap();
+        let marker = marker_comment(&plan.fix_id);
+        assert_eq!(
+            content,
+            format!(
+                "{marker}export ANTHROPIC_CUSTOM_HEADERS=\"x-a: 1\n{marker}x-b: 2\"\nexport OTHER=1\n"
+            )
+        );
+    }
+
+    #[test]
+    fn apply_rejects_stale_plan_and_unknown_targets() {
+        let home = TempDir::new().unwrap();
+        let zshrc = write(home.path(), ".zshrc", "export ANTHROPIC_API_KEY=sk-1\n");
+        let found = scan_rc_files(home.path(), &[]);
+        let plan = plan_env_fix(home.path(), vec![target(&found[0])], FixMode::Auto).unwrap();
+
+        fs::write(&zshrc, "export ANTHROPIC_API_KEY=sk-2\n").unwrap();
+        assert!(apply_env_plan(home.path(), &plan).is_err());
+        assert_eq!(
+            fs::read_to_string(&zshrc).unwrap(),
+            "export ANTHROPIC_API_KEY=sk-2\n"
+        );
+
+        let outside = write(home.path(), "notes.txt", "export ANTHROPIC_API_KEY=x\n");
+        let err = plan_env_fix(
+            home.path(),
+            vec![EnvFixTarget {
+                file: outside.to_string_lossy().to_string(),
+                line: 1,
+                var_name: "ANTHROPIC_API_KEY".to_string(),
+            }],
+            FixMode::Auto,
+        )
+        .unwrap_err();
+        assert!(err.contains("不是受支持的 shell 配置文件"), "{err}");
+    }
+}
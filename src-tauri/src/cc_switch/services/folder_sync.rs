@@ -0,0 +1,1559 @@
+//! 目录同步
+//!
+//! 将供应商、MCP 服务器、提示词逐条序列化到用户指定的目录（git 工作区、
+//! Syncthing/Dropbox 同步目录等），并合并其他设备写入的变更：
+//!
+//! ```text
+//! <dir>/mnemosyne-sync.json          - 格式版本与加密参数
+//! <dir>/providers/<app>/<id>.json    - 供应商
+//! <dir>/mcp/<id>.json                - MCP 服务器
+//! <dir>/prompts/<app>/<id>.json      - 提示词
+//! <dir>/conflicts/*.json             - 冲突记录
+//! ```
+//!
+//! 每个条目一个文件，删除以墓碑记录表示。本机记录每个条目上次同步时的内容哈希，
+//! 据此判断本机与目录两侧是否有变更；两侧同时变更时按更新时间后写者胜出，
+//! 落败的一方写入冲突记录，不会丢失。
+//!
+//! 密钥可以移除（合并时沿用本机密钥）或用口令加密后写入目录。
+//! 应用远端变更前会创建内存快照，写入失败时整体回滚。
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use base64::prelude::*;
+
+use crate::cc_switch::app_config::{AppType, McpServer};
+use crate::cc_switch::config::atomic_write;
+use crate::cc_switch::error::AppError;
+use crate::cc_switch::prompt::Prompt;
+use crate::cc_switch::provider::Provider;
+use crate::cc_switch::secrets::{self, KdfParams, SecretKey};
+use crate::cc_switch::services::{McpService, PromptService, ProviderService};
+use crate::cc_switch::store::AppState;
+
+/// 同步目录格式标识
+const SYNC_FORMAT: &str = "mnemosyne-sync";
+/// 同步目录格式版本
+const SYNC_VERSION: u32 = 1;
+
+const META_FILE: &str = "mnemosyne-sync.json";
+const PROVIDERS_DIR: &str = "providers";
+const MCP_DIR: &str = "mcp";
+const PROMPTS_DIR: &str = "prompts";
+const CONFLICTS_DIR: &str = "conflicts";
+
+/// 默认同步间隔（秒）
+const DEFAULT_INTERVAL_SECS: u64 = 300;
+/// 最小同步间隔（秒）
+const MIN_INTERVAL_SECS: u64 = 30;
+/// 目录变更检测间隔（秒）
+const WATCH_POLL_SECS: u64 = 5;
+
+/// 参与同步的应用
+const SYNC_APPS: [AppType; 6] = AppType::ALL;
+
+/// 同一时间只允许一次同步（后台监听与手动同步共用）
+static SYNC_LOCK: Mutex<()> = Mutex::new(());
+
+// ============================================================================
+// 配置
+// ============================================================================
+
+/// 密钥处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncSecretsMode {
+    /// 移除密钥，合并时沿用本机密钥
+    #[default]
+    Exclude,
+    /// 用口令加密后写入（所有设备需使用相同口令）
+    Encrypt,
+}
+
+/// 目录同步配置（设备本地，不参与同步）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderSyncConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 同步目录
+    #[serde(default)]
+    pub dir: Option<String>,
+    #[serde(default)]
+    pub secrets_mode: SyncSecretsMode,
+    /// 加密口令（仅 Encrypt 模式）
+    #[serde(default)]
+    pub passphrase: Option<String>,
+    /// 定时同步间隔（秒），目录内容变化时会立即同步
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    /// 本设备标识，写入记录用于区分来源
+    #[serde(default)]
+    pub device_id: String,
+}
+
+fn default_interval_secs() -> u64 {
+    DEFAULT_INTERVAL_SECS
+}
+
+impl Default for FolderSyncConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: None,
+            secrets_mode: SyncSecretsMode::default(),
+            passphrase: None,
+            interval_secs: DEFAULT_INTERVAL_SECS,
+            device_id: String::new(),
+        }
+    }
+}
+
+/// 本机各条目上次同步时的状态
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncBaseEntry {
+    /// 内容哈希（None 表示已删除）
+    #[serde(default)]
+    pub hash: Option<String>,
+    pub updated_at: i64,
+    /// 本机最近一次观察到的内容哈希（同步后清空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_hash: Option<String>,
+    /// 本机观察到内容变化的时间，作为该条目本机修改时间参与后写者判定
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_modified_at: Option<i64>,
+}
+
+impl SyncBaseEntry {
+    /// 本机上次观察到的内容：未记录过本机变化时即上次同步的内容
+    fn observed_hash(&self) -> Option<&String> {
+        if self.local_modified_at.is_some() {
+            self.local_hash.as_ref()
+        } else {
+            self.hash.as_ref()
+        }
+    }
+}
+
+/// 同步目录元数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncMeta {
+    format: String,
+    version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    encryption: Option<KdfParams>,
+    /// 口令校验值（加密的固定内容），用于在写入前发现口令错误
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    key_check: Option<String>,
+}
+
+// ============================================================================
+// 记录
+// ============================================================================
+
+/// 条目类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncEntityKind {
+    Provider,
+    Mcp,
+    Prompt,
+}
+
+/// 目录中的单条记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncRecord {
+    pub kind: SyncEntityKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_type: Option<String>,
+    pub id: String,
+    pub updated_at: i64,
+    pub device_id: String,
+    #[serde(default)]
+    pub deleted: bool,
+    /// 明文内容（Exclude 模式，已移除密钥）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    /// Base64 编码的加密内容（Encrypt 模式）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sealed: Option<String>,
+}
+
+impl SyncRecord {
+    fn key(&self) -> String {
+        entity_key(self.kind, self.app_type.as_deref(), &self.id)
+    }
+
+    /// 记录在同步目录中的相对路径
+    fn relative_path(&self) -> PathBuf {
+        let file = format!("{}.json", file_stem_for_id(&self.id));
+        match (self.kind, self.app_type.as_deref()) {
+            (SyncEntityKind::Provider, Some(app)) => Path::new(PROVIDERS_DIR).join(app).join(file),
+            (SyncEntityKind::Prompt, Some(app)) => Path::new(PROMPTS_DIR).join(app).join(file),
+            _ => Path::new(MCP_DIR).join(file),
+        }
+    }
+}
+
+/// 冲突记录（写入 conflicts 目录）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConflictRecord {
+    pub key: String,
+    pub detected_at: i64,
+    /// 检测到冲突的设备
+    pub device_id: String,
+    pub winner: SyncSide,
+    pub local: SyncRecord,
+    pub remote: SyncRecord,
+}
+
+/// 冲突中的一方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncSide {
+    Local,
+    Remote,
+}
+
+/// 冲突记录及其文件名
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConflictEntry {
+    pub file_name: String,
+    pub record: SyncConflictRecord,
+}
+
+// ============================================================================
+// 同步结果
+// ============================================================================
+
+/// 单条变更
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncChange {
+    pub key: String,
+    pub kind: SyncEntityKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_type: Option<String>,
+    pub id: String,
+    pub deleted: bool,
+}
+
+/// 一次同步的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderSyncReport {
+    pub synced_at: i64,
+    /// 写入目录的本机变更
+    pub pushed: Vec<SyncChange>,
+    /// 应用到本机的远端变更
+    pub pulled: Vec<SyncChange>,
+    /// 本次新产生的冲突记录文件名
+    pub conflicts: Vec<String>,
+    pub warnings: Vec<String>,
+    /// 被远端变更覆盖或删除前的 MCP 服务器，用于清理 live 配置
+    #[serde(skip)]
+    previous_mcp: HashMap<String, McpServer>,
+}
+
+/// 本机条目
+struct LocalEntry {
+    kind: SyncEntityKind,
+    app_type: Option<String>,
+    id: String,
+    value: Value,
+    hash: String,
+    /// 条目自带的更新时间（仅提示词有），其余条目使用观察到的修改时间
+    updated_at: Option<i64>,
+}
+
+/// 目录中的条目（已解密）
+struct RemoteEntry {
+    record: SyncRecord,
+    value: Option<Value>,
+    hash: Option<String>,
+}
+
+/// 条目内容编解码（移除密钥或加密）
+struct RecordCodec {
+    mode: SyncSecretsMode,
+    key: Option<SecretKey>,
+}
+
+impl RecordCodec {
+    /// 本机值 → 写入目录前的可同步值
+    fn syncable(&self, mut value: Value) -> Value {
+        if self.mode == SyncSecretsMode::Exclude {
+            secrets::strip_secrets(&mut value);
+        }
+        value
+    }
+
+    fn seal(&self, record: &mut SyncRecord, value: Value) -> Result<(), AppError> {
+        match &self.key {
+            Some(key) => {
+                let bytes = serde_json::to_vec(&value)
+                    .map_err(|e| AppError::JsonSerialize { source: e })?;
+                let sealed = key.encrypt(&bytes, record.key().as_bytes())?;
+                record.sealed = Some(BASE64_STANDARD.encode(sealed));
+            }
+            None => record.data = Some(value),
+        }
+        Ok(())
+    }
+
+    fn open(&self, record: &SyncRecord) -> Result<Option<Value>, AppError> {
+        if record.deleted {
+            return Ok(None);
+        }
+        match (&self.key, &record.sealed, &record.data) {
+            (Some(key), Some(sealed), _) => {
+                let sealed = BASE64_STANDARD
+                    .decode(sealed)
+                    .map_err(|e| AppError::InvalidInput(format!("加密内容无效: {e}")))?;
+                let bytes = key.decrypt(&sealed, record.key().as_bytes())?;
+                serde_json::from_slice(&bytes)
+                    .map(Some)
+                    .map_err(|e| AppError::InvalidInput(format!("记录内容无效: {e}")))
+            }
+            (None, None, Some(data)) => Ok(Some(data.clone())),
+            _ => Err(AppError::InvalidInput(
+                "记录的加密方式与同步目录不一致".to_string(),
+            )),
+        }
+    }
+}
+
+// ============================================================================
+// 服务
+// ============================================================================
+
+/// 目录同步业务逻辑
+pub struct FolderSyncService;
+
+impl FolderSyncService {
+    /// 获取同步配置（首次调用时生成设备标识）
+    pub fn get_config(state: &AppState) -> Result<FolderSyncConfig, AppError> {
+        let mut config = state.db.get_folder_sync_config()?;
+        if config.device_id.is_empty() {
+            config.device_id = uuid::Uuid::new_v4().to_string();
+            state.db.set_folder_sync_config(&config)?;
+        }
+        Ok(config)
+    }
+
+    /// 更新同步配置
+    ///
+    /// 更换同步目录或密钥处理方式时清空同步状态，下次同步按首次同步处理。
+    pub fn set_config(
+        state: &AppState,
+        mut config: FolderSyncConfig,
+    ) -> Result<FolderSyncConfig, AppError> {
+        let previous = Self::get_config(state)?;
+        config.device_id = previous.device_id.clone();
+        config.interval_secs = config.interval_secs.max(MIN_INTERVAL_SECS);
+        config.dir = config
+            .dir
+            .map(|dir| dir.trim().to_string())
+            .filter(|dir| !dir.is_empty());
+        config.passphrase = config.passphrase.filter(|p| !p.is_empty());
+
+        if config.enabled {
+            let dir = config
+                .dir
+                .as_deref()
+                .ok_or_else(|| AppError::InvalidInput("请先选择同步目录".to_string()))?;
+            if !Path::new(dir).is_dir() {
+                return Err(AppError::InvalidInput(format!("同步目录不存在: {dir}")));
+            }
+            if config.secrets_mode == SyncSecretsMode::Encrypt && config.passphrase.is_none() {
+                return Err(AppError::InvalidInput("加密同步需要设置口令".to_string()));
+            }
+        }
+
+        if config.dir != previous.dir || config.secrets_mode != previous.secrets_mode {
+            state.db.set_folder_sync_state(&BTreeMap::new())?;
+        }
+        state.db.set_folder_sync_config(&config)?;
+        Ok(config)
+    }
+
+    /// 按当前配置立即同步一次
+    pub fn sync_now(state: &AppState) -> Result<FolderSyncReport, AppError> {
+        let config = Self::get_config(state)?;
+        Self::sync_with_config(state, &config)
+    }
+
+    /// 按指定配置同步一次（仅写数据库与同步目录，live 配置由 `apply_to_live` 处理）
+    pub fn sync_with_config(
+        state: &AppState,
+        config: &FolderSyncConfig,
+    ) -> Result<FolderSyncReport, AppError> {
+        let dir = config
+            .dir
+            .as_deref()
+            .map(PathBuf::from)
+            .ok_or_else(|| AppError::InvalidInput("请先选择同步目录".to_string()))?;
+        if !dir.is_dir() {
+            return Err(AppError::InvalidInput(format!(
+                "同步目录不存在: {}",
+                dir.display()
+            )));
+        }
+
+        let _guard = SYNC_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let codec = Self::open_codec(&dir, config)?;
+        let now = chrono::Utc::now().timestamp();
+        let mut report = FolderSyncReport {
+            synced_at: now,
+            ..Default::default()
+        };
+
+        let remote = Self::read_remote(&dir, &codec, &mut report)?;
+        let local = Self::collect_local(state, &codec)?;
+        let mut base = state.db.get_folder_sync_state()?;
+        stamp_local_changes(&local, &mut base, now);
+
+        // 应用远端变更前创建内存快照，任何一步失败都整体回滚数据库
+        let snapshot = state.db.snapshot_to_memory()?;
+        let result = Self::merge(
+            state,
+            &dir,
+            config,
+            &codec,
+            &local,
+            &remote,
+            &mut base,
+            &mut report,
+        );
+        if let Err(err) = result {
+            if let Err(rollback_err) = state.db.restore_from_snapshot(&snapshot) {
+                log::error!("目录同步失败后回滚数据库失败: {rollback_err}");
+            }
+            return Err(err);
+        }
+        state.db.set_folder_sync_state(&base)?;
+
+        if !report.pushed.is_empty() || !report.pulled.is_empty() {
+            log::info!(
+                "目录同步完成：写出 {} 条，应用 {} 条，冲突 {} 条",
+                report.pushed.len(),
+                report.pulled.len(),
+                report.conflicts.len()
+            );
+        }
+        Ok(report)
+    }
+
+    /// 记录本机条目的修改时间（不读写同步目录）
+    ///
+    /// 供应商与 MCP 服务器没有自带的更新时间，后台定期调用以便在同步前
+    /// 记下每个条目内容变化的时间，冲突时按条目比较修改时间而非同步时间。
+    pub fn record_local_changes(
+        state: &AppState,
+        config: &FolderSyncConfig,
+    ) -> Result<(), AppError> {
+        Self::record_local_changes_at(state, config, chrono::Utc::now().timestamp())
+    }
+
+    fn record_local_changes_at(
+        state: &AppState,
+        config: &FolderSyncConfig,
+        now: i64,
+    ) -> Result<(), AppError> {
+        let _guard = SYNC_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        // 只计算可同步内容的哈希，无需解锁加密密钥
+        let codec = RecordCodec {
+            mode: config.secrets_mode,
+            key: None,
+        };
+        let local = Self::collect_local(state, &codec)?;
+        let mut base = state.db.get_folder_sync_state()?;
+        if stamp_local_changes(&local, &mut base, now) {
+            state.db.set_folder_sync_state(&base)?;
+        }
+        Ok(())
+    }
+
+    /// 将已应用的远端变更同步到各应用的 live 配置
+    pub fn apply_to_live(state: &AppState, report: &FolderSyncReport) -> Result<(), AppError> {
+        let pulled_kind = |kind| report.pulled.iter().any(|c| c.kind == kind);
+
+        if pulled_kind(SyncEntityKind::Provider) {
+            ProviderService::sync_current_to_live(state)?;
+        }
+
+        if pulled_kind(SyncEntityKind::Mcp) {
+            let servers = state.db.get_all_mcp_servers()?;
+            for (id, previous) in &report.previous_mcp {
+                match servers.get(id) {
+                    None => McpService::remove_server_from_all_apps(state, id, previous)?,
+                    Some(current) => {
+                        for app in previous.apps.enabled_apps() {
+                            if !current.apps.is_enabled_for(&app) {
+                                McpService::remove_server_from_app(state, id, &app)?;
+                            }
+                        }
+                    }
+                }
+            }
+            McpService::sync_all_enabled(state)?;
+        }
+
+        // 已启用的提示词内容被更新时重新写入文件
+        for change in report
+            .pulled
+            .iter()
+            .filter(|c| c.kind == SyncEntityKind::Prompt && !c.deleted)
+        {
+            let Some(app) = change.app_type.as_deref() else {
+                continue;
+            };
+            let app_type = app.parse::<AppType>()?;
+            if let Some(prompt) = state.db.get_prompts(app)?.shift_remove(&change.id) {
+                if prompt.enabled {
+                    PromptService::upsert_prompt(state, app_type, &change.id, prompt)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 列出同步目录中的冲突记录（按检测时间倒序）
+    pub fn list_conflicts(state: &AppState) -> Result<Vec<SyncConflictEntry>, AppError> {
+        let config = Self::get_config(state)?;
+        let Some(dir) = config.dir.as_deref().map(PathBuf::from) else {
+            return Ok(Vec::new());
+        };
+        let conflicts_dir = dir.join(CONFLICTS_DIR);
+
+        let mut entries = Vec::new();
+        for path in list_json_files(&conflicts_dir)? {
+            let Some(file_name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+                continue;
+            };
+            match read_json::<SyncConflictRecord>(&path) {
+                Ok(record) => entries.push(SyncConflictEntry { file_name, record }),
+                Err(err) => log::warn!("读取冲突记录 {} 失败: {err}", path.display()),
+            }
+        }
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.record.detected_at));
+        Ok(entries)
+    }
+
+    /// 删除（确认）一条冲突记录
+    pub fn dismiss_conflict(state: &AppState, file_name: &str) -> Result<(), AppError> {
+        if file_name.contains(['/', '\\']) || file_name.starts_with('.') {
+            return Err(AppError::InvalidInput(format!(
+                "无效的冲突记录: {file_name}"
+            )));
+        }
+        let config = Self::get_config(state)?;
+        let dir = config
+            .dir
+            .as_deref()
+            .ok_or_else(|| AppError::InvalidInput("请先选择同步目录".to_string()))?;
+        let path = Path::new(dir).join(CONFLICTS_DIR).join(file_name);
+        fs::remove_file(&path).map_err(|e| AppError::io(&path, e))
+    }
+
+    /// 同步目录内容指纹（文件路径、大小、修改时间），用于检测远端变更
+    pub fn folder_fingerprint(dir: &Path) -> Option<String> {
+        let mut hasher = Sha256::new();
+        let mut files = Vec::new();
+        for sub in [PROVIDERS_DIR, MCP_DIR, PROMPTS_DIR] {
+            collect_record_files(&dir.join(sub), &mut files).ok()?;
+        }
+        files.sort();
+        for path in files {
+            let metadata = fs::metadata(&path).ok()?;
+            hasher.update(path.to_string_lossy().as_bytes());
+            hasher.update(metadata.len().to_le_bytes());
+            if let Ok(modified) = metadata.modified() {
+                hasher.update(format!("{modified:?}").as_bytes());
+            }
+        }
+        Some(hex::encode(hasher.finalize()))
+    }
+
+    // ------------------------------------------------------------------------
+    // 内部实现
+    // ------------------------------------------------------------------------
+
+    /// 读取（或初始化）目录元数据并构造编解码器
+    fn open_codec(dir: &Path, config: &FolderSyncConfig) -> Result<RecordCodec, AppError> {
+        let meta_path = dir.join(META_FILE);
+        let mut meta_dirty = !meta_path.exists();
+        let mut meta = if meta_path.exists() {
+            let meta: SyncMeta = read_json(&meta_path)?;
+            if meta.format != SYNC_FORMAT {
+                return Err(AppError::InvalidInput(format!(
+                    "同步目录格式不支持: {}",
+                    meta.format
+                )));
+            }
+            if meta.version > SYNC_VERSION {
+                return Err(AppError::InvalidInput(format!(
+                    "同步目录版本 {} 高于当前支持的版本 {}，请升级应用",
+                    meta.version, SYNC_VERSION
+                )));
+            }
+            meta
+        } else {
+            SyncMeta {
+                format: SYNC_FORMAT.to_string(),
+                version: SYNC_VERSION,
+                encryption: match config.secrets_mode {
+                    SyncSecretsMode::Encrypt => Some(KdfParams::generate()?),
+                    SyncSecretsMode::Exclude => None,
+                },
+                key_check: None,
+            }
+        };
+
+        let key = match (config.secrets_mode, &meta.encryption) {
+            (SyncSecretsMode::Encrypt, Some(params)) => {
+                let passphrase = config
+                    .passphrase
+                    .as_deref()
+                    .ok_or_else(|| AppError::InvalidInput("加密同步需要设置口令".to_string()))?;
+                Some(SecretKey::derive(passphrase, params)?)
+            }
+            (SyncSecretsMode::Exclude, None) => None,
+            (SyncSecretsMode::Encrypt, None) => {
+                return Err(AppError::InvalidInput(
+                    "同步目录未加密，请改用移除密钥模式".to_string(),
+                ))
+            }
+            (SyncSecretsMode::Exclude, Some(_)) => {
+                return Err(AppError::InvalidInput(
+                    "同步目录已加密，请改用加密模式并输入口令".to_string(),
+                ))
+            }
+        };
+
+        // 口令错误时直接失败，避免用错误的密钥覆盖其他设备写入的记录
+        if let Some(key) = &key {
+            match &meta.key_check {
+                Some(check) => {
+                    let sealed = BASE64_STANDARD
+                        .decode(check)
+                        .map_err(|e| AppError::InvalidInput(format!("口令校验值无效: {e}")))?;
+                    key.decrypt(&sealed, META_FILE.as_bytes())
+                        .map_err(|_| AppError::InvalidInput("同步口令错误".to_string()))?;
+                }
+                None => {
+                    let sealed = key.encrypt(SYNC_FORMAT.as_bytes(), META_FILE.as_bytes())?;
+                    meta.key_check = Some(BASE64_STANDARD.encode(sealed));
+                    meta_dirty = true;
+                }
+            }
+        }
+        if meta_dirty {
+            write_json(&meta_path, &meta)?;
+        }
+
+        Ok(RecordCodec {
+            mode: config.secrets_mode,
+            key,
+        })
+    }
+
+    /// 读取目录中的全部记录
+    fn read_remote(
+        dir: &Path,
+        codec: &RecordCodec,
+        report: &mut FolderSyncReport,
+    ) -> Result<HashMap<String, RemoteEntry>, AppError> {
+        let mut files = Vec::new();
+        for sub in [PROVIDERS_DIR, MCP_DIR, PROMPTS_DIR] {
+            collect_record_files(&dir.join(sub), &mut files)?;
+        }
+
+        let mut remote: HashMap<String, RemoteEntry> = HashMap::new();
+        for path in files {
+            let record = match read_json::<SyncRecord>(&path) {
+                Ok(record) => record,
+                Err(err) => {
+                    report
+                        .warnings
+                        .push(format!("跳过无法解析的记录 {}: {err}", path.display()));
+                    continue;
+                }
+            };
+            let value = match codec.open(&record) {
+                Ok(value) => value,
+                Err(err) => {
+                    report
+                        .warnings
+                        .push(format!("跳过无法读取的记录 {}: {err}", path.display()));
+                    continue;
+                }
+            };
+
+            let key = record.key();
+            // 同一条目出现多个文件时（如手动复制），保留较新的一份
+            if remote
+                .get(&key)
+                .is_some_and(|existing| existing.record.updated_at >= record.updated_at)
+            {
+                continue;
+            }
+            let hash = value.as_ref().map(content_hash).transpose()?;
+            remote.insert(
+                key,
+                RemoteEntry {
+                    record,
+                    value,
+                    hash,
+                },
+            );
+        }
+        Ok(remote)
+    }
+
+    /// 收集本机条目
+    fn collect_local(
+        state: &AppState,
+        codec: &RecordCodec,
+    ) -> Result<HashMap<String, LocalEntry>, AppError> {
+        let mut local = HashMap::new();
+        let mut insert = |kind: SyncEntityKind,
+                          app_type: Option<&str>,
+                          id: &str,
+                          value: Value,
+                          updated_at: Option<i64>|
+         -> Result<(), AppError> {
+            let value = codec.syncable(value);
+            let hash = content_hash(&value)?;
+            local.insert(
+                entity_key(kind, app_type, id),
+                LocalEntry {
+                    kind,
+                    app_type: app_type.map(str::to_string),
+                    id: id.to_string(),
+                    value,
+                    hash,
+                    updated_at,
+                },
+            );
+            Ok(())
+        };
+
+        for app in SYNC_APPS {
+            for (id, provider) in state.db.get_all_providers(app.as_str())? {
+                let value = to_value(&provider)?;
+                insert(
+                    SyncEntityKind::Provider,
+                    Some(app.as_str()),
+                    &id,
+                    value,
+                    None,
+                )?;
+            }
+            for (id, mut prompt) in state.db.get_prompts(app.as_str())? {
+                // 启用状态因设备而异，不参与同步
+                prompt.enabled = false;
+                let updated_at = prompt.updated_at;
+                let value = to_value(&prompt)?;
+                insert(
+                    SyncEntityKind::Prompt,
+                    Some(app.as_str()),
+                    &id,
+                    value,
+                    updated_at,
+                )?;
+            }
+        }
+        for (id, server) in state.db.get_all_mcp_servers()? {
+            insert(SyncEntityKind::Mcp, None, &id, to_value(&server)?, None)?;
+        }
+        Ok(local)
+    }
+
+    /// 逐条合并本机与目录中的条目
+    #[allow(clippy::too_many_arguments)]
+    fn merge(
+        state: &AppState,
+        dir: &Path,
+        config: &FolderSyncConfig,
+        codec: &RecordCodec,
+        local: &HashMap<String, LocalEntry>,
+        remote: &HashMap<String, RemoteEntry>,
+        base: &mut BTreeMap<String, SyncBaseEntry>,
+        report: &mut FolderSyncReport,
+    ) -> Result<(), AppError> {
+        let now = report.synced_at;
+        let keys: BTreeSet<&String> = local.keys().chain(remote.keys()).collect();
+
+        for key in keys {
+            let local_entry = local.get(key);
+            let remote_entry = remote.get(key);
+            let base_hash = base.get(key).and_then(|b| b.hash.clone());
+            let local_hash = local_entry.map(|l| l.hash.clone());
+
+            let local_changed = local_hash != base_hash;
+            let remote_changed = remote_entry.is_some_and(|r| r.hash != base_hash);
+            // 本机修改时间：提示词自带更新时间，其余取观察到内容变化的时间
+            let local_time = local_entry
+                .and_then(|l| l.updated_at)
+                .or_else(|| base.get(key).and_then(|b| b.local_modified_at))
+                .unwrap_or(now);
+
+            match (local_changed, remote_changed) {
+                (false, false) => {
+                    // 目录中缺失（如被手动删除）时重新写出
+                    if let (Some(entry), None) = (local_entry, remote_entry) {
+                        let updated_at = base.get(key).map_or(now, |b| b.updated_at);
+                        Self::push_local(dir, config, codec, entry, updated_at)?;
+                    }
+                }
+                (true, false) => {
+                    let record = Self::build_record(
+                        config,
+                        codec,
+                        key,
+                        local_entry,
+                        remote_entry,
+                        local_time,
+                    )?;
+                    write_record(dir, &record)?;
+                    report.pushed.push(change_of(&record));
+                    base.insert(key.clone(), base_entry(local_hash, local_time));
+                }
+                (false, true) => {
+                    let remote_entry = remote_entry.expect("remote changed implies present");
+                    Self::pull(state, codec, local_entry, remote_entry, report)?;
+                    base.insert(
+                        key.clone(),
+                        base_entry(remote_entry.hash.clone(), remote_entry.record.updated_at),
+                    );
+                }
+                (true, true) => {
+                    let remote_entry = remote_entry.expect("remote changed implies present");
+                    if local_hash == remote_entry.hash {
+                        base.insert(
+                            key.clone(),
+                            base_entry(local_hash, remote_entry.record.updated_at),
+                        );
+                        continue;
+                    }
+
+                    // 双方都有变更：按条目修改时间后写者胜出，相同时按设备标识决定
+                    let remote = &remote_entry.record;
+                    let local_wins = (local_time, config.device_id.as_str())
+                        > (remote.updated_at, remote.device_id.as_str());
+
+                    let local_record = Self::build_record(
+                        config,
+                        codec,
+                        key,
+                        local_entry,
+                        Some(remote_entry),
+                        local_time,
+                    )?;
+                    let file_name = Self::write_conflict(
+                        dir,
+                        config,
+                        key,
+                        if local_wins {
+                            SyncSide::Local
+                        } else {
+                            SyncSide::Remote
+                        },
+                        &local_record,
+                        remote,
+                        now,
+                    )?;
+                    report.conflicts.push(file_name);
+
+                    if local_wins {
+                        write_record(dir, &local_record)?;
+                        report.pushed.push(change_of(&local_record));
+                        base.insert(key.clone(), base_entry(local_hash, local_time));
+                    } else {
+                        Self::pull(state, codec, local_entry, remote_entry, report)?;
+                        base.insert(
+                            key.clone(),
+                            base_entry(remote_entry.hash.clone(), remote.updated_at),
+                        );
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 构造本机条目（或本机删除）对应的记录
+    fn build_record(
+        config: &FolderSyncConfig,
+        codec: &RecordCodec,
+        key: &str,
+        local_entry: Option<&LocalEntry>,
+        remote_entry: Option<&RemoteEntry>,
+        updated_at: i64,
+    ) -> Result<SyncRecord, AppError> {
+        match local_entry {
+            Some(entry) => {
+                let mut record = SyncRecord {
+                    kind: entry.kind,
+                    app_type: entry.app_type.clone(),
+                    id: entry.id.clone(),
+                    updated_at,
+                    device_id: config.device_id.clone(),
+                    deleted: false,
+                    data: None,
+                    sealed: None,
+                };
+                codec.seal(&mut record, entry.value.clone())?;
+                Ok(record)
+            }
+            None => {
+                let remote = remote_entry
+                    .map(|r| &r.record)
+                    .ok_or_else(|| AppError::Message(format!("同步条目 {key} 不存在")))?;
+                Ok(SyncRecord {
+                    updated_at,
+                    device_id: config.device_id.clone(),
+                    deleted: true,
+                    data: None,
+                    sealed: None,
+                    ..remote.clone()
+                })
+            }
+        }
+    }
+
+    fn push_local(
+        dir: &Path,
+        config: &FolderSyncConfig,
+        codec: &RecordCodec,
+        entry: &LocalEntry,
+        updated_at: i64,
+    ) -> Result<(), AppError> {
+        let key = entity_key(entry.kind, entry.app_type.as_deref(), &entry.id);
+        let record = Self::build_record(config, codec, &key, Some(entry), None, updated_at)?;
+        write_record(dir, &record)
+    }
+
+    /// 将远端记录应用到本机数据库
+    fn pull(
+        state: &AppState,
+        codec: &RecordCodec,
+        local_entry: Option<&LocalEntry>,
+        remote_entry: &RemoteEntry,
+        report: &mut FolderSyncReport,
+    ) -> Result<(), AppError> {
+        let record = &remote_entry.record;
+        let app = record.app_type.as_deref().unwrap_or_default();
+
+        let Some(mut value) = remote_entry.value.clone() else {
+            // 远端删除
+            if local_entry.is_none() {
+                return Ok(());
+            }
+            match record.kind {
+                SyncEntityKind::Provider => {
+                    if state.db.get_current_provider(app)?.as_deref() == Some(record.id.as_str()) {
+                        report.warnings.push(format!(
+                            "供应商 {} 是 {app} 的当前供应商，已忽略远端删除",
+                            record.id
+                        ));
+                        return Ok(());
+                    }
+                    state.db.delete_provider(app, &record.id)?;
+                }
+                SyncEntityKind::Mcp => {
+                    if let Some(previous) = state.db.get_all_mcp_servers()?.shift_remove(&record.id)
+                    {
+                        report.previous_mcp.insert(record.id.clone(), previous);
+                    }
+                    state.db.delete_mcp_server(&record.id)?;
+                }
+                SyncEntityKind::Prompt => {
+                    let prompts = state.db.get_prompts(app)?;
+                    if prompts.get(&record.id).is_some_and(|p| p.enabled) {
+                        report.warnings.push(format!(
+                            "提示词 {} 在 {app} 中已启用，已忽略远端删除",
+                            record.id
+                        ));
+                        return Ok(());
+                    }
+                    state.db.delete_prompt(app, &record.id)?;
+                }
+            }
+            report.pulled.push(change_of(record));
+            return Ok(());
+        };
+
+        // 移除密钥模式下沿用本机密钥
+        if codec.mode == SyncSecretsMode::Exclude {
+            let existing = match record.kind {
+                SyncEntityKind::Provider => state
+                    .db
+                    .get_all_providers(app)?
+                    .shift_remove(&record.id)
+                    .map(|p| to_value(&p))
+                    .transpose()?,
+                SyncEntityKind::Mcp => state
+                    .db
+                    .get_all_mcp_servers()?
+                    .shift_remove(&record.id)
+                    .map(|s| to_value(&s))
+                    .transpose()?,
+                SyncEntityKind::Prompt => None,
+            };
+            if let Some(existing) = existing {
+                secrets::restore_secrets(&mut value, &existing);
+            }
+        }
+
+        match record.kind {
+            SyncEntityKind::Provider => {
+                let mut provider: Provider = from_value(value, record)?;
+                provider.id = record.id.clone();
+                if secrets::has_stripped_secrets(&provider.settings_config) {
+                    report.warnings.push(format!(
+                        "供应商「{}」（{app}）缺少密钥，请手动补全",
+                        provider.name
+                    ));
+                }
+                state.db.save_provider(app, &provider)?;
+            }
+            SyncEntityKind::Mcp => {
+                let mut server: McpServer = from_value(value, record)?;
+                server.id = record.id.clone();
+                if let Some(previous) = state.db.get_all_mcp_servers()?.shift_remove(&record.id) {
+                    report.previous_mcp.insert(record.id.clone(), previous);
+                }
+                state.db.save_mcp_server(&server)?;
+            }
+            SyncEntityKind::Prompt => {
+                let mut prompt: Prompt = from_value(value, record)?;
+                prompt.id = record.id.clone();
+                // 保留本机启用状态
+                prompt.enabled = state
+                    .db
+                    .get_prompts(app)?
+                    .get(&record.id)
+                    .is_some_and(|p| p.enabled);
+                state.db.save_prompt(app, &prompt)?;
+            }
+        }
+        report.pulled.push(change_of(record));
+        Ok(())
+    }
+
+    /// 写入冲突记录，返回文件名
+    fn write_conflict(
+        dir: &Path,
+        config: &FolderSyncConfig,
+        key: &str,
+        winner: SyncSide,
+        local: &SyncRecord,
+        remote: &SyncRecord,
+        now: i64,
+    ) -> Result<String, AppError> {
+        let conflict = SyncConflictRecord {
+            key: key.to_string(),
+            detected_at: now,
+            device_id: config.device_id.clone(),
+            winner,
+            local: local.clone(),
+            remote: remote.clone(),
+        };
+        let device = config.device_id.chars().take(8).collect::<String>();
+        let file_name = format!("{now}-{device}-{}.json", file_stem_for_id(key));
+        write_json(&dir.join(CONFLICTS_DIR).join(&file_name), &conflict)?;
+        log::warn!("目录同步检测到冲突: {key}（胜出方: {winner:?}）");
+        Ok(file_name)
+    }
+}
+
+/// 后台监听同步目录：目录内容变化或到达同步间隔时执行同步，并在应用远端变更后通知前端
+pub async fn run_folder_sync_watcher(app: tauri::AppHandle) {
+    use tauri::{Emitter, Manager};
+
+    let mut last_fingerprint: Option<String> = None;
+    let mut last_sync: Option<std::time::Instant> = None;
+
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(WATCH_POLL_SECS)).await;
+
+        let db = app.state::<AppState>().db.clone();
+        let config = match db.get_folder_sync_config() {
+            Ok(config) => config,
+            Err(e) => {
+                log::warn!("读取目录同步配置失败: {e}");
+                continue;
+            }
+        };
+        let Some(dir) = config.dir.as_deref().filter(|_| config.enabled) else {
+            last_fingerprint = None;
+            continue;
+        };
+
+        // 每次轮询记下本机条目的修改时间，冲突时按条目判定后写者
+        if let Err(e) = FolderSyncService::record_local_changes(&app.state::<AppState>(), &config) {
+            log::warn!("记录本机条目修改时间失败: {e}");
+        }
+
+        let fingerprint = FolderSyncService::folder_fingerprint(Path::new(dir));
+        let due = last_sync.is_none_or(|t| t.elapsed().as_secs() >= config.interval_secs);
+        if !due && fingerprint == last_fingerprint {
+            continue;
+        }
+
+        // 使用托管的 AppState，以便写入 live 配置时识别正在运行的代理接管
+        let handle = app.clone();
+        let result = tauri::async_runtime::spawn_blocking(move || {
+            let state = handle.state::<AppState>();
+            let report = FolderSyncService::sync_now(&state)?;
+            if !report.pulled.is_empty() {
+                FolderSyncService::apply_to_live(&state, &report)?;
+            }
+            Ok::<_, AppError>(report)
+        })
+        .await;
+
+        last_sync = Some(std::time::Instant::now());
+        last_fingerprint = FolderSyncService::folder_fingerprint(Path::new(dir));
+
+        match result {
+            Ok(Ok(report)) => {
+                if !report.pulled.is_empty() || !report.conflicts.is_empty() {
+                    if let Err(e) = app.emit("folder-sync-applied", &report) {
+                        log::error!("发送目录同步事件失败: {e}");
+                    }
+                }
+            }
+            Ok(Err(e)) => log::warn!("目录同步失败: {e}"),
+            Err(e) => log::error!("目录同步任务异常: {e}"),
+        }
+    }
+}
+
+// ============================================================================
+// 辅助函数
+// ============================================================================
+
+fn entity_key(kind: SyncEntityKind, app_type: Option<&str>, id: &str) -> String {
+    match (kind, app_type) {
+        (SyncEntityKind::Provider, Some(app)) => format!("provider:{app}:{id}"),
+        (SyncEntityKind::Prompt, Some(app)) => format!("prompt:{app}:{id}"),
+        _ => format!("mcp:{id}"),
+    }
+}
+
+fn change_of(record: &SyncRecord) -> SyncChange {
+    SyncChange {
+        key: record.key(),
+        kind: record.kind,
+        app_type: record.app_type.clone(),
+        id: record.id.clone(),
+        deleted: record.deleted,
+    }
+}
+
+fn base_entry(hash: Option<String>, updated_at: i64) -> SyncBaseEntry {
+    SyncBaseEntry {
+        hash,
+        updated_at,
+        ..Default::default()
+    }
+}
+
+/// 对比本机条目与上次观察到的内容，为发生变化的条目记下修改时间
+///
+/// 返回是否有条目被更新。
+fn stamp_local_changes(
+    local: &HashMap<String, LocalEntry>,
+    base: &mut BTreeMap<String, SyncBaseEntry>,
+    now: i64,
+) -> bool {
+    let mut changed = false;
+    let deleted: Vec<String> = base
+        .iter()
+        .filter(|(key, entry)| !local.contains_key(*key) && entry.observed_hash().is_some())
+        .map(|(key, _)| key.clone())
+        .collect();
+    let present = local
+        .iter()
+        .map(|(key, entry)| (key.clone(), Some(&entry.hash)));
+    for (key, hash) in present.chain(deleted.into_iter().map(|key| (key, None))) {
+        let entry = base.entry(key).or_default();
+        if entry.observed_hash() != hash {
+            entry.local_hash = hash.cloned();
+            entry.local_modified_at = Some(now);
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// ID 转为安全的文件名；含特殊字符时附加哈希避免不同 ID 映射到同一文件
+fn file_stem_for_id(id: &str) -> String {
+    let sanitized: String = id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if sanitized == id {
+        sanitized
+    } else {
+        let digest = hex::encode(Sha256::digest(id.as_bytes()));
+        format!("{sanitized}-{}", &digest[..8])
+    }
+}
+
+fn content_hash(value: &Value) -> Result<String, AppError> {
+    let bytes = serde_json::to_vec(value).map_err(|e| AppError::JsonSerialize { source: e })?;
+    Ok(hex::encode(Sha256::digest(bytes)))
+}
+
+fn to_value<T: Serialize>(value: &T) -> Result<Value, AppError> {
+    serde_json::to_value(value).map_err(|e| AppError::JsonSerialize { source: e })
+}
+
+fn from_value<T: serde::de::DeserializeOwned>(
+    value: Value,
+    record: &SyncRecord,
+) -> Result<T, AppError> {
+    serde_json::from_value(value)
+        .map_err(|e| AppError::InvalidInput(format!("同步记录 {} 内容无效: {e}", record.key())))
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, AppError> {
+    let text = fs::read_to_string(path).map_err(|e| AppError::io(path, e))?;
+    serde_json::from_str(&text)
+        .map_err(|e| AppError::InvalidInput(format!("{} 解析失败: {e}", path.display())))
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), AppError> {
+    let mut text =
+        serde_json::to_string_pretty(value).map_err(|e| AppError::JsonSerialize { source: e })?;
+    text.push('\n');
+    // 内容未变化时不重写，避免同步工具产生无意义的变更
+    if fs::read_to_string(path).is_ok_and(|existing| existing == text) {
+        return Ok(());
+    }
+    atomic_write(path, text.as_bytes())
+}
+
+fn write_record(dir: &Path, record: &SyncRecord) -> Result<(), AppError> {
+    write_json(&dir.join(record.relative_path()), record)
+}
+
+/// 列出目录下的 JSON 文件（不递归，目录不存在时为空）
+fn list_json_files(dir: &Path) -> Result<Vec<PathBuf>, AppError> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(AppError::io(dir, e)),
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.is_file()
+                && path.extension().is_some_and(|ext| ext == "json")
+                && !path
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// 收集记录文件（providers/prompts 下多一层应用目录）
+fn collect_record_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), AppError> {
+    files.extend(list_json_files(dir)?);
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(AppError::io(dir, e)),
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            files.extend(list_json_files(&path)?);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cc_switch::app_config::McpApps;
+    use crate::cc_switch::database::Database;
+    use serde_json::json;
+    use std::sync::Arc;
+    use tempfile::{tempdir, TempDir};
+
+    struct Device {
+        state: AppState,
+        dir: TempDir,
+        config: FolderSyncConfig,
+    }
+
+    impl Device {
+        fn new(device_id: &str, secrets_mode: SyncSecretsMode) -> Self {
+            let dir = tempdir().expect("tempdir");
+            let config = FolderSyncConfig {
+                enabled: true,
+                dir: Some(dir.path().to_string_lossy().to_string()),
+                secrets_mode,
+                passphrase: Some("team passphrase".to_string()),
+                device_id: device_id.to_string(),
+                ..Default::default()
+            };
+            Self {
+                state: AppState::new(Arc::new(Database::memory().expect("init db"))),
+                dir,
+                config,
+            }
+        }
+
+        fn sync(&self) -> FolderSyncReport {
+            FolderSyncService::sync_with_config(&self.state, &self.config).expect("sync")
+        }
+
+        /// 模拟同步工具：把本设备目录的内容复制到另一台设备
+        fn mirror_to(&self, other: &Device) {
+            copy_dir(self.dir.path(), other.dir.path());
+        }
+    }
+
+    fn copy_dir(from: &Path, to: &Path) {
+        fs::create_dir_all(to).expect("create dir");
+        for entry in fs::read_dir(from).expect("read dir").flatten() {
+            let target = to.join(entry.file_name());
+            if entry.path().is_dir() {
+                copy_dir(&entry.path(), &target);
+            } else {
+                fs::copy(entry.path(), target).expect("copy file");
+            }
+        }
+    }
+
+    fn provider(id: &str, name: &str, token: &str) -> Provider {
+        Provider::with_id(
+            id.to_string(),
+            name.to_string(),
+            json!({
+                "env": {
+                    "ANTHROPIC_BASE_URL": "https://api.example.com",
+                    "ANTHROPIC_AUTH_TOKEN": token
+                }
+            }),
+            None,
+        )
+    }
+
+    fn token_of(device: &Device, id: &str) -> Value {
+        device
+            .state
+            .db
+            .get_all_providers("claude")
+            .expect("providers")[id]
+            .settings_config["env"]["ANTHROPIC_AUTH_TOKEN"]
+            .clone()
+    }
+
+    #[test]
+    fn changes_propagate_without_secrets() {
+        let a = Device::new("device-a", SyncSecretsMode::Exclude);
+        let b = Device::new("device-b", SyncSecretsMode::Exclude);
+
+        a.state
+            .db
+            .save_provider("claude", &provider("p1", "Team", "sk-a"))
+            .expect("save provider");
+        a.state
+            .db
+            .save_mcp_server(&McpServer {
+                id: "fetch".to_string(),
+                name: "Fetch".to_string(),
+                server: json!({ "command": "uvx", "args": ["mcp-server-fetch"] }),
+                apps: McpApps {
+                    claude: true,
+                    ..Default::default()
+                },
+                description: None,
+                homepage: None,
+                docs: None,
+                tags: Vec::new(),
+            })
+            .expect("save mcp");
+        b.state
+            .db
+            .save_provider("claude", &provider("p1", "Team", "sk-b"))
+            .expect("save provider");
+        b.sync();
+
+        let report = a.sync();
+        assert_eq!(report.pushed.len(), 2);
+        let written = fs::read_to_string(a.dir.path().join("providers/claude/p1.json"))
+            .expect("provider record");
+        assert!(!written.contains("sk-a"), "secrets must not be written");
+
+        a.mirror_to(&b);
+        let report = b.sync();
+        assert!(
+            report.conflicts.is_empty(),
+            "identical content is not a conflict"
+        );
+        assert!(b
+            .state
+            .db
+            .get_all_mcp_servers()
+            .expect("servers")
+            .contains_key("fetch"));
+        assert_eq!(token_of(&b, "p1"), "sk-b", "local secret is kept");
+
+        // 远端删除以墓碑同步
+        a.state.db.delete_mcp_server("fetch").expect("delete");
+        assert_eq!(a.sync().pushed.len(), 1);
+        a.mirror_to(&b);
+        let report = b.sync();
+        assert_eq!(report.pulled.len(), 1);
+        assert!(report.pulled[0].deleted);
+        assert!(b
+            .state
+            .db
+            .get_all_mcp_servers()
+            .expect("servers")
+            .is_empty());
+        assert!(report.previous_mcp.contains_key("fetch"));
+    }
+
+    #[test]
+    fn concurrent_edits_resolve_last_writer_wins_with_conflict_record() {
+        let a = Device::new("device-a", SyncSecretsMode::Exclude);
+        let b = Device::new("device-b", SyncSecretsMode::Exclude);
+        for id in ["p1", "p2"] {
+            a.state
+                .db
+                .save_provider("claude", &provider(id, "Team", "sk-a"))
+                .expect("save provider");
+        }
+        a.sync();
+        a.mirror_to(&b);
+        b.sync();
+
+        let now = chrono::Utc::now().timestamp();
+        let edit = |device: &Device, id: &str, name: &str, token: &str, at: Option<i64>| {
+            device
+                .state
+                .db
+                .save_provider("claude", &provider(id, name, token))
+                .expect("save provider");
+            if let Some(at) = at {
+                FolderSyncService::record_local_changes_at(&device.state, &device.config, at)
+                    .expect("record local changes");
+            }
+        };
+
+        // p1：A 的修改较新；p2：B 的修改较新（B 在同步时才观察到）
+        edit(&a, "p2", "Older by A", "sk-a", Some(now - 200));
+        edit(&b, "p1", "Older by B", "", Some(now - 100));
+        edit(&a, "p1", "Newer by A", "sk-a", Some(now - 50));
+        edit(&b, "p2", "Newer by B", "", None);
+
+        a.sync();
+        a.mirror_to(&b);
+
+        // B 最后同步，但每个条目仍由修改时间较新的一方胜出
+        let report = b.sync();
+        assert_eq!(report.conflicts.len(), 2);
+        let conflicts: HashMap<String, SyncConflictRecord> = report
+            .conflicts
+            .iter()
+            .map(|file| {
+                let record: SyncConflictRecord =
+                    read_json(&b.dir.path().join(CONFLICTS_DIR).join(file))
+                        .expect("conflict record");
+                (record.local.id.clone(), record)
+            })
+            .collect();
+        assert_eq!(conflicts["p1"].winner, SyncSide::Remote);
+        assert_eq!(conflicts["p2"].winner, SyncSide::Local);
+        assert_eq!(
+            conflicts["p2"].remote.data.as_ref().unwrap()["name"],
+            "Older by A"
+        );
+        let names = |device: &Device| {
+            let providers = device
+                .state
+                .db
+                .get_all_providers("claude")
+                .expect("providers");
+            (providers["p1"].name.clone(), providers["p2"].name.clone())
+        };
+        assert_eq!(
+            names(&b),
+            ("Newer by A".to_string(), "Newer by B".to_string())
+        );
+        assert_eq!(token_of(&b, "p1"), "", "local secret is kept");
+
+        b.mirror_to(&a);
+        let report = a.sync();
+        assert_eq!(report.pulled.len(), 1);
+        assert!(report.conflicts.is_empty());
+        assert_eq!(
+            names(&a),
+            ("Newer by A".to_string(), "Newer by B".to_string())
+        );
+        assert_eq!(token_of(&a, "p2"), "sk-a", "stripped secret is restored");
+    }
+
+    #[test]
+    fn encrypted_folder_requires_matching_passphrase() {
+        let a = Device::new("device-a", SyncSecretsMode::Encrypt);
+        let b = Device::new("device-b", SyncSecretsMode::Encrypt);
+        a.state
+            .db
+            .save_provider("claude", &provider("p1", "Team", "sk-secret"))
+            .expect("save provider");
+        a.sync();
+
+        let written = fs::read_to_string(a.dir.path().join("providers/claude/p1.json"))
+            .expect("provider record");
+        assert!(!written.contains("sk-secret") && !written.contains("Team"));
+
+        a.mirror_to(&b);
+        let wrong = FolderSyncConfig {
+            passphrase: Some("wrong".to_string()),
+            ..b.config.clone()
+        };
+        let err = FolderSyncService::sync_with_config(&b.state, &wrong)
+            .expect_err("wrong passphrase must fail");
+        assert!(err.to_string().contains("口令错误"));
+
+        let report = b.sync();
+        assert_eq!(report.pulled.len(), 1);
+        assert_eq!(token_of(&b, "p1"), "sk-secret");
+
+        let plain = FolderSyncConfig {
+            secrets_mode: SyncSecretsMode::Exclude,
+            ..b.config.clone()
+        };
+        assert!(FolderSyncService::sync_with_config(&b.state, &plain).is_err());
+    }
+}
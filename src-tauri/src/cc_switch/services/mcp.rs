@@ -2,6 +2,7 @@ use indexmap::IndexMap;
 use std::collections::HashMap;
 
 use crate::cc_switch::app_config::{AppType, McpServer};
+use crate::cc_switch::app_target::{not_ported, target_for, AppTarget};
 use crate::cc_switch::error::AppError;
 use crate::cc_switch::mcp;
 use crate::cc_switch::store::AppState;
@@ -108,6 +109,10 @@ impl McpService {
     }
 
     fn sync_server_to_app_no_config(server: &McpServer, app: &AppType) -> Result<(), AppError> {
+        if let Some(target) = target_for(app) {
+            return Self::sync_server_to_target(target, server);
+        }
+
         match app {
             AppType::Claude => {
                 mcp::sync_single_server_to_claude(&Default::default(), &server.id, &server.server)?;
@@ -116,9 +121,6 @@ impl McpService {
                 // Codex uses TOML format, must use the correct function
                 mcp::sync_single_server_to_codex(&Default::default(), &server.id, &server.server)?;
             }
-            AppType::Gemini => {
-                mcp::sync_single_server_to_gemini(&Default::default(), &server.id, &server.server)?;
-            }
             AppType::OpenCode => {
                 mcp::sync_single_server_to_opencode(
                     &Default::default(),
@@ -129,12 +131,13 @@ impl McpService {
             AppType::Cursor => {
                 mcp::sync_single_server_to_cursor(&Default::default(), &server.id, &server.server)?;
             }
+            _ => return Err(not_ported(app)),
         }
         Ok(())
     }
 
     /// 从所有曾启用过该服务器的应用中移除
-    fn remove_server_from_all_apps(
+    pub(crate) fn remove_server_from_all_apps(
         state: &AppState,
         id: &str,
         server: &McpServer,
@@ -146,17 +149,47 @@ impl McpService {
         Ok(())
     }
 
-    fn remove_server_from_app(_state: &AppState, id: &str, app: &AppType) -> Result<(), AppError> {
+    pub(crate) fn remove_server_from_app(
+        _state: &AppState,
+        id: &str,
+        app: &AppType,
+    ) -> Result<(), AppError> {
+        if let Some(target) = target_for(app) {
+            return Self::remove_server_from_target(target, id);
+        }
+
         match app {
             AppType::Claude => mcp::remove_server_from_claude(id)?,
             AppType::Codex => mcp::remove_server_from_codex(id)?,
-            AppType::Gemini => mcp::remove_server_from_gemini(id)?,
             AppType::OpenCode => {
                 mcp::remove_server_from_opencode(id)?;
             }
             AppType::Cursor => {
                 mcp::remove_server_from_cursor(id)?;
             }
+            _ => return Err(not_ported(app)),
+        }
+        Ok(())
+    }
+
+    /// 通过 [`AppTarget`] 同步单个服务器（应用未安装时跳过）
+    fn sync_server_to_target(target: &dyn AppTarget, server: &McpServer) -> Result<(), AppError> {
+        if !target.is_installed() {
+            return Ok(());
+        }
+        let mut servers = target.read_mcp_servers()?;
+        servers.insert(server.id.clone(), server.server.clone());
+        target.write_mcp_servers(&servers)
+    }
+
+    /// 通过 [`AppTarget`] 移除单个服务器（不存在时不写文件）
+    fn remove_server_from_target(target: &dyn AppTarget, id: &str) -> Result<(), AppError> {
+        if !target.mcp_config_path().exists() {
+            return Ok(());
+        }
+        let mut servers = target.read_mcp_servers()?;
+        if servers.remove(id).is_some() {
+            target.write_mcp_servers(&servers)?;
         }
         Ok(())
     }
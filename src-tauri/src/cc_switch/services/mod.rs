@@ -1,22 +1,34 @@
+pub mod auto_switch;
 pub mod config;
+pub mod config_bundle;
+pub mod endpoint_auto_select;
 pub mod env_checker;
+pub mod env_fixer;
 pub mod env_manager;
+pub mod folder_sync;
 pub mod mcp;
 pub mod prompt;
 pub mod provider;
 pub mod proxy;
 pub mod skill;
+pub mod skill_scan;
+pub mod snapshot;
 pub mod speedtest;
 pub mod stream_check;
 pub mod usage_stats;
 
+pub use auto_switch::AutoSwitchService;
 pub use config::ConfigService;
+pub use config_bundle::ConfigBundleService;
+pub use endpoint_auto_select::EndpointAutoSelectService;
+pub use folder_sync::FolderSyncService;
 pub use mcp::McpService;
 pub use prompt::PromptService;
 pub use provider::{ProviderService, ProviderSortUpdate};
 pub use proxy::ProxyService;
 #[allow(unused_imports)]
 pub use skill::{DiscoverableSkill, Skill, SkillRepo, SkillService};
+pub use snapshot::SnapshotService;
 pub use speedtest::{EndpointLatency, SpeedtestService};
 #[allow(unused_imports)]
 pub use usage_stats::{
@@ -1,11 +1,18 @@
 use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 
 use crate::cc_switch::app_config::AppType;
 use crate::cc_switch::config::write_text_file;
 use crate::cc_switch::error::AppError;
-use crate::cc_switch::prompt::Prompt;
-use crate::cc_switch::prompt_files::prompt_file_path;
+use crate::cc_switch::prompt::{Prompt, PromptFragment, PromptVersion, PromptVersionSource};
+use crate::cc_switch::prompt_files::{prompt_apps, prompt_file_path};
+use crate::cc_switch::prompt_template::{self, RenderedPrompt, TemplateContext};
 use crate::cc_switch::store::AppState;
+use crate::cc_switch::text_diff::{self, DiffKind, DiffLine};
+
+/// 每个提示词（及 live 文件）保留的历史版本数
+const PROMPT_VERSION_RETAIN: usize = 100;
 
 /// 安全地获取当前 Unix 时间戳
 fn get_unix_timestamp() -> Result<i64, AppError> {
@@ -15,6 +22,20 @@ fn get_unix_timestamp() -> Result<i64, AppError> {
         .map_err(|e| AppError::Message(format!("Failed to get system time: {e}")))
 }
 
+/// 两个提示词版本之间的差异
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptVersionDiff {
+    pub from_version_id: i64,
+    /// 为空表示与当前内容对比
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_version_id: Option<i64>,
+    pub added: usize,
+    pub removed: usize,
+    pub lines: Vec<DiffLine>,
+    pub unified: String,
+}
+
 pub struct PromptService;
 
 impl PromptService {
@@ -30,16 +51,40 @@ impl PromptService {
         app: AppType,
         _id: &str,
         prompt: Prompt,
+    ) -> Result<(), AppError> {
+        Self::save_prompt_with_source(state, app, prompt, PromptVersionSource::Save)
+    }
+
+    /// 保存提示词并记录历史版本
+    fn save_prompt_with_source(
+        state: &AppState,
+        app: AppType,
+        prompt: Prompt,
+        source: PromptVersionSource,
     ) -> Result<(), AppError> {
         // 检查是否为已启用的提示词
         let is_enabled = prompt.enabled;
 
+        // 先渲染再保存，模板有误时不落库
+        let live_content = if is_enabled {
+            Some(Self::render_prompt(state, &app, &prompt)?)
+        } else {
+            if prompt.templated {
+                Self::preview(state, app.clone(), &prompt.content)?;
+            }
+            None
+        };
+
+        // 覆盖前保留旧内容（兼容启用历史版本之前保存的提示词）
+        if let Some(existing) = state.db.get_prompts(app.as_str())?.get(&prompt.id) {
+            Self::record_prompt_version(state, &app, existing, PromptVersionSource::Save)?;
+        }
         state.db.save_prompt(app.as_str(), &prompt)?;
+        Self::record_prompt_version(state, &app, &prompt, source)?;
 
-        if is_enabled {
-            // 启用提示词：写入内容到文件
-            let target_path = prompt_file_path(&app)?;
-            write_text_file(&target_path, &prompt.content)?;
+        if let Some(content) = live_content {
+            // 启用提示词：写入（渲染后的）内容到文件
+            Self::write_live_file(state, &app, &content, PromptVersionSource::Write)?;
         } else {
             // 禁用提示词：检查是否还有其他已启用的提示词
             let prompts = state.db.get_prompts(app.as_str())?;
@@ -49,7 +94,7 @@ impl PromptService {
                 // 所有提示词都已禁用，清空文件
                 let target_path = prompt_file_path(&app)?;
                 if target_path.exists() {
-                    write_text_file(&target_path, "")?;
+                    Self::write_live_file(state, &app, "", PromptVersionSource::Write)?;
                 }
             }
         }
@@ -72,51 +117,53 @@ impl PromptService {
 
     pub fn enable_prompt(state: &AppState, app: AppType, id: &str) -> Result<(), AppError> {
         // 回填当前 live 文件内容到已启用的提示词，或创建备份
-        let target_path = prompt_file_path(&app)?;
-        if target_path.exists() {
-            if let Ok(live_content) = std::fs::read_to_string(&target_path) {
-                if !live_content.trim().is_empty() {
-                    let mut prompts = state.db.get_prompts(app.as_str())?;
-
-                    // 尝试回填到当前已启用的提示词
-                    if let Some((enabled_id, enabled_prompt)) = prompts
-                        .iter_mut()
-                        .find(|(_, p)| p.enabled)
-                        .map(|(id, p)| (id.clone(), p))
-                    {
-                        let timestamp = get_unix_timestamp()?;
-                        enabled_prompt.content = live_content.clone();
-                        enabled_prompt.updated_at = Some(timestamp);
-                        log::info!("回填 live 提示词内容到已启用项: {enabled_id}");
-                        state.db.save_prompt(app.as_str(), enabled_prompt)?;
-                    } else {
-                        // 没有已启用的提示词，则创建一次备份（避免重复备份）
-                        let content_exists = prompts
-                            .values()
-                            .any(|p| p.content.trim() == live_content.trim());
-                        if !content_exists {
-                            let timestamp = std::time::SystemTime::now()
-                                .duration_since(std::time::UNIX_EPOCH)
-                                .unwrap_or_default()
-                                .as_secs() as i64;
-                            let backup_id = format!("backup-{timestamp}");
-                            let backup_prompt = Prompt {
-                                id: backup_id.clone(),
-                                name: format!(
-                                    "原始提示词 {}",
-                                    chrono::Local::now().format("%Y-%m-%d %H:%M")
-                                ),
-                                content: live_content,
-                                description: Some("自动备份的原始提示词".to_string()),
-                                enabled: false,
-                                created_at: Some(timestamp),
-                                updated_at: Some(timestamp),
-                            };
-                            log::info!("回填 live 提示词内容，创建备份: {backup_id}");
-                            state.db.save_prompt(app.as_str(), &backup_prompt)?;
-                        }
+        if let Some(live_content) =
+            Self::capture_live_file(state, &app)?.filter(|content| !content.trim().is_empty())
+        {
+            let mut prompts = state.db.get_prompts(app.as_str())?;
+
+            // 尝试回填到当前已启用的提示词
+            let needs_backup = match prompts
+                .iter_mut()
+                .find(|(_, p)| p.enabled)
+                .map(|(id, p)| (id.clone(), p))
+            {
+                Some((enabled_id, enabled_prompt)) if !enabled_prompt.templated => {
+                    let timestamp = get_unix_timestamp()?;
+                    Self::record_prompt_version(
+                        state,
+                        &app,
+                        enabled_prompt,
+                        PromptVersionSource::Save,
+                    )?;
+                    enabled_prompt.content = live_content.clone();
+                    enabled_prompt.updated_at = Some(timestamp);
+                    log::info!("回填 live 提示词内容到已启用项: {enabled_id}");
+                    state.db.save_prompt(app.as_str(), enabled_prompt)?;
+                    Self::record_prompt_version(
+                        state,
+                        &app,
+                        enabled_prompt,
+                        PromptVersionSource::Import,
+                    )?;
+                    false
+                }
+                Some((enabled_id, enabled_prompt)) => {
+                    // 模板提示词保存的是模板而非渲染结果，不能直接回填；
+                    // live 文件与渲染结果不一致说明被手动修改过，改为创建备份
+                    let rendered = Self::render_prompt(state, &app, enabled_prompt).ok();
+                    let modified = rendered.as_deref().map(str::trim) != Some(live_content.trim());
+                    if modified {
+                        log::info!("模板提示词 {enabled_id} 的 live 文件已被修改");
                     }
+                    modified
                 }
+                // 没有已启用的提示词，则创建一次备份
+                None => true,
+            };
+
+            if needs_backup {
+                Self::backup_live_content(state, &app, &prompts, live_content)?;
             }
         }
 
@@ -129,7 +176,8 @@ impl PromptService {
 
         if let Some(prompt) = prompts.get_mut(id) {
             prompt.enabled = true;
-            write_text_file(&target_path, &prompt.content)?; // 原子写入
+            let content = Self::render_prompt(state, &app, prompt)?;
+            Self::write_live_file(state, &app, &content, PromptVersionSource::Write)?; // 原子写入
             state.db.save_prompt(app.as_str(), prompt)?;
         } else {
             return Err(AppError::InvalidInput(format!("提示词 {id} 不存在")));
@@ -143,6 +191,39 @@ impl PromptService {
         Ok(())
     }
 
+    /// 将 live 文件内容保存为备份提示词（内容已存在时跳过，避免重复备份）
+    fn backup_live_content(
+        state: &AppState,
+        app: &AppType,
+        prompts: &IndexMap<String, Prompt>,
+        live_content: String,
+    ) -> Result<(), AppError> {
+        let content_exists = prompts
+            .values()
+            .any(|p| p.content.trim() == live_content.trim());
+        if content_exists {
+            return Ok(());
+        }
+
+        let timestamp = get_unix_timestamp()?;
+        let backup_id = format!("backup-{timestamp}");
+        let backup_prompt = Prompt {
+            id: backup_id.clone(),
+            name: format!(
+                "原始提示词 {}",
+                chrono::Local::now().format("%Y-%m-%d %H:%M")
+            ),
+            content: live_content,
+            description: Some("自动备份的原始提示词".to_string()),
+            enabled: false,
+            templated: false,
+            created_at: Some(timestamp),
+            updated_at: Some(timestamp),
+        };
+        log::info!("回填 live 提示词内容，创建备份: {backup_id}");
+        state.db.save_prompt(app.as_str(), &backup_prompt)
+    }
+
     pub fn import_from_file(state: &AppState, app: AppType) -> Result<String, AppError> {
         let file_path = prompt_file_path(&app)?;
 
@@ -152,6 +233,16 @@ impl PromptService {
 
         let content =
             std::fs::read_to_string(&file_path).map_err(|e| AppError::io(&file_path, e))?;
+        // 导入即检测到的外部修改，先记入 live 文件历史
+        Self::record_version(
+            state,
+            &app,
+            None,
+            None,
+            &content,
+            false,
+            PromptVersionSource::External,
+        )?;
         let timestamp = get_unix_timestamp()?;
 
         let id = format!("imported-{timestamp}");
@@ -164,11 +255,12 @@ impl PromptService {
             content,
             description: Some("从现有配置文件导入".to_string()),
             enabled: false,
+            templated: false,
             created_at: Some(timestamp),
             updated_at: Some(timestamp),
         };
 
-        Self::upsert_prompt(state, app, &id, prompt)?;
+        Self::save_prompt_with_source(state, app, prompt, PromptVersionSource::Import)?;
         Ok(id)
     }
 
@@ -229,15 +321,467 @@ impl PromptService {
             content,
             description: Some("Automatically imported on first launch".to_string()),
             enabled: true, // 首次导入时自动启用
+            templated: false,
             created_at: Some(timestamp),
             updated_at: Some(timestamp),
         };
 
         // 保存到数据库
         state.db.save_prompt(app.as_str(), &prompt)?;
+        Self::record_version(
+            state,
+            &app,
+            None,
+            None,
+            &prompt.content,
+            false,
+            PromptVersionSource::External,
+        )?;
+        Self::record_prompt_version(state, &app, &prompt, PromptVersionSource::Import)?;
 
         log::info!("自动导入完成: {}", app.as_str());
         Ok(1)
     }
+
+    // ========== 历史版本 ==========
+
+    /// 记录历史版本（与该对象最新版本内容相同时跳过）
+    fn record_version(
+        state: &AppState,
+        app: &AppType,
+        prompt_id: Option<&str>,
+        name: Option<&str>,
+        content: &str,
+        templated: bool,
+        source: PromptVersionSource,
+    ) -> Result<(), AppError> {
+        let latest = state
+            .db
+            .get_latest_prompt_version_content(app.as_str(), prompt_id)?;
+        if latest.as_deref() == Some(content) {
+            return Ok(());
+        }
+
+        state.db.insert_prompt_version(&PromptVersion {
+            id: 0,
+            app_type: app.as_str().to_string(),
+            prompt_id: prompt_id.map(str::to_string),
+            name: name.map(str::to_string),
+            content: content.to_string(),
+            templated,
+            source: source.as_str().to_string(),
+            created_at: get_unix_timestamp()?,
+        })?;
+        state
+            .db
+            .prune_prompt_versions(app.as_str(), prompt_id, PROMPT_VERSION_RETAIN)?;
+        Ok(())
+    }
+
+    fn record_prompt_version(
+        state: &AppState,
+        app: &AppType,
+        prompt: &Prompt,
+        source: PromptVersionSource,
+    ) -> Result<(), AppError> {
+        Self::record_version(
+            state,
+            app,
+            Some(&prompt.id),
+            Some(&prompt.name),
+            &prompt.content,
+            prompt.templated,
+            source,
+        )
+    }
+
+    /// 读取 live 文件，并把未记录过的内容（外部手动修改）记入历史
+    fn capture_live_file(state: &AppState, app: &AppType) -> Result<Option<String>, AppError> {
+        let file_path = prompt_file_path(app)?;
+        if !file_path.exists() {
+            return Ok(None);
+        }
+        let content = match std::fs::read_to_string(&file_path) {
+            Ok(content) => content,
+            Err(e) => {
+                log::warn!("读取提示词文件失败: {file_path:?}, 错误: {e}");
+                return Ok(None);
+            }
+        };
+
+        if !content.trim().is_empty() {
+            Self::record_version(
+                state,
+                app,
+                None,
+                None,
+                &content,
+                false,
+                PromptVersionSource::External,
+            )?;
+        }
+        Ok(Some(content))
+    }
+
+    /// 写入 live 文件：先保留即将被覆盖的外部修改，再写入并记录
+    fn write_live_file(
+        state: &AppState,
+        app: &AppType,
+        content: &str,
+        source: PromptVersionSource,
+    ) -> Result<(), AppError> {
+        Self::capture_live_file(state, app)?;
+        let file_path = prompt_file_path(app)?;
+        write_text_file(&file_path, content)?;
+        Self::record_version(state, app, None, None, content, false, source)
+    }
+
+    /// 列出历史版本（最新在前）；`prompt_id` 为空时列出 live 文件的历史
+    pub fn list_versions(
+        state: &AppState,
+        app: AppType,
+        prompt_id: Option<&str>,
+    ) -> Result<Vec<PromptVersion>, AppError> {
+        if prompt_id.is_none() {
+            // 列出前先捕获当前 live 文件，确保未记录的手动修改也能看到
+            Self::capture_live_file(state, &app)?;
+        }
+        state.db.list_prompt_versions(app.as_str(), prompt_id)
+    }
+
+    fn get_version(state: &AppState, app: &AppType, id: i64) -> Result<PromptVersion, AppError> {
+        state
+            .db
+            .get_prompt_version(id)?
+            .filter(|version| version.app_type == app.as_str())
+            .ok_or_else(|| AppError::InvalidInput(format!("提示词版本 {id} 不存在")))
+    }
+
+    /// 版本所属对象的当前内容（提示词已删除 / live 文件不存在时为空）
+    fn current_content(
+        state: &AppState,
+        app: &AppType,
+        version: &PromptVersion,
+    ) -> Result<String, AppError> {
+        match version.prompt_id.as_deref() {
+            Some(prompt_id) => Ok(state
+                .db
+                .get_prompts(app.as_str())?
+                .get(prompt_id)
+                .map(|prompt| prompt.content.clone())
+                .unwrap_or_default()),
+            None => Ok(Self::get_current_file_content(app.clone())?.unwrap_or_default()),
+        }
+    }
+
+    /// 对比两个历史版本；`to_id` 为空时与当前内容对比
+    pub fn diff_versions(
+        state: &AppState,
+        app: AppType,
+        from_id: i64,
+        to_id: Option<i64>,
+    ) -> Result<PromptVersionDiff, AppError> {
+        let from = Self::get_version(state, &app, from_id)?;
+        let (to_label, to_content) = match to_id {
+            Some(to_id) => {
+                let to = Self::get_version(state, &app, to_id)?;
+                if to.prompt_id != from.prompt_id {
+                    return Err(AppError::InvalidInput(
+                        "只能对比同一提示词的历史版本".to_string(),
+                    ));
+                }
+                (format!("version {to_id}"), to.content)
+            }
+            None => (
+                "current".to_string(),
+                Self::current_content(state, &app, &from)?,
+            ),
+        };
+
+        let lines = text_diff::diff_lines(&from.content, &to_content);
+        let unified = text_diff::unified_diff(
+            &from.content,
+            &to_content,
+            &format!("version {from_id}"),
+            &to_label,
+            3,
+        );
+
+        Ok(PromptVersionDiff {
+            from_version_id: from_id,
+            to_version_id: to_id,
+            added: lines.iter().filter(|l| l.kind == DiffKind::Insert).count(),
+            removed: lines.iter().filter(|l| l.kind == DiffKind::Delete).count(),
+            lines,
+            unified,
+        })
+    }
+
+    /// 恢复历史版本
+    ///
+    /// 提示词版本会写回该提示词（已删除时重新创建，已启用时同步到 live 文件）；
+    /// live 文件版本直接写回文件。当前内容会先记入历史，恢复本身可撤销。
+    pub fn restore_version(state: &AppState, app: AppType, id: i64) -> Result<(), AppError> {
+        let version = Self::get_version(state, &app, id)?;
+
+        let Some(prompt_id) = version.prompt_id.clone() else {
+            return Self::write_live_file(
+                state,
+                &app,
+                &version.content,
+                PromptVersionSource::Restore,
+            );
+        };
+
+        let timestamp = get_unix_timestamp()?;
+        let prompt = match state.db.get_prompts(app.as_str())?.get(&prompt_id) {
+            Some(existing) => Prompt {
+                content: version.content,
+                templated: version.templated,
+                updated_at: Some(timestamp),
+                ..existing.clone()
+            },
+            None => Prompt {
+                id: prompt_id.clone(),
+                name: version.name.unwrap_or_else(|| prompt_id.clone()),
+                content: version.content,
+                description: None,
+                enabled: false,
+                templated: version.templated,
+                created_at: Some(timestamp),
+                updated_at: Some(timestamp),
+            },
+        };
+
+        log::info!("恢复提示词 {prompt_id} 到历史版本 {id}");
+        Self::save_prompt_with_source(state, app, prompt, PromptVersionSource::Restore)
+    }
+
+    // ========== 模板与片段 ==========
+
+    /// 构建指定应用的模板渲染上下文
+    fn template_context(state: &AppState, app: &AppType) -> Result<TemplateContext, AppError> {
+        let variables = state.db.get_prompt_variables()?;
+        let fragments: HashMap<String, String> = state
+            .db
+            .get_prompt_fragments()?
+            .into_values()
+            .map(|fragment| (fragment.id, fragment.content))
+            .collect();
+        Ok(TemplateContext::for_app(app, variables, fragments))
+    }
+
+    /// 计算提示词写入文件的内容：模板提示词渲染后返回，普通提示词原样返回
+    pub fn render_prompt(
+        state: &AppState,
+        app: &AppType,
+        prompt: &Prompt,
+    ) -> Result<String, AppError> {
+        if !prompt.templated {
+            return Ok(prompt.content.clone());
+        }
+        let ctx = Self::template_context(state, app)?;
+        let rendered = prompt_template::render(&prompt.content, &ctx)?;
+        rendered.ensure_complete()?;
+        Ok(rendered.content)
+    }
+
+    /// 预览模板渲染结果（允许存在未定义的变量 / 片段，由结果标出）
+    pub fn preview(
+        state: &AppState,
+        app: AppType,
+        content: &str,
+    ) -> Result<RenderedPrompt, AppError> {
+        let ctx = Self::template_context(state, &app)?;
+        prompt_template::render(content, &ctx)
+    }
+
+    pub fn get_fragments(state: &AppState) -> Result<IndexMap<String, PromptFragment>, AppError> {
+        state.db.get_prompt_fragments()
+    }
+
+    pub fn upsert_fragment(state: &AppState, fragment: PromptFragment) -> Result<(), AppError> {
+        prompt_template::validate_identifier("片段", &fragment.id)?;
+
+        // 以新内容替换后检查语法与循环引用
+        let mut ctx = Self::template_context(state, &AppType::Claude)?;
+        ctx.fragments
+            .insert(fragment.id.clone(), fragment.content.clone());
+        prompt_template::render(&format!("{{{{> {}}}}}", fragment.id), &ctx)?;
+
+        let timestamp = get_unix_timestamp()?;
+        let existing = state.db.get_prompt_fragments()?;
+        let fragment = PromptFragment {
+            created_at: existing
+                .get(&fragment.id)
+                .and_then(|f| f.created_at)
+                .or(fragment.created_at)
+                .or(Some(timestamp)),
+            updated_at: Some(timestamp),
+            ..fragment
+        };
+        state.db.save_prompt_fragment(&fragment)?;
+        Self::refresh_templated_live_files(state)
+    }
+
+    /// 删除片段（仍被提示词或其他片段引用时拒绝）
+    pub fn delete_fragment(state: &AppState, id: &str) -> Result<(), AppError> {
+        let mut referrers = Vec::new();
+        for app in prompt_apps() {
+            for prompt in state.db.get_prompts(app.as_str())?.values() {
+                if prompt.templated
+                    && prompt_template::referenced_fragments(&prompt.content)
+                        .iter()
+                        .any(|f| f == id)
+                {
+                    referrers.push(format!("{}/{}", app.as_str(), prompt.name));
+                }
+            }
+        }
+        for fragment in state.db.get_prompt_fragments()?.values() {
+            if fragment.id != id
+                && prompt_template::referenced_fragments(&fragment.content)
+                    .iter()
+                    .any(|f| f == id)
+            {
+                referrers.push(format!("片段 {}", fragment.name));
+            }
+        }
+
+        if !referrers.is_empty() {
+            return Err(AppError::InvalidInput(format!(
+                "片段 {id} 仍被引用: {}",
+                referrers.join(", ")
+            )));
+        }
+
+        state.db.delete_prompt_fragment(id)
+    }
+
+    pub fn get_variables(state: &AppState) -> Result<BTreeMap<String, String>, AppError> {
+        state.db.get_prompt_variables()
+    }
+
+    pub fn set_variable(state: &AppState, name: &str, value: &str) -> Result<(), AppError> {
+        prompt_template::validate_identifier("变量", name)?;
+        state.db.set_prompt_variable(name, value)?;
+        Self::refresh_templated_live_files(state)
+    }
+
+    pub fn delete_variable(state: &AppState, name: &str) -> Result<(), AppError> {
+        state.db.delete_prompt_variable(name)?;
+        Self::refresh_templated_live_files(state)
+    }
+
+    /// 片段或变量变化后重新渲染各应用已启用的模板提示词
+    ///
+    /// 只有渲染结果与 live 文件不同（即引用了变化的片段或变量）时才写入；
+    /// 渲染失败（如引用的变量已删除）时保留原文件并记录警告。
+    fn refresh_templated_live_files(state: &AppState) -> Result<(), AppError> {
+        for app in prompt_apps() {
+            let prompts = state.db.get_prompts(app.as_str())?;
+            let Some(prompt) = prompts.values().find(|p| p.enabled && p.templated) else {
+                continue;
+            };
+            let content = match Self::render_prompt(state, &app, prompt) {
+                Ok(content) => content,
+                Err(e) => {
+                    log::warn!("重新渲染提示词 {} 失败，保留 live 文件: {e}", prompt.id);
+                    continue;
+                }
+            };
+            let current = std::fs::read_to_string(prompt_file_path(&app)?).ok();
+            if current.as_deref() != Some(content.as_str()) {
+                log::info!("模板依赖已变化，重新写入 {} 提示词文件", app.as_str());
+                Self::write_live_file(state, &app, &content, PromptVersionSource::Write)?;
+            }
+        }
+        Ok(())
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cc_switch::database::Database;
+    use serial_test::serial;
+    use std::env;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn templated(id: &str, content: &str) -> Prompt {
+        Prompt {
+            id: id.to_string(),
+            name: id.to_string(),
+            content: content.to_string(),
+            description: None,
+            enabled: true,
+            templated: true,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn fragment_and_variable_changes_rerender_live_files() {
+        let dir = TempDir::new().expect("temp home");
+        let original_home = env::var("HOME").ok();
+        env::set_var("HOME", dir.path());
+        crate::cc_switch::settings::reload_settings().expect("reload settings");
+
+        let state = AppState::new(Arc::new(Database::memory().expect("init db")));
+        PromptService::set_variable(&state, "team", "core").expect("set variable");
+        PromptService::upsert_fragment(
+            &state,
+            PromptFragment {
+                id: "rules".to_string(),
+                name: "rules".to_string(),
+                content: "Be brief.".to_string(),
+                description: None,
+                created_at: None,
+                updated_at: None,
+            },
+        )
+        .expect("save fragment");
+        PromptService::upsert_prompt(
+            &state,
+            AppType::Claude,
+            "main",
+            templated("main", "Team {{team}}. {{> rules}}"),
+        )
+        .expect("save prompt");
+        let live_path = prompt_file_path(&AppType::Claude).expect("prompt path");
+        assert_eq!(
+            std::fs::read_to_string(&live_path).expect("live"),
+            "Team core. Be brief."
+        );
+
+        PromptService::set_variable(&state, "team", "infra").expect("update variable");
+        assert_eq!(
+            std::fs::read_to_string(&live_path).expect("live"),
+            "Team infra. Be brief."
+        );
+
+        let mut fragment =
+            PromptService::get_fragments(&state).expect("fragments")["rules"].clone();
+        fragment.content = "Be thorough.".to_string();
+        PromptService::upsert_fragment(&state, fragment).expect("update fragment");
+        assert_eq!(
+            std::fs::read_to_string(&live_path).expect("live"),
+            "Team infra. Be thorough."
+        );
+
+        // 删除仍被引用的变量时渲染失败，保留原文件
+        PromptService::delete_variable(&state, "team").expect("delete variable");
+        assert_eq!(
+            std::fs::read_to_string(&live_path).expect("live"),
+            "Team infra. Be thorough."
+        );
+
+        match original_home {
+            Some(value) => env::set_var("HOME", value),
+            None => env::remove_var("HOME"),
+        }
+    }
+}
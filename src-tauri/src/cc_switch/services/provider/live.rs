@@ -7,6 +7,7 @@ use std::collections::HashMap;
 use serde_json::{json, Value};
 
 use crate::cc_switch::app_config::AppType;
+use crate::cc_switch::app_target::{not_ported, target_for};
 use crate::cc_switch::codex_config::{get_codex_auth_path, get_codex_config_path};
 use crate::cc_switch::config::{delete_file, get_claude_settings_path, read_json_file, write_json_file};
 use crate::cc_switch::error::AppError;
@@ -93,6 +94,10 @@ impl LiveSnapshot {
 
 /// Write live configuration snapshot for a provider
 pub(crate) fn write_live_snapshot(app_type: &AppType, provider: &Provider) -> Result<(), AppError> {
+    if let Some(target) = target_for(app_type) {
+        return target.write_provider_live(provider);
+    }
+
     match app_type {
         AppType::Claude => {
             let path = get_claude_settings_path();
@@ -115,10 +120,6 @@ pub(crate) fn write_live_snapshot(app_type: &AppType, provider: &Provider) -> Re
             let config_path = get_codex_config_path();
             std::fs::write(&config_path, config_str).map_err(|e| AppError::io(&config_path, e))?;
         }
-        AppType::Gemini => {
-            // Delegate to write_gemini_live which handles env file writing correctly
-            write_gemini_live(provider)?;
-        }
         AppType::OpenCode => {
             // OpenCode uses additive mode - write provider to config
             use crate::cc_switch::opencode_config;
@@ -180,17 +181,40 @@ pub(crate) fn write_live_snapshot(app_type: &AppType, provider: &Provider) -> Re
         AppType::Cursor => {
             // Cursor doesn't support live snapshot
         }
+        _ => return Err(not_ported(app_type)),
     }
     Ok(())
 }
 
+/// 该应用的 live 配置是否正被运行中的代理接管
+///
+/// 接管期间不能用供应商配置覆盖 live 配置（切换供应商走热切换）。
+pub(crate) fn is_live_taken_over(state: &AppState, app_type: &AppType) -> bool {
+    if !futures::executor::block_on(state.proxy_service.is_running()) {
+        return false;
+    }
+    let has_backup = futures::executor::block_on(state.db.get_live_backup(app_type.as_str()))
+        .ok()
+        .flatten()
+        .is_some();
+    has_backup
+        || state
+            .proxy_service
+            .detect_takeover_in_live_config_for_app(app_type)
+}
+
 /// Sync current provider to live configuration
 ///
 /// 使用有效的当前供应商 ID（验证过存在性）。
 /// 优先从本地 settings 读取，验证后 fallback 到数据库的 is_current 字段。
 /// 这确保了配置导入后无效 ID 会自动 fallback 到数据库。
 pub fn sync_current_to_live(state: &AppState) -> Result<(), AppError> {
-    for app_type in [AppType::Claude, AppType::Codex, AppType::Gemini] {
+    for app_type in [
+        AppType::Claude,
+        AppType::Codex,
+        AppType::Gemini,
+        AppType::Qwen,
+    ] {
         // Use validated effective current provider
         let current_id =
             match crate::cc_switch::settings::get_effective_current_provider(&state.db, &app_type)? {
@@ -200,7 +224,17 @@ pub fn sync_current_to_live(state: &AppState) -> Result<(), AppError> {
 
         let providers = state.db.get_all_providers(app_type.as_str())?;
         if let Some(provider) = providers.get(&current_id) {
-            write_live_snapshot(&app_type, provider)?;
+            if is_live_taken_over(state, &app_type) {
+                // 代理接管中：live 配置指向本地代理，只更新 Live 备份，代理停止时再写回
+                futures::executor::block_on(
+                    state
+                        .proxy_service
+                        .update_live_backup_from_provider(app_type.as_str(), provider),
+                )
+                .map_err(|e| AppError::Message(format!("更新 Live 备份失败: {e}")))?;
+            } else {
+                write_live_snapshot(&app_type, provider)?;
+            }
         }
         // Note: get_effective_current_provider already validates existence,
         // so providers.get() should always succeed here
@@ -210,8 +244,15 @@ pub fn sync_current_to_live(state: &AppState) -> Result<(), AppError> {
     McpService::sync_all_enabled(state)?;
 
     // Skill sync
-    for app_type in [AppType::Claude, AppType::Codex, AppType::Gemini] {
-        if let Err(e) = crate::cc_switch::services::skill::SkillService::sync_to_app(&state.db, &app_type) {
+    for app_type in [
+        AppType::Claude,
+        AppType::Codex,
+        AppType::Gemini,
+        AppType::Qwen,
+    ] {
+        if let Err(e) =
+            crate::cc_switch::services::skill::SkillService::sync_to_app(&state.db, &app_type)
+        {
             log::warn!("同步 Skill 到 {app_type:?} 失败: {e}");
             // Continue syncing other apps, don't abort
         }
@@ -222,6 +263,10 @@ pub fn sync_current_to_live(state: &AppState) -> Result<(), AppError> {
 
 /// Read current live settings for an app type
 pub fn read_live_settings(app_type: AppType) -> Result<Value, AppError> {
+    if let Some(target) = target_for(&app_type) {
+        return target.read_provider_live();
+    }
+
     match app_type {
         AppType::Codex => {
             let auth_path = get_codex_auth_path();
@@ -247,10 +292,6 @@ pub fn read_live_settings(app_type: AppType) -> Result<Value, AppError> {
             }
             read_json_file(&path)
         }
-        AppType::Gemini => {
-            let env_map = crate::cc_switch::gemini_config::read_gemini_env()?;
-            Ok(crate::cc_switch::gemini_config::env_to_json(&env_map))
-        }
         AppType::OpenCode => {
             // OpenCode doesn't have a separate live config file
             Ok(json!({}))
@@ -259,6 +300,7 @@ pub fn read_live_settings(app_type: AppType) -> Result<Value, AppError> {
             // Cursor doesn't have a separate live config file
             Ok(json!({}))
         }
+        _ => Err(not_ported(&app_type)),
     }
 }
 
@@ -274,7 +316,30 @@ pub fn import_default_config(state: &AppState, app_type: AppType) -> Result<bool
         }
     }
 
-    let settings_config = match app_type {
+    let settings_config = match target_for(&app_type) {
+        Some(target) => target.read_provider_live()?,
+        None => read_default_config_legacy(&app_type)?,
+    };
+
+    let mut provider = Provider::with_id(
+        "default".to_string(),
+        "default".to_string(),
+        settings_config,
+        None,
+    );
+    provider.category = Some("custom".to_string());
+
+    state.db.save_provider(app_type.as_str(), &provider)?;
+    state
+        .db
+        .set_current_provider(app_type.as_str(), &provider.id)?;
+
+    Ok(true) // 真正导入了
+}
+
+/// 读取尚未迁移到 AppTarget 的应用的默认配置
+fn read_default_config_legacy(app_type: &AppType) -> Result<Value, AppError> {
+    Ok(match app_type {
         AppType::Codex => {
             let auth_path = get_codex_auth_path();
             if !auth_path.exists() {
@@ -299,10 +364,6 @@ pub fn import_default_config(state: &AppState, app_type: AppType) -> Result<bool
             }
             read_json_file(&settings_path)?
         }
-        AppType::Gemini => {
-            let env_map = crate::cc_switch::gemini_config::read_gemini_env()?;
-            crate::cc_switch::gemini_config::env_to_json(&env_map)
-        }
         AppType::OpenCode => {
             // OpenCode doesn't have a separate live config file
             json!({})
@@ -311,22 +372,8 @@ pub fn import_default_config(state: &AppState, app_type: AppType) -> Result<bool
             // Cursor doesn't have a separate live config file
             json!({})
         }
-    };
-
-    let mut provider = Provider::with_id(
-        "default".to_string(),
-        "default".to_string(),
-        settings_config,
-        None,
-    );
-    provider.category = Some("custom".to_string());
-
-    state.db.save_provider(app_type.as_str(), &provider)?;
-    state
-        .db
-        .set_current_provider(app_type.as_str(), &provider.id)?;
-
-    Ok(true) // 真正导入了
+        _ => return Err(not_ported(app_type)),
+    })
 }
 
 /// Write Gemini live configuration with authentication handling
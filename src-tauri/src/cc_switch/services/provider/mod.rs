@@ -13,6 +13,7 @@ use serde::Deserialize;
 use serde_json::Value;
 
 use crate::cc_switch::app_config::AppType;
+use crate::cc_switch::app_target::{not_ported, target_for};
 use crate::cc_switch::error::AppError;
 use crate::cc_switch::provider::{Provider, UsageResult};
 use crate::cc_switch::services::mcp::McpService;
@@ -29,7 +30,7 @@ pub use live::{
 pub(crate) use live::write_live_snapshot;
 
 // Internal re-exports
-use live::{remove_opencode_provider_from_live, write_gemini_live};
+use live::{is_live_taken_over, remove_opencode_provider_from_live, write_gemini_live};
 use usage::validate_usage_script;
 
 /// Provider business logic service
@@ -163,7 +164,7 @@ impl ProviderService {
 
         // OpenCode uses additive mode - always write to live config
         if matches!(app_type, AppType::OpenCode) {
-            write_live_snapshot(&app_type, &provider)?;
+            Self::write_opencode_provider(state, &provider)?;
             return Ok(true);
         }
 
@@ -196,7 +197,7 @@ impl ProviderService {
 
         // OpenCode uses additive mode - always update in live config
         if matches!(app_type, AppType::OpenCode) {
-            write_live_snapshot(&app_type, &provider)?;
+            Self::write_opencode_provider(state, &provider)?;
             return Ok(true);
         }
 
@@ -245,6 +246,13 @@ impl ProviderService {
             state.db.delete_provider(app_type.as_str(), id)?;
             // Also remove from live config
             remove_opencode_provider_from_live(id)?;
+            // 接管期间同时从备份移除，避免停止代理时被恢复回来
+            if Self::is_opencode_taken_over(state) {
+                futures::executor::block_on(
+                    state.proxy_service.remove_opencode_provider_from_backup(id),
+                )
+                .map_err(|e| AppError::Message(format!("更新 Live 备份失败: {e}")))?;
+            }
             return Ok(());
         }
 
@@ -261,6 +269,31 @@ impl ProviderService {
         state.db.delete_provider(app_type.as_str(), id)
     }
 
+    /// OpenCode 代理接管处于激活状态且代理服务正在运行
+    fn is_opencode_taken_over(state: &AppState) -> bool {
+        let has_backup = futures::executor::block_on(state.db.get_live_backup("opencode"))
+            .ok()
+            .flatten()
+            .is_some();
+        has_backup && futures::executor::block_on(state.proxy_service.is_running())
+    }
+
+    /// 写入 OpenCode 供应商到 live 配置
+    ///
+    /// 接管期间写入指向代理的条目并更新备份，否则供应商会绕过代理直连上游。
+    fn write_opencode_provider(state: &AppState, provider: &Provider) -> Result<(), AppError> {
+        if Self::is_opencode_taken_over(state) {
+            return futures::executor::block_on(
+                state
+                    .proxy_service
+                    .write_opencode_provider_under_takeover(provider),
+            )
+            .map_err(AppError::Message);
+        }
+
+        write_live_snapshot(&AppType::OpenCode, provider)
+    }
+
     /// Remove provider from live config only (for additive mode apps like OpenCode)
     ///
     /// Does NOT delete from database - provider remains in the list.
@@ -301,23 +334,8 @@ impl ProviderService {
             .get(id)
             .ok_or_else(|| AppError::Message(format!("供应商 {id} 不存在")))?;
 
-        // Check if proxy takeover mode is active AND proxy server is actually running
-        // Both conditions must be true to use hot-switch mode
-        // Use blocking wait since this is a sync function
-        let is_app_taken_over =
-            futures::executor::block_on(state.db.get_live_backup(app_type.as_str()))
-                .ok()
-                .flatten()
-                .is_some();
-        let is_proxy_running = futures::executor::block_on(state.proxy_service.is_running());
-        let live_taken_over = state
-            .proxy_service
-            .detect_takeover_in_live_config_for_app(&app_type);
-
         // Hot-switch only when BOTH: this app is taken over AND proxy server is actually running
-        let should_hot_switch = (is_app_taken_over || live_taken_over) && is_proxy_running;
-
-        if should_hot_switch {
+        if is_live_taken_over(state, &app_type) {
             // Proxy takeover mode: hot-switch only, don't write Live config
             log::info!(
                 "代理接管模式：热切换 {} 的目标供应商为 {}",
@@ -439,6 +457,7 @@ impl ProviderService {
             AppType::Claude => Self::extract_claude_common_config(&provider.settings_config),
             AppType::Codex => Self::extract_codex_common_config(&provider.settings_config),
             AppType::Gemini => Self::extract_gemini_common_config(&provider.settings_config),
+            AppType::Qwen => Self::extract_qwen_common_config(&provider.settings_config),
             AppType::OpenCode => Self::extract_opencode_common_config(&provider.settings_config),
             AppType::Cursor => {
                 // Cursor doesn't support provider config extraction
@@ -456,6 +475,7 @@ impl ProviderService {
             AppType::Claude => Self::extract_claude_common_config(settings_config),
             AppType::Codex => Self::extract_codex_common_config(settings_config),
             AppType::Gemini => Self::extract_gemini_common_config(settings_config),
+            AppType::Qwen => Self::extract_qwen_common_config(settings_config),
             AppType::OpenCode => Self::extract_opencode_common_config(settings_config),
             AppType::Cursor => {
                 // Cursor doesn't support provider config extraction
@@ -568,12 +588,22 @@ impl ProviderService {
     /// - GOOGLE_GEMINI_BASE_URL
     /// - GEMINI_API_KEY
     fn extract_gemini_common_config(settings: &Value) -> Result<String, AppError> {
+        Self::extract_env_common_config(settings, &["GOOGLE_GEMINI_BASE_URL", "GEMINI_API_KEY"])
+    }
+
+    /// Extract common config for Qwen Code (.env format, same shape as Gemini)
+    fn extract_qwen_common_config(settings: &Value) -> Result<String, AppError> {
+        Self::extract_env_common_config(settings, &["OPENAI_BASE_URL", "OPENAI_API_KEY"])
+    }
+
+    /// Extract non-credential `env` entries as a JSON snippet
+    fn extract_env_common_config(settings: &Value, excludes: &[&str]) -> Result<String, AppError> {
         let env = settings.get("env").and_then(|v| v.as_object());
 
         let mut snippet = serde_json::Map::new();
         if let Some(env) = env {
             for (key, value) in env {
-                if key == "GOOGLE_GEMINI_BASE_URL" || key == "GEMINI_API_KEY" {
+                if excludes.contains(&key.as_str()) {
                     continue;
                 }
                 let Value::String(v) = value else {
@@ -729,6 +759,26 @@ impl ProviderService {
     }
 
     fn validate_provider_settings(app_type: &AppType, provider: &Provider) -> Result<(), AppError> {
+        match target_for(app_type) {
+            Some(target) => target.validate_provider_settings(&provider.settings_config)?,
+            None => Self::validate_provider_settings_legacy(app_type, provider)?,
+        }
+
+        // Validate and clean UsageScript configuration (common for all app types)
+        if let Some(meta) = &provider.meta {
+            if let Some(usage_script) = &meta.usage_script {
+                validate_usage_script(usage_script)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 校验尚未迁移到 AppTarget 的应用的供应商配置
+    fn validate_provider_settings_legacy(
+        app_type: &AppType,
+        provider: &Provider,
+    ) -> Result<(), AppError> {
         match app_type {
             AppType::Claude => {
                 if !provider.settings_config.is_object() {
@@ -779,10 +829,6 @@ impl ProviderService {
                     }
                 }
             }
-            AppType::Gemini => {
-                use crate::cc_switch::gemini_config::validate_gemini_settings;
-                validate_gemini_settings(&provider.settings_config)?
-            }
             AppType::OpenCode => {
                 // OpenCode uses a different config structure: { npm, options, models }
                 // Basic validation - must be an object
@@ -802,13 +848,7 @@ impl ProviderService {
                     "Cursor does not support custom providers",
                 ));
             }
-        }
-
-        // Validate and clean UsageScript configuration (common for all app types)
-        if let Some(meta) = &provider.meta {
-            if let Some(usage_script) = &meta.usage_script {
-                validate_usage_script(usage_script)?;
-            }
+            _ => return Err(not_ported(app_type)),
         }
 
         Ok(())
@@ -939,6 +979,25 @@ impl ProviderService {
 
                 Ok((api_key, base_url))
             }
+            AppType::Qwen => {
+                use crate::cc_switch::gemini_config::json_to_env;
+
+                let env_map = json_to_env(&provider.settings_config)?;
+
+                let api_key = env_map.get("OPENAI_API_KEY").cloned().ok_or_else(|| {
+                    AppError::localized(
+                        "qwen.missing_api_key",
+                        "缺少 OPENAI_API_KEY",
+                        "Missing OPENAI_API_KEY",
+                    )
+                })?;
+
+                let base_url = env_map.get("OPENAI_BASE_URL").cloned().unwrap_or_else(|| {
+                    "https://dashscope.aliyuncs.com/compatible-mode/v1".to_string()
+                });
+
+                Ok((api_key, base_url))
+            }
             AppType::OpenCode => {
                 // OpenCode uses options.apiKey and options.baseURL
                 let options = provider
@@ -19,7 +19,7 @@ pub(crate) async fn execute_and_format_usage_result(
     user_id: Option<&str>,
     template_type: Option<&str>,
 ) -> Result<UsageResult, AppError> {
-    match usage_script::execute_usage_script(
+    let output = usage_script::execute_usage_script(
         script_code,
         api_key,
         base_url,
@@ -28,8 +28,9 @@ pub(crate) async fn execute_and_format_usage_result(
         user_id,
         template_type,
     )
-    .await
-    {
+    .await;
+
+    match output.result {
         Ok(data) => {
             let usage_list: Vec<UsageData> = if data.is_array() {
                 serde_json::from_value(data).map_err(|e| {
@@ -54,6 +55,7 @@ pub(crate) async fn execute_and_format_usage_result(
                 success: true,
                 data: Some(usage_list),
                 error: None,
+                logs: output.logs,
             })
         }
         Err(err) => {
@@ -76,6 +78,7 @@ pub(crate) async fn execute_and_format_usage_result(
                 success: false,
                 data: None,
                 error: Some(msg),
+                logs: output.logs,
             })
         }
     }
@@ -210,11 +210,19 @@ impl ProxyService {
             .await
             .map(|c| c.enabled)
             .unwrap_or(false);
+        let opencode_enabled = self
+            .db
+            .get_proxy_config_for_app("opencode")
+            .await
+            .map(|c| c.enabled)
+            .unwrap_or(false);
 
         Ok(ProxyTakeoverStatus {
             claude: claude_enabled,
             codex: codex_enabled,
             gemini: gemini_enabled,
+            opencode: opencode_enabled,
+            cursor: false,
         })
     }
 
@@ -368,14 +376,15 @@ impl ProxyService {
             AppType::Claude => self.read_claude_live()?,
             AppType::Codex => self.read_codex_live()?,
             AppType::Gemini => self.read_gemini_live()?,
-            AppType::OpenCode => {
-                // OpenCode doesn't support proxy features
-                return Err("OpenCode 不支持代理功能".to_string());
-            }
+            AppType::OpenCode => self.read_opencode_live()?,
             AppType::Cursor => {
                 // Cursor doesn't support proxy features
                 return Err("Cursor 不支持代理功能".to_string());
             }
+            AppType::Qwen => {
+                // Qwen Code doesn't support proxy features
+                return Err("Qwen Code 不支持代理功能".to_string());
+            }
         };
 
         self.sync_live_config_to_provider(app_type, &live_config)
@@ -590,16 +599,80 @@ impl ProxyService {
                 }
             }
             AppType::OpenCode => {
-                // OpenCode doesn't support proxy features, skip silently
+                // OpenCode 为累加模式：逐个同步 Live 中已登记供应商的真实地址与 Key
+                let live_providers = live_config.get("provider").and_then(|v| v.as_object());
+                for (provider_id, live) in live_providers.into_iter().flatten() {
+                    let Ok(Some(mut provider)) =
+                        self.db.get_provider_by_id(provider_id, "opencode")
+                    else {
+                        continue;
+                    };
+
+                    let api_key = live
+                        .pointer("/options/apiKey")
+                        .and_then(|v| v.as_str())
+                        .map(str::trim)
+                        .filter(|key| !key.is_empty() && *key != PROXY_TOKEN_PLACEHOLDER);
+                    let base_url = live
+                        .pointer("/options/baseURL")
+                        .and_then(|v| v.as_str())
+                        .map(str::trim)
+                        .filter(|url| !url.is_empty() && !Self::is_local_proxy_url(url));
+
+                    let mut changed = false;
+                    if let Some(key) = api_key {
+                        changed |=
+                            Self::set_opencode_option(&mut provider.settings_config, "apiKey", key);
+                    }
+                    if let Some(url) = base_url {
+                        changed |= Self::set_opencode_option(
+                            &mut provider.settings_config,
+                            "baseURL",
+                            url,
+                        );
+                    }
+                    if !changed {
+                        continue;
+                    }
+
+                    if let Err(e) = self.db.update_provider_settings_config(
+                        "opencode",
+                        provider_id,
+                        &provider.settings_config,
+                    ) {
+                        log::warn!("同步 OpenCode 供应商配置到数据库失败: {e}");
+                    } else {
+                        log::info!("已同步 OpenCode 供应商配置到数据库 (provider: {provider_id})");
+                    }
+                }
             }
             AppType::Cursor => {
                 // Cursor doesn't support proxy features, skip silently
             }
+            AppType::Qwen => {
+                // Qwen Code doesn't support proxy features, skip silently
+            }
         }
 
         Ok(())
     }
 
+    /// 写入 OpenCode 供应商 `options.<key>`，返回是否发生变化
+    fn set_opencode_option(settings_config: &mut Value, key: &str, value: &str) -> bool {
+        let Some(root) = settings_config.as_object_mut() else {
+            return false;
+        };
+        let options = root.entry("options").or_insert_with(|| json!({}));
+        let Some(options) = options.as_object_mut() else {
+            return false;
+        };
+        if options.get(key).and_then(|v| v.as_str()) == Some(value) {
+            return false;
+        }
+        options.insert(key.to_string(), json!(value));
+        true
+    }
+
     async fn sync_live_to_providers(&self) -> Result<(), String> {
         if let Ok(live_config) = self.read_claude_live() {
             self.sync_live_config_to_provider(&AppType::Claude, &live_config)
@@ -616,6 +689,11 @@ impl ProxyService {
                 .await?;
         }
 
+        if let Ok(live_config) = self.read_opencode_live() {
+            self.sync_live_config_to_provider(&AppType::OpenCode, &live_config)
+                .await?;
+        }
+
         log::info!("Live 配置 Token 同步完成");
         Ok(())
     }
@@ -668,7 +746,7 @@ impl ProxyService {
             .map_err(|e| format!("清除接管状态失败: {e}"))?;
 
         // 4. 清除所有应用的 enabled 状态（用户手动关闭，不需要下次自动恢复）
-        for app_type in ["claude", "codex", "gemini"] {
+        for app_type in ["claude", "codex", "gemini", "opencode"] {
             if let Ok(mut config) = self.db.get_proxy_config_for_app(app_type).await {
                 if config.enabled {
                     config.enabled = false;
@@ -763,6 +841,16 @@ impl ProxyService {
                 .map_err(|e| format!("备份 Gemini 配置失败: {e}"))?;
         }
 
+        // OpenCode
+        if let Ok(config) = self.read_opencode_live() {
+            let json_str = serde_json::to_string(&config)
+                .map_err(|e| format!("序列化 OpenCode 配置失败: {e}"))?;
+            self.db
+                .save_live_backup("opencode", &json_str)
+                .await
+                .map_err(|e| format!("备份 OpenCode 配置失败: {e}"))?;
+        }
+
         log::info!("已备份所有应用的 Live 配置");
         Ok(())
     }
@@ -773,14 +861,15 @@ impl ProxyService {
             AppType::Claude => ("claude", self.read_claude_live()?),
             AppType::Codex => ("codex", self.read_codex_live()?),
             AppType::Gemini => ("gemini", self.read_gemini_live()?),
-            AppType::OpenCode => {
-                // OpenCode doesn't support proxy features
-                return Err("OpenCode 不支持代理功能".to_string());
-            }
+            AppType::OpenCode => ("opencode", self.read_opencode_live()?),
             AppType::Cursor => {
                 // Cursor doesn't support proxy features
                 return Err("Cursor 不支持代理功能".to_string());
             }
+            AppType::Qwen => {
+                // Qwen Code doesn't support proxy features
+                return Err("Qwen Code 不支持代理功能".to_string());
+            }
         };
 
         let json_str = serde_json::to_string(&config)
@@ -829,6 +918,7 @@ impl ProxyService {
     /// - `/v1beta/*` → Gemini
     ///
     /// 因此不需要在 URL 中添加应用前缀。
+    /// OpenCode 例外：各供应商协议不同，按 `/opencode/<provider_id>` 逐个改写。
     async fn takeover_live_configs(&self) -> Result<(), String> {
         let (proxy_url, proxy_codex_base_url) = self.build_proxy_urls().await?;
 
@@ -908,6 +998,13 @@ impl ProxyService {
             log::info!("Gemini Live 配置已接管，代理地址: {proxy_url}");
         }
 
+        // OpenCode: 将已登记供应商的 baseURL 指向代理，apiKey 使用占位符
+        if let Ok(mut live_config) = self.read_opencode_live() {
+            let count = self.rewrite_opencode_live_for_proxy(&mut live_config, &proxy_url)?;
+            self.write_opencode_live(&live_config)?;
+            log::info!("OpenCode Live 配置已接管 {count} 个供应商，代理地址: {proxy_url}");
+        }
+
         Ok(())
     }
 
@@ -990,13 +1087,19 @@ impl ProxyService {
                 log::info!("Gemini Live 配置已接管，代理地址: {proxy_url}");
             }
             AppType::OpenCode => {
-                // OpenCode doesn't support proxy features
-                return Err("OpenCode 不支持代理功能".to_string());
+                let mut live_config = self.read_opencode_live()?;
+                let count = self.rewrite_opencode_live_for_proxy(&mut live_config, &proxy_url)?;
+                self.write_opencode_live(&live_config)?;
+                log::info!("OpenCode Live 配置已接管 {count} 个供应商，代理地址: {proxy_url}");
             }
             AppType::Cursor => {
                 // Cursor doesn't support proxy features
                 return Err("Cursor 不支持代理功能".to_string());
             }
+            AppType::Qwen => {
+                // Qwen Code doesn't support proxy features
+                return Err("Qwen Code 不支持代理功能".to_string());
+            }
         }
 
         Ok(())
@@ -1081,11 +1184,21 @@ impl ProxyService {
                 }
             }
             AppType::OpenCode => {
-                // OpenCode doesn't support proxy features, skip silently
+                if let Ok(mut live_config) = self.read_opencode_live() {
+                    if self
+                        .rewrite_opencode_live_for_proxy(&mut live_config, &proxy_url)
+                        .is_ok()
+                    {
+                        let _ = self.write_opencode_live(&live_config);
+                    }
+                }
             }
             AppType::Cursor => {
                 // Cursor doesn't support proxy features, skip silently
             }
+            AppType::Qwen => {
+                // Qwen Code doesn't support proxy features, skip silently
+            }
         }
 
         Ok(())
@@ -1119,11 +1232,19 @@ impl ProxyService {
                 }
             }
             AppType::OpenCode => {
-                // OpenCode doesn't support proxy features, skip silently
+                if let Ok(Some(backup)) = self.db.get_live_backup("opencode").await {
+                    let config: Value = serde_json::from_str(&backup.original_config)
+                        .map_err(|e| format!("解析 OpenCode 备份失败: {e}"))?;
+                    self.write_opencode_live(&config)?;
+                    log::info!("OpenCode Live 配置已恢复");
+                }
             }
             AppType::Cursor => {
                 // Cursor doesn't support proxy features, skip silently
             }
+            AppType::Qwen => {
+                // Qwen Code doesn't support proxy features, skip silently
+            }
         }
 
         Ok(())
@@ -1133,7 +1254,12 @@ impl ProxyService {
     async fn restore_live_configs(&self) -> Result<(), String> {
         let mut errors = Vec::new();
 
-        for app_type in [AppType::Claude, AppType::Codex, AppType::Gemini] {
+        for app_type in [
+            AppType::Claude,
+            AppType::Codex,
+            AppType::Gemini,
+            AppType::OpenCode,
+        ] {
             if let Err(e) = self
                 .restore_live_config_for_app_with_fallback(&app_type)
                 .await
@@ -1203,14 +1329,15 @@ impl ProxyService {
             AppType::Claude => self.write_claude_live(config),
             AppType::Codex => self.write_codex_live(config),
             AppType::Gemini => self.write_gemini_live(config),
-            AppType::OpenCode => {
-                // OpenCode doesn't support proxy features
-                Err("OpenCode 不支持代理功能".to_string())
-            }
+            AppType::OpenCode => self.write_opencode_live(config),
             AppType::Cursor => {
                 // Cursor doesn't support proxy features
                 Err("Cursor 不支持代理功能".to_string())
             }
+            AppType::Qwen => {
+                // Qwen Code doesn't support proxy features
+                Err("Qwen Code 不支持代理功能".to_string())
+            }
         }
     }
 
@@ -1228,14 +1355,18 @@ impl ProxyService {
                 Ok(config) => Self::is_gemini_live_taken_over(&config),
                 Err(_) => false,
             },
-            AppType::OpenCode => {
-                // OpenCode doesn't support proxy takeover
-                false
-            }
+            AppType::OpenCode => match self.read_opencode_live() {
+                Ok(config) => Self::is_opencode_live_taken_over(&config),
+                Err(_) => false,
+            },
             AppType::Cursor => {
                 // Cursor doesn't support proxy takeover
                 false
             }
+            AppType::Qwen => {
+                // Qwen Code doesn't support proxy takeover
+                false
+            }
         }
     }
 
@@ -1245,6 +1376,11 @@ impl ProxyService {
     /// - Ok(true)：已成功写回
     /// - Ok(false)：缺少当前供应商/供应商不存在，无法写回
     fn restore_live_from_ssot_for_app(&self, app_type: &AppType) -> Result<bool, String> {
+        // OpenCode 没有“当前供应商”，改为逐个用数据库中的供应商覆盖被接管的条目
+        if matches!(app_type, AppType::OpenCode) {
+            return self.restore_opencode_live_from_ssot();
+        }
+
         let current_id = crate::cc_switch::settings::get_effective_current_provider(&self.db, app_type)
             .map_err(|e| format!("获取 {app_type:?} 当前供应商失败: {e}"))?;
 
@@ -1275,14 +1411,15 @@ impl ProxyService {
             AppType::Claude => self.cleanup_claude_takeover_placeholders_in_live(),
             AppType::Codex => self.cleanup_codex_takeover_placeholders_in_live(),
             AppType::Gemini => self.cleanup_gemini_takeover_placeholders_in_live(),
-            AppType::OpenCode => {
-                // OpenCode doesn't support proxy features
-                Ok(())
-            }
+            AppType::OpenCode => self.cleanup_opencode_takeover_placeholders_in_live(),
             AppType::Cursor => {
                 // Cursor doesn't support proxy features
                 Ok(())
             }
+            AppType::Qwen => {
+                // Qwen Code doesn't support proxy features
+                Ok(())
+            }
         }
     }
 
@@ -1422,10 +1559,174 @@ impl ProxyService {
         Ok(())
     }
 
+    fn cleanup_opencode_takeover_placeholders_in_live(&self) -> Result<(), String> {
+        let mut config = self.read_opencode_live()?;
+
+        let Some(providers) = config.get_mut("provider").and_then(|v| v.as_object_mut()) else {
+            return Ok(());
+        };
+
+        for provider in providers.values_mut() {
+            let Some(options) = provider.get_mut("options").and_then(|v| v.as_object_mut()) else {
+                continue;
+            };
+
+            if options.get("apiKey").and_then(|v| v.as_str()) == Some(PROXY_TOKEN_PLACEHOLDER) {
+                options.remove("apiKey");
+            }
+
+            if options
+                .get("baseURL")
+                .and_then(|v| v.as_str())
+                .map(Self::is_local_proxy_url)
+                .unwrap_or(false)
+            {
+                options.remove("baseURL");
+            }
+        }
+
+        self.write_opencode_live(&config)?;
+        Ok(())
+    }
+
+    /// 无备份时用数据库中的供应商配置覆盖 OpenCode Live 中被接管的条目
+    fn restore_opencode_live_from_ssot(&self) -> Result<bool, String> {
+        let mut config = self.read_opencode_live()?;
+        let known = self
+            .db
+            .get_all_providers("opencode")
+            .map_err(|e| format!("读取 OpenCode 供应商列表失败: {e}"))?;
+
+        let Some(providers) = config.get_mut("provider").and_then(|v| v.as_object_mut()) else {
+            return Ok(false);
+        };
+
+        let mut restored = 0usize;
+        for (provider_id, live) in providers.iter_mut() {
+            let taken_over = live.pointer("/options/apiKey").and_then(|v| v.as_str())
+                == Some(PROXY_TOKEN_PLACEHOLDER);
+            if let (true, Some(provider)) = (taken_over, known.get(provider_id)) {
+                *live = provider.settings_config.clone();
+                restored += 1;
+            }
+        }
+
+        if restored == 0 {
+            return Ok(false);
+        }
+
+        self.write_opencode_live(&config)?;
+        Ok(true)
+    }
+
+    /// 将 OpenCode Live 中已在数据库登记的供应商改写为经由本地代理
+    ///
+    /// 代理按数据库中的供应商配置转发，未登记的条目无法路由，保持原样。
+    fn rewrite_opencode_live_for_proxy(
+        &self,
+        live_config: &mut Value,
+        proxy_url: &str,
+    ) -> Result<usize, String> {
+        let known = self
+            .db
+            .get_all_providers("opencode")
+            .map_err(|e| format!("读取 OpenCode 供应商列表失败: {e}"))?;
+
+        let Some(providers) = live_config
+            .get_mut("provider")
+            .and_then(|v| v.as_object_mut())
+        else {
+            return Ok(0);
+        };
+
+        let mut count = 0usize;
+        for (provider_id, provider) in providers.iter_mut() {
+            if known.contains_key(provider_id)
+                && Self::point_opencode_provider_to_proxy(provider, proxy_url, provider_id)
+            {
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// 改写单个 OpenCode 供应商条目：baseURL 指向 `/opencode/<id>`，apiKey 使用占位符
+    fn point_opencode_provider_to_proxy(provider: &mut Value, proxy_url: &str, id: &str) -> bool {
+        let Some(root) = provider.as_object_mut() else {
+            return false;
+        };
+        let options = root.entry("options").or_insert_with(|| json!({}));
+        let Some(options) = options.as_object_mut() else {
+            return false;
+        };
+
+        let base_url = format!("{}/opencode/{id}", proxy_url.trim_end_matches('/'));
+        options.insert("baseURL".to_string(), json!(base_url));
+        options.insert("apiKey".to_string(), json!(PROXY_TOKEN_PLACEHOLDER));
+        true
+    }
+
+    /// OpenCode 接管期间新增/编辑供应商：写入指向代理的条目，并同步更新 Live 备份
+    ///
+    /// 直接写入真实配置会让该供应商绕过代理，且停止代理时备份里也缺少它。
+    pub async fn write_opencode_provider_under_takeover(
+        &self,
+        provider: &Provider,
+    ) -> Result<(), String> {
+        self.update_live_backup_from_provider("opencode", provider)
+            .await?;
+
+        let (proxy_url, _) = self.build_proxy_urls().await?;
+        let mut fragment = provider.settings_config.clone();
+        if !Self::point_opencode_provider_to_proxy(&mut fragment, &proxy_url, &provider.id) {
+            return Err(format!(
+                "OpenCode 供应商 {} 配置格式异常（非对象）",
+                provider.id
+            ));
+        }
+
+        crate::cc_switch::opencode_config::set_provider(&provider.id, fragment)
+            .map_err(|e| format!("写入 OpenCode 配置失败: {e}"))
+    }
+
+    /// OpenCode 接管期间删除供应商：同时从 Live 备份中移除，避免停止代理时被恢复回来
+    pub async fn remove_opencode_provider_from_backup(
+        &self,
+        provider_id: &str,
+    ) -> Result<(), String> {
+        let Some(backup) = self
+            .db
+            .get_live_backup("opencode")
+            .await
+            .map_err(|e| format!("获取 OpenCode Live 备份失败: {e}"))?
+        else {
+            return Ok(());
+        };
+
+        let mut config: Value = serde_json::from_str(&backup.original_config)
+            .map_err(|e| format!("解析 OpenCode 备份失败: {e}"))?;
+        let removed = config
+            .get_mut("provider")
+            .and_then(|v| v.as_object_mut())
+            .and_then(|providers| providers.remove(provider_id))
+            .is_some();
+        if !removed {
+            return Ok(());
+        }
+
+        let json_str =
+            serde_json::to_string(&config).map_err(|e| format!("序列化 OpenCode 配置失败: {e}"))?;
+        self.db
+            .save_live_backup("opencode", &json_str)
+            .await
+            .map_err(|e| format!("更新 OpenCode 备份失败: {e}"))
+    }
+
     /// 检查是否处于 Live 接管模式
     pub async fn is_takeover_active(&self) -> Result<bool, String> {
         let status = self.get_takeover_status().await?;
-        Ok(status.claude || status.codex || status.gemini)
+        Ok(status.claude || status.codex || status.gemini || status.opencode)
     }
 
     /// 从异常退出中恢复（启动时调用）
@@ -1475,6 +1776,12 @@ impl ProxyService {
             }
         }
 
+        if let Ok(config) = self.read_opencode_live() {
+            if Self::is_opencode_live_taken_over(&config) {
+                return true;
+            }
+        }
+
         false
     }
 
@@ -1514,6 +1821,18 @@ impl ProxyService {
         env.get("GEMINI_API_KEY").and_then(|v| v.as_str()) == Some(PROXY_TOKEN_PLACEHOLDER)
     }
 
+    fn is_opencode_live_taken_over(config: &Value) -> bool {
+        config
+            .get("provider")
+            .and_then(|v| v.as_object())
+            .is_some_and(|providers| {
+                providers.values().any(|provider| {
+                    provider.pointer("/options/apiKey").and_then(|v| v.as_str())
+                        == Some(PROXY_TOKEN_PLACEHOLDER)
+                })
+            })
+    }
+
     /// 从供应商配置更新 Live 备份（用于代理模式下的热切换）
     ///
     /// 与 backup_live_configs() 不同，此方法从供应商的 settings_config 生成备份，
@@ -1545,6 +1864,24 @@ impl ProxyService {
                 serde_json::to_string(&env_backup)
                     .map_err(|e| format!("序列化 Gemini 配置失败: {e}"))?
             }
+            "opencode" => {
+                // OpenCode: 备份格式为 {"provider": {...}}（累加模式），仅替换该供应商条目
+                let mut backup = match self.db.get_live_backup("opencode").await {
+                    Ok(Some(backup)) => {
+                        serde_json::from_str(&backup.original_config).unwrap_or_else(|_| json!({}))
+                    }
+                    _ => json!({}),
+                };
+                if !backup.is_object() {
+                    backup = json!({});
+                }
+                if !backup.get("provider").is_some_and(Value::is_object) {
+                    backup["provider"] = json!({});
+                }
+                backup["provider"][provider.id.as_str()] = provider.settings_config.clone();
+                serde_json::to_string(&backup)
+                    .map_err(|e| format!("序列化 OpenCode 配置失败: {e}"))?
+            }
             _ => return Err(format!("未知的应用类型: {app_type}")),
         };
 
@@ -1610,7 +1947,7 @@ impl ProxyService {
     // ==================== Live 配置读写辅助方法 ====================
 
     /// 更新 TOML 字符串中的 base_url
-    fn update_toml_base_url(toml_str: &str, new_url: &str) -> String {
+    pub(crate) fn update_toml_base_url(toml_str: &str, new_url: &str) -> String {
         use toml_edit::DocumentMut;
 
         let mut doc = match toml_str.parse::<DocumentMut>() {
@@ -1762,6 +2099,43 @@ impl ProxyService {
         Ok(())
     }
 
+    /// 读取 OpenCode Live 中的供应商段，返回 `{"provider": {...}}`
+    ///
+    /// opencode.json 还包含 MCP 等其它配置，接管只涉及 provider 段。
+    fn read_opencode_live(&self) -> Result<Value, String> {
+        use crate::cc_switch::opencode_config::{get_opencode_config_path, read_opencode_config};
+
+        if !get_opencode_config_path().exists() {
+            return Err("OpenCode 配置文件不存在".to_string());
+        }
+
+        let config = read_opencode_config().map_err(|e| format!("读取 OpenCode 配置失败: {e}"))?;
+        let providers = config
+            .get("provider")
+            .filter(|v| v.is_object())
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+        Ok(json!({ "provider": providers }))
+    }
+
+    /// 写回 OpenCode Live 的供应商段，保留 opencode.json 中的其它配置
+    fn write_opencode_live(&self, config: &Value) -> Result<(), String> {
+        use crate::cc_switch::opencode_config::{read_opencode_config, write_opencode_config};
+
+        let mut full =
+            read_opencode_config().map_err(|e| format!("读取 OpenCode 配置失败: {e}"))?;
+        if !full.is_object() {
+            full = json!({});
+        }
+        full["provider"] = config
+            .get("provider")
+            .filter(|v| v.is_object())
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+
+        write_opencode_config(&full).map_err(|e| format!("写入 OpenCode 配置失败: {e}"))
+    }
+
     // ==================== 原有方法 ====================
 
     /// 获取服务器状态
@@ -2185,7 +2559,69 @@ model = "gpt-5.1-codex"
         let expected = serde_json::to_string(&provider_b.settings_config).expect("serialize");
         assert_eq!(backup.original_config, expected);
     }
-}
 
+    #[tokio::test]
+    #[serial]
+    async fn opencode_takeover_rewrites_known_providers_and_restores_backup() {
+        let _home = TempHome::new();
+        crate::cc_switch::settings::reload_settings().expect("reload settings");
+
+        let db = Arc::new(Database::memory().expect("init db"));
+        let service = ProxyService::new(db.clone());
 
+        let relay = json!({
+            "npm": "@ai-sdk/openai-compatible",
+            "options": { "baseURL": "https://relay.example.com/v1", "apiKey": "sk-relay" }
+        });
+        let original = json!({
+            "$schema": "https://opencode.ai/config.json",
+            "mcp": { "fs": { "type": "local", "command": ["fs-mcp"] } },
+            "provider": {
+                "relay": relay,
+                "manual": {
+                    "npm": "@ai-sdk/anthropic",
+                    "options": { "apiKey": "sk-manual" }
+                }
+            }
+        });
+        crate::cc_switch::opencode_config::write_opencode_config(&original)
+            .expect("seed opencode config");
+        db.save_provider(
+            "opencode",
+            &Provider::with_id("relay".to_string(), "Relay".to_string(), relay, None),
+        )
+        .expect("save provider");
 
+        service
+            .backup_live_config_strict(&AppType::OpenCode)
+            .await
+            .expect("backup");
+        service
+            .takeover_live_config_strict(&AppType::OpenCode)
+            .await
+            .expect("takeover");
+
+        let live = crate::cc_switch::opencode_config::read_opencode_config().expect("read live");
+        let base_url = live["provider"]["relay"]["options"]["baseURL"]
+            .as_str()
+            .expect("baseURL");
+        assert!(base_url.ends_with("/opencode/relay"), "{base_url}");
+        assert!(ProxyService::is_local_proxy_url(base_url));
+        assert_eq!(
+            live["provider"]["relay"]["options"]["apiKey"],
+            PROXY_TOKEN_PLACEHOLDER
+        );
+        // 未登记的供应商无法路由，保持原样；其它配置（MCP）不受影响
+        assert_eq!(live["provider"]["manual"], original["provider"]["manual"]);
+        assert_eq!(live["mcp"], original["mcp"]);
+        assert!(service.detect_takeover_in_live_config_for_app(&AppType::OpenCode));
+
+        service
+            .restore_live_config_for_app(&AppType::OpenCode)
+            .await
+            .expect("restore");
+        let restored =
+            crate::cc_switch::opencode_config::read_opencode_config().expect("read restored");
+        assert_eq!(restored, original);
+    }
+}
@@ -17,15 +17,28 @@ use std::time::Duration;
 use tokio::time::timeout;
 
 use crate::cc_switch::app_config::{AppType, InstalledSkill, SkillApps, UnmanagedSkill};
+use crate::cc_switch::app_target::{not_ported, target_for};
 use crate::cc_switch::config::get_skills_dir;
 use crate::cc_switch::database::dao::skill_cache::{RepoTreeCacheRow, SkillCacheRow};
+use crate::cc_switch::database::dao::skill_versions::SkillVersion;
 use crate::cc_switch::database::Database;
 use crate::cc_switch::error::format_skill_error;
+use crate::cc_switch::services::skill_scan::{
+    self, ScanAckRequired, SkillScanDiff, SkillScanReport,
+};
 
 /// Git clone 超时（对齐 vercel-labs/skills 的 CLONE_TIMEOUT_MS = 60000）
 const CLONE_TIMEOUT_SECS: u64 = 60;
 const MAX_SKILL_FILE_TREE_ENTRIES: usize = 5000;
 const MAX_SKILL_FILE_PREVIEW_BYTES: usize = 512 * 1024;
+/// 每个 Skill 保留的历史版本数量
+const SKILL_VERSION_RETAIN: usize = 5;
+/// 历史版本目录（位于 SSOT 下，以 . 开头避免被当作 Skill 扫描）
+const SKILL_VERSIONS_DIR: &str = ".versions";
+/// 内容替换时使用的暂存目录
+const SKILL_STAGING_DIR: &str = ".staging";
+/// Lockfile 格式版本
+const SKILL_LOCKFILE_VERSION: u32 = 1;
 
 // ========== 数据结构 ==========
 
@@ -217,6 +230,19 @@ pub struct SkillUpdateInfo {
     pub new_skills: Vec<String>,
     #[serde(rename = "removedSkills")]
     pub removed_skills: Vec<String>,
+    /// 已安装技能新版本的安全扫描结果（含与本地版本的差异）
+    #[serde(rename = "scanResults", default, skip_serializing_if = "Vec::is_empty")]
+    pub scan_results: Vec<SkillUpdateScan>,
+}
+
+/// 已安装技能新版本的扫描结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillUpdateScan {
+    #[serde(rename = "skillId")]
+    pub skill_id: String,
+    pub directory: String,
+    pub report: SkillScanReport,
+    pub diff: SkillScanDiff,
 }
 
 /// 已安装技能远程刷新结果
@@ -281,6 +307,81 @@ pub struct SkillFileContentResult {
     pub byte_size: u64,
 }
 
+/// 已安装技能内容更新结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillContentUpdateResult {
+    #[serde(rename = "skillId")]
+    pub skill_id: String,
+    pub updated: bool,
+    #[serde(rename = "previousCommitId", skip_serializing_if = "Option::is_none")]
+    pub previous_commit_id: Option<String>,
+    #[serde(rename = "commitId", skip_serializing_if = "Option::is_none")]
+    pub commit_id: Option<String>,
+    #[serde(rename = "archivedVersionId", skip_serializing_if = "Option::is_none")]
+    pub archived_version_id: Option<i64>,
+}
+
+/// Skills lockfile（可提交到仓库，用于在其他机器上复现同一组 Skills）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkillLockfile {
+    pub version: u32,
+    pub skills: Vec<SkillLockEntry>,
+}
+
+/// Lockfile 单条目
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillLockEntry {
+    /// 唯一标识: "owner/name:path"
+    pub id: String,
+    pub name: String,
+    pub repo_owner: String,
+    pub repo_name: String,
+    pub repo_branch: String,
+    /// 仓库内的技能目录
+    pub path: String,
+    /// 锁定的 commit SHA 或 tag（缺失时安装分支最新提交）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    #[serde(default)]
+    pub apps: SkillApps,
+    /// 导出时的内容指纹，仅用于检测内容漂移，不代表扫描报告已被确认
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scan_fingerprint: Option<String>,
+}
+
+/// Lockfile 中等待用户确认扫描报告的条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillLockPending {
+    pub id: String,
+    pub report: SkillScanReport,
+}
+
+/// Lockfile 应用失败条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillLockFailure {
+    pub id: String,
+    pub error: String,
+}
+
+/// Lockfile 应用结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SkillLockApplyResult {
+    pub installed: Vec<String>,
+    pub updated: Vec<String>,
+    pub unchanged: Vec<String>,
+    pub failed: Vec<SkillLockFailure>,
+    /// 扫描报告需确认的条目，确认后带上指纹重新应用
+    pub pending: Vec<SkillLockPending>,
+}
+
+/// 单个 lockfile 条目的应用结果
+enum LockEntryOutcome {
+    Installed,
+    Updated,
+    Unchanged,
+}
+
 // ========== SkillService ==========
 
 pub struct SkillService;
@@ -307,6 +408,11 @@ impl SkillService {
 
     /// 获取应用的 skills 目录
     pub fn get_app_skills_dir(app: &AppType) -> Result<PathBuf> {
+        // AppTarget 已处理目录覆盖与默认路径
+        if let Some(target) = target_for(app) {
+            return Ok(target.skills_dir());
+        }
+
         // 目录覆盖：优先使用用户在 settings.json 中配置的 override 目录
         match app {
             AppType::Claude => {
@@ -319,11 +425,6 @@ impl SkillService {
                     return Ok(custom.join("skills"));
                 }
             }
-            AppType::Gemini => {
-                if let Some(custom) = crate::cc_switch::settings::get_gemini_override_dir() {
-                    return Ok(custom.join("skills"));
-                }
-            }
             AppType::OpenCode => {
                 if let Some(custom) = crate::cc_switch::settings::get_opencode_override_dir() {
                     return Ok(custom.join("skills"));
@@ -334,6 +435,7 @@ impl SkillService {
                     return Ok(custom.join("skills-cursor"));
                 }
             }
+            _ => return Err(not_ported(app).into()),
         }
 
         // 默认路径：回退到用户主目录下的标准位置
@@ -346,9 +448,9 @@ impl SkillService {
         Ok(match app {
             AppType::Claude => home.join(".claude").join("skills"),
             AppType::Codex => home.join(".codex").join("skills"),
-            AppType::Gemini => home.join(".gemini").join("skills"),
             AppType::OpenCode => home.join(".config").join("opencode").join("skills"),
             AppType::Cursor => home.join(".cursor").join("skills-cursor"),
+            _ => return Err(not_ported(app).into()),
         })
     }
 
@@ -359,10 +461,12 @@ impl SkillService {
         let mut skills: Vec<InstalledSkill> = db.get_all_installed_skills()?.into_values().collect();
 
         for skill in &mut skills {
-            if skill
-                .tree_commit_id
-                .as_ref()
-                .is_some_and(|value| !value.trim().is_empty())
+            // 已锁定的技能不回填：缓存中的 tree SHA 对应分支最新提交，而非锁定版本
+            if skill.pinned_ref.is_some()
+                || skill
+                    .tree_commit_id
+                    .as_ref()
+                    .is_some_and(|value| !value.trim().is_empty())
             {
                 continue;
             }
@@ -507,12 +611,52 @@ impl SkillService {
     /// 1. 下载到 SSOT 目录
     /// 2. 保存到数据库
     /// 3. 同步到启用的应用目录
+    ///
+    /// 扫描报告存在风险项时，需传入用户已确认报告的指纹才会继续安装。
     pub async fn install(
         &self,
         db: &Arc<Database>,
         skill: &DiscoverableSkill,
         current_app: &AppType,
+        acknowledged_fingerprint: Option<&str>,
     ) -> Result<InstalledSkill> {
+        let installed_skill = self
+            .install_at_ref(
+                db,
+                skill,
+                SkillApps::only(current_app),
+                None,
+                acknowledged_fingerprint,
+                None,
+            )
+            .await?;
+
+        log::info!(
+            "Skill {} 安装成功，已启用 {:?}",
+            installed_skill.name,
+            current_app
+        );
+
+        Ok(installed_skill)
+    }
+
+    /// 安装 Skill 到指定 Git 引用
+    ///
+    /// `git_ref` 为 None 时安装分支最新提交；否则安装该引用并记录为锁定版本。
+    /// `expected_fingerprint` 存在时内容指纹必须与之一致。
+    async fn install_at_ref(
+        &self,
+        db: &Arc<Database>,
+        skill: &DiscoverableSkill,
+        apps: SkillApps,
+        git_ref: Option<&str>,
+        acknowledged_fingerprint: Option<&str>,
+        expected_fingerprint: Option<&str>,
+    ) -> Result<InstalledSkill> {
+        if let Some(git_ref) = git_ref {
+            Self::validate_git_ref(git_ref)?;
+        }
+
         let ssot_dir = Self::get_ssot_dir()?;
         let repo = SkillRepo {
             owner: skill.repo_owner.clone(),
@@ -529,10 +673,16 @@ impl SkillService {
 
         let dest = ssot_dir.join(&install_name);
 
+        let mut commit_id = None;
+        let mut fetched_tree_sha = None;
+
         // 如果已存在则跳过下载
         if !dest.exists() {
-            // 克隆仓库
-            let temp_dir = self.clone_repo(&repo).await?;
+            // 克隆仓库（锁定引用时按引用浅获取）
+            let temp_dir = match git_ref {
+                Some(git_ref) => self.clone_repo_at_ref(&repo, git_ref).await?,
+                None => self.clone_repo(&repo).await?,
+            };
 
             // 复制到 SSOT
             let source = temp_dir.join(&skill.directory);
@@ -545,19 +695,45 @@ impl SkillService {
                 )));
             }
 
+            // 复制前做安全扫描，风险内容需用户确认
+            let acknowledged = skill_scan::scan_dir(&source).and_then(|report| {
+                skill_scan::ensure_fingerprint_matches(
+                    &report,
+                    &skill.directory,
+                    expected_fingerprint,
+                )?;
+                skill_scan::ensure_acknowledged(&report, &skill.directory, acknowledged_fingerprint)
+            });
+            if let Err(e) = acknowledged {
+                let _ = fs::remove_dir_all(&temp_dir);
+                return Err(e);
+            }
+
+            commit_id = Self::git_rev_parse(&temp_dir, "HEAD").await;
+            fetched_tree_sha =
+                Self::git_rev_parse(&temp_dir, &format!("HEAD:{}", skill.directory)).await;
+
             Self::copy_dir_recursive(&source, &dest)?;
             let _ = fs::remove_dir_all(&temp_dir);
         }
 
-        let tree_commit_id = if let Some(tree_sha) = skill
-            .tree_sha
-            .clone()
-            .filter(|value| !value.trim().is_empty())
-        {
-            Some(tree_sha)
+        // 锁定引用时发现列表中的 tree SHA 对应分支最新提交，以实际获取的内容为准
+        let preferred_tree_sha = if git_ref.is_some() {
+            fetched_tree_sha.clone()
         } else {
-            self.resolve_tree_commit_id(&repo, &skill.directory, &install_name)
-                .await
+            skill
+                .tree_sha
+                .clone()
+                .filter(|value| !value.trim().is_empty())
+        };
+        let known_tree_sha = preferred_tree_sha.or(fetched_tree_sha);
+
+        let tree_commit_id = match known_tree_sha {
+            Some(tree_sha) => Some(tree_sha),
+            None => {
+                self.resolve_tree_commit_id(&repo, &skill.directory, &install_name)
+                    .await
+            }
         };
 
         // 创建 InstalledSkill 记录
@@ -575,21 +751,19 @@ impl SkillService {
             repo_branch: Some(skill.repo_branch.clone()),
             readme_url: skill.readme_url.clone(),
             tree_commit_id,
-            apps: SkillApps::only(current_app),
+            pinned_ref: git_ref.map(str::to_string),
+            commit_id,
+            apps,
             installed_at: chrono::Utc::now().timestamp(),
         };
 
         // 保存到数据库
         db.save_skill(&installed_skill)?;
 
-        // 同步到当前应用目录
-        Self::sync_to_app_dir(&install_name, current_app)?;
-
-        log::info!(
-            "Skill {} 安装成功，已启用 {:?}",
-            installed_skill.name,
-            current_app
-        );
+        // 同步到启用的应用目录
+        for app in installed_skill.apps.enabled_apps() {
+            Self::sync_to_app_dir(&install_name, &app)?;
+        }
 
         Ok(installed_skill)
     }
@@ -607,13 +781,7 @@ impl SkillService {
             .ok_or_else(|| anyhow!("Skill not found: {id}"))?;
 
         // 从所有应用目录删除
-        for app in [
-            AppType::Claude,
-            AppType::Codex,
-            AppType::Gemini,
-            AppType::OpenCode,
-            AppType::Cursor,
-        ] {
+        for app in AppType::ALL {
             let _ = Self::remove_from_app(&skill.directory, &app);
         }
 
@@ -624,6 +792,13 @@ impl SkillService {
             fs::remove_dir_all(&skill_path)?;
         }
 
+        // 删除历史版本
+        let versions_dir = Self::get_versions_dir(&skill.directory)?;
+        if versions_dir.exists() {
+            fs::remove_dir_all(&versions_dir)?;
+        }
+        db.delete_skill_versions_by_skill(id)?;
+
         // 从数据库删除
         db.delete_skill(id)?;
 
@@ -672,13 +847,7 @@ impl SkillService {
 
         let mut unmanaged: HashMap<String, UnmanagedSkill> = HashMap::new();
 
-        for app in [
-            AppType::Claude,
-            AppType::Codex,
-            AppType::Gemini,
-            AppType::OpenCode,
-            AppType::Cursor,
-        ] {
+        for app in AppType::ALL {
             let app_dir = match Self::get_app_skills_dir(&app) {
                 Ok(d) => d,
                 Err(_) => continue,
@@ -729,6 +898,7 @@ impl SkillService {
                     AppType::Gemini => "gemini",
                     AppType::OpenCode => "opencode",
                     AppType::Cursor => "cursor",
+                    AppType::Qwen => "qwen",
                 };
 
                 unmanaged
@@ -763,13 +933,7 @@ impl SkillService {
             let mut source_path: Option<PathBuf> = None;
             let mut found_in: Vec<String> = Vec::new();
 
-            for app in [
-                AppType::Claude,
-                AppType::Codex,
-                AppType::Gemini,
-                AppType::OpenCode,
-                AppType::Cursor,
-            ] {
+            for app in AppType::ALL {
                 if let Ok(app_dir) = Self::get_app_skills_dir(&app) {
                     let skill_path = app_dir.join(&dir_name);
                     if skill_path.exists() {
@@ -782,6 +946,7 @@ impl SkillService {
                             AppType::Gemini => "gemini",
                             AppType::OpenCode => "opencode",
                             AppType::Cursor => "cursor",
+                            AppType::Qwen => "qwen",
                         };
                         found_in.push(app_str.to_string());
                     }
@@ -822,6 +987,7 @@ impl SkillService {
                     "gemini" => apps.gemini = true,
                     "opencode" => apps.opencode = true,
                     "cursor" => apps.cursor = true,
+                    "qwen" => apps.qwen = true,
                     _ => {}
                 }
             }
@@ -837,6 +1003,8 @@ impl SkillService {
                 repo_branch: None,
                 readme_url: None,
                 tree_commit_id: None,
+                pinned_ref: None,
+                commit_id: None,
                 apps,
                 installed_at: chrono::Utc::now().timestamp(),
             };
@@ -852,6 +1020,7 @@ impl SkillService {
                     "gemini" => AppType::Gemini,
                     "opencode" => AppType::OpenCode,
                     "cursor" => AppType::Cursor,
+                    "qwen" => AppType::Qwen,
                     _ => continue,
                 };
                 if let Err(e) = Self::sync_to_app_dir(&skill.directory, &app) {
@@ -1702,6 +1871,74 @@ impl SkillService {
         Ok(())
     }
 
+    /// 浅获取指定 Git 引用（branch / tag / commit SHA）到临时目录
+    ///
+    /// 使用 `git init` + `git fetch --depth 1 origin <ref>`，GitHub 支持按完整 commit SHA 获取。
+    async fn clone_repo_at_ref(&self, repo: &SkillRepo, git_ref: &str) -> Result<PathBuf> {
+        let temp_dir = tempfile::tempdir()?;
+        let temp_path = temp_dir.path().to_path_buf();
+        let _ = temp_dir.keep(); // 保留临时目录，由调用方负责清理
+
+        let url = format!("https://github.com/{}/{}.git", repo.owner, repo.name);
+        match timeout(
+            Duration::from_secs(CLONE_TIMEOUT_SECS),
+            Self::execute_git_fetch_ref(&url, git_ref, &temp_path),
+        )
+        .await
+        {
+            Ok(Ok(())) => Ok(temp_path),
+            Ok(Err(e)) => {
+                let _ = Self::cleanup_temp_dir(&temp_path);
+                Err(e)
+            }
+            Err(_) => {
+                let _ = Self::cleanup_temp_dir(&temp_path);
+                Err(anyhow!(format_skill_error(
+                    "CLONE_TIMEOUT",
+                    &[("url", &url), ("branch", git_ref), ("timeout", "60")],
+                    Some("checkNetwork"),
+                )))
+            }
+        }
+    }
+
+    /// 在空目录中按引用浅获取并检出
+    async fn execute_git_fetch_ref(url: &str, git_ref: &str, dest: &Path) -> Result<()> {
+        Self::run_git(dest, &["init", "-q"]).await?;
+        Self::run_git(dest, &["remote", "add", "origin", url]).await?;
+        Self::run_git(dest, &["fetch", "-q", "--depth", "1", "origin", git_ref])
+            .await
+            .with_context(|| format!("获取 Git 引用 {git_ref} 失败"))?;
+        Self::run_git(dest, &["checkout", "-q", "--detach", "FETCH_HEAD"]).await?;
+        Ok(())
+    }
+
+    /// 在指定目录执行 git 命令，返回去除首尾空白的 stdout
+    async fn run_git(dir: &Path, args: &[&str]) -> Result<String> {
+        let output = tokio::process::Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("git {} failed: {}", args.join(" "), stderr.trim()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// 解析 Git 对象 SHA（失败时返回 None）
+    async fn git_rev_parse(dir: &Path, rev: &str) -> Option<String> {
+        Self::run_git(dir, &["rev-parse", rev])
+            .await
+            .ok()
+            .filter(|value| !value.is_empty())
+    }
+
     /// 安全清理临时目录（对齐 vercel-labs/skills 的路径遍历防护）
     fn cleanup_temp_dir(dir: &Path) -> Result<()> {
         let normalized_dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
@@ -1738,6 +1975,542 @@ impl SkillService {
         Ok(())
     }
 
+    // ========== 版本锁定与回滚 ==========
+
+    /// 获取 Skill 历史版本目录（`<SSOT>/.versions/<directory>/`）
+    fn get_versions_dir(directory: &str) -> Result<PathBuf> {
+        Ok(Self::get_ssot_dir()?
+            .join(SKILL_VERSIONS_DIR)
+            .join(directory))
+    }
+
+    /// 校验 Git 引用格式（commit SHA / tag / 分支名），防止参数注入
+    fn validate_git_ref(git_ref: &str) -> Result<()> {
+        let valid = !git_ref.is_empty()
+            && !git_ref.starts_with('-')
+            && !git_ref.contains("..")
+            && git_ref
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '/'));
+        if !valid {
+            return Err(anyhow!("非法的 Git 引用: {git_ref}"));
+        }
+        Ok(())
+    }
+
+    /// 从技能 ID 中解析仓库内目录（"owner/name:path" → "path"）
+    fn remote_directory_of(skill: &InstalledSkill) -> String {
+        skill
+            .id
+            .split_once(':')
+            .and_then(|(prefix, remote_dir)| {
+                if prefix.eq_ignore_ascii_case("local") {
+                    None
+                } else {
+                    Some(remote_dir.trim().to_string())
+                }
+            })
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| skill.directory.clone())
+    }
+
+    /// 列出 Skill 的历史版本（最新在前）
+    pub fn list_versions(db: &Arc<Database>, skill_id: &str) -> Result<Vec<SkillVersion>> {
+        Ok(db.list_skill_versions(skill_id)?)
+    }
+
+    /// 设置或清除 Skill 的锁定引用
+    ///
+    /// 仅更新记录，不改动文件；需调用 `update_installed` 拉取锁定版本的内容。
+    pub fn set_pinned_ref(
+        db: &Arc<Database>,
+        skill_id: &str,
+        git_ref: Option<String>,
+    ) -> Result<InstalledSkill> {
+        let mut skill = db
+            .get_installed_skill(skill_id)?
+            .ok_or_else(|| anyhow!("Skill not found: {skill_id}"))?;
+
+        let normalized = git_ref
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty());
+
+        if let Some(git_ref) = normalized.as_deref() {
+            Self::validate_git_ref(git_ref)?;
+            if skill.repo_owner.is_none() || skill.repo_name.is_none() {
+                return Err(anyhow!("本地 Skill 没有远程仓库，无法锁定版本: {skill_id}"));
+            }
+        }
+
+        skill.pinned_ref = normalized;
+        db.save_skill(&skill)?;
+
+        log::info!(
+            "Skill {} 锁定引用已更新为 {:?}",
+            skill.name,
+            skill.pinned_ref
+        );
+
+        Ok(skill)
+    }
+
+    /// 将已安装 Skill 的内容更新到锁定引用（未锁定时为分支最新提交）
+    ///
+    /// 内容变化前会将当前版本归档到 `.versions`，commit 未变化时不做改动。
+    /// 新内容同样需要通过安全扫描确认。
+    pub async fn update_installed(
+        &self,
+        db: &Arc<Database>,
+        skill_id: &str,
+        acknowledged_fingerprint: Option<&str>,
+    ) -> Result<SkillContentUpdateResult> {
+        self.update_installed_checked(db, skill_id, acknowledged_fingerprint, None)
+            .await
+    }
+
+    /// 同 [`Self::update_installed`]，`expected_fingerprint` 存在时新内容指纹必须与之一致
+    async fn update_installed_checked(
+        &self,
+        db: &Arc<Database>,
+        skill_id: &str,
+        acknowledged_fingerprint: Option<&str>,
+        expected_fingerprint: Option<&str>,
+    ) -> Result<SkillContentUpdateResult> {
+        let mut skill = db
+            .get_installed_skill(skill_id)?
+            .ok_or_else(|| anyhow!("Skill not found: {skill_id}"))?;
+
+        let (Some(repo_owner), Some(repo_name)) =
+            (skill.repo_owner.clone(), skill.repo_name.clone())
+        else {
+            return Err(anyhow!("本地 Skill 没有远程仓库，无法更新: {skill_id}"));
+        };
+
+        let repo = SkillRepo {
+            owner: repo_owner,
+            name: repo_name,
+            branch: skill
+                .repo_branch
+                .clone()
+                .unwrap_or_else(|| "main".to_string()),
+            enabled: true,
+        };
+
+        let temp_dir = match skill.pinned_ref.clone() {
+            Some(git_ref) => {
+                Self::validate_git_ref(&git_ref)?;
+                self.clone_repo_at_ref(&repo, &git_ref).await?
+            }
+            None => self.clone_repo(&repo).await?,
+        };
+
+        let result = Self::apply_fetched_content(
+            db,
+            &mut skill,
+            &temp_dir,
+            acknowledged_fingerprint,
+            expected_fingerprint,
+        )
+        .await;
+        let _ = Self::cleanup_temp_dir(&temp_dir);
+        result
+    }
+
+    /// 用临时克隆目录中的内容替换 SSOT 中的 Skill
+    async fn apply_fetched_content(
+        db: &Arc<Database>,
+        skill: &mut InstalledSkill,
+        temp_dir: &Path,
+        acknowledged_fingerprint: Option<&str>,
+        expected_fingerprint: Option<&str>,
+    ) -> Result<SkillContentUpdateResult> {
+        let remote_dir = Self::remote_directory_of(skill);
+        let source = temp_dir.join(&remote_dir);
+        if !source.exists() {
+            return Err(anyhow!(format_skill_error(
+                "SKILL_DIR_NOT_FOUND",
+                &[("path", &source.display().to_string())],
+                Some("checkRepoUrl"),
+            )));
+        }
+
+        let previous_commit_id = skill.commit_id.clone();
+        let commit_id = Self::git_rev_parse(temp_dir, "HEAD").await;
+        if commit_id.is_some() && commit_id == previous_commit_id {
+            return Ok(SkillContentUpdateResult {
+                skill_id: skill.id.clone(),
+                updated: false,
+                previous_commit_id,
+                commit_id,
+                archived_version_id: None,
+            });
+        }
+
+        let report = skill_scan::scan_dir(&source)?;
+        skill_scan::ensure_fingerprint_matches(&report, &skill.directory, expected_fingerprint)?;
+        skill_scan::ensure_acknowledged(&report, &skill.directory, acknowledged_fingerprint)?;
+
+        let tree_commit_id = Self::git_rev_parse(temp_dir, &format!("HEAD:{remote_dir}")).await;
+
+        let staging = Self::stage_content(&skill.directory, &source)?;
+        let archived = Self::archive_current_version(db, skill)?;
+        Self::commit_staged_content(&skill.directory, &staging)?;
+
+        skill.commit_id = commit_id.clone();
+        if tree_commit_id.is_some() {
+            skill.tree_commit_id = tree_commit_id;
+        }
+        db.save_skill(skill)?;
+        Self::resync_enabled_apps(skill)?;
+
+        log::info!(
+            "Skill {} 已更新: {:?} -> {:?}",
+            skill.name,
+            previous_commit_id,
+            commit_id
+        );
+
+        Ok(SkillContentUpdateResult {
+            skill_id: skill.id.clone(),
+            updated: true,
+            previous_commit_id,
+            commit_id,
+            archived_version_id: archived.map(|version| version.id),
+        })
+    }
+
+    /// 回滚 Skill 到指定历史版本
+    ///
+    /// 回滚前同样会归档当前内容（回滚本身可撤销），并将 Skill 锁定到该版本的 commit，
+    /// 避免下次更新时重新移动到分支最新提交。
+    pub fn rollback(db: &Arc<Database>, skill_id: &str, version_id: i64) -> Result<InstalledSkill> {
+        let mut skill = db
+            .get_installed_skill(skill_id)?
+            .ok_or_else(|| anyhow!("Skill not found: {skill_id}"))?;
+
+        let version = db
+            .get_skill_version(version_id)?
+            .filter(|version| version.skill_id == skill.id)
+            .ok_or_else(|| anyhow!("Skill 版本不存在: {version_id}"))?;
+
+        let snapshot = Self::get_versions_dir(&skill.directory)?.join(&version.snapshot_dir);
+        if !snapshot.exists() {
+            return Err(anyhow!("Skill 版本快照已丢失: {}", snapshot.display()));
+        }
+
+        // 先暂存目标快照，避免归档当前版本时的清理删除目标版本
+        let staging = Self::stage_content(&skill.directory, &snapshot)?;
+        Self::archive_current_version(db, &skill)?;
+        Self::commit_staged_content(&skill.directory, &staging)?;
+
+        skill.commit_id = version.commit_id.clone();
+        skill.tree_commit_id = version.tree_commit_id.clone();
+        if let Some(commit_id) = version.commit_id.clone() {
+            skill.pinned_ref = Some(commit_id);
+        } else if version.pinned_ref.is_some() {
+            skill.pinned_ref = version.pinned_ref.clone();
+        }
+        db.save_skill(&skill)?;
+        Self::resync_enabled_apps(&skill)?;
+
+        log::info!(
+            "Skill {} 已回滚到版本 {} ({:?})",
+            skill.name,
+            version.id,
+            version.commit_id
+        );
+
+        Ok(skill)
+    }
+
+    /// 将 SSOT 中的当前内容归档为历史版本，并按保留数量清理旧版本
+    fn archive_current_version(
+        db: &Arc<Database>,
+        skill: &InstalledSkill,
+    ) -> Result<Option<SkillVersion>> {
+        let source = Self::get_ssot_dir()?.join(&skill.directory);
+        if !source.exists() {
+            return Ok(None);
+        }
+
+        let now = chrono::Utc::now();
+        let timestamp = now.format("%Y%m%dT%H%M%S%3f").to_string();
+        let snapshot_dir = match skill.commit_id.as_deref() {
+            Some(commit_id) => format!("{timestamp}-{}", &commit_id[..commit_id.len().min(12)]),
+            None => timestamp,
+        };
+
+        let dest = Self::get_versions_dir(&skill.directory)?.join(&snapshot_dir);
+        Self::copy_dir_recursive(&source, &dest)?;
+
+        let mut version = SkillVersion {
+            id: 0,
+            skill_id: skill.id.clone(),
+            snapshot_dir,
+            commit_id: skill.commit_id.clone(),
+            tree_commit_id: skill.tree_commit_id.clone(),
+            pinned_ref: skill.pinned_ref.clone(),
+            created_at: now.timestamp(),
+        };
+        version.id = db.insert_skill_version(&version)?;
+
+        Self::prune_versions(db, skill)?;
+
+        Ok(Some(version))
+    }
+
+    /// 清理超出保留数量的历史版本
+    fn prune_versions(db: &Arc<Database>, skill: &InstalledSkill) -> Result<()> {
+        let versions_dir = Self::get_versions_dir(&skill.directory)?;
+        for stale in db
+            .list_skill_versions(&skill.id)?
+            .into_iter()
+            .skip(SKILL_VERSION_RETAIN)
+        {
+            let path = versions_dir.join(&stale.snapshot_dir);
+            if path.exists() {
+                fs::remove_dir_all(&path)?;
+            }
+            db.delete_skill_version(stale.id)?;
+        }
+        Ok(())
+    }
+
+    /// 将新内容复制到暂存目录
+    fn stage_content(directory: &str, source: &Path) -> Result<PathBuf> {
+        let staging = Self::get_ssot_dir()?
+            .join(SKILL_STAGING_DIR)
+            .join(directory);
+        if staging.exists() {
+            fs::remove_dir_all(&staging)?;
+        }
+        Self::copy_dir_recursive(source, &staging)?;
+        Ok(staging)
+    }
+
+    /// 用暂存目录替换 SSOT 中的 Skill 目录
+    fn commit_staged_content(directory: &str, staging: &Path) -> Result<()> {
+        let dest = Self::get_ssot_dir()?.join(directory);
+        if dest.exists() || Self::is_symlink(&dest) {
+            Self::remove_path(&dest)?;
+        }
+        fs::rename(staging, &dest).with_context(|| {
+            format!(
+                "替换 Skill 目录失败: {} -> {}",
+                staging.display(),
+                dest.display()
+            )
+        })?;
+        Ok(())
+    }
+
+    /// 重新同步到所有已启用的应用目录（copy 模式下需要刷新副本）
+    fn resync_enabled_apps(skill: &InstalledSkill) -> Result<()> {
+        for app in skill.apps.enabled_apps() {
+            Self::sync_to_app_dir(&skill.directory, &app)?;
+        }
+        Ok(())
+    }
+
+    // ========== Lockfile ==========
+
+    /// 根据已安装 Skills 生成 lockfile
+    ///
+    /// 仅包含有远程仓库信息的 Skill；条目按 ID 排序，便于提交到仓库后 diff。
+    pub fn build_lockfile(skills: &[InstalledSkill]) -> SkillLockfile {
+        let mut entries: Vec<SkillLockEntry> = skills
+            .iter()
+            .filter_map(|skill| {
+                let repo_owner = skill.repo_owner.clone()?;
+                let repo_name = skill.repo_name.clone()?;
+                Some(SkillLockEntry {
+                    id: skill.id.clone(),
+                    name: skill.name.clone(),
+                    repo_owner,
+                    repo_name,
+                    repo_branch: skill
+                        .repo_branch
+                        .clone()
+                        .unwrap_or_else(|| "main".to_string()),
+                    path: Self::remote_directory_of(skill),
+                    commit: skill.commit_id.clone().or_else(|| skill.pinned_ref.clone()),
+                    apps: skill.apps.clone(),
+                    scan_fingerprint: None,
+                })
+            })
+            .collect();
+        entries.sort_by(|a, b| a.id.cmp(&b.id));
+
+        SkillLockfile {
+            version: SKILL_LOCKFILE_VERSION,
+            skills: entries,
+        }
+    }
+
+    /// 导出 lockfile 到指定路径
+    ///
+    /// 导出时附带已安装内容的扫描指纹，其他机器应用时据此检测内容漂移。
+    pub fn export_lockfile(db: &Arc<Database>, path: &Path) -> Result<SkillLockfile> {
+        let skills: Vec<InstalledSkill> = db.get_all_installed_skills()?.into_values().collect();
+        let mut lockfile = Self::build_lockfile(&skills);
+
+        let ssot_dir = Self::get_ssot_dir()?;
+        for entry in &mut lockfile.skills {
+            let Some(skill) = skills.iter().find(|skill| skill.id == entry.id) else {
+                continue;
+            };
+            entry.scan_fingerprint = skill_scan::scan_dir(&ssot_dir.join(&skill.directory))
+                .ok()
+                .map(|report| report.fingerprint);
+        }
+        let content = serde_json::to_string_pretty(&lockfile)?;
+        crate::cc_switch::config::write_text_file(path, &format!("{content}\n"))?;
+
+        log::info!(
+            "已导出 Skills lockfile（{} 项）: {}",
+            lockfile.skills.len(),
+            path.display()
+        );
+
+        Ok(lockfile)
+    }
+
+    /// 按 lockfile 安装/更新 Skills，使本机与 lockfile 一致
+    ///
+    /// `acknowledged` 为用户在界面上确认过的扫描指纹（条目 ID -> 指纹）。
+    /// 扫描报告需确认而尚未确认的条目不会安装，其报告放在结果的 `pending` 中；
+    /// lockfile 自带的指纹只用于检测内容漂移，不同则拒绝应用。
+    pub async fn apply_lockfile(
+        &self,
+        db: &Arc<Database>,
+        path: &Path,
+        acknowledged: &HashMap<String, String>,
+    ) -> Result<SkillLockApplyResult> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("读取 lockfile 失败: {}", path.display()))?;
+        let lockfile: SkillLockfile = serde_json::from_str(&content)
+            .with_context(|| format!("解析 lockfile 失败: {}", path.display()))?;
+
+        if lockfile.version > SKILL_LOCKFILE_VERSION {
+            return Err(anyhow!(
+                "lockfile 版本过新（{}），当前仅支持 {}",
+                lockfile.version,
+                SKILL_LOCKFILE_VERSION
+            ));
+        }
+
+        let mut result = SkillLockApplyResult::default();
+        for entry in &lockfile.skills {
+            let acknowledged_fingerprint = acknowledged.get(&entry.id).map(String::as_str);
+            let outcome = self
+                .apply_lock_entry(db, entry, acknowledged_fingerprint)
+                .await;
+            match outcome {
+                Ok(LockEntryOutcome::Installed) => result.installed.push(entry.id.clone()),
+                Ok(LockEntryOutcome::Updated) => result.updated.push(entry.id.clone()),
+                Ok(LockEntryOutcome::Unchanged) => result.unchanged.push(entry.id.clone()),
+                // 风险内容需用户看过报告后才能安装，不计为失败
+                Err(error) => match error.downcast_ref::<ScanAckRequired>() {
+                    Some(pending) => result.pending.push(SkillLockPending {
+                        id: entry.id.clone(),
+                        report: pending.report.clone(),
+                    }),
+                    None => {
+                        log::warn!("应用 lockfile 条目 {} 失败: {error:#}", entry.id);
+                        result.failed.push(SkillLockFailure {
+                            id: entry.id.clone(),
+                            error: format!("{error:#}"),
+                        });
+                    }
+                },
+            }
+        }
+
+        log::info!(
+            "Skills lockfile 已应用：新增 {}，更新 {}，未变 {}，失败 {}，待确认 {}",
+            result.installed.len(),
+            result.updated.len(),
+            result.unchanged.len(),
+            result.failed.len(),
+            result.pending.len()
+        );
+
+        Ok(result)
+    }
+
+    /// 应用单个 lockfile 条目
+    async fn apply_lock_entry(
+        &self,
+        db: &Arc<Database>,
+        entry: &SkillLockEntry,
+        acknowledged_fingerprint: Option<&str>,
+    ) -> Result<LockEntryOutcome> {
+        if let Some(commit) = entry.commit.as_deref() {
+            Self::validate_git_ref(commit)?;
+        }
+
+        let Some(mut existing) = db.get_installed_skill(&entry.id)? else {
+            let skill = DiscoverableSkill {
+                key: entry.id.clone(),
+                name: entry.name.clone(),
+                description: String::new(),
+                directory: entry.path.clone(),
+                readme_url: Some(format!(
+                    "https://github.com/{}/{}/tree/{}/{}",
+                    entry.repo_owner, entry.repo_name, entry.repo_branch, entry.path
+                )),
+                repo_owner: entry.repo_owner.clone(),
+                repo_name: entry.repo_name.clone(),
+                repo_branch: entry.repo_branch.clone(),
+                tree_sha: None,
+            };
+            self.install_at_ref(
+                db,
+                &skill,
+                entry.apps.clone(),
+                entry.commit.as_deref(),
+                acknowledged_fingerprint,
+                entry.scan_fingerprint.as_deref(),
+            )
+            .await?;
+            return Ok(LockEntryOutcome::Installed);
+        };
+
+        let apps_changed = existing.apps != entry.apps;
+        let pin_changed = existing.pinned_ref != entry.commit;
+        existing.pinned_ref = entry.commit.clone();
+        existing.apps = entry.apps.clone();
+        db.save_skill(&existing)?;
+
+        for app in AppType::ALL {
+            if existing.apps.is_enabled_for(&app) {
+                Self::sync_to_app_dir(&existing.directory, &app)?;
+            } else {
+                Self::remove_from_app(&existing.directory, &app)?;
+            }
+        }
+
+        let at_target = entry.commit.is_some() && existing.commit_id == entry.commit;
+        let content_updated = if at_target {
+            false
+        } else {
+            self.update_installed_checked(
+                db,
+                &entry.id,
+                acknowledged_fingerprint,
+                entry.scan_fingerprint.as_deref(),
+            )
+            .await?
+            .updated
+        };
+
+        if content_updated || apps_changed || pin_changed {
+            Ok(LockEntryOutcome::Updated)
+        } else {
+            Ok(LockEntryOutcome::Unchanged)
+        }
+    }
+
     // ========== 仓库管理（保留原有逻辑）==========
 
     /// 列出仓库
@@ -1855,15 +2628,115 @@ impl SkillService {
             return Ok(None);
         }
 
+        let scan_results = match self.scan_repo_updates(repo, db, &updated).await {
+            Ok(results) => results,
+            Err(e) => {
+                log::warn!(
+                    "扫描仓库 {}/{} 的技能更新失败: {}",
+                    repo.owner,
+                    repo.name,
+                    e
+                );
+                Vec::new()
+            }
+        };
+
         Ok(Some(SkillUpdateInfo {
             repo_owner: repo.owner.clone(),
             repo_name: repo.name.clone(),
             updated_skills: updated,
             new_skills,
             removed_skills: removed,
+            scan_results,
         }))
     }
 
+    /// 对有更新的已安装技能扫描新版本，并与本地版本对比
+    ///
+    /// 仅在存在未锁定的已安装技能需要更新时才克隆仓库（每个仓库一次）。
+    async fn scan_repo_updates(
+        &self,
+        repo: &SkillRepo,
+        db: &Arc<Database>,
+        updated_dirs: &[String],
+    ) -> Result<Vec<SkillUpdateScan>> {
+        let installed: Vec<InstalledSkill> = db
+            .get_all_installed_skills()?
+            .into_values()
+            .filter(|skill| {
+                skill.pinned_ref.is_none()
+                    && skill.repo_owner.as_deref() == Some(repo.owner.as_str())
+                    && skill.repo_name.as_deref() == Some(repo.name.as_str())
+                    && updated_dirs.contains(&Self::remote_directory_of(skill))
+            })
+            .collect();
+        if installed.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ssot_dir = Self::get_ssot_dir()?;
+        let temp_dir = self.clone_repo(repo).await?;
+
+        let mut results = Vec::new();
+        for skill in &installed {
+            let remote_dir = Self::remote_directory_of(skill);
+            let source = temp_dir.join(&remote_dir);
+            if !source.exists() {
+                continue;
+            }
+            let report = match skill_scan::scan_dir(&source) {
+                Ok(report) => report,
+                Err(e) => {
+                    log::warn!("扫描技能 {} 新版本失败: {}", skill.id, e);
+                    continue;
+                }
+            };
+            let current =
+                skill_scan::scan_dir(&ssot_dir.join(&skill.directory)).unwrap_or_default();
+            let diff = skill_scan::diff_reports(&current, &report);
+            results.push(SkillUpdateScan {
+                skill_id: skill.id.clone(),
+                directory: remote_dir,
+                report,
+                diff,
+            });
+        }
+
+        let _ = Self::cleanup_temp_dir(&temp_dir);
+        Ok(results)
+    }
+
+    /// 安装前扫描可发现技能（克隆仓库到临时目录，不写入 SSOT）
+    pub async fn scan_discoverable(&self, skill: &DiscoverableSkill) -> Result<SkillScanReport> {
+        let repo = SkillRepo {
+            owner: skill.repo_owner.clone(),
+            name: skill.repo_name.clone(),
+            branch: skill.repo_branch.clone(),
+            enabled: true,
+        };
+        let temp_dir = self.clone_repo(&repo).await?;
+
+        let source = temp_dir.join(&skill.directory);
+        let result = if source.exists() {
+            skill_scan::scan_dir(&source)
+        } else {
+            Err(anyhow!(format_skill_error(
+                "SKILL_DIR_NOT_FOUND",
+                &[("path", &source.display().to_string())],
+                Some("checkRepoUrl"),
+            )))
+        };
+
+        let _ = Self::cleanup_temp_dir(&temp_dir);
+        result
+    }
+
+    /// 扫描已安装技能（SSOT 中的当前内容）
+    pub fn scan_installed(db: &Arc<Database>, skill_id: &str) -> Result<SkillScanReport> {
+        let (_, skill_root) = Self::resolve_installed_skill_root(db, skill_id)?;
+        skill_scan::scan_dir(&skill_root)
+    }
+
     /// 清空所有技能缓存
     pub fn clear_cache(db: &Arc<Database>) -> Result<()> {
         db.clear_all_skill_cache()?;
@@ -1997,6 +2870,16 @@ impl SkillService {
             });
         };
 
+        // 已锁定版本的技能不跟随远程分支刷新
+        if installed_skill.pinned_ref.is_some() {
+            return Ok(SkillSingleRemoteRefreshResult {
+                skill_id: installed_skill.id,
+                updated: false,
+                matched_remote: false,
+                tree_commit_id: installed_skill.tree_commit_id,
+            });
+        }
+
         let repo_branch = installed_skill
             .repo_branch
             .clone()
@@ -2143,6 +3026,9 @@ impl SkillService {
         // 1. 去重后收集需要刷新的仓库集合
         let mut repos_by_key: HashMap<String, SkillRepo> = HashMap::new();
         for skill in &installed {
+            if skill.pinned_ref.is_some() {
+                continue;
+            }
             let (Some(repo_owner), Some(repo_name)) = (&skill.repo_owner, &skill.repo_name) else {
                 continue;
             };
@@ -2227,6 +3113,10 @@ impl SkillService {
         let mut updated_skills: usize = 0;
 
         for installed_skill in &mut installed {
+            // 已锁定版本的技能不跟随远程分支刷新
+            if installed_skill.pinned_ref.is_some() {
+                continue;
+            }
             let (Some(repo_owner), Some(repo_name)) =
                 (&installed_skill.repo_owner, &installed_skill.repo_name)
             else {
@@ -2381,13 +3271,7 @@ pub fn migrate_skills_to_ssot(db: &Arc<Database>) -> Result<usize> {
     let mut discovered: HashMap<String, SkillApps> = HashMap::new();
 
     // 扫描各应用目录
-    for app in [
-        AppType::Claude,
-        AppType::Codex,
-        AppType::Gemini,
-        AppType::OpenCode,
-        AppType::Cursor,
-    ] {
+    for app in AppType::ALL {
         let app_dir = match SkillService::get_app_skills_dir(&app) {
             Ok(d) => d,
             Err(_) => continue,
@@ -2456,6 +3340,8 @@ pub fn migrate_skills_to_ssot(db: &Arc<Database>) -> Result<usize> {
             repo_branch: None,
             readme_url: None,
             tree_commit_id: None,
+            pinned_ref: None,
+            commit_id: None,
             apps,
             installed_at: chrono::Utc::now().timestamp(),
         };
@@ -2469,5 +3355,80 @@ pub fn migrate_skills_to_ssot(db: &Arc<Database>) -> Result<usize> {
     Ok(count)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn installed(id: &str, repo: Option<(&str, &str)>) -> InstalledSkill {
+        InstalledSkill {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: None,
+            directory: id.rsplit(['/', ':']).next().unwrap_or(id).to_string(),
+            repo_owner: repo.map(|(owner, _)| owner.to_string()),
+            repo_name: repo.map(|(_, name)| name.to_string()),
+            repo_branch: repo.map(|_| "main".to_string()),
+            readme_url: None,
+            tree_commit_id: None,
+            pinned_ref: None,
+            commit_id: None,
+            apps: SkillApps::only(&AppType::Claude),
+            installed_at: 0,
+        }
+    }
+
+    #[test]
+    fn build_lockfile_skips_local_skills_and_sorts_by_id() {
+        let mut pinned = installed("acme/skills:tools/zeta", Some(("acme", "skills")));
+        pinned.pinned_ref = Some("v1.2.0".to_string());
+        let mut resolved = installed("acme/skills:tools/alpha", Some(("acme", "skills")));
+        resolved.commit_id = Some("0123456789abcdef".to_string());
+        resolved.pinned_ref = Some("v1.0.0".to_string());
+        let local = installed("local:mine", None);
+
+        let lockfile = SkillService::build_lockfile(&[pinned, local, resolved]);
+
+        assert_eq!(lockfile.version, SKILL_LOCKFILE_VERSION);
+        assert_eq!(
+            lockfile
+                .skills
+                .iter()
+                .map(|entry| entry.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["acme/skills:tools/alpha", "acme/skills:tools/zeta"]
+        );
+        // 优先记录实际 commit，其次才是锁定引用
+        assert_eq!(
+            lockfile.skills[0].commit.as_deref(),
+            Some("0123456789abcdef")
+        );
+        assert_eq!(lockfile.skills[1].commit.as_deref(), Some("v1.2.0"));
+        assert_eq!(lockfile.skills[1].path, "tools/zeta");
+    }
 
+    #[test]
+    fn lockfile_round_trips_through_json() {
+        let lockfile = SkillService::build_lockfile(&[installed(
+            "acme/skills:alpha",
+            Some(("acme", "skills")),
+        )]);
+        let json = serde_json::to_string_pretty(&lockfile).expect("serialize");
+        assert!(json.contains("\"repoOwner\": \"acme\""));
+
+        let parsed: SkillLockfile = serde_json::from_str(&json).expect("deserialize");
+        assert_eq!(parsed, lockfile);
+    }
 
+    #[test]
+    fn validate_git_ref_rejects_option_like_values() {
+        for valid in ["main", "v1.2.3", "0123abcd", "release/2024-01"] {
+            assert!(SkillService::validate_git_ref(valid).is_ok(), "{valid}");
+        }
+        for invalid in ["", "--upload-pack=evil", "a..b", "ref with space", "a;b"] {
+            assert!(
+                SkillService::validate_git_ref(invalid).is_err(),
+                "{invalid}"
+            );
+        }
+    }
+}
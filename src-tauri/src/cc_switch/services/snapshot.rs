@@ -0,0 +1,260 @@
+//! 数据库快照服务
+//!
+//! - 定时自动快照（每小时 / 每天），按配置保留最新的若干个
+//! - 列出、检查快照，并与当前库对比供应商与 MCP 服务器差异
+//! - 恢复快照：恢复前自动创建安全快照，恢复后同步各应用的 live 配置
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::cc_switch::app_config::McpServer;
+use crate::cc_switch::config::get_db_path;
+use crate::cc_switch::database::{
+    Database, DbSnapshotDetails, DbSnapshotDiff, DbSnapshotInfo, DbSnapshotKind,
+};
+use crate::cc_switch::error::AppError;
+use crate::cc_switch::prompt_files::prompt_apps;
+use crate::cc_switch::services::{McpService, PromptService, ProviderService};
+use crate::cc_switch::store::AppState;
+
+/// 默认保留的自动快照数量
+const DEFAULT_RETAIN: usize = 7;
+
+/// 允许保留的最大快照数量
+const MAX_RETAIN: usize = 100;
+
+/// 保留的恢复前安全快照数量
+const PRE_RESTORE_RETAIN: usize = 5;
+
+/// 后台检查间隔（秒）
+const SCHEDULER_POLL_SECS: u64 = 60;
+
+/// 自动快照频率
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SnapshotInterval {
+    Hourly,
+    #[default]
+    Daily,
+}
+
+impl SnapshotInterval {
+    fn as_secs(self) -> i64 {
+        match self {
+            Self::Hourly => 60 * 60,
+            Self::Daily => 24 * 60 * 60,
+        }
+    }
+}
+
+/// 定时快照配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotScheduleConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub interval: SnapshotInterval,
+    /// 保留的自动快照数量
+    #[serde(default = "default_retain")]
+    pub retain: usize,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_retain() -> usize {
+    DEFAULT_RETAIN
+}
+
+impl Default for SnapshotScheduleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval: SnapshotInterval::default(),
+            retain: DEFAULT_RETAIN,
+        }
+    }
+}
+
+impl SnapshotScheduleConfig {
+    /// 距上次自动快照是否已超过设定间隔
+    fn is_due(&self, last_created_at: Option<i64>, now: i64) -> bool {
+        self.enabled && last_created_at.is_none_or(|last| now - last >= self.interval.as_secs())
+    }
+}
+
+pub struct SnapshotService;
+
+impl SnapshotService {
+    /// 获取定时快照配置
+    pub fn get_schedule(state: &AppState) -> Result<SnapshotScheduleConfig, AppError> {
+        state.db.get_db_snapshot_schedule()
+    }
+
+    /// 更新定时快照配置，并立即按新的保留数量清理
+    pub fn set_schedule(
+        state: &AppState,
+        config: SnapshotScheduleConfig,
+    ) -> Result<SnapshotScheduleConfig, AppError> {
+        if config.retain == 0 || config.retain > MAX_RETAIN {
+            return Err(AppError::InvalidInput(format!(
+                "快照保留数量需在 1 到 {MAX_RETAIN} 之间"
+            )));
+        }
+        state.db.set_db_snapshot_schedule(&config)?;
+        Database::cleanup_snapshots(
+            &Database::snapshot_dir()?,
+            DbSnapshotKind::Scheduled,
+            config.retain,
+        )?;
+        Ok(config)
+    }
+
+    /// 列出全部快照（按创建时间倒序）
+    pub fn list() -> Result<Vec<DbSnapshotInfo>, AppError> {
+        Database::list_snapshots_in(&Database::snapshot_dir()?)
+    }
+
+    /// 立即创建一个自动快照
+    pub fn create_now(state: &AppState) -> Result<DbSnapshotInfo, AppError> {
+        let config = Self::get_schedule(state)?;
+        state.db.create_snapshot_in(
+            &Database::snapshot_dir()?,
+            DbSnapshotKind::Scheduled,
+            config.retain,
+        )
+    }
+
+    /// 到期时创建自动快照，未到期或未启用时返回 None
+    pub fn run_if_due(state: &AppState) -> Result<Option<DbSnapshotInfo>, AppError> {
+        let config = Self::get_schedule(state)?;
+        let dir = Database::snapshot_dir()?;
+        let last = Database::list_snapshots_in(&dir)?
+            .into_iter()
+            .find(|s| s.kind == DbSnapshotKind::Scheduled)
+            .map(|s| s.created_at);
+        if !config.is_due(last, chrono::Utc::now().timestamp()) {
+            return Ok(None);
+        }
+        let info = state
+            .db
+            .create_snapshot_in(&dir, DbSnapshotKind::Scheduled, config.retain)?;
+        log::info!("已创建数据库自动快照: {}", info.id);
+        Ok(Some(info))
+    }
+
+    /// 检查快照内容
+    pub fn inspect(id: &str) -> Result<DbSnapshotDetails, AppError> {
+        Database::inspect_snapshot_in(&Database::snapshot_dir()?, id)
+    }
+
+    /// 对比快照与当前库
+    pub fn diff(state: &AppState, id: &str) -> Result<DbSnapshotDiff, AppError> {
+        state.db.diff_snapshot_in(&Database::snapshot_dir()?, id)
+    }
+
+    /// 恢复快照，返回恢复前创建的安全快照
+    pub fn restore(state: &AppState, id: &str) -> Result<DbSnapshotInfo, AppError> {
+        let previous_mcp = state.db.get_all_mcp_servers()?;
+        let safety =
+            state
+                .db
+                .restore_snapshot_in(&Database::snapshot_dir()?, id, PRE_RESTORE_RETAIN)?;
+
+        if let Err(err) = ProviderService::sync_current_to_live(state) {
+            log::warn!("恢复快照后同步 live 配置失败: {err}");
+        }
+        if let Err(err) = Self::sync_mcp_after_restore(state, &previous_mcp) {
+            log::warn!("恢复快照后同步 MCP 配置失败: {err}");
+        }
+        for app in prompt_apps() {
+            let enabled = state
+                .db
+                .get_prompts(app.as_str())?
+                .into_values()
+                .find(|p| p.enabled);
+            if let Some(prompt) = enabled {
+                let id = prompt.id.clone();
+                if let Err(err) = PromptService::upsert_prompt(state, app.clone(), &id, prompt) {
+                    log::warn!("恢复快照后写入 {} 提示词失败: {err}", app.as_str());
+                }
+            }
+        }
+        if let Err(err) = crate::cc_switch::settings::reload_settings() {
+            log::warn!("恢复快照后重载设置失败: {err}");
+        }
+
+        Ok(safety)
+    }
+
+    /// 移除快照中已不存在或已停用的 MCP 服务器，再写入所有已启用的服务器
+    fn sync_mcp_after_restore(
+        state: &AppState,
+        previous: &IndexMap<String, McpServer>,
+    ) -> Result<(), AppError> {
+        let servers = state.db.get_all_mcp_servers()?;
+        for (id, old) in previous {
+            match servers.get(id) {
+                None => McpService::remove_server_from_all_apps(state, id, old)?,
+                Some(current) => {
+                    for app in old.apps.enabled_apps() {
+                        if !current.apps.is_enabled_for(&app) {
+                            McpService::remove_server_from_app(state, id, &app)?;
+                        }
+                    }
+                }
+            }
+        }
+        McpService::sync_all_enabled(state)
+    }
+}
+
+/// 后台定时快照任务
+pub async fn run_snapshot_scheduler(app: tauri::AppHandle) {
+    use tauri::Manager;
+
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(SCHEDULER_POLL_SECS)).await;
+
+        // 数据库文件尚未创建时无需快照
+        if !get_db_path().exists() {
+            continue;
+        }
+
+        let handle = app.clone();
+        let result = tauri::async_runtime::spawn_blocking(move || {
+            SnapshotService::run_if_due(&handle.state::<AppState>())
+        })
+        .await;
+
+        match result {
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => log::warn!("创建数据库自动快照失败: {e}"),
+            Err(e) => log::error!("数据库自动快照任务异常: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schedule_is_due_after_interval() {
+        let config = SnapshotScheduleConfig {
+            interval: SnapshotInterval::Hourly,
+            ..Default::default()
+        };
+        assert!(config.is_due(None, 1_000));
+        assert!(!config.is_due(Some(1_000), 1_000 + 3_599));
+        assert!(config.is_due(Some(1_000), 1_000 + 3_600));
+
+        let disabled = SnapshotScheduleConfig {
+            enabled: false,
+            ..config
+        };
+        assert!(!disabled.is_due(None, 1_000));
+    }
+}
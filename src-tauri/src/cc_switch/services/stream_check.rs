@@ -248,6 +248,14 @@ impl StreamCheckService {
                     "Cursor does not support health check yet",
                 ));
             }
+            AppType::Qwen => {
+                // Qwen Code doesn't support stream check yet
+                return Err(AppError::localized(
+                    "qwen_no_stream_check",
+                    "Qwen Code 暂不支持健康检查",
+                    "Qwen Code does not support health check yet",
+                ));
+            }
         };
 
         let response_time = start.elapsed().as_millis() as u64;
@@ -559,6 +567,8 @@ impl StreamCheckService {
                 // Cursor doesn't support stream check
                 "claude-3-5-sonnet-20241022".to_string()
             }
+            AppType::Qwen => Self::extract_env_model(provider, "OPENAI_MODEL")
+                .unwrap_or_else(|| config.codex_model.clone()),
         }
     }
 
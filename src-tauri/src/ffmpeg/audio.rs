@@ -0,0 +1,588 @@
+use std::path::Path;
+
+use super::get_ffmpeg_path;
+use super::info::probe_video_info;
+use super::job::{progress_command, run_job, run_job_with_stderr, JobProgress};
+use crate::models::StreamKind;
+
+fn default_mode() -> String {
+    "replace".to_string()
+}
+
+fn default_volume() -> f64 {
+    1.0
+}
+
+fn default_target_i() -> f64 {
+    -16.0
+}
+
+fn default_target_tp() -> f64 {
+    -1.5
+}
+
+fn default_target_lra() -> f64 {
+    11.0
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct AudioExtractParams {
+    pub format: String,       // mp3, aac, opus, wav, flac
+    pub bitrate: Option<u32>, // kbps，无损格式忽略
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct AudioReplaceParams {
+    /// 外部音频文件路径
+    pub audio_path: String,
+    #[serde(default = "default_mode")]
+    pub mode: String, // replace, mix
+    #[serde(default = "default_volume")]
+    pub volume: f64, // 外部音频音量倍数
+    #[serde(default = "default_volume")]
+    pub original_volume: f64, // 混音时原音轨音量倍数
+    /// 外部音频比视频短时循环播放
+    #[serde(default)]
+    pub loop_audio: bool,
+}
+
+/// EBU R128 响度标准化目标
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct LoudnormParams {
+    #[serde(default = "default_target_i")]
+    pub target_i: f64, // 综合响度 LUFS
+    #[serde(default = "default_target_tp")]
+    pub target_tp: f64, // 真峰值 dBTP
+    #[serde(default = "default_target_lra")]
+    pub target_lra: f64, // 响度范围 LU
+}
+
+/// `loudnorm` 滤镜打印的响度统计
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LoudnessStats {
+    pub input_i: f64,
+    pub input_tp: f64,
+    pub input_lra: f64,
+    pub input_thresh: f64,
+    pub output_i: f64,
+    pub output_tp: f64,
+    pub output_lra: f64,
+    pub output_thresh: f64,
+    /// dynamic / linear
+    pub normalization_type: String,
+    pub target_offset: f64,
+}
+
+/// 响度标准化结果
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LoudnormResult {
+    pub output_path: String,
+    pub stats: LoudnessStats,
+}
+
+/// 音频格式对应的编码器、扩展名与默认码率（kbps）
+fn audio_format(format: &str) -> Option<(&'static str, &'static str, Option<u32>)> {
+    match format {
+        "mp3" => Some(("libmp3lame", "mp3", Some(192))),
+        "aac" => Some(("aac", "m4a", Some(192))),
+        "opus" => Some(("libopus", "opus", Some(128))),
+        "wav" => Some(("pcm_s16le", "wav", None)),
+        "flac" => Some(("flac", "flac", None)),
+        _ => None,
+    }
+}
+
+/// 提取音频时输出文件的扩展名
+pub fn audio_extension(format: &str) -> Option<&'static str> {
+    audio_format(format).map(|(_, extension, _)| extension)
+}
+
+/// 根据输出容器选择音频编码器与码率（kbps）
+fn encoder_for_output(output_path: &str) -> (&'static str, Option<u32>) {
+    let extension = Path::new(output_path)
+        .extension()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "mp3" => ("libmp3lame", Some(192)),
+        "webm" | "opus" | "ogg" => ("libopus", Some(128)),
+        "wav" => ("pcm_s16le", None),
+        "flac" => ("flac", None),
+        _ => ("aac", Some(192)),
+    }
+}
+
+fn audio_codec_args(output_path: &str) -> Vec<String> {
+    let (encoder, bitrate) = encoder_for_output(output_path);
+    let mut args = vec!["-c:a".to_string(), encoder.to_string()];
+    if let Some(kbps) = bitrate {
+        args.extend(["-b:a".to_string(), format!("{}k", kbps)]);
+    }
+    args
+}
+
+/// 构建替换/混入外部音频的参数（不含输出路径）
+fn replace_args(
+    input_path: &str,
+    output_path: &str,
+    params: &AudioReplaceParams,
+    duration: f64,
+    has_audio: bool,
+) -> Result<Vec<String>, String> {
+    let mix = match params.mode.as_str() {
+        "replace" => false,
+        // 原视频没有音轨时混音等同于替换
+        "mix" => has_audio,
+        other => return Err(format!("未知的音频模式: {}", other)),
+    };
+
+    let mut args = vec!["-i".to_string(), input_path.to_string()];
+    if params.loop_audio {
+        args.extend(["-stream_loop".to_string(), "-1".to_string()]);
+    }
+    args.extend(["-i".to_string(), params.audio_path.clone()]);
+
+    let filter = if mix {
+        format!(
+            "[0:a:0]volume={}[a0];[1:a:0]volume={}[a1];\
+             [a0][a1]amix=inputs=2:duration=first:dropout_transition=0:normalize=0[outa]",
+            params.original_volume, params.volume
+        )
+    } else {
+        format!("[1:a:0]volume={}[outa]", params.volume)
+    };
+    args.extend([
+        "-filter_complex".to_string(),
+        filter,
+        "-map".to_string(),
+        "0:v?".to_string(),
+        "-map".to_string(),
+        "[outa]".to_string(),
+        "-c:v".to_string(),
+        "copy".to_string(),
+    ]);
+    args.extend(audio_codec_args(output_path));
+    // 输出时长与原视频一致
+    if duration > 0.0 {
+        args.extend(["-t".to_string(), duration.to_string()]);
+    }
+    Ok(args)
+}
+
+/// 从 stderr 中解析 `loudnorm` 打印的 JSON 统计
+///
+/// loudnorm 的数值以字符串形式输出，如 `"input_i" : "-27.61"`，静音时可能为 `-inf`。
+fn parse_loudness_stats(stderr: &str) -> Result<LoudnessStats, String> {
+    let start = stderr
+        .rfind("{")
+        .ok_or_else(|| "未找到响度测量结果".to_string())?;
+    let end = stderr[start..]
+        .find('}')
+        .map(|i| start + i + 1)
+        .ok_or_else(|| "响度测量结果不完整".to_string())?;
+    let value: serde_json::Value = serde_json::from_str(&stderr[start..end])
+        .map_err(|e| format!("解析响度测量结果失败: {}", e))?;
+
+    let text = |key: &str| -> Result<String, String> {
+        value
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+            .ok_or_else(|| format!("响度测量结果缺少字段: {}", key))
+    };
+    let number = |key: &str| -> Result<f64, String> {
+        text(key)?
+            .parse()
+            .map_err(|_| format!("响度测量结果字段无效: {}", key))
+    };
+
+    Ok(LoudnessStats {
+        input_i: number("input_i")?,
+        input_tp: number("input_tp")?,
+        input_lra: number("input_lra")?,
+        input_thresh: number("input_thresh")?,
+        output_i: number("output_i")?,
+        output_tp: number("output_tp")?,
+        output_lra: number("output_lra")?,
+        output_thresh: number("output_thresh")?,
+        normalization_type: text("normalization_type")?,
+        target_offset: number("target_offset")?,
+    })
+}
+
+fn loudnorm_targets(params: &LoudnormParams) -> String {
+    format!(
+        "loudnorm=I={}:TP={}:LRA={}",
+        params.target_i, params.target_tp, params.target_lra
+    )
+}
+
+/// 第二遍使用第一遍的测量值进行线性标准化
+fn loudnorm_second_pass_filter(params: &LoudnormParams, measured: &LoudnessStats) -> String {
+    format!(
+        "{}:measured_I={}:measured_TP={}:measured_LRA={}:measured_thresh={}:offset={}:linear=true:print_format=json",
+        loudnorm_targets(params),
+        measured.input_i,
+        measured.input_tp,
+        measured.input_lra,
+        measured.input_thresh,
+        measured.target_offset
+    )
+}
+
+/// 提取音轨
+///
+/// # Arguments
+/// * input_path - 输入视频文件路径
+/// * output_path - 输出音频文件路径（扩展名应与格式一致，见 [`audio_extension`]）
+/// * params - 提取参数
+/// * job_id - 任务ID，可通过 `cancel_job` 取消
+/// * on_progress - 进度回调函数
+pub async fn extract_audio(
+    input_path: String,
+    output_path: String,
+    params: AudioExtractParams,
+    job_id: String,
+    on_progress: impl Fn(JobProgress) + Send + 'static,
+) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let ffmpeg_path = get_ffmpeg_path().ok_or_else(|| "FFmpeg未找到".to_string())?;
+
+        let (encoder, _, default_bitrate) = audio_format(&params.format)
+            .ok_or_else(|| format!("不支持的音频格式: {}", params.format))?;
+        let duration = probe_video_info(&ffmpeg_path, input_path.clone())?.duration;
+
+        let mut cmd = progress_command(&ffmpeg_path);
+        cmd.arg("-i").arg(&input_path);
+        cmd.args(["-vn", "-map", "0:a:0"]);
+        cmd.arg("-c:a").arg(encoder);
+        if let Some(kbps) = default_bitrate.map(|d| params.bitrate.unwrap_or(d)) {
+            cmd.arg("-b:a").arg(format!("{}k", kbps));
+        }
+        cmd.arg("-y").arg(&output_path);
+
+        run_job(
+            &job_id,
+            cmd,
+            duration,
+            "提取音频失败",
+            &on_progress,
+            || {
+                let _ = std::fs::remove_file(&output_path);
+            },
+        )
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+/// 替换音轨或混入外部音频（视频流直接复制）
+///
+/// # Arguments
+/// * input_path - 输入视频文件路径
+/// * output_path - 输出视频文件路径
+/// * params - 音频参数
+/// * job_id - 任务ID，可通过 `cancel_job` 取消
+/// * on_progress - 进度回调函数
+pub async fn replace_audio(
+    input_path: String,
+    output_path: String,
+    params: AudioReplaceParams,
+    job_id: String,
+    on_progress: impl Fn(JobProgress) + Send + 'static,
+) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let ffmpeg_path = get_ffmpeg_path().ok_or_else(|| "FFmpeg未找到".to_string())?;
+
+        if !Path::new(&params.audio_path).is_file() {
+            return Err(format!("音频文件不存在: {}", params.audio_path));
+        }
+
+        let info = probe_video_info(&ffmpeg_path, input_path.clone())?;
+        // 没有流信息时无法确认原视频是否有音轨，按有音轨处理
+        let has_audio =
+            info.streams.is_empty() || info.streams.iter().any(|s| s.kind == StreamKind::Audio);
+
+        let mut cmd = progress_command(&ffmpeg_path);
+        cmd.args(replace_args(
+            &input_path,
+            &output_path,
+            &params,
+            info.duration,
+            has_audio,
+        )?);
+        cmd.arg("-y").arg(&output_path);
+
+        run_job(
+            &job_id,
+            cmd,
+            info.duration,
+            "替换音频失败",
+            &on_progress,
+            || {
+                let _ = std::fs::remove_file(&output_path);
+            },
+        )
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+/// 去除音轨（视频流直接复制）
+///
+/// # Arguments
+/// * input_path - 输入视频文件路径
+/// * output_path - 输出视频文件路径
+/// * job_id - 任务ID，可通过 `cancel_job` 取消
+/// * on_progress - 进度回调函数
+pub async fn mute_video(
+    input_path: String,
+    output_path: String,
+    job_id: String,
+    on_progress: impl Fn(JobProgress) + Send + 'static,
+) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let ffmpeg_path = get_ffmpeg_path().ok_or_else(|| "FFmpeg未找到".to_string())?;
+
+        let duration = probe_video_info(&ffmpeg_path, input_path.clone())?.duration;
+
+        let mut cmd = progress_command(&ffmpeg_path);
+        cmd.arg("-i").arg(&input_path);
+        cmd.args(["-c", "copy", "-an"]);
+        cmd.arg("-y").arg(&output_path);
+
+        run_job(&job_id, cmd, duration, "静音失败", &on_progress, || {
+            let _ = std::fs::remove_file(&output_path);
+        })
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+/// 测量响度（loudnorm 第一遍）
+fn measure(
+    ffmpeg_path: &Path,
+    input_path: &str,
+    params: &LoudnormParams,
+    duration: f64,
+    job_id: &str,
+    on_progress: &dyn Fn(JobProgress),
+) -> Result<LoudnessStats, String> {
+    let mut cmd = progress_command(ffmpeg_path);
+    cmd.arg("-i").arg(input_path);
+    cmd.args(["-map", "0:a:0", "-af"]);
+    cmd.arg(format!("{}:print_format=json", loudnorm_targets(params)));
+    cmd.args(["-f", "null", "-"]);
+
+    let stderr = run_job_with_stderr(job_id, cmd, duration, "响度测量失败", on_progress, || {})?;
+    parse_loudness_stats(&stderr)
+}
+
+/// 测量音频响度（EBU R128）
+///
+/// # Returns
+/// 返回测量结果，`input_*` 为原始响度
+pub async fn measure_loudness(
+    input_path: String,
+    params: LoudnormParams,
+    job_id: String,
+    on_progress: impl Fn(JobProgress) + Send + 'static,
+) -> Result<LoudnessStats, String> {
+    tokio::task::spawn_blocking(move || {
+        let ffmpeg_path = get_ffmpeg_path().ok_or_else(|| "FFmpeg未找到".to_string())?;
+
+        let duration = probe_video_info(&ffmpeg_path, input_path.clone())?.duration;
+        measure(
+            &ffmpeg_path,
+            &input_path,
+            &params,
+            duration,
+            &job_id,
+            &on_progress,
+        )
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+/// 响度标准化（EBU R128 两遍 loudnorm，视频流直接复制）
+///
+/// # Arguments
+/// * input_path - 输入音视频文件路径
+/// * output_path - 输出文件路径
+/// * params - 目标响度
+/// * job_id - 任务ID，可通过 `cancel_job` 取消
+/// * on_progress - 进度回调函数
+///
+/// # Returns
+/// 返回第二遍的统计结果：`input_*` 为原始响度，`output_*` 为标准化后的响度
+pub async fn normalize_loudness(
+    input_path: String,
+    output_path: String,
+    params: LoudnormParams,
+    job_id: String,
+    on_progress: impl Fn(JobProgress) + Send + 'static,
+) -> Result<LoudnessStats, String> {
+    tokio::task::spawn_blocking(move || {
+        let ffmpeg_path = get_ffmpeg_path().ok_or_else(|| "FFmpeg未找到".to_string())?;
+
+        let info = probe_video_info(&ffmpeg_path, input_path.clone())?;
+        let duration = info.duration;
+        // loudnorm 内部以 192kHz 处理，输出需恢复原采样率
+        let sample_rate = info
+            .streams
+            .iter()
+            .find(|s| s.kind == StreamKind::Audio)
+            .and_then(|s| s.sample_rate)
+            .unwrap_or(48000);
+
+        // 第一遍测量占前一半进度，第二遍标准化占后一半
+        let first_progress = |mut progress: JobProgress| {
+            progress.percent /= 2.0;
+            on_progress(progress);
+        };
+        let measured = measure(
+            &ffmpeg_path,
+            &input_path,
+            &params,
+            duration,
+            &job_id,
+            &first_progress,
+        )?;
+        println!(
+            "响度测量: I={} LUFS, TP={} dBTP, LRA={} LU",
+            measured.input_i, measured.input_tp, measured.input_lra
+        );
+
+        let mut cmd = progress_command(&ffmpeg_path);
+        cmd.arg("-i").arg(&input_path);
+        cmd.args(["-map", "0:v?", "-map", "0:a:0", "-c:v", "copy", "-af"]);
+        cmd.arg(loudnorm_second_pass_filter(&params, &measured));
+        cmd.args(audio_codec_args(&output_path));
+        cmd.arg("-ar").arg(sample_rate.to_string());
+        cmd.arg("-y").arg(&output_path);
+
+        let second_progress = |mut progress: JobProgress| {
+            progress.percent = 50.0 + progress.percent / 2.0;
+            on_progress(progress);
+        };
+        let stderr = run_job_with_stderr(
+            &job_id,
+            cmd,
+            duration,
+            "响度标准化失败",
+            &second_progress,
+            || {
+                let _ = std::fs::remove_file(&output_path);
+            },
+        )?;
+
+        // 第二遍统计解析失败不影响输出，返回测量值
+        Ok(parse_loudness_stats(&stderr).unwrap_or(measured))
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOUDNORM_STDERR: &str = r#"[Parsed_loudnorm_0 @ 0x600000b5c000]
+{
+	"input_i" : "-27.61",
+	"input_tp" : "-4.47",
+	"input_lra" : "18.06",
+	"input_thresh" : "-39.20",
+	"output_i" : "-16.58",
+	"output_tp" : "-1.50",
+	"output_lra" : "14.78",
+	"output_thresh" : "-27.71",
+	"normalization_type" : "dynamic",
+	"target_offset" : "0.58"
+}
+[out#0/null @ 0x600001c58000] video:0KiB audio:1KiB subtitle:0KiB"#;
+
+    fn replace_params(mode: &str) -> AudioReplaceParams {
+        serde_json::from_value(serde_json::json!({
+            "audio_path": "music.mp3",
+            "mode": mode,
+            "volume": 0.5
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn parses_loudnorm_json() {
+        let stats = parse_loudness_stats(LOUDNORM_STDERR).unwrap();
+        assert_eq!(stats.input_i, -27.61);
+        assert_eq!(stats.input_thresh, -39.2);
+        assert_eq!(stats.output_tp, -1.5);
+        assert_eq!(stats.normalization_type, "dynamic");
+        assert_eq!(stats.target_offset, 0.58);
+
+        assert!(parse_loudness_stats("no stats here").is_err());
+        let silent = LOUDNORM_STDERR.replace("\"-27.61\"", "\"-inf\"");
+        assert_eq!(
+            parse_loudness_stats(&silent).unwrap().input_i,
+            f64::NEG_INFINITY
+        );
+    }
+
+    #[test]
+    fn second_pass_uses_measured_values() {
+        let params: LoudnormParams = serde_json::from_value(serde_json::json!({})).unwrap();
+        let measured = parse_loudness_stats(LOUDNORM_STDERR).unwrap();
+        assert_eq!(
+            loudnorm_second_pass_filter(&params, &measured),
+            "loudnorm=I=-16:TP=-1.5:LRA=11:measured_I=-27.61:measured_TP=-4.47:\
+             measured_LRA=18.06:measured_thresh=-39.2:offset=0.58:linear=true:print_format=json"
+        );
+    }
+
+    #[test]
+    fn replace_and_mix_args() {
+        let replace = replace_args("in.mp4", "out.mp4", &replace_params("replace"), 12.5, true)
+            .unwrap()
+            .join(" ");
+        assert_eq!(
+            replace,
+            "-i in.mp4 -i music.mp3 -filter_complex [1:a:0]volume=0.5[outa] \
+             -map 0:v? -map [outa] -c:v copy -c:a aac -b:a 192k -t 12.5"
+        );
+
+        let mix = replace_args("in.webm", "out.webm", &replace_params("mix"), 0.0, true)
+            .unwrap()
+            .join(" ");
+        assert!(mix.contains("[0:a:0]volume=1[a0];[1:a:0]volume=0.5[a1];[a0][a1]amix=inputs=2"));
+        assert!(mix.ends_with("-c:a libopus -b:a 128k"));
+
+        // 原视频无音轨时混音退化为替换
+        let silent = replace_args("in.mp4", "out.mp4", &replace_params("mix"), 0.0, false)
+            .unwrap()
+            .join(" ");
+        assert!(!silent.contains("amix"));
+
+        assert!(replace_args("in.mp4", "out.mp4", &replace_params("duck"), 0.0, true).is_err());
+    }
+
+    #[test]
+    fn loop_audio_precedes_external_input() {
+        let mut params = replace_params("replace");
+        params.loop_audio = true;
+        let args = replace_args("in.mp4", "out.mp4", &params, 10.0, true)
+            .unwrap()
+            .join(" ");
+        assert!(args.starts_with("-i in.mp4 -stream_loop -1 -i music.mp3"));
+    }
+
+    #[test]
+    fn audio_formats() {
+        assert_eq!(audio_extension("aac"), Some("m4a"));
+        assert_eq!(audio_extension("flac"), Some("flac"));
+        assert_eq!(audio_extension("ogg"), None);
+        assert_eq!(encoder_for_output("/a/b.WAV"), ("pcm_s16le", None));
+        assert_eq!(encoder_for_output("/a/b.mkv"), ("aac", Some(192)));
+    }
+}
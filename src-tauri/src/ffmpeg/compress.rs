@@ -1,15 +1,257 @@
-use std::process::{Command, Stdio};
+use std::path::{Path, PathBuf};
+
+use super::encoders::{
+    list_encoders, resolve_audio_encoder, resolve_video_encoder, supports_two_pass,
+};
 use super::get_ffmpeg_path;
+use super::info::probe_video_info;
+use super::job::{progress_command, run_job, JobProgress};
+use crate::models::StreamKind;
+
+/// 目标大小中为容器开销预留的比例
+const CONTAINER_OVERHEAD: f64 = 0.03;
+
+/// 目标大小模式下视频码率的下限（kbps）
+const MIN_VIDEO_KBPS: f64 = 50.0;
+
+/// 复制音频流且无法读取其码率时的估计值（kbps）
+const DEFAULT_AUDIO_KBPS: u32 = 128;
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct CompressParams {
-    pub preset: String,  // mobile, web, high_quality, custom
+    pub preset: String, // mobile, web, high_quality, custom
     pub width: Option<u32>,
     pub height: Option<u32>,
     pub bitrate: Option<u32>,  // kbps
-    pub crf: Option<u32>,  // 0-51
-    pub codec: Option<String>,  // h264, h265, vp9
+    pub crf: Option<u32>,      // 0-51（AV1 为 0-63）
+    pub codec: Option<String>, // h264, h265, vp9, av1
     pub fps: Option<u32>,
+    /// 目标文件大小（MB，按 1 MB = 1,000,000 字节计算）
+    ///
+    /// 设置后根据时长计算视频码率，编码器支持时使用两遍编码，忽略 crf/bitrate
+    pub target_size_mb: Option<f64>,
+    pub audio_codec: Option<String>, // aac, opus, mp3, copy
+    pub audio_bitrate: Option<u32>,  // kbps，默认 128
+    /// 混音为单声道
+    #[serde(default)]
+    pub audio_mono: bool,
+    /// 去除音轨
+    #[serde(default)]
+    pub strip_audio: bool,
+}
+
+/// 视频码率控制方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RateControl {
+    /// 恒定质量，可选码率上限（kbps）
+    Quality { crf: u32, bitrate: Option<u32> },
+    /// 平均码率（kbps）
+    Bitrate(u32),
+}
+
+/// 音频处理方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AudioPlan {
+    Strip,
+    Copy,
+    Encode {
+        encoder: &'static str,
+        bitrate: u32,
+        mono: bool,
+    },
+}
+
+/// 两遍编码中的一遍
+#[derive(Debug, Clone, Copy)]
+struct Pass<'a> {
+    number: u8,
+    log_prefix: &'a Path,
+}
+
+/// 根据参数确定音频处理方式
+fn audio_plan(
+    params: &CompressParams,
+    encoders: Option<&[super::encoders::EncoderInfo]>,
+) -> Result<AudioPlan, String> {
+    if params.strip_audio {
+        return Ok(AudioPlan::Strip);
+    }
+    match params.audio_codec.as_deref().unwrap_or("aac") {
+        "copy" => {
+            if params.audio_mono {
+                return Err("复制音频流时无法转换为单声道".to_string());
+            }
+            Ok(AudioPlan::Copy)
+        }
+        codec => Ok(AudioPlan::Encode {
+            encoder: resolve_audio_encoder(codec, encoders)?,
+            bitrate: params.audio_bitrate.unwrap_or(DEFAULT_AUDIO_KBPS),
+            mono: params.audio_mono,
+        }),
+    }
+}
+
+/// 恒定质量模式下的 CRF
+fn quality_crf(params: &CompressParams, encoder: &str) -> u32 {
+    // AV1 的 CRF 范围为 0-63，同等画质下数值高于 H.264
+    let is_av1 = matches!(encoder, "libsvtav1" | "libaom-av1");
+    match (params.preset.as_str(), is_av1) {
+        ("mobile", false) => 26,
+        ("web", false) => 30,
+        ("high_quality", false) => 23,
+        ("mobile", true) => 38,
+        ("web", true) => 42,
+        ("high_quality", true) => 30,
+        (_, false) => params.crf.unwrap_or(23),
+        (_, true) => params.crf.unwrap_or(32),
+    }
+}
+
+/// 根据目标大小计算视频码率（kbps）
+fn target_video_bitrate(
+    target_size_mb: f64,
+    duration: f64,
+    audio_kbps: u32,
+) -> Result<u32, String> {
+    if duration <= 0.0 {
+        return Err("无法获取视频时长，不能按目标大小压缩".to_string());
+    }
+    if target_size_mb <= 0.0 {
+        return Err("目标大小必须大于 0".to_string());
+    }
+
+    let total_kbps = target_size_mb * 8_000.0 * (1.0 - CONTAINER_OVERHEAD) / duration;
+    let video_kbps = total_kbps - audio_kbps as f64;
+    if video_kbps < MIN_VIDEO_KBPS {
+        let min_size_mb =
+            (MIN_VIDEO_KBPS + audio_kbps as f64) * duration / 8_000.0 / (1.0 - CONTAINER_OVERHEAD);
+        return Err(format!(
+            "目标大小过小：该视频至少需要约 {:.1} MB",
+            min_size_mb
+        ));
+    }
+    Ok(video_kbps.floor() as u32)
+}
+
+/// 构建 FFmpeg 参数（不含 `progress_command` 的通用参数）
+///
+/// `output` 为 `None` 时为两遍编码的第一遍，只分析不输出。
+fn build_args(
+    input_path: &str,
+    output_path: Option<&str>,
+    params: &CompressParams,
+    encoder: &str,
+    rate: RateControl,
+    audio: AudioPlan,
+    pass: Option<Pass>,
+) -> Vec<String> {
+    let mut args: Vec<String> = vec![
+        "-i".into(),
+        input_path.into(),
+        "-c:v".into(),
+        encoder.into(),
+    ];
+
+    // 分辨率与帧率
+    if let (Some(w), Some(h)) = (params.width, params.height) {
+        args.extend(["-vf".into(), format!("scale={}:{}", w, h)]);
+    }
+    if let Some(fps) = params.fps {
+        args.extend(["-r".into(), fps.to_string()]);
+    }
+
+    // 编码速度
+    match encoder {
+        "libx264" | "libx265" => args.extend(["-preset".into(), "medium".into()]),
+        "libvpx-vp9" => {
+            args.extend(["-deadline", "good", "-cpu-used", "2", "-row-mt", "1"].map(String::from))
+        }
+        "libsvtav1" => args.extend(["-preset".into(), "8".into()]),
+        "libaom-av1" => args.extend(["-cpu-used", "6", "-row-mt", "1"].map(String::from)),
+        _ => {}
+    }
+
+    // 码率控制
+    match rate {
+        RateControl::Quality { crf, bitrate } => {
+            args.extend(["-crf".into(), crf.to_string()]);
+            match bitrate {
+                Some(kbps) => args.extend(["-b:v".into(), format!("{}k", kbps)]),
+                // libvpx/libaom 需要 -b:v 0 才是恒定质量模式
+                None if matches!(encoder, "libvpx-vp9" | "libaom-av1") => {
+                    args.extend(["-b:v".into(), "0".into()])
+                }
+                None => {}
+            }
+        }
+        RateControl::Bitrate(kbps) => args.extend(["-b:v".into(), format!("{}k", kbps)]),
+    }
+
+    if let Some(pass) = pass {
+        let prefix = pass.log_prefix.to_string_lossy();
+        if encoder == "libx265" {
+            args.extend([
+                "-x265-params".into(),
+                format!("pass={}:stats={}.log", pass.number, prefix),
+            ]);
+        } else {
+            args.extend([
+                "-pass".into(),
+                pass.number.to_string(),
+                "-passlogfile".into(),
+                prefix.to_string(),
+            ]);
+        }
+    }
+
+    let Some(output_path) = output_path else {
+        // 第一遍：丢弃输出
+        args.extend(["-an", "-f", "null", "-"].map(String::from));
+        return args;
+    };
+
+    match audio {
+        AudioPlan::Strip => args.push("-an".into()),
+        AudioPlan::Copy => args.extend(["-c:a".into(), "copy".into()]),
+        AudioPlan::Encode {
+            encoder,
+            bitrate,
+            mono,
+        } => {
+            args.extend([
+                "-c:a".into(),
+                encoder.into(),
+                "-b:a".into(),
+                format!("{}k", bitrate),
+            ]);
+            if mono {
+                args.extend(["-ac".into(), "1".into()]);
+            }
+        }
+    }
+
+    args.extend(["-y".into(), output_path.into()]);
+    args
+}
+
+/// 两遍编码日志文件前缀
+fn pass_log_prefix(job_id: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("mnemosyne-2pass-{}", job_id))
+}
+
+/// 删除两遍编码产生的日志文件（`{前缀}-0.log`、`.mbtree`、`.cutree` 等）
+fn remove_pass_logs(prefix: &Path) {
+    let (Some(dir), Some(name)) = (prefix.parent(), prefix.file_name().and_then(|n| n.to_str()))
+    else {
+        return;
+    };
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with(name) {
+                let _ = std::fs::remove_file(entry.path());
+            }
+        }
+    }
 }
 
 /// 压缩视频
@@ -18,81 +260,342 @@ pub struct CompressParams {
 /// * input_path - 输入视频文件路径
 /// * output_path - 输出视频文件路径
 /// * params - 压缩参数
-/// * on_progress - 进度回调函数 (0.0-100.0)
+/// * job_id - 任务ID，可通过 `cancel_job` 取消
+/// * on_progress - 进度回调函数
 pub async fn compress_video(
     input_path: String,
     output_path: String,
     params: CompressParams,
-    on_progress: impl Fn(f64) + Send + 'static,
+    job_id: String,
+    on_progress: impl Fn(JobProgress) + Send + 'static,
 ) -> Result<(), String> {
     tokio::task::spawn_blocking(move || {
-        let ffmpeg_path = get_ffmpeg_path()
-            .ok_or_else(|| "FFmpeg未找到".to_string())?;
+        let ffmpeg_path = get_ffmpeg_path().ok_or_else(|| "FFmpeg未找到".to_string())?;
+
+        let info = probe_video_info(&ffmpeg_path, input_path.clone())?;
+        let duration = info.duration;
 
-        let mut cmd = Command::new(&ffmpeg_path);
+        // 只使用已安装的编码器
+        let encoders = list_encoders(&ffmpeg_path).ok();
+        let encoder = resolve_video_encoder(
+            params.codec.as_deref().unwrap_or("h264"),
+            encoders.as_deref(),
+        )?;
+        let audio = audio_plan(&params, encoders.as_deref())?;
 
-        // 输入文件
-        cmd.arg("-i").arg(&input_path);
+        let remove_output = || {
+            let _ = std::fs::remove_file(&output_path);
+        };
 
-        // 视频编码器
-        let codec = params.codec.as_ref().map(|s| s.as_str()).unwrap_or("libx264");
-        match codec {
-            "h265" => cmd.arg("-c:v").arg("libx265"),
-            "vp9" => cmd.arg("-c:v").arg("libvpx-vp9"),
-            _ => cmd.arg("-c:v").arg("libx264"),
+        let Some(target_size_mb) = params.target_size_mb else {
+            let rate = RateControl::Quality {
+                crf: quality_crf(&params, encoder),
+                bitrate: params.bitrate,
+            };
+            let mut cmd = progress_command(&ffmpeg_path);
+            cmd.args(build_args(
+                &input_path,
+                Some(&output_path),
+                &params,
+                encoder,
+                rate,
+                audio,
+                None,
+            ));
+            return run_job(
+                &job_id,
+                cmd,
+                duration,
+                "压缩失败",
+                &on_progress,
+                remove_output,
+            );
         };
 
-        // 分辨率
-        if let Some(w) = params.width {
-            if let Some(h) = params.height {
-                cmd.arg("-vf").arg(format!("scale={}:{}", w, h));
+        let audio_kbps = match audio {
+            AudioPlan::Strip => 0,
+            AudioPlan::Encode { bitrate, .. } => bitrate,
+            AudioPlan::Copy => {
+                let bps: u64 = info
+                    .streams
+                    .iter()
+                    .filter(|s| s.kind == StreamKind::Audio)
+                    .filter_map(|s| s.bitrate)
+                    .sum();
+                if bps > 0 {
+                    (bps / 1000) as u32
+                } else {
+                    DEFAULT_AUDIO_KBPS
+                }
             }
-        }
+        };
+        let rate =
+            RateControl::Bitrate(target_video_bitrate(target_size_mb, duration, audio_kbps)?);
 
-        // 帧率
-        if let Some(fps) = params.fps {
-            cmd.arg("-r").arg(fps.to_string());
+        if !supports_two_pass(encoder) {
+            let mut cmd = progress_command(&ffmpeg_path);
+            cmd.args(build_args(
+                &input_path,
+                Some(&output_path),
+                &params,
+                encoder,
+                rate,
+                audio,
+                None,
+            ));
+            return run_job(
+                &job_id,
+                cmd,
+                duration,
+                "压缩失败",
+                &on_progress,
+                remove_output,
+            );
         }
 
-        // CRF质量控制
-        let preset_crf = match params.preset.as_str() {
-            "mobile" => 26,
-            "web" => 30,
-            "high_quality" => 23,
-            _ => params.crf.unwrap_or(23),
+        // 两遍编码：第一遍分析占前一半进度，第二遍编码占后一半
+        let log_prefix = pass_log_prefix(&job_id);
+        let first = Pass {
+            number: 1,
+            log_prefix: &log_prefix,
+        };
+        let second = Pass {
+            number: 2,
+            log_prefix: &log_prefix,
         };
-        cmd.arg("-crf").arg(preset_crf.to_string());
 
-        // 预设速度
-        cmd.arg("-preset").arg("medium");
+        let mut cmd = progress_command(&ffmpeg_path);
+        cmd.args(build_args(
+            &input_path,
+            None,
+            &params,
+            encoder,
+            rate,
+            audio,
+            Some(first),
+        ));
+        let first_progress = |mut progress: JobProgress| {
+            progress.percent /= 2.0;
+            // 假设第二遍速度与第一遍相同
+            progress.eta = progress
+                .eta
+                .zip(progress.speed)
+                .map(|(eta, speed)| eta + progress.duration / speed);
+            on_progress(progress);
+        };
+        let result = run_job(
+            &job_id,
+            cmd,
+            duration,
+            "第一遍编码失败",
+            &first_progress,
+            || {},
+        )
+        .and_then(|_| {
+            let mut cmd = progress_command(&ffmpeg_path);
+            cmd.args(build_args(
+                &input_path,
+                Some(&output_path),
+                &params,
+                encoder,
+                rate,
+                audio,
+                Some(second),
+            ));
+            let second_progress = |mut progress: JobProgress| {
+                progress.percent = 50.0 + progress.percent / 2.0;
+                on_progress(progress);
+            };
+            run_job(
+                &job_id,
+                cmd,
+                duration,
+                "压缩失败",
+                &second_progress,
+                remove_output,
+            )
+        });
 
-        // 比特率
-        if let Some(bitrate) = params.bitrate {
-            cmd.arg("-b:v").arg(format!("{}k", bitrate));
-        }
+        remove_pass_logs(&log_prefix);
+        result
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        // 音频编码
-        cmd.arg("-c:a").arg("aac");
-        cmd.arg("-b:a").arg("128k");
+    fn params(json: serde_json::Value) -> CompressParams {
+        serde_json::from_value(json).unwrap()
+    }
 
-        // 输出文件
-        cmd.arg("-y").arg(&output_path);
+    fn joined(args: &[String]) -> String {
+        args.join(" ")
+    }
 
-        // 执行命令
-        let output = cmd
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .map_err(|e| format!("执行FFmpeg失败: {}", e))?;
+    #[test]
+    fn legacy_params_still_deserialize() {
+        let p = params(serde_json::json!({"preset": "mobile"}));
+        assert_eq!(p.target_size_mb, None);
+        assert!(!p.strip_audio);
+        assert_eq!(
+            audio_plan(&p, None),
+            Ok(AudioPlan::Encode {
+                encoder: "aac",
+                bitrate: 128,
+                mono: false
+            })
+        );
+    }
 
-        if !output.status.success() {
-            let error = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("压缩失败: {}", error));
+    #[test]
+    fn target_bitrate_accounts_for_audio_and_overhead() {
+        // 25 MB / 60 秒 ≈ 3233 kbps，减去 128 kbps 音频
+        assert_eq!(target_video_bitrate(25.0, 60.0, 128), Ok(3105));
+        assert_eq!(target_video_bitrate(25.0, 60.0, 0), Ok(3233));
+
+        let err = target_video_bitrate(1.0, 600.0, 128).unwrap_err();
+        assert!(err.contains("至少需要约 13.8 MB"), "{}", err);
+        assert!(target_video_bitrate(25.0, 0.0, 128).is_err());
+    }
+
+    #[test]
+    fn quality_mode_matches_previous_h264_args() {
+        let p = params(serde_json::json!({"preset": "web"}));
+        let audio = audio_plan(&p, None).unwrap();
+        let rate = RateControl::Quality {
+            crf: quality_crf(&p, "libx264"),
+            bitrate: None,
+        };
+        let args = build_args("in.mp4", Some("out.mp4"), &p, "libx264", rate, audio, None);
+        assert_eq!(
+            joined(&args),
+            "-i in.mp4 -c:v libx264 -preset medium -crf 30 -c:a aac -b:a 128k -y out.mp4"
+        );
+    }
+
+    #[test]
+    fn av1_uses_its_own_crf_scale_and_speed_options() {
+        let p = params(serde_json::json!({"preset": "high_quality", "codec": "av1"}));
+        let rate = RateControl::Quality {
+            crf: quality_crf(&p, "libaom-av1"),
+            bitrate: None,
+        };
+        let args = build_args(
+            "in.mp4",
+            Some("out.mkv"),
+            &p,
+            "libaom-av1",
+            rate,
+            AudioPlan::Strip,
+            None,
+        );
+        assert_eq!(
+            joined(&args),
+            "-i in.mp4 -c:v libaom-av1 -cpu-used 6 -row-mt 1 -crf 30 -b:v 0 -an -y out.mkv"
+        );
+        assert_eq!(quality_crf(&p, "libsvtav1"), 30);
+    }
+
+    #[test]
+    fn two_pass_args() {
+        let p = params(serde_json::json!({
+            "preset": "custom",
+            "target_size_mb": 25.0,
+            "audio_codec": "opus",
+            "audio_bitrate": 64,
+            "audio_mono": true
+        }));
+        let audio = audio_plan(&p, None).unwrap();
+        let prefix = Path::new("/tmp/log");
+        let rate = RateControl::Bitrate(3000);
+
+        let first = build_args(
+            "in.mp4",
+            None,
+            &p,
+            "libx264",
+            rate,
+            audio,
+            Some(Pass {
+                number: 1,
+                log_prefix: prefix,
+            }),
+        );
+        assert_eq!(
+            joined(&first),
+            "-i in.mp4 -c:v libx264 -preset medium -b:v 3000k -pass 1 -passlogfile /tmp/log -an -f null -"
+        );
+
+        let second = build_args(
+            "in.mp4",
+            Some("out.mp4"),
+            &p,
+            "libx264",
+            rate,
+            audio,
+            Some(Pass {
+                number: 2,
+                log_prefix: prefix,
+            }),
+        );
+        assert_eq!(
+            joined(&second),
+            "-i in.mp4 -c:v libx264 -preset medium -b:v 3000k -pass 2 -passlogfile /tmp/log \
+             -c:a libopus -b:a 64k -ac 1 -y out.mp4"
+        );
+
+        let x265 = build_args(
+            "in.mp4",
+            None,
+            &p,
+            "libx265",
+            rate,
+            audio,
+            Some(Pass {
+                number: 1,
+                log_prefix: prefix,
+            }),
+        );
+        assert!(joined(&x265).contains("-x265-params pass=1:stats=/tmp/log.log"));
+    }
+
+    #[test]
+    fn audio_options() {
+        let strip =
+            params(serde_json::json!({"preset": "web", "strip_audio": true, "audio_mono": true}));
+        assert_eq!(audio_plan(&strip, None), Ok(AudioPlan::Strip));
+
+        let copy = params(serde_json::json!({"preset": "web", "audio_codec": "copy"}));
+        assert_eq!(audio_plan(&copy, None), Ok(AudioPlan::Copy));
+
+        let copy_mono =
+            params(serde_json::json!({"preset": "web", "audio_codec": "copy", "audio_mono": true}));
+        assert!(audio_plan(&copy_mono, None).is_err());
+
+        let unknown = params(serde_json::json!({"preset": "web", "audio_codec": "flac"}));
+        assert!(audio_plan(&unknown, None).is_err());
+    }
+
+    #[test]
+    fn remove_pass_logs_only_touches_prefix() {
+        let dir = tempfile::tempdir().unwrap();
+        let prefix = dir.path().join("mnemosyne-2pass-job");
+        for name in [
+            "mnemosyne-2pass-job-0.log",
+            "mnemosyne-2pass-job-0.log.mbtree",
+            "other.log",
+        ] {
+            std::fs::write(dir.path().join(name), b"x").unwrap();
         }
+        remove_pass_logs(&prefix);
 
-        on_progress(100.0);
-        Ok::<(), String>(())
-    })
-    .await
-    .map_err(|e| format!("任务执行失败: {}", e))?
+        let left: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(left, vec!["other.log"]);
+    }
 }
@@ -0,0 +1,363 @@
+use std::path::PathBuf;
+
+use super::get_ffmpeg_path;
+use super::info::probe_video_info;
+use super::job::{progress_command, run_job, JobProgress};
+use crate::models::{StreamKind, VideoInfo};
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ConcatParams {
+    /// 拼接在输入视频之后的片段（按顺序）
+    pub inputs: Vec<String>,
+    /// 强制重新编码（默认仅在片段格式不一致时重新编码）
+    #[serde(default)]
+    pub reencode: bool,
+}
+
+/// 片段的编码格式，完全一致时才能直接复制流
+#[derive(Debug, PartialEq)]
+struct ClipFormat {
+    video_codec: String,
+    width: u32,
+    height: u32,
+    fps: i64,
+    rotation: u32,
+    pixel_format: Option<String>,
+    audio: Option<(String, Option<u32>, Option<u32>)>,
+}
+
+/// 参与拼接的片段
+#[derive(Debug)]
+struct Clip {
+    path: String,
+    duration: f64,
+    has_audio: bool,
+}
+
+fn clip_format(info: &VideoInfo) -> ClipFormat {
+    let video = info.streams.iter().find(|s| s.kind == StreamKind::Video);
+    let audio = info.streams.iter().find(|s| s.kind == StreamKind::Audio);
+    ClipFormat {
+        video_codec: info.codec.clone(),
+        width: info.width,
+        height: info.height,
+        // 帧率按 0.01 精度比较，避免 29.97 的浮点误差
+        fps: (info.fps * 100.0).round() as i64,
+        rotation: info.rotation,
+        pixel_format: video.and_then(|s| s.pixel_format.clone()),
+        audio: audio.map(|s| (s.codec.clone(), s.sample_rate, s.channels)),
+    }
+}
+
+/// 所有片段格式一致时可使用 concat 分离器直接复制流
+///
+/// 没有 ffprobe 流信息时无法确认像素格式与音频参数，按不一致处理。
+fn can_stream_copy(infos: &[VideoInfo]) -> bool {
+    if infos.iter().any(|info| info.streams.is_empty()) {
+        return false;
+    }
+    let first = clip_format(&infos[0]);
+    infos[1..].iter().all(|info| clip_format(info) == first)
+}
+
+/// 生成 concat 分离器的文件列表
+fn concat_list(paths: &[&str]) -> String {
+    paths
+        .iter()
+        .map(|path| format!("file '{}'\n", path.replace('\'', "'\\''")))
+        .collect()
+}
+
+/// 构建重新编码拼接的参数（输入与滤镜部分）
+///
+/// 所有片段缩放并填充到第一个片段的分辨率和帧率；
+/// 部分片段没有音轨时用静音补齐，保证 concat 滤镜的音视频段数一致。
+fn reencode_args(clips: &[Clip], width: u32, height: u32, fps: f64) -> Vec<String> {
+    let mut args = Vec::new();
+    for clip in clips {
+        args.extend(["-i".to_string(), clip.path.clone()]);
+    }
+
+    let with_audio = clips.iter().any(|c| c.has_audio);
+    let mut silence_index = clips.len();
+    let mut filters = Vec::new();
+    let mut concat_inputs = String::new();
+
+    for (i, clip) in clips.iter().enumerate() {
+        filters.push(format!(
+            "[{i}:v:0]scale={w}:{h}:force_original_aspect_ratio=decrease,\
+             pad={w}:{h}:(ow-iw)/2:(oh-ih)/2,setsar=1,fps={fps},format=yuv420p[v{i}]",
+            i = i,
+            w = width,
+            h = height,
+            fps = fps
+        ));
+        concat_inputs.push_str(&format!("[v{}]", i));
+
+        if !with_audio {
+            continue;
+        }
+        let audio_input = if clip.has_audio {
+            format!("{}:a:0", i)
+        } else {
+            args.extend(
+                [
+                    "-f",
+                    "lavfi",
+                    "-t",
+                    &clip.duration.to_string(),
+                    "-i",
+                    "anullsrc=r=48000:cl=stereo",
+                ]
+                .map(String::from),
+            );
+            silence_index += 1;
+            format!("{}:a", silence_index - 1)
+        };
+        filters.push(format!(
+            "[{}]aresample=48000,aformat=channel_layouts=stereo[a{}]",
+            audio_input, i
+        ));
+        concat_inputs.push_str(&format!("[a{}]", i));
+    }
+
+    filters.push(format!(
+        "{}concat=n={}:v=1:a={}[outv]{}",
+        concat_inputs,
+        clips.len(),
+        if with_audio { 1 } else { 0 },
+        if with_audio { "[outa]" } else { "" }
+    ));
+
+    args.extend(["-filter_complex".to_string(), filters.join(";")]);
+    args.extend(["-map".to_string(), "[outv]".to_string()]);
+    if with_audio {
+        args.extend(["-map".to_string(), "[outa]".to_string()]);
+    }
+    args
+}
+
+/// 拼接视频
+///
+/// 片段编码格式一致时使用 concat 分离器直接复制流，否则重新编码。
+///
+/// # Arguments
+/// * input_path - 第一个视频文件路径
+/// * output_path - 输出视频文件路径
+/// * params - 拼接参数
+/// * job_id - 任务ID，可通过 `cancel_job` 取消
+/// * on_progress - 进度回调函数
+pub async fn concat_videos(
+    input_path: String,
+    output_path: String,
+    params: ConcatParams,
+    job_id: String,
+    on_progress: impl Fn(JobProgress) + Send + 'static,
+) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let ffmpeg_path = get_ffmpeg_path().ok_or_else(|| "FFmpeg未找到".to_string())?;
+
+        if params.inputs.is_empty() {
+            return Err("至少需要两个视频才能拼接".to_string());
+        }
+
+        let paths: Vec<&str> = std::iter::once(input_path.as_str())
+            .chain(params.inputs.iter().map(|s| s.as_str()))
+            .collect();
+        let infos = paths
+            .iter()
+            .map(|path| probe_video_info(&ffmpeg_path, path.to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let duration: f64 = infos.iter().map(|info| info.duration).sum();
+
+        let remove_output = || {
+            let _ = std::fs::remove_file(&output_path);
+        };
+
+        if !params.reencode && can_stream_copy(&infos) {
+            let list_path: PathBuf =
+                std::env::temp_dir().join(format!("mnemosyne-concat-{}.txt", job_id));
+            std::fs::write(&list_path, concat_list(&paths))
+                .map_err(|e| format!("写入拼接列表失败: {}", e))?;
+
+            let mut cmd = progress_command(&ffmpeg_path);
+            cmd.args(["-f", "concat", "-safe", "0", "-i"]);
+            cmd.arg(&list_path);
+            cmd.arg("-c").arg("copy");
+            cmd.arg("-y").arg(&output_path);
+
+            let result = run_job(
+                &job_id,
+                cmd,
+                duration,
+                "拼接失败",
+                &on_progress,
+                remove_output,
+            );
+            let _ = std::fs::remove_file(&list_path);
+            return result;
+        }
+
+        println!("片段格式不一致，重新编码拼接");
+        let first = &infos[0];
+        let fps = if first.fps > 0.0 { first.fps } else { 30.0 };
+        let clips: Vec<Clip> = paths
+            .iter()
+            .zip(&infos)
+            .map(|(path, info)| Clip {
+                path: path.to_string(),
+                duration: info.duration,
+                // 没有流信息时按有音轨处理
+                has_audio: info.streams.is_empty()
+                    || info.streams.iter().any(|s| s.kind == StreamKind::Audio),
+            })
+            .collect();
+
+        let mut cmd = progress_command(&ffmpeg_path);
+        // 宽高取偶数，满足 yuv420p 编码要求
+        cmd.args(reencode_args(
+            &clips,
+            first.width & !1,
+            first.height & !1,
+            fps,
+        ));
+        cmd.arg("-y").arg(&output_path);
+
+        run_job(
+            &job_id,
+            cmd,
+            duration,
+            "拼接失败",
+            &on_progress,
+            remove_output,
+        )
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::StreamInfo;
+
+    fn stream(kind: StreamKind, codec: &str) -> StreamInfo {
+        serde_json::from_value(serde_json::json!({
+            "index": 0,
+            "kind": kind,
+            "codec": codec,
+            "profile": null,
+            "bitrate": null,
+            "language": null,
+            "title": null,
+            "is_default": true,
+            "width": null,
+            "height": null,
+            "fps": null,
+            "pixel_format": if kind == StreamKind::Video { Some("yuv420p") } else { None },
+            "color_space": null,
+            "color_transfer": null,
+            "color_primaries": null,
+            "rotation": 0,
+            "hdr": false,
+            "attached_pic": false,
+            "sample_rate": if kind == StreamKind::Audio { Some(48000) } else { None },
+            "channels": if kind == StreamKind::Audio { Some(2) } else { None },
+            "channel_layout": null
+        }))
+        .unwrap()
+    }
+
+    fn info(codec: &str, width: u32, streams: Vec<StreamInfo>) -> VideoInfo {
+        VideoInfo {
+            path: String::new(),
+            filename: String::new(),
+            duration: 10.0,
+            width,
+            height: 1080,
+            fps: 29.97,
+            codec: codec.to_string(),
+            bitrate: 0,
+            file_size: 0,
+            format: "mp4".to_string(),
+            rotation: 0,
+            streams,
+        }
+    }
+
+    #[test]
+    fn stream_copy_requires_matching_formats() {
+        let streams = || {
+            vec![
+                stream(StreamKind::Video, "h264"),
+                stream(StreamKind::Audio, "aac"),
+            ]
+        };
+        let a = || info("h264", 1920, streams());
+
+        assert!(can_stream_copy(&[a(), info("h264", 1920, streams())]));
+        assert!(!can_stream_copy(&[a(), info("h264", 1280, streams())]));
+        assert!(!can_stream_copy(&[a(), info("hevc", 1920, streams())]));
+        // 缺少音轨
+        assert!(!can_stream_copy(&[
+            a(),
+            info("h264", 1920, vec![stream(StreamKind::Video, "h264")])
+        ]));
+        // 没有流信息
+        assert!(!can_stream_copy(&[a(), info("h264", 1920, Vec::new())]));
+    }
+
+    #[test]
+    fn concat_list_escapes_quotes() {
+        assert_eq!(
+            concat_list(&["/v/a.mp4", "/v/it's.mp4"]),
+            "file '/v/a.mp4'\nfile '/v/it'\\''s.mp4'\n"
+        );
+    }
+
+    #[test]
+    fn reencode_fills_missing_audio_with_silence() {
+        let clips = [
+            Clip {
+                path: "a.mp4".into(),
+                duration: 5.0,
+                has_audio: true,
+            },
+            Clip {
+                path: "b.mp4".into(),
+                duration: 3.5,
+                has_audio: false,
+            },
+        ];
+        let args = reencode_args(&clips, 1280, 720, 30.0).join(" ");
+
+        assert!(args.starts_with(
+            "-i a.mp4 -i b.mp4 -f lavfi -t 3.5 -i anullsrc=r=48000:cl=stereo -filter_complex "
+        ));
+        assert!(args.contains("[1:v:0]scale=1280:720:force_original_aspect_ratio=decrease,"));
+        assert!(args.contains("[0:a:0]aresample=48000"));
+        assert!(args.contains("[2:a]aresample=48000"));
+        assert!(args.contains("[v0][a0][v1][a1]concat=n=2:v=1:a=1[outv][outa]"));
+        assert!(args.ends_with("-map [outv] -map [outa]"));
+    }
+
+    #[test]
+    fn reencode_without_audio() {
+        let clips = [
+            Clip {
+                path: "a.mp4".into(),
+                duration: 5.0,
+                has_audio: false,
+            },
+            Clip {
+                path: "b.mp4".into(),
+                duration: 5.0,
+                has_audio: false,
+            },
+        ];
+        let args = reencode_args(&clips, 640, 360, 25.0).join(" ");
+        assert!(args.contains("[v0][v1]concat=n=2:v=1:a=0[outv]"));
+        assert!(!args.contains("anullsrc"));
+        assert!(args.ends_with("-map [outv]"));
+    }
+}
@@ -0,0 +1,200 @@
+use super::get_ffmpeg_path;
+use super::info::probe_video_info;
+use super::job::{progress_command, run_job, JobProgress};
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct CropParams {
+    /// 画面比例，如 9:16、1:1、4:5、16:9；设置后居中裁剪出最大区域
+    pub aspect: Option<String>,
+    // 自定义区域（未设置 aspect 时使用，单位像素）
+    pub x: Option<u32>,
+    pub y: Option<u32>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+/// 裁剪区域
+#[derive(Debug, PartialEq)]
+struct CropRect {
+    width: u32,
+    height: u32,
+    x: u32,
+    y: u32,
+}
+
+fn parse_aspect(aspect: &str) -> Option<(u32, u32)> {
+    let (w, h) = aspect.split_once(':')?;
+    let w: u32 = w.trim().parse().ok()?;
+    let h: u32 = h.trim().parse().ok()?;
+    (w > 0 && h > 0).then_some((w, h))
+}
+
+/// 根据参数和源视频尺寸计算裁剪区域
+///
+/// 宽高向下取偶数，满足 yuv420p 编码要求。
+fn crop_rect(params: &CropParams, src_width: u32, src_height: u32) -> Result<CropRect, String> {
+    if let Some(aspect) = &params.aspect {
+        let (aw, ah) = parse_aspect(aspect).ok_or_else(|| format!("无效的画面比例: {}", aspect))?;
+        // 源画面比目标更宽时保留全部高度，否则保留全部宽度
+        let (width, height) = if src_width as u64 * ah as u64 >= src_height as u64 * aw as u64 {
+            (
+                (src_height as u64 * aw as u64 / ah as u64) as u32,
+                src_height,
+            )
+        } else {
+            (src_width, (src_width as u64 * ah as u64 / aw as u64) as u32)
+        };
+        let (width, height) = (width & !1, height & !1);
+        if width == 0 || height == 0 {
+            return Err("裁剪区域过小".to_string());
+        }
+        return Ok(CropRect {
+            width,
+            height,
+            x: (src_width - width) / 2,
+            y: (src_height - height) / 2,
+        });
+    }
+
+    let (Some(width), Some(height)) = (params.width, params.height) else {
+        return Err("请设置画面比例或裁剪区域".to_string());
+    };
+    let x = params.x.unwrap_or(0);
+    let y = params.y.unwrap_or(0);
+    let (width, height) = (width & !1, height & !1);
+    if width == 0 || height == 0 {
+        return Err("裁剪区域过小".to_string());
+    }
+    if x + width > src_width || y + height > src_height {
+        return Err(format!(
+            "裁剪区域超出画面范围（{}x{}）",
+            src_width, src_height
+        ));
+    }
+    Ok(CropRect {
+        width,
+        height,
+        x,
+        y,
+    })
+}
+
+/// 裁剪视频画面
+///
+/// # Arguments
+/// * input_path - 输入视频文件路径
+/// * output_path - 输出视频文件路径
+/// * params - 裁剪参数
+/// * job_id - 任务ID，可通过 `cancel_job` 取消
+/// * on_progress - 进度回调函数
+pub async fn crop_video(
+    input_path: String,
+    output_path: String,
+    params: CropParams,
+    job_id: String,
+    on_progress: impl Fn(JobProgress) + Send + 'static,
+) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let ffmpeg_path = get_ffmpeg_path().ok_or_else(|| "FFmpeg未找到".to_string())?;
+
+        // 宽高为旋转后的显示尺寸，与 FFmpeg 自动旋转后滤镜看到的画面一致
+        let info = probe_video_info(&ffmpeg_path, input_path.clone())?;
+        let rect = crop_rect(&params, info.width, info.height)?;
+
+        let mut cmd = progress_command(&ffmpeg_path);
+        cmd.arg("-i").arg(&input_path);
+        cmd.arg("-vf").arg(format!(
+            "crop={}:{}:{}:{}",
+            rect.width, rect.height, rect.x, rect.y
+        ));
+        cmd.arg("-c:a").arg("copy");
+        cmd.arg("-y").arg(&output_path);
+
+        run_job(
+            &job_id,
+            cmd,
+            info.duration,
+            "裁剪失败",
+            &on_progress,
+            || {
+                let _ = std::fs::remove_file(&output_path);
+            },
+        )
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aspect(value: &str) -> CropParams {
+        CropParams {
+            aspect: Some(value.to_string()),
+            x: None,
+            y: None,
+            width: None,
+            height: None,
+        }
+    }
+
+    #[test]
+    fn aspect_crop_is_centered() {
+        assert_eq!(
+            crop_rect(&aspect("9:16"), 1920, 1080),
+            Ok(CropRect {
+                width: 606,
+                height: 1080,
+                x: 657,
+                y: 0
+            })
+        );
+        assert_eq!(
+            crop_rect(&aspect("1:1"), 1080, 1920),
+            Ok(CropRect {
+                width: 1080,
+                height: 1080,
+                x: 0,
+                y: 420
+            })
+        );
+        assert_eq!(
+            crop_rect(&aspect("16:9"), 1920, 1080),
+            Ok(CropRect {
+                width: 1920,
+                height: 1080,
+                x: 0,
+                y: 0
+            })
+        );
+        assert!(crop_rect(&aspect("wide"), 1920, 1080).is_err());
+    }
+
+    #[test]
+    fn custom_crop_is_bounds_checked() {
+        let params = CropParams {
+            aspect: None,
+            x: Some(100),
+            y: Some(50),
+            width: Some(641),
+            height: Some(480),
+        };
+        assert_eq!(
+            crop_rect(&params, 1280, 720),
+            Ok(CropRect {
+                width: 640,
+                height: 480,
+                x: 100,
+                y: 50
+            })
+        );
+        assert!(crop_rect(&params, 640, 480).is_err());
+
+        let missing = CropParams {
+            width: None,
+            ..params
+        };
+        assert!(crop_rect(&missing, 1280, 720).is_err());
+    }
+}
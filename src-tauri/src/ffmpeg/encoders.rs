@@ -0,0 +1,252 @@
+use std::path::Path;
+
+use super::get_ffmpeg_path;
+use super::managed::BinaryStore;
+
+/// 编码格式及候选编码器（按优先级排列）
+type CodecTable = &'static [(&'static str, &'static [&'static str])];
+
+/// 视频编码格式（均为软件编码器，不依赖特定硬件）
+const VIDEO_CODECS: CodecTable = &[
+    ("h264", &["libx264"]),
+    ("h265", &["libx265"]),
+    ("vp9", &["libvpx-vp9"]),
+    ("av1", &["libsvtav1", "libaom-av1"]),
+];
+
+/// 音频编码格式
+const AUDIO_CODECS: CodecTable = &[
+    ("aac", &["aac", "libfdk_aac"]),
+    ("opus", &["libopus"]),
+    ("mp3", &["libmp3lame"]),
+];
+
+/// `ffmpeg -encoders` 中的一个编码器
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct EncoderInfo {
+    /// 编码器名称，如 libx264
+    pub name: String,
+    /// 类型：video / audio / subtitle
+    pub kind: String,
+    pub description: String,
+}
+
+/// 可用的编码格式
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CodecOption {
+    /// 编码格式，如 h264、av1、aac
+    pub codec: String,
+    /// 实际使用的编码器
+    pub encoder: String,
+    /// 是否支持两遍编码
+    pub two_pass: bool,
+}
+
+/// 当前 FFmpeg 支持的编码能力
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct EncoderCapabilities {
+    pub video_codecs: Vec<CodecOption>,
+    pub audio_codecs: Vec<CodecOption>,
+    /// 全部编码器
+    pub encoders: Vec<EncoderInfo>,
+}
+
+/// 解析 `ffmpeg -encoders` 输出
+///
+/// 格式为 ` V....D libx264   libx264 H.264 / AVC ...`，编码器列表位于 `------` 行之后。
+pub fn parse_encoders(output: &str) -> Vec<EncoderInfo> {
+    output
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("---"))
+        .skip(1)
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let flags = parts.next()?;
+            let name = parts.next()?;
+            let kind = match flags.chars().next()? {
+                'V' => "video",
+                'A' => "audio",
+                'S' => "subtitle",
+                _ => return None,
+            };
+            Some(EncoderInfo {
+                name: name.to_string(),
+                kind: kind.to_string(),
+                description: parts.collect::<Vec<_>>().join(" "),
+            })
+        })
+        .collect()
+}
+
+/// 读取 FFmpeg 支持的编码器
+///
+/// 通过能力报告获取，按可执行文件的路径、大小和修改时间缓存到磁盘
+pub fn list_encoders(ffmpeg_path: &Path) -> Result<Vec<EncoderInfo>, String> {
+    BinaryStore::default_store()
+        .capability_report(ffmpeg_path, false)
+        .map(|report| report.encoders)
+}
+
+/// 编码器是否支持两遍编码（libsvtav1 仅使用单遍码率控制）
+pub fn supports_two_pass(encoder: &str) -> bool {
+    matches!(encoder, "libx264" | "libx265" | "libvpx-vp9" | "libaom-av1")
+}
+
+fn pick(table: CodecTable, codec: &str, encoders: &[EncoderInfo]) -> Option<&'static str> {
+    let (_, candidates) = table.iter().find(|(name, _)| *name == codec)?;
+    candidates
+        .iter()
+        .find(|c| encoders.iter().any(|e| e.name == **c))
+        .copied()
+}
+
+fn options(table: CodecTable, encoders: &[EncoderInfo]) -> Vec<CodecOption> {
+    table
+        .iter()
+        .filter_map(|(codec, _)| {
+            pick(table, codec, encoders).map(|encoder| CodecOption {
+                codec: codec.to_string(),
+                encoder: encoder.to_string(),
+                two_pass: supports_two_pass(encoder),
+            })
+        })
+        .collect()
+}
+
+/// 为视频编码格式选择已安装的编码器
+pub fn select_video_encoder(codec: &str, encoders: &[EncoderInfo]) -> Option<&'static str> {
+    pick(VIDEO_CODECS, codec, encoders)
+}
+
+/// 为音频编码格式选择已安装的编码器
+pub fn select_audio_encoder(codec: &str, encoders: &[EncoderInfo]) -> Option<&'static str> {
+    pick(AUDIO_CODECS, codec, encoders)
+}
+
+/// 确定视频编码格式使用的编码器
+///
+/// 无法读取编码器列表时（`encoders` 为 `None`）直接使用首选编码器，由 FFmpeg 报告错误。
+pub fn resolve_video_encoder(
+    codec: &str,
+    encoders: Option<&[EncoderInfo]>,
+) -> Result<&'static str, String> {
+    resolve(VIDEO_CODECS, codec, encoders).ok_or_else(|| unsupported("视频", codec))
+}
+
+/// 确定音频编码格式使用的编码器
+pub fn resolve_audio_encoder(
+    codec: &str,
+    encoders: Option<&[EncoderInfo]>,
+) -> Result<&'static str, String> {
+    resolve(AUDIO_CODECS, codec, encoders).ok_or_else(|| unsupported("音频", codec))
+}
+
+fn resolve(
+    table: CodecTable,
+    codec: &str,
+    encoders: Option<&[EncoderInfo]>,
+) -> Option<&'static str> {
+    match encoders {
+        Some(encoders) => pick(table, codec, encoders),
+        None => table
+            .iter()
+            .find(|(name, _)| *name == codec)
+            .and_then(|(_, candidates)| candidates.first().copied()),
+    }
+}
+
+fn unsupported(kind: &str, codec: &str) -> String {
+    format!("当前FFmpeg不支持{}编码格式: {}", kind, codec)
+}
+
+/// 根据编码器列表汇总可用的编码格式
+pub fn capabilities_from(encoders: Vec<EncoderInfo>) -> EncoderCapabilities {
+    EncoderCapabilities {
+        video_codecs: options(VIDEO_CODECS, &encoders),
+        audio_codecs: options(AUDIO_CODECS, &encoders),
+        encoders,
+    }
+}
+
+/// 获取当前 FFmpeg 的编码能力
+pub async fn get_encoder_capabilities() -> Result<EncoderCapabilities, String> {
+    let ffmpeg_path = get_ffmpeg_path().ok_or_else(|| "FFmpeg未找到".to_string())?;
+
+    tokio::task::spawn_blocking(move || list_encoders(&ffmpeg_path).map(capabilities_from))
+        .await
+        .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENCODERS: &str = "\
+Encoders:
+ V..... = Video
+ A..... = Audio
+ S..... = Subtitle
+ .F.... = Frame-level multithreading
+ ------
+ V....D libx264              libx264 H.264 / AVC / MPEG-4 AVC / MPEG-4 part 10 (codec h264)
+ V....D h264_videotoolbox    VideoToolbox H.264 Encoder (codec h264)
+ V....D libaom-av1           libaom AV1 (codec av1)
+ V..... libsvtav1            SVT-AV1(Scalable Video Technology for AV1) encoder (codec av1)
+ A....D aac                  AAC (Advanced Audio Coding)
+ A....D libopus              libopus Opus (codec opus)
+ S..... srt                  SubRip subtitle
+";
+
+    #[test]
+    fn parses_encoder_list() {
+        let encoders = parse_encoders(ENCODERS);
+        assert_eq!(encoders.len(), 7);
+        assert_eq!(encoders[0].name, "libx264");
+        assert_eq!(encoders[0].kind, "video");
+        assert!(encoders[0].description.starts_with("libx264 H.264"));
+        assert_eq!(encoders[4].kind, "audio");
+        assert_eq!(encoders[6].kind, "subtitle");
+    }
+
+    #[test]
+    fn offers_only_installed_codecs() {
+        let caps = capabilities_from(parse_encoders(ENCODERS));
+        let video: Vec<(&str, &str, bool)> = caps
+            .video_codecs
+            .iter()
+            .map(|c| (c.codec.as_str(), c.encoder.as_str(), c.two_pass))
+            .collect();
+        // 未安装 libx265 / libvpx-vp9；AV1 优先使用 SVT-AV1
+        assert_eq!(
+            video,
+            vec![("h264", "libx264", true), ("av1", "libsvtav1", false)]
+        );
+
+        let audio: Vec<&str> = caps.audio_codecs.iter().map(|c| c.codec.as_str()).collect();
+        assert_eq!(audio, vec!["aac", "opus"]);
+    }
+
+    #[test]
+    fn falls_back_to_next_candidate() {
+        let encoders: Vec<EncoderInfo> = parse_encoders(ENCODERS)
+            .into_iter()
+            .filter(|e| e.name != "libsvtav1")
+            .collect();
+        assert_eq!(select_video_encoder("av1", &encoders), Some("libaom-av1"));
+        assert_eq!(select_video_encoder("h265", &encoders), None);
+        assert_eq!(select_audio_encoder("mp3", &encoders), None);
+    }
+
+    #[test]
+    fn resolve_without_encoder_list_uses_first_candidate() {
+        assert_eq!(resolve_video_encoder("av1", None), Ok("libsvtav1"));
+        assert_eq!(resolve_audio_encoder("opus", None), Ok("libopus"));
+        assert!(resolve_video_encoder("mpeg2", None).is_err());
+
+        let encoders = parse_encoders(ENCODERS);
+        assert_eq!(
+            resolve_video_encoder("h265", Some(&encoders)),
+            Err("当前FFmpeg不支持视频编码格式: h265".to_string())
+        );
+    }
+}
@@ -1,15 +1,26 @@
-use std::process::{Command, Stdio};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use super::get_ffmpeg_path;
+use super::info::probe_duration;
+use super::job::{progress_command, run_job, JobProgress};
+use super::storyboard::{frame_signature, render_contact_sheet, select_frames, ContactSheetParams};
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct ExtractParams {
-    pub mode: String,  // single, interval, uniform
+    pub mode: String,  // single, interval, uniform, scene, keyframe
     pub format: String,  // jpg, png, webp
     pub quality: u32,  // 1-100
     pub interval: Option<f64>,  // 秒
     pub count: Option<u32>,  // 帧数
     pub output_dir: String,
+    pub scene_threshold: Option<f64>, // 场景变化阈值 0-1，默认 0.3，越小提取越多
+    /// 跳过接近全黑的帧
+    #[serde(default)]
+    pub skip_black: bool,
+    /// 跳过与上一张保留帧几乎相同的帧
+    #[serde(default)]
+    pub skip_duplicates: bool,
+    /// 额外生成联系表 `contact_sheet_<批次>.jpg`（PNG 格式时为 `.png`）
+    pub contact_sheet: Option<ContactSheetParams>,
 }
 
 /// 提取视频帧
@@ -17,105 +28,331 @@ pub struct ExtractParams {
 /// # Arguments
 /// * input_path - 输入视频文件路径
 /// * params - 提取参数
+/// * job_id - 任务ID，可通过 `cancel_job` 取消
+/// * on_progress - 进度回调函数
 ///
 /// # Returns
-/// 返回生成的图片文件路径列表
+/// 返回生成的图片文件路径列表，生成联系表时其路径在最后
 pub async fn extract_frames(
     input_path: String,
     params: ExtractParams,
+    job_id: String,
+    on_progress: impl Fn(JobProgress) + Send + 'static,
 ) -> Result<Vec<String>, String> {
     tokio::task::spawn_blocking(move || {
         let ffmpeg_path = get_ffmpeg_path()
             .ok_or_else(|| "FFmpeg未找到".to_string())?;
 
+        let input_path =
+            std::path::absolute(&input_path).map_err(|e| format!("无效的输入路径: {}", e))?;
+        let output_dir = std::path::absolute(&params.output_dir)
+            .map_err(|e| format!("无效的输出目录: {}", e))?;
+
         // 确保输出目录存在
-        std::fs::create_dir_all(&params.output_dir)
+        std::fs::create_dir_all(&output_dir)
             .map_err(|e| format!("创建输出目录失败: {}", e))?;
 
-        let output_pattern = Path::new(&params.output_dir)
-            .join(format!("frame_%04d.{}", params.format))
+        // 每次提取使用独立的文件名前缀，重复提取到同一目录时不会覆盖上次的帧
+        let run_tag = uuid::Uuid::new_v4().simple().to_string()[..8].to_string();
+        let frame_prefix = format!("frame_{}_", run_tag);
+
+        if params.mode == "single" {
+            // 提取单帧(默认第5秒)，使用特殊输出路径
+            let single_output = output_dir
+                .join(format!("frame.{}", params.format))
+                .to_string_lossy()
+                .to_string();
+            let mut cmd = progress_command(&ffmpeg_path);
+            cmd.arg("-i").arg(&input_path);
+            cmd.arg("-ss").arg("00:00:05");
+            cmd.arg("-vframes").arg("1");
+            cmd.arg("-y").arg(&single_output);
+
+            run_job(&job_id, cmd, 0.0, "提取帧失败", &on_progress, || {
+                let _ = std::fs::remove_file(&single_output);
+            })?;
+
+            return Ok(vec![single_output]);
+        }
+
+        // 联系表需要每帧的时间戳：文件名中的序号改为帧的 pts（毫秒）
+        let with_times = params
+            .contact_sheet
+            .as_ref()
+            .is_some_and(|sheet| sheet.timestamps);
+        let number = if with_times { "%012d" } else { "%04d" };
+        let output_pattern = output_dir
+            .join(format!("{}{}.{}", frame_prefix, number, params.format))
             .to_string_lossy()
             .to_string();
 
-        let mut cmd = Command::new(&ffmpeg_path);
-        cmd.arg("-i").arg(&input_path);
-
-        match params.mode.as_str() {
-            "single" => {
-                // 提取单帧(默认第5秒)
-                cmd.arg("-ss").arg("00:00:05");
-                cmd.arg("-vframes").arg("1");
-
-                // 单帧模式使用特殊输出路径
-                let single_output = Path::new(&params.output_dir)
-                    .join(format!("frame.{}", params.format))
-                    .to_string_lossy()
-                    .to_string();
-                cmd.arg("-y").arg(&single_output);
-
-                let output = cmd
-                    .stdout(Stdio::piped())
-                    .stderr(Stdio::piped())
-                    .output()
-                    .map_err(|e| format!("执行FFmpeg失败: {}", e))?;
-
-                if !output.status.success() {
-                    return Err("提取帧失败".to_string());
-                }
-
-                return Ok(vec![single_output]);
-            }
-            "interval" => {
-                // 间隔提取
-                let interval = params.interval.unwrap_or(1.0);
-                cmd.arg("-vf").arg(format!("fps=1/{}", interval));
-            }
-            "uniform" => {
-                // 均匀提取N帧
-                let count = params.count.unwrap_or(10);
-                cmd.arg("-vf").arg(format!("select='eq(n,0)+gt(mod(n,{}),{})'",
-                    count, count - 1));
-            }
-            _ => return Err("无效的提取模式".to_string()),
-        }
+        let mut cmd = progress_command(&ffmpeg_path);
+        cmd.args(build_args(
+            &input_path.to_string_lossy(),
+            &output_pattern,
+            &params,
+            with_times,
+        )?);
 
-        // 质量设置
-        match params.format.as_str() {
-            "jpg" | "jpeg" => {
-                cmd.arg("-q:v").arg((100 - params.quality).to_string());
-            }
-            "png" => {
-                // PNG使用compression级别
-                cmd.arg("-compression_level").arg("9");
-            }
-            "webp" => {
-                cmd.arg("-quality").arg(params.quality.to_string());
+        // 失败或取消时只删除本次生成的帧，保留目录中原有文件
+        let duration = probe_duration(&ffmpeg_path, &input_path.to_string_lossy());
+        let cleanup = || {
+            for path in list_frame_files(&output_dir, &frame_prefix, &params.format) {
+                let _ = std::fs::remove_file(path);
             }
-            _ => {}
-        }
+        };
+        run_job(&job_id, cmd, duration, "提取帧失败", &on_progress, cleanup)?;
 
-        cmd.arg("-y").arg(&output_pattern);
+        // 本次生成的帧按文件名（即时间顺序）排列
+        let new_frames = list_frame_files(&output_dir, &frame_prefix, &params.format);
 
-        let output = cmd
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .map_err(|e| format!("执行FFmpeg失败: {}", e))?;
+        let mut frames: Vec<(PathBuf, Option<f64>)> = new_frames
+            .into_iter()
+            .map(|path| {
+                let time = with_times
+                    .then(|| frame_time(&path, &frame_prefix, &params.format))
+                    .flatten();
+                (path, time)
+            })
+            .collect();
 
-        if !output.status.success() {
-            return Err("提取帧失败".to_string());
+        if params.skip_black || params.skip_duplicates {
+            let signatures = frames
+                .iter()
+                .map(|(path, _)| {
+                    image::open(path)
+                        .map(|img| frame_signature(&img))
+                        .map_err(|e| format!("无法打开图片: {}", e))
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            let kept = select_frames(&signatures, params.skip_black, params.skip_duplicates);
+            let total = frames.len();
+            frames = frames
+                .into_iter()
+                .enumerate()
+                .filter_map(|(i, frame)| {
+                    if kept.contains(&i) {
+                        Some(frame)
+                    } else {
+                        let _ = std::fs::remove_file(&frame.0);
+                        None
+                    }
+                })
+                .collect();
+            println!("已跳过 {} 张黑帧或重复帧", total - frames.len());
         }
 
-        // 查找生成的文件
-        let files = std::fs::read_dir(&params.output_dir)
-            .map_err(|e| format!("读取输出目录失败: {}", e))?
-            .filter_map(|entry| entry.ok())
-            .map(|entry| entry.path().to_string_lossy().to_string())
-            .filter(|path| path.ends_with(&params.format))
+        let mut files: Vec<String> = frames
+            .iter()
+            .map(|(path, _)| path.to_string_lossy().to_string())
             .collect();
 
+        if let Some(sheet) = &params.contact_sheet {
+            let extension = if params.format == "png" { "png" } else { "jpg" };
+            let sheet_path = output_dir.join(format!("contact_sheet_{}.{}", run_tag, extension));
+            render_contact_sheet(&frames, sheet, &sheet_path)?;
+            files.push(sheet_path.to_string_lossy().to_string());
+        }
+
         Ok::<Vec<String>, String>(files)
     })
     .await
     .map_err(|e| format!("任务执行失败: {}", e))?
 }
+
+/// 构建多帧提取的 FFmpeg 参数（不含 `progress_command` 的通用参数）
+///
+/// `with_times` 为 true 时按帧的 pts（毫秒）命名输出文件，
+/// 时间戳直接取自被选中的帧，与提取模式无关。
+fn build_args(
+    input_path: &str,
+    output_pattern: &str,
+    params: &ExtractParams,
+    with_times: bool,
+) -> Result<Vec<String>, String> {
+    let mut args: Vec<String> = Vec::new();
+    if params.mode == "keyframe" {
+        // 只解码关键帧，比逐帧判断快得多
+        args.extend(["-skip_frame".into(), "nokey".into()]);
+    }
+    if with_times {
+        // 保留原始时间戳，文件名中的 pts 即为帧在视频中的位置
+        args.push("-copyts".into());
+    }
+    args.extend(["-i".into(), input_path.into()]);
+
+    let filter = match params.mode.as_str() {
+        "interval" => {
+            // 间隔提取
+            let interval = params.interval.unwrap_or(1.0);
+            Some(format!("fps=1/{}", interval))
+        }
+        "uniform" => {
+            // 均匀提取N帧
+            let count = params.count.unwrap_or(10);
+            Some(format!(
+                "select='eq(n,0)+gt(mod(n,{}),{})'",
+                count,
+                count - 1
+            ))
+        }
+        "scene" => {
+            // 场景变化提取，保留第一帧作为开场画面
+            let threshold = params.scene_threshold.unwrap_or(0.3);
+            if !(threshold > 0.0 && threshold < 1.0) {
+                return Err("场景变化阈值必须在 0 到 1 之间".to_string());
+            }
+            Some(format!("select='eq(n,0)+gt(scene,{})'", threshold))
+        }
+        "keyframe" => None,
+        _ => return Err("无效的提取模式".to_string()),
+    };
+    if let Some(filter) = filter {
+        args.extend(["-vf".into(), filter]);
+    }
+    if with_times || matches!(params.mode.as_str(), "scene" | "keyframe") {
+        // 按选中帧输出，不补帧
+        args.extend(["-vsync".into(), "vfr".into()]);
+    }
+    if with_times {
+        args.extend(["-enc_time_base", "1:1000", "-frame_pts", "1"].map(String::from));
+    }
+
+    // 质量设置
+    match params.format.as_str() {
+        "jpg" | "jpeg" => {
+            args.extend(["-q:v".into(), (100 - params.quality).to_string()]);
+        }
+        "png" => {
+            // PNG使用compression级别
+            args.extend(["-compression_level".into(), "9".into()]);
+        }
+        "webp" => {
+            args.extend(["-quality".into(), params.quality.to_string()]);
+        }
+        _ => {}
+    }
+
+    args.extend(["-y".into(), output_pattern.into()]);
+    Ok(args)
+}
+
+/// 从按 pts 命名的帧文件（`<prefix><毫秒>.<format>`）中解析时间戳（秒）
+fn frame_time(path: &Path, prefix: &str, format: &str) -> Option<f64> {
+    let name = path.file_name()?.to_str()?;
+    let millis: u64 = name
+        .strip_prefix(prefix)?
+        .strip_suffix(&format!(".{}", format))?
+        .parse()
+        .ok()?;
+    Some(millis as f64 / 1000.0)
+}
+
+/// 列出输出目录中本次提取生成的帧文件（`<prefix>XXXX.<format>`），按文件名排序
+fn list_frame_files(output_dir: &Path, prefix: &str, format: &str) -> Vec<PathBuf> {
+    let suffix = format!(".{}", format);
+    let mut frames: Vec<PathBuf> = std::fs::read_dir(output_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.starts_with(prefix) && n.ends_with(&suffix))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    frames.sort();
+    frames
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(mode: &str) -> ExtractParams {
+        ExtractParams {
+            mode: mode.to_string(),
+            format: "jpg".to_string(),
+            quality: 90,
+            interval: Some(2.0),
+            count: None,
+            output_dir: String::new(),
+            scene_threshold: None,
+            skip_black: false,
+            skip_duplicates: false,
+            contact_sheet: None,
+        }
+    }
+
+    #[test]
+    fn interval_mode_names_frames_by_pts_when_timestamps_needed() {
+        let args = build_args("in.mp4", "out/frame_%012d.jpg", &params("interval"), true)
+            .unwrap()
+            .join(" ");
+        assert_eq!(
+            args,
+            "-copyts -i in.mp4 -vf fps=1/2 -vsync vfr -enc_time_base 1:1000 -frame_pts 1 \
+             -q:v 10 -y out/frame_%012d.jpg"
+        );
+
+        let args = build_args("in.mp4", "out/frame_%04d.jpg", &params("interval"), false)
+            .unwrap()
+            .join(" ");
+        assert_eq!(args, "-i in.mp4 -vf fps=1/2 -q:v 10 -y out/frame_%04d.jpg");
+
+        assert!(build_args("in.mp4", "out.jpg", &params("bogus"), false).is_err());
+    }
+
+    #[test]
+    fn frame_times_come_from_each_file_name() {
+        let dir = tempfile::tempdir().unwrap();
+        // 中间的帧缺失（如被跳过）不影响其余帧的时间戳
+        for name in [
+            "frame_aaaa1111_000000010000.jpg",
+            "frame_aaaa1111_000000000000.jpg",
+            "frame_aaaa1111_000000002500.jpg",
+        ] {
+            std::fs::write(dir.path().join(name), b"x").unwrap();
+        }
+
+        let times: Vec<Option<f64>> = list_frame_files(dir.path(), "frame_aaaa1111_", "jpg")
+            .iter()
+            .map(|path| frame_time(path, "frame_aaaa1111_", "jpg"))
+            .collect();
+        assert_eq!(times, vec![Some(0.0), Some(2.5), Some(10.0)]);
+        assert_eq!(
+            frame_time(
+                Path::new("frame_aaaa1111_0001.png"),
+                "frame_aaaa1111_",
+                "jpg"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn list_frame_files_only_matches_current_run() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in [
+            "frame_aaaa1111_0002.jpg",
+            "frame_aaaa1111_0001.jpg",
+            "frame_bbbb2222_0001.jpg",
+            "frame_0001.jpg",
+            "frame_aaaa1111_0003.png",
+        ] {
+            std::fs::write(dir.path().join(name), b"x").unwrap();
+        }
+
+        let frames = list_frame_files(dir.path(), "frame_aaaa1111_", "jpg");
+        assert_eq!(
+            frames,
+            vec![
+                dir.path().join("frame_aaaa1111_0001.jpg"),
+                dir.path().join("frame_aaaa1111_0002.jpg"),
+            ]
+        );
+    }
+}
@@ -1,5 +1,26 @@
-use std::process::{Command, Stdio};
 use super::get_ffmpeg_path;
+use super::job::{progress_command, run_job, JobProgress};
+
+/// 目标大小模式下最多尝试生成的次数
+const MAX_SIZE_ATTEMPTS: usize = 6;
+
+/// 目标大小搜索时各参数的下限
+const MIN_FPS: u32 = 5;
+const MIN_WIDTH: u32 = 120;
+const MIN_COLORS: u32 = 16;
+const MIN_QUALITY: u32 = 20;
+
+fn default_format() -> String {
+    "gif".to_string()
+}
+
+fn default_stats_mode() -> String {
+    "full".to_string()
+}
+
+fn default_quality() -> u32 {
+    75
+}
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct GifParams {
@@ -9,55 +30,418 @@ pub struct GifParams {
     pub width: u32,
     pub colors: u32,  // 2-256
     pub dither: bool,
+    #[serde(default = "default_format")]
+    pub format: String, // gif, webp, apng
+    /// 调色板统计方式（仅 GIF/APNG）
+    #[serde(default = "default_stats_mode")]
+    pub stats_mode: String, // full（全局调色板）, diff（侧重变化区域）, single（逐帧调色板）
+    /// 抖动算法，未设置时按 `dither` 选择 sierra 或 none
+    pub dither_mode: Option<String>, // none, bayer, sierra, floyd
+    pub bayer_scale: Option<u32>, // 0-5，越大网纹越明显、文件越小
+    /// 循环次数：0 为无限循环，n 为共播放 n 次
+    #[serde(default)]
+    pub loop_count: u32,
+    #[serde(default = "default_quality")]
+    pub quality: u32, // WebP 质量 0-100
+    /// 目标文件大小（MB，按 1 MB = 1,000,000 字节计算）
+    ///
+    /// 超出时依次降低帧率、宽度和颜色数（WebP 为质量）重新生成
+    pub target_size_mb: Option<f64>,
+}
+
+/// 目标大小搜索中会被调整的参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Settings {
+    fps: u32,
+    width: u32,
+    colors: u32,
+    quality: u32,
+}
+
+impl Settings {
+    fn from_params(params: &GifParams) -> Self {
+        Self {
+            fps: params.fps,
+            width: params.width,
+            colors: params.colors.clamp(2, 256),
+            quality: params.quality.min(100),
+        }
+    }
+}
+
+/// 动图格式对应的输出扩展名
+pub fn animation_extension(format: &str) -> Option<&'static str> {
+    match format {
+        "gif" => Some("gif"),
+        "webp" => Some("webp"),
+        // APNG 使用 .png 扩展名，浏览器与大多数看图软件都能直接播放
+        "apng" => Some("png"),
+        _ => None,
+    }
+}
+
+fn format_label(format: &str) -> &'static str {
+    match format {
+        "webp" => "WebP",
+        "apng" => "APNG",
+        _ => "GIF",
+    }
+}
+
+/// 生成 `paletteuse` 的抖动选项
+fn dither_option(params: &GifParams) -> Result<String, String> {
+    let fallback = if params.dither { "sierra" } else { "none" };
+    match params.dither_mode.as_deref().unwrap_or(fallback) {
+        "none" => Ok("none".to_string()),
+        "sierra" => Ok("sierra2_4a".to_string()),
+        "floyd" => Ok("floyd_steinberg".to_string()),
+        "bayer" => Ok(format!(
+            "bayer:bayer_scale={}",
+            params.bayer_scale.unwrap_or(2).min(5)
+        )),
+        other => Err(format!("未知的抖动算法: {}", other)),
+    }
+}
+
+/// 构建滤镜：GIF/APNG 先生成调色板再映射，WebP 只需缩放
+fn build_filter(params: &GifParams, settings: Settings) -> Result<String, String> {
+    if settings.fps == 0 || settings.width == 0 {
+        return Err("帧率和宽度必须大于 0".to_string());
+    }
+    let base = format!(
+        "fps={},scale={}:-1:flags=lanczos",
+        settings.fps, settings.width
+    );
+    if params.format == "webp" {
+        return Ok(base);
+    }
+
+    let palette_extra = match params.stats_mode.as_str() {
+        "full" => "",
+        // 只统计变化区域时，只对变化的矩形区域重新映射
+        "diff" => ":diff_mode=rectangle",
+        // 每帧单独生成调色板
+        "single" => ":new=1",
+        other => return Err(format!("未知的调色板模式: {}", other)),
+    };
+    Ok(format!(
+        "{},split[s0][s1];[s0]palettegen=max_colors={}:reserve_transparent=1:stats_mode={}[p];[s1][p]paletteuse=dither={}{}",
+        base,
+        settings.colors,
+        params.stats_mode,
+        dither_option(params)?,
+        palette_extra
+    ))
+}
+
+/// 构建完整的 FFmpeg 参数
+fn build_args(
+    input_path: &str,
+    output_path: &str,
+    params: &GifParams,
+    settings: Settings,
+) -> Result<Vec<String>, String> {
+    if animation_extension(&params.format).is_none() {
+        return Err(format!("不支持的动图格式: {}", params.format));
+    }
+    let duration = (params.end_time - params.start_time).max(0.0);
+    let mut args: Vec<String> = vec![
+        "-ss".into(),
+        params.start_time.to_string(),
+        "-t".into(),
+        duration.to_string(),
+        "-i".into(),
+        input_path.into(),
+        "-filter_complex".into(),
+        build_filter(params, settings)?,
+    ];
+
+    match params.format.as_str() {
+        "webp" => {
+            args.extend([
+                "-c:v".into(),
+                "libwebp".into(),
+                "-lossless".into(),
+                "0".into(),
+                "-quality".into(),
+                settings.quality.to_string(),
+                "-loop".into(),
+                params.loop_count.to_string(),
+            ]);
+        }
+        "apng" => {
+            args.extend([
+                "-f".into(),
+                "apng".into(),
+                "-plays".into(),
+                params.loop_count.to_string(),
+            ]);
+        }
+        _ => {
+            // GIF 的 loop 表示额外重复次数，-1 为不循环
+            let repeats = match params.loop_count {
+                0 => 0,
+                1 => -1,
+                n => n as i64 - 1,
+            };
+            args.extend(["-loop".into(), repeats.to_string()]);
+        }
+    }
+
+    args.extend(["-y".into(), output_path.into()]);
+    Ok(args)
+}
+
+/// 超出目标大小后的下一组参数：按帧率、宽度、颜色数（WebP 为质量）轮流降低
+///
+/// `ratio` 为目标大小与实际大小之比；所有参数都已到下限时返回 `None`
+fn shrink_settings(settings: Settings, format: &str, ratio: f64, round: usize) -> Option<Settings> {
+    // 大小约与帧率、宽度的平方成正比，降幅限制在 15%-50% 之间
+    let factor = ratio.sqrt().clamp(0.5, 0.85);
+    for offset in 0..3 {
+        let mut next = settings;
+        match (round + offset) % 3 {
+            0 => next.fps = ((settings.fps as f64 * factor) as u32).max(MIN_FPS),
+            1 => {
+                let width = (settings.width as f64 * factor) as u32 / 2 * 2;
+                next.width = width.max(MIN_WIDTH);
+            }
+            _ if format == "webp" => {
+                next.quality = settings.quality.saturating_sub(15).max(MIN_QUALITY)
+            }
+            _ => next.colors = (settings.colors / 2).max(MIN_COLORS),
+        }
+        // 已在下限的参数不再降低，换下一个
+        if next != settings
+            && next.fps <= settings.fps
+            && next.width <= settings.width
+            && next.colors <= settings.colors
+            && next.quality <= settings.quality
+        {
+            return Some(next);
+        }
+    }
+    None
 }
 
-/// 转换视频为GIF
+/// 转换视频为动图（GIF / WebP / APNG）
 ///
 /// # Arguments
 /// * input_path - 输入视频文件路径
-/// * output_path - 输出GIF文件路径
-/// * params - GIF参数
+/// * output_path - 输出文件路径
+/// * params - 动图参数
+/// * job_id - 任务ID，可通过 `cancel_job` 取消
+/// * on_progress - 进度回调函数
 pub async fn convert_to_gif(
     input_path: String,
     output_path: String,
     params: GifParams,
+    job_id: String,
+    on_progress: impl Fn(JobProgress) + Send + 'static,
 ) -> Result<(), String> {
     tokio::task::spawn_blocking(move || {
         let ffmpeg_path = get_ffmpeg_path()
             .ok_or_else(|| "FFmpeg未找到".to_string())?;
 
-        let mut cmd = Command::new(&ffmpeg_path);
+        let duration = (params.end_time - params.start_time).max(0.0);
+        let error_prefix = format!("转{}失败", format_label(&params.format));
+        let remove_output = || {
+            let _ = std::fs::remove_file(&output_path);
+        };
+        let run = |settings: Settings| {
+            let mut cmd = progress_command(&ffmpeg_path);
+            cmd.args(build_args(&input_path, &output_path, &params, settings)?);
+            run_job(
+                &job_id,
+                cmd,
+                duration,
+                &error_prefix,
+                &on_progress,
+                remove_output,
+            )
+        };
 
-        cmd.arg("-ss").arg(&params.start_time.to_string());
-        cmd.arg("-t").arg(&(params.end_time - params.start_time).to_string());
-        cmd.arg("-i").arg(&input_path);
-
-        // 构建filter_complex
-        let dither_mode = if params.dither { "sierra2_4a" } else { "none" };
-        let filters = format!(
-            "fps={},scale={}:-1:flags=lanczos,split[s0][s1];[s0]palettegen=max_colors={}:reserve_transparent=1[p];[s1][p]paletteuse=dither={}",
-            params.fps,
-            params.width,
-            params.colors,
-            dither_mode
-        );
-
-        cmd.arg("-filter_complex").arg(&filters);
-        cmd.arg("-y").arg(&output_path);
+        let mut settings = Settings::from_params(&params);
+        let Some(target_size_mb) = params.target_size_mb else {
+            return run(settings);
+        };
+        if target_size_mb <= 0.0 {
+            return Err("目标大小必须大于 0".to_string());
+        }
+        let target_bytes = target_size_mb * 1_000_000.0;
 
-        let output = cmd
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .map_err(|e| format!("执行FFmpeg失败: {}", e))?;
+        for round in 0..MAX_SIZE_ATTEMPTS {
+            run(settings)?;
+            let size = std::fs::metadata(&output_path)
+                .map_err(|e| format!("读取输出文件失败: {}", e))?
+                .len() as f64;
+            if size <= target_bytes {
+                return Ok(());
+            }
 
-        if !output.status.success() {
-            let error = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("转GIF失败: {}", error));
+            println!(
+                "动图大小 {:.2} MB 超出目标 {:.2} MB（帧率 {}，宽度 {}，颜色 {}，质量 {}），降低参数重试",
+                size / 1_000_000.0,
+                target_size_mb,
+                settings.fps,
+                settings.width,
+                settings.colors,
+                settings.quality
+            );
+            match shrink_settings(settings, &params.format, target_bytes / size, round) {
+                Some(next) => settings = next,
+                None => break,
+            }
         }
 
-        Ok::<(), String>(())
+        remove_output();
+        Err(format!(
+            "无法将动图压缩到 {:.2} MB 以内，请缩短时长或降低宽度",
+            target_size_mb
+        ))
     })
     .await
     .map_err(|e| format!("任务执行失败: {}", e))?
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(json: serde_json::Value) -> GifParams {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn legacy() -> serde_json::Value {
+        serde_json::json!({
+            "start_time": 1.0,
+            "end_time": 4.0,
+            "fps": 10,
+            "width": 480,
+            "colors": 128,
+            "dither": true
+        })
+    }
+
+    fn with(extra: serde_json::Value) -> GifParams {
+        let mut json = legacy();
+        json.as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        params(json)
+    }
+
+    fn args(p: &GifParams) -> String {
+        build_args("in.mp4", "out", p, Settings::from_params(p))
+            .unwrap()
+            .join(" ")
+    }
+
+    #[test]
+    fn legacy_params_produce_global_palette_gif() {
+        let p = params(legacy());
+        assert_eq!(p.format, "gif");
+        assert_eq!(
+            args(&p),
+            "-ss 1 -t 3 -i in.mp4 -filter_complex \
+             fps=10,scale=480:-1:flags=lanczos,split[s0][s1];\
+             [s0]palettegen=max_colors=128:reserve_transparent=1:stats_mode=full[p];\
+             [s1][p]paletteuse=dither=sierra2_4a -loop 0 -y out"
+        );
+
+        let p = with(serde_json::json!({"dither": false}));
+        assert!(args(&p).contains("paletteuse=dither=none -loop"));
+    }
+
+    #[test]
+    fn palette_mode_and_dither_options() {
+        let p = with(serde_json::json!({
+            "stats_mode": "single",
+            "dither_mode": "bayer",
+            "bayer_scale": 9
+        }));
+        assert!(args(&p)
+            .contains("stats_mode=single[p];[s1][p]paletteuse=dither=bayer:bayer_scale=5:new=1 "));
+
+        let p = with(serde_json::json!({"stats_mode": "diff", "dither_mode": "floyd"}));
+        assert!(args(&p).contains("paletteuse=dither=floyd_steinberg:diff_mode=rectangle "));
+
+        let p = with(serde_json::json!({"dither_mode": "atkinson"}));
+        assert!(build_args("in.mp4", "out", &p, Settings::from_params(&p)).is_err());
+        let p = with(serde_json::json!({"stats_mode": "local"}));
+        assert!(build_args("in.mp4", "out", &p, Settings::from_params(&p)).is_err());
+    }
+
+    #[test]
+    fn loop_count_per_format() {
+        let gif = |n: u32| args(&with(serde_json::json!({"loop_count": n})));
+        assert!(gif(0).ends_with("-loop 0 -y out"));
+        assert!(gif(1).ends_with("-loop -1 -y out"));
+        assert!(gif(3).ends_with("-loop 2 -y out"));
+
+        let webp = args(&with(
+            serde_json::json!({"format": "webp", "loop_count": 2, "quality": 60}),
+        ));
+        assert_eq!(
+            webp,
+            "-ss 1 -t 3 -i in.mp4 -filter_complex fps=10,scale=480:-1:flags=lanczos \
+             -c:v libwebp -lossless 0 -quality 60 -loop 2 -y out"
+        );
+
+        let apng = args(&with(
+            serde_json::json!({"format": "apng", "loop_count": 1}),
+        ));
+        assert!(apng.contains("paletteuse=dither=sierra2_4a -f apng -plays 1 -y out"));
+
+        let p = with(serde_json::json!({"format": "mp4"}));
+        assert!(build_args("in.mp4", "out", &p, Settings::from_params(&p)).is_err());
+    }
+
+    #[test]
+    fn extensions() {
+        assert_eq!(animation_extension("gif"), Some("gif"));
+        assert_eq!(animation_extension("webp"), Some("webp"));
+        assert_eq!(animation_extension("apng"), Some("png"));
+        assert_eq!(animation_extension("avi"), None);
+    }
+
+    #[test]
+    fn shrink_rotates_through_fps_width_and_colors() {
+        let start = Settings {
+            fps: 20,
+            width: 640,
+            colors: 256,
+            quality: 75,
+        };
+        let a = shrink_settings(start, "gif", 0.25, 0).unwrap();
+        assert_eq!(a, Settings { fps: 10, ..start });
+        let b = shrink_settings(a, "gif", 0.64, 1).unwrap();
+        assert_eq!(b, Settings { width: 512, ..a });
+        let c = shrink_settings(b, "gif", 0.9, 2).unwrap();
+        assert_eq!(c, Settings { colors: 128, ..b });
+
+        // WebP 没有调色板，降低质量
+        let w = shrink_settings(b, "webp", 0.9, 2).unwrap();
+        assert_eq!(w, Settings { quality: 60, ..b });
+    }
+
+    #[test]
+    fn shrink_skips_parameters_at_their_floor() {
+        let floor = Settings {
+            fps: MIN_FPS,
+            width: 400,
+            colors: MIN_COLORS,
+            quality: 75,
+        };
+        let next = shrink_settings(floor, "gif", 0.5, 0).unwrap();
+        assert_eq!(next.fps, MIN_FPS);
+        assert!(next.width < 400);
+
+        let exhausted = Settings {
+            width: MIN_WIDTH,
+            ..floor
+        };
+        assert_eq!(shrink_settings(exhausted, "gif", 0.5, 0), None);
+        assert!(shrink_settings(exhausted, "webp", 0.5, 0).is_some());
+    }
+}
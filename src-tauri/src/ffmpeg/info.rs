@@ -1,6 +1,221 @@
+use super::{get_ffmpeg_path, get_ffprobe_path};
+use crate::models::{StreamInfo, StreamKind, VideoInfo};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
 use std::process::Command;
-use super::get_ffmpeg_path;
-use crate::models::VideoInfo;
+
+/// ffprobe -print_format json 的输出（只声明用到的字段）
+#[derive(Debug, Default, Deserialize)]
+struct ProbeOutput {
+    #[serde(default)]
+    streams: Vec<ProbeStream>,
+    #[serde(default)]
+    format: ProbeFormat,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ProbeFormat {
+    duration: Option<String>,
+    bit_rate: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ProbeStream {
+    #[serde(default)]
+    index: u32,
+    codec_type: Option<String>,
+    codec_name: Option<String>,
+    profile: Option<String>,
+    pix_fmt: Option<String>,
+    color_space: Option<String>,
+    color_transfer: Option<String>,
+    color_primaries: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+    avg_frame_rate: Option<String>,
+    r_frame_rate: Option<String>,
+    sample_rate: Option<String>,
+    channels: Option<u32>,
+    channel_layout: Option<String>,
+    bit_rate: Option<String>,
+    duration: Option<String>,
+    #[serde(default)]
+    tags: HashMap<String, String>,
+    #[serde(default)]
+    side_data_list: Vec<Value>,
+    #[serde(default)]
+    disposition: HashMap<String, i64>,
+}
+
+impl ProbeStream {
+    fn kind(&self) -> StreamKind {
+        match self.codec_type.as_deref() {
+            Some("video") => StreamKind::Video,
+            Some("audio") => StreamKind::Audio,
+            Some("subtitle") => StreamKind::Subtitle,
+            Some("data") => StreamKind::Data,
+            Some("attachment") => StreamKind::Attachment,
+            _ => StreamKind::Unknown,
+        }
+    }
+
+    fn disposition(&self, key: &str) -> bool {
+        self.disposition.get(key).copied().unwrap_or(0) != 0
+    }
+
+    /// 顺时针旋转角度
+    ///
+    /// 旧版 ffprobe 在 tags.rotate 中给出顺时针角度；
+    /// 新版改为 Display Matrix 侧数据中的 rotation（逆时针，通常为负数）
+    fn rotation(&self) -> u32 {
+        let degrees = if let Some(rotate) = self.tags.get("rotate") {
+            rotate.trim().parse::<f64>().unwrap_or(0.0)
+        } else {
+            self.side_data_list
+                .iter()
+                .find_map(|side| side.get("rotation").and_then(Value::as_f64))
+                .map(|r| -r)
+                .unwrap_or(0.0)
+        };
+        (degrees.round() as i64).rem_euclid(360) as u32
+    }
+
+    fn fps(&self) -> Option<f64> {
+        [&self.avg_frame_rate, &self.r_frame_rate]
+            .into_iter()
+            .flatten()
+            .find_map(|rate| parse_rate(rate))
+    }
+
+    fn to_stream_info(&self) -> StreamInfo {
+        let kind = self.kind();
+        let is_video = kind == StreamKind::Video;
+        let color_transfer = self.color_transfer.clone();
+        let hdr = matches!(
+            color_transfer.as_deref(),
+            Some("smpte2084") | Some("arib-std-b67")
+        );
+
+        StreamInfo {
+            index: self.index,
+            kind,
+            codec: self
+                .codec_name
+                .clone()
+                .unwrap_or_else(|| "unknown".to_string()),
+            profile: self.profile.clone(),
+            bitrate: self.bit_rate.as_deref().and_then(|b| b.parse().ok()),
+            language: self.tags.get("language").cloned(),
+            title: self.tags.get("title").cloned(),
+            is_default: self.disposition("default"),
+            width: self.width,
+            height: self.height,
+            fps: if is_video { self.fps() } else { None },
+            pixel_format: self.pix_fmt.clone(),
+            color_space: self.color_space.clone(),
+            color_transfer,
+            color_primaries: self.color_primaries.clone(),
+            rotation: if is_video { self.rotation() } else { 0 },
+            hdr,
+            attached_pic: self.disposition("attached_pic"),
+            sample_rate: self.sample_rate.as_deref().and_then(|r| r.parse().ok()),
+            channels: self.channels,
+            channel_layout: self.channel_layout.clone(),
+        }
+    }
+}
+
+/// 解析 "30000/1001" 形式的帧率，"0/0" 视为未知
+fn parse_rate(rate: &str) -> Option<f64> {
+    let (num, den) = rate.split_once('/')?;
+    let num: f64 = num.parse().ok()?;
+    let den: f64 = den.parse().ok()?;
+    if num > 0.0 && den > 0.0 {
+        Some(num / den)
+    } else {
+        None
+    }
+}
+
+/// 文件名、大小和容器格式（取扩展名）
+fn file_meta(path: &str) -> (String, u64, String) {
+    let path_obj = Path::new(path);
+    let filename = path_obj
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+    let file_size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let format = path_obj
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("unknown")
+        .to_uppercase();
+    (filename, file_size, format)
+}
+
+/// 从ffprobe JSON输出解析视频信息
+fn parse_ffprobe_output(output: &str, path: String) -> Result<VideoInfo, String> {
+    let probe: ProbeOutput =
+        serde_json::from_str(output).map_err(|e| format!("解析ffprobe输出失败: {}", e))?;
+
+    let streams: Vec<StreamInfo> = probe
+        .streams
+        .iter()
+        .map(ProbeStream::to_stream_info)
+        .collect();
+
+    // 主视频流：第一个非封面图的视频流
+    let primary = streams
+        .iter()
+        .find(|s| s.kind == StreamKind::Video && !s.attached_pic);
+
+    let rotation = primary.map(|s| s.rotation).unwrap_or(0);
+    let (mut width, mut height) = primary
+        .map(|s| (s.width.unwrap_or(0), s.height.unwrap_or(0)))
+        .unwrap_or((0, 0));
+    // 手机竖拍视频以横向编码 + 旋转元数据存储，按显示方向给出宽高
+    if rotation == 90 || rotation == 270 {
+        std::mem::swap(&mut width, &mut height);
+    }
+
+    let duration = probe
+        .format
+        .duration
+        .as_deref()
+        .and_then(|d| d.parse::<f64>().ok())
+        .unwrap_or_else(|| {
+            probe
+                .streams
+                .iter()
+                .filter_map(|s| s.duration.as_deref().and_then(|d| d.parse::<f64>().ok()))
+                .fold(0.0, f64::max)
+        });
+
+    let (filename, file_size, format) = file_meta(&path);
+
+    Ok(VideoInfo {
+        path,
+        filename,
+        duration,
+        width,
+        height,
+        fps: primary.and_then(|s| s.fps).unwrap_or(0.0),
+        codec: primary.map(|s| s.codec.clone()).unwrap_or_default(),
+        bitrate: probe
+            .format
+            .bit_rate
+            .as_deref()
+            .and_then(|b| b.parse().ok())
+            .unwrap_or(0),
+        file_size,
+        format,
+        rotation,
+        streams,
+    })
+}
 
 /// 从FFmpeg输出解析视频信息
 fn parse_ffmpeg_output(output: &str, path: String) -> Result<VideoInfo, String> {
@@ -36,6 +251,7 @@ fn parse_ffmpeg_output(output: &str, path: String) -> Result<VideoInfo, String>
 
             // 解析分辨率
             for part in &parts {
+                let part = part.trim_end_matches(',');
                 if part.contains(&['x'][..]) && part.len() < 10 {
                     let dims: Vec<&str> = part.split('x').collect();
                     if dims.len() == 2 {
@@ -46,8 +262,12 @@ fn parse_ffmpeg_output(output: &str, path: String) -> Result<VideoInfo, String>
             }
 
             // 解析帧率 - 改进:使用 ends_with 而不是 contains 以避免误匹配
-            for part in &parts {
-                if part.ends_with("fps") {
+            for (i, part) in parts.iter().enumerate() {
+                let part = part.trim_end_matches(',');
+                if part == "fps" && i > 0 {
+                    // 常见格式 "25 fps,"：数值在前一个字段
+                    fps = parts[i - 1].parse().unwrap_or(0.0);
+                } else if part.ends_with("fps") {
                     // 更精确地提取数值:移除 fps 后缀并清理可能的特殊字符
                     let fps_str = part
                         .trim_end_matches("fps")
@@ -67,18 +287,7 @@ fn parse_ffmpeg_output(output: &str, path: String) -> Result<VideoInfo, String>
     }
 
     // 获取文件信息
-    let path_obj = std::path::Path::new(&path);
-    let filename = path_obj.file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("unknown")
-        .to_string();
-    let file_size = std::fs::metadata(&path)
-        .map(|m| m.len())
-        .unwrap_or(0);
-    let format = path_obj.extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("unknown")
-        .to_uppercase();
+    let (filename, file_size, format) = file_meta(&path);
 
     Ok(VideoInfo {
         path,
@@ -91,9 +300,65 @@ fn parse_ffmpeg_output(output: &str, path: String) -> Result<VideoInfo, String>
         bitrate,
         file_size,
         format,
+        rotation: 0,
+        streams: Vec::new(),
     })
 }
 
+/// 使用ffprobe读取结构化的流信息
+fn probe_with_ffprobe(ffprobe_path: &Path, path: &str) -> Result<VideoInfo, String> {
+    let output = Command::new(ffprobe_path)
+        .args([
+            "-v",
+            "error",
+            "-print_format",
+            "json",
+            "-show_streams",
+            "-show_format",
+        ])
+        .arg(path)
+        .output()
+        .map_err(|e| format!("执行ffprobe失败: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("ffprobe执行失败: {}", stderr.trim()));
+    }
+
+    parse_ffprobe_output(&String::from_utf8_lossy(&output.stdout), path.to_string())
+}
+
+/// 同步读取视频信息（需在阻塞线程中调用）
+///
+/// 优先使用ffprobe的JSON输出，不可用或失败时回退到解析 ffmpeg -i 的stderr
+pub(crate) fn probe_video_info(ffmpeg_path: &Path, path: String) -> Result<VideoInfo, String> {
+    if let Some(ffprobe_path) = get_ffprobe_path() {
+        match probe_with_ffprobe(&ffprobe_path, &path) {
+            Ok(info) => return Ok(info),
+            Err(e) => println!("ffprobe读取失败，回退到ffmpeg解析: {}", e),
+        }
+    }
+
+    // 运行FFmpeg -i命令
+    let output = Command::new(ffmpeg_path)
+        .arg("-i")
+        .arg(&path)
+        .output()
+        .map_err(|e| format!("执行FFmpeg失败: {}", e))?;
+
+    // FFmpeg将信息输出到stderr
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    parse_ffmpeg_output(&stderr, path)
+}
+
+/// 读取视频时长（秒），用于计算任务进度；无法解析时返回 0
+pub(crate) fn probe_duration(ffmpeg_path: &Path, path: &str) -> f64 {
+    probe_video_info(ffmpeg_path, path.to_string())
+        .map(|info| info.duration)
+        .unwrap_or(0.0)
+}
+
 /// 获取视频信息
 pub async fn get_video_info(path: String) -> Result<VideoInfo, String> {
     // 验证文件存在
@@ -106,19 +371,169 @@ pub async fn get_video_info(path: String) -> Result<VideoInfo, String> {
 
     // 使用 spawn_blocking 将同步的 FFmpeg 操作移到独立线程
     // 避免 async 函数中执行阻塞操作影响异步运行时
-    tokio::task::spawn_blocking(move || {
-        // 运行FFmpeg -i命令
-        let output = Command::new(&ffmpeg_path)
-            .arg("-i")
-            .arg(&path)
-            .output()
-            .map_err(|e| format!("执行FFmpeg失败: {}", e))?;
-
-        // FFmpeg将信息输出到stderr
-        let stderr = String::from_utf8_lossy(&output.stderr);
+    tokio::task::spawn_blocking(move || probe_video_info(&ffmpeg_path, path))
+        .await
+        .map_err(|e| format!("异步任务执行失败: {}", e))?
+}
 
-        parse_ffmpeg_output(&stderr, path)
-    })
-    .await
-    .map_err(|e| format!("异步任务执行失败: {}", e))?
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MULTI_STREAM: &str = r#"{
+        "streams": [
+            {"index": 0, "codec_name": "h264", "profile": "High", "codec_type": "video",
+             "width": 1920, "height": 1080, "pix_fmt": "yuv420p", "color_space": "bt709",
+             "r_frame_rate": "30000/1001", "avg_frame_rate": "30000/1001",
+             "bit_rate": "4500000", "disposition": {"default": 1, "attached_pic": 0},
+             "tags": {"language": "und"}},
+            {"index": 1, "codec_name": "aac", "profile": "LC", "codec_type": "audio",
+             "sample_rate": "48000", "channels": 6, "channel_layout": "5.1",
+             "r_frame_rate": "0/0", "avg_frame_rate": "0/0", "bit_rate": "384000",
+             "disposition": {"default": 1}, "tags": {"language": "eng", "title": "Surround"}},
+            {"index": 2, "codec_name": "aac", "codec_type": "audio",
+             "sample_rate": "44100", "channels": 2, "channel_layout": "stereo",
+             "disposition": {"default": 0}, "tags": {"language": "jpn"}},
+            {"index": 3, "codec_name": "mov_text", "codec_type": "subtitle",
+             "tags": {"language": "chi"}},
+            {"index": 4, "codec_type": "data", "tags": {"handler_name": "GoPro MET"}}
+        ],
+        "format": {"duration": "125.458000", "bit_rate": "4980000"}
+    }"#;
+
+    #[test]
+    fn ffprobe_exposes_all_streams() {
+        let info = parse_ffprobe_output(MULTI_STREAM, "/tmp/movie.mp4".to_string()).unwrap();
+
+        assert_eq!(info.duration, 125.458);
+        assert_eq!((info.width, info.height), (1920, 1080));
+        assert!((info.fps - 29.97).abs() < 0.01);
+        assert_eq!(info.codec, "h264");
+        assert_eq!(info.bitrate, 4_980_000);
+        assert_eq!(info.format, "MP4");
+        assert_eq!(info.streams.len(), 5);
+
+        let kinds: Vec<StreamKind> = info.streams.iter().map(|s| s.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                StreamKind::Video,
+                StreamKind::Audio,
+                StreamKind::Audio,
+                StreamKind::Subtitle,
+                StreamKind::Data,
+            ]
+        );
+
+        let video = &info.streams[0];
+        assert_eq!(video.profile.as_deref(), Some("High"));
+        assert_eq!(video.pixel_format.as_deref(), Some("yuv420p"));
+        assert_eq!(video.color_space.as_deref(), Some("bt709"));
+        assert!(!video.hdr);
+
+        let surround = &info.streams[1];
+        assert_eq!(surround.sample_rate, Some(48000));
+        assert_eq!(surround.channels, Some(6));
+        assert_eq!(surround.channel_layout.as_deref(), Some("5.1"));
+        assert_eq!(surround.language.as_deref(), Some("eng"));
+        assert_eq!(surround.title.as_deref(), Some("Surround"));
+        assert_eq!(surround.fps, None);
+        assert!(surround.is_default);
+        assert!(!info.streams[2].is_default);
+
+        assert_eq!(info.streams[3].language.as_deref(), Some("chi"));
+        assert_eq!(info.streams[4].codec, "unknown");
+    }
+
+    #[test]
+    fn ffprobe_rotated_phone_video_reports_display_size() {
+        // 新版 ffprobe：Display Matrix 侧数据（逆时针 -90 即顺时针 90）
+        let side_data = r#"{
+            "streams": [{"index": 0, "codec_name": "hevc", "codec_type": "video",
+                "width": 1920, "height": 1080, "avg_frame_rate": "30/1",
+                "side_data_list": [{"side_data_type": "Display Matrix", "rotation": -90}]}],
+            "format": {"duration": "3.0"}
+        }"#;
+        let info = parse_ffprobe_output(side_data, "IMG_0001.MOV".to_string()).unwrap();
+        assert_eq!(info.rotation, 90);
+        assert_eq!((info.width, info.height), (1080, 1920));
+        assert_eq!(info.streams[0].width, Some(1920));
+
+        // 旧版 ffprobe：tags.rotate
+        let tag = r#"{
+            "streams": [{"index": 0, "codec_name": "h264", "codec_type": "video",
+                "width": 1280, "height": 720, "tags": {"rotate": "270"}}],
+            "format": {}
+        }"#;
+        let info = parse_ffprobe_output(tag, "clip.mp4".to_string()).unwrap();
+        assert_eq!(info.rotation, 270);
+        assert_eq!((info.width, info.height), (720, 1280));
+
+        let upside_down = r#"{
+            "streams": [{"index": 0, "codec_type": "video", "width": 640, "height": 480,
+                "side_data_list": [{"rotation": 180}]}]
+        }"#;
+        let info = parse_ffprobe_output(upside_down, "a.mp4".to_string()).unwrap();
+        assert_eq!(info.rotation, 180);
+        assert_eq!((info.width, info.height), (640, 480));
+    }
+
+    #[test]
+    fn ffprobe_detects_hdr_and_skips_cover_art() {
+        let output = r#"{
+            "streams": [
+                {"index": 0, "codec_name": "mjpeg", "codec_type": "video",
+                 "width": 600, "height": 600, "disposition": {"attached_pic": 1}},
+                {"index": 1, "codec_name": "hevc", "profile": "Main 10", "codec_type": "video",
+                 "width": 3840, "height": 2160, "pix_fmt": "yuv420p10le",
+                 "color_space": "bt2020nc", "color_transfer": "smpte2084",
+                 "color_primaries": "bt2020", "avg_frame_rate": "24000/1001",
+                 "duration": "60.5"},
+                {"index": 2, "codec_name": "eac3", "codec_type": "audio", "duration": "61.0"}
+            ],
+            "format": {}
+        }"#;
+        let info = parse_ffprobe_output(output, "hdr.mkv".to_string()).unwrap();
+
+        assert_eq!(info.codec, "hevc");
+        assert_eq!((info.width, info.height), (3840, 2160));
+        assert_eq!(info.duration, 61.0);
+        assert!(info.streams[0].attached_pic);
+        assert!(!info.streams[0].hdr);
+
+        let hdr = &info.streams[1];
+        assert!(hdr.hdr);
+        assert_eq!(hdr.pixel_format.as_deref(), Some("yuv420p10le"));
+        assert_eq!(hdr.color_primaries.as_deref(), Some("bt2020"));
+    }
+
+    #[test]
+    fn ffprobe_invalid_json_is_error() {
+        assert!(parse_ffprobe_output("not json", "a.mp4".to_string()).is_err());
+    }
+
+    #[test]
+    fn parse_rate_handles_unknown() {
+        assert_eq!(parse_rate("25/1"), Some(25.0));
+        assert_eq!(parse_rate("0/0"), None);
+        assert_eq!(parse_rate("garbage"), None);
+    }
+
+    #[test]
+    fn fallback_parses_ffmpeg_stderr() {
+        let stderr = "\
+Input #0, mov,mp4,m4a,3gp,3g2,mj2, from 'in.mp4':
+  Duration: 00:01:05.50, start: 0.000000, bitrate: 1205 kb/s
+  Stream #0:0[0x1](und): Video: h264 (High) (avc1 / 0x31637661), yuv420p, 1280x720, 1000 kb/s, 25 fps, 25 tbr
+  Stream #0:1[0x2](und): Audio: aac (LC) (mp4a / 0x6134706D), 44100 Hz, stereo, fltp, 128 kb/s";
+        let info = parse_ffmpeg_output(stderr, "in.mp4".to_string()).unwrap();
+
+        assert_eq!(info.duration, 65.5);
+        assert_eq!((info.width, info.height), (1280, 720));
+        assert_eq!(info.fps, 25.0);
+        assert_eq!(info.codec, "h264");
+        assert_eq!(info.bitrate, 1_205_000);
+        assert_eq!(info.rotation, 0);
+        assert!(info.streams.is_empty());
+    }
 }
@@ -0,0 +1,462 @@
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use once_cell::sync::Lazy;
+
+/// 任务被取消时返回的错误信息
+pub const JOB_CANCELLED: &str = "任务已取消";
+
+/// 失败时保留的 stderr 行数
+const STDERR_TAIL_LINES: usize = 40;
+
+/// 正在运行的 FFmpeg 任务
+static JOBS: Lazy<Mutex<HashMap<String, Arc<JobHandle>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 任务进度（通过 `ffmpeg-progress` 事件发送给前端）
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct JobProgress {
+    /// 任务ID
+    pub job_id: String,
+    /// 进度百分比 (0.0-100.0)
+    pub percent: f64,
+    /// 已处理的输出时长（秒）
+    pub out_time: f64,
+    /// 预期输出总时长（秒），未知时为 0
+    pub duration: f64,
+    /// 处理速度（相对实时倍率，如 2.5 表示 2.5x）
+    pub speed: Option<f64>,
+    /// 预计剩余时间（秒）
+    pub eta: Option<f64>,
+    /// 当前编码帧率
+    pub fps: Option<f64>,
+    /// 已运行时间（秒）
+    pub elapsed: f64,
+}
+
+#[derive(Default)]
+struct JobHandle {
+    child: Mutex<Option<Child>>,
+    cancelled: AtomicBool,
+}
+
+/// 任务注册守卫：存活期间任务可被 [`cancel_job`] 取消，析构时自动注销
+///
+/// 操作队列会在多个 FFmpeg 步骤间复用同一个任务ID，因此注册与单次执行分离。
+pub struct JobGuard {
+    id: String,
+    handle: Arc<JobHandle>,
+}
+
+impl JobGuard {
+    /// 注册任务ID；ID 已被占用时返回错误
+    pub fn register(id: &str) -> Result<Self, String> {
+        let mut jobs = JOBS
+            .lock()
+            .map_err(|e| format!("任务注册表锁定失败: {}", e))?;
+        if jobs.contains_key(id) {
+            return Err(format!("任务ID已存在: {}", id));
+        }
+        let handle = Arc::new(JobHandle::default());
+        jobs.insert(id.to_string(), handle.clone());
+        Ok(Self {
+            id: id.to_string(),
+            handle,
+        })
+    }
+
+    /// 任务ID
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// 任务是否已被取消
+    pub fn is_cancelled(&self) -> bool {
+        self.handle.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for JobGuard {
+    fn drop(&mut self) {
+        if let Ok(mut jobs) = JOBS.lock() {
+            if jobs
+                .get(&self.id)
+                .is_some_and(|h| Arc::ptr_eq(h, &self.handle))
+            {
+                jobs.remove(&self.id);
+            }
+        }
+    }
+}
+
+/// 生成新的任务ID
+pub fn new_job_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// 取消任务：终止正在运行的 FFmpeg 进程
+///
+/// 由执行方负责清理未完成的输出文件。
+pub fn cancel_job(job_id: &str) -> Result<(), String> {
+    let handle = JOBS
+        .lock()
+        .map_err(|e| format!("任务注册表锁定失败: {}", e))?
+        .get(job_id)
+        .cloned()
+        .ok_or_else(|| format!("任务不存在或已结束: {}", job_id))?;
+
+    handle.cancelled.store(true, Ordering::SeqCst);
+    if let Some(child) = handle.child.lock().map_err(|e| e.to_string())?.as_mut() {
+        let _ = child.kill();
+    }
+    Ok(())
+}
+
+/// 创建带进度输出的 FFmpeg 命令
+///
+/// 进度以 `key=value` 形式写入 stdout，禁用交互输入以免进程等待终端。
+pub fn progress_command(ffmpeg_path: &Path) -> Command {
+    let mut cmd = Command::new(ffmpeg_path);
+    cmd.args([
+        "-nostdin",
+        "-hide_banner",
+        "-nostats",
+        "-progress",
+        "pipe:1",
+    ]);
+    cmd
+}
+
+/// 执行 FFmpeg 任务并上报进度
+///
+/// # Arguments
+/// * job_id - 任务ID；若未被 [`JobGuard`] 注册，则在执行期间临时注册
+/// * cmd - 由 [`progress_command`] 创建的命令
+/// * duration - 预期输出时长（秒），用于计算百分比，未知时传 0
+/// * failure_label - 失败时的错误前缀，如 "压缩失败"
+/// * on_progress - 进度回调
+/// * cleanup - 失败或取消时清理未完成的输出
+pub fn run_job(
+    job_id: &str,
+    cmd: Command,
+    duration: f64,
+    failure_label: &str,
+    on_progress: &dyn Fn(JobProgress),
+    cleanup: impl FnOnce(),
+) -> Result<(), String> {
+    run_job_with_stderr(job_id, cmd, duration, failure_label, on_progress, cleanup).map(|_| ())
+}
+
+/// 与 [`run_job`] 相同，成功时返回 stderr 末尾的输出
+///
+/// 用于读取滤镜在结束时打印的统计信息（如 `loudnorm` 的测量结果）。
+pub fn run_job_with_stderr(
+    job_id: &str,
+    mut cmd: Command,
+    duration: f64,
+    failure_label: &str,
+    on_progress: &dyn Fn(JobProgress),
+    cleanup: impl FnOnce(),
+) -> Result<String, String> {
+    let registered = JOBS
+        .lock()
+        .map_err(|e| format!("任务注册表锁定失败: {}", e))?
+        .get(job_id)
+        .cloned();
+    let (handle, _temp_guard) = match registered {
+        Some(handle) => (handle, None),
+        None => {
+            let guard = JobGuard::register(job_id)?;
+            (guard.handle.clone(), Some(guard))
+        }
+    };
+
+    if handle.cancelled.load(Ordering::SeqCst) {
+        cleanup();
+        return Err(JOB_CANCELLED.to_string());
+    }
+
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("执行FFmpeg失败: {}", e))?;
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    {
+        let mut slot = handle.child.lock().map_err(|e| e.to_string())?;
+        // 注册进程前已请求取消：立即终止
+        if handle.cancelled.load(Ordering::SeqCst) {
+            let _ = child.kill();
+        }
+        *slot = Some(child);
+    }
+
+    // stderr 需要并行读取，否则缓冲区写满会阻塞 FFmpeg
+    let stderr_reader = std::thread::spawn(move || {
+        let mut tail = VecDeque::with_capacity(STDERR_TAIL_LINES);
+        if let Some(stderr) = stderr {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                if tail.len() == STDERR_TAIL_LINES {
+                    tail.pop_front();
+                }
+                tail.push_back(line);
+            }
+        }
+        Vec::from(tail).join("\n")
+    });
+
+    let started = Instant::now();
+    if let Some(stdout) = stdout {
+        let mut parser = ProgressParser::default();
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if let Some(sample) = parser.feed(&line) {
+                on_progress(sample.to_progress(job_id, duration, started.elapsed().as_secs_f64()));
+            }
+        }
+    }
+
+    let status = handle
+        .child
+        .lock()
+        .map_err(|e| e.to_string())?
+        .take()
+        .map(|mut child| child.wait())
+        .transpose()
+        .map_err(|e| format!("等待FFmpeg退出失败: {}", e))?;
+    let stderr_tail = stderr_reader.join().unwrap_or_default();
+
+    if handle.cancelled.load(Ordering::SeqCst) {
+        cleanup();
+        return Err(JOB_CANCELLED.to_string());
+    }
+    if !status.is_some_and(|s| s.success()) {
+        cleanup();
+        return Err(format!("{}: {}", failure_label, stderr_tail));
+    }
+    Ok(stderr_tail)
+}
+
+/// 一个完整的进度块（以 `progress=` 行结束）
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ProgressSample {
+    out_time_us: Option<i64>,
+    speed: Option<f64>,
+    fps: Option<f64>,
+    finished: bool,
+}
+
+impl ProgressSample {
+    fn to_progress(self, job_id: &str, duration: f64, elapsed: f64) -> JobProgress {
+        let out_time = self
+            .out_time_us
+            .map(|us| us.max(0) as f64 / 1_000_000.0)
+            .unwrap_or(0.0);
+        let percent = if self.finished {
+            100.0
+        } else if duration > 0.0 {
+            (out_time / duration * 100.0).clamp(0.0, 99.9)
+        } else {
+            0.0
+        };
+        let eta = match self.speed {
+            Some(speed) if speed > 0.0 && duration > 0.0 && !self.finished => {
+                Some(((duration - out_time) / speed).max(0.0))
+            }
+            _ if self.finished => Some(0.0),
+            _ => None,
+        };
+
+        JobProgress {
+            job_id: job_id.to_string(),
+            percent,
+            out_time,
+            duration,
+            speed: self.speed,
+            eta,
+            fps: self.fps,
+            elapsed,
+        }
+    }
+}
+
+/// 解析 `-progress` 输出
+///
+/// 注意：FFmpeg 的 `out_time_ms` 实际单位是微秒，与 `out_time_us` 相同。
+#[derive(Default)]
+struct ProgressParser {
+    current: Option<ProgressSample>,
+}
+
+impl ProgressParser {
+    fn feed(&mut self, line: &str) -> Option<ProgressSample> {
+        let (key, value) = line.trim().split_once('=')?;
+        let value = value.trim();
+        let sample = self.current.get_or_insert(ProgressSample {
+            out_time_us: None,
+            speed: None,
+            fps: None,
+            finished: false,
+        });
+
+        match key {
+            "out_time_us" | "out_time_ms" => {
+                if let Ok(us) = value.parse::<i64>() {
+                    sample.out_time_us = Some(us);
+                }
+            }
+            "speed" => {
+                sample.speed = value.trim_end_matches('x').trim().parse::<f64>().ok();
+            }
+            "fps" => {
+                sample.fps = value.parse::<f64>().ok();
+            }
+            "progress" => {
+                let mut done = self.current.take()?;
+                done.finished = value == "end";
+                return Some(done);
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed_all(parser: &mut ProgressParser, text: &str) -> Vec<ProgressSample> {
+        text.lines().filter_map(|line| parser.feed(line)).collect()
+    }
+
+    #[test]
+    fn test_parse_progress_blocks() {
+        let mut parser = ProgressParser::default();
+        let samples = feed_all(
+            &mut parser,
+            "frame=120\nfps=30.00\nout_time_us=5000000\nout_time_ms=5000000\n\
+             out_time=00:00:05.000000\nspeed=2.5x\nprogress=continue\n\
+             frame=240\nfps=N/A\nout_time_ms=10000000\nspeed=N/A\nprogress=end\n",
+        );
+
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].out_time_us, Some(5_000_000));
+        assert_eq!(samples[0].speed, Some(2.5));
+        assert_eq!(samples[0].fps, Some(30.0));
+        assert!(!samples[0].finished);
+
+        assert_eq!(samples[1].out_time_us, Some(10_000_000));
+        assert_eq!(samples[1].speed, None);
+        assert_eq!(samples[1].fps, None);
+        assert!(samples[1].finished);
+    }
+
+    #[test]
+    fn test_progress_percent_and_eta() {
+        let sample = ProgressSample {
+            out_time_us: Some(5_000_000),
+            speed: Some(2.0),
+            fps: None,
+            finished: false,
+        };
+        let progress = sample.to_progress("job", 20.0, 1.0);
+        assert_eq!(progress.percent, 25.0);
+        assert_eq!(progress.out_time, 5.0);
+        assert_eq!(progress.eta, Some(7.5));
+
+        // 时长未知时不估算百分比与剩余时间
+        let unknown = sample.to_progress("job", 0.0, 1.0);
+        assert_eq!(unknown.percent, 0.0);
+        assert_eq!(unknown.eta, None);
+
+        // 未结束前不显示 100%，结束时为 100%
+        let overshoot = ProgressSample {
+            out_time_us: Some(30_000_000),
+            ..sample
+        };
+        assert!(overshoot.to_progress("job", 20.0, 1.0).percent < 100.0);
+        let done = ProgressSample {
+            finished: true,
+            ..sample
+        };
+        assert_eq!(done.to_progress("job", 20.0, 1.0).percent, 100.0);
+        assert_eq!(done.to_progress("job", 20.0, 1.0).eta, Some(0.0));
+    }
+
+    #[test]
+    fn test_register_and_cancel_unknown_job() {
+        let guard = JobGuard::register("test-register-job").unwrap();
+        assert!(JobGuard::register("test-register-job").is_err());
+        assert!(!guard.is_cancelled());
+
+        cancel_job(guard.id()).unwrap();
+        assert!(guard.is_cancelled());
+
+        drop(guard);
+        assert!(cancel_job("test-register-job").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cancel_kills_running_process_and_cleans_up() {
+        use std::sync::atomic::AtomicUsize;
+
+        let job_id = "test-cancel-running-job";
+        let guard = JobGuard::register(job_id).unwrap();
+
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg("echo out_time_us=1000000; echo progress=continue; exec sleep 30");
+
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(200));
+            cancel_job(job_id).unwrap();
+        });
+
+        let reports = AtomicUsize::new(0);
+        let cleaned = AtomicBool::new(false);
+        let started = Instant::now();
+        let result = run_job(
+            guard.id(),
+            cmd,
+            10.0,
+            "测试失败",
+            &|progress| {
+                assert_eq!(progress.percent, 10.0);
+                reports.fetch_add(1, Ordering::SeqCst);
+            },
+            || cleaned.store(true, Ordering::SeqCst),
+        );
+        canceller.join().unwrap();
+
+        assert_eq!(result, Err(JOB_CANCELLED.to_string()));
+        assert!(started.elapsed().as_secs() < 10);
+        assert_eq!(reports.load(Ordering::SeqCst), 1);
+        assert!(cleaned.load(Ordering::SeqCst));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_failed_job_reports_stderr_and_cleans_up() {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg("echo 'Invalid data found' >&2; exit 1");
+
+        let cleaned = AtomicBool::new(false);
+        let result = run_job("test-failed-job", cmd, 0.0, "压缩失败", &|_| {}, || {
+            cleaned.store(true, Ordering::SeqCst)
+        });
+
+        assert_eq!(result, Err("压缩失败: Invalid data found".to_string()));
+        assert!(cleaned.load(Ordering::SeqCst));
+        // 临时注册的任务执行结束后自动注销
+        assert!(cancel_job("test-failed-job").is_err());
+    }
+}
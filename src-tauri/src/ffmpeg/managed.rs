@@ -0,0 +1,879 @@
+//! 托管的 FFmpeg 可执行文件
+//!
+//! 按清单下载指定版本的 ffmpeg/ffprobe 到应用数据目录，安装前校验 SHA-256，
+//! 支持切换、回滚已安装的版本，或改用用户指定的可执行文件。目录结构：
+//!
+//! ```text
+//! <root>/state.json            已安装版本与当前选择
+//! <root>/versions/<版本>/      ffmpeg、ffprobe
+//! <root>/capabilities/*.json   每个可执行文件的能力报告缓存
+//! ```
+
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use sha2::{Digest, Sha256};
+
+use super::encoders::{parse_encoders, EncoderInfo};
+use super::parse_ffmpeg_version;
+
+const STATE_FILE: &str = "state.json";
+const VERSIONS_DIR: &str = "versions";
+const CAPABILITIES_DIR: &str = "capabilities";
+
+/// 读-改-写 state.json 时持有，避免并发安装/切换互相覆盖
+static STATE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// 清单中的一个下载文件
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ManifestFile {
+    /// 下载地址（https）或本地文件路径
+    pub url: String,
+    /// 下载文件的 SHA-256（十六进制）
+    pub sha256: String,
+    /// 下载的是 zip 压缩包时，可执行文件在包内的路径（按路径后缀匹配）
+    pub archive_path: Option<String>,
+}
+
+/// 某个平台的构建
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ManifestBuild {
+    pub ffmpeg: ManifestFile,
+    pub ffprobe: Option<ManifestFile>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ManifestVersion {
+    pub version: String,
+    pub notes: Option<String>,
+    /// 键为 `{os}-{arch}`，如 windows-x86_64、macos-aarch64
+    pub platforms: HashMap<String, ManifestBuild>,
+}
+
+/// 版本清单
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Manifest {
+    pub versions: Vec<ManifestVersion>,
+}
+
+/// 已安装的托管版本
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct InstalledVersion {
+    /// 清单中的版本名
+    pub version: String,
+    /// `ffmpeg -version` 报告的版本号
+    pub ffmpeg_version: String,
+    /// 安装的 ffmpeg 可执行文件的 SHA-256
+    pub ffmpeg_sha256: String,
+    /// 安装来源清单
+    pub manifest: String,
+    /// 安装时间（毫秒时间戳）
+    pub installed_at: i64,
+}
+
+/// 托管状态（持久化到 state.json）
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ManagedState {
+    #[serde(default)]
+    pub installed: Vec<InstalledVersion>,
+    /// 当前使用的托管版本，为空时使用系统 FFmpeg
+    pub active: Option<String>,
+    /// 切换前的版本，用于回滚
+    pub previous: Option<String>,
+    /// 用户指定的 ffmpeg 路径，优先于托管版本
+    pub custom_path: Option<String>,
+    /// 最近使用的清单地址
+    pub manifest: Option<String>,
+}
+
+/// 清单中可安装的版本
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AvailableVersion {
+    pub version: String,
+    pub notes: Option<String>,
+    pub installed: bool,
+    pub active: bool,
+}
+
+/// FFmpeg 可执行文件的能力报告
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CapabilityReport {
+    pub path: String,
+    pub version: String,
+    pub encoders: Vec<EncoderInfo>,
+    pub filters: Vec<String>,
+    pub hwaccels: Vec<String>,
+}
+
+/// 当前平台在清单中的键
+pub fn platform_key() -> String {
+    format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH)
+}
+
+/// 平台对应的可执行文件名
+fn binary_name(program: &str) -> String {
+    if cfg!(windows) {
+        format!("{}.exe", program)
+    } else {
+        program.to_string()
+    }
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// 版本名会作为目录名，只允许字母、数字和 `.`、`_`、`-`
+fn validate_version_name(version: &str) -> Result<(), String> {
+    let valid = !version.is_empty()
+        && !version.starts_with('.')
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("无效的版本名: {}", version))
+    }
+}
+
+/// 读取远程或本地文件
+///
+/// 远程地址只接受 HTTPS：明文 HTTP 的清单可被中间人连同 SHA-256 一起替换，校验形同虚设。
+async fn fetch(source: &str) -> Result<Vec<u8>, String> {
+    let scheme = source
+        .split_once("://")
+        .map(|(scheme, _)| scheme.to_ascii_lowercase());
+    if scheme
+        .as_deref()
+        .is_some_and(|s| s != "https" && s != "file")
+    {
+        return Err(format!(
+            "不支持的地址: {}（仅支持 https:// 或本地文件）",
+            source
+        ));
+    }
+
+    if scheme.as_deref() == Some("https") {
+        let response = reqwest::get(source)
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("下载失败: {}", e))?;
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| format!("读取下载内容失败: {}", e))?;
+        return Ok(bytes.to_vec());
+    }
+
+    let path = match scheme {
+        Some(_) => &source["file://".len()..],
+        None => source,
+    };
+    tokio::fs::read(path)
+        .await
+        .map_err(|e| format!("读取文件失败: {} ({})", path, e))
+}
+
+/// 读取版本清单
+pub async fn load_manifest(source: &str) -> Result<Manifest, String> {
+    let bytes = fetch(source).await?;
+    serde_json::from_slice(&bytes).map_err(|e| format!("解析版本清单失败: {}", e))
+}
+
+/// 从下载内容中取出可执行文件
+fn extract_binary(bytes: &[u8], archive_path: Option<&str>) -> Result<Vec<u8>, String> {
+    let Some(archive_path) = archive_path else {
+        return Ok(bytes.to_vec());
+    };
+
+    let mut archive =
+        zip::ZipArchive::new(Cursor::new(bytes)).map_err(|e| format!("读取压缩文件失败: {}", e))?;
+    for i in 0..archive.len() {
+        let mut file = archive
+            .by_index(i)
+            .map_err(|e| format!("读取压缩条目失败: {}", e))?;
+        if file
+            .enclosed_name()
+            .is_some_and(|path| path.ends_with(archive_path))
+        {
+            let mut content = Vec::new();
+            file.read_to_end(&mut content)
+                .map_err(|e| format!("解压{}失败: {}", archive_path, e))?;
+            return Ok(content);
+        }
+    }
+    Err(format!("压缩包中未找到: {}", archive_path))
+}
+
+fn write_executable(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    std::fs::write(path, bytes).map_err(|e| format!("写入{}失败: {}", path.display(), e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("设置执行权限失败: {}", e))?;
+    }
+    Ok(())
+}
+
+/// 运行 `-version` 确认可执行文件可用，返回版本号
+fn probe_version(ffmpeg_path: &Path) -> Result<String, String> {
+    let output = Command::new(ffmpeg_path)
+        .arg("-version")
+        .output()
+        .map_err(|e| format!("执行FFmpeg失败: {}", e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() || !stdout.starts_with("ffmpeg version") {
+        return Err(format!(
+            "不是有效的FFmpeg可执行文件: {}",
+            ffmpeg_path.display()
+        ));
+    }
+    Ok(parse_ffmpeg_version(&stdout))
+}
+
+/// 解析 `ffmpeg -filters` 输出，返回滤镜名
+///
+/// 格式为 ` TSC acompressor  A->A  Audio compressor.`，说明行不含 `->`
+pub fn parse_filters(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let _flags = parts.next()?;
+            let name = parts.next()?;
+            parts
+                .next()
+                .filter(|io| io.contains("->"))
+                .map(|_| name.to_string())
+        })
+        .collect()
+}
+
+/// 解析 `ffmpeg -hwaccels` 输出
+pub fn parse_hwaccels(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .skip_while(|line| !line.starts_with("Hardware acceleration methods"))
+        .skip(1)
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// 运行 `ffmpeg -hide_banner <arg>` 并返回 stdout
+fn run_query(ffmpeg_path: &Path, arg: &str) -> Result<String, String> {
+    let output = Command::new(ffmpeg_path)
+        .args(["-hide_banner", arg])
+        .output()
+        .map_err(|e| format!("执行FFmpeg失败: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("FFmpeg {} 执行失败", arg));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// 托管可执行文件的存储目录
+#[derive(Debug, Clone)]
+pub struct BinaryStore {
+    root: PathBuf,
+}
+
+impl BinaryStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// 应用数据目录下的默认存储
+    pub fn default_store() -> Self {
+        Self::new(crate::cc_switch::config::get_ffmpeg_binaries_dir())
+    }
+
+    fn version_dir(&self, version: &str) -> PathBuf {
+        self.root.join(VERSIONS_DIR).join(version)
+    }
+
+    /// 读取状态；文件不存在或损坏时返回默认状态
+    pub fn load_state(&self) -> ManagedState {
+        let path = self.root.join(STATE_FILE);
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return ManagedState::default();
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            log::warn!("FFmpeg 托管状态文件损坏，已忽略: {} ({e})", path.display());
+            ManagedState::default()
+        })
+    }
+
+    fn save_state(&self, state: &ManagedState) -> Result<(), String> {
+        crate::cc_switch::config::write_json_file(&self.root.join(STATE_FILE), state)
+            .map_err(|e| format!("保存FFmpeg托管状态失败: {}", e))
+    }
+
+    /// 在状态锁内修改并保存状态
+    fn update_state<T>(
+        &self,
+        f: impl FnOnce(&mut ManagedState) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let _guard = STATE_LOCK
+            .lock()
+            .map_err(|e| format!("状态锁定失败: {}", e))?;
+        let mut state = self.load_state();
+        let result = f(&mut state)?;
+        self.save_state(&state)?;
+        Ok(result)
+    }
+
+    /// 当前应使用的 ffmpeg：用户指定的路径优先，其次为选中的托管版本
+    ///
+    /// 文件已被删除时返回 `None`，由调用方回退到系统 FFmpeg
+    pub fn active_ffmpeg(&self) -> Option<PathBuf> {
+        let state = self.load_state();
+        if let Some(custom) = state.custom_path {
+            let path = PathBuf::from(custom);
+            if path.is_file() {
+                return Some(path);
+            }
+        }
+        let path = self
+            .version_dir(state.active.as_deref()?)
+            .join(binary_name("ffmpeg"));
+        path.is_file().then_some(path)
+    }
+
+    /// 列出清单中的版本，并记住清单地址
+    pub async fn available_versions(
+        &self,
+        manifest_source: &str,
+    ) -> Result<Vec<AvailableVersion>, String> {
+        let manifest = load_manifest(manifest_source).await?;
+        let platform = platform_key();
+        let state = self.update_state(|state| {
+            state.manifest = Some(manifest_source.to_string());
+            Ok(state.clone())
+        })?;
+
+        Ok(manifest
+            .versions
+            .into_iter()
+            .filter(|v| v.platforms.contains_key(&platform))
+            .map(|v| AvailableVersion {
+                installed: state.installed.iter().any(|i| i.version == v.version),
+                active: state.active.as_deref() == Some(v.version.as_str()),
+                version: v.version,
+                notes: v.notes,
+            })
+            .collect())
+    }
+
+    /// 下载并安装清单中的版本
+    ///
+    /// 所有下载文件通过 SHA-256 校验、ffmpeg 能正常运行后才替换版本目录；
+    /// 尚未选择任何版本时自动启用新安装的版本。
+    pub async fn install(
+        &self,
+        manifest_source: &str,
+        version: &str,
+    ) -> Result<InstalledVersion, String> {
+        validate_version_name(version)?;
+        let manifest = load_manifest(manifest_source).await?;
+        let platform = platform_key();
+        let build = manifest
+            .versions
+            .into_iter()
+            .find(|v| v.version == version)
+            .ok_or_else(|| format!("清单中没有版本: {}", version))?
+            .platforms
+            .remove(&platform)
+            .ok_or_else(|| format!("版本 {} 没有适用于 {} 的构建", version, platform))?;
+
+        // ffmpeg 与 ffprobe 可能来自同一个压缩包，只下载一次
+        let mut downloads: HashMap<String, Vec<u8>> = HashMap::new();
+        for file in std::iter::once(&build.ffmpeg).chain(build.ffprobe.as_ref()) {
+            if downloads.contains_key(&file.url) {
+                continue;
+            }
+            println!("正在下载 {}", file.url);
+            let bytes = fetch(&file.url).await?;
+            let actual = sha256_hex(&bytes);
+            if !actual.eq_ignore_ascii_case(file.sha256.trim()) {
+                return Err(format!(
+                    "SHA-256 校验失败: {}\n期望: {}\n实际: {}",
+                    file.url, file.sha256, actual
+                ));
+            }
+            downloads.insert(file.url.clone(), bytes);
+        }
+
+        let store = self.clone();
+        let version = version.to_string();
+        let manifest_source = manifest_source.to_string();
+        tokio::task::spawn_blocking(move || {
+            store.commit_install(&version, &manifest_source, &build, &downloads)
+        })
+        .await
+        .map_err(|e| format!("任务执行失败: {}", e))?
+    }
+
+    /// 解压到临时目录、验证后替换版本目录并记录状态
+    fn commit_install(
+        &self,
+        version: &str,
+        manifest_source: &str,
+        build: &ManifestBuild,
+        downloads: &HashMap<String, Vec<u8>>,
+    ) -> Result<InstalledVersion, String> {
+        let staging = self
+            .root
+            .join(VERSIONS_DIR)
+            .join(format!(".{}.partial", version));
+        let _ = std::fs::remove_dir_all(&staging);
+        std::fs::create_dir_all(&staging).map_err(|e| format!("创建安装目录失败: {}", e))?;
+
+        let staged = (|| {
+            let mut ffmpeg_sha256 = String::new();
+            let files = [
+                ("ffmpeg", Some(&build.ffmpeg)),
+                ("ffprobe", build.ffprobe.as_ref()),
+            ];
+            for (program, file) in files {
+                let Some(file) = file else { continue };
+                let bytes = extract_binary(&downloads[&file.url], file.archive_path.as_deref())?;
+                if program == "ffmpeg" {
+                    ffmpeg_sha256 = sha256_hex(&bytes);
+                }
+                write_executable(&staging.join(binary_name(program)), &bytes)?;
+            }
+            let ffmpeg_version = probe_version(&staging.join(binary_name("ffmpeg")))?;
+
+            let target = self.version_dir(version);
+            if target.exists() {
+                std::fs::remove_dir_all(&target)
+                    .map_err(|e| format!("删除旧版本失败（可能正在使用）: {}", e))?;
+            }
+            std::fs::rename(&staging, &target).map_err(|e| format!("安装失败: {}", e))?;
+
+            Ok::<_, String>(InstalledVersion {
+                version: version.to_string(),
+                ffmpeg_version,
+                ffmpeg_sha256,
+                manifest: manifest_source.to_string(),
+                installed_at: chrono::Utc::now().timestamp_millis(),
+            })
+        })();
+        let installed = match staged {
+            Ok(installed) => installed,
+            Err(e) => {
+                let _ = std::fs::remove_dir_all(&staging);
+                return Err(e);
+            }
+        };
+
+        self.update_state(|state| {
+            state.installed.retain(|v| v.version != installed.version);
+            state.installed.push(installed.clone());
+            if state.active.is_none() {
+                state.active = Some(installed.version.clone());
+            }
+            state.manifest = Some(manifest_source.to_string());
+            Ok(())
+        })?;
+        println!(
+            "FFmpeg {} 安装完成（{}）",
+            installed.version, installed.ffmpeg_version
+        );
+        Ok(installed)
+    }
+
+    /// 选择托管版本；`None` 表示使用系统 FFmpeg
+    pub fn select(&self, version: Option<&str>) -> Result<(), String> {
+        self.update_state(|state| {
+            if let Some(version) = version {
+                if !state.installed.iter().any(|v| v.version == version) {
+                    return Err(format!("版本未安装: {}", version));
+                }
+            }
+            let version = version.map(str::to_string);
+            if state.active != version {
+                state.previous = std::mem::replace(&mut state.active, version);
+            }
+            Ok(())
+        })
+    }
+
+    /// 回滚到切换前的版本，返回回滚后的版本（`None` 为系统 FFmpeg）
+    pub fn rollback(&self) -> Result<Option<String>, String> {
+        self.update_state(|state| {
+            let Some(previous) = state.previous.take() else {
+                return Err("没有可回滚的版本".to_string());
+            };
+            if !state.installed.iter().any(|v| v.version == previous) {
+                return Err(format!("版本已被删除: {}", previous));
+            }
+            state.previous = state.active.replace(previous);
+            Ok(state.active.clone())
+        })
+    }
+
+    /// 删除已安装的版本（不能删除正在使用的版本）
+    pub fn remove(&self, version: &str) -> Result<(), String> {
+        validate_version_name(version)?;
+        self.update_state(|state| {
+            if state.active.as_deref() == Some(version) {
+                return Err("不能删除正在使用的版本，请先切换到其他版本".to_string());
+            }
+            state.installed.retain(|v| v.version != version);
+            if state.previous.as_deref() == Some(version) {
+                state.previous = None;
+            }
+            let dir = self.version_dir(version);
+            if dir.exists() {
+                std::fs::remove_dir_all(&dir).map_err(|e| format!("删除版本失败: {}", e))?;
+            }
+            Ok(())
+        })
+    }
+
+    /// 设置用户指定的 ffmpeg 路径（验证可运行后保存）；`None` 清除
+    pub fn set_custom_path(&self, path: Option<&str>) -> Result<Option<String>, String> {
+        let version = path.map(|p| probe_version(Path::new(p))).transpose()?;
+        self.update_state(|state| {
+            state.custom_path = path.map(str::to_string);
+            Ok(version)
+        })
+    }
+
+    /// 可执行文件的能力报告，按路径、大小和修改时间缓存
+    pub fn capability_report(
+        &self,
+        ffmpeg_path: &Path,
+        refresh: bool,
+    ) -> Result<CapabilityReport, String> {
+        let metadata =
+            std::fs::metadata(ffmpeg_path).map_err(|e| format!("读取FFmpeg文件失败: {}", e))?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        let fingerprint = format!("{}|{}|{}", ffmpeg_path.display(), metadata.len(), modified);
+        let cache_path = self.root.join(CAPABILITIES_DIR).join(format!(
+            "{}.json",
+            &sha256_hex(fingerprint.as_bytes())[..16]
+        ));
+
+        if !refresh {
+            let cached = std::fs::read_to_string(&cache_path)
+                .ok()
+                .and_then(|content| serde_json::from_str(&content).ok());
+            if let Some(report) = cached {
+                return Ok(report);
+            }
+        }
+
+        let report = CapabilityReport {
+            path: ffmpeg_path.to_string_lossy().to_string(),
+            version: probe_version(ffmpeg_path)?,
+            encoders: parse_encoders(&run_query(ffmpeg_path, "-encoders")?),
+            filters: parse_filters(&run_query(ffmpeg_path, "-filters")?),
+            hwaccels: parse_hwaccels(&run_query(ffmpeg_path, "-hwaccels")?),
+        };
+        if let Err(e) = crate::cc_switch::config::write_json_file(&cache_path, &report) {
+            log::warn!("缓存FFmpeg能力报告失败: {e}");
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILTERS: &str = "\
+Filters:
+  T.. = Timeline support
+  .S. = Slice threading
+  ..C = Command support
+  A = Audio input/output
+  V = Video input/output
+  | = Source or sink filter
+ ... abench            A->A       Benchmark part of a filtergraph.
+ TSC acompressor       A->A       Audio compressor.
+ ..C scale             V->V       Scale the input video size and/or convert the image format.
+ ... anullsrc          |->A       Null audio source, return empty audio frames.
+";
+
+    const HWACCELS: &str = "\
+Hardware acceleration methods:
+cuda
+videotoolbox
+
+";
+
+    #[test]
+    fn parses_filters_and_hwaccels() {
+        assert_eq!(
+            parse_filters(FILTERS),
+            vec!["abench", "acompressor", "scale", "anullsrc"]
+        );
+        assert_eq!(parse_hwaccels(HWACCELS), vec!["cuda", "videotoolbox"]);
+        assert!(parse_hwaccels("Hardware acceleration methods:\n\n").is_empty());
+    }
+
+    #[test]
+    fn version_names_are_safe_directory_names() {
+        assert!(validate_version_name("7.1").is_ok());
+        assert!(validate_version_name("7.1-essentials_2024").is_ok());
+        assert!(validate_version_name("").is_err());
+        assert!(validate_version_name(".hidden").is_err());
+        assert!(validate_version_name("../7.1").is_err());
+        assert!(validate_version_name("7.1/evil").is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_insecure_sources() {
+        for source in [
+            "http://example.com/manifest.json",
+            "HTTP://example.com/manifest.json",
+            "ftp://example.com/manifest.json",
+        ] {
+            let err = load_manifest(source).await.unwrap_err();
+            assert!(err.contains("仅支持 https://"), "{}", err);
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("manifest.json");
+        std::fs::write(&path, r#"{"versions": []}"#).unwrap();
+        for source in [
+            path.to_string_lossy().to_string(),
+            format!("file://{}", path.display()),
+        ] {
+            assert!(load_manifest(&source).await.unwrap().versions.is_empty());
+        }
+    }
+
+    #[test]
+    fn extracts_binary_from_zip() {
+        use std::io::Write;
+
+        let mut buffer = Cursor::new(Vec::new());
+        {
+            let mut zip = zip::ZipWriter::new(&mut buffer);
+            let options = zip::write::SimpleFileOptions::default();
+            zip.start_file("ffmpeg-7.1/README.txt", options).unwrap();
+            zip.write_all(b"readme").unwrap();
+            zip.start_file("ffmpeg-7.1/bin/ffmpeg", options).unwrap();
+            zip.write_all(b"binary").unwrap();
+            zip.finish().unwrap();
+        }
+        let bytes = buffer.into_inner();
+
+        assert_eq!(
+            extract_binary(&bytes, Some("bin/ffmpeg")).unwrap(),
+            b"binary"
+        );
+        assert!(extract_binary(&bytes, Some("bin/ffprobe")).is_err());
+        assert_eq!(extract_binary(b"raw", None).unwrap(), b"raw");
+    }
+
+    #[test]
+    fn select_rollback_and_remove() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = BinaryStore::new(dir.path());
+        store
+            .update_state(|state| {
+                for version in ["6.1", "7.1"] {
+                    state.installed.push(InstalledVersion {
+                        version: version.to_string(),
+                        ffmpeg_version: version.to_string(),
+                        ffmpeg_sha256: String::new(),
+                        manifest: "manifest.json".to_string(),
+                        installed_at: 0,
+                    });
+                }
+                state.active = Some("6.1".to_string());
+                Ok(())
+            })
+            .unwrap();
+
+        assert!(store.select(Some("8.0")).is_err());
+        assert!(store.rollback().is_err());
+
+        store.select(Some("7.1")).unwrap();
+        let state = store.load_state();
+        assert_eq!(state.active.as_deref(), Some("7.1"));
+        assert_eq!(state.previous.as_deref(), Some("6.1"));
+
+        assert_eq!(store.rollback().unwrap().as_deref(), Some("6.1"));
+        assert_eq!(store.rollback().unwrap().as_deref(), Some("7.1"));
+
+        // 切回系统 FFmpeg 后也可以回滚
+        store.select(None).unwrap();
+        assert_eq!(store.load_state().active, None);
+        assert_eq!(store.rollback().unwrap().as_deref(), Some("7.1"));
+
+        assert!(store.remove("7.1").is_err());
+        store.remove("6.1").unwrap();
+        let state = store.load_state();
+        assert_eq!(state.installed.len(), 1);
+        assert_eq!(state.previous, None);
+    }
+
+    #[cfg(unix)]
+    mod fake_binary {
+        use super::*;
+
+        /// 模拟 ffmpeg 的 shell 脚本，每次执行向 calls 文件追加一行
+        fn fake_ffmpeg(version: &str, calls: &Path) -> Vec<u8> {
+            format!(
+                "#!/bin/sh\n\
+                 echo \"$1 $2\" >> '{}'\n\
+                 case \"$*\" in\n\
+                 *-version*) echo 'ffmpeg version {} Copyright (c) the FFmpeg developers' ;;\n\
+                 *-encoders*) printf ' ------\\n V....D libx264  libx264 H.264\\n' ;;\n\
+                 *-filters*) echo ' TSC scale  V->V  Scale.' ;;\n\
+                 *-hwaccels*) printf 'Hardware acceleration methods:\\nvaapi\\n' ;;\n\
+                 esac\n",
+                calls.display(),
+                version
+            )
+            .into_bytes()
+        }
+
+        struct Fixture {
+            dir: tempfile::TempDir,
+            store: BinaryStore,
+            manifest: String,
+        }
+
+        fn fixture(ffmpeg_sha256: Option<&str>) -> Fixture {
+            let dir = tempfile::tempdir().unwrap();
+            let ffmpeg = dir.path().join("ffmpeg-dl");
+            let ffprobe = dir.path().join("ffprobe-dl");
+            let bytes = fake_ffmpeg("7.1-test", &dir.path().join("calls.log"));
+            std::fs::write(&ffmpeg, &bytes).unwrap();
+            std::fs::write(&ffprobe, b"#!/bin/sh\n").unwrap();
+
+            let sha = ffmpeg_sha256
+                .map(str::to_string)
+                .unwrap_or_else(|| sha256_hex(&bytes));
+            let manifest = serde_json::json!({
+                "versions": [{
+                    "version": "7.1",
+                    "notes": "测试构建",
+                    "platforms": {
+                        platform_key(): {
+                            "ffmpeg": {"url": ffmpeg, "sha256": sha},
+                            "ffprobe": {
+                                "url": format!("file://{}", ffprobe.display()),
+                                "sha256": sha256_hex(b"#!/bin/sh\n")
+                            }
+                        }
+                    }
+                }, {
+                    "version": "other-os",
+                    "platforms": {}
+                }]
+            });
+            let manifest_path = dir.path().join("manifest.json");
+            std::fs::write(&manifest_path, manifest.to_string()).unwrap();
+
+            Fixture {
+                store: BinaryStore::new(dir.path().join("managed")),
+                manifest: manifest_path.to_string_lossy().to_string(),
+                dir,
+            }
+        }
+
+        #[tokio::test]
+        async fn installs_verified_version_from_local_manifest() {
+            let f = fixture(None);
+            assert_eq!(f.store.active_ffmpeg(), None);
+
+            let available = f.store.available_versions(&f.manifest).await.unwrap();
+            assert_eq!(available.len(), 1);
+            assert!(!available[0].installed);
+
+            let installed = f.store.install(&f.manifest, "7.1").await.unwrap();
+            assert_eq!(installed.ffmpeg_version, "7.1-test");
+
+            let version_dir = f.dir.path().join("managed/versions/7.1");
+            assert!(version_dir.join("ffprobe").is_file());
+            assert_eq!(f.store.active_ffmpeg(), Some(version_dir.join("ffmpeg")));
+
+            let state = f.store.load_state();
+            assert_eq!(state.active.as_deref(), Some("7.1"));
+            assert_eq!(state.manifest.as_deref(), Some(f.manifest.as_str()));
+            let available = f.store.available_versions(&f.manifest).await.unwrap();
+            assert!(available[0].installed && available[0].active);
+
+            assert!(f.store.install(&f.manifest, "other-os").await.is_err());
+            assert!(f.store.install(&f.manifest, "9.9").await.is_err());
+        }
+
+        #[tokio::test]
+        async fn rejects_checksum_mismatch() {
+            let f = fixture(Some(&"0".repeat(64)));
+            let err = f.store.install(&f.manifest, "7.1").await.unwrap_err();
+            assert!(err.contains("SHA-256 校验失败"), "{}", err);
+
+            assert!(!f.dir.path().join("managed/versions/7.1").exists());
+            assert_eq!(f.store.load_state(), ManagedState::default());
+        }
+
+        #[tokio::test]
+        async fn custom_binary_takes_precedence_and_reports_are_cached() {
+            let f = fixture(None);
+            f.store.install(&f.manifest, "7.1").await.unwrap();
+
+            let custom = f.dir.path().join("custom-ffmpeg");
+            write_executable(
+                &custom,
+                &fake_ffmpeg("6.0-custom", &f.dir.path().join("calls.log")),
+            )
+            .unwrap();
+            let not_ffmpeg = f.dir.path().join("ffprobe-dl");
+            assert!(f
+                .store
+                .set_custom_path(Some(&not_ffmpeg.to_string_lossy()))
+                .is_err());
+
+            let version = f
+                .store
+                .set_custom_path(Some(&custom.to_string_lossy()))
+                .unwrap();
+            assert_eq!(version.as_deref(), Some("6.0-custom"));
+            assert_eq!(f.store.active_ffmpeg(), Some(custom.clone()));
+
+            let calls = f.dir.path().join("calls.log");
+            std::fs::remove_file(&calls).unwrap();
+            let report = f.store.capability_report(&custom, false).unwrap();
+            assert_eq!(report.version, "6.0-custom");
+            assert_eq!(report.encoders[0].name, "libx264");
+            assert_eq!(report.filters, vec!["scale"]);
+            assert_eq!(report.hwaccels, vec!["vaapi"]);
+            let first_calls = std::fs::read_to_string(&calls).unwrap().lines().count();
+
+            // 第二次读取缓存，不再运行可执行文件
+            assert_eq!(f.store.capability_report(&custom, false).unwrap(), report);
+            let after_cache = std::fs::read_to_string(&calls).unwrap().lines().count();
+            assert_eq!(after_cache, first_calls);
+            f.store.capability_report(&custom, true).unwrap();
+            let after_refresh = std::fs::read_to_string(&calls).unwrap().lines().count();
+            assert_eq!(after_refresh, first_calls * 2);
+
+            f.store.set_custom_path(None).unwrap();
+            assert_eq!(
+                f.store.active_ffmpeg(),
+                Some(f.dir.path().join("managed/versions/7.1/ffmpeg"))
+            );
+        }
+    }
+}
@@ -5,14 +5,41 @@ pub mod extract;
 pub mod trim;
 pub mod gif;
 pub mod thumbnails;
+pub mod job;
+pub mod encoders;
+pub mod concat;
+pub mod crop;
+pub mod rotate;
+pub mod overlay;
+pub mod audio;
+pub mod subtitle;
+pub mod storyboard;
+pub mod managed;
 
 pub use info::get_video_info;
 pub use thumbnails::generate_thumbnails;
 pub use compress::{compress_video, CompressParams};
 pub use speed::{change_video_speed, SpeedParams};
 pub use extract::{extract_frames, ExtractParams};
+pub use storyboard::ContactSheetParams;
 pub use trim::{trim_video, TrimParams};
-pub use gif::{convert_to_gif, GifParams};
+pub use gif::{animation_extension, convert_to_gif, GifParams};
+pub use concat::{concat_videos, ConcatParams};
+pub use crop::{crop_video, CropParams};
+pub use rotate::{rotate_video, RotateParams};
+pub use overlay::{overlay_watermark, OverlayParams};
+pub use audio::{
+    audio_extension, extract_audio, measure_loudness, mute_video, normalize_loudness,
+    replace_audio, AudioExtractParams, AudioReplaceParams, LoudnessStats, LoudnormParams,
+    LoudnormResult,
+};
+pub use subtitle::{
+    burn_subtitles, extract_subtitles, mux_subtitles, shift_srt_file, BurnSubtitleParams,
+    ExtractSubtitleParams, MuxSubtitleParams,
+};
+pub use encoders::{get_encoder_capabilities, CodecOption, EncoderCapabilities, EncoderInfo};
+pub use managed::{AvailableVersion, BinaryStore, CapabilityReport, InstalledVersion, ManagedState};
+pub use job::{cancel_job, new_job_id, JobGuard, JobProgress, JOB_CANCELLED};
 
 use std::env;
 use std::path::PathBuf;
@@ -24,11 +51,16 @@ use std::process::Command;
 /// 返回找到的FFmpeg可执行文件的完整路径
 ///
 /// # 查找策略
+/// - 优先使用用户指定的路径或选中的托管版本（见 [`managed`]）
 /// - Windows: 使用 `where` 命令查找系统PATH中的ffmpeg
 /// - 回退: 如果系统PATH中找不到,尝试使用应用目录下的ffmpeg
 pub fn get_ffmpeg_path() -> Option<PathBuf> {
+    if let Some(path) = BinaryStore::default_store().active_ffmpeg() {
+        return Some(path);
+    }
+
     // 首先尝试从系统PATH中查找ffmpeg
-    if let Some(path) = find_ffmpeg_in_path() {
+    if let Some(path) = find_in_path("ffmpeg") {
         return Some(path);
     }
 
@@ -51,11 +83,30 @@ pub fn get_ffmpeg_path() -> Option<PathBuf> {
     None
 }
 
-/// 在系统PATH中查找ffmpeg
+/// 查找ffprobe可执行文件路径
+///
+/// 优先使用与ffmpeg同目录的ffprobe（保证版本一致），其次查找系统PATH
+pub fn get_ffprobe_path() -> Option<PathBuf> {
+    let ffprobe_name = if cfg!(windows) {
+        "ffprobe.exe"
+    } else {
+        "ffprobe"
+    };
+    if let Some(ffmpeg_dir) = get_ffmpeg_path().and_then(|p| p.parent().map(|d| d.to_path_buf())) {
+        let ffprobe_path = ffmpeg_dir.join(ffprobe_name);
+        if ffprobe_path.exists() {
+            return Some(ffprobe_path);
+        }
+    }
+
+    find_in_path("ffprobe")
+}
+
+/// 在系统PATH中查找可执行文件
 #[cfg(target_os = "windows")]
-fn find_ffmpeg_in_path() -> Option<PathBuf> {
+fn find_in_path(program: &str) -> Option<PathBuf> {
     let output = Command::new("where")
-        .args(["ffmpeg"])
+        .args([program])
         .output()
         .ok()?;
 
@@ -69,11 +120,11 @@ fn find_ffmpeg_in_path() -> Option<PathBuf> {
     }
 }
 
-/// 在系统PATH中查找ffmpeg
+/// 在系统PATH中查找可执行文件
 #[cfg(not(target_os = "windows"))]
-fn find_ffmpeg_in_path() -> Option<PathBuf> {
+fn find_in_path(program: &str) -> Option<PathBuf> {
     let output = Command::new("which")
-        .args(["ffmpeg"])
+        .args([program])
         .output()
         .ok()?;
 
@@ -0,0 +1,153 @@
+use super::get_ffmpeg_path;
+use super::info::probe_video_info;
+use super::job::{progress_command, run_job, JobProgress};
+
+fn default_position() -> String {
+    "bottom_right".to_string()
+}
+
+fn default_margin() -> u32 {
+    20
+}
+
+fn default_opacity() -> f64 {
+    1.0
+}
+
+fn default_scale() -> f64 {
+    0.15
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct OverlayParams {
+    /// 水印图片路径（PNG 透明背景效果最佳）
+    pub image_path: String,
+    #[serde(default = "default_position")]
+    pub position: String, // top_left, top_right, bottom_left, bottom_right, center
+    #[serde(default = "default_margin")]
+    pub margin: u32, // 距边缘像素
+    #[serde(default = "default_opacity")]
+    pub opacity: f64, // 0-1
+    #[serde(default = "default_scale")]
+    pub scale: f64, // 水印宽度占视频宽度的比例
+}
+
+/// 生成水印叠加滤镜
+fn overlay_filter(params: &OverlayParams, video_width: u32) -> Result<String, String> {
+    let m = params.margin;
+    let position = match params.position.as_str() {
+        "top_left" => format!("{}:{}", m, m),
+        "top_right" => format!("W-w-{}:{}", m, m),
+        "bottom_left" => format!("{}:H-h-{}", m, m),
+        "bottom_right" => format!("W-w-{}:H-h-{}", m, m),
+        "center" => "(W-w)/2:(H-h)/2".to_string(),
+        other => return Err(format!("未知的水印位置: {}", other)),
+    };
+    if !(params.opacity > 0.0 && params.opacity <= 1.0) {
+        return Err("水印不透明度必须在 0 到 1 之间".to_string());
+    }
+    if !(params.scale > 0.0 && params.scale <= 1.0) {
+        return Err("水印缩放比例必须在 0 到 1 之间".to_string());
+    }
+
+    let logo_width = ((video_width as f64 * params.scale).round() as u32).max(1);
+    let mut logo = format!("[1:v]scale={}:-1,format=rgba", logo_width);
+    if params.opacity < 1.0 {
+        logo.push_str(&format!(",colorchannelmixer=aa={}", params.opacity));
+    }
+    Ok(format!("{}[wm];[0:v][wm]overlay={}[outv]", logo, position))
+}
+
+/// 叠加图片水印
+///
+/// # Arguments
+/// * input_path - 输入视频文件路径
+/// * output_path - 输出视频文件路径
+/// * params - 水印参数
+/// * job_id - 任务ID，可通过 `cancel_job` 取消
+/// * on_progress - 进度回调函数
+pub async fn overlay_watermark(
+    input_path: String,
+    output_path: String,
+    params: OverlayParams,
+    job_id: String,
+    on_progress: impl Fn(JobProgress) + Send + 'static,
+) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let ffmpeg_path = get_ffmpeg_path().ok_or_else(|| "FFmpeg未找到".to_string())?;
+
+        if !std::path::Path::new(&params.image_path).is_file() {
+            return Err(format!("水印图片不存在: {}", params.image_path));
+        }
+
+        let info = probe_video_info(&ffmpeg_path, input_path.clone())?;
+        let filter = overlay_filter(&params, info.width)?;
+
+        let mut cmd = progress_command(&ffmpeg_path);
+        cmd.arg("-i").arg(&input_path);
+        cmd.arg("-i").arg(&params.image_path);
+        cmd.arg("-filter_complex").arg(filter);
+        cmd.arg("-map").arg("[outv]");
+        cmd.arg("-map").arg("0:a?");
+        cmd.arg("-c:a").arg("copy");
+        cmd.arg("-y").arg(&output_path);
+
+        run_job(
+            &job_id,
+            cmd,
+            info.duration,
+            "添加水印失败",
+            &on_progress,
+            || {
+                let _ = std::fs::remove_file(&output_path);
+            },
+        )
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(json: serde_json::Value) -> OverlayParams {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn default_is_opaque_bottom_right() {
+        let p = params(serde_json::json!({"image_path": "logo.png"}));
+        assert_eq!(
+            overlay_filter(&p, 1920),
+            Ok(
+                "[1:v]scale=288:-1,format=rgba[wm];[0:v][wm]overlay=W-w-20:H-h-20[outv]"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn applies_opacity_and_position() {
+        let p = params(serde_json::json!({
+            "image_path": "logo.png",
+            "position": "top_left",
+            "margin": 0,
+            "opacity": 0.5,
+            "scale": 0.25
+        }));
+        assert_eq!(
+            overlay_filter(&p, 1280),
+            Ok("[1:v]scale=320:-1,format=rgba,colorchannelmixer=aa=0.5[wm];[0:v][wm]overlay=0:0[outv]"
+                .to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_params() {
+        let bad_position = params(serde_json::json!({"image_path": "a.png", "position": "middle"}));
+        assert!(overlay_filter(&bad_position, 1920).is_err());
+        let bad_opacity = params(serde_json::json!({"image_path": "a.png", "opacity": 0}));
+        assert!(overlay_filter(&bad_opacity, 1920).is_err());
+    }
+}
@@ -0,0 +1,117 @@
+use super::get_ffmpeg_path;
+use super::info::probe_duration;
+use super::job::{progress_command, run_job, JobProgress};
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct RotateParams {
+    #[serde(default)]
+    pub rotation: u32, // 顺时针角度：0, 90, 180, 270
+    #[serde(default)]
+    pub flip_horizontal: bool,
+    #[serde(default)]
+    pub flip_vertical: bool,
+}
+
+/// 生成旋转/翻转滤镜（先旋转后翻转）
+fn rotate_filters(params: &RotateParams) -> Result<Vec<&'static str>, String> {
+    let mut filters = Vec::new();
+    let mut hflip = params.flip_horizontal;
+    let mut vflip = params.flip_vertical;
+
+    match params.rotation {
+        0 => {}
+        90 => filters.push("transpose=clock"),
+        // 旋转 180° 等同于水平加垂直翻转，与翻转选项合并
+        180 => {
+            hflip = !hflip;
+            vflip = !vflip;
+        }
+        270 => filters.push("transpose=cclock"),
+        other => return Err(format!("不支持的旋转角度: {}", other)),
+    }
+
+    if hflip {
+        filters.push("hflip");
+    }
+    if vflip {
+        filters.push("vflip");
+    }
+    if filters.is_empty() {
+        return Err("未设置旋转或翻转".to_string());
+    }
+    Ok(filters)
+}
+
+/// 旋转或翻转视频
+///
+/// # Arguments
+/// * input_path - 输入视频文件路径
+/// * output_path - 输出视频文件路径
+/// * params - 旋转参数
+/// * job_id - 任务ID，可通过 `cancel_job` 取消
+/// * on_progress - 进度回调函数
+pub async fn rotate_video(
+    input_path: String,
+    output_path: String,
+    params: RotateParams,
+    job_id: String,
+    on_progress: impl Fn(JobProgress) + Send + 'static,
+) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let ffmpeg_path = get_ffmpeg_path().ok_or_else(|| "FFmpeg未找到".to_string())?;
+
+        let filters = rotate_filters(&params)?;
+        let duration = probe_duration(&ffmpeg_path, &input_path);
+
+        let mut cmd = progress_command(&ffmpeg_path);
+        cmd.arg("-i").arg(&input_path);
+        cmd.arg("-vf").arg(filters.join(","));
+        cmd.arg("-c:a").arg("copy");
+        cmd.arg("-y").arg(&output_path);
+
+        run_job(&job_id, cmd, duration, "旋转失败", &on_progress, || {
+            let _ = std::fs::remove_file(&output_path);
+        })
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(rotation: u32, flip_horizontal: bool, flip_vertical: bool) -> RotateParams {
+        RotateParams {
+            rotation,
+            flip_horizontal,
+            flip_vertical,
+        }
+    }
+
+    #[test]
+    fn builds_filters() {
+        assert_eq!(
+            rotate_filters(&params(90, false, false)),
+            Ok(vec!["transpose=clock"])
+        );
+        assert_eq!(
+            rotate_filters(&params(270, true, false)),
+            Ok(vec!["transpose=cclock", "hflip"])
+        );
+        assert_eq!(
+            rotate_filters(&params(180, false, false)),
+            Ok(vec!["hflip", "vflip"])
+        );
+        assert_eq!(rotate_filters(&params(180, true, false)), Ok(vec!["vflip"]));
+        assert_eq!(rotate_filters(&params(0, false, true)), Ok(vec!["vflip"]));
+    }
+
+    #[test]
+    fn rejects_invalid_or_empty() {
+        assert!(rotate_filters(&params(45, false, false)).is_err());
+        assert!(rotate_filters(&params(0, false, false)).is_err());
+        // 旋转 180° 再水平、垂直翻转等于原画面
+        assert!(rotate_filters(&params(180, true, true)).is_err());
+    }
+}
@@ -1,5 +1,6 @@
-use std::process::{Command, Stdio};
 use super::get_ffmpeg_path;
+use super::info::probe_duration;
+use super::job::{progress_command, run_job, JobProgress};
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct SpeedParams {
@@ -14,20 +15,27 @@ pub struct SpeedParams {
 /// * input_path - 输入视频文件路径
 /// * output_path - 输出视频文件路径
 /// * params - 速度参数
+/// * job_id - 任务ID，可通过 `cancel_job` 取消
+/// * on_progress - 进度回调函数
 pub async fn change_video_speed(
     input_path: String,
     output_path: String,
     params: SpeedParams,
+    job_id: String,
+    on_progress: impl Fn(JobProgress) + Send + 'static,
 ) -> Result<(), String> {
     tokio::task::spawn_blocking(move || {
         let ffmpeg_path = get_ffmpeg_path()
             .ok_or_else(|| "FFmpeg未找到".to_string())?;
 
-        let mut cmd = Command::new(&ffmpeg_path);
+        let mut cmd = progress_command(&ffmpeg_path);
 
         // 计算速度因子
         let video_speed = 1.0 / params.speed;
 
+        // 输出时长按倍速缩放
+        let duration = probe_duration(&ffmpeg_path, &input_path) / params.speed;
+
         cmd.arg("-i").arg(&input_path);
 
         // 视频速度
@@ -61,18 +69,9 @@ pub async fn change_video_speed(
 
         cmd.arg("-y").arg(&output_path);
 
-        let output = cmd
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .map_err(|e| format!("执行FFmpeg失败: {}", e))?;
-
-        if !output.status.success() {
-            let error = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("变速失败: {}", error));
-        }
-
-        Ok::<(), String>(())
+        run_job(&job_id, cmd, duration, "变速失败", &on_progress, || {
+            let _ = std::fs::remove_file(&output_path);
+        })
     })
     .await
     .map_err(|e| format!("任务执行失败: {}", e))?
@@ -0,0 +1,328 @@
+use std::path::{Path, PathBuf};
+
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, Rgb, RgbImage};
+
+/// 平均亮度不高于该值（0-255）的帧视为黑帧
+const BLACK_LUMA: f64 = 16.0;
+
+/// 感知哈希的汉明距离不超过该值且亮度接近时视为重复帧
+const DUPLICATE_DISTANCE: u32 = 4;
+const DUPLICATE_LUMA_DELTA: f64 = 10.0;
+
+/// 联系表中缩略图之间的间距（像素）
+const SHEET_GAP: u32 = 6;
+const SHEET_BACKGROUND: Rgb<u8> = Rgb([24, 24, 24]);
+
+/// 时间戳字体放大倍数与内边距
+const LABEL_SCALE: u32 = 2;
+const LABEL_PADDING: u32 = 3;
+
+fn default_columns() -> u32 {
+    4
+}
+
+fn default_thumb_width() -> u32 {
+    320
+}
+
+fn default_timestamps() -> bool {
+    true
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ContactSheetParams {
+    #[serde(default = "default_columns")]
+    pub columns: u32,
+    #[serde(default = "default_thumb_width")]
+    pub thumb_width: u32, // 每张缩略图宽度（像素）
+    /// 最多放入的帧数，超出时均匀抽取
+    pub max_frames: Option<u32>,
+    /// 在缩略图右下角标注时间戳
+    #[serde(default = "default_timestamps")]
+    pub timestamps: bool,
+}
+
+/// 用于筛选的帧特征
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameSignature {
+    /// 平均亮度 (0-255)
+    pub luma: f64,
+    /// 64 位差值哈希（dHash）
+    pub hash: u64,
+}
+
+/// 计算帧的平均亮度与差值哈希
+pub fn frame_signature(img: &DynamicImage) -> FrameSignature {
+    let gray = img.resize_exact(32, 32, FilterType::Triangle).to_luma8();
+    let luma = gray.pixels().map(|p| p[0] as f64).sum::<f64>() / (32.0 * 32.0);
+
+    let small = imageops::resize(&gray, 9, 8, FilterType::Triangle);
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if small.get_pixel(x, y)[0] > small.get_pixel(x + 1, y)[0] {
+                hash |= 1;
+            }
+        }
+    }
+    FrameSignature { luma, hash }
+}
+
+/// 按顺序筛选帧，返回保留帧的下标
+///
+/// 重复帧只与上一个保留的帧比较，因此画面来回切换时两边都会保留
+pub fn select_frames(
+    signatures: &[FrameSignature],
+    skip_black: bool,
+    skip_duplicates: bool,
+) -> Vec<usize> {
+    let mut kept: Vec<usize> = Vec::new();
+    for (index, signature) in signatures.iter().enumerate() {
+        if skip_black && signature.luma <= BLACK_LUMA {
+            continue;
+        }
+        if skip_duplicates {
+            if let Some(&last) = kept.last() {
+                let previous = signatures[last];
+                if (signature.hash ^ previous.hash).count_ones() <= DUPLICATE_DISTANCE
+                    && (signature.luma - previous.luma).abs() < DUPLICATE_LUMA_DELTA
+                {
+                    continue;
+                }
+            }
+        }
+        kept.push(index);
+    }
+    kept
+}
+
+/// 从 `len` 个元素中均匀抽取至多 `max` 个下标
+fn sample_evenly(len: usize, max: usize) -> Vec<usize> {
+    if max == 0 || len <= max {
+        return (0..len).collect();
+    }
+    (0..max).map(|i| i * len / max).collect()
+}
+
+/// 时间戳文本：不足一小时为 MM:SS，否则为 H:MM:SS
+fn format_timestamp(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    let (h, m, s) = (total / 3600, total / 60 % 60, total % 60);
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{:02}:{:02}", m, s)
+    }
+}
+
+/// 5x7 点阵字形，每行低 5 位从左到右
+fn glyph(c: char) -> Option<[u8; 7]> {
+    Some(match c {
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        _ => return None,
+    })
+}
+
+/// 在 (right, bottom) 左上方绘制黑底白字的时间戳，超出画布的部分被裁掉
+fn draw_label(canvas: &mut RgbImage, text: &str, right: u32, bottom: u32) {
+    let advance = 6 * LABEL_SCALE;
+    let text_width = text.chars().count() as u32 * advance - LABEL_SCALE;
+    let box_width = text_width + LABEL_PADDING * 2;
+    let box_height = 7 * LABEL_SCALE + LABEL_PADDING * 2;
+    let left = right.saturating_sub(box_width);
+    let top = bottom.saturating_sub(box_height);
+
+    let mut put = |x: u32, y: u32, color: Rgb<u8>| {
+        if x < canvas.width() && y < canvas.height() {
+            canvas.put_pixel(x, y, color);
+        }
+    };
+    for y in top..top + box_height {
+        for x in left..left + box_width {
+            put(x, y, Rgb([0, 0, 0]));
+        }
+    }
+    for (i, c) in text.chars().enumerate() {
+        let Some(rows) = glyph(c) else { continue };
+        let origin_x = left + LABEL_PADDING + i as u32 * advance;
+        let origin_y = top + LABEL_PADDING;
+        for (row, bits) in rows.iter().enumerate() {
+            for col in 0..5u32 {
+                if bits & (0x10 >> col) == 0 {
+                    continue;
+                }
+                for dy in 0..LABEL_SCALE {
+                    for dx in 0..LABEL_SCALE {
+                        put(
+                            origin_x + col * LABEL_SCALE + dx,
+                            origin_y + row as u32 * LABEL_SCALE + dy,
+                            Rgb([255, 255, 255]),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 将帧拼成一张联系表
+///
+/// # Arguments
+/// * frames - 帧图片路径及其时间戳（秒）
+/// * params - 联系表参数
+/// * output_path - 输出图片路径，扩展名为 png 时保存 PNG，否则保存 JPEG
+pub fn render_contact_sheet(
+    frames: &[(PathBuf, Option<f64>)],
+    params: &ContactSheetParams,
+    output_path: &Path,
+) -> Result<(), String> {
+    let picked = sample_evenly(frames.len(), params.max_frames.unwrap_or(0) as usize);
+    if picked.is_empty() {
+        return Err("没有可用于生成联系表的帧".to_string());
+    }
+    if params.columns == 0 || params.thumb_width == 0 {
+        return Err("联系表列数和缩略图宽度必须大于 0".to_string());
+    }
+
+    let mut thumbs = Vec::with_capacity(picked.len());
+    for &index in &picked {
+        let (path, time) = &frames[index];
+        let img = image::open(path).map_err(|e| format!("无法打开图片: {}", e))?;
+        thumbs.push((img, *time));
+    }
+
+    // 所有格子使用第一帧的宽高比
+    let (first_width, first_height) = (thumbs[0].0.width(), thumbs[0].0.height());
+    let cell_width = params.thumb_width;
+    let cell_height =
+        ((cell_width as u64 * first_height as u64 / first_width.max(1) as u64) as u32).max(1);
+    let columns = params.columns.min(thumbs.len() as u32);
+    let rows = (thumbs.len() as u32).div_ceil(columns);
+
+    let mut sheet = RgbImage::from_pixel(
+        columns * (cell_width + SHEET_GAP) + SHEET_GAP,
+        rows * (cell_height + SHEET_GAP) + SHEET_GAP,
+        SHEET_BACKGROUND,
+    );
+    for (i, (img, time)) in thumbs.iter().enumerate() {
+        let x = SHEET_GAP + (i as u32 % columns) * (cell_width + SHEET_GAP);
+        let y = SHEET_GAP + (i as u32 / columns) * (cell_height + SHEET_GAP);
+        let thumb = img
+            .resize_exact(cell_width, cell_height, FilterType::Triangle)
+            .to_rgb8();
+        imageops::replace(&mut sheet, &thumb, x as i64, y as i64);
+        if let (true, Some(time)) = (params.timestamps, time) {
+            draw_label(
+                &mut sheet,
+                &format_timestamp(*time),
+                x + cell_width,
+                y + cell_height,
+            );
+        }
+    }
+
+    let is_png = output_path
+        .extension()
+        .and_then(|s| s.to_str())
+        .is_some_and(|s| s.eq_ignore_ascii_case("png"));
+    if is_png {
+        sheet
+            .save_with_format(output_path, image::ImageFormat::Png)
+            .map_err(|e| format!("保存联系表失败: {}", e))
+    } else {
+        let mut file =
+            std::fs::File::create(output_path).map_err(|e| format!("创建输出文件失败: {}", e))?;
+        JpegEncoder::new_with_quality(&mut file, 90)
+            .encode_image(&sheet)
+            .map_err(|e| format!("保存联系表失败: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: u32, height: u32, value: u8) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_pixel(width, height, Rgb([value; 3])))
+    }
+
+    fn gradient(reverse: bool) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(64, 36, |x, _| {
+            let v = (x * 4) as u8;
+            Rgb([if reverse { 255 - v } else { v }; 3])
+        }))
+    }
+
+    #[test]
+    fn signatures_detect_black_and_duplicate_frames() {
+        let frames = [
+            frame_signature(&solid(64, 36, 2)),
+            frame_signature(&gradient(false)),
+            frame_signature(&gradient(false)),
+            frame_signature(&gradient(true)),
+            frame_signature(&solid(64, 36, 5)),
+            frame_signature(&gradient(true)),
+        ];
+        assert!(frames[0].luma <= BLACK_LUMA);
+        assert_ne!(frames[1].hash, frames[3].hash);
+
+        assert_eq!(select_frames(&frames, false, false), vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(select_frames(&frames, true, false), vec![1, 2, 3, 5]);
+        assert_eq!(select_frames(&frames, false, true), vec![0, 1, 3, 4, 5]);
+        // 黑帧被跳过后，前后相同的画面也不会重复保留
+        assert_eq!(select_frames(&frames, true, true), vec![1, 3]);
+    }
+
+    #[test]
+    fn sampling_and_timestamps() {
+        assert_eq!(sample_evenly(3, 0), vec![0, 1, 2]);
+        assert_eq!(sample_evenly(3, 5), vec![0, 1, 2]);
+        assert_eq!(sample_evenly(10, 4), vec![0, 2, 5, 7]);
+
+        assert_eq!(format_timestamp(4.8), "00:04");
+        assert_eq!(format_timestamp(754.0), "12:34");
+        assert_eq!(format_timestamp(3725.0), "1:02:05");
+    }
+
+    #[test]
+    fn renders_grid_with_labels() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut frames = Vec::new();
+        for i in 0..5 {
+            let path = dir.path().join(format!("frame_{:04}.png", i + 1));
+            solid(160, 90, 200).save(&path).unwrap();
+            frames.push((path, Some(i as f64 * 10.0)));
+        }
+        let params = ContactSheetParams {
+            columns: 2,
+            thumb_width: 100,
+            max_frames: Some(4),
+            timestamps: true,
+        };
+        let output = dir.path().join("contact_sheet.png");
+        render_contact_sheet(&frames, &params, &output).unwrap();
+
+        let sheet = image::open(&output).unwrap().to_rgb8();
+        // 2 列 x 2 行，缩略图 100x56
+        assert_eq!(sheet.dimensions(), (2 * 106 + 6, 2 * 62 + 6));
+        assert_eq!(*sheet.get_pixel(0, 0), SHEET_BACKGROUND);
+        assert_eq!(*sheet.get_pixel(10, 10), Rgb([200; 3]));
+        // 右下角是时间戳底色
+        assert_eq!(*sheet.get_pixel(6 + 99, 6 + 55), Rgb([0, 0, 0]));
+
+        assert!(render_contact_sheet(&[], &params, &output).is_err());
+    }
+}
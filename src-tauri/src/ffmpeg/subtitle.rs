@@ -0,0 +1,542 @@
+use std::path::Path;
+
+use super::get_ffmpeg_path;
+use super::info::probe_video_info;
+use super::job::{progress_command, run_job, JobProgress};
+use crate::models::{StreamInfo, StreamKind};
+
+/// 图形字幕编码，无法转换为文本字幕
+const BITMAP_SUBTITLE_CODECS: &[&str] =
+    &["hdmv_pgs_subtitle", "dvd_subtitle", "dvb_subtitle", "xsub"];
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct BurnSubtitleParams {
+    /// SRT/ASS 字幕文件路径
+    pub subtitle_path: String,
+    pub font_name: Option<String>,
+    pub font_size: Option<u32>,
+    pub primary_color: Option<String>, // #RRGGBB
+    pub outline_color: Option<String>, // #RRGGBB
+    pub outline: Option<f64>,          // 描边宽度
+    pub margin_v: Option<u32>,         // 距底部像素
+    #[serde(default)]
+    pub bold: bool,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct MuxSubtitleParams {
+    /// SRT/ASS 字幕文件路径
+    pub subtitle_path: String,
+    pub language: Option<String>, // ISO 639-2，如 chi、eng
+    pub title: Option<String>,
+    /// 设为默认字幕轨
+    #[serde(default)]
+    pub default: bool,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ExtractSubtitleParams {
+    /// 字幕流索引（`StreamInfo::index`），为空时使用第一条字幕流
+    pub stream_index: Option<u32>,
+    pub format: String, // srt, ass
+}
+
+/// `#RRGGBB` 转换为 ASS 颜色 `&H00BBGGRR`
+fn ass_color(color: &str) -> Result<String, String> {
+    let hex = color.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("无效的颜色: {}", color));
+    }
+    let hex = hex.to_ascii_uppercase();
+    Ok(format!("&H00{}{}{}", &hex[4..6], &hex[2..4], &hex[0..2]))
+}
+
+/// 生成 `force_style` 样式覆盖（ASS 样式字段，逗号分隔）
+fn force_style(params: &BurnSubtitleParams) -> Result<String, String> {
+    let mut styles = Vec::new();
+    if let Some(font_name) = &params.font_name {
+        // 逗号和引号会破坏滤镜参数
+        if font_name.contains([',', '\'', ':']) {
+            return Err(format!("无效的字体名称: {}", font_name));
+        }
+        styles.push(format!("FontName={}", font_name));
+    }
+    if let Some(font_size) = params.font_size {
+        styles.push(format!("FontSize={}", font_size));
+    }
+    if let Some(color) = &params.primary_color {
+        styles.push(format!("PrimaryColour={}", ass_color(color)?));
+    }
+    if let Some(color) = &params.outline_color {
+        styles.push(format!("OutlineColour={}", ass_color(color)?));
+    }
+    if let Some(outline) = params.outline {
+        styles.push(format!("Outline={}", outline));
+    }
+    if let Some(margin_v) = params.margin_v {
+        styles.push(format!("MarginV={}", margin_v));
+    }
+    if params.bold {
+        styles.push("Bold=1".to_string());
+    }
+    Ok(styles.join(","))
+}
+
+/// 生成字幕烧录滤镜
+///
+/// `file_name` 为工作目录下的文件名，避免在滤镜参数中转义 Windows 路径的盘符和反斜杠。
+fn burn_filter(file_name: &str, params: &BurnSubtitleParams) -> Result<String, String> {
+    let style = force_style(params)?;
+    if style.is_empty() {
+        Ok(format!("subtitles=filename='{}'", file_name))
+    } else {
+        Ok(format!(
+            "subtitles=filename='{}':force_style='{}'",
+            file_name, style
+        ))
+    }
+}
+
+fn lowercase_extension(path: &str) -> String {
+    Path::new(path)
+        .extension()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_ascii_lowercase())
+        .unwrap_or_default()
+}
+
+/// 输出容器对应的字幕编码
+fn subtitle_codec_for(output_path: &str) -> Result<&'static str, String> {
+    match lowercase_extension(output_path).as_str() {
+        "mp4" | "mov" | "m4v" => Ok("mov_text"),
+        // mkv 可直接保存 SRT/ASS
+        "mkv" => Ok("copy"),
+        "webm" => Ok("webvtt"),
+        other => Err(format!("{} 格式不支持内嵌字幕轨，请使用 MP4 或 MKV", other)),
+    }
+}
+
+/// 构建封装字幕轨的参数（不含输出路径）
+///
+/// `existing_subtitles` 为原视频已有的字幕流数量，新字幕轨排在其后。
+fn mux_args(
+    input_path: &str,
+    output_path: &str,
+    params: &MuxSubtitleParams,
+    existing_subtitles: usize,
+) -> Result<Vec<String>, String> {
+    let codec = subtitle_codec_for(output_path)?;
+    let mut args: Vec<String> = [
+        "-i",
+        input_path,
+        "-i",
+        &params.subtitle_path,
+        "-map",
+        "0",
+        "-map",
+        "1:s:0",
+        "-c",
+        "copy",
+        "-c:s",
+        codec,
+    ]
+    .map(String::from)
+    .to_vec();
+
+    let n = existing_subtitles;
+    if let Some(language) = &params.language {
+        args.extend([
+            format!("-metadata:s:s:{}", n),
+            format!("language={}", language),
+        ]);
+    }
+    if let Some(title) = &params.title {
+        args.extend([format!("-metadata:s:s:{}", n), format!("title={}", title)]);
+    }
+    if params.default {
+        args.extend([format!("-disposition:s:{}", n), "default".to_string()]);
+    }
+    Ok(args)
+}
+
+/// 选择要提取的字幕流
+fn pick_subtitle_stream(
+    streams: &[StreamInfo],
+    stream_index: Option<u32>,
+) -> Result<&StreamInfo, String> {
+    let stream = match stream_index {
+        Some(index) => streams
+            .iter()
+            .find(|s| s.index == index)
+            .filter(|s| s.kind == StreamKind::Subtitle)
+            .ok_or_else(|| format!("流 #{} 不是字幕流", index))?,
+        None => streams
+            .iter()
+            .find(|s| s.kind == StreamKind::Subtitle)
+            .ok_or_else(|| "视频中没有字幕流".to_string())?,
+    };
+    if BITMAP_SUBTITLE_CODECS.contains(&stream.codec.as_str()) {
+        return Err(format!(
+            "字幕流 #{} 为图形字幕（{}），无法转换为文本",
+            stream.index, stream.codec
+        ));
+    }
+    Ok(stream)
+}
+
+/// SRT 时间戳（毫秒）
+fn parse_srt_time(text: &str) -> Option<i64> {
+    let (hms, ms) = text.trim().split_once([',', '.'])?;
+    let mut parts = hms.split(':');
+    let h: i64 = parts.next()?.parse().ok()?;
+    let m: i64 = parts.next()?.parse().ok()?;
+    let s: i64 = parts.next()?.parse().ok()?;
+    if parts.next().is_some() || ms.len() != 3 {
+        return None;
+    }
+    let ms: i64 = ms.parse().ok()?;
+    Some(((h * 60 + m) * 60 + s) * 1000 + ms)
+}
+
+fn format_srt_time(ms: i64) -> String {
+    let ms = ms.max(0);
+    format!(
+        "{:02}:{:02}:{:02},{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+/// 平移 SRT 字幕时间
+///
+/// 偏移后开始时间小于 0 的字幕截断到 0，结束时间不大于 0 的字幕被删除，并重新编号。
+pub fn shift_srt(content: &str, offset_ms: i64) -> Result<String, String> {
+    let content = content.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+    let mut cues = Vec::new();
+
+    for (block_index, block) in content.split("\n\n").enumerate() {
+        let lines: Vec<&str> = block.lines().filter(|l| !l.trim().is_empty()).collect();
+        if lines.is_empty() {
+            continue;
+        }
+        // 序号行可省略
+        let timing_at = if lines[0].contains("-->") { 0 } else { 1 };
+        let timing = lines
+            .get(timing_at)
+            .and_then(|line| line.split_once("-->"))
+            .ok_or_else(|| format!("第 {} 条字幕缺少时间轴", block_index + 1))?;
+        let start = parse_srt_time(timing.0);
+        // 结束时间后可能带有位置信息，如 `X1:100`
+        let end = timing.1.split_whitespace().next().and_then(parse_srt_time);
+        let (Some(start), Some(end)) = (start, end) else {
+            return Err(format!("第 {} 条字幕时间格式无效", block_index + 1));
+        };
+
+        let (start, end) = (start + offset_ms, end + offset_ms);
+        if end <= 0 {
+            continue;
+        }
+        let text = lines[timing_at + 1..].join("\n");
+        cues.push(format!(
+            "{}\n{} --> {}\n{}",
+            cues.len() + 1,
+            format_srt_time(start),
+            format_srt_time(end),
+            text
+        ));
+    }
+
+    if cues.is_empty() {
+        return Ok(String::new());
+    }
+    Ok(cues.join("\n\n") + "\n")
+}
+
+/// 平移 SRT 字幕文件时间并写入新文件
+pub fn shift_srt_file(input_path: &str, output_path: &str, offset_ms: i64) -> Result<(), String> {
+    let bytes = std::fs::read(input_path).map_err(|e| format!("读取字幕文件失败: {}", e))?;
+    let shifted = shift_srt(&String::from_utf8_lossy(&bytes), offset_ms)?;
+    std::fs::write(output_path, shifted).map_err(|e| format!("写入字幕文件失败: {}", e))
+}
+
+/// 烧录字幕到画面
+///
+/// # Arguments
+/// * input_path - 输入视频文件路径
+/// * output_path - 输出视频文件路径
+/// * params - 字幕参数
+/// * job_id - 任务ID，可通过 `cancel_job` 取消
+/// * on_progress - 进度回调函数
+pub async fn burn_subtitles(
+    input_path: String,
+    output_path: String,
+    params: BurnSubtitleParams,
+    job_id: String,
+    on_progress: impl Fn(JobProgress) + Send + 'static,
+) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let ffmpeg_path = get_ffmpeg_path().ok_or_else(|| "FFmpeg未找到".to_string())?;
+
+        let extension = lowercase_extension(&params.subtitle_path);
+        if !matches!(extension.as_str(), "srt" | "ass" | "ssa") {
+            return Err("仅支持 SRT/ASS 字幕文件".to_string());
+        }
+
+        // 字幕复制到临时目录，以不含特殊字符的文件名传给滤镜
+        let work_dir = std::env::temp_dir();
+        let file_name = format!("mnemosyne-subtitle-{}.{}", job_id, extension);
+        let temp_subtitle = work_dir.join(&file_name);
+        std::fs::copy(&params.subtitle_path, &temp_subtitle)
+            .map_err(|e| format!("读取字幕文件失败: {}", e))?;
+
+        let result = burn_filter(&file_name, &params).and_then(|filter| {
+            let duration = probe_video_info(&ffmpeg_path, input_path.clone())?.duration;
+
+            let mut cmd = progress_command(&ffmpeg_path);
+            cmd.current_dir(&work_dir);
+            cmd.arg("-i").arg(&input_path);
+            cmd.arg("-vf").arg(filter);
+            cmd.arg("-c:a").arg("copy");
+            cmd.arg("-y").arg(&output_path);
+
+            run_job(
+                &job_id,
+                cmd,
+                duration,
+                "烧录字幕失败",
+                &on_progress,
+                || {
+                    let _ = std::fs::remove_file(&output_path);
+                },
+            )
+        });
+
+        let _ = std::fs::remove_file(&temp_subtitle);
+        result
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+/// 封装外部字幕为软字幕轨（音视频流直接复制）
+///
+/// # Arguments
+/// * input_path - 输入视频文件路径
+/// * output_path - 输出视频文件路径（MP4 使用 mov_text，MKV 保留原字幕格式）
+/// * params - 字幕参数
+/// * job_id - 任务ID，可通过 `cancel_job` 取消
+/// * on_progress - 进度回调函数
+pub async fn mux_subtitles(
+    input_path: String,
+    output_path: String,
+    params: MuxSubtitleParams,
+    job_id: String,
+    on_progress: impl Fn(JobProgress) + Send + 'static,
+) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let ffmpeg_path = get_ffmpeg_path().ok_or_else(|| "FFmpeg未找到".to_string())?;
+
+        if !Path::new(&params.subtitle_path).is_file() {
+            return Err(format!("字幕文件不存在: {}", params.subtitle_path));
+        }
+
+        let info = probe_video_info(&ffmpeg_path, input_path.clone())?;
+        let existing = info
+            .streams
+            .iter()
+            .filter(|s| s.kind == StreamKind::Subtitle)
+            .count();
+
+        let mut cmd = progress_command(&ffmpeg_path);
+        cmd.args(mux_args(&input_path, &output_path, &params, existing)?);
+        cmd.arg("-y").arg(&output_path);
+
+        run_job(
+            &job_id,
+            cmd,
+            info.duration,
+            "封装字幕失败",
+            &on_progress,
+            || {
+                let _ = std::fs::remove_file(&output_path);
+            },
+        )
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+/// 提取内嵌字幕为 SRT/ASS 文件
+///
+/// # Arguments
+/// * input_path - 输入视频文件路径
+/// * output_path - 输出字幕文件路径
+/// * params - 提取参数
+/// * job_id - 任务ID，可通过 `cancel_job` 取消
+/// * on_progress - 进度回调函数
+pub async fn extract_subtitles(
+    input_path: String,
+    output_path: String,
+    params: ExtractSubtitleParams,
+    job_id: String,
+    on_progress: impl Fn(JobProgress) + Send + 'static,
+) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let ffmpeg_path = get_ffmpeg_path().ok_or_else(|| "FFmpeg未找到".to_string())?;
+
+        if !matches!(params.format.as_str(), "srt" | "ass") {
+            return Err(format!("不支持的字幕格式: {}", params.format));
+        }
+
+        let info = probe_video_info(&ffmpeg_path, input_path.clone())?;
+        if info.streams.is_empty() {
+            return Err("需要 ffprobe 才能读取字幕流".to_string());
+        }
+        let stream = pick_subtitle_stream(&info.streams, params.stream_index)?;
+
+        let mut cmd = progress_command(&ffmpeg_path);
+        cmd.arg("-i").arg(&input_path);
+        cmd.arg("-map").arg(format!("0:{}", stream.index));
+        cmd.arg("-c:s").arg(&params.format);
+        cmd.arg("-y").arg(&output_path);
+
+        run_job(
+            &job_id,
+            cmd,
+            info.duration,
+            "提取字幕失败",
+            &on_progress,
+            || {
+                let _ = std::fs::remove_file(&output_path);
+            },
+        )
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = "\u{feff}1\r\n00:00:01,000 --> 00:00:02,500\r\n第一句\r\n\r\n\
+                           2\r\n00:00:03,000 --> 00:00:04,000 X1:10\r\nSecond line\r\ncontinued\r\n\r\n\
+                           3\r\n01:59:59,900 --> 02:00:00,100\r\nLast\r\n";
+
+    fn subtitle_stream(index: u32, codec: &str) -> StreamInfo {
+        serde_json::from_value(serde_json::json!({
+            "index": index, "kind": "subtitle", "codec": codec, "profile": null,
+            "bitrate": null, "language": "eng", "title": null, "is_default": false,
+            "width": null, "height": null, "fps": null, "pixel_format": null,
+            "color_space": null, "color_transfer": null, "color_primaries": null,
+            "rotation": 0, "hdr": false, "attached_pic": false,
+            "sample_rate": null, "channels": null, "channel_layout": null
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn shifts_srt_forward() {
+        let shifted = shift_srt(FIXTURE, 1500).unwrap();
+        assert_eq!(
+            shifted,
+            "1\n00:00:02,500 --> 00:00:04,000\n第一句\n\n\
+             2\n00:00:04,500 --> 00:00:05,500\nSecond line\ncontinued\n\n\
+             3\n02:00:01,400 --> 02:00:01,600\nLast\n"
+        );
+    }
+
+    #[test]
+    fn shifting_backwards_drops_and_renumbers() {
+        let shifted = shift_srt(FIXTURE, -2_800).unwrap();
+        assert!(shifted.starts_with("1\n00:00:00,200 --> 00:00:01,200\nSecond line\n"));
+        assert!(shifted.contains("2\n01:59:57,100 --> 01:59:57,300\nLast"));
+        assert!(!shifted.contains("第一句"));
+
+        // 部分落在 0 之前的字幕截断到 0
+        let clamped = shift_srt(FIXTURE, -1_500).unwrap();
+        assert!(clamped.starts_with("1\n00:00:00,000 --> 00:00:01,000\n第一句"));
+    }
+
+    #[test]
+    fn rejects_malformed_srt() {
+        assert!(shift_srt("1\n00:00:01 --> 00:00:02\nhi\n", 0).is_err());
+        assert!(shift_srt("1\nhello\n", 0).is_err());
+        assert_eq!(shift_srt("", 1000), Ok(String::new()));
+    }
+
+    #[test]
+    fn shift_srt_file_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("in.srt");
+        let output = dir.path().join("out.srt");
+        std::fs::write(&input, FIXTURE).unwrap();
+
+        shift_srt_file(input.to_str().unwrap(), output.to_str().unwrap(), 500).unwrap();
+        let content = std::fs::read_to_string(&output).unwrap();
+        assert!(content.starts_with("1\n00:00:01,500 --> 00:00:03,000\n第一句"));
+
+        // 再平移回去与规范化后的原文件一致
+        shift_srt_file(output.to_str().unwrap(), input.to_str().unwrap(), -500).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&input).unwrap(),
+            shift_srt(FIXTURE, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn burn_filter_with_style() {
+        let params = BurnSubtitleParams {
+            subtitle_path: "a.srt".into(),
+            font_size: Some(28),
+            primary_color: Some("#FFCC00".into()),
+            outline: Some(1.5),
+            bold: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            burn_filter("sub.srt", &params),
+            Ok("subtitles=filename='sub.srt':force_style='FontSize=28,PrimaryColour=&H0000CCFF,Outline=1.5,Bold=1'".to_string())
+        );
+        assert_eq!(
+            burn_filter("sub.ass", &BurnSubtitleParams::default()),
+            Ok("subtitles=filename='sub.ass'".to_string())
+        );
+        assert!(ass_color("red").is_err());
+    }
+
+    #[test]
+    fn mux_uses_container_codec() {
+        let params = MuxSubtitleParams {
+            subtitle_path: "zh.srt".into(),
+            language: Some("chi".into()),
+            title: None,
+            default: true,
+        };
+        let mp4 = mux_args("in.mp4", "out.mp4", &params, 1).unwrap().join(" ");
+        assert_eq!(
+            mp4,
+            "-i in.mp4 -i zh.srt -map 0 -map 1:s:0 -c copy -c:s mov_text \
+             -metadata:s:s:1 language=chi -disposition:s:1 default"
+        );
+        assert!(mux_args("in.mkv", "out.mkv", &params, 0)
+            .unwrap()
+            .join(" ")
+            .contains("-c:s copy"));
+        assert!(mux_args("in.avi", "out.avi", &params, 0).is_err());
+    }
+
+    #[test]
+    fn picks_text_subtitle_stream() {
+        let streams = vec![
+            subtitle_stream(2, "hdmv_pgs_subtitle"),
+            subtitle_stream(3, "subrip"),
+        ];
+        assert!(pick_subtitle_stream(&streams, None).is_err());
+        assert_eq!(pick_subtitle_stream(&streams, Some(3)).unwrap().index, 3);
+        assert!(pick_subtitle_stream(&streams, Some(7)).is_err());
+        assert!(pick_subtitle_stream(&[], None).is_err());
+    }
+}
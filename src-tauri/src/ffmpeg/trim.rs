@@ -1,5 +1,5 @@
-use std::process::{Command, Stdio};
 use super::get_ffmpeg_path;
+use super::job::{progress_command, run_job, JobProgress};
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct TrimParams {
@@ -14,19 +14,24 @@ pub struct TrimParams {
 /// * input_path - 输入视频文件路径
 /// * output_path - 输出视频文件路径
 /// * params - 截断参数
+/// * job_id - 任务ID，可通过 `cancel_job` 取消
+/// * on_progress - 进度回调函数
 pub async fn trim_video(
     input_path: String,
     output_path: String,
     params: TrimParams,
+    job_id: String,
+    on_progress: impl Fn(JobProgress) + Send + 'static,
 ) -> Result<(), String> {
     tokio::task::spawn_blocking(move || {
         let ffmpeg_path = get_ffmpeg_path()
             .ok_or_else(|| "FFmpeg未找到".to_string())?;
 
-        let mut cmd = Command::new(&ffmpeg_path);
+        let mut cmd = progress_command(&ffmpeg_path);
+        let duration = (params.end_time - params.start_time).max(0.0);
 
-        cmd.arg("-ss").arg(&params.start_time.to_string());
-        cmd.arg("-to").arg(&params.end_time.to_string());
+        cmd.arg("-ss").arg(params.start_time.to_string());
+        cmd.arg("-to").arg(params.end_time.to_string());
         cmd.arg("-i").arg(&input_path);
 
         if params.precise {
@@ -40,18 +45,9 @@ pub async fn trim_video(
 
         cmd.arg("-y").arg(&output_path);
 
-        let output = cmd
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .map_err(|e| format!("执行FFmpeg失败: {}", e))?;
-
-        if !output.status.success() {
-            let error = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("截断失败: {}", error));
-        }
-
-        Ok::<(), String>(())
+        run_job(&job_id, cmd, duration, "截断失败", &on_progress, || {
+            let _ = std::fs::remove_file(&output_path);
+        })
     })
     .await
     .map_err(|e| format!("任务执行失败: {}", e))?
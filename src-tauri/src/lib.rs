@@ -16,6 +16,7 @@ use tauri::{Manager, RunEvent};
 pub mod background_remover;
 pub mod ffmpeg;
 pub mod image_converter;
+pub mod media_jobs;
 pub mod models;
 pub mod process_manager;
 pub mod sticky_notes;
@@ -52,6 +53,61 @@ async fn download_ffmpeg() -> Result<String, String> {
     ffmpeg::download_ffmpeg().await
 }
 
+#[tauri::command]
+async fn get_managed_ffmpeg_status() -> Result<ffmpeg::ManagedState, String> {
+    Ok(ffmpeg::BinaryStore::default_store().load_state())
+}
+
+#[tauri::command]
+async fn list_ffmpeg_versions(manifest: String) -> Result<Vec<ffmpeg::AvailableVersion>, String> {
+    ffmpeg::BinaryStore::default_store()
+        .available_versions(&manifest)
+        .await
+}
+
+#[tauri::command]
+async fn install_ffmpeg_version(
+    manifest: String,
+    version: String,
+) -> Result<ffmpeg::InstalledVersion, String> {
+    ffmpeg::BinaryStore::default_store()
+        .install(&manifest, &version)
+        .await
+}
+
+#[tauri::command]
+async fn select_ffmpeg_version(version: Option<String>) -> Result<(), String> {
+    ffmpeg::BinaryStore::default_store().select(version.as_deref())
+}
+
+#[tauri::command]
+async fn rollback_ffmpeg_version() -> Result<Option<String>, String> {
+    ffmpeg::BinaryStore::default_store().rollback()
+}
+
+#[tauri::command]
+async fn remove_ffmpeg_version(version: String) -> Result<(), String> {
+    ffmpeg::BinaryStore::default_store().remove(&version)
+}
+
+#[tauri::command]
+async fn set_custom_ffmpeg_path(path: Option<String>) -> Result<Option<String>, String> {
+    ffmpeg::BinaryStore::default_store().set_custom_path(path.as_deref())
+}
+
+#[tauri::command]
+async fn get_ffmpeg_capability_report(
+    refresh: Option<bool>,
+) -> Result<ffmpeg::CapabilityReport, String> {
+    tokio::task::spawn_blocking(move || {
+        let ffmpeg_path = ffmpeg::get_ffmpeg_path().ok_or("未找到FFmpeg可执行文件")?;
+        ffmpeg::BinaryStore::default_store()
+            .capability_report(&ffmpeg_path, refresh.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
 #[tauri::command]
 async fn generate_thumbnails(
     input_path: String,
@@ -67,10 +123,31 @@ async fn load_video(path: String) -> Result<models::VideoInfo, String> {
     ffmpeg::get_video_info(path).await
 }
 
+/// 将 FFmpeg 任务进度发送到前端（`ffmpeg-progress` 事件）
+fn emit_job_progress(
+    window: &tauri::Window,
+) -> impl Fn(ffmpeg::JobProgress) + Clone + Send + 'static {
+    let window = window.clone();
+    move |progress| {
+        let _ = window.emit("ffmpeg-progress", &progress);
+    }
+}
+
+/// 注册 FFmpeg 任务；前端未指定任务ID时自动生成
+fn register_job(job_id: Option<String>) -> Result<ffmpeg::JobGuard, String> {
+    ffmpeg::JobGuard::register(&job_id.unwrap_or_else(ffmpeg::new_job_id))
+}
+
+#[tauri::command]
+async fn cancel_job(job_id: String) -> Result<(), String> {
+    ffmpeg::cancel_job(&job_id)
+}
+
 #[tauri::command]
 async fn compress_video_command(
     input_path: String,
     params: ffmpeg::CompressParams,
+    job_id: Option<String>,
     window: tauri::Window,
 ) -> Result<String, String> {
     let input_path_obj = std::path::Path::new(&input_path);
@@ -89,19 +166,30 @@ async fn compress_video_command(
 
     let output_path = format!("{}\\{}_compressed.{}", parent_dir, filename, extension);
 
-    let window_clone = window.clone();
-    ffmpeg::compress_video(input_path, output_path.clone(), params, move |progress| {
-        let _ = window_clone.emit("progress", progress);
-    })
+    let job = register_job(job_id)?;
+    ffmpeg::compress_video(
+        input_path,
+        output_path.clone(),
+        params,
+        job.id().to_string(),
+        emit_job_progress(&window),
+    )
     .await?;
 
     Ok(output_path)
 }
 
+#[tauri::command]
+async fn get_encoder_capabilities() -> Result<ffmpeg::EncoderCapabilities, String> {
+    ffmpeg::get_encoder_capabilities().await
+}
+
 #[tauri::command]
 async fn change_video_speed(
     input_path: String,
     params: ffmpeg::SpeedParams,
+    job_id: Option<String>,
+    window: tauri::Window,
 ) -> Result<String, String> {
     let input_path_obj = std::path::Path::new(&input_path);
     let parent_dir = input_path_obj
@@ -122,7 +210,15 @@ async fn change_video_speed(
         parent_dir, filename, params.speed, extension
     );
 
-    ffmpeg::change_video_speed(input_path, output_path.clone(), params).await?;
+    let job = register_job(job_id)?;
+    ffmpeg::change_video_speed(
+        input_path,
+        output_path.clone(),
+        params,
+        job.id().to_string(),
+        emit_job_progress(&window),
+    )
+    .await?;
 
     Ok(output_path)
 }
@@ -131,12 +227,26 @@ async fn change_video_speed(
 async fn extract_frames(
     input_path: String,
     params: ffmpeg::ExtractParams,
+    job_id: Option<String>,
+    window: tauri::Window,
 ) -> Result<Vec<String>, String> {
-    ffmpeg::extract_frames(input_path, params).await
+    let job = register_job(job_id)?;
+    ffmpeg::extract_frames(
+        input_path,
+        params,
+        job.id().to_string(),
+        emit_job_progress(&window),
+    )
+    .await
 }
 
 #[tauri::command]
-async fn trim_video(input_path: String, params: ffmpeg::TrimParams) -> Result<String, String> {
+async fn trim_video(
+    input_path: String,
+    params: ffmpeg::TrimParams,
+    job_id: Option<String>,
+    window: tauri::Window,
+) -> Result<String, String> {
     let input_path_obj = std::path::Path::new(&input_path);
     let parent_dir = input_path_obj
         .parent()
@@ -153,13 +263,238 @@ async fn trim_video(input_path: String, params: ffmpeg::TrimParams) -> Result<St
 
     let output_path = format!("{}\\{}_trimmed.{}", parent_dir, filename, extension);
 
-    ffmpeg::trim_video(input_path, output_path.clone(), params).await?;
+    let job = register_job(job_id)?;
+    ffmpeg::trim_video(
+        input_path,
+        output_path.clone(),
+        params,
+        job.id().to_string(),
+        emit_job_progress(&window),
+    )
+    .await?;
+
+    Ok(output_path)
+}
+
+#[tauri::command]
+async fn convert_to_gif(
+    input_path: String,
+    params: ffmpeg::GifParams,
+    job_id: Option<String>,
+    window: tauri::Window,
+) -> Result<String, String> {
+    let input_path_obj = std::path::Path::new(&input_path);
+    let parent_dir = input_path_obj
+        .parent()
+        .and_then(|p| p.to_str())
+        .unwrap_or(".");
+    let filename = input_path_obj
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("output");
+
+    let extension = ffmpeg::animation_extension(&params.format)
+        .ok_or_else(|| format!("不支持的动图格式: {}", params.format))?;
+    let output_path = format!("{}\\{}.{}", parent_dir, filename, extension);
+
+    let job = register_job(job_id)?;
+    ffmpeg::convert_to_gif(
+        input_path,
+        output_path.clone(),
+        params,
+        job.id().to_string(),
+        emit_job_progress(&window),
+    )
+    .await?;
+
+    Ok(output_path)
+}
+
+#[tauri::command]
+async fn concat_videos(
+    input_path: String,
+    params: ffmpeg::ConcatParams,
+    job_id: Option<String>,
+    window: tauri::Window,
+) -> Result<String, String> {
+    let input_path_obj = std::path::Path::new(&input_path);
+    let parent_dir = input_path_obj
+        .parent()
+        .and_then(|p| p.to_str())
+        .unwrap_or(".");
+    let filename = input_path_obj
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("output");
+    let extension = input_path_obj
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("mp4");
+
+    let output_path = format!("{}\\{}_concat.{}", parent_dir, filename, extension);
+
+    let job = register_job(job_id)?;
+    ffmpeg::concat_videos(
+        input_path,
+        output_path.clone(),
+        params,
+        job.id().to_string(),
+        emit_job_progress(&window),
+    )
+    .await?;
+
+    Ok(output_path)
+}
+
+#[tauri::command]
+async fn crop_video(
+    input_path: String,
+    params: ffmpeg::CropParams,
+    job_id: Option<String>,
+    window: tauri::Window,
+) -> Result<String, String> {
+    let input_path_obj = std::path::Path::new(&input_path);
+    let parent_dir = input_path_obj
+        .parent()
+        .and_then(|p| p.to_str())
+        .unwrap_or(".");
+    let filename = input_path_obj
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("output");
+    let extension = input_path_obj
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("mp4");
+
+    let output_path = format!("{}\\{}_cropped.{}", parent_dir, filename, extension);
+
+    let job = register_job(job_id)?;
+    ffmpeg::crop_video(
+        input_path,
+        output_path.clone(),
+        params,
+        job.id().to_string(),
+        emit_job_progress(&window),
+    )
+    .await?;
+
+    Ok(output_path)
+}
+
+#[tauri::command]
+async fn rotate_video(
+    input_path: String,
+    params: ffmpeg::RotateParams,
+    job_id: Option<String>,
+    window: tauri::Window,
+) -> Result<String, String> {
+    let input_path_obj = std::path::Path::new(&input_path);
+    let parent_dir = input_path_obj
+        .parent()
+        .and_then(|p| p.to_str())
+        .unwrap_or(".");
+    let filename = input_path_obj
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("output");
+    let extension = input_path_obj
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("mp4");
+
+    let output_path = format!("{}\\{}_rotated.{}", parent_dir, filename, extension);
+
+    let job = register_job(job_id)?;
+    ffmpeg::rotate_video(
+        input_path,
+        output_path.clone(),
+        params,
+        job.id().to_string(),
+        emit_job_progress(&window),
+    )
+    .await?;
+
+    Ok(output_path)
+}
+
+#[tauri::command]
+async fn overlay_watermark(
+    input_path: String,
+    params: ffmpeg::OverlayParams,
+    job_id: Option<String>,
+    window: tauri::Window,
+) -> Result<String, String> {
+    let input_path_obj = std::path::Path::new(&input_path);
+    let parent_dir = input_path_obj
+        .parent()
+        .and_then(|p| p.to_str())
+        .unwrap_or(".");
+    let filename = input_path_obj
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("output");
+    let extension = input_path_obj
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("mp4");
+
+    let output_path = format!("{}\\{}_watermarked.{}", parent_dir, filename, extension);
+
+    let job = register_job(job_id)?;
+    ffmpeg::overlay_watermark(
+        input_path,
+        output_path.clone(),
+        params,
+        job.id().to_string(),
+        emit_job_progress(&window),
+    )
+    .await?;
+
+    Ok(output_path)
+}
+
+#[tauri::command]
+async fn extract_audio(
+    input_path: String,
+    params: ffmpeg::AudioExtractParams,
+    job_id: Option<String>,
+    window: tauri::Window,
+) -> Result<String, String> {
+    let input_path_obj = std::path::Path::new(&input_path);
+    let parent_dir = input_path_obj
+        .parent()
+        .and_then(|p| p.to_str())
+        .unwrap_or(".");
+    let filename = input_path_obj
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("output");
+    let extension = ffmpeg::audio_extension(&params.format)
+        .ok_or_else(|| format!("不支持的音频格式: {}", params.format))?;
+
+    let output_path = format!("{}\\{}_audio.{}", parent_dir, filename, extension);
+
+    let job = register_job(job_id)?;
+    ffmpeg::extract_audio(
+        input_path,
+        output_path.clone(),
+        params,
+        job.id().to_string(),
+        emit_job_progress(&window),
+    )
+    .await?;
 
     Ok(output_path)
 }
 
 #[tauri::command]
-async fn convert_to_gif(input_path: String, params: ffmpeg::GifParams) -> Result<String, String> {
+async fn replace_audio(
+    input_path: String,
+    params: ffmpeg::AudioReplaceParams,
+    job_id: Option<String>,
+    window: tauri::Window,
+) -> Result<String, String> {
     let input_path_obj = std::path::Path::new(&input_path);
     let parent_dir = input_path_obj
         .parent()
@@ -169,10 +504,231 @@ async fn convert_to_gif(input_path: String, params: ffmpeg::GifParams) -> Result
         .file_stem()
         .and_then(|s| s.to_str())
         .unwrap_or("output");
+    let extension = input_path_obj
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("mp4");
 
-    let output_path = format!("{}\\{}.gif", parent_dir, filename);
+    let output_path = format!("{}\\{}_new_audio.{}", parent_dir, filename, extension);
 
-    ffmpeg::convert_to_gif(input_path, output_path.clone(), params).await?;
+    let job = register_job(job_id)?;
+    ffmpeg::replace_audio(
+        input_path,
+        output_path.clone(),
+        params,
+        job.id().to_string(),
+        emit_job_progress(&window),
+    )
+    .await?;
+
+    Ok(output_path)
+}
+
+#[tauri::command]
+async fn mute_video(
+    input_path: String,
+    job_id: Option<String>,
+    window: tauri::Window,
+) -> Result<String, String> {
+    let input_path_obj = std::path::Path::new(&input_path);
+    let parent_dir = input_path_obj
+        .parent()
+        .and_then(|p| p.to_str())
+        .unwrap_or(".");
+    let filename = input_path_obj
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("output");
+    let extension = input_path_obj
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("mp4");
+
+    let output_path = format!("{}\\{}_muted.{}", parent_dir, filename, extension);
+
+    let job = register_job(job_id)?;
+    ffmpeg::mute_video(
+        input_path,
+        output_path.clone(),
+        job.id().to_string(),
+        emit_job_progress(&window),
+    )
+    .await?;
+
+    Ok(output_path)
+}
+
+#[tauri::command]
+async fn measure_loudness(
+    input_path: String,
+    params: ffmpeg::LoudnormParams,
+    job_id: Option<String>,
+    window: tauri::Window,
+) -> Result<ffmpeg::LoudnessStats, String> {
+    let job = register_job(job_id)?;
+    ffmpeg::measure_loudness(
+        input_path,
+        params,
+        job.id().to_string(),
+        emit_job_progress(&window),
+    )
+    .await
+}
+
+#[tauri::command]
+async fn normalize_loudness(
+    input_path: String,
+    params: ffmpeg::LoudnormParams,
+    job_id: Option<String>,
+    window: tauri::Window,
+) -> Result<ffmpeg::LoudnormResult, String> {
+    let input_path_obj = std::path::Path::new(&input_path);
+    let parent_dir = input_path_obj
+        .parent()
+        .and_then(|p| p.to_str())
+        .unwrap_or(".");
+    let filename = input_path_obj
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("output");
+    let extension = input_path_obj
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("mp4");
+
+    let output_path = format!("{}\\{}_normalized.{}", parent_dir, filename, extension);
+
+    let job = register_job(job_id)?;
+    let stats = ffmpeg::normalize_loudness(
+        input_path,
+        output_path.clone(),
+        params,
+        job.id().to_string(),
+        emit_job_progress(&window),
+    )
+    .await?;
+
+    Ok(ffmpeg::LoudnormResult { output_path, stats })
+}
+
+#[tauri::command]
+async fn burn_subtitles(
+    input_path: String,
+    params: ffmpeg::BurnSubtitleParams,
+    job_id: Option<String>,
+    window: tauri::Window,
+) -> Result<String, String> {
+    let input_path_obj = std::path::Path::new(&input_path);
+    let parent_dir = input_path_obj
+        .parent()
+        .and_then(|p| p.to_str())
+        .unwrap_or(".");
+    let filename = input_path_obj
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("output");
+    let extension = input_path_obj
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("mp4");
+
+    let output_path = format!("{}\\{}_subtitled.{}", parent_dir, filename, extension);
+
+    let job = register_job(job_id)?;
+    ffmpeg::burn_subtitles(
+        input_path,
+        output_path.clone(),
+        params,
+        job.id().to_string(),
+        emit_job_progress(&window),
+    )
+    .await?;
+
+    Ok(output_path)
+}
+
+#[tauri::command]
+async fn mux_subtitles(
+    input_path: String,
+    params: ffmpeg::MuxSubtitleParams,
+    job_id: Option<String>,
+    window: tauri::Window,
+) -> Result<String, String> {
+    let input_path_obj = std::path::Path::new(&input_path);
+    let parent_dir = input_path_obj
+        .parent()
+        .and_then(|p| p.to_str())
+        .unwrap_or(".");
+    let filename = input_path_obj
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("output");
+    let extension = input_path_obj
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("mp4");
+
+    let output_path = format!("{}\\{}_with_subs.{}", parent_dir, filename, extension);
+
+    let job = register_job(job_id)?;
+    ffmpeg::mux_subtitles(
+        input_path,
+        output_path.clone(),
+        params,
+        job.id().to_string(),
+        emit_job_progress(&window),
+    )
+    .await?;
+
+    Ok(output_path)
+}
+
+#[tauri::command]
+async fn extract_subtitles(
+    input_path: String,
+    params: ffmpeg::ExtractSubtitleParams,
+    job_id: Option<String>,
+    window: tauri::Window,
+) -> Result<String, String> {
+    let input_path_obj = std::path::Path::new(&input_path);
+    let parent_dir = input_path_obj
+        .parent()
+        .and_then(|p| p.to_str())
+        .unwrap_or(".");
+    let filename = input_path_obj
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("output");
+
+    let output_path = format!("{}\\{}.{}", parent_dir, filename, params.format);
+
+    let job = register_job(job_id)?;
+    ffmpeg::extract_subtitles(
+        input_path,
+        output_path.clone(),
+        params,
+        job.id().to_string(),
+        emit_job_progress(&window),
+    )
+    .await?;
+
+    Ok(output_path)
+}
+
+#[tauri::command]
+async fn shift_srt(input_path: String, offset_ms: i64) -> Result<String, String> {
+    let input_path_obj = std::path::Path::new(&input_path);
+    let parent_dir = input_path_obj
+        .parent()
+        .and_then(|p| p.to_str())
+        .unwrap_or(".");
+    let filename = input_path_obj
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("output");
+
+    let output_path = format!("{}\\{}_shifted.srt", parent_dir, filename);
+    ffmpeg::shift_srt_file(&input_path, &output_path, offset_ms)?;
 
     Ok(output_path)
 }
@@ -181,115 +737,156 @@ async fn convert_to_gif(input_path: String, params: ffmpeg::GifParams) -> Result
 async fn process_operation_queue(
     input_path: String,
     operations: Vec<models::QueueOperation>,
+    job_id: Option<String>,
     window: tauri::Window,
 ) -> Result<String, String> {
-    use tempfile::NamedTempFile;
-
-    let mut current_path = input_path.clone();
-    let mut temp_files: Vec<String> = Vec::new();
-    let total_operations = operations.len();
-
-    let process_result = async {
-        for (index, operation) in operations.iter().enumerate() {
-            let is_last = index == total_operations - 1;
-            let output_path = if is_last {
-                let input_path_obj = std::path::Path::new(&input_path);
-                let parent_dir = input_path_obj.parent().unwrap_or(std::path::Path::new("."));
-                let filename = input_path_obj
-                    .file_stem()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("output");
-                let extension = match operation.operation_type.as_str() {
-                    "to_gif" => "gif",
-                    _ => input_path_obj
-                        .extension()
-                        .and_then(|s| s.to_str())
-                        .unwrap_or("mp4"),
-                };
-                parent_dir
-                    .join(format!("{}_final.{}", filename, extension))
-                    .to_str()
-                    .unwrap()
-                    .to_string()
-            } else {
-                let extension = match operation.operation_type.as_str() {
-                    "to_gif" => "gif",
-                    _ => input_path.split('.').last().unwrap_or("mp4"),
-                };
-                let temp_file =
-                    NamedTempFile::new().map_err(|e| format!("创建临时文件失败: {}", e))?;
-                let temp_path_with_ext = temp_file.path().with_extension(extension);
-                let temp_path_kept = temp_file
-                    .into_temp_path()
-                    .keep()
-                    .map_err(|e| format!("保持临时文件失败: {}", e))?;
-                std::fs::rename(&temp_path_kept, &temp_path_with_ext)
-                    .map_err(|e| format!("重命名临时文件失败: {}", e))?;
-                temp_path_with_ext.to_str().unwrap().to_string()
-            };
+    // 整个队列共用一个任务ID，取消时终止当前步骤并跳过后续步骤
+    let job = register_job(job_id)?;
+
+    let mut outputs = media_jobs::run_operations(
+        &job,
+        &input_path,
+        &operations,
+        None,
+        emit_job_progress(&window),
+    )
+    .await?
+    .outputs;
+
+    Ok(outputs.pop().unwrap_or(input_path))
+}
 
-            temp_files.push(output_path.clone());
-
-            let window_clone = window.clone();
-            match operation.operation_type.as_str() {
-                "compress" => {
-                    let params = serde_json::from_value(operation.params.clone())
-                        .map_err(|e| format!("解析压缩参数失败: {}", e))?;
-                    ffmpeg::compress_video(
-                        current_path.clone(),
-                        output_path.clone(),
-                        params,
-                        move |progress| {
-                            let _ = window_clone.emit("progress", progress);
-                        },
-                    )
-                    .await?;
-                }
-                "speed" => {
-                    let params = serde_json::from_value(operation.params.clone())
-                        .map_err(|e| format!("解析变速参数失败: {}", e))?;
-                    ffmpeg::change_video_speed(current_path.clone(), output_path.clone(), params)
-                        .await?;
-                }
-                "trim" => {
-                    let params = serde_json::from_value(operation.params.clone())
-                        .map_err(|e| format!("解析截断参数失败: {}", e))?;
-                    ffmpeg::trim_video(current_path.clone(), output_path.clone(), params).await?;
-                }
-                "to_gif" => {
-                    let params = serde_json::from_value(operation.params.clone())
-                        .map_err(|e| format!("解析GIF参数失败: {}", e))?;
-                    ffmpeg::convert_to_gif(current_path.clone(), output_path.clone(), params)
-                        .await?;
-                }
-                "extract_frames" => {
-                    continue;
-                }
-                _ => {
-                    return Err(format!("未知的操作类型: {}", operation.operation_type));
-                }
-            }
+// ============================================================
+// Media Job Queue Commands
+// ============================================================
 
-            current_path = output_path;
-        }
+type MediaQueue<'a> = tauri::State<'a, Arc<media_jobs::MediaJobQueue>>;
 
-        for temp_file in temp_files.iter().take(temp_files.len() - 1) {
-            let _ = std::fs::remove_file(temp_file);
-        }
+#[tauri::command]
+async fn list_media_jobs(queue: MediaQueue<'_>) -> Result<Vec<media_jobs::MediaJob>, String> {
+    Ok(queue.list_jobs())
+}
 
-        Ok(current_path)
-    }
-    .await;
+#[tauri::command]
+async fn get_media_queue_settings(
+    queue: MediaQueue<'_>,
+) -> Result<media_jobs::MediaQueueSettings, String> {
+    Ok(queue.settings())
+}
+
+#[tauri::command]
+async fn update_media_queue_settings(
+    settings: media_jobs::MediaQueueSettings,
+    queue: MediaQueue<'_>,
+) -> Result<media_jobs::MediaQueueSettings, String> {
+    queue.update_settings(settings)
+}
+
+#[tauri::command]
+async fn submit_media_job(
+    request: media_jobs::SubmitJobRequest,
+    queue: MediaQueue<'_>,
+) -> Result<media_jobs::MediaJob, String> {
+    queue.submit(request)
+}
 
-    match process_result {
-        Ok(final_path) => Ok(final_path),
-        Err(error) => {
-            for temp_file in &temp_files {
-                let _ = std::fs::remove_file(temp_file);
+#[tauri::command]
+async fn submit_media_batch(
+    request: media_jobs::SubmitBatchRequest,
+    queue: MediaQueue<'_>,
+) -> Result<Vec<media_jobs::MediaJob>, String> {
+    queue.submit_batch(request)
+}
+
+#[tauri::command]
+async fn pause_media_job(
+    job_id: String,
+    queue: MediaQueue<'_>,
+) -> Result<media_jobs::MediaJob, String> {
+    queue.pause_job(&job_id)
+}
+
+#[tauri::command]
+async fn resume_media_job(
+    job_id: String,
+    queue: MediaQueue<'_>,
+) -> Result<media_jobs::MediaJob, String> {
+    queue.resume_job(&job_id)
+}
+
+#[tauri::command]
+async fn retry_media_job(
+    job_id: String,
+    queue: MediaQueue<'_>,
+) -> Result<media_jobs::MediaJob, String> {
+    queue.retry_job(&job_id)
+}
+
+#[tauri::command]
+async fn cancel_media_job(
+    job_id: String,
+    queue: MediaQueue<'_>,
+) -> Result<media_jobs::MediaJob, String> {
+    queue.cancel_job(&job_id)
+}
+
+#[tauri::command]
+async fn set_media_job_priority(
+    job_id: String,
+    priority: i32,
+    queue: MediaQueue<'_>,
+) -> Result<media_jobs::MediaJob, String> {
+    queue.set_priority(&job_id, priority)
+}
+
+#[tauri::command]
+async fn remove_media_job(job_id: String, queue: MediaQueue<'_>) -> Result<(), String> {
+    queue.remove_job(&job_id)
+}
+
+#[tauri::command]
+async fn clear_finished_media_jobs(queue: MediaQueue<'_>) -> Result<Vec<String>, String> {
+    Ok(queue.clear_finished())
+}
+
+/// 将队列事件转发给前端，任务结束时发送系统通知
+fn media_job_listener(app: tauri::AppHandle) -> media_jobs::JobListener {
+    use media_jobs::{MediaJobEvent, MediaJobStatus};
+    use tauri_plugin_notification::NotificationExt;
+
+    Arc::new(move |event: MediaJobEvent| match event {
+        MediaJobEvent::Updated(job) => {
+            let _ = app.emit("media-job-updated", &job);
+        }
+        MediaJobEvent::Finished(job) => {
+            let _ = app.emit("media-job-updated", &job);
+
+            let notify = app
+                .try_state::<Arc<media_jobs::MediaJobQueue>>()
+                .is_some_and(|queue| queue.settings().notify_on_complete);
+            if !notify {
+                return;
+            }
+            let filename = std::path::Path::new(&job.input_path)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or(&job.input_path);
+            let (title, body) = if job.status == MediaJobStatus::Completed {
+                ("媒体任务已完成", filename.to_string())
+            } else {
+                (
+                    "媒体任务失败",
+                    format!("{}: {}", filename, job.error.as_deref().unwrap_or("")),
+                )
+            };
+            if let Err(e) = app.notification().builder().title(title).body(body).show() {
+                log::warn!("发送媒体任务通知失败: {e}");
             }
-            Err(error)
         }
-    }
+        MediaJobEvent::Removed(job_ids) => {
+            let _ = app.emit("media-job-removed", &job_ids);
+        }
+    })
 }
 
 #[tauri::command]
@@ -661,7 +1258,7 @@ async fn cleanup_before_exit(app_handle: &tauri::AppHandle) {
 
 async fn restore_proxy_state_on_startup(state: &cc_switch::store::AppState) {
     let mut apps_to_restore = Vec::new();
-    for app_type in ["claude", "codex", "gemini"] {
+    for app_type in ["claude", "codex", "gemini", "opencode"] {
         if let Ok(config) = state.db.get_proxy_config_for_app(app_type).await {
             if config.enabled {
                 apps_to_restore.push(app_type);
@@ -763,6 +1360,7 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::new().build())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .setup(|app| {
             // Initialize app config directory override
@@ -1017,6 +1615,13 @@ pub fn run() {
             // Initialize NoteWindowManager for sticky notes
             app.manage(sticky_notes::NoteWindowManager::new());
 
+            // Restore the media job queue and resume unfinished jobs
+            let media_queue =
+                media_jobs::MediaJobQueue::load(cc_switch::config::get_media_jobs_path());
+            media_queue.set_listener(media_job_listener(app.handle().clone()));
+            app.manage(media_queue.clone());
+            media_queue.start();
+
             // Register sticky notes global shortcuts
             if let Err(e) = sticky_notes::register_global_shortcuts(app.handle()) {
                 log::warn!("Failed to register sticky notes shortcuts: {}", e);
@@ -1064,6 +1669,31 @@ pub fn run() {
                 restore_proxy_state_on_startup(&state).await;
             });
 
+            // Watch the folder sync directory
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                cc_switch::services::folder_sync::run_folder_sync_watcher(app_handle).await;
+            });
+
+            // Scheduled database snapshots
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                cc_switch::services::snapshot::run_snapshot_scheduler(app_handle).await;
+            });
+
+            // Periodic endpoint speed tests with automatic switching
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                cc_switch::services::endpoint_auto_select::run_endpoint_auto_select(app_handle)
+                    .await;
+            });
+
+            // Schedule- and quota-based provider switching policies
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                cc_switch::services::auto_switch::run_auto_switch_scheduler(app_handle).await;
+            });
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -1072,14 +1702,49 @@ pub fn run() {
             // ============================================================
             check_ffmpeg,
             download_ffmpeg,
+            get_managed_ffmpeg_status,
+            list_ffmpeg_versions,
+            install_ffmpeg_version,
+            select_ffmpeg_version,
+            rollback_ffmpeg_version,
+            remove_ffmpeg_version,
+            set_custom_ffmpeg_path,
+            get_ffmpeg_capability_report,
             generate_thumbnails,
             load_video,
             compress_video_command,
+            get_encoder_capabilities,
             change_video_speed,
             extract_frames,
             trim_video,
             convert_to_gif,
+            concat_videos,
+            crop_video,
+            rotate_video,
+            overlay_watermark,
+            extract_audio,
+            replace_audio,
+            mute_video,
+            measure_loudness,
+            normalize_loudness,
+            burn_subtitles,
+            mux_subtitles,
+            extract_subtitles,
+            shift_srt,
             process_operation_queue,
+            cancel_job,
+            list_media_jobs,
+            get_media_queue_settings,
+            update_media_queue_settings,
+            submit_media_job,
+            submit_media_batch,
+            pause_media_job,
+            resume_media_job,
+            retry_media_job,
+            cancel_media_job,
+            set_media_job_priority,
+            remove_media_job,
+            clear_finished_media_jobs,
             get_image_info,
             convert_image,
             batch_convert_images,
@@ -1182,16 +1847,52 @@ pub fn run() {
             cc_switch::commands::enable_prompt,
             cc_switch::commands::import_prompt_from_file,
             cc_switch::commands::get_current_prompt_file_content,
+            cc_switch::commands::preview_prompt_template,
+            cc_switch::commands::get_prompt_fragments,
+            cc_switch::commands::upsert_prompt_fragment,
+            cc_switch::commands::delete_prompt_fragment,
+            cc_switch::commands::get_prompt_variables,
+            cc_switch::commands::set_prompt_variable,
+            cc_switch::commands::delete_prompt_variable,
+            cc_switch::commands::list_prompt_versions,
+            cc_switch::commands::diff_prompt_versions,
+            cc_switch::commands::restore_prompt_version,
             cc_switch::commands::test_api_endpoints,
             cc_switch::commands::get_custom_endpoints,
             cc_switch::commands::add_custom_endpoint,
             cc_switch::commands::remove_custom_endpoint,
             cc_switch::commands::update_endpoint_last_used,
+            cc_switch::commands::get_endpoint_auto_select_config,
+            cc_switch::commands::set_endpoint_auto_select_config,
+            cc_switch::commands::probe_provider_endpoints,
+            cc_switch::commands::run_endpoint_auto_select_now,
+            cc_switch::commands::get_endpoint_switch_history,
+            cc_switch::commands::get_auto_switch_config,
+            cc_switch::commands::set_auto_switch_config,
+            cc_switch::commands::preview_auto_switch_schedule,
+            cc_switch::commands::run_auto_switch_quota_check_now,
+            cc_switch::commands::get_auto_switch_history,
             cc_switch::commands::get_app_config_dir_override,
             cc_switch::commands::set_app_config_dir_override,
             cc_switch::commands::update_providers_sort_order,
             cc_switch::commands::export_config_to_file,
             cc_switch::commands::import_config_from_file,
+            cc_switch::commands::export_config_bundle,
+            cc_switch::commands::read_config_bundle_manifest,
+            cc_switch::commands::inspect_config_bundle,
+            cc_switch::commands::import_config_bundle,
+            cc_switch::commands::get_folder_sync_config,
+            cc_switch::commands::set_folder_sync_config,
+            cc_switch::commands::run_folder_sync,
+            cc_switch::commands::list_folder_sync_conflicts,
+            cc_switch::commands::dismiss_folder_sync_conflict,
+            cc_switch::commands::get_db_snapshot_schedule,
+            cc_switch::commands::set_db_snapshot_schedule,
+            cc_switch::commands::list_db_snapshots,
+            cc_switch::commands::create_db_snapshot,
+            cc_switch::commands::inspect_db_snapshot,
+            cc_switch::commands::diff_db_snapshot,
+            cc_switch::commands::restore_db_snapshot,
             cc_switch::commands::save_file_dialog,
             cc_switch::commands::open_file_dialog,
             cc_switch::commands::sync_current_providers_live,
@@ -1199,10 +1900,20 @@ pub fn run() {
             cc_switch::commands::merge_deeplink_config,
             cc_switch::commands::import_from_deeplink,
             cc_switch::commands::import_from_deeplink_unified,
+            cc_switch::commands::preview_deeplink_bundle,
+            cc_switch::commands::import_deeplink_bundle,
+            cc_switch::commands::get_trusted_bundle_publishers,
+            cc_switch::commands::add_trusted_bundle_publisher,
+            cc_switch::commands::remove_trusted_bundle_publisher,
             update_tray_menu,
             cc_switch::commands::check_env_conflicts,
             cc_switch::commands::delete_env_vars,
             cc_switch::commands::restore_env_backup,
+            cc_switch::commands::scan_env_rc_files,
+            cc_switch::commands::preview_env_fix,
+            cc_switch::commands::preview_env_fix_restore,
+            cc_switch::commands::apply_env_fix,
+            cc_switch::commands::list_env_fixes,
             cc_switch::commands::get_installed_skills,
             cc_switch::commands::get_installed_skill_file_tree,
             cc_switch::commands::read_installed_skill_file,
@@ -1227,6 +1938,14 @@ pub fn run() {
             cc_switch::commands::remove_skill_repo,
             cc_switch::commands::check_skill_updates,
             cc_switch::commands::clear_skill_cache,
+            cc_switch::commands::set_skill_pin,
+            cc_switch::commands::update_installed_skill,
+            cc_switch::commands::list_skill_versions,
+            cc_switch::commands::rollback_skill,
+            cc_switch::commands::export_skill_lockfile,
+            cc_switch::commands::apply_skill_lockfile,
+            cc_switch::commands::scan_skill_before_install,
+            cc_switch::commands::scan_installed_skill,
             cc_switch::commands::set_auto_launch,
             cc_switch::commands::get_auto_launch_status,
             cc_switch::commands::start_proxy_server,
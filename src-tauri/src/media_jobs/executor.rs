@@ -0,0 +1,416 @@
+use std::path::{Path, PathBuf};
+
+use super::types::JobOutput;
+use crate::ffmpeg::{self, JobGuard, JobProgress, JOB_CANCELLED};
+use crate::image_converter;
+use crate::models::QueueOperation;
+use crate::watermark_remover;
+
+/// 依次执行操作链
+///
+/// 视频操作的中间结果写入临时文件，最后一步输出为 `{输入文件名}_final.{扩展名}`；
+/// 提取帧和提取字幕只产生附带文件，不改变后续步骤的输入。
+///
+/// # Returns
+/// 返回生成的全部文件（最终文件在最后）以及各步骤的附加结果
+pub async fn run_operations(
+    job: &JobGuard,
+    input_path: &str,
+    operations: &[QueueOperation],
+    output_dir: Option<&str>,
+    on_progress: impl Fn(JobProgress) + Clone + Send + 'static,
+) -> Result<JobOutput, String> {
+    if operations.is_empty() {
+        return Err("操作列表为空".to_string());
+    }
+
+    let input = Path::new(input_path);
+    let target_dir = match output_dir {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => input.parent().unwrap_or(Path::new(".")).to_path_buf(),
+    };
+    let stem = input
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("output")
+        .to_string();
+    std::fs::create_dir_all(&target_dir).map_err(|e| format!("创建输出目录失败: {}", e))?;
+
+    let total_operations = operations.len();
+    let mut current_path = input_path.to_string();
+    let mut temp_files: Vec<String> = Vec::new();
+    let mut outputs: Vec<String> = Vec::new();
+    let mut operation_results = vec![serde_json::Value::Null; total_operations];
+
+    let process_result = async {
+        for (index, operation) in operations.iter().enumerate() {
+            if job.is_cancelled() {
+                return Err(JOB_CANCELLED.to_string());
+            }
+
+            let is_last = index == total_operations - 1;
+            // 单步进度换算为整个操作链的进度
+            let emit = on_progress.clone();
+            let step_progress = move |mut progress: JobProgress| {
+                progress.percent =
+                    (index as f64 * 100.0 + progress.percent) / total_operations as f64;
+                emit(progress);
+            };
+            let step_job_id = job.id().to_string();
+
+            match operation.operation_type.as_str() {
+                "compress" | "speed" | "trim" | "to_gif" | "concat" | "crop" | "rotate"
+                | "overlay" | "extract_audio" | "replace_audio" | "mute" | "loudnorm"
+                | "burn_subtitles" | "mux_subtitles" => {
+                    let extension = match operation.operation_type.as_str() {
+                        "to_gif" => operation
+                            .params
+                            .get("format")
+                            .and_then(|f| f.as_str())
+                            .and_then(ffmpeg::animation_extension)
+                            .unwrap_or("gif")
+                            .to_string(),
+                        "extract_audio" => operation
+                            .params
+                            .get("format")
+                            .and_then(|f| f.as_str())
+                            .and_then(ffmpeg::audio_extension)
+                            .unwrap_or("m4a")
+                            .to_string(),
+                        _ => file_extension(&current_path).unwrap_or_else(|| "mp4".to_string()),
+                    };
+                    let output_path = if is_last {
+                        final_output_path(&target_dir, &stem, &extension)
+                    } else {
+                        let temp_path = temp_output_path(&extension)?;
+                        temp_files.push(temp_path.clone());
+                        temp_path
+                    };
+                    if let Some(result) = run_video_operation(
+                        operation,
+                        current_path.clone(),
+                        output_path.clone(),
+                        step_job_id,
+                        step_progress,
+                    )
+                    .await?
+                    {
+                        operation_results[index] = result;
+                    }
+                    current_path = output_path;
+                }
+                "extract_frames" => {
+                    let mut params: ffmpeg::ExtractParams =
+                        serde_json::from_value(operation.params.clone())
+                            .map_err(|e| format!("解析提取帧参数失败: {}", e))?;
+                    if params.output_dir.is_empty() {
+                        params.output_dir = target_dir
+                            .join(format!("{}_frames", stem))
+                            .to_string_lossy()
+                            .to_string();
+                    }
+                    let frames = ffmpeg::extract_frames(
+                        current_path.clone(),
+                        params,
+                        step_job_id,
+                        step_progress,
+                    )
+                    .await?;
+                    outputs.extend(frames);
+                }
+                "extract_subtitles" => {
+                    let params: ffmpeg::ExtractSubtitleParams =
+                        serde_json::from_value(operation.params.clone())
+                            .map_err(|e| format!("解析提取字幕参数失败: {}", e))?;
+                    let output_path = target_dir
+                        .join(format!("{}.{}", stem, params.format))
+                        .to_string_lossy()
+                        .to_string();
+                    ffmpeg::extract_subtitles(
+                        current_path.clone(),
+                        output_path.clone(),
+                        params,
+                        step_job_id,
+                        step_progress,
+                    )
+                    .await?;
+                    outputs.push(output_path);
+                }
+                "image_convert" | "remove_watermark" | "remove_background" => {
+                    let produced = run_image_operation(operation, current_path.clone()).await?;
+                    let extension = file_extension(&produced).unwrap_or_else(|| "png".to_string());
+                    // 图片操作输出在其输入文件旁，最后一步不在输出目录时移动过去
+                    let in_target_dir = Path::new(&produced).parent() == Some(&target_dir);
+                    current_path = if is_last && !in_target_dir {
+                        let final_path = final_output_path(&target_dir, &stem, &extension);
+                        move_file(&produced, &final_path)?;
+                        final_path
+                    } else {
+                        if !is_last {
+                            temp_files.push(produced.clone());
+                        }
+                        produced
+                    };
+                    step_progress(step_done_progress(job.id()));
+                }
+                _ => {
+                    return Err(format!("未知的操作类型: {}", operation.operation_type));
+                }
+            }
+        }
+
+        // 最后一步为提取帧或字幕时，上一步的中间结果即为最终文件
+        if temp_files.contains(&current_path) {
+            let extension = file_extension(&current_path).unwrap_or_else(|| "mp4".to_string());
+            let final_path = final_output_path(&target_dir, &stem, &extension);
+            move_file(&current_path, &final_path)?;
+            current_path = final_path;
+        }
+        if current_path != input_path {
+            outputs.push(current_path.clone());
+        }
+
+        Ok(())
+    }
+    .await;
+
+    for temp_file in &temp_files {
+        let _ = std::fs::remove_file(temp_file);
+    }
+
+    process_result.map(|_| JobOutput {
+        outputs,
+        operation_results,
+    })
+}
+
+/// 执行单个 FFmpeg 视频操作，有附加结果（如响度统计）时一并返回
+async fn run_video_operation(
+    operation: &QueueOperation,
+    input_path: String,
+    output_path: String,
+    job_id: String,
+    on_progress: impl Fn(JobProgress) + Send + 'static,
+) -> Result<Option<serde_json::Value>, String> {
+    let result = match operation.operation_type.as_str() {
+        "compress" => {
+            let params = serde_json::from_value(operation.params.clone())
+                .map_err(|e| format!("解析压缩参数失败: {}", e))?;
+            ffmpeg::compress_video(input_path, output_path, params, job_id, on_progress).await
+        }
+        "speed" => {
+            let params = serde_json::from_value(operation.params.clone())
+                .map_err(|e| format!("解析变速参数失败: {}", e))?;
+            ffmpeg::change_video_speed(input_path, output_path, params, job_id, on_progress).await
+        }
+        "trim" => {
+            let params = serde_json::from_value(operation.params.clone())
+                .map_err(|e| format!("解析截断参数失败: {}", e))?;
+            ffmpeg::trim_video(input_path, output_path, params, job_id, on_progress).await
+        }
+        "to_gif" => {
+            let params = serde_json::from_value(operation.params.clone())
+                .map_err(|e| format!("解析GIF参数失败: {}", e))?;
+            ffmpeg::convert_to_gif(input_path, output_path, params, job_id, on_progress).await
+        }
+        "concat" => {
+            let params = serde_json::from_value(operation.params.clone())
+                .map_err(|e| format!("解析拼接参数失败: {}", e))?;
+            ffmpeg::concat_videos(input_path, output_path, params, job_id, on_progress).await
+        }
+        "crop" => {
+            let params = serde_json::from_value(operation.params.clone())
+                .map_err(|e| format!("解析裁剪参数失败: {}", e))?;
+            ffmpeg::crop_video(input_path, output_path, params, job_id, on_progress).await
+        }
+        "rotate" => {
+            let params = serde_json::from_value(operation.params.clone())
+                .map_err(|e| format!("解析旋转参数失败: {}", e))?;
+            ffmpeg::rotate_video(input_path, output_path, params, job_id, on_progress).await
+        }
+        "overlay" => {
+            let params = serde_json::from_value(operation.params.clone())
+                .map_err(|e| format!("解析水印参数失败: {}", e))?;
+            ffmpeg::overlay_watermark(input_path, output_path, params, job_id, on_progress).await
+        }
+        "extract_audio" => {
+            let params = serde_json::from_value(operation.params.clone())
+                .map_err(|e| format!("解析提取音频参数失败: {}", e))?;
+            ffmpeg::extract_audio(input_path, output_path, params, job_id, on_progress).await
+        }
+        "replace_audio" => {
+            let params = serde_json::from_value(operation.params.clone())
+                .map_err(|e| format!("解析替换音频参数失败: {}", e))?;
+            ffmpeg::replace_audio(input_path, output_path, params, job_id, on_progress).await
+        }
+        "mute" => ffmpeg::mute_video(input_path, output_path, job_id, on_progress).await,
+        "loudnorm" => {
+            let params = serde_json::from_value(operation.params.clone())
+                .map_err(|e| format!("解析响度标准化参数失败: {}", e))?;
+            let stats =
+                ffmpeg::normalize_loudness(input_path, output_path, params, job_id, on_progress)
+                    .await?;
+            return serde_json::to_value(stats)
+                .map(Some)
+                .map_err(|e| format!("序列化响度统计失败: {}", e));
+        }
+        "burn_subtitles" => {
+            let params = serde_json::from_value(operation.params.clone())
+                .map_err(|e| format!("解析烧录字幕参数失败: {}", e))?;
+            ffmpeg::burn_subtitles(input_path, output_path, params, job_id, on_progress).await
+        }
+        "mux_subtitles" => {
+            let params = serde_json::from_value(operation.params.clone())
+                .map_err(|e| format!("解析封装字幕参数失败: {}", e))?;
+            ffmpeg::mux_subtitles(input_path, output_path, params, job_id, on_progress).await
+        }
+        other => Err(format!("未知的操作类型: {}", other)),
+    };
+    result.map(|_| None)
+}
+
+/// 执行单个图片操作，返回生成的文件路径（与输入文件同目录）
+async fn run_image_operation(
+    operation: &QueueOperation,
+    input_path: String,
+) -> Result<String, String> {
+    match operation.operation_type.as_str() {
+        "image_convert" => {
+            let params = serde_json::from_value(operation.params.clone())
+                .map_err(|e| format!("解析图片转换参数失败: {}", e))?;
+            image_converter::convert_image(input_path, params)
+                .await
+                .map(|r| r.output_path)
+        }
+        "remove_watermark" => {
+            let params = serde_json::from_value(operation.params.clone())
+                .map_err(|e| format!("解析去水印参数失败: {}", e))?;
+            watermark_remover::remove_watermark(input_path, params)
+                .await
+                .map(|r| r.output_path)
+        }
+        #[cfg(feature = "background-remover")]
+        "remove_background" => {
+            let params = serde_json::from_value(operation.params.clone())
+                .map_err(|e| format!("解析去背景参数失败: {}", e))?;
+            crate::background_remover::remove_background(input_path, params)
+                .await
+                .map(|r| r.output_path)
+        }
+        other => Err(format!("不支持的操作类型: {}", other)),
+    }
+}
+
+/// 图片操作没有细粒度进度，完成时按整步上报
+fn step_done_progress(job_id: &str) -> JobProgress {
+    JobProgress {
+        job_id: job_id.to_string(),
+        percent: 100.0,
+        out_time: 0.0,
+        duration: 0.0,
+        speed: None,
+        eta: None,
+        fps: None,
+        elapsed: 0.0,
+    }
+}
+
+fn file_extension(path: &str) -> Option<String> {
+    Path::new(path)
+        .extension()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_string())
+}
+
+fn final_output_path(target_dir: &Path, stem: &str, extension: &str) -> String {
+    target_dir
+        .join(format!("{}_final.{}", stem, extension))
+        .to_string_lossy()
+        .to_string()
+}
+
+/// 创建带扩展名的临时文件，返回其路径
+fn temp_output_path(extension: &str) -> Result<String, String> {
+    let temp_file =
+        tempfile::NamedTempFile::new().map_err(|e| format!("创建临时文件失败: {}", e))?;
+    let temp_path_with_ext = temp_file.path().with_extension(extension);
+    let temp_path_kept = temp_file
+        .into_temp_path()
+        .keep()
+        .map_err(|e| format!("保持临时文件失败: {}", e))?;
+    std::fs::rename(&temp_path_kept, &temp_path_with_ext)
+        .map_err(|e| format!("重命名临时文件失败: {}", e))?;
+    Ok(temp_path_with_ext.to_string_lossy().to_string())
+}
+
+/// 移动文件；临时目录与目标目录不在同一分区时回退为复制后删除
+fn move_file(from: &str, to: &str) -> Result<(), String> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    std::fs::copy(from, to).map_err(|e| format!("移动输出文件失败: {}", e))?;
+    let _ = std::fs::remove_file(from);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn operation(operation_type: &str, params: serde_json::Value) -> QueueOperation {
+        QueueOperation {
+            operation_type: operation_type.to_string(),
+            name: operation_type.to_string(),
+            params,
+        }
+    }
+
+    #[tokio::test]
+    async fn empty_operations_is_error() {
+        let job = JobGuard::register(&ffmpeg::new_job_id()).unwrap();
+        let result = run_operations(&job, "/tmp/a.mp4", &[], None, |_| {}).await;
+        assert_eq!(result.unwrap_err(), "操作列表为空");
+    }
+
+    #[tokio::test]
+    async fn unknown_operation_is_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("a.mp4");
+        std::fs::write(&input, b"x").unwrap();
+
+        let job = JobGuard::register(&ffmpeg::new_job_id()).unwrap();
+        let result = run_operations(
+            &job,
+            input.to_str().unwrap(),
+            &[operation("explode", json!({}))],
+            None,
+            |_| {},
+        )
+        .await;
+        assert_eq!(result.unwrap_err(), "未知的操作类型: explode");
+    }
+
+    #[tokio::test]
+    async fn cancelled_job_stops_before_first_step() {
+        let dir = tempfile::tempdir().unwrap();
+        let job = JobGuard::register(&ffmpeg::new_job_id()).unwrap();
+        ffmpeg::cancel_job(job.id()).unwrap();
+
+        let result = run_operations(
+            &job,
+            "/tmp/a.mp4",
+            &[operation("compress", json!({}))],
+            dir.path().join("out").to_str(),
+            |_| {},
+        )
+        .await;
+        assert_eq!(result.unwrap_err(), JOB_CANCELLED);
+    }
+
+    #[test]
+    fn final_output_path_uses_input_stem() {
+        let path = final_output_path(Path::new("/videos"), "clip", "gif");
+        assert_eq!(Path::new(&path), Path::new("/videos/clip_final.gif"));
+    }
+}
@@ -0,0 +1,16 @@
+//! 媒体任务队列
+//!
+//! 持久化的后台任务队列，视频（FFmpeg）和图片操作都以操作链的形式提交，
+//! 支持并发数、优先级、暂停/恢复/重试，以及按文件夹批量提交。
+
+pub mod executor;
+pub mod queue;
+mod store;
+pub mod types;
+
+pub use executor::run_operations;
+pub use queue::{JobListener, JobRunner, MediaJobQueue, ProgressFn, MAX_CONCURRENCY};
+pub use types::{
+    JobOutput, MediaJob, MediaJobEvent, MediaJobStatus, MediaQueueSettings, SubmitBatchRequest,
+    SubmitJobRequest,
+};
@@ -0,0 +1,1008 @@
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use futures::future::BoxFuture;
+
+use super::executor;
+use super::store::{self, PersistedQueue};
+use super::types::{
+    JobOutput, MediaJob, MediaJobEvent, MediaJobStatus, MediaQueueSettings, SubmitBatchRequest,
+    SubmitJobRequest,
+};
+use crate::ffmpeg::{self, JobGuard, JobProgress, JOB_CANCELLED};
+
+/// 同时执行任务数的上限
+pub const MAX_CONCURRENCY: usize = 8;
+
+/// 进度回调
+pub type ProgressFn = Arc<dyn Fn(JobProgress) + Send + Sync>;
+
+/// 任务执行器：执行任务并返回生成的文件及各步骤的附加结果
+pub type JobRunner = Arc<
+    dyn Fn(MediaJob, ProgressFn) -> BoxFuture<'static, Result<JobOutput, String>> + Send + Sync,
+>;
+
+/// 队列事件监听器
+pub type JobListener = Arc<dyn Fn(MediaJobEvent) + Send + Sync>;
+
+struct QueueState {
+    settings: MediaQueueSettings,
+    jobs: Vec<MediaJob>,
+    /// 执行中的任务ID（暂停或取消后，直到执行器真正退出才移除）
+    running: HashSet<String>,
+}
+
+/// 持久化的媒体任务队列
+///
+/// 任务按优先级（高者优先）和提交顺序调度，最多同时执行 `concurrency` 个。
+/// 每次状态变化都会写入队列文件，应用重启后未完成的任务重新排队。
+pub struct MediaJobQueue {
+    state: Mutex<QueueState>,
+    store_path: Option<PathBuf>,
+    runner: JobRunner,
+    listener: Mutex<Option<JobListener>>,
+}
+
+impl MediaJobQueue {
+    /// 从队列文件恢复，使用默认执行器（FFmpeg 及图片操作）
+    pub fn load(store_path: PathBuf) -> Arc<Self> {
+        Self::with_runner(Some(store_path), default_runner())
+    }
+
+    /// 使用指定执行器创建队列；`store_path` 为空时不持久化
+    pub fn with_runner(store_path: Option<PathBuf>, runner: JobRunner) -> Arc<Self> {
+        let PersistedQueue { settings, mut jobs } =
+            store_path.as_deref().map(store::load).unwrap_or_default();
+
+        // 上次退出时仍在执行的任务已被中断，重新排队
+        for job in jobs
+            .iter_mut()
+            .filter(|j| j.status == MediaJobStatus::Running)
+        {
+            job.status = MediaJobStatus::Queued;
+            job.progress = 0.0;
+            job.started_at = None;
+        }
+
+        Arc::new(Self {
+            state: Mutex::new(QueueState {
+                settings,
+                jobs,
+                running: HashSet::new(),
+            }),
+            store_path,
+            runner,
+            listener: Mutex::new(None),
+        })
+    }
+
+    /// 设置事件监听器
+    pub fn set_listener(&self, listener: JobListener) {
+        *self.listener.lock().unwrap_or_else(|e| e.into_inner()) = Some(listener);
+    }
+
+    /// 开始调度（应用启动时调用一次）
+    pub fn start(self: &Arc<Self>) {
+        self.schedule();
+    }
+
+    /// 全部任务（按提交顺序）
+    pub fn list_jobs(&self) -> Vec<MediaJob> {
+        self.lock().jobs.clone()
+    }
+
+    pub fn get_job(&self, job_id: &str) -> Option<MediaJob> {
+        self.lock().jobs.iter().find(|j| j.id == job_id).cloned()
+    }
+
+    pub fn settings(&self) -> MediaQueueSettings {
+        self.lock().settings.clone()
+    }
+
+    /// 更新队列设置；取消暂停或提高并发数后立即调度
+    pub fn update_settings(
+        self: &Arc<Self>,
+        settings: MediaQueueSettings,
+    ) -> Result<MediaQueueSettings, String> {
+        if settings.concurrency == 0 || settings.concurrency > MAX_CONCURRENCY {
+            return Err(format!("并发数必须在 1-{} 之间", MAX_CONCURRENCY));
+        }
+        {
+            let mut state = self.lock();
+            state.settings = settings.clone();
+            self.persist(&state);
+        }
+        self.schedule();
+        Ok(settings)
+    }
+
+    /// 提交单个任务
+    pub fn submit(self: &Arc<Self>, request: SubmitJobRequest) -> Result<MediaJob, String> {
+        if !Path::new(&request.input_path).exists() {
+            return Err(format!("文件不存在: {}", request.input_path));
+        }
+        let job = new_job(
+            request.input_path,
+            request.operations,
+            request.output_dir,
+            request.priority,
+            None,
+        )?;
+        self.enqueue(vec![job.clone()]);
+        Ok(job)
+    }
+
+    /// 提交文件夹中所有匹配的文件，每个文件一个任务
+    pub fn submit_batch(
+        self: &Arc<Self>,
+        request: SubmitBatchRequest,
+    ) -> Result<Vec<MediaJob>, String> {
+        let inputs = collect_inputs(
+            Path::new(&request.folder),
+            &request.extensions,
+            request.recursive,
+        )?;
+        if inputs.is_empty() {
+            return Err("文件夹中没有匹配的文件".to_string());
+        }
+
+        let folder = Path::new(&request.folder);
+        let batch_id = uuid::Uuid::new_v4().to_string();
+        let jobs = inputs
+            .into_iter()
+            .map(|input| {
+                let output_dir = batch_output_dir(request.output_dir.as_deref(), folder, &input);
+                new_job(
+                    input.to_string_lossy().to_string(),
+                    request.operations.clone(),
+                    output_dir,
+                    request.priority,
+                    Some(batch_id.clone()),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.enqueue(jobs.clone());
+        Ok(jobs)
+    }
+
+    /// 暂停任务；正在执行的任务会被终止，恢复后从头执行
+    pub fn pause_job(self: &Arc<Self>, job_id: &str) -> Result<MediaJob, String> {
+        let (job, was_running) = self.transition(job_id, |job| match job.status {
+            MediaJobStatus::Queued | MediaJobStatus::Running => {
+                job.status = MediaJobStatus::Paused;
+                Ok(())
+            }
+            _ => Err("只能暂停等待中或执行中的任务".to_string()),
+        })?;
+        if was_running {
+            let _ = ffmpeg::cancel_job(job_id);
+        }
+        Ok(job)
+    }
+
+    /// 恢复已暂停的任务
+    pub fn resume_job(self: &Arc<Self>, job_id: &str) -> Result<MediaJob, String> {
+        let (job, _) = self.transition(job_id, |job| match job.status {
+            MediaJobStatus::Paused => {
+                job.status = MediaJobStatus::Queued;
+                job.progress = 0.0;
+                Ok(())
+            }
+            _ => Err("只能恢复已暂停的任务".to_string()),
+        })?;
+        self.schedule();
+        Ok(job)
+    }
+
+    /// 重试失败或已取消的任务
+    pub fn retry_job(self: &Arc<Self>, job_id: &str) -> Result<MediaJob, String> {
+        let (job, _) = self.transition(job_id, |job| match job.status {
+            MediaJobStatus::Failed | MediaJobStatus::Cancelled => {
+                job.status = MediaJobStatus::Queued;
+                job.progress = 0.0;
+                job.error = None;
+                job.outputs.clear();
+                job.operation_results.clear();
+                job.finished_at = None;
+                Ok(())
+            }
+            _ => Err("只能重试失败或已取消的任务".to_string()),
+        })?;
+        self.schedule();
+        Ok(job)
+    }
+
+    /// 取消任务；正在执行的任务会被终止
+    pub fn cancel_job(self: &Arc<Self>, job_id: &str) -> Result<MediaJob, String> {
+        let (job, was_running) = self.transition(job_id, |job| {
+            if job.status.is_finished() {
+                return Err("任务已结束".to_string());
+            }
+            job.status = MediaJobStatus::Cancelled;
+            job.finished_at = Some(now_millis());
+            Ok(())
+        })?;
+        if was_running {
+            let _ = ffmpeg::cancel_job(job_id);
+        }
+        Ok(job)
+    }
+
+    /// 调整任务优先级
+    pub fn set_priority(self: &Arc<Self>, job_id: &str, priority: i32) -> Result<MediaJob, String> {
+        let (job, _) = self.transition(job_id, |job| {
+            job.priority = priority;
+            Ok(())
+        })?;
+        Ok(job)
+    }
+
+    /// 从队列中移除任务（执行中的任务需先取消）
+    pub fn remove_job(self: &Arc<Self>, job_id: &str) -> Result<(), String> {
+        {
+            let mut state = self.lock();
+            if state.running.contains(job_id) {
+                return Err("任务正在执行，请先取消".to_string());
+            }
+            let before = state.jobs.len();
+            state.jobs.retain(|j| j.id != job_id);
+            if state.jobs.len() == before {
+                return Err(format!("任务不存在: {}", job_id));
+            }
+            self.persist(&state);
+        }
+        self.emit(MediaJobEvent::Removed(vec![job_id.to_string()]));
+        Ok(())
+    }
+
+    /// 清除已结束的任务，返回被清除的任务ID
+    pub fn clear_finished(self: &Arc<Self>) -> Vec<String> {
+        let removed: Vec<String> = {
+            let mut state = self.lock();
+            let removed = state
+                .jobs
+                .iter()
+                .filter(|j| j.status.is_finished() && !state.running.contains(&j.id))
+                .map(|j| j.id.clone())
+                .collect::<Vec<_>>();
+            state.jobs.retain(|j| !removed.contains(&j.id));
+            self.persist(&state);
+            removed
+        };
+        if !removed.is_empty() {
+            self.emit(MediaJobEvent::Removed(removed.clone()));
+        }
+        removed
+    }
+
+    fn lock(&self) -> MutexGuard<'_, QueueState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn persist(&self, state: &QueueState) {
+        let Some(path) = &self.store_path else {
+            return;
+        };
+        let queue = PersistedQueue {
+            settings: state.settings.clone(),
+            jobs: state.jobs.clone(),
+        };
+        if let Err(e) = store::save(path, &queue) {
+            log::error!("{e}");
+        }
+    }
+
+    fn emit(&self, event: MediaJobEvent) {
+        let listener = self
+            .listener
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        if let Some(listener) = listener {
+            listener(event);
+        }
+    }
+
+    fn enqueue(self: &Arc<Self>, jobs: Vec<MediaJob>) {
+        {
+            let mut state = self.lock();
+            state.jobs.extend(jobs.iter().cloned());
+            self.persist(&state);
+        }
+        for job in jobs {
+            self.emit(MediaJobEvent::Updated(job));
+        }
+        self.schedule();
+    }
+
+    /// 修改任务状态并持久化，返回修改后的任务及其是否正在执行
+    fn transition(
+        &self,
+        job_id: &str,
+        apply: impl FnOnce(&mut MediaJob) -> Result<(), String>,
+    ) -> Result<(MediaJob, bool), String> {
+        let (job, was_running) = {
+            let mut state = self.lock();
+            let was_running = state.running.contains(job_id);
+            let job = state
+                .jobs
+                .iter_mut()
+                .find(|j| j.id == job_id)
+                .ok_or_else(|| format!("任务不存在: {}", job_id))?;
+            apply(job)?;
+            let job = job.clone();
+            self.persist(&state);
+            (job, was_running)
+        };
+        self.emit(MediaJobEvent::Updated(job.clone()));
+        Ok((job, was_running))
+    }
+
+    /// 在并发数允许的范围内启动等待中的任务
+    fn schedule(self: &Arc<Self>) {
+        let mut started = Vec::new();
+        {
+            let mut state = self.lock();
+            if state.settings.paused {
+                return;
+            }
+            while state.running.len() < state.settings.concurrency.max(1) {
+                let Some(index) = next_job_index(&state) else {
+                    break;
+                };
+                let job = &mut state.jobs[index];
+                job.status = MediaJobStatus::Running;
+                job.progress = 0.0;
+                job.attempts += 1;
+                job.started_at = Some(now_millis());
+                let job = job.clone();
+                state.running.insert(job.id.clone());
+                started.push(job);
+            }
+            if !started.is_empty() {
+                self.persist(&state);
+            }
+        }
+
+        for job in started {
+            self.emit(MediaJobEvent::Updated(job.clone()));
+            self.spawn_job(job);
+        }
+    }
+
+    fn spawn_job(self: &Arc<Self>, job: MediaJob) {
+        let job_id = job.id.clone();
+        let progress_queue = Arc::clone(self);
+        let progress_job_id = job_id.clone();
+        let on_progress: ProgressFn = Arc::new(move |progress: JobProgress| {
+            progress_queue.report_progress(&progress_job_id, progress.percent);
+        });
+
+        let future = (self.runner)(job, on_progress);
+        let queue = Arc::clone(self);
+        tauri::async_runtime::spawn(async move {
+            let result = future.await;
+            queue.finish(&job_id, result);
+        });
+    }
+
+    /// 更新内存中的进度（不写入队列文件）
+    fn report_progress(&self, job_id: &str, percent: f64) {
+        let job = {
+            let mut state = self.lock();
+            let Some(job) = state
+                .jobs
+                .iter_mut()
+                .find(|j| j.id == job_id && j.status == MediaJobStatus::Running)
+            else {
+                return;
+            };
+            job.progress = percent.clamp(0.0, 100.0);
+            job.clone()
+        };
+        self.emit(MediaJobEvent::Updated(job));
+    }
+
+    fn finish(self: &Arc<Self>, job_id: &str, result: Result<JobOutput, String>) {
+        let event = {
+            let mut state = self.lock();
+            state.running.remove(job_id);
+            let event = state.jobs.iter_mut().find(|j| j.id == job_id).map(|job| {
+                // 执行期间被用户暂停或取消时保留用户设置的状态
+                if job.status != MediaJobStatus::Running {
+                    if job.status == MediaJobStatus::Paused {
+                        job.progress = 0.0;
+                    }
+                    return MediaJobEvent::Updated(job.clone());
+                }
+                job.finished_at = Some(now_millis());
+                match result {
+                    Ok(output) => {
+                        job.status = MediaJobStatus::Completed;
+                        job.progress = 100.0;
+                        job.outputs = output.outputs;
+                        job.operation_results = output.operation_results;
+                    }
+                    Err(e) if e == JOB_CANCELLED => {
+                        job.status = MediaJobStatus::Cancelled;
+                        return MediaJobEvent::Updated(job.clone());
+                    }
+                    Err(e) => {
+                        job.status = MediaJobStatus::Failed;
+                        job.error = Some(e);
+                    }
+                }
+                MediaJobEvent::Finished(job.clone())
+            });
+            self.persist(&state);
+            event
+        };
+
+        if let Some(event) = event {
+            self.emit(event);
+        }
+        self.schedule();
+    }
+}
+
+/// 默认执行器：注册 FFmpeg 任务后依次执行操作链
+fn default_runner() -> JobRunner {
+    Arc::new(|job: MediaJob, on_progress: ProgressFn| {
+        Box::pin(async move {
+            let guard = JobGuard::register(&job.id)?;
+            executor::run_operations(
+                &guard,
+                &job.input_path,
+                &job.operations,
+                job.output_dir.as_deref(),
+                move |progress| on_progress(progress),
+            )
+            .await
+        })
+    })
+}
+
+fn new_job(
+    input_path: String,
+    operations: Vec<crate::models::QueueOperation>,
+    output_dir: Option<String>,
+    priority: i32,
+    batch_id: Option<String>,
+) -> Result<MediaJob, String> {
+    if operations.is_empty() {
+        return Err("操作列表为空".to_string());
+    }
+    Ok(MediaJob {
+        id: ffmpeg::new_job_id(),
+        input_path,
+        operations,
+        output_dir,
+        priority,
+        status: MediaJobStatus::Queued,
+        progress: 0.0,
+        outputs: Vec::new(),
+        operation_results: Vec::new(),
+        error: None,
+        attempts: 0,
+        batch_id,
+        created_at: now_millis(),
+        started_at: None,
+        finished_at: None,
+    })
+}
+
+/// 优先级高者优先，同优先级按提交顺序
+fn next_job_index(state: &QueueState) -> Option<usize> {
+    state
+        .jobs
+        .iter()
+        .enumerate()
+        .filter(|(_, j)| j.status == MediaJobStatus::Queued && !state.running.contains(&j.id))
+        .min_by_key(|(index, j)| (Reverse(j.priority), j.created_at, *index))
+        .map(|(index, _)| index)
+}
+
+/// 收集文件夹中的输入文件（按路径排序，跳过隐藏文件）
+fn collect_inputs(
+    folder: &Path,
+    extensions: &[String],
+    recursive: bool,
+) -> Result<Vec<PathBuf>, String> {
+    if !folder.is_dir() {
+        return Err(format!("文件夹不存在: {}", folder.display()));
+    }
+
+    let extensions: Vec<String> = extensions
+        .iter()
+        .map(|e| e.trim_start_matches('.').to_lowercase())
+        .collect();
+    let mut inputs = Vec::new();
+    let mut pending = vec![folder.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let entries = std::fs::read_dir(&dir).map_err(|e| format!("读取文件夹失败: {}", e))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let hidden = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with('.'));
+            if hidden {
+                continue;
+            }
+            if path.is_dir() {
+                if recursive {
+                    pending.push(path);
+                }
+                continue;
+            }
+            let matches = extensions.is_empty()
+                || path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| extensions.contains(&e.to_lowercase()));
+            if matches {
+                inputs.push(path);
+            }
+        }
+    }
+
+    inputs.sort();
+    Ok(inputs)
+}
+
+/// 批量任务的输出目录
+///
+/// 指定了统一输出目录时保留输入文件相对批量文件夹的子目录，
+/// 避免递归收集到的同名文件写到同一个 `{文件名}_final` 上
+fn batch_output_dir(output_dir: Option<&str>, folder: &Path, input: &Path) -> Option<String> {
+    let output_dir = output_dir.filter(|dir| !dir.is_empty())?;
+    match input
+        .parent()
+        .and_then(|parent| parent.strip_prefix(folder).ok())
+        .filter(|relative| !relative.as_os_str().is_empty())
+    {
+        Some(relative) => Some(
+            Path::new(output_dir)
+                .join(relative)
+                .to_string_lossy()
+                .to_string(),
+        ),
+        None => Some(output_dir.to_string()),
+    }
+}
+
+fn now_millis() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::QueueOperation;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    fn operations() -> Vec<QueueOperation> {
+        vec![QueueOperation {
+            operation_type: "compress".to_string(),
+            name: "压缩".to_string(),
+            params: serde_json::json!({}),
+        }]
+    }
+
+    fn request(input: &Path, priority: i32) -> SubmitJobRequest {
+        SubmitJobRequest {
+            input_path: input.to_string_lossy().to_string(),
+            operations: operations(),
+            output_dir: None,
+            priority,
+        }
+    }
+
+    fn paused(concurrency: usize) -> MediaQueueSettings {
+        MediaQueueSettings {
+            concurrency,
+            paused: true,
+            notify_on_complete: false,
+        }
+    }
+
+    /// 记录执行顺序的执行器，每个任务耗时 `delay`
+    fn recording_runner(order: Arc<Mutex<Vec<String>>>, delay: Duration) -> JobRunner {
+        Arc::new(move |job: MediaJob, on_progress: ProgressFn| {
+            let order = order.clone();
+            Box::pin(async move {
+                order.lock().unwrap().push(job.input_path.clone());
+                tokio::time::sleep(delay).await;
+                on_progress(executor_progress(&job.id, 50.0));
+                Ok(JobOutput {
+                    outputs: vec![format!("{}.out", job.input_path)],
+                    ..Default::default()
+                })
+            })
+        })
+    }
+
+    fn executor_progress(job_id: &str, percent: f64) -> JobProgress {
+        JobProgress {
+            job_id: job_id.to_string(),
+            percent,
+            out_time: 0.0,
+            duration: 0.0,
+            speed: None,
+            eta: None,
+            fps: None,
+            elapsed: 0.0,
+        }
+    }
+
+    async fn wait_until(queue: &Arc<MediaJobQueue>, done: impl Fn(&[MediaJob]) -> bool) {
+        for _ in 0..200 {
+            if done(&queue.list_jobs()) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("等待队列超时: {:?}", queue.list_jobs());
+    }
+
+    fn all_finished(jobs: &[MediaJob]) -> bool {
+        jobs.iter().all(|j| j.status.is_finished())
+    }
+
+    #[tokio::test]
+    async fn runs_by_priority_then_submission_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let queue =
+            MediaJobQueue::with_runner(None, recording_runner(order.clone(), Duration::ZERO));
+        queue.update_settings(paused(1)).unwrap();
+
+        for (name, priority) in [("low", 0), ("high", 5), ("low2", 0), ("mid", 1)] {
+            let input = dir.path().join(name);
+            std::fs::write(&input, b"x").unwrap();
+            queue.submit(request(&input, priority)).unwrap();
+        }
+        queue
+            .update_settings(MediaQueueSettings {
+                paused: false,
+                ..paused(1)
+            })
+            .unwrap();
+        wait_until(&queue, all_finished).await;
+
+        let names: Vec<String> = order
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|p| {
+                Path::new(p)
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(names, vec!["high", "mid", "low", "low2"]);
+
+        let job = &queue.list_jobs()[0];
+        assert_eq!(job.status, MediaJobStatus::Completed);
+        assert_eq!(job.progress, 100.0);
+        assert_eq!(job.attempts, 1);
+        assert_eq!(job.outputs, vec![format!("{}.out", job.input_path)]);
+    }
+
+    #[tokio::test]
+    async fn respects_concurrency_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let current = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let (c, p) = (current.clone(), peak.clone());
+        let runner: JobRunner = Arc::new(move |_job, _progress| {
+            let (current, peak) = (c.clone(), p.clone());
+            Box::pin(async move {
+                let now = current.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(30)).await;
+                current.fetch_sub(1, Ordering::SeqCst);
+                Ok(JobOutput::default())
+            })
+        });
+        let queue = MediaJobQueue::with_runner(None, runner);
+        queue.update_settings(paused(2)).unwrap();
+
+        for i in 0..5 {
+            let input = dir.path().join(format!("{i}.mp4"));
+            std::fs::write(&input, b"x").unwrap();
+            queue.submit(request(&input, 0)).unwrap();
+        }
+        queue
+            .update_settings(MediaQueueSettings {
+                paused: false,
+                ..paused(2)
+            })
+            .unwrap();
+        wait_until(&queue, all_finished).await;
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        assert!(queue.update_settings(paused(0)).is_err());
+        assert!(queue.update_settings(paused(MAX_CONCURRENCY + 1)).is_err());
+    }
+
+    #[tokio::test]
+    async fn failed_job_can_be_retried_and_notifies() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("a.mp4");
+        std::fs::write(&input, b"x").unwrap();
+
+        let runner: JobRunner = Arc::new(|job: MediaJob, _progress| {
+            Box::pin(async move {
+                if job.attempts == 1 {
+                    Err("编码失败".to_string())
+                } else {
+                    Ok(JobOutput {
+                        outputs: vec!["done.mp4".to_string()],
+                        ..Default::default()
+                    })
+                }
+            })
+        });
+        let queue = MediaJobQueue::with_runner(None, runner);
+        let finished = Arc::new(Mutex::new(Vec::new()));
+        let events = finished.clone();
+        queue.set_listener(Arc::new(move |event| {
+            if let MediaJobEvent::Finished(job) = event {
+                events.lock().unwrap().push(job.status);
+            }
+        }));
+
+        let job = queue.submit(request(&input, 0)).unwrap();
+        wait_until(&queue, all_finished).await;
+        let failed = queue.get_job(&job.id).unwrap();
+        assert_eq!(failed.status, MediaJobStatus::Failed);
+        assert_eq!(failed.error.as_deref(), Some("编码失败"));
+        assert!(queue.resume_job(&job.id).is_err());
+
+        queue.retry_job(&job.id).unwrap();
+        wait_until(&queue, all_finished).await;
+        let retried = queue.get_job(&job.id).unwrap();
+        assert_eq!(retried.status, MediaJobStatus::Completed);
+        assert_eq!(retried.attempts, 2);
+        assert_eq!(retried.error, None);
+
+        assert_eq!(
+            *finished.lock().unwrap(),
+            vec![MediaJobStatus::Failed, MediaJobStatus::Completed]
+        );
+    }
+
+    #[tokio::test]
+    async fn operation_results_are_persisted_and_emitted() {
+        let dir = tempfile::tempdir().unwrap();
+        let store_path = dir.path().join("media_jobs.json");
+        let input = dir.path().join("a.mp4");
+        std::fs::write(&input, b"x").unwrap();
+
+        let stats = serde_json::json!({ "input_i": -23.5, "output_i": -16.0 });
+        let step_results = vec![serde_json::Value::Null, stats.clone()];
+        let results = step_results.clone();
+        let runner: JobRunner = Arc::new(move |_job, _progress| {
+            let operation_results = results.clone();
+            Box::pin(async move {
+                Ok(JobOutput {
+                    outputs: vec!["a_final.mp4".to_string()],
+                    operation_results,
+                })
+            })
+        });
+        let queue = MediaJobQueue::with_runner(Some(store_path.clone()), runner);
+        let finished = Arc::new(Mutex::new(Vec::new()));
+        let events = finished.clone();
+        queue.set_listener(Arc::new(move |event| {
+            if let MediaJobEvent::Finished(job) = event {
+                events.lock().unwrap().push(job.operation_results);
+            }
+        }));
+
+        let job = queue.submit(request(&input, 0)).unwrap();
+        wait_until(&queue, all_finished).await;
+        assert_eq!(
+            queue.get_job(&job.id).unwrap().operation_results,
+            step_results
+        );
+        assert_eq!(*finished.lock().unwrap(), vec![step_results.clone()]);
+
+        // 重启后仍可读取
+        let restored = MediaJobQueue::with_runner(
+            Some(store_path),
+            Arc::new(|_job, _progress| Box::pin(async { Ok(JobOutput::default()) })),
+        );
+        assert_eq!(
+            restored.get_job(&job.id).unwrap().operation_results[1],
+            stats
+        );
+    }
+
+    #[tokio::test]
+    async fn pause_resume_and_cancel_queued_jobs() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("a.mp4");
+        std::fs::write(&input, b"x").unwrap();
+
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let queue =
+            MediaJobQueue::with_runner(None, recording_runner(order.clone(), Duration::ZERO));
+        queue.update_settings(paused(1)).unwrap();
+
+        let job = queue.submit(request(&input, 0)).unwrap();
+        assert_eq!(
+            queue.pause_job(&job.id).unwrap().status,
+            MediaJobStatus::Paused
+        );
+        assert!(queue.retry_job(&job.id).is_err());
+
+        // 队列恢复后已暂停的任务不会执行
+        queue
+            .update_settings(MediaQueueSettings {
+                paused: false,
+                ..paused(1)
+            })
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(order.lock().unwrap().is_empty());
+
+        queue.update_settings(paused(1)).unwrap();
+        assert_eq!(
+            queue.resume_job(&job.id).unwrap().status,
+            MediaJobStatus::Queued
+        );
+        assert_eq!(
+            queue.cancel_job(&job.id).unwrap().status,
+            MediaJobStatus::Cancelled
+        );
+        assert!(queue.cancel_job(&job.id).is_err());
+
+        assert_eq!(queue.clear_finished(), vec![job.id.clone()]);
+        assert!(queue.list_jobs().is_empty());
+        assert!(queue.remove_job(&job.id).is_err());
+    }
+
+    #[tokio::test]
+    async fn restart_requeues_interrupted_jobs() {
+        let dir = tempfile::tempdir().unwrap();
+        let store_path = dir.path().join("media_jobs.json");
+        let input = dir.path().join("a.mp4");
+        std::fs::write(&input, b"x").unwrap();
+
+        let never: JobRunner = Arc::new(|_job, _progress| Box::pin(std::future::pending()));
+        let queue = MediaJobQueue::with_runner(Some(store_path.clone()), never);
+        queue
+            .update_settings(MediaQueueSettings {
+                concurrency: 3,
+                ..Default::default()
+            })
+            .unwrap();
+        let running = queue.submit(request(&input, 0)).unwrap();
+        let paused_job = queue.submit(request(&input, 2)).unwrap();
+        queue.pause_job(&paused_job.id).unwrap();
+        wait_until(&queue, |jobs| jobs[0].status == MediaJobStatus::Running).await;
+
+        // 模拟应用重启：从队列文件恢复
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let restored = MediaJobQueue::with_runner(
+            Some(store_path.clone()),
+            recording_runner(order.clone(), Duration::ZERO),
+        );
+        assert_eq!(restored.settings().concurrency, 3);
+        let jobs = restored.list_jobs();
+        assert_eq!(jobs[0].id, running.id);
+        assert_eq!(jobs[0].status, MediaJobStatus::Queued);
+        assert_eq!(jobs[1].status, MediaJobStatus::Paused);
+
+        restored.start();
+        wait_until(&restored, |jobs| jobs[0].status.is_finished()).await;
+        assert_eq!(restored.get_job(&running.id).unwrap().attempts, 2);
+        assert_eq!(order.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn corrupt_store_starts_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let store_path = dir.path().join("media_jobs.json");
+        std::fs::write(&store_path, "{not json").unwrap();
+
+        let queue = MediaJobQueue::with_runner(
+            Some(store_path.clone()),
+            Arc::new(|_job, _progress| Box::pin(async { Ok(JobOutput::default()) })),
+        );
+        assert!(queue.list_jobs().is_empty());
+        assert!(dir.path().join("media_jobs.json.corrupt").exists());
+    }
+
+    #[tokio::test]
+    async fn submit_batch_collects_matching_files() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["b.MOV", "a.mp4", "notes.txt", ".hidden.mp4", "sub/c.mp4"] {
+            let path = dir.path().join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, b"x").unwrap();
+        }
+
+        let queue =
+            MediaJobQueue::with_runner(None, recording_runner(Default::default(), Duration::ZERO));
+        queue.update_settings(paused(1)).unwrap();
+        let batch = |recursive| SubmitBatchRequest {
+            folder: dir.path().to_string_lossy().to_string(),
+            extensions: vec!["mp4".to_string(), ".mov".to_string()],
+            recursive,
+            operations: operations(),
+            output_dir: None,
+            priority: 0,
+        };
+
+        let jobs = queue.submit_batch(batch(false)).unwrap();
+        let names: Vec<_> = jobs
+            .iter()
+            .map(|j| {
+                Path::new(&j.input_path)
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(names, vec!["a.mp4", "b.MOV"]);
+        assert!(jobs[0].batch_id.is_some());
+        assert_eq!(jobs[0].batch_id, jobs[1].batch_id);
+
+        assert_eq!(queue.submit_batch(batch(true)).unwrap().len(), 3);
+
+        let empty = SubmitBatchRequest {
+            extensions: vec!["mkv".to_string()],
+            ..batch(true)
+        };
+        assert!(queue.submit_batch(empty).is_err());
+    }
+
+    #[tokio::test]
+    async fn submit_batch_keeps_subfolders_under_output_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let input_dir = dir.path().join("in");
+        let output_dir = dir.path().join("out");
+        for name in ["clip.mp4", "a/clip.mp4", "b/clip.mp4"] {
+            let path = input_dir.join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, b"x").unwrap();
+        }
+
+        let queue =
+            MediaJobQueue::with_runner(None, recording_runner(Default::default(), Duration::ZERO));
+        queue.update_settings(paused(1)).unwrap();
+        let jobs = queue
+            .submit_batch(SubmitBatchRequest {
+                folder: input_dir.to_string_lossy().to_string(),
+                extensions: vec!["mp4".to_string()],
+                recursive: true,
+                operations: operations(),
+                output_dir: Some(output_dir.to_string_lossy().to_string()),
+                priority: 0,
+            })
+            .unwrap();
+
+        let dirs: Vec<_> = jobs
+            .iter()
+            .map(|j| PathBuf::from(j.output_dir.as_deref().unwrap()))
+            .collect();
+        assert_eq!(
+            dirs,
+            vec![
+                output_dir.join("a"),
+                output_dir.join("b"),
+                output_dir.clone()
+            ]
+        );
+    }
+}
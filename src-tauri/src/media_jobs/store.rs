@@ -0,0 +1,38 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::types::{MediaJob, MediaQueueSettings};
+
+/// 持久化的队列内容
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PersistedQueue {
+    #[serde(default)]
+    pub settings: MediaQueueSettings,
+    #[serde(default)]
+    pub jobs: Vec<MediaJob>,
+}
+
+/// 读取队列文件；文件不存在或损坏时返回空队列（损坏的文件备份为 `.corrupt`）
+pub(crate) fn load(path: &Path) -> PersistedQueue {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => return PersistedQueue::default(),
+    };
+
+    match serde_json::from_str(&content) {
+        Ok(queue) => queue,
+        Err(e) => {
+            log::warn!("媒体任务队列文件损坏，已重置: {} ({e})", path.display());
+            let _ = std::fs::rename(path, path.with_extension("json.corrupt"));
+            PersistedQueue::default()
+        }
+    }
+}
+
+/// 原子写入队列文件
+pub(crate) fn save(path: &Path, queue: &PersistedQueue) -> Result<(), String> {
+    crate::cc_switch::config::write_json_file(path, queue)
+        .map_err(|e| format!("保存媒体任务队列失败: {}", e))
+}
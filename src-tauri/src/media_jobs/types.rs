@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::QueueOperation;
+
+/// 任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaJobStatus {
+    /// 等待执行
+    Queued,
+    /// 正在执行
+    Running,
+    /// 已暂停（恢复后从头执行）
+    Paused,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl MediaJobStatus {
+    /// 是否已结束（完成、失败或取消）
+    pub fn is_finished(self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::Cancelled)
+    }
+}
+
+/// 媒体处理任务
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaJob {
+    /// 任务ID（同时作为 FFmpeg 任务ID，可通过 `cancel_job` 终止当前步骤）
+    pub id: String,
+    /// 输入文件路径
+    pub input_path: String,
+    /// 依次执行的操作链
+    pub operations: Vec<QueueOperation>,
+    /// 输出目录，为空时输出到输入文件所在目录
+    #[serde(default)]
+    pub output_dir: Option<String>,
+    /// 优先级，数值越大越先执行
+    #[serde(default)]
+    pub priority: i32,
+    pub status: MediaJobStatus,
+    /// 进度百分比 (0.0-100.0)
+    #[serde(default)]
+    pub progress: f64,
+    /// 生成的文件（最终文件在最后）
+    #[serde(default)]
+    pub outputs: Vec<String>,
+    /// 各步骤的附加结果，与 `operations` 一一对应（如响度标准化的统计），无结果的步骤为 null
+    #[serde(default)]
+    pub operation_results: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub error: Option<String>,
+    /// 已执行次数
+    #[serde(default)]
+    pub attempts: u32,
+    /// 所属批次ID（按文件夹批量提交时设置）
+    #[serde(default)]
+    pub batch_id: Option<String>,
+    /// 创建时间（毫秒时间戳）
+    pub created_at: i64,
+    #[serde(default)]
+    pub started_at: Option<i64>,
+    #[serde(default)]
+    pub finished_at: Option<i64>,
+}
+
+/// 操作链的执行结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JobOutput {
+    /// 生成的文件（最终文件在最后）
+    pub outputs: Vec<String>,
+    /// 各步骤的附加结果，与操作链一一对应
+    pub operation_results: Vec<serde_json::Value>,
+}
+
+/// 队列设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaQueueSettings {
+    /// 同时执行的任务数
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize,
+    /// 暂停整个队列（正在执行的任务不受影响）
+    #[serde(default)]
+    pub paused: bool,
+    /// 任务结束时发送系统通知
+    #[serde(default = "default_notify")]
+    pub notify_on_complete: bool,
+}
+
+fn default_concurrency() -> usize {
+    2
+}
+
+fn default_notify() -> bool {
+    true
+}
+
+impl Default for MediaQueueSettings {
+    fn default() -> Self {
+        Self {
+            concurrency: default_concurrency(),
+            paused: false,
+            notify_on_complete: default_notify(),
+        }
+    }
+}
+
+/// 提交单个任务
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmitJobRequest {
+    pub input_path: String,
+    pub operations: Vec<QueueOperation>,
+    #[serde(default)]
+    pub output_dir: Option<String>,
+    #[serde(default)]
+    pub priority: i32,
+}
+
+/// 按文件夹批量提交任务
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmitBatchRequest {
+    /// 输入文件夹
+    pub folder: String,
+    /// 文件扩展名过滤（不含点，不区分大小写），为空时包含所有文件
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    /// 是否包含子文件夹
+    #[serde(default)]
+    pub recursive: bool,
+    pub operations: Vec<QueueOperation>,
+    #[serde(default)]
+    pub output_dir: Option<String>,
+    #[serde(default)]
+    pub priority: i32,
+}
+
+/// 队列事件（由 lib.rs 转发为前端事件和系统通知）
+#[derive(Debug, Clone)]
+pub enum MediaJobEvent {
+    /// 任务状态或进度变化
+    Updated(MediaJob),
+    /// 任务执行结束（完成或失败，不含用户取消）
+    Finished(MediaJob),
+    /// 任务已从队列中移除
+    Removed(Vec<String>),
+}
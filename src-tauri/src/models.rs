@@ -13,6 +13,61 @@ pub struct VideoInfo {
     pub bitrate: u64,
     pub file_size: u64,
     pub format: String,
+    /// 主视频流的旋转角度（顺时针，0/90/180/270），width/height 已按旋转后的显示方向给出
+    #[serde(default)]
+    pub rotation: u32,
+    /// 全部流信息（仅 ffprobe 可用时提供）
+    #[serde(default)]
+    pub streams: Vec<StreamInfo>,
+}
+
+/// 流类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StreamKind {
+    Video,
+    Audio,
+    Subtitle,
+    Data,
+    Attachment,
+    Unknown,
+}
+
+/// 单个媒体流信息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamInfo {
+    /// 流索引
+    pub index: u32,
+    pub kind: StreamKind,
+    pub codec: String,
+    pub profile: Option<String>,
+    /// 码率（bps）
+    pub bitrate: Option<u64>,
+    /// 语言（ISO 639-2，如 "eng"）
+    pub language: Option<String>,
+    pub title: Option<String>,
+    /// 是否为默认流
+    pub is_default: bool,
+
+    // 视频流
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub fps: Option<f64>,
+    pub pixel_format: Option<String>,
+    pub color_space: Option<String>,
+    pub color_transfer: Option<String>,
+    pub color_primaries: Option<String>,
+    /// 顺时针旋转角度（0/90/180/270）
+    pub rotation: u32,
+    /// 是否为 HDR（PQ / HLG 传输特性）
+    pub hdr: bool,
+    /// 是否为封面图（MP3/MP4 内嵌的 attached_pic）
+    pub attached_pic: bool,
+
+    // 音频流
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u32>,
+    pub channel_layout: Option<String>,
 }
 
 /// 队列操作
@@ -80,6 +80,7 @@ function App() {
     gemini: true,
     opencode: true,
     cursor: true,
+    qwen: true,
   };
 
   // Get first visible app for fallback
@@ -89,6 +90,7 @@ function App() {
     if (visibleApps.gemini) return "gemini";
     if (visibleApps.opencode) return "opencode";
     if (visibleApps.cursor) return "cursor";
+    if (visibleApps.qwen) return "qwen";
     return "claude"; // fallback
   };
 
@@ -808,7 +810,7 @@ function App() {
             )}
             {currentView === "providers" && (
               <>
-                {activeApp !== "opencode" && (
+                {activeApp !== "cursor" && activeApp !== "qwen" && (
                   <>
                     <ProxyToggle activeApp={activeApp} />
                     <div
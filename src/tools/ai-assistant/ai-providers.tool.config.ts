@@ -11,7 +11,7 @@ const aiProvidersToolConfig: ToolMetadata = {
   icon: 'Bot',
   category: 'dev',
   component: withAiAssistantProviders(ProvidersPage),
-  tags: ['ai', 'provider', 'claude', 'codex', 'gemini', 'opencode', 'cursor'],
+  tags: ['ai', 'provider', 'claude', 'codex', 'gemini', 'opencode', 'cursor', 'qwen'],
   status: 'stable',
   supportFileDrop: false,
 };
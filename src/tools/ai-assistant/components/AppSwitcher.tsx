@@ -9,7 +9,13 @@ interface AppSwitcherProps {
   compact?: boolean;
 }
 
-const ALL_APPS: AppId[] = ["claude", "codex", "gemini", "opencode"];
+const ALL_APPS: AppId[] = [
+  "claude",
+  "codex",
+  "gemini",
+  "opencode",
+  "qwen",
+];
 
 export function AppSwitcher({
   activeApp,
@@ -28,6 +34,7 @@ export function AppSwitcher({
     gemini: "gemini",
     opencode: "opencode",
     cursor: "cursor",
+    qwen: "qwen",
   };
   const appDisplayName: Record<AppId, string> = {
     claude: "Claude",
@@ -35,6 +42,7 @@ export function AppSwitcher({
     gemini: "Gemini",
     opencode: "OpenCode",
     cursor: "Cursor",
+    qwen: "Qwen Code",
   };
 
   // Filter apps based on visibility settings (default all visible)
@@ -283,6 +283,10 @@ const UsageScriptModal: React.FC<UsageScriptModalProps> = ({
         script.userId,
         selectedTemplate as "custom" | "general" | "newapi" | undefined,
       );
+      // 展示脚本最近的 console 输出，便于调试
+      const logs = result.logs?.length
+        ? result.logs.slice(-5).join("\n")
+        : undefined;
       if (result.success && result.data && result.data.length > 0) {
         const summary = result.data
           .map((plan: UsageData) => {
@@ -291,6 +295,7 @@ const UsageScriptModal: React.FC<UsageScriptModalProps> = ({
           })
           .join(", ");
         toast.success(`${t("usageScript.testSuccess")}${summary}`, {
+          description: logs,
           duration: 3000,
           closeButton: true,
         });
@@ -301,6 +306,7 @@ const UsageScriptModal: React.FC<UsageScriptModalProps> = ({
         toast.error(
           `${t("usageScript.testFailed")}: ${result.error || t("endpointTest.noResult")}`,
           {
+            description: logs,
             duration: 5000,
           },
         );
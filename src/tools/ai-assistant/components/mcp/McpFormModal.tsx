@@ -73,6 +73,7 @@ const McpFormModal: React.FC<McpFormModalProps> = ({
     gemini: boolean;
     opencode: boolean;
     cursor: boolean;
+    qwen: boolean;
   }>(() => {
     if (initialData?.apps) {
       return { ...initialData.apps };
@@ -83,6 +84,7 @@ const McpFormModal: React.FC<McpFormModalProps> = ({
       gemini: defaultEnabledApps.includes("gemini"),
       opencode: defaultEnabledApps.includes("opencode"),
       cursor: defaultEnabledApps.includes("cursor"),
+      qwen: defaultEnabledApps.includes("qwen"),
     };
   });
 
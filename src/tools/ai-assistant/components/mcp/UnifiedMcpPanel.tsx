@@ -59,13 +59,21 @@ const UnifiedMcpPanel = React.forwardRef<
 
   // Count enabled servers per app
   const enabledCounts = useMemo(() => {
-    const counts = { claude: 0, codex: 0, gemini: 0, opencode: 0, cursor: 0 };
+    const counts = {
+      claude: 0,
+      codex: 0,
+      gemini: 0,
+      opencode: 0,
+      cursor: 0,
+      qwen: 0,
+    };
     serverEntries.forEach(([_, server]) => {
       if (server.apps.claude) counts.claude++;
       if (server.apps.codex) counts.codex++;
       if (server.apps.gemini) counts.gemini++;
       if (server.apps.opencode) counts.opencode++;
       if (server.apps.cursor) counts.cursor++;
+      if (server.apps.qwen) counts.qwen++;
     });
     return counts;
   }, [serverEntries]);
@@ -152,7 +160,8 @@ const UnifiedMcpPanel = React.forwardRef<
           {t("mcp.unifiedPanel.apps.codex")}: {enabledCounts.codex} ·{" "}
           {t("mcp.unifiedPanel.apps.gemini")}: {enabledCounts.gemini} ·{" "}
           {t("mcp.unifiedPanel.apps.opencode")}: {enabledCounts.opencode} ·{" "}
-          {t("mcp.unifiedPanel.apps.cursor")}: {enabledCounts.cursor}
+          {t("mcp.unifiedPanel.apps.cursor")}: {enabledCounts.cursor} ·{" "}
+          {t("mcp.unifiedPanel.apps.qwen")}: {enabledCounts.qwen}
         </div>
       </div>
 
@@ -373,6 +382,22 @@ const UnifiedMcpListItem: React.FC<UnifiedMcpListItemProps> = ({
             }
           />
         </div>
+
+        <div className="flex items-center justify-between gap-3">
+          <label
+            htmlFor={`${id}-qwen`}
+            className="text-sm text-foreground/80 cursor-pointer"
+          >
+            {t("mcp.unifiedPanel.apps.qwen")}
+          </label>
+          <Switch
+            id={`${id}-qwen`}
+            checked={server.apps.qwen}
+            onCheckedChange={(checked: boolean) =>
+              onToggleApp(id, "qwen", checked)
+            }
+          />
+        </div>
       </div>
 
       {/* 右侧：操作按钮 */}
@@ -36,6 +36,7 @@ const PromptFormPanel: React.FC<PromptFormPanelProps> = ({
     gemini: "GEMINI.md",
     opencode: "AGENTS.md",
     cursor: "AGENTS.md",
+    qwen: "QWEN.md",
   };
   const filename = filenameMap[appId];
   const [name, setName] = useState("");
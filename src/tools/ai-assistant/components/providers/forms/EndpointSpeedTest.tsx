@@ -15,6 +15,7 @@ const ENDPOINT_TIMEOUT_SECS: Record<AppId, number> = {
   gemini: 8,
   opencode: 8,
   cursor: 8,
+  qwen: 8,
 };
 
 interface TestResult {
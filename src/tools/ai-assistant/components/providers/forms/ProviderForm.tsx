@@ -78,6 +78,19 @@ const GEMINI_DEFAULT_CONFIG = JSON.stringify(
   2,
 );
 
+const QWEN_DEFAULT_CONFIG = JSON.stringify(
+  {
+    env: {
+      OPENAI_BASE_URL: "",
+      OPENAI_API_KEY: "",
+      OPENAI_MODEL: "qwen3-coder-plus",
+    },
+    config: {},
+  },
+  null,
+  2,
+);
+
 const OPENCODE_DEFAULT_CONFIG = JSON.stringify(
   {
     npm: "@ai-sdk/openai-compatible",
@@ -203,7 +216,9 @@ export function ProviderForm({
             ? GEMINI_DEFAULT_CONFIG
             : appId === "opencode"
               ? OPENCODE_DEFAULT_CONFIG
-              : CLAUDE_DEFAULT_CONFIG,
+              : appId === "qwen"
+                ? QWEN_DEFAULT_CONFIG
+                : CLAUDE_DEFAULT_CONFIG,
       icon: initialData?.icon ?? "",
       iconColor: initialData?.iconColor ?? "",
     }),
@@ -4,9 +4,10 @@ import {
   setCodexBaseUrl as setCodexBaseUrlInConfig,
 } from "@ai-assistant/utils/providerConfigUtils";
 import type { ProviderCategory } from "@ai-assistant/types";
+import type { AppId } from "@ai-assistant/lib/api";
 
 interface UseBaseUrlStateProps {
-  appType: "claude" | "codex" | "gemini" | "opencode" | "cursor";
+  appType: AppId;
   category: ProviderCategory | undefined;
   settingsConfig: string;
   codexConfig?: string;
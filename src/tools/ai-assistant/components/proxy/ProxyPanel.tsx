@@ -26,6 +26,9 @@ import {
 import type { ProxyStatus } from "@ai-assistant/types/proxy";
 import { useTranslation } from "react-i18next";
 
+/** 支持代理接管的应用（Cursor 没有可改写的供应商配置） */
+const TAKEOVER_APPS = ["claude", "codex", "gemini", "opencode"] as const;
+
 export function ProxyPanel() {
   const { t } = useTranslation();
   const { status, isRunning } = useProxyStatus();
@@ -50,11 +53,12 @@ export function ProxyPanel() {
     }
   }, [globalConfig]);
 
-  // 获取所有三个应用类型的故障转移队列
+  // 获取所有应用类型的故障转移队列
   // 启用自动故障转移后，将按队列优先级（P1→P2→...）选择供应商
   const { data: claudeQueue = [] } = useFailoverQueue("claude");
   const { data: codexQueue = [] } = useFailoverQueue("codex");
   const { data: geminiQueue = [] } = useFailoverQueue("gemini");
+  const { data: opencodeQueue = [] } = useFailoverQueue("opencode");
 
   const handleTakeoverChange = async (appType: string, enabled: boolean) => {
     try {
@@ -270,8 +274,8 @@ export function ProxyPanel() {
                     defaultValue: "应用接管",
                   })}
                 </p>
-                <div className="grid gap-2 sm:grid-cols-3">
-                  {(["claude", "codex", "gemini"] as const).map((appType) => {
+                <div className="grid gap-2 sm:grid-cols-2">
+                  {TAKEOVER_APPS.map((appType) => {
                     const isEnabled =
                       takeoverStatus?.[
                         appType as keyof typeof takeoverStatus
@@ -323,7 +327,8 @@ export function ProxyPanel() {
               {/* 供应商队列 - 按应用类型分组展示 */}
               {(claudeQueue.length > 0 ||
                 codexQueue.length > 0 ||
-                geminiQueue.length > 0) && (
+                geminiQueue.length > 0 ||
+                opencodeQueue.length > 0) && (
                 <div className="pt-3 border-t border-border space-y-3">
                   <div className="flex items-center gap-2">
                     <ListOrdered className="h-3.5 w-3.5 text-muted-foreground" />
@@ -370,6 +375,19 @@ export function ProxyPanel() {
                       status={status}
                     />
                   )}
+
+                  {/* OpenCode 队列 */}
+                  {opencodeQueue.length > 0 && (
+                    <ProviderQueueGroup
+                      appType="opencode"
+                      appLabel="OpenCode"
+                      targets={opencodeQueue.map((item) => ({
+                        id: item.providerId,
+                        name: item.providerName,
+                      }))}
+                      status={status}
+                    />
+                  )}
                 </div>
               )}
             </div>
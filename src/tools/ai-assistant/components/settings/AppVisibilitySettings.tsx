@@ -20,6 +20,7 @@ const APP_CONFIG: Array<{
   { id: "codex", icon: "openai", nameKey: "apps.codex" },
   { id: "gemini", icon: "gemini", nameKey: "apps.gemini" },
   { id: "opencode", icon: "opencode", nameKey: "apps.opencode" },
+  { id: "qwen", icon: "qwen", nameKey: "apps.qwen" },
 ];
 
 export function AppVisibilitySettings({
@@ -34,6 +35,7 @@ export function AppVisibilitySettings({
     gemini: false,
     opencode: true,
     cursor: true,
+    qwen: true,
   };
 
   // Count how many apps are currently visible
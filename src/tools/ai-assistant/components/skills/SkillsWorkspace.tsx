@@ -33,6 +33,7 @@ import {
   Clock3,
   Check,
   Search,
+  ShieldAlert,
   X,
 } from "lucide-react";
 import { toast } from "sonner";
@@ -69,6 +70,7 @@ import {
   skillsApi,
   type SkillFileContentResult,
   type SkillFileTreeEntry,
+  type SkillScanReport,
   type SkillsShCategory,
   type SkillsShSkill,
 } from "@ai-assistant/lib/api/skills";
@@ -114,6 +116,7 @@ const SKILL_APP_TOGGLES: Array<{ label: string; app: AppType }> = [
   { label: "Codex", app: "codex" },
   { label: "OpenCode", app: "opencode" },
   { label: "Gemini CLI", app: "gemini" },
+  { label: "Qwen Code", app: "qwen" },
 ];
 
 const EXTERNAL_SYNC_APPS: string[] = ["Antigravity", "TRAE IDE"];
@@ -124,6 +127,7 @@ const APP_ICON_MAP: Record<string, string> = {
   gemini: "gemini",
   opencode: "opencode",
   cursor: "cursor",
+  qwen: "qwen",
 };
 
 const BROWSE_CATEGORY_META: Record<
@@ -146,6 +150,7 @@ const TOOL_STATUS_ORDER: Array<{ id: string; name: string; code: string }> = [
   { id: "roo_code", name: "Roo Code", code: "roo_code" },
   { id: "goose", name: "Goose", code: "goose" },
   { id: "gemini", name: "Gemini CLI", code: "gemini_cli" },
+  { id: "qwen", name: "Qwen Code", code: "qwen_code" },
   { id: "github_copilot", name: "GitHub Copilot", code: "github_copilot" },
   { id: "clawdbot", name: "Clawdbot", code: "clawdbot" },
   { id: "droid", name: "Droid", code: "droid" },
@@ -615,6 +620,7 @@ async function loadToolStatuses(): Promise<ToolStatusView[]> {
       settingsApi.getConfigStatus("gemini"),
       settingsApi.getConfigStatus("opencode"),
       settingsApi.getConfigStatus("cursor"),
+      settingsApi.getConfigStatus("qwen"),
     ]),
   ]);
 
@@ -638,8 +644,14 @@ async function loadToolStatuses(): Promise<ToolStatusView[]> {
       ),
   );
 
-  const [claudeStatus, codexStatus, geminiStatus, opencodeStatus, cursorStatus]:
-    TauriConfigStatus[] = configStatuses as TauriConfigStatus[];
+  const [
+    claudeStatus,
+    codexStatus,
+    geminiStatus,
+    opencodeStatus,
+    cursorStatus,
+    qwenStatus,
+  ]: TauriConfigStatus[] = configStatuses as TauriConfigStatus[];
 
   const configInstalledById: Record<string, boolean> = {
     claude: claudeStatus.exists,
@@ -647,6 +659,7 @@ async function loadToolStatuses(): Promise<ToolStatusView[]> {
     gemini: geminiStatus.exists,
     opencode: opencodeStatus.exists,
     cursor: cursorStatus.exists,
+    qwen: qwenStatus.exists,
   };
 
   return TOOL_STATUS_ORDER.map(
@@ -703,6 +716,10 @@ export function SkillsWorkspace() {
   const [skillViewerContentLoading, setSkillViewerContentLoading] = useState<boolean>(false);
   const [skillViewerContentError, setSkillViewerContentError] = useState<string | null>(null);
   const skillViewerReadSeqRef = useRef<number>(0);
+  const [scanReview, setScanReview] = useState<{ name: string; report: SkillScanReport } | null>(
+    null,
+  );
+  const scanReviewResolveRef = useRef<((acknowledged: boolean) => void) | null>(null);
 
   const installedQuery = useInstalledSkills();
   const uninstallMutation = useUninstallSkill();
@@ -936,10 +953,33 @@ export function SkillsWorkspace() {
       throw new Error(t("skills.workspace.toast.noSkillInRepo"));
     }
 
+    // 扫描报告含风险项时，需用户确认后携带报告指纹安装
+    const report: SkillScanReport = await skillsApi.scanBeforeInstall(pickedSkill);
+    if (
+      report.requiresAcknowledgement &&
+      !(await requestScanAcknowledgement(pickedSkill.name, report))
+    ) {
+      throw new Error(t("skills.workspace.scan.cancelled", { defaultValue: "已取消安装" }));
+    }
+
     await installMutation.mutateAsync({
       skill: pickedSkill,
       currentApp: "claude",
+      acknowledgedFingerprint: report.requiresAcknowledgement ? report.fingerprint : undefined,
+    });
+  };
+
+  const requestScanAcknowledgement = (name: string, report: SkillScanReport): Promise<boolean> =>
+    new Promise<boolean>((resolve) => {
+      scanReviewResolveRef.current?.(false);
+      scanReviewResolveRef.current = resolve;
+      setScanReview({ name, report });
     });
+
+  const resolveScanReview = (acknowledged: boolean): void => {
+    scanReviewResolveRef.current?.(acknowledged);
+    scanReviewResolveRef.current = null;
+    setScanReview(null);
   };
 
   const handleInstallFromBrowse = async (item: SkillsShSkill): Promise<void> => {
@@ -2308,6 +2348,117 @@ export function SkillsWorkspace() {
           </div>
         </DialogContent>
       </Dialog>
+
+      <Dialog
+        open={scanReview !== null}
+        onOpenChange={(open: boolean) => {
+          if (!open) {
+            resolveScanReview(false);
+          }
+        }}
+      >
+        <DialogContent className="max-h-[80vh] max-w-[720px]" zIndex="top">
+          <DialogHeader className="space-y-1">
+            <DialogTitle className="flex items-center gap-2">
+              <ShieldAlert className="size-5 text-amber-500" />
+              {t("skills.workspace.scan.title", { defaultValue: "安全扫描报告" })}
+            </DialogTitle>
+            <DialogDescription>
+              {t("skills.workspace.scan.description", {
+                defaultValue: "{{name}} 包含需要确认的内容，请检查后再决定是否安装",
+                name: scanReview?.name ?? "",
+              })}
+            </DialogDescription>
+          </DialogHeader>
+
+          {scanReview && (
+            <ScrollArea className="max-h-[50vh] pr-3">
+              <div className="space-y-3 text-sm">
+                <p className="text-muted-foreground">
+                  {t("skills.workspace.scan.summary", {
+                    defaultValue: "共扫描 {{files}} 个文件",
+                    files: scanReview.report.filesScanned,
+                  })}
+                  {scanReview.report.truncated
+                    ? ` · ${t("skills.workspace.scan.truncated", {
+                        defaultValue: "文件过多，仅扫描了部分内容",
+                      })}`
+                    : ""}
+                </p>
+                {!scanReview.report.frontmatter.valid && (
+                  <p className="text-destructive">
+                    {t("skills.workspace.scan.frontmatterInvalid", {
+                      defaultValue: "SKILL.md 元数据校验失败",
+                    })}
+                    {scanReview.report.frontmatter.errors.length > 0
+                      ? `：${scanReview.report.frontmatter.errors.join("；")}`
+                      : ""}
+                  </p>
+                )}
+                {scanReview.report.executables.length > 0 && (
+                  <div>
+                    <p className="font-medium text-foreground">
+                      {t("skills.workspace.scan.executables", { defaultValue: "可执行文件" })}
+                    </p>
+                    <p className="break-all font-mono text-xs text-muted-foreground">
+                      {scanReview.report.executables.join(", ")}
+                    </p>
+                  </div>
+                )}
+                {scanReview.report.scripts.length > 0 && (
+                  <div>
+                    <p className="font-medium text-foreground">
+                      {t("skills.workspace.scan.scripts", { defaultValue: "脚本" })}
+                    </p>
+                    <p className="break-all font-mono text-xs text-muted-foreground">
+                      {scanReview.report.scripts.join(", ")}
+                    </p>
+                  </div>
+                )}
+                {scanReview.report.findings.length > 0 && (
+                  <div className="space-y-2">
+                    <p className="font-medium text-foreground">
+                      {t("skills.workspace.scan.findings", { defaultValue: "风险项" })}
+                    </p>
+                    {scanReview.report.findings.map((finding, index: number) => (
+                      <div
+                        key={`${finding.ruleId}-${finding.path}-${finding.line}-${index}`}
+                        className="rounded-md border border-border p-2"
+                      >
+                        <div className="flex items-center gap-2">
+                          <Badge variant={finding.severity === "info" ? "secondary" : "destructive"}>
+                            {t(`skills.workspace.scan.severity.${finding.severity}`, {
+                              defaultValue: finding.severity,
+                            })}
+                          </Badge>
+                          <span className="break-all font-mono text-xs text-muted-foreground">
+                            {finding.line > 0 ? `${finding.path}:${finding.line}` : finding.path}
+                          </span>
+                        </div>
+                        <p className="mt-1 text-foreground">{finding.message}</p>
+                        {finding.excerpt && (
+                          <pre className="mt-1 whitespace-pre-wrap break-all rounded bg-muted px-2 py-1 text-xs">
+                            {finding.excerpt}
+                          </pre>
+                        )}
+                      </div>
+                    ))}
+                  </div>
+                )}
+              </div>
+            </ScrollArea>
+          )}
+
+          <div className="flex justify-end gap-2">
+            <Button variant="outline" onClick={() => resolveScanReview(false)}>
+              {t("skills.workspace.scan.cancel", { defaultValue: "取消安装" })}
+            </Button>
+            <Button variant="destructive" onClick={() => resolveScanReview(true)}>
+              {t("skills.workspace.scan.confirm", { defaultValue: "我已了解风险，继续安装" })}
+            </Button>
+          </div>
+        </DialogContent>
+      </Dialog>
     </div>
   );
 }
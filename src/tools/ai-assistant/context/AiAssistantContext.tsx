@@ -30,7 +30,7 @@ export function AiAssistantProvider({
   // 持久化到 localStorage
   useEffect(() => {
     const saved = localStorage.getItem('ai-assistant:activeApp');
-    if (saved && ['claude', 'codex', 'gemini', 'opencode', 'cursor'].includes(saved)) {
+    if (saved && ['claude', 'codex', 'gemini', 'opencode', 'cursor', 'qwen'].includes(saved)) {
       setActiveApp(saved as AppId);
     }
   }, []);
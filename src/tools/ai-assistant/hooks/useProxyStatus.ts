@@ -183,6 +183,7 @@ export function useProxyStatus() {
       takeoverStatus?.claude ||
       takeoverStatus?.codex ||
       takeoverStatus?.gemini ||
+      takeoverStatus?.opencode ||
       false,
 
     // 启动/停止（总开关）
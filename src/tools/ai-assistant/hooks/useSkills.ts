@@ -36,10 +36,12 @@ export function useInstallSkill() {
     mutationFn: ({
       skill,
       currentApp,
+      acknowledgedFingerprint,
     }: {
       skill: DiscoverableSkill;
       currentApp: AppType;
-    }) => skillsApi.installUnified(skill, currentApp),
+      acknowledgedFingerprint?: string;
+    }) => skillsApi.installUnified(skill, currentApp, acknowledgedFingerprint),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ["skills", "installed"] });
       queryClient.invalidateQueries({ queryKey: ["skills", "discoverable"] });
@@ -1,5 +1,13 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { EnvConflict, BackupInfo } from "@ai-assistant/types/env";
+import type {
+  EnvConflict,
+  BackupInfo,
+  RcAssignment,
+  EnvFixMode,
+  EnvFixTarget,
+  EnvFixPlan,
+  EnvFixRecord,
+} from "@ai-assistant/types/env";
 
 /**
  * 环境变量管理 API
@@ -35,6 +43,49 @@ export async function restoreEnvBackup(backupPath: string): Promise<void> {
   return invoke<void>("restore_env_backup", { backupPath });
 }
 
+/**
+ * 扫描 shell 配置文件 (含 source 引入的文件) 中的环境变量赋值
+ * @param appType 应用类型 ("claude" | "codex" | "gemini")
+ */
+export async function scanEnvRcFiles(
+  appType: string,
+): Promise<RcAssignment[]> {
+  return invoke<RcAssignment[]>("scan_env_rc_files", { app: appType });
+}
+
+/**
+ * 预览禁用选中的赋值语句 (返回 unified diff)
+ */
+export async function previewEnvFix(
+  targets: EnvFixTarget[],
+  mode?: EnvFixMode,
+): Promise<EnvFixPlan> {
+  return invoke<EnvFixPlan>("preview_env_fix", { targets, mode });
+}
+
+/**
+ * 预览恢复某次修复
+ */
+export async function previewEnvFixRestore(
+  fixId: string,
+): Promise<EnvFixPlan> {
+  return invoke<EnvFixPlan>("preview_env_fix_restore", { fixId });
+}
+
+/**
+ * 执行预览过的修复 / 恢复计划 (文件在预览后被修改时会拒绝执行)
+ */
+export async function applyEnvFix(plan: EnvFixPlan): Promise<void> {
+  return invoke<void>("apply_env_fix", { plan });
+}
+
+/**
+ * 列出仍可恢复的修复记录
+ */
+export async function listEnvFixes(): Promise<EnvFixRecord[]> {
+  return invoke<EnvFixRecord[]>("list_env_fixes");
+}
+
 /**
  * 检查所有应用的环境变量冲突
  * @returns 按应用类型分组的环境变量冲突
@@ -2,7 +2,7 @@ import { invoke } from "@tauri-apps/api/core";
 
 // ========== 类型定义 ==========
 
-export type AppType = "claude" | "codex" | "gemini" | "opencode" | "cursor";
+export type AppType = "claude" | "codex" | "gemini" | "opencode" | "cursor" | "qwen";
 export type SkillsShCategory = "all-time" | "trending" | "hot";
 
 /** Skill 应用启用状态 */
@@ -12,6 +12,7 @@ export interface SkillApps {
   gemini: boolean;
   opencode: boolean;
   cursor: boolean;
+  qwen: boolean;
 }
 
 /** 已安装的 Skill（v3.10.0+ 统一结构） */
@@ -29,6 +30,41 @@ export interface InstalledSkill {
   installedAt: number;
 }
 
+/** 安全扫描风险等级 */
+export type SkillScanSeverity = "info" | "warning" | "critical";
+
+/** 单条扫描发现 */
+export interface SkillScanFinding {
+  ruleId: string;
+  severity: SkillScanSeverity;
+  path: string;
+  /** 行号（从 1 开始，文件级发现为 0） */
+  line: number;
+  excerpt: string;
+  message: string;
+}
+
+/** 安装前安全扫描报告 */
+export interface SkillScanReport {
+  frontmatter: {
+    present: boolean;
+    valid: boolean;
+    name?: string;
+    description?: string;
+    errors: string[];
+  };
+  filesScanned: number;
+  executables: string[];
+  scripts: string[];
+  findings: SkillScanFinding[];
+  maxSeverity?: SkillScanSeverity;
+  /** 目录内容指纹，确认报告后随安装请求回传 */
+  fingerprint: string;
+  /** 是否需要用户确认后才能安装 */
+  requiresAcknowledgement: boolean;
+  truncated: boolean;
+}
+
 /** 可发现的 Skill（来自仓库） */
 export interface DiscoverableSkill {
   key: string;
@@ -332,12 +368,26 @@ export const skillsApi = {
     return await invoke("read_installed_skill_file", { skillId, relativePath });
   },
 
-  /** 安装 Skill（统一安装） */
+  /** 安装前扫描 Skill，返回安全扫描报告 */
+  async scanBeforeInstall(skill: DiscoverableSkill): Promise<SkillScanReport> {
+    return await invoke("scan_skill_before_install", { skill });
+  },
+
+  /**
+   * 安装 Skill（统一安装）
+   *
+   * 扫描报告需要确认时，须传入已确认报告的 fingerprint
+   */
   async installUnified(
     skill: DiscoverableSkill,
     currentApp: AppType,
+    acknowledgedFingerprint?: string,
   ): Promise<InstalledSkill> {
-    return await invoke("install_skill_unified", { skill, currentApp });
+    return await invoke("install_skill_unified", {
+      skill,
+      currentApp,
+      acknowledgedFingerprint: acknowledgedFingerprint ?? null,
+    });
   },
 
   /** 卸载 Skill（统一卸载） */
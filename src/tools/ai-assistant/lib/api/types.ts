@@ -1,2 +1,8 @@
 // 前端统一使用 AppId 作为应用标识（与后端命令参数 `app` 一致）
-export type AppId = "claude" | "codex" | "gemini" | "opencode" | "cursor";
+export type AppId =
+  | "claude"
+  | "codex"
+  | "gemini"
+  | "opencode"
+  | "cursor"
+  | "qwen";
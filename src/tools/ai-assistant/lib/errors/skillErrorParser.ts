@@ -39,6 +39,7 @@ function getErrorI18nKey(code: string): string {
     GET_HOME_DIR_FAILED: "skills.error.getHomeDirFailed",
     IMPORT_FAILED: "skills.error.importFailed",
     SCAN_FAILED: "skills.error.scanFailed",
+    SKILL_SCAN_ACK_REQUIRED: "skills.error.scanAckRequired",
   };
 
   return mapping[code] || "skills.error.unknownError";
@@ -54,6 +55,7 @@ function getSuggestionI18nKey(suggestion: string): string {
     retryLater: "skills.error.suggestion.retryLater",
     checkRepoUrl: "skills.error.suggestion.checkRepoUrl",
     checkPermission: "skills.error.suggestion.checkPermission",
+    reviewScanReport: "skills.error.suggestion.reviewScanReport",
     http403: "skills.error.http403",
     http404: "skills.error.http404",
     http429: "skills.error.http429",
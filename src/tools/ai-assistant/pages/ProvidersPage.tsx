@@ -54,6 +54,7 @@ export default function ProvidersPage() {
     gemini: true,
     opencode: true,
     cursor: true,
+    qwen: true,
   };
 
   // 获取代理服务状态
@@ -85,6 +85,7 @@ export interface UsageResult {
   success: boolean;
   data?: UsageData[]; // 改为数组，支持返回多个套餐
   error?: string;
+  logs?: string[]; // 脚本 console 输出（可选）
 }
 
 // 供应商单独的模型测试配置
@@ -147,6 +148,7 @@ export interface VisibleApps {
   gemini: boolean;
   opencode: boolean;
   cursor: boolean;
+  qwen: boolean;
 }
 
 // 应用设置类型（用于设置对话框与 Tauri API）
@@ -215,6 +217,7 @@ export interface McpApps {
   gemini: boolean;
   opencode: boolean;
   cursor: boolean;
+  qwen: boolean;
 }
 
 // MCP 服务器条目（v3.7.0 统一结构）
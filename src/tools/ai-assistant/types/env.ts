@@ -27,3 +27,69 @@ export interface BackupInfo {
   /** 被备份的环境变量冲突列表 */
   conflicts: EnvConflict[];
 }
+
+/**
+ * shell 配置文件中的环境变量赋值
+ */
+export interface RcAssignment {
+  /** 配置文件路径 */
+  file: string;
+  /** 起始行号 (从 1 开始) */
+  line: number;
+  /** 结束行号 (多行值时与 line 不同) */
+  endLine: number;
+  varName: string;
+  varValue: string;
+  shell: "posix" | "fish";
+  /** 是否位于 if / case / 循环 / 函数块内 */
+  inConditional: boolean;
+  /** 同一行是否还有其他语句 (会被一并禁用) */
+  sharesLine: boolean;
+  /** 通过 source 引入该文件的配置文件 */
+  sourcedFrom?: string;
+}
+
+/**
+ * 禁用方式: auto 在条件块内使用 guard, 其余注释掉
+ */
+export type EnvFixMode = "auto" | "comment" | "guard";
+
+/**
+ * 选中要修复的环境变量
+ */
+export interface EnvFixTarget {
+  file: string;
+  line: number;
+  varName: string;
+}
+
+/**
+ * 单个文件的待写入修改
+ */
+export interface EnvFixFile {
+  path: string;
+  /** 预览时文件内容的 SHA-256 */
+  checksum: string;
+  /** unified diff 预览 */
+  diff: string;
+}
+
+/**
+ * 修复 / 恢复计划 (预览后原样传回以执行)
+ */
+export interface EnvFixPlan {
+  fixId: string;
+  action: "fix" | "restore";
+  mode: EnvFixMode;
+  targets: EnvFixTarget[];
+  files: EnvFixFile[];
+}
+
+/**
+ * 可恢复的修复记录
+ */
+export interface EnvFixRecord {
+  fixId: string;
+  file: string;
+  lines: number[];
+}
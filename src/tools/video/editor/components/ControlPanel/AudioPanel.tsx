@@ -0,0 +1,273 @@
+import { useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { open as openDialog } from '@tauri-apps/plugin-dialog';
+import { useVideoStore } from '../../store/videoStore';
+import { useOperationQueue } from '../../contexts/OperationQueueContext';
+import { JOB_CANCELLED } from '../../types';
+import type {
+  AudioExtractParams,
+  AudioFormat,
+  AudioReplaceParams,
+  LoudnessStats,
+  LoudnormParams,
+  LoudnormResult,
+} from '../../types';
+import { Card, CardHeader, CardTitle, CardContent } from '@/components/ui/card';
+import { Label } from '@/components/ui/label';
+import { Slider } from '@/components/ui/slider';
+import { Switch } from '@/components/ui/switch';
+import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue, SelectGroup } from '@/components/ui/select';
+import { Button } from '@/components/ui/button';
+import { Music, Plus } from 'lucide-react';
+
+const FORMATS: { value: AudioFormat; label: string }[] = [
+  { value: 'mp3', label: 'MP3' },
+  { value: 'aac', label: 'AAC (.m4a)' },
+  { value: 'opus', label: 'Opus' },
+  { value: 'wav', label: 'WAV - 无损' },
+  { value: 'flac', label: 'FLAC - 无损' },
+];
+
+// 常见平台的目标响度
+const LOUDNESS_TARGETS: { value: string; label: string; params: LoudnormParams }[] = [
+  { value: 'streaming', label: '-16 LUFS - 网络视频/播客', params: { target_i: -16, target_tp: -1.5, target_lra: 11 } },
+  { value: 'music', label: '-14 LUFS - 音乐平台', params: { target_i: -14, target_tp: -1, target_lra: 11 } },
+  { value: 'broadcast', label: '-23 LUFS - 广播电视 (EBU R128)', params: { target_i: -23, target_tp: -1, target_lra: 7 } },
+];
+
+function formatLufs(value: number): string {
+  return Number.isFinite(value) ? value.toFixed(1) : '-∞';
+}
+
+export function AudioPanel() {
+  const { currentVideo, isProcessing, startJob, finishJob } = useVideoStore();
+  const { addToQueue } = useOperationQueue();
+  const [format, setFormat] = useState<AudioFormat>('mp3');
+  const [audioPath, setAudioPath] = useState('');
+  const [mode, setMode] = useState<AudioReplaceParams['mode']>('replace');
+  const [volume, setVolume] = useState(1);
+  const [originalVolume, setOriginalVolume] = useState(1);
+  const [loopAudio, setLoopAudio] = useState(false);
+  const [target, setTarget] = useState('streaming');
+  const [stats, setStats] = useState<LoudnessStats | null>(null);
+
+  const extractParams: AudioExtractParams = { format };
+  const replaceParams: AudioReplaceParams = {
+    audio_path: audioPath,
+    mode,
+    volume,
+    original_volume: originalVolume,
+    loop_audio: loopAudio,
+  };
+  const loudnormParams = LOUDNESS_TARGETS.find((t) => t.value === target)!.params;
+
+  const run = async <T,>(command: string, params: object | undefined, label: string): Promise<T | undefined> => {
+    if (!currentVideo) return;
+
+    const jobId = startJob(`正在${label}...`);
+
+    try {
+      return await invoke<T>(command, {
+        inputPath: currentVideo.path,
+        ...(params ? { params } : {}),
+        jobId,
+      });
+    } catch (error) {
+      if (error !== JOB_CANCELLED) alert(`${label}失败: ${error}`);
+    } finally {
+      finishJob();
+    }
+  };
+
+  const runToFile = async (command: string, params: object | undefined, label: string) => {
+    const outputPath = await run<string>(command, params, label);
+    if (outputPath) alert(`${label}完成: ${outputPath}`);
+  };
+
+  const handleSelectAudio = async () => {
+    const selected = await openDialog({
+      multiple: false,
+      filters: [{ name: '音频', extensions: ['mp3', 'm4a', 'aac', 'wav', 'flac', 'ogg', 'opus'] }],
+    });
+    if (typeof selected === 'string') setAudioPath(selected);
+  };
+
+  const handleMeasure = async () => {
+    const measured = await run<LoudnessStats>('measure_loudness', loudnormParams, '测量响度');
+    if (measured) setStats(measured);
+  };
+
+  const handleNormalize = async () => {
+    const result = await run<LoudnormResult>('normalize_loudness', loudnormParams, '响度标准化');
+    if (!result) return;
+    setStats(result.stats);
+    alert(`响度标准化完成: ${result.output_path}`);
+  };
+
+  const queueButton = (onClick: () => void, disabled = false) => (
+    <Button
+      onClick={onClick}
+      disabled={!currentVideo || disabled}
+      variant="secondary"
+      size="icon"
+      title="添加到操作队列"
+    >
+      <Plus className="w-4 h-4" />
+      队列
+    </Button>
+  );
+
+  return (
+    <div className="space-y-4">
+      <Card>
+        <CardHeader>
+          <CardTitle>提取音频</CardTitle>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          <div className="space-y-2">
+            <Label htmlFor="audio-format">格式</Label>
+            <Select value={format} onValueChange={(value) => setFormat(value as AudioFormat)} disabled={isProcessing}>
+              <SelectTrigger id="audio-format">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectGroup>
+                  {FORMATS.map((f) => (
+                    <SelectItem key={f.value} value={f.value}>{f.label}</SelectItem>
+                  ))}
+                </SelectGroup>
+              </SelectContent>
+            </Select>
+          </div>
+          <div className="flex gap-2">
+            <Button onClick={() => runToFile('extract_audio', extractParams, '提取音频')} disabled={isProcessing} className="flex-1">
+              {isProcessing ? '处理中...' : '立即执行'}
+            </Button>
+            {queueButton(() => addToQueue({ type: 'extract_audio', name: `提取音频 (${format})`, params: extractParams }))}
+          </div>
+        </CardContent>
+      </Card>
+
+      <Card>
+        <CardHeader>
+          <CardTitle>替换 / 混入音频</CardTitle>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          <Button onClick={handleSelectAudio} variant="outline" className="w-full justify-start" disabled={isProcessing}>
+            <Music className="w-4 h-4 mr-2" />
+            <span className="truncate">{audioPath ? audioPath.split(/[\\/]/).pop() : '选择音频文件'}</span>
+          </Button>
+
+          <div className="space-y-2">
+            <Label htmlFor="audio-mode">方式</Label>
+            <Select value={mode} onValueChange={(value) => setMode(value as AudioReplaceParams['mode'])} disabled={isProcessing}>
+              <SelectTrigger id="audio-mode">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectGroup>
+                  <SelectItem value="replace">替换原音轨</SelectItem>
+                  <SelectItem value="mix">与原音轨混合</SelectItem>
+                </SelectGroup>
+              </SelectContent>
+            </Select>
+          </div>
+
+          <div className="space-y-2">
+            <Label>外部音频音量: {Math.round(volume * 100)}%</Label>
+            <Slider value={[volume]} onValueChange={([v]) => setVolume(v)} min={0} max={2} step={0.05} disabled={isProcessing} />
+          </div>
+          {mode === 'mix' && (
+            <div className="space-y-2">
+              <Label>原音轨音量: {Math.round(originalVolume * 100)}%</Label>
+              <Slider value={[originalVolume]} onValueChange={([v]) => setOriginalVolume(v)} min={0} max={2} step={0.05} disabled={isProcessing} />
+            </div>
+          )}
+
+          <div className="flex items-center space-x-2">
+            <Switch id="loop-audio" checked={loopAudio} onCheckedChange={setLoopAudio} />
+            <Label htmlFor="loop-audio" className="text-sm cursor-pointer">音频较短时循环播放</Label>
+          </div>
+
+          <div className="flex gap-2">
+            <Button
+              onClick={() => runToFile('replace_audio', replaceParams, mode === 'mix' ? '混音' : '替换音频')}
+              disabled={isProcessing || !audioPath}
+              className="flex-1"
+            >
+              {isProcessing ? '处理中...' : '立即执行'}
+            </Button>
+            {queueButton(
+              () => addToQueue({ type: 'replace_audio', name: mode === 'mix' ? '混入音频' : '替换音频', params: replaceParams }),
+              !audioPath,
+            )}
+          </div>
+        </CardContent>
+      </Card>
+
+      <Card>
+        <CardHeader>
+          <CardTitle>响度标准化</CardTitle>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          <div className="space-y-2">
+            <Label htmlFor="loudness-target">目标响度</Label>
+            <Select value={target} onValueChange={setTarget} disabled={isProcessing}>
+              <SelectTrigger id="loudness-target">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectGroup>
+                  {LOUDNESS_TARGETS.map((t) => (
+                    <SelectItem key={t.value} value={t.value}>{t.label}</SelectItem>
+                  ))}
+                </SelectGroup>
+              </SelectContent>
+            </Select>
+            <p className="text-xs text-muted-foreground">两遍处理：先测量响度，再按测量结果线性调整</p>
+          </div>
+
+          {stats && (
+            <div className="grid grid-cols-3 gap-2 text-xs bg-muted/50 dark:bg-muted/20 rounded p-3">
+              <span className="text-muted-foreground">综合响度</span>
+              <span>{formatLufs(stats.input_i)} LUFS</span>
+              <span>→ {formatLufs(stats.output_i)} LUFS</span>
+              <span className="text-muted-foreground">真峰值</span>
+              <span>{formatLufs(stats.input_tp)} dBTP</span>
+              <span>→ {formatLufs(stats.output_tp)} dBTP</span>
+              <span className="text-muted-foreground">响度范围</span>
+              <span>{formatLufs(stats.input_lra)} LU</span>
+              <span>→ {formatLufs(stats.output_lra)} LU</span>
+            </div>
+          )}
+
+          <div className="flex gap-2">
+            <Button onClick={handleMeasure} disabled={isProcessing} variant="outline">
+              测量
+            </Button>
+            <Button onClick={handleNormalize} disabled={isProcessing} className="flex-1">
+              {isProcessing ? '处理中...' : '标准化'}
+            </Button>
+            {queueButton(() =>
+              addToQueue({ type: 'loudnorm', name: `响度标准化 (${loudnormParams.target_i} LUFS)`, params: loudnormParams }),
+            )}
+          </div>
+        </CardContent>
+      </Card>
+
+      <Card>
+        <CardHeader>
+          <CardTitle>静音</CardTitle>
+        </CardHeader>
+        <CardContent>
+          <div className="flex gap-2">
+            <Button onClick={() => runToFile('mute_video', undefined, '静音')} disabled={isProcessing} className="flex-1">
+              {isProcessing ? '处理中...' : '去除音轨'}
+            </Button>
+            {queueButton(() => addToQueue({ type: 'mute', name: '静音', params: {} }))}
+          </div>
+        </CardContent>
+      </Card>
+    </div>
+  );
+}
@@ -1,42 +1,85 @@
-import { useState } from 'react';
+import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useVideoStore } from '../../store/videoStore';
 import { useOperationQueue } from '../../contexts/OperationQueueContext';
+import { JOB_CANCELLED } from '../../types';
+import type { AudioCodec, CompressParams, EncoderCapabilities, VideoCodec } from '../../types';
 import { Card, CardHeader, CardTitle, CardContent } from '@/components/ui/card';
 import { Label } from '@/components/ui/label';
+import { Input } from '@/components/ui/input';
+import { Switch } from '@/components/ui/switch';
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue, SelectGroup } from '@/components/ui/select';
 import { Button } from '@/components/ui/button';
 import { Plus } from 'lucide-react';
 
 type CompressPreset = 'mobile' | 'web' | 'high_quality' | 'custom';
 
+const VIDEO_CODEC_NAMES: Record<string, string> = {
+  h264: 'H.264 - 兼容性最好',
+  h265: 'H.265 - 体积更小',
+  vp9: 'VP9',
+  av1: 'AV1 - 压缩率最高，编码较慢',
+};
+
+const AUDIO_CODEC_NAMES: Record<string, string> = {
+  aac: 'AAC',
+  opus: 'Opus',
+  mp3: 'MP3',
+};
+
 export function CompressPanel() {
-  const { currentVideo, isProcessing, setProcessing, setProgress, setOperation } = useVideoStore();
+  const { currentVideo, isProcessing, startJob, finishJob } = useVideoStore();
   const { addToQueue } = useOperationQueue();
   const [preset, setPreset] = useState<CompressPreset>('mobile');
+  const [capabilities, setCapabilities] = useState<EncoderCapabilities | null>(null);
+  const [codec, setCodec] = useState<VideoCodec>('h264');
+  const [targetSizeMb, setTargetSizeMb] = useState('');
+  const [audioCodec, setAudioCodec] = useState<AudioCodec | 'copy'>('aac');
+  const [audioBitrate, setAudioBitrate] = useState(128);
+  const [audioMono, setAudioMono] = useState(false);
+  const [stripAudio, setStripAudio] = useState(false);
+
+  // 只提供当前 FFmpeg 已安装的编码器
+  useEffect(() => {
+    invoke<EncoderCapabilities>('get_encoder_capabilities')
+      .then(setCapabilities)
+      .catch(console.error);
+  }, []);
+
+  const videoCodecs = capabilities?.video_codecs.map((c) => c.codec) ?? ['h264'];
+  const audioCodecs = capabilities?.audio_codecs.map((c) => c.codec) ?? ['aac'];
+  const targetSize = parseFloat(targetSizeMb);
+  const hasTargetSize = targetSize > 0;
+  const twoPass = capabilities?.video_codecs.find((c) => c.codec === codec)?.two_pass ?? false;
+
+  const buildParams = (): CompressParams => ({
+    preset,
+    // 不指定宽高，保持原分辨率
+    codec,
+    target_size_mb: hasTargetSize ? targetSize : undefined,
+    audio_codec: audioCodec,
+    audio_bitrate: audioBitrate,
+    audio_mono: audioMono,
+    strip_audio: stripAudio,
+  });
 
   const handleCompress = async () => {
     if (!currentVideo) return;
 
-    setProcessing(true);
-    setProgress(0);
-    setOperation('正在压缩视频...');
+    const jobId = startJob('正在压缩视频...');
 
     try {
       const outputPath = await invoke<string>('compress_video_command', {
         inputPath: currentVideo.path,
-        params: {
-          preset,
-          // 不指定宽高，保持原分辨率
-        }
+        params: buildParams(),
+        jobId,
       });
 
-      setProgress(100);
       alert(`压缩完成: ${outputPath}`);
     } catch (error) {
-      alert(`压缩失败: ${error}`);
+      if (error !== JOB_CANCELLED) alert(`压缩失败: ${error}`);
     } finally {
-      setProcessing(false);
+      finishJob();
     }
   };
 
@@ -52,11 +95,8 @@ export function CompressPanel() {
 
     addToQueue({
       type: 'compress',
-      name: `压缩 (${presetNames[preset]})`,
-      params: {
-        preset,
-        // 不指定宽高，保持原分辨率
-      }
+      name: hasTargetSize ? `压缩 (${targetSize} MB)` : `压缩 (${presetNames[preset]})`,
+      params: buildParams(),
     });
   };
 
@@ -68,7 +108,7 @@ export function CompressPanel() {
       <CardContent className="space-y-4">
         <div className="space-y-2">
           <Label htmlFor="preset">预设</Label>
-          <Select value={preset} onValueChange={(value) => setPreset(value as CompressPreset)} disabled={isProcessing}>
+          <Select value={preset} onValueChange={(value) => setPreset(value as CompressPreset)} disabled={isProcessing || hasTargetSize}>
             <SelectTrigger id="preset">
               <SelectValue />
             </SelectTrigger>
@@ -86,6 +126,114 @@ export function CompressPanel() {
           </p>
         </div>
 
+        <div className="space-y-2">
+          <Label htmlFor="target-size">目标大小(MB)</Label>
+          <Input
+            id="target-size"
+            type="number"
+            value={targetSizeMb}
+            onChange={(e) => setTargetSizeMb(e.target.value)}
+            placeholder="不限制，例如 25"
+            min="1"
+            step="1"
+            disabled={isProcessing}
+          />
+          {hasTargetSize && (
+            <p className="text-xs text-muted-foreground">
+              按视频时长计算码率{twoPass ? '，使用两遍编码' : ''}，忽略预设
+            </p>
+          )}
+        </div>
+
+        <div className="space-y-2">
+          <Label htmlFor="codec">视频编码</Label>
+          <Select value={codec} onValueChange={(value) => setCodec(value as VideoCodec)} disabled={isProcessing}>
+            <SelectTrigger id="codec">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              <SelectGroup>
+                {videoCodecs.map((c) => (
+                  <SelectItem key={c} value={c}>{VIDEO_CODEC_NAMES[c] ?? c}</SelectItem>
+                ))}
+              </SelectGroup>
+            </SelectContent>
+          </Select>
+        </div>
+
+        <div className="flex items-center space-x-2">
+          <Switch
+            id="strip-audio"
+            checked={stripAudio}
+            onCheckedChange={setStripAudio}
+            disabled={isProcessing}
+          />
+          <Label htmlFor="strip-audio" className="text-sm cursor-pointer">
+            去除音轨
+          </Label>
+        </div>
+
+        {!stripAudio && (
+          <>
+            <div className="grid grid-cols-2 gap-2">
+              <div className="space-y-2">
+                <Label htmlFor="audio-codec">音频编码</Label>
+                <Select
+                  value={audioCodec}
+                  onValueChange={(value) => {
+                    setAudioCodec(value as AudioCodec | 'copy');
+                    if (value === 'copy') setAudioMono(false);
+                  }}
+                  disabled={isProcessing}
+                >
+                  <SelectTrigger id="audio-codec">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectGroup>
+                      {audioCodecs.map((c) => (
+                        <SelectItem key={c} value={c}>{AUDIO_CODEC_NAMES[c] ?? c}</SelectItem>
+                      ))}
+                      <SelectItem value="copy">保留原音频</SelectItem>
+                    </SelectGroup>
+                  </SelectContent>
+                </Select>
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="audio-bitrate">音频码率(kbps)</Label>
+                <Select
+                  value={String(audioBitrate)}
+                  onValueChange={(value) => setAudioBitrate(Number(value))}
+                  disabled={isProcessing || audioCodec === 'copy'}
+                >
+                  <SelectTrigger id="audio-bitrate">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectGroup>
+                      {[64, 96, 128, 192, 256].map((kbps) => (
+                        <SelectItem key={kbps} value={String(kbps)}>{kbps}</SelectItem>
+                      ))}
+                    </SelectGroup>
+                  </SelectContent>
+                </Select>
+              </div>
+            </div>
+
+            <div className="flex items-center space-x-2">
+              <Switch
+                id="audio-mono"
+                checked={audioMono}
+                onCheckedChange={setAudioMono}
+                disabled={isProcessing || audioCodec === 'copy'}
+              />
+              <Label htmlFor="audio-mono" className="text-sm cursor-pointer">
+                单声道
+              </Label>
+            </div>
+          </>
+        )}
+
         <div className="flex gap-2">
           <Button
             onClick={handleCompress}
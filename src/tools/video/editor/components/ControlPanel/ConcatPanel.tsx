@@ -0,0 +1,155 @@
+import { useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { open as openDialog } from '@tauri-apps/plugin-dialog';
+import { useVideoStore } from '../../store/videoStore';
+import { useOperationQueue } from '../../contexts/OperationQueueContext';
+import { JOB_CANCELLED } from '../../types';
+import type { ConcatParams } from '../../types';
+import { Card, CardHeader, CardTitle, CardContent } from '@/components/ui/card';
+import { Label } from '@/components/ui/label';
+import { Switch } from '@/components/ui/switch';
+import { Button } from '@/components/ui/button';
+import { ArrowDown, ArrowUp, Plus, X } from 'lucide-react';
+
+const VIDEO_EXTENSIONS = ['mp4', 'mov', 'avi', 'wmv', 'mkv', 'flv', 'webm', 'm4v'];
+
+function fileName(path: string): string {
+  return path.split(/[\\/]/).pop() || path;
+}
+
+export function ConcatPanel() {
+  const { currentVideo, isProcessing, startJob, finishJob } = useVideoStore();
+  const { addToQueue } = useOperationQueue();
+  const [inputs, setInputs] = useState<string[]>([]);
+  const [reencode, setReencode] = useState(false);
+
+  const params: ConcatParams = { inputs, reencode };
+
+  const handleAddClips = async () => {
+    const selected = await openDialog({
+      multiple: true,
+      filters: [{ name: '视频', extensions: VIDEO_EXTENSIONS }],
+    });
+    if (!selected) return;
+    setInputs((prev) => [...prev, ...(Array.isArray(selected) ? selected : [selected])]);
+  };
+
+  const moveClip = (index: number, offset: number) => {
+    setInputs((prev) => {
+      const target = index + offset;
+      if (target < 0 || target >= prev.length) return prev;
+      const next = [...prev];
+      [next[index], next[target]] = [next[target], next[index]];
+      return next;
+    });
+  };
+
+  const handleConcat = async () => {
+    if (!currentVideo || inputs.length === 0) return;
+
+    const jobId = startJob('正在拼接视频...');
+
+    try {
+      const outputPath = await invoke<string>('concat_videos', {
+        inputPath: currentVideo.path,
+        params,
+        jobId,
+      });
+
+      alert(`拼接完成: ${outputPath}`);
+    } catch (error) {
+      if (error !== JOB_CANCELLED) alert(`拼接失败: ${error}`);
+    } finally {
+      finishJob();
+    }
+  };
+
+  const handleAddToQueue = () => {
+    if (!currentVideo || inputs.length === 0) return;
+
+    addToQueue({
+      type: 'concat',
+      name: `拼接 (${inputs.length + 1} 个片段)`,
+      params,
+    });
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle>拼接视频</CardTitle>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        <div className="space-y-2">
+          <Label>片段顺序</Label>
+          <div className="space-y-1">
+            <div className="px-3 py-2 bg-muted/50 dark:bg-muted/20 rounded text-sm truncate">
+              1. {currentVideo ? currentVideo.filename : '当前视频'}
+            </div>
+            {inputs.map((path, index) => (
+              <div
+                key={`${path}-${index}`}
+                className="flex items-center gap-1 px-3 py-1 bg-muted/50 dark:bg-muted/20 rounded text-sm"
+              >
+                <span className="flex-1 truncate" title={path}>
+                  {index + 2}. {fileName(path)}
+                </span>
+                <Button onClick={() => moveClip(index, -1)} variant="ghost" size="icon" title="上移" disabled={index === 0}>
+                  <ArrowUp className="w-4 h-4" />
+                </Button>
+                <Button
+                  onClick={() => moveClip(index, 1)}
+                  variant="ghost"
+                  size="icon"
+                  title="下移"
+                  disabled={index === inputs.length - 1}
+                >
+                  <ArrowDown className="w-4 h-4" />
+                </Button>
+                <Button
+                  onClick={() => setInputs((prev) => prev.filter((_, i) => i !== index))}
+                  variant="ghost"
+                  size="icon"
+                  title="移除"
+                >
+                  <X className="w-4 h-4" />
+                </Button>
+              </div>
+            ))}
+          </div>
+          <Button onClick={handleAddClips} variant="outline" className="w-full" disabled={isProcessing}>
+            <Plus className="w-4 h-4 mr-1" />
+            添加片段
+          </Button>
+        </div>
+
+        <div className="flex items-center space-x-2">
+          <Switch id="reencode" checked={reencode} onCheckedChange={setReencode} />
+          <Label htmlFor="reencode" className="text-sm cursor-pointer">
+            强制重新编码(片段格式不同时会自动重新编码)
+          </Label>
+        </div>
+
+        <div className="flex gap-2">
+          <Button
+            onClick={handleConcat}
+            disabled={isProcessing || inputs.length === 0}
+            className="flex-1"
+          >
+            {isProcessing ? '拼接中...' : '立即执行'}
+          </Button>
+          <Button
+            onClick={handleAddToQueue}
+            disabled={!currentVideo || inputs.length === 0}
+            variant="secondary"
+            size="icon"
+            title="添加到操作队列"
+          >
+            <Plus className="w-4 h-4" />
+            队列
+          </Button>
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
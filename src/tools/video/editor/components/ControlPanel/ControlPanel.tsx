@@ -4,20 +4,32 @@ import { SpeedPanel } from './SpeedPanel';
 import { ExtractPanel } from './ExtractPanel';
 import { TrimPanel } from './TrimPanel';
 import { GifPanel } from './GifPanel';
+import { ConcatPanel } from './ConcatPanel';
+import { CropPanel } from './CropPanel';
+import { WatermarkPanel } from './WatermarkPanel';
+import { AudioPanel } from './AudioPanel';
+import { SubtitlePanel } from './SubtitlePanel';
 import { OperationQueuePanel } from '../OperationQueuePanel';
+import { MediaJobsPanel } from '../MediaJobsPanel';
 
-type TabType = 'queue' | 'compress' | 'speed' | 'extract' | 'trim' | 'gif';
+type TabType = 'queue' | 'jobs' | 'compress' | 'speed' | 'extract' | 'trim' | 'gif' | 'concat' | 'crop' | 'watermark' | 'audio' | 'subtitle';
 
 export function ControlPanel() {
   const [activeTab, setActiveTab] = useState<TabType>('queue');
 
   const tabs = [
     { id: 'queue' as TabType, label: '队列', icon: '📋' },
+    { id: 'jobs' as TabType, label: '后台任务', icon: '⏳' },
     { id: 'compress' as TabType, label: '压缩', icon: '🗜️' },
     { id: 'speed' as TabType, label: '变速', icon: '⚡' },
     { id: 'extract' as TabType, label: '提取帧', icon: '🖼️' },
     { id: 'trim' as TabType, label: '截断', icon: '✂️' },
-    { id: 'gif' as TabType, label: '转GIF', icon: '🎞️' },
+    { id: 'gif' as TabType, label: '转动图', icon: '🎞️' },
+    { id: 'concat' as TabType, label: '拼接', icon: '🔗' },
+    { id: 'crop' as TabType, label: '画面', icon: '📐' },
+    { id: 'watermark' as TabType, label: '水印', icon: '💧' },
+    { id: 'audio' as TabType, label: '音频', icon: '🔊' },
+    { id: 'subtitle' as TabType, label: '字幕', icon: '💬' },
   ];
 
   return (
@@ -45,11 +57,17 @@ export function ControlPanel() {
       {/* 面板内容 */}
       <div>
         {activeTab === 'queue' && <OperationQueuePanel />}
+        {activeTab === 'jobs' && <MediaJobsPanel />}
         {activeTab === 'compress' && <CompressPanel />}
         {activeTab === 'speed' && <SpeedPanel />}
         {activeTab === 'extract' && <ExtractPanel />}
         {activeTab === 'trim' && <TrimPanel />}
         {activeTab === 'gif' && <GifPanel />}
+        {activeTab === 'concat' && <ConcatPanel />}
+        {activeTab === 'crop' && <CropPanel />}
+        {activeTab === 'watermark' && <WatermarkPanel />}
+        {activeTab === 'audio' && <AudioPanel />}
+        {activeTab === 'subtitle' && <SubtitlePanel />}
       </div>
     </div>
   );
@@ -0,0 +1,198 @@
+import { useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { useVideoStore } from '../../store/videoStore';
+import { useOperationQueue } from '../../contexts/OperationQueueContext';
+import { JOB_CANCELLED } from '../../types';
+import type { CropParams, RotateParams } from '../../types';
+import { Card, CardHeader, CardTitle, CardContent } from '@/components/ui/card';
+import { Label } from '@/components/ui/label';
+import { Input } from '@/components/ui/input';
+import { Switch } from '@/components/ui/switch';
+import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue, SelectGroup } from '@/components/ui/select';
+import { Button } from '@/components/ui/button';
+import { Plus } from 'lucide-react';
+
+const ASPECT_PRESETS = [
+  { value: '9:16', label: '9:16 - 竖屏短视频' },
+  { value: '1:1', label: '1:1 - 正方形' },
+  { value: '4:5', label: '4:5 - 竖版贴文' },
+  { value: '16:9', label: '16:9 - 横屏' },
+  { value: 'custom', label: '自定义区域' },
+];
+
+/**
+ * 画面面板：裁剪与旋转/翻转
+ */
+export function CropPanel() {
+  const { currentVideo, isProcessing, startJob, finishJob } = useVideoStore();
+  const { addToQueue } = useOperationQueue();
+  const [aspect, setAspect] = useState('9:16');
+  const [region, setRegion] = useState({ x: 0, y: 0, width: 0, height: 0 });
+  const [rotation, setRotation] = useState<RotateParams['rotation']>(90);
+  const [flipHorizontal, setFlipHorizontal] = useState(false);
+  const [flipVertical, setFlipVertical] = useState(false);
+
+  const cropParams: CropParams = aspect === 'custom' ? region : { aspect };
+  const rotateParams: RotateParams = {
+    rotation,
+    flip_horizontal: flipHorizontal,
+    flip_vertical: flipVertical,
+  };
+  const rotateName = [
+    rotation ? `旋转 ${rotation}°` : '',
+    flipHorizontal ? '水平翻转' : '',
+    flipVertical ? '垂直翻转' : '',
+  ].filter(Boolean).join(' + ');
+
+  const run = async (command: string, params: CropParams | RotateParams, label: string) => {
+    if (!currentVideo) return;
+
+    const jobId = startJob(`正在${label}...`);
+
+    try {
+      const outputPath = await invoke<string>(command, {
+        inputPath: currentVideo.path,
+        params,
+        jobId,
+      });
+
+      alert(`${label}完成: ${outputPath}`);
+    } catch (error) {
+      if (error !== JOB_CANCELLED) alert(`${label}失败: ${error}`);
+    } finally {
+      finishJob();
+    }
+  };
+
+  const updateRegion = (key: keyof typeof region, value: string) => {
+    setRegion((prev) => ({ ...prev, [key]: parseInt(value) || 0 }));
+  };
+
+  return (
+    <div className="space-y-4">
+      <Card>
+        <CardHeader>
+          <CardTitle>裁剪画面</CardTitle>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          <div className="space-y-2">
+            <Label htmlFor="aspect">画面比例</Label>
+            <Select value={aspect} onValueChange={setAspect} disabled={isProcessing}>
+              <SelectTrigger id="aspect">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectGroup>
+                  {ASPECT_PRESETS.map((preset) => (
+                    <SelectItem key={preset.value} value={preset.value}>{preset.label}</SelectItem>
+                  ))}
+                </SelectGroup>
+              </SelectContent>
+            </Select>
+            {aspect !== 'custom' && (
+              <p className="text-xs text-muted-foreground">居中裁剪出该比例的最大区域</p>
+            )}
+          </div>
+
+          {aspect === 'custom' && (
+            <div className="grid grid-cols-2 gap-2">
+              {(['x', 'y', 'width', 'height'] as const).map((key) => (
+                <div key={key} className="space-y-1">
+                  <Label htmlFor={`crop-${key}`}>
+                    {{ x: 'X', y: 'Y', width: '宽度', height: '高度' }[key]}
+                  </Label>
+                  <Input
+                    id={`crop-${key}`}
+                    type="number"
+                    value={region[key]}
+                    onChange={(e) => updateRegion(key, e.target.value)}
+                    min="0"
+                    disabled={isProcessing}
+                  />
+                </div>
+              ))}
+              {currentVideo && (
+                <p className="col-span-2 text-xs text-muted-foreground">
+                  原画面 {currentVideo.width}x{currentVideo.height}
+                </p>
+              )}
+            </div>
+          )}
+
+          <div className="flex gap-2">
+            <Button onClick={() => run('crop_video', cropParams, '裁剪')} disabled={isProcessing} className="flex-1">
+              {isProcessing ? '处理中...' : '立即执行'}
+            </Button>
+            <Button
+              onClick={() => addToQueue({ type: 'crop', name: `裁剪 (${aspect === 'custom' ? '自定义' : aspect})`, params: cropParams })}
+              disabled={!currentVideo}
+              variant="secondary"
+              size="icon"
+              title="添加到操作队列"
+            >
+              <Plus className="w-4 h-4" />
+              队列
+            </Button>
+          </div>
+        </CardContent>
+      </Card>
+
+      <Card>
+        <CardHeader>
+          <CardTitle>旋转 / 翻转</CardTitle>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          <div className="space-y-2">
+            <Label htmlFor="rotation">顺时针旋转</Label>
+            <Select
+              value={String(rotation)}
+              onValueChange={(value) => setRotation(Number(value) as RotateParams['rotation'])}
+              disabled={isProcessing}
+            >
+              <SelectTrigger id="rotation">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectGroup>
+                  <SelectItem value="0">不旋转</SelectItem>
+                  <SelectItem value="90">90°</SelectItem>
+                  <SelectItem value="180">180°</SelectItem>
+                  <SelectItem value="270">270°</SelectItem>
+                </SelectGroup>
+              </SelectContent>
+            </Select>
+          </div>
+
+          <div className="flex items-center space-x-2">
+            <Switch id="flip-horizontal" checked={flipHorizontal} onCheckedChange={setFlipHorizontal} />
+            <Label htmlFor="flip-horizontal" className="text-sm cursor-pointer">水平翻转</Label>
+          </div>
+          <div className="flex items-center space-x-2">
+            <Switch id="flip-vertical" checked={flipVertical} onCheckedChange={setFlipVertical} />
+            <Label htmlFor="flip-vertical" className="text-sm cursor-pointer">垂直翻转</Label>
+          </div>
+
+          <div className="flex gap-2">
+            <Button
+              onClick={() => run('rotate_video', rotateParams, '旋转')}
+              disabled={isProcessing || !rotateName}
+              className="flex-1"
+            >
+              {isProcessing ? '处理中...' : '立即执行'}
+            </Button>
+            <Button
+              onClick={() => addToQueue({ type: 'rotate', name: rotateName, params: rotateParams })}
+              disabled={!currentVideo || !rotateName}
+              variant="secondary"
+              size="icon"
+              title="添加到操作队列"
+            >
+              <Plus className="w-4 h-4" />
+              队列
+            </Button>
+          </div>
+        </CardContent>
+      </Card>
+    </div>
+  );
+}
@@ -1,48 +1,88 @@
 import { useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useVideoStore } from '../../store/videoStore';
+import { useOperationQueue } from '../../contexts/OperationQueueContext';
+import { JOB_CANCELLED } from '../../types';
+import type { ExtractFramesParams, ExtractMode } from '../../types';
 import { Card, CardHeader, CardTitle, CardContent } from '@/components/ui/card';
 import { Label } from '@/components/ui/label';
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue, SelectGroup } from '@/components/ui/select';
 import { Slider } from '@/components/ui/slider';
+import { Switch } from '@/components/ui/switch';
 import { Input } from '@/components/ui/input';
 import { Button } from '@/components/ui/button';
+import { Plus } from 'lucide-react';
 
-type ExtractMode = 'single' | 'interval' | 'uniform';
+const MODE_NAMES: Record<ExtractMode, string> = {
+  single: '单帧',
+  interval: '间隔提取',
+  uniform: '均匀提取',
+  scene: '场景变化',
+  keyframe: '仅关键帧',
+};
 
 export function ExtractPanel() {
-  const { currentVideo, isProcessing, setProcessing } = useVideoStore();
+  const { currentVideo, isProcessing, startJob, finishJob } = useVideoStore();
+  const { addToQueue } = useOperationQueue();
   const [mode, setMode] = useState<ExtractMode>('single');
   const [format, setFormat] = useState<'jpg' | 'png' | 'webp'>('jpg');
   const [quality, setQuality] = useState(90);
   const [interval, setInterval] = useState(1);
   const [count, setCount] = useState(10);
+  const [sceneThreshold, setSceneThreshold] = useState(0.3);
+  const [skipBlack, setSkipBlack] = useState(false);
+  const [skipDuplicates, setSkipDuplicates] = useState(false);
+  const [contactSheet, setContactSheet] = useState(false);
+  const [columns, setColumns] = useState(4);
+  const [maxFrames, setMaxFrames] = useState(24);
+
+  const buildParams = (outputDir: string): ExtractFramesParams => ({
+    mode,
+    format,
+    quality,
+    interval: mode === 'interval' ? interval : undefined,
+    count: mode === 'uniform' ? count : undefined,
+    output_dir: outputDir,
+    scene_threshold: mode === 'scene' ? sceneThreshold : undefined,
+    skip_black: skipBlack,
+    skip_duplicates: skipDuplicates,
+    contact_sheet:
+      contactSheet && mode !== 'single'
+        ? { columns, thumb_width: 320, max_frames: maxFrames, timestamps: true }
+        : undefined,
+  });
 
   const handleExtract = async () => {
     if (!currentVideo) return;
 
-    setProcessing(true);
+    const jobId = startJob('正在提取帧...');
 
     try {
       const result = await invoke<string[]>('extract_frames', {
         inputPath: currentVideo.path,
-        params: {
-          mode,
-          format,
-          quality,
-          interval: mode === 'interval' ? interval : undefined,
-          count: mode === 'uniform' ? count : undefined,
-          outputDir: './extracted_frames'
-        }
+        // 输出到视频旁的 {文件名}_frames 目录
+        params: buildParams(`${currentVideo.path.replace(/\.[^.\\/]+$/, '')}_frames`),
+        jobId,
       });
-      alert(`提取完成,生成了${result.length}帧`);
+      const frames = contactSheet && mode !== 'single' ? result.length - 1 : result.length;
+      alert(`提取完成,生成了${frames}帧${frames < result.length ? `\n联系表: ${result[result.length - 1]}` : ''}`);
     } catch (error) {
-      alert(`提取失败: ${error}`);
+      if (error !== JOB_CANCELLED) alert(`提取失败: ${error}`);
     } finally {
-      setProcessing(false);
+      finishJob();
     }
   };
 
+  const handleAddToQueue = () => {
+    if (!currentVideo) return;
+
+    addToQueue({
+      type: 'extract_frames',
+      name: `提取帧 (${MODE_NAMES[mode]})`,
+      params: buildParams(''),
+    });
+  };
+
   return (
     <Card>
       <CardHeader>
@@ -57,9 +97,9 @@ export function ExtractPanel() {
             </SelectTrigger>
             <SelectContent>
               <SelectGroup>
-                <SelectItem value="single">单帧</SelectItem>
-                <SelectItem value="interval">间隔提取</SelectItem>
-                <SelectItem value="uniform">均匀提取</SelectItem>
+                {(Object.keys(MODE_NAMES) as ExtractMode[]).map((m) => (
+                  <SelectItem key={m} value={m}>{MODE_NAMES[m]}</SelectItem>
+                ))}
               </SelectGroup>
             </SelectContent>
           </Select>
@@ -123,13 +163,84 @@ export function ExtractPanel() {
           </div>
         )}
 
-        <Button
-          onClick={handleExtract}
-          disabled={isProcessing}
-          className="w-full"
-        >
-          {isProcessing ? '提取中...' : '开始提取'}
-        </Button>
+        {mode === 'scene' && (
+          <div className="space-y-3">
+            <Label htmlFor="scene-threshold">场景变化阈值: {sceneThreshold.toFixed(2)}</Label>
+            <Slider
+              id="scene-threshold"
+              min={0.05}
+              max={0.9}
+              step={0.05}
+              value={[sceneThreshold]}
+              onValueChange={(value) => setSceneThreshold(value[0])}
+              disabled={isProcessing}
+            />
+            <p className="text-xs text-muted-foreground">阈值越小，提取的画面越多</p>
+          </div>
+        )}
+
+        {mode !== 'single' && (
+          <div className="space-y-3">
+            <div className="flex items-center space-x-2">
+              <Switch id="skip-black" checked={skipBlack} onCheckedChange={setSkipBlack} />
+              <Label htmlFor="skip-black" className="text-sm cursor-pointer">跳过黑帧</Label>
+            </div>
+            <div className="flex items-center space-x-2">
+              <Switch id="skip-duplicates" checked={skipDuplicates} onCheckedChange={setSkipDuplicates} />
+              <Label htmlFor="skip-duplicates" className="text-sm cursor-pointer">跳过重复画面</Label>
+            </div>
+            <div className="flex items-center space-x-2">
+              <Switch id="contact-sheet" checked={contactSheet} onCheckedChange={setContactSheet} />
+              <Label htmlFor="contact-sheet" className="text-sm cursor-pointer">生成联系表(带时间戳的拼图)</Label>
+            </div>
+            {contactSheet && (
+              <div className="grid grid-cols-2 gap-2">
+                <div className="space-y-1">
+                  <Label htmlFor="sheet-columns" className="text-xs">列数</Label>
+                  <Input
+                    id="sheet-columns"
+                    type="number"
+                    value={columns}
+                    onChange={(e) => setColumns(Math.max(1, parseInt(e.target.value) || 1))}
+                    min="1"
+                    disabled={isProcessing}
+                  />
+                </div>
+                <div className="space-y-1">
+                  <Label htmlFor="sheet-max-frames" className="text-xs">最多帧数</Label>
+                  <Input
+                    id="sheet-max-frames"
+                    type="number"
+                    value={maxFrames}
+                    onChange={(e) => setMaxFrames(Math.max(1, parseInt(e.target.value) || 1))}
+                    min="1"
+                    disabled={isProcessing}
+                  />
+                </div>
+              </div>
+            )}
+          </div>
+        )}
+
+        <div className="flex gap-2">
+          <Button
+            onClick={handleExtract}
+            disabled={isProcessing}
+            className="flex-1"
+          >
+            {isProcessing ? '提取中...' : '开始提取'}
+          </Button>
+          <Button
+            onClick={handleAddToQueue}
+            disabled={!currentVideo}
+            variant="secondary"
+            size="icon"
+            title="添加到操作队列"
+          >
+            <Plus className="w-4 h-4" />
+            队列
+          </Button>
+        </div>
       </CardContent>
     </Card>
   );
@@ -1,45 +1,121 @@
 import { useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useVideoStore } from '../../store/videoStore';
+import { useOperationQueue } from '../../contexts/OperationQueueContext';
+import { JOB_CANCELLED } from '../../types';
+import type { AnimationFormat, DitherMode, PaletteStatsMode, ToGifParams } from '../../types';
 import { Card, CardHeader, CardTitle, CardContent } from '@/components/ui/card';
 import { Label } from '@/components/ui/label';
+import { Input } from '@/components/ui/input';
 import { Slider } from '@/components/ui/slider';
+import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue, SelectGroup } from '@/components/ui/select';
 import { Button } from '@/components/ui/button';
+import { Plus } from 'lucide-react';
+
+const FORMATS: { value: AnimationFormat; name: string; label: string }[] = [
+  { value: 'gif', name: 'GIF', label: 'GIF - 兼容性最好' },
+  { value: 'webp', name: 'WebP', label: 'WebP - 体积更小' },
+  { value: 'apng', name: 'APNG', label: 'APNG - 支持半透明' },
+];
+
+const STATS_MODES: { value: PaletteStatsMode; label: string }[] = [
+  { value: 'full', label: '全局调色板' },
+  { value: 'diff', label: '侧重变化区域' },
+  { value: 'single', label: '逐帧调色板 - 颜色更准，体积更大' },
+];
+
+const DITHER_MODES: { value: DitherMode; label: string }[] = [
+  { value: 'sierra', label: 'Sierra' },
+  { value: 'floyd', label: 'Floyd-Steinberg' },
+  { value: 'bayer', label: 'Bayer - 体积更小' },
+  { value: 'none', label: '不抖动' },
+];
 
 export function GifPanel() {
-  const { currentVideo, timelineStart, timelineEnd } = useVideoStore();
+  const { currentVideo, timelineStart, timelineEnd, isProcessing, startJob, finishJob } = useVideoStore();
+  const { addToQueue } = useOperationQueue();
+  const [format, setFormat] = useState<AnimationFormat>('gif');
   const [fps, setFps] = useState(10);
   const [width, setWidth] = useState(480);
   const [colors, setColors] = useState(256);
+  const [statsMode, setStatsMode] = useState<PaletteStatsMode>('full');
+  const [ditherMode, setDitherMode] = useState<DitherMode>('sierra');
+  const [bayerScale, setBayerScale] = useState(2);
+  const [quality, setQuality] = useState(75);
+  const [loopCount, setLoopCount] = useState(0);
+  const [targetSizeMb, setTargetSizeMb] = useState('');
+
+  const targetSize = parseFloat(targetSizeMb);
+  const usesPalette = format !== 'webp';
+
+  const params: ToGifParams = {
+    start_time: timelineStart,
+    end_time: timelineEnd,
+    fps,
+    width,
+    colors,
+    dither: ditherMode !== 'none',
+    format,
+    stats_mode: statsMode,
+    dither_mode: ditherMode,
+    bayer_scale: bayerScale,
+    loop_count: loopCount,
+    quality,
+    target_size_mb: targetSize > 0 ? targetSize : undefined,
+  };
 
   const handleConvert = async () => {
     if (!currentVideo) return;
 
+    const jobId = startJob('正在生成动图...');
+
     try {
       const outputPath = await invoke<string>('convert_to_gif', {
         inputPath: currentVideo.path,
-        params: {
-          startTime: timelineStart,
-          endTime: timelineEnd,
-          fps,
-          width,
-          colors,
-          dither: true
-        }
+        params,
+        jobId,
       });
 
       alert(`转换完成: ${outputPath}`);
     } catch (error) {
-      alert(`转换失败: ${error}`);
+      if (error !== JOB_CANCELLED) alert(`转换失败: ${error}`);
+    } finally {
+      finishJob();
     }
   };
 
+  const handleAddToQueue = () => {
+    if (!currentVideo) return;
+
+    addToQueue({
+      type: 'to_gif',
+      name: `转${FORMATS.find((f) => f.value === format)?.name} (${fps}fps, ${width}px)`,
+      params,
+    });
+  };
+
   return (
     <Card>
       <CardHeader>
-        <CardTitle>转换为GIF</CardTitle>
+        <CardTitle>转换为动图</CardTitle>
       </CardHeader>
       <CardContent className="space-y-4">
+        <div className="space-y-2">
+          <Label htmlFor="gif-format">格式</Label>
+          <Select value={format} onValueChange={(value) => setFormat(value as AnimationFormat)} disabled={isProcessing}>
+            <SelectTrigger id="gif-format">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              <SelectGroup>
+                {FORMATS.map((f) => (
+                  <SelectItem key={f.value} value={f.value}>{f.label}</SelectItem>
+                ))}
+              </SelectGroup>
+            </SelectContent>
+          </Select>
+        </div>
+
         <div className="space-y-3">
           <Label htmlFor="fps">帧率: {fps} fps</Label>
           <Slider
@@ -64,24 +140,126 @@ export function GifPanel() {
           />
         </div>
 
-        <div className="space-y-3">
-          <Label htmlFor="colors">颜色数: {colors}</Label>
-          <Slider
-            id="colors"
-            min={16}
-            max={256}
-            step={1}
-            value={[colors]}
-            onValueChange={(value) => setColors(value[0])}
-          />
+        {usesPalette ? (
+          <>
+            <div className="space-y-3">
+              <Label htmlFor="colors">颜色数: {colors}</Label>
+              <Slider
+                id="colors"
+                min={16}
+                max={256}
+                step={1}
+                value={[colors]}
+                onValueChange={(value) => setColors(value[0])}
+              />
+            </div>
+
+            <div className="grid grid-cols-2 gap-2">
+              <div className="space-y-2">
+                <Label htmlFor="stats-mode">调色板</Label>
+                <Select value={statsMode} onValueChange={(value) => setStatsMode(value as PaletteStatsMode)} disabled={isProcessing}>
+                  <SelectTrigger id="stats-mode">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectGroup>
+                      {STATS_MODES.map((m) => (
+                        <SelectItem key={m.value} value={m.value}>{m.label}</SelectItem>
+                      ))}
+                    </SelectGroup>
+                  </SelectContent>
+                </Select>
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="dither-mode">抖动</Label>
+                <Select value={ditherMode} onValueChange={(value) => setDitherMode(value as DitherMode)} disabled={isProcessing}>
+                  <SelectTrigger id="dither-mode">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectGroup>
+                      {DITHER_MODES.map((d) => (
+                        <SelectItem key={d.value} value={d.value}>{d.label}</SelectItem>
+                      ))}
+                    </SelectGroup>
+                  </SelectContent>
+                </Select>
+              </div>
+            </div>
+
+            {ditherMode === 'bayer' && (
+              <div className="space-y-3">
+                <Label htmlFor="bayer-scale">Bayer 强度: {bayerScale}</Label>
+                <Slider
+                  id="bayer-scale"
+                  min={0}
+                  max={5}
+                  step={1}
+                  value={[bayerScale]}
+                  onValueChange={(value) => setBayerScale(value[0])}
+                />
+              </div>
+            )}
+          </>
+        ) : (
+          <div className="space-y-3">
+            <Label htmlFor="quality">质量: {quality}</Label>
+            <Slider
+              id="quality"
+              min={20}
+              max={100}
+              step={1}
+              value={[quality]}
+              onValueChange={(value) => setQuality(value[0])}
+            />
+          </div>
+        )}
+
+        <div className="grid grid-cols-2 gap-2">
+          <div className="space-y-2">
+            <Label htmlFor="loop-count">播放次数</Label>
+            <Input
+              id="loop-count"
+              type="number"
+              value={loopCount}
+              onChange={(e) => setLoopCount(Math.max(0, parseInt(e.target.value) || 0))}
+              min="0"
+              disabled={isProcessing}
+            />
+          </div>
+          <div className="space-y-2">
+            <Label htmlFor="gif-target-size">目标大小(MB)</Label>
+            <Input
+              id="gif-target-size"
+              type="number"
+              value={targetSizeMb}
+              onChange={(e) => setTargetSizeMb(e.target.value)}
+              placeholder="不限制"
+              min="0.1"
+              step="0.5"
+              disabled={isProcessing}
+            />
+          </div>
         </div>
+        <p className="text-xs text-muted-foreground">
+          播放次数为 0 时无限循环；超出目标大小时会依次降低帧率、宽度和{usesPalette ? '颜色数' : '质量'}重新生成
+        </p>
 
-        <Button
-          onClick={handleConvert}
-          className="w-full"
-        >
-          开始转换
-        </Button>
+        <div className="flex gap-2">
+          <Button onClick={handleConvert} disabled={!currentVideo || isProcessing} className="flex-1">
+            {isProcessing ? '处理中...' : '开始转换'}
+          </Button>
+          <Button
+            onClick={handleAddToQueue}
+            disabled={!currentVideo}
+            variant="secondary"
+            size="icon"
+            title="添加到操作队列"
+          >
+            <Plus className="w-4 h-4" />
+            队列
+          </Button>
+        </div>
       </CardContent>
     </Card>
   );
@@ -3,6 +3,7 @@ import { invoke } from '@tauri-apps/api/core';
 import { open } from '@tauri-apps/plugin-shell';
 import { useVideoStore } from '../../store/videoStore';
 import { useOperationQueue } from '../../contexts/OperationQueueContext';
+import { JOB_CANCELLED } from '../../types';
 import { Card, CardHeader, CardTitle, CardContent } from '@/components/ui/card';
 import { Label } from '@/components/ui/label';
 import { Slider } from '@/components/ui/slider';
@@ -11,7 +12,7 @@ import { Button } from '@/components/ui/button';
 import { FolderOpen, Plus } from 'lucide-react';
 
 export function SpeedPanel() {
-  const { currentVideo, isProcessing, setProcessing } = useVideoStore();
+  const { currentVideo, isProcessing, startJob, finishJob } = useVideoStore();
   const { addToQueue } = useOperationQueue();
   const [speed, setSpeed] = useState(1.0);
   const [preservePitch, setPreservePitch] = useState(false);
@@ -20,20 +21,21 @@ export function SpeedPanel() {
   const handleSpeedChange = async () => {
     if (!currentVideo) return;
 
-    setProcessing(true);
+    const jobId = startJob('正在变速处理...');
     setOutputPath(null);
 
     try {
       const path = await invoke<string>('change_video_speed', {
         inputPath: currentVideo.path,
-        params: { speed, preservePitch }
+        params: { speed, preservePitch },
+        jobId,
       });
 
       setOutputPath(path);
     } catch (error) {
-      alert(`变速失败: ${error}`);
+      if (error !== JOB_CANCELLED) alert(`变速失败: ${error}`);
     } finally {
-      setProcessing(false);
+      finishJob();
     }
   };
 
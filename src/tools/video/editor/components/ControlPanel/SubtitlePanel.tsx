@@ -0,0 +1,293 @@
+import { useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { open as openDialog } from '@tauri-apps/plugin-dialog';
+import { useVideoStore } from '../../store/videoStore';
+import { useOperationQueue } from '../../contexts/OperationQueueContext';
+import { JOB_CANCELLED } from '../../types';
+import type { BurnSubtitleParams, ExtractSubtitleParams, MuxSubtitleParams } from '../../types';
+import { Card, CardHeader, CardTitle, CardContent } from '@/components/ui/card';
+import { Label } from '@/components/ui/label';
+import { Input } from '@/components/ui/input';
+import { Switch } from '@/components/ui/switch';
+import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue, SelectGroup } from '@/components/ui/select';
+import { Button } from '@/components/ui/button';
+import { FileText, Plus } from 'lucide-react';
+
+const LANGUAGES = [
+  { value: 'chi', label: '中文' },
+  { value: 'eng', label: '英语' },
+  { value: 'jpn', label: '日语' },
+  { value: 'kor', label: '韩语' },
+];
+
+function fileName(path: string): string {
+  return path.split(/[\\/]/).pop() || path;
+}
+
+export function SubtitlePanel() {
+  const { currentVideo, isProcessing, startJob, finishJob } = useVideoStore();
+  const { addToQueue } = useOperationQueue();
+  const [subtitlePath, setSubtitlePath] = useState('');
+  const [fontSize, setFontSize] = useState(24);
+  const [color, setColor] = useState('#FFFFFF');
+  const [outlineColor, setOutlineColor] = useState('#000000');
+  const [bold, setBold] = useState(false);
+  const [language, setLanguage] = useState('chi');
+  const [isDefault, setIsDefault] = useState(true);
+  const [streamIndex, setStreamIndex] = useState<string>('');
+  const [extractFormat, setExtractFormat] = useState<ExtractSubtitleParams['format']>('srt');
+  const [shiftPath, setShiftPath] = useState('');
+  const [offsetMs, setOffsetMs] = useState(0);
+
+  const subtitleStreams = currentVideo?.streams?.filter((s) => s.kind === 'subtitle') ?? [];
+
+  const burnParams: BurnSubtitleParams = {
+    subtitle_path: subtitlePath,
+    font_size: fontSize,
+    primary_color: color,
+    outline_color: outlineColor,
+    bold,
+  };
+  const muxParams: MuxSubtitleParams = { subtitle_path: subtitlePath, language, default: isDefault };
+  const extractParams: ExtractSubtitleParams = {
+    stream_index: streamIndex ? Number(streamIndex) : undefined,
+    format: extractFormat,
+  };
+
+  const selectSubtitle = async (setter: (path: string) => void, extensions: string[]) => {
+    const selected = await openDialog({
+      multiple: false,
+      filters: [{ name: '字幕', extensions }],
+    });
+    if (typeof selected === 'string') setter(selected);
+  };
+
+  const run = async (command: string, params: object, label: string) => {
+    if (!currentVideo) return;
+
+    const jobId = startJob(`正在${label}...`);
+
+    try {
+      const outputPath = await invoke<string>(command, {
+        inputPath: currentVideo.path,
+        params,
+        jobId,
+      });
+
+      alert(`${label}完成: ${outputPath}`);
+    } catch (error) {
+      if (error !== JOB_CANCELLED) alert(`${label}失败: ${error}`);
+    } finally {
+      finishJob();
+    }
+  };
+
+  const handleShift = async () => {
+    if (!shiftPath) return;
+    try {
+      const outputPath = await invoke<string>('shift_srt', { inputPath: shiftPath, offsetMs });
+      alert(`字幕平移完成: ${outputPath}`);
+    } catch (error) {
+      alert(`字幕平移失败: ${error}`);
+    }
+  };
+
+  const queueButton = (onClick: () => void, disabled = false) => (
+    <Button
+      onClick={onClick}
+      disabled={!currentVideo || disabled}
+      variant="secondary"
+      size="icon"
+      title="添加到操作队列"
+    >
+      <Plus className="w-4 h-4" />
+      队列
+    </Button>
+  );
+
+  return (
+    <div className="space-y-4">
+      <Card>
+        <CardHeader>
+          <CardTitle>添加字幕</CardTitle>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          <Button
+            onClick={() => selectSubtitle(setSubtitlePath, ['srt', 'ass', 'ssa'])}
+            variant="outline"
+            className="w-full justify-start"
+            disabled={isProcessing}
+          >
+            <FileText className="w-4 h-4 mr-2" />
+            <span className="truncate">{subtitlePath ? fileName(subtitlePath) : '选择 SRT/ASS 字幕文件'}</span>
+          </Button>
+
+          <div className="space-y-2">
+            <Label className="text-sm font-medium">烧录到画面</Label>
+            <div className="grid grid-cols-3 gap-2">
+              <div className="space-y-1">
+                <Label htmlFor="font-size" className="text-xs">字号</Label>
+                <Input
+                  id="font-size"
+                  type="number"
+                  value={fontSize}
+                  onChange={(e) => setFontSize(parseInt(e.target.value) || 24)}
+                  min="8"
+                  disabled={isProcessing}
+                />
+              </div>
+              <div className="space-y-1">
+                <Label htmlFor="font-color" className="text-xs">颜色</Label>
+                <Input id="font-color" type="color" value={color} onChange={(e) => setColor(e.target.value)} disabled={isProcessing} />
+              </div>
+              <div className="space-y-1">
+                <Label htmlFor="outline-color" className="text-xs">描边</Label>
+                <Input
+                  id="outline-color"
+                  type="color"
+                  value={outlineColor}
+                  onChange={(e) => setOutlineColor(e.target.value)}
+                  disabled={isProcessing}
+                />
+              </div>
+            </div>
+            <div className="flex items-center space-x-2">
+              <Switch id="bold" checked={bold} onCheckedChange={setBold} />
+              <Label htmlFor="bold" className="text-sm cursor-pointer">粗体</Label>
+            </div>
+            <div className="flex gap-2">
+              <Button
+                onClick={() => run('burn_subtitles', burnParams, '烧录字幕')}
+                disabled={isProcessing || !subtitlePath}
+                className="flex-1"
+              >
+                {isProcessing ? '处理中...' : '烧录字幕'}
+              </Button>
+              {queueButton(
+                () => addToQueue({ type: 'burn_subtitles', name: `烧录字幕 (${fileName(subtitlePath)})`, params: burnParams }),
+                !subtitlePath,
+              )}
+            </div>
+          </div>
+
+          <div className="space-y-2">
+            <Label className="text-sm font-medium">封装为字幕轨(可开关)</Label>
+            <Select value={language} onValueChange={setLanguage} disabled={isProcessing}>
+              <SelectTrigger>
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectGroup>
+                  {LANGUAGES.map((l) => (
+                    <SelectItem key={l.value} value={l.value}>{l.label}</SelectItem>
+                  ))}
+                </SelectGroup>
+              </SelectContent>
+            </Select>
+            <div className="flex items-center space-x-2">
+              <Switch id="default-track" checked={isDefault} onCheckedChange={setIsDefault} />
+              <Label htmlFor="default-track" className="text-sm cursor-pointer">设为默认字幕</Label>
+            </div>
+            <p className="text-xs text-muted-foreground">支持 MP4 / MOV / MKV / WebM，音视频不重新编码</p>
+            <div className="flex gap-2">
+              <Button
+                onClick={() => run('mux_subtitles', muxParams, '封装字幕')}
+                disabled={isProcessing || !subtitlePath}
+                variant="outline"
+                className="flex-1"
+              >
+                封装字幕轨
+              </Button>
+              {queueButton(
+                () => addToQueue({ type: 'mux_subtitles', name: `封装字幕 (${fileName(subtitlePath)})`, params: muxParams }),
+                !subtitlePath,
+              )}
+            </div>
+          </div>
+        </CardContent>
+      </Card>
+
+      <Card>
+        <CardHeader>
+          <CardTitle>提取内嵌字幕</CardTitle>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          {subtitleStreams.length === 0 ? (
+            <p className="text-sm text-muted-foreground">当前视频没有字幕流</p>
+          ) : (
+            <>
+              <div className="grid grid-cols-2 gap-2">
+                <Select value={streamIndex} onValueChange={setStreamIndex} disabled={isProcessing}>
+                  <SelectTrigger>
+                    <SelectValue placeholder="第一条字幕流" />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectGroup>
+                      {subtitleStreams.map((s) => (
+                        <SelectItem key={s.index} value={String(s.index)}>
+                          #{s.index} {s.language ?? ''} {s.title ?? s.codec}
+                        </SelectItem>
+                      ))}
+                    </SelectGroup>
+                  </SelectContent>
+                </Select>
+                <Select
+                  value={extractFormat}
+                  onValueChange={(value) => setExtractFormat(value as ExtractSubtitleParams['format'])}
+                  disabled={isProcessing}
+                >
+                  <SelectTrigger>
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectGroup>
+                      <SelectItem value="srt">SRT</SelectItem>
+                      <SelectItem value="ass">ASS</SelectItem>
+                    </SelectGroup>
+                  </SelectContent>
+                </Select>
+              </div>
+              <div className="flex gap-2">
+                <Button
+                  onClick={() => run('extract_subtitles', extractParams, '提取字幕')}
+                  disabled={isProcessing}
+                  className="flex-1"
+                >
+                  {isProcessing ? '处理中...' : '立即执行'}
+                </Button>
+                {queueButton(() =>
+                  addToQueue({ type: 'extract_subtitles', name: `提取字幕 (${extractFormat})`, params: extractParams }),
+                )}
+              </div>
+            </>
+          )}
+        </CardContent>
+      </Card>
+
+      <Card>
+        <CardHeader>
+          <CardTitle>SRT 时间平移</CardTitle>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          <Button onClick={() => selectSubtitle(setShiftPath, ['srt'])} variant="outline" className="w-full justify-start">
+            <FileText className="w-4 h-4 mr-2" />
+            <span className="truncate">{shiftPath ? fileName(shiftPath) : '选择 SRT 文件'}</span>
+          </Button>
+          <div className="space-y-2">
+            <Label htmlFor="offset">偏移(毫秒，负数为提前)</Label>
+            <Input
+              id="offset"
+              type="number"
+              value={offsetMs}
+              onChange={(e) => setOffsetMs(parseInt(e.target.value) || 0)}
+              step="100"
+            />
+          </div>
+          <Button onClick={handleShift} disabled={!shiftPath || offsetMs === 0} className="w-full">
+            平移并另存
+          </Button>
+        </CardContent>
+      </Card>
+    </div>
+  );
+}
@@ -3,6 +3,7 @@ import { invoke } from '@tauri-apps/api/core';
 import { useVideoStore } from '../../store/videoStore';
 import { useOperationQueue } from '../../contexts/OperationQueueContext';
 import { formatDuration } from '../../utils/fileValidation';
+import { JOB_CANCELLED } from '../../types';
 import { Card, CardContent, CardHeader, CardTitle } from '@/components/ui/card';
 import { Label } from '@/components/ui/label';
 import { Switch } from '@/components/ui/switch';
@@ -10,16 +11,14 @@ import { Button } from '@/components/ui/button';
 import { Plus } from 'lucide-react';
 
 export function TrimPanel() {
-  const { currentVideo, timelineStart, timelineEnd, isProcessing, setProcessing, setProgress, setOperation } = useVideoStore();
+  const { currentVideo, timelineStart, timelineEnd, isProcessing, startJob, finishJob } = useVideoStore();
   const { addToQueue } = useOperationQueue();
   const [precise, setPrecise] = useState(false);
 
   const handleTrim = async () => {
     if (!currentVideo) return;
 
-    setProcessing(true);
-    setProgress(0);
-    setOperation('正在截断视频...');
+    const jobId = startJob('正在截断视频...');
 
     try {
       const outputPath = await invoke<string>('trim_video', {
@@ -28,15 +27,15 @@ export function TrimPanel() {
           start_time: timelineStart,
           end_time: timelineEnd,
           precise
-        }
+        },
+        jobId,
       });
 
-      setProgress(100);
       alert(`截断完成: ${outputPath}`);
     } catch (error) {
-      alert(`截断失败: ${error}`);
+      if (error !== JOB_CANCELLED) alert(`截断失败: ${error}`);
     } finally {
-      setProcessing(false);
+      finishJob();
     }
   };
 
@@ -0,0 +1,141 @@
+import { useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { open as openDialog } from '@tauri-apps/plugin-dialog';
+import { useVideoStore } from '../../store/videoStore';
+import { useOperationQueue } from '../../contexts/OperationQueueContext';
+import { JOB_CANCELLED } from '../../types';
+import type { OverlayParams, OverlayPosition } from '../../types';
+import { Card, CardHeader, CardTitle, CardContent } from '@/components/ui/card';
+import { Label } from '@/components/ui/label';
+import { Slider } from '@/components/ui/slider';
+import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue, SelectGroup } from '@/components/ui/select';
+import { Button } from '@/components/ui/button';
+import { ImagePlus, Plus } from 'lucide-react';
+
+const POSITIONS: { value: OverlayPosition; label: string }[] = [
+  { value: 'top_left', label: '左上角' },
+  { value: 'top_right', label: '右上角' },
+  { value: 'bottom_left', label: '左下角' },
+  { value: 'bottom_right', label: '右下角' },
+  { value: 'center', label: '居中' },
+];
+
+export function WatermarkPanel() {
+  const { currentVideo, isProcessing, startJob, finishJob } = useVideoStore();
+  const { addToQueue } = useOperationQueue();
+  const [imagePath, setImagePath] = useState('');
+  const [position, setPosition] = useState<OverlayPosition>('bottom_right');
+  const [opacity, setOpacity] = useState(0.8);
+  const [scale, setScale] = useState(0.15);
+  const [margin, setMargin] = useState(20);
+
+  const params: OverlayParams = { image_path: imagePath, position, opacity, scale, margin };
+
+  const handleSelectImage = async () => {
+    const selected = await openDialog({
+      multiple: false,
+      filters: [{ name: '图片', extensions: ['png', 'jpg', 'jpeg', 'webp'] }],
+    });
+    if (typeof selected === 'string') setImagePath(selected);
+  };
+
+  const handleOverlay = async () => {
+    if (!currentVideo || !imagePath) return;
+
+    const jobId = startJob('正在添加水印...');
+
+    try {
+      const outputPath = await invoke<string>('overlay_watermark', {
+        inputPath: currentVideo.path,
+        params,
+        jobId,
+      });
+
+      alert(`添加水印完成: ${outputPath}`);
+    } catch (error) {
+      if (error !== JOB_CANCELLED) alert(`添加水印失败: ${error}`);
+    } finally {
+      finishJob();
+    }
+  };
+
+  const handleAddToQueue = () => {
+    if (!currentVideo || !imagePath) return;
+
+    addToQueue({
+      type: 'overlay',
+      name: `水印 (${POSITIONS.find((p) => p.value === position)?.label})`,
+      params,
+    });
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle>图片水印</CardTitle>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        <div className="space-y-2">
+          <Label>水印图片</Label>
+          <Button onClick={handleSelectImage} variant="outline" className="w-full justify-start" disabled={isProcessing}>
+            <ImagePlus className="w-4 h-4 mr-2" />
+            <span className="truncate">{imagePath ? imagePath.split(/[\\/]/).pop() : '选择图片(推荐透明背景 PNG)'}</span>
+          </Button>
+        </div>
+
+        <div className="space-y-2">
+          <Label htmlFor="position">位置</Label>
+          <Select value={position} onValueChange={(value) => setPosition(value as OverlayPosition)} disabled={isProcessing}>
+            <SelectTrigger id="position">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              <SelectGroup>
+                {POSITIONS.map((p) => (
+                  <SelectItem key={p.value} value={p.value}>{p.label}</SelectItem>
+                ))}
+              </SelectGroup>
+            </SelectContent>
+          </Select>
+        </div>
+
+        <div className="space-y-2">
+          <Label>不透明度: {Math.round(opacity * 100)}%</Label>
+          <Slider value={[opacity]} onValueChange={([v]) => setOpacity(v)} min={0.1} max={1} step={0.05} disabled={isProcessing} />
+        </div>
+
+        <div className="space-y-2">
+          <Label>水印宽度: 视频宽度的 {Math.round(scale * 100)}%</Label>
+          <Slider value={[scale]} onValueChange={([v]) => setScale(v)} min={0.05} max={0.5} step={0.01} disabled={isProcessing} />
+        </div>
+
+        {position !== 'center' && (
+          <div className="space-y-2">
+            <Label>边距: {margin}px</Label>
+            <Slider value={[margin]} onValueChange={([v]) => setMargin(v)} min={0} max={100} step={1} disabled={isProcessing} />
+          </div>
+        )}
+
+        <div className="flex gap-2">
+          <Button
+            onClick={handleOverlay}
+            disabled={isProcessing || !imagePath}
+            className="flex-1"
+          >
+            {isProcessing ? '处理中...' : '立即执行'}
+          </Button>
+          <Button
+            onClick={handleAddToQueue}
+            disabled={!currentVideo || !imagePath}
+            variant="secondary"
+            size="icon"
+            title="添加到操作队列"
+          >
+            <Plus className="w-4 h-4" />
+            队列
+          </Button>
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
@@ -4,3 +4,8 @@ export { SpeedPanel } from './SpeedPanel';
 export { ExtractPanel } from './ExtractPanel';
 export { TrimPanel } from './TrimPanel';
 export { GifPanel } from './GifPanel';
+export { ConcatPanel } from './ConcatPanel';
+export { CropPanel } from './CropPanel';
+export { WatermarkPanel } from './WatermarkPanel';
+export { AudioPanel } from './AudioPanel';
+export { SubtitlePanel } from './SubtitlePanel';
@@ -1,7 +1,8 @@
 import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { Loader2, Download, CheckCircle2, AlertCircle } from 'lucide-react';
+import { Loader2, Download, CheckCircle2, AlertCircle, Settings } from 'lucide-react';
 import { Button } from '@/components/ui/button';
+import { FfmpegManager } from './FfmpegManager';
 
 interface FfmpegInfo {
   available: boolean;
@@ -19,14 +20,16 @@ export function FfmpegChecker({
   const [downloading, setDownloading] = useState(false);
   const [ffmpegInfo, setFfmpegInfo] = useState<FfmpegInfo | null>(null);
   const [error, setError] = useState<string | null>(null);
+  const [managing, setManaging] = useState(false);
 
   useEffect(() => {
     checkFfmpeg();
   }, []);
 
-  const checkFfmpeg = async () => {
+  // silent: 版本管理中切换后静默重新检测，不卸载管理面板
+  const checkFfmpeg = async (silent = false) => {
     try {
-      setChecking(true);
+      if (!silent) setChecking(true);
       setError(null);
       const info = await invoke<FfmpegInfo>('check_ffmpeg');
       setFfmpegInfo(info);
@@ -65,9 +68,16 @@ export function FfmpegChecker({
 
   if (ffmpegInfo?.available) {
     return (
-      <div className="flex items-center gap-2 text-primary text-sm">
-        <CheckCircle2 className="w-4 h-4" />
-        <span>FFmpeg {ffmpegInfo.version} 已就绪</span>
+      <div className="space-y-4">
+        <div className="flex items-center gap-2 text-primary text-sm">
+          <CheckCircle2 className="w-4 h-4" />
+          <span>FFmpeg {ffmpegInfo.version} 已就绪</span>
+          <Button variant="ghost" size="sm" onClick={() => setManaging(!managing)}>
+            <Settings className="w-4 h-4" />
+            版本管理
+          </Button>
+        </div>
+        {managing && <FfmpegManager onChanged={() => checkFfmpeg(true)} />}
       </div>
     );
   }
@@ -106,7 +116,15 @@ export function FfmpegChecker({
         <p className="text-muted-foreground text-xs mt-2">
           支持 Windows 和 macOS，或手动安装后重启应用
         </p>
+        <Button variant="link" size="sm" onClick={() => setManaging(!managing)}>
+          从版本清单安装或指定 FFmpeg 路径
+        </Button>
       </div>
+      {managing && (
+        <div className="w-full max-w-2xl">
+          <FfmpegManager onChanged={() => checkFfmpeg(true)} />
+        </div>
+      )}
     </div>
   );
 }
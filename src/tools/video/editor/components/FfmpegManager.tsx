@@ -0,0 +1,240 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { open as openDialog } from '@tauri-apps/plugin-dialog';
+import { Loader2, RotateCcw, Trash2, RefreshCw } from 'lucide-react';
+import { Card, CardHeader, CardTitle, CardContent } from '@/components/ui/card';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Badge } from '@/components/ui/badge';
+import { showError, showSuccess } from '../utils/errorHandling';
+import type {
+  AvailableFfmpegVersion,
+  FfmpegCapabilityReport,
+  ManagedFfmpegState,
+} from '../types';
+
+/**
+ * FFmpeg 版本管理
+ *
+ * 按清单安装并校验托管版本，支持切换、回滚和指定自定义可执行文件
+ */
+export function FfmpegManager({ onChanged }: { onChanged: () => void }) {
+  const [state, setState] = useState<ManagedFfmpegState | null>(null);
+  const [manifest, setManifest] = useState('');
+  const [versions, setVersions] = useState<AvailableFfmpegVersion[]>([]);
+  const [report, setReport] = useState<FfmpegCapabilityReport | null>(null);
+  const [busy, setBusy] = useState<string | null>(null);
+
+  const refreshState = async () => {
+    const next = await invoke<ManagedFfmpegState>('get_managed_ffmpeg_status');
+    setState(next);
+    return next;
+  };
+
+  useEffect(() => {
+    refreshState()
+      .then((next) => setManifest(next.manifest ?? ''))
+      .catch(console.error);
+    invoke<FfmpegCapabilityReport>('get_ffmpeg_capability_report')
+      .then(setReport)
+      .catch(() => setReport(null));
+  }, []);
+
+  // 执行操作后刷新状态，并通知外层重新检测 FFmpeg
+  const run = async (label: string, action: () => Promise<unknown>, success?: string) => {
+    setBusy(label);
+    try {
+      await action();
+      await refreshState();
+      if (manifest && versions.length > 0) {
+        setVersions(await invoke<AvailableFfmpegVersion[]>('list_ffmpeg_versions', { manifest }));
+      }
+      setReport(await invoke<FfmpegCapabilityReport>('get_ffmpeg_capability_report').catch(() => null));
+      onChanged();
+      if (success) showSuccess(success);
+    } catch (error) {
+      showError(`${label}失败`, error);
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  const handleLoadManifest = () =>
+    run('读取版本清单', async () => {
+      setVersions(await invoke<AvailableFfmpegVersion[]>('list_ffmpeg_versions', { manifest }));
+    });
+
+  const handlePickManifest = async () => {
+    const selected = await openDialog({
+      multiple: false,
+      filters: [{ name: '版本清单', extensions: ['json'] }],
+    });
+    if (typeof selected === 'string') setManifest(selected);
+  };
+
+  const handlePickCustom = async () => {
+    const selected = await openDialog({ multiple: false });
+    if (typeof selected !== 'string') return;
+    await run('设置自定义 FFmpeg', () => invoke('set_custom_ffmpeg_path', { path: selected }), '已使用自定义 FFmpeg');
+  };
+
+  if (!state) return null;
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle>FFmpeg 版本管理</CardTitle>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        <div className="space-y-2">
+          <Label htmlFor="ffmpeg-manifest">版本清单(URL 或本地文件)</Label>
+          <div className="flex gap-2">
+            <Input
+              id="ffmpeg-manifest"
+              value={manifest}
+              onChange={(e) => setManifest(e.target.value)}
+              placeholder="https://.../ffmpeg-manifest.json"
+            />
+            <Button variant="outline" onClick={handlePickManifest}>浏览</Button>
+            <Button onClick={handleLoadManifest} disabled={!manifest || busy !== null}>读取</Button>
+          </div>
+        </div>
+
+        {versions.length > 0 && (
+          <div className="space-y-2">
+            {versions.map((v) => (
+              <div key={v.version} className="flex items-center gap-2 text-sm">
+                <span className="font-medium">{v.version}</span>
+                {v.active && <Badge>使用中</Badge>}
+                {v.notes && <span className="text-muted-foreground truncate flex-1">{v.notes}</span>}
+                <div className="ml-auto flex gap-2">
+                  {!v.installed && (
+                    <Button
+                      size="sm"
+                      disabled={busy !== null}
+                      onClick={() =>
+                        run(
+                          `安装 ${v.version}`,
+                          () => invoke('install_ffmpeg_version', { manifest, version: v.version }),
+                          `FFmpeg ${v.version} 安装完成`,
+                        )
+                      }
+                    >
+                      {busy === `安装 ${v.version}` && <Loader2 className="w-4 h-4 animate-spin" />}
+                      安装
+                    </Button>
+                  )}
+                </div>
+              </div>
+            ))}
+          </div>
+        )}
+
+        <div className="space-y-2">
+          <Label>已安装版本</Label>
+          {state.installed.length === 0 && (
+            <p className="text-muted-foreground text-sm">尚未安装托管版本，当前使用系统 FFmpeg</p>
+          )}
+          {state.installed.map((v) => (
+            <div key={v.version} className="flex items-center gap-2 text-sm">
+              <span className="font-medium">{v.version}</span>
+              <span className="text-muted-foreground">ffmpeg {v.ffmpeg_version}</span>
+              {state.active === v.version && <Badge>使用中</Badge>}
+              <div className="ml-auto flex gap-2">
+                {state.active !== v.version && (
+                  <>
+                    <Button
+                      size="sm"
+                      variant="outline"
+                      disabled={busy !== null}
+                      onClick={() => run('切换版本', () => invoke('select_ffmpeg_version', { version: v.version }))}
+                    >
+                      使用
+                    </Button>
+                    <Button
+                      size="sm"
+                      variant="ghost"
+                      title="删除"
+                      disabled={busy !== null}
+                      onClick={() => run('删除版本', () => invoke('remove_ffmpeg_version', { version: v.version }))}
+                    >
+                      <Trash2 className="w-4 h-4" />
+                    </Button>
+                  </>
+                )}
+              </div>
+            </div>
+          ))}
+          <div className="flex gap-2">
+            {state.active && (
+              <Button
+                size="sm"
+                variant="outline"
+                disabled={busy !== null}
+                onClick={() => run('切换版本', () => invoke('select_ffmpeg_version', { version: null }))}
+              >
+                使用系统 FFmpeg
+              </Button>
+            )}
+            {state.previous !== null && (
+              <Button
+                size="sm"
+                variant="outline"
+                disabled={busy !== null}
+                onClick={() => run('回滚', () => invoke('rollback_ffmpeg_version'), '已回滚到上一个版本')}
+              >
+                <RotateCcw className="w-4 h-4" />
+                回滚到 {state.previous}
+              </Button>
+            )}
+          </div>
+        </div>
+
+        <div className="space-y-2">
+          <Label>自定义可执行文件</Label>
+          <div className="flex items-center gap-2 text-sm">
+            <span className="text-muted-foreground truncate flex-1">{state.custom_path ?? '未设置'}</span>
+            <Button size="sm" variant="outline" onClick={handlePickCustom} disabled={busy !== null}>选择</Button>
+            {state.custom_path && (
+              <Button
+                size="sm"
+                variant="ghost"
+                disabled={busy !== null}
+                onClick={() => run('清除自定义 FFmpeg', () => invoke('set_custom_ffmpeg_path', { path: null }))}
+              >
+                清除
+              </Button>
+            )}
+          </div>
+        </div>
+
+        {report && (
+          <div className="space-y-1 text-sm">
+            <div className="flex items-center gap-2">
+              <Label>当前能力</Label>
+              <Button
+                size="sm"
+                variant="ghost"
+                title="重新检测"
+                disabled={busy !== null}
+                onClick={() =>
+                  run('检测能力', async () => {
+                    setReport(await invoke<FfmpegCapabilityReport>('get_ffmpeg_capability_report', { refresh: true }));
+                  })
+                }
+              >
+                <RefreshCw className="w-4 h-4" />
+              </Button>
+            </div>
+            <p className="text-muted-foreground break-all">{report.path} ({report.version})</p>
+            <p className="text-muted-foreground">
+              编码器 {report.encoders.length} 个 · 滤镜 {report.filters.length} 个 · 硬件加速:{' '}
+              {report.hwaccels.length > 0 ? report.hwaccels.join(', ') : '无'}
+            </p>
+          </div>
+        )}
+      </CardContent>
+    </Card>
+  );
+}
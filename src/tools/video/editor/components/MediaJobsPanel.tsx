@@ -0,0 +1,192 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { open } from '@tauri-apps/plugin-shell';
+import { Pause, Play, RotateCcw, X, Trash2, FolderOpen } from 'lucide-react';
+import { Card } from '@/components/ui/card';
+import { Button } from '@/components/ui/button';
+import { Badge } from '@/components/ui/badge';
+import { Progress } from '@/components/ui/progress';
+import { showError } from '../utils/errorHandling';
+import type { MediaJob, MediaJobStatus, MediaQueueSettings } from '../types';
+
+const STATUS_LABELS: Record<MediaJobStatus, string> = {
+  queued: '等待中',
+  running: '执行中',
+  paused: '已暂停',
+  completed: '已完成',
+  failed: '失败',
+  cancelled: '已取消',
+};
+
+function fileName(path: string): string {
+  return path.split(/[\\/]/).pop() || path;
+}
+
+/**
+ * 后台任务面板
+ *
+ * 显示持久化的媒体任务队列，支持暂停/恢复/重试/取消及并发数设置
+ */
+export function MediaJobsPanel() {
+  const [jobs, setJobs] = useState<MediaJob[]>([]);
+  const [settings, setSettings] = useState<MediaQueueSettings | null>(null);
+
+  useEffect(() => {
+    invoke<MediaJob[]>('list_media_jobs').then(setJobs).catch(console.error);
+    invoke<MediaQueueSettings>('get_media_queue_settings').then(setSettings).catch(console.error);
+
+    const unlistenUpdated = listen<MediaJob>('media-job-updated', (event) => {
+      const job = event.payload;
+      setJobs((prev) => {
+        const index = prev.findIndex((j) => j.id === job.id);
+        if (index === -1) return [...prev, job];
+        const next = [...prev];
+        next[index] = job;
+        return next;
+      });
+    });
+    const unlistenRemoved = listen<string[]>('media-job-removed', (event) => {
+      const removed = new Set(event.payload);
+      setJobs((prev) => prev.filter((j) => !removed.has(j.id)));
+    });
+
+    return () => {
+      unlistenUpdated.then((fn) => fn());
+      unlistenRemoved.then((fn) => fn());
+    };
+  }, []);
+
+  const runAction = useCallback(async (command: string, jobId: string) => {
+    try {
+      await invoke(command, { jobId });
+    } catch (error) {
+      showError(String(error));
+    }
+  }, []);
+
+  const updateSettings = useCallback(async (patch: Partial<MediaQueueSettings>) => {
+    if (!settings) return;
+    try {
+      setSettings(await invoke<MediaQueueSettings>('update_media_queue_settings', {
+        settings: { ...settings, ...patch },
+      }));
+    } catch (error) {
+      showError(String(error));
+    }
+  }, [settings]);
+
+  const handleOpenFolder = (outputPath: string) => {
+    const separator = outputPath.includes('\\') ? '\\' : '/';
+    open(outputPath.substring(0, outputPath.lastIndexOf(separator)));
+  };
+
+  const hasFinished = jobs.some((j) => ['completed', 'failed', 'cancelled'].includes(j.status));
+
+  return (
+    <Card className="p-4">
+      <div className="flex items-center justify-between mb-4">
+        <h3 className="text-lg font-semibold">后台任务</h3>
+        <div className="flex items-center gap-2">
+          {settings && (
+            <>
+              <label className="flex items-center gap-1 text-xs text-muted-foreground">
+                并发
+                <select
+                  value={settings.concurrency}
+                  onChange={(e) => updateSettings({ concurrency: Number(e.target.value) })}
+                  className="bg-background border rounded px-1 py-0.5"
+                >
+                  {[1, 2, 3, 4, 6, 8].map((n) => (
+                    <option key={n} value={n}>{n}</option>
+                  ))}
+                </select>
+              </label>
+              <Button
+                onClick={() => updateSettings({ paused: !settings.paused })}
+                variant="ghost"
+                size="sm"
+                className="flex items-center gap-1"
+              >
+                {settings.paused ? <Play className="w-4 h-4" /> : <Pause className="w-4 h-4" />}
+                {settings.paused ? '继续队列' : '暂停队列'}
+              </Button>
+            </>
+          )}
+          {hasFinished && (
+            <Button
+              onClick={() => invoke('clear_finished_media_jobs').catch(console.error)}
+              variant="ghost"
+              size="sm"
+              className="flex items-center gap-1"
+            >
+              <Trash2 className="w-4 h-4" />
+              清除已结束
+            </Button>
+          )}
+        </div>
+      </div>
+
+      {jobs.length === 0 ? (
+        <div className="text-center py-8 text-muted-foreground">
+          <p className="text-sm">暂无后台任务</p>
+          <p className="text-xs mt-1">在操作队列中点击"后台执行"或"批量处理文件夹"</p>
+        </div>
+      ) : (
+        <div className="space-y-2">
+          {jobs.map((job) => {
+            const finalOutput = job.outputs[job.outputs.length - 1];
+            return (
+              <div key={job.id} className="p-3 bg-muted/50 dark:bg-muted/20 rounded-lg">
+                <div className="flex items-center gap-2">
+                  <div className="flex-1 min-w-0">
+                    <p className="text-sm font-medium truncate" title={job.inputPath}>
+                      {fileName(job.inputPath)}
+                    </p>
+                    <p className="text-xs text-muted-foreground truncate">
+                      {job.operations.map((op) => op.name).join(' → ')}
+                    </p>
+                  </div>
+                  <Badge variant={job.status === 'failed' ? 'destructive' : 'secondary'}>
+                    {STATUS_LABELS[job.status]}
+                  </Badge>
+                  {(job.status === 'queued' || job.status === 'running') && (
+                    <Button onClick={() => runAction('pause_media_job', job.id)} variant="ghost" size="icon" title="暂停">
+                      <Pause className="w-4 h-4" />
+                    </Button>
+                  )}
+                  {job.status === 'paused' && (
+                    <Button onClick={() => runAction('resume_media_job', job.id)} variant="ghost" size="icon" title="恢复">
+                      <Play className="w-4 h-4" />
+                    </Button>
+                  )}
+                  {(job.status === 'failed' || job.status === 'cancelled') && (
+                    <Button onClick={() => runAction('retry_media_job', job.id)} variant="ghost" size="icon" title="重试">
+                      <RotateCcw className="w-4 h-4" />
+                    </Button>
+                  )}
+                  {job.status === 'completed' && finalOutput && (
+                    <Button onClick={() => handleOpenFolder(finalOutput)} variant="ghost" size="icon" title="打开文件夹">
+                      <FolderOpen className="w-4 h-4" />
+                    </Button>
+                  )}
+                  {['completed', 'failed', 'cancelled'].includes(job.status) ? (
+                    <Button onClick={() => runAction('remove_media_job', job.id)} variant="ghost" size="icon" title="移除">
+                      <Trash2 className="w-4 h-4" />
+                    </Button>
+                  ) : (
+                    <Button onClick={() => runAction('cancel_media_job', job.id)} variant="ghost" size="icon" title="取消">
+                      <X className="w-4 h-4" />
+                    </Button>
+                  )}
+                </div>
+                {job.status === 'running' && <Progress value={job.progress} className="h-1.5 mt-2" />}
+                {job.error && <p className="text-xs text-destructive mt-1 break-all">{job.error}</p>}
+              </div>
+            );
+          })}
+        </div>
+      )}
+    </Card>
+  );
+}
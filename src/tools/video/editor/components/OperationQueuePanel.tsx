@@ -1,7 +1,9 @@
 import { useOperationQueue } from '../contexts/OperationQueueContext';
 import { useVideoStore } from '../store/videoStore';
-import { X, Play, Trash2, FolderOpen, CheckCircle2, XCircle } from 'lucide-react';
+import { X, Play, Trash2, FolderOpen, CheckCircle2, XCircle, Clock, FolderInput } from 'lucide-react';
 import { open } from '@tauri-apps/plugin-shell';
+import { open as openDialog } from '@tauri-apps/plugin-dialog';
+import { showError, showSuccess } from '../utils/errorHandling';
 import { Card } from '@/components/ui/card';
 import { Button } from '@/components/ui/button';
 import { Badge } from '@/components/ui/badge';
@@ -14,6 +16,8 @@ export function OperationQueuePanel() {
     removeFromQueue,
     clearQueue,
     processQueue,
+    submitToBackground,
+    submitFolder,
     clearResults,
   } = useOperationQueue();
   const { currentVideo } = useVideoStore();
@@ -23,6 +27,27 @@ export function OperationQueuePanel() {
     await processQueue(currentVideo.path);
   };
 
+  const handleSubmitBackground = async () => {
+    if (!currentVideo) return;
+    try {
+      await submitToBackground(currentVideo.path);
+      showSuccess('已加入后台任务');
+    } catch (error) {
+      showError(`提交后台任务失败: ${error}`);
+    }
+  };
+
+  const handleSubmitFolder = async () => {
+    const folder = await openDialog({ directory: true, multiple: false });
+    if (!folder || typeof folder !== 'string') return;
+    try {
+      const jobs = await submitFolder(folder, false);
+      showSuccess(`已加入 ${jobs.length} 个后台任务`);
+    } catch (error) {
+      showError(`批量提交失败: ${error}`);
+    }
+  };
+
   const handleOpenFolder = (outputPath: string) => {
     // 使用 path.posix 或 path.win32 来处理不同平台的路径分隔符
     const separator = outputPath.includes('\\') ? '\\' : '/';
@@ -73,7 +98,7 @@ export function OperationQueuePanel() {
                   {operation.type === 'compress' && '压缩视频'}
                   {operation.type === 'speed' && `变速 ${operation.params.speed}x`}
                   {operation.type === 'trim' && '截断视频'}
-                  {operation.type === 'to_gif' && '转GIF'}
+                  {operation.type === 'to_gif' && '转动图'}
                   {operation.type === 'extract_frames' && '提取帧'}
                 </p>
               </div>
@@ -112,6 +137,30 @@ export function OperationQueuePanel() {
         </Button>
       )}
 
+      {/* 后台执行 */}
+      {queue.length > 0 && (
+        <div className="grid grid-cols-2 gap-2 mt-2">
+          <Button
+            onClick={handleSubmitBackground}
+            disabled={isProcessing || !currentVideo}
+            variant="outline"
+            size="sm"
+          >
+            <Clock className="w-4 h-4" />
+            后台执行
+          </Button>
+          <Button
+            onClick={handleSubmitFolder}
+            disabled={isProcessing}
+            variant="outline"
+            size="sm"
+          >
+            <FolderInput className="w-4 h-4" />
+            批量处理文件夹
+          </Button>
+        </div>
+      )}
+
       {/* 执行结果 */}
       {results.length > 0 && (
         <div className="mt-4 pt-4 border-t">
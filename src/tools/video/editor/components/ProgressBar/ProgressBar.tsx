@@ -1,8 +1,41 @@
+import { useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { useVideoStore } from '../../store/videoStore';
+import { formatDuration } from '../../utils/fileValidation';
+import type { JobProgress } from '../../types';
 import { Progress } from '@/components/ui/progress';
+import { Button } from '@/components/ui/button';
+import { X } from 'lucide-react';
 
 export function ProgressBar() {
-  const { isProcessing, progress, currentOperation } = useVideoStore();
+  const {
+    isProcessing,
+    progress,
+    currentOperation,
+    jobId,
+    jobSpeed,
+    jobEta,
+    applyJobProgress,
+  } = useVideoStore();
+
+  useEffect(() => {
+    const unlisten = listen<JobProgress>('ffmpeg-progress', (event) => {
+      applyJobProgress(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [applyJobProgress]);
+
+  const handleCancel = async () => {
+    if (!jobId) return;
+    try {
+      await invoke('cancel_job', { jobId });
+    } catch (error) {
+      console.error('取消任务失败:', error);
+    }
+  };
 
   if (!isProcessing) return null;
 
@@ -13,9 +46,32 @@ export function ProgressBar() {
           <span className="text-sm font-medium">
             {currentOperation || '处理中...'}
           </span>
-          <span className="text-sm text-muted-foreground">
-            {progress.toFixed(0)}%
-          </span>
+          <div className="flex items-center gap-3">
+            {jobSpeed !== null && (
+              <span className="text-xs text-muted-foreground">
+                {jobSpeed.toFixed(1)}x
+              </span>
+            )}
+            {jobEta !== null && (
+              <span className="text-xs text-muted-foreground">
+                剩余 {formatDuration(jobEta)}
+              </span>
+            )}
+            <span className="text-sm text-muted-foreground">
+              {progress.toFixed(0)}%
+            </span>
+            {jobId && (
+              <Button
+                variant="ghost"
+                size="icon"
+                className="h-6 w-6"
+                onClick={handleCancel}
+                title="取消任务"
+              >
+                <X className="w-4 h-4" />
+              </Button>
+            )}
+          </div>
         </div>
 
         <Progress value={progress} className="h-2" />
@@ -1,6 +1,40 @@
 import { useVideoStore } from '../../store/videoStore';
 import { Card, CardHeader, CardTitle, CardContent } from '@/components/ui/card';
 import { Label } from '@/components/ui/label';
+import type { StreamInfo } from '../../types';
+
+const STREAM_KIND_LABELS: Record<StreamInfo['kind'], string> = {
+  video: '视频',
+  audio: '音频',
+  subtitle: '字幕',
+  data: '数据',
+  attachment: '附件',
+  unknown: '未知',
+};
+
+function describeStream(stream: StreamInfo): string {
+  const parts: string[] = [stream.profile ? `${stream.codec} (${stream.profile})` : stream.codec];
+
+  if (stream.kind === 'video') {
+    if (stream.attached_pic) parts.push('封面');
+    if (stream.width && stream.height) parts.push(`${stream.width}x${stream.height}`);
+    if (stream.fps) parts.push(`${Number(stream.fps.toFixed(3))} fps`);
+    if (stream.pixel_format) parts.push(stream.pixel_format);
+    if (stream.color_space) parts.push(stream.color_space);
+    if (stream.hdr) parts.push('HDR');
+    if (stream.rotation) parts.push(`旋转 ${stream.rotation}°`);
+  } else if (stream.kind === 'audio') {
+    if (stream.sample_rate) parts.push(`${stream.sample_rate} Hz`);
+    if (stream.channel_layout) parts.push(stream.channel_layout);
+    else if (stream.channels) parts.push(`${stream.channels} 声道`);
+  }
+
+  if (stream.bitrate) parts.push(`${Math.round(stream.bitrate / 1000)} kbps`);
+  if (stream.language && stream.language !== 'und') parts.push(stream.language);
+  if (stream.title) parts.push(stream.title);
+
+  return parts.join(' · ');
+}
 
 export function VideoInfo() {
   const { currentVideo } = useVideoStore();
@@ -51,6 +85,20 @@ export function VideoInfo() {
             <p className="font-medium">{currentVideo.format}</p>
           </div>
         </div>
+        {currentVideo.streams && currentVideo.streams.length > 0 && (
+          <div className="mt-4 space-y-1 text-sm">
+            <Label>媒体流:</Label>
+            {currentVideo.streams.map((stream) => (
+              <p key={stream.index} className="text-muted-foreground">
+                <span className="font-medium text-foreground">
+                  #{stream.index} {STREAM_KIND_LABELS[stream.kind]}
+                  {stream.is_default && stream.kind !== 'video' ? '（默认）' : ''}
+                </span>{' '}
+                {describeStream(stream)}
+              </p>
+            ))}
+          </div>
+        )}
       </CardContent>
     </Card>
   );
@@ -1,9 +1,14 @@
 import React, { createContext, useContext, useState, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { useVideoStore } from '../store/videoStore';
+import type { MediaJob, OperationType } from '../types';
+
+// 批量处理时匹配的视频扩展名
+const VIDEO_EXTENSIONS = ['mp4', 'mov', 'avi', 'wmv', 'mkv', 'flv', 'webm', 'm4v', 'mpg', 'mpeg'];
 
 interface QueueOperation {
   id: string;
-  type: 'compress' | 'speed' | 'extract_frames' | 'trim' | 'to_gif';
+  type: OperationType;
   name: string;
   params: any;
 }
@@ -23,6 +28,8 @@ interface QueueContextType {
   removeFromQueue: (id: string) => void;
   clearQueue: () => void;
   processQueue: (inputPath: string) => Promise<void>;
+  submitToBackground: (inputPath: string) => Promise<MediaJob>;
+  submitFolder: (folder: string, recursive: boolean) => Promise<MediaJob[]>;
   clearResults: () => void;
 }
 
@@ -32,6 +39,7 @@ export function OperationQueueProvider({ children }: { children: React.ReactNode
   const [queue, setQueue] = useState<QueueOperation[]>([]);
   const [isProcessing, setIsProcessing] = useState(false);
   const [results, setResults] = useState<OperationResult[]>([]);
+  const { startJob, finishJob } = useVideoStore();
 
   const addToQueue = useCallback((operation: Omit<QueueOperation, 'id'>) => {
     const newOp: QueueOperation = {
@@ -59,6 +67,7 @@ export function OperationQueueProvider({ children }: { children: React.ReactNode
 
     setIsProcessing(true);
     setResults([]);
+    const jobId = startJob('正在处理操作队列...');
 
     try {
       // 转换队列为后端期望的格式
@@ -72,6 +81,7 @@ export function OperationQueueProvider({ children }: { children: React.ReactNode
       const finalOutputPath = await invoke<string>('process_operation_queue', {
         inputPath,
         operations,
+        jobId,
       });
 
       // 所有操作都成功完成，记录结果
@@ -94,8 +104,34 @@ export function OperationQueueProvider({ children }: { children: React.ReactNode
       setResults(operationResults);
     } finally {
       setIsProcessing(false);
+      finishJob();
     }
-  }, [queue]);
+  }, [queue, startJob, finishJob]);
+
+  const toBackendOperations = useCallback(() => queue.map(op => ({
+    type: op.type,
+    name: op.name,
+    params: op.params,
+  })), [queue]);
+
+  // 提交到后台任务队列，关闭窗口后继续执行
+  const submitToBackground = useCallback(async (inputPath: string) => {
+    return invoke<MediaJob>('submit_media_job', {
+      request: { inputPath, operations: toBackendOperations() },
+    });
+  }, [toBackendOperations]);
+
+  // 对文件夹中的每个视频执行当前队列
+  const submitFolder = useCallback(async (folder: string, recursive: boolean) => {
+    return invoke<MediaJob[]>('submit_media_batch', {
+      request: {
+        folder,
+        recursive,
+        extensions: VIDEO_EXTENSIONS,
+        operations: toBackendOperations(),
+      },
+    });
+  }, [toBackendOperations]);
 
   return (
     <OperationQueueContext.Provider
@@ -107,6 +143,8 @@ export function OperationQueueProvider({ children }: { children: React.ReactNode
         removeFromQueue,
         clearQueue,
         processQueue,
+        submitToBackground,
+        submitFolder,
         clearResults,
       }}
     >
@@ -1,5 +1,5 @@
 import { create } from 'zustand';
-import type { VideoInfo, Operation } from '../types';
+import type { VideoInfo, Operation, JobProgress } from '../types';
 
 interface VideoStore {
   // 当前视频信息
@@ -21,6 +21,14 @@ interface VideoStore {
   setProgress: (progress: number) => void;
   setOperation: (operation: string) => void;
 
+  // 当前 FFmpeg 任务（用于进度匹配与取消）
+  jobId: string | null;
+  jobSpeed: number | null;
+  jobEta: number | null;
+  startJob: (operation: string) => string;
+  finishJob: () => void;
+  applyJobProgress: (progress: JobProgress) => void;
+
   // 历史记录
   history: Operation[];
   addHistory: (operation: Operation) => void;
@@ -50,6 +58,29 @@ export const useVideoStore = create<VideoStore>((set) => ({
   setProgress: (progress) => set({ progress }),
   setOperation: (operation) => set({ currentOperation: operation }),
 
+  // 任务
+  jobId: null,
+  jobSpeed: null,
+  jobEta: null,
+  startJob: (operation) => {
+    const jobId = crypto.randomUUID();
+    set({
+      jobId,
+      jobSpeed: null,
+      jobEta: null,
+      isProcessing: true,
+      progress: 0,
+      currentOperation: operation,
+    });
+    return jobId;
+  },
+  finishJob: () => set({ jobId: null, isProcessing: false }),
+  applyJobProgress: (progress) => set((state) => (
+    state.jobId === progress.job_id
+      ? { progress: progress.percent, jobSpeed: progress.speed, jobEta: progress.eta }
+      : {}
+  )),
+
   // 历史记录
   history: [],
   addHistory: (operation) => set((state) => ({
@@ -13,10 +13,102 @@ export interface VideoInfo {
   bitrate: number;
   fileSize: number;
   format: string;
+  rotation?: number; // 主视频流顺时针旋转角度，宽高已按显示方向给出
+  streams?: StreamInfo[]; // 仅 ffprobe 可用时提供
+}
+
+// 媒体流类型
+export type StreamKind = 'video' | 'audio' | 'subtitle' | 'data' | 'attachment' | 'unknown';
+
+// 单个媒体流信息（后端 ffprobe 解析结果）
+export interface StreamInfo {
+  index: number;
+  kind: StreamKind;
+  codec: string;
+  profile: string | null;
+  bitrate: number | null; // bps
+  language: string | null;
+  title: string | null;
+  is_default: boolean;
+  // 视频流
+  width: number | null;
+  height: number | null;
+  fps: number | null;
+  pixel_format: string | null;
+  color_space: string | null;
+  color_transfer: string | null;
+  color_primaries: string | null;
+  rotation: number;
+  hdr: boolean;
+  attached_pic: boolean;
+  // 音频流
+  sample_rate: number | null;
+  channels: number | null;
+  channel_layout: string | null;
+}
+
+// FFmpeg 任务进度（后端 ffmpeg-progress 事件）
+export interface JobProgress {
+  job_id: string;
+  percent: number;
+  out_time: number; // 秒
+  duration: number; // 秒，未知时为 0
+  speed: number | null; // 相对实时倍率
+  eta: number | null; // 预计剩余秒数
+  fps: number | null;
+  elapsed: number; // 秒
+}
+
+// 任务被取消时后端返回的错误信息
+export const JOB_CANCELLED = '任务已取消';
+
+// 后台媒体任务状态
+export type MediaJobStatus = 'queued' | 'running' | 'paused' | 'completed' | 'failed' | 'cancelled';
+
+// 后台媒体任务（后端 media-job-updated 事件）
+export interface MediaJob {
+  id: string;
+  inputPath: string;
+  operations: { type: string; name: string; params: unknown }[];
+  outputDir: string | null;
+  priority: number; // 数值越大越先执行
+  status: MediaJobStatus;
+  progress: number; // 0-100
+  outputs: string[]; // 最终文件在最后
+  operationResults: unknown[]; // 与 operations 一一对应，如响度统计；无结果为 null
+  error: string | null;
+  attempts: number;
+  batchId: string | null;
+  createdAt: number; // 毫秒时间戳
+  startedAt: number | null;
+  finishedAt: number | null;
+}
+
+// 后台任务队列设置
+export interface MediaQueueSettings {
+  concurrency: number; // 1-8
+  paused: boolean;
+  notifyOnComplete: boolean;
 }
 
 // 操作类型
-export type OperationType = 'compress' | 'speed' | 'extract_frames' | 'trim' | 'to_gif';
+export type OperationType =
+  | 'compress'
+  | 'speed'
+  | 'extract_frames'
+  | 'trim'
+  | 'to_gif'
+  | 'concat'
+  | 'crop'
+  | 'rotate'
+  | 'overlay'
+  | 'extract_audio'
+  | 'replace_audio'
+  | 'mute'
+  | 'loudnorm'
+  | 'burn_subtitles'
+  | 'mux_subtitles'
+  | 'extract_subtitles';
 
 // 压缩预设
 export type CompressPreset = 'mobile' | 'web' | 'high_quality' | 'custom';
@@ -30,8 +122,68 @@ export interface CompressParams {
   };
   bitrate?: number;
   crf?: number;
-  codec?: 'h264' | 'h265' | 'vp9';
+  codec?: VideoCodec;
   fps?: number;
+  /** 目标文件大小（MB），设置后按时长计算码率并两遍编码 */
+  target_size_mb?: number;
+  audio_codec?: AudioCodec | 'copy';
+  audio_bitrate?: number; // kbps
+  audio_mono?: boolean;
+  strip_audio?: boolean;
+}
+
+export type VideoCodec = 'h264' | 'h265' | 'vp9' | 'av1';
+export type AudioCodec = 'aac' | 'opus' | 'mp3';
+
+// 当前 FFmpeg 可用的编码格式
+export interface CodecOption {
+  codec: string;
+  encoder: string;
+  two_pass: boolean;
+}
+
+export interface EncoderInfo {
+  name: string;
+  kind: 'video' | 'audio' | 'subtitle';
+  description: string;
+}
+
+export interface EncoderCapabilities {
+  video_codecs: CodecOption[];
+  audio_codecs: CodecOption[];
+  encoders: EncoderInfo[];
+}
+
+// 托管的 FFmpeg 版本
+export interface InstalledFfmpegVersion {
+  version: string;
+  ffmpeg_version: string;
+  ffmpeg_sha256: string;
+  manifest: string;
+  installed_at: number; // 毫秒时间戳
+}
+
+export interface ManagedFfmpegState {
+  installed: InstalledFfmpegVersion[];
+  active: string | null; // 为空时使用系统 FFmpeg
+  previous: string | null;
+  custom_path: string | null;
+  manifest: string | null;
+}
+
+export interface AvailableFfmpegVersion {
+  version: string;
+  notes: string | null;
+  installed: boolean;
+  active: boolean;
+}
+
+export interface FfmpegCapabilityReport {
+  path: string;
+  version: string;
+  encoders: EncoderInfo[];
+  filters: string[];
+  hwaccels: string[];
 }
 
 // 变速参数
@@ -41,7 +193,7 @@ export interface SpeedParams {
 }
 
 // 提取帧模式
-export type ExtractMode = 'single' | 'interval' | 'uniform';
+export type ExtractMode = 'single' | 'interval' | 'uniform' | 'scene' | 'keyframe';
 
 // 提取帧参数
 export interface ExtractFramesParams {
@@ -50,6 +202,19 @@ export interface ExtractFramesParams {
   quality: number;
   interval?: number; // 秒
   count?: number; // 帧数
+  output_dir: string; // 为空时队列输出到视频旁的 {文件名}_frames 目录
+  scene_threshold?: number; // 场景变化阈值 0-1
+  skip_black?: boolean;
+  skip_duplicates?: boolean;
+  contact_sheet?: ContactSheetParams;
+}
+
+// 联系表参数（多帧拼成一张带时间戳的缩略图）
+export interface ContactSheetParams {
+  columns: number;
+  thumb_width: number;
+  max_frames?: number;
+  timestamps: boolean;
 }
 
 // 截断参数
@@ -60,13 +225,129 @@ export interface TrimParams {
 }
 
 // 转GIF参数
+export type AnimationFormat = 'gif' | 'webp' | 'apng';
+
+// 调色板统计方式：full 全局调色板，diff 侧重变化区域，single 逐帧调色板
+export type PaletteStatsMode = 'full' | 'diff' | 'single';
+
+export type DitherMode = 'none' | 'bayer' | 'sierra' | 'floyd';
+
 export interface ToGifParams {
-  startTime: number;
-  endTime: number;
+  start_time: number;
+  end_time: number;
   fps: number;
   width: number;
-  colors: number;
+  colors: number; // 2-256
   dither: boolean;
+  format?: AnimationFormat;
+  stats_mode?: PaletteStatsMode;
+  dither_mode?: DitherMode;
+  bayer_scale?: number; // 0-5
+  loop_count?: number; // 0 为无限循环
+  quality?: number; // WebP 质量 0-100
+  target_size_mb?: number;
+}
+
+// 拼接参数（当前视频在前，inputs 依次拼接在后）
+export interface ConcatParams {
+  inputs: string[];
+  reencode?: boolean; // 强制重新编码
+}
+
+// 裁剪参数：设置 aspect 时居中裁剪，否则使用自定义区域
+export interface CropParams {
+  aspect?: string; // 如 '9:16'
+  x?: number;
+  y?: number;
+  width?: number;
+  height?: number;
+}
+
+// 旋转/翻转参数
+export interface RotateParams {
+  rotation: 0 | 90 | 180 | 270; // 顺时针
+  flip_horizontal: boolean;
+  flip_vertical: boolean;
+}
+
+export type OverlayPosition = 'top_left' | 'top_right' | 'bottom_left' | 'bottom_right' | 'center';
+
+// 图片水印参数
+export interface OverlayParams {
+  image_path: string;
+  position: OverlayPosition;
+  margin: number; // 像素
+  opacity: number; // 0-1
+  scale: number; // 水印宽度占视频宽度的比例
+}
+
+export type AudioFormat = 'mp3' | 'aac' | 'opus' | 'wav' | 'flac';
+
+// 提取音频参数
+export interface AudioExtractParams {
+  format: AudioFormat;
+  bitrate?: number; // kbps，无损格式忽略
+}
+
+// 替换/混入外部音频参数
+export interface AudioReplaceParams {
+  audio_path: string;
+  mode: 'replace' | 'mix';
+  volume: number; // 外部音频音量倍数
+  original_volume: number; // 混音时原音轨音量倍数
+  loop_audio: boolean;
+}
+
+// EBU R128 响度标准化目标
+export interface LoudnormParams {
+  target_i: number; // LUFS
+  target_tp: number; // dBTP
+  target_lra: number; // LU
+}
+
+// loudnorm 响度统计
+export interface LoudnessStats {
+  input_i: number;
+  input_tp: number;
+  input_lra: number;
+  input_thresh: number;
+  output_i: number;
+  output_tp: number;
+  output_lra: number;
+  output_thresh: number;
+  normalization_type: string;
+  target_offset: number;
+}
+
+export interface LoudnormResult {
+  output_path: string;
+  stats: LoudnessStats;
+}
+
+// 烧录字幕参数（颜色为 #RRGGBB）
+export interface BurnSubtitleParams {
+  subtitle_path: string;
+  font_name?: string;
+  font_size?: number;
+  primary_color?: string;
+  outline_color?: string;
+  outline?: number;
+  margin_v?: number;
+  bold?: boolean;
+}
+
+// 封装软字幕参数
+export interface MuxSubtitleParams {
+  subtitle_path: string;
+  language?: string; // ISO 639-2，如 chi、eng
+  title?: string;
+  default?: boolean;
+}
+
+// 提取内嵌字幕参数
+export interface ExtractSubtitleParams {
+  stream_index?: number; // StreamInfo.index，为空时使用第一条字幕流
+  format: 'srt' | 'ass';
 }
 
 // 操作历史
@@ -75,7 +356,22 @@ export interface Operation {
   type: OperationType;
   inputPath: string;
   outputPath: string;
-  params: CompressParams | SpeedParams | ExtractFramesParams | TrimParams | ToGifParams;
+  params:
+    | CompressParams
+    | SpeedParams
+    | ExtractFramesParams
+    | TrimParams
+    | ToGifParams
+    | ConcatParams
+    | CropParams
+    | RotateParams
+    | OverlayParams
+    | AudioExtractParams
+    | AudioReplaceParams
+    | LoudnormParams
+    | BurnSubtitleParams
+    | MuxSubtitleParams
+    | ExtractSubtitleParams;
   timestamp: number;
   duration: number; // 处理耗时(毫秒)
 }